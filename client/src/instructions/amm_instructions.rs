@@ -7,7 +7,10 @@ use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program, sysva
 use gamma::accounts as gamma_accounts;
 use gamma::instruction as gamma_instructions;
 use gamma::{
-    states::{AMM_CONFIG_SEED, OBSERVATION_SEED, POOL_LP_MINT_SEED, POOL_SEED, POOL_VAULT_SEED},
+    states::{
+        AMM_CONFIG_SEED, OBSERVATION_SEED, PAIR_INDEX_SEED, POOL_LP_MINT_SEED, POOL_SEED,
+        POOL_VAULT_SEED,
+    },
     AUTH_SEED,
 };
 use std::rc::Rc;
@@ -129,6 +132,14 @@ pub fn initialize_pool_instr(
         &program.id(),
     )
     .0;
+    let (pair_index, __bump) = Pubkey::find_program_address(
+        &[
+            PAIR_INDEX_SEED.as_bytes(),
+            token_0_mint.to_bytes().as_ref(),
+            token_1_mint.to_bytes().as_ref(),
+        ],
+        &program.id(),
+    );
 
     let instructions = program
         .request()
@@ -150,6 +161,7 @@ pub fn initialize_pool_instr(
             token_1_vault,
             create_pool_fee,
             observation_state: observation_key,
+            pair_index,
             token_program: spl_token::id(),
             token_0_program,
             token_1_program,
@@ -163,6 +175,8 @@ pub fn initialize_pool_instr(
             open_time,
             max_trade_fee_rate: 1000000,
             volatility_factor: 0,
+            amplification: 0,
+            token_0_weight: 0,
         })
         .instructions()?;
     Ok(instructions)
@@ -197,6 +211,33 @@ pub fn create_referral_project_instr(
     Instruction::new_with_bytes(config.gamma_program, &data, accounts)
 }
 
+pub fn update_referral_project_instr(
+    config: &ClientConfig,
+    signer: Pubkey,
+    amm_config: Pubkey,
+    name: Option<String>,
+    default_share_bps: Option<u16>,
+    referral_program: Pubkey,
+) -> Instruction {
+    let project =
+        Pubkey::find_program_address(&[b"project", amm_config.as_ref()], &referral_program).0;
+    let data = anchor_lang::InstructionData::data(&gamma_instructions::UpdateSwapReferral {
+        name,
+        default_share_bps,
+    });
+    let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+        &gamma_accounts::UpdateReferralProject {
+            owner: signer,
+            amm_config,
+            project,
+            referral_program,
+        },
+        None,
+    );
+
+    Instruction::new_with_bytes(config.gamma_program, &data, accounts)
+}
+
 pub fn deposit_instr(
     config: &ClientConfig,
     pool_id: Pubkey,
@@ -246,6 +287,7 @@ pub fn deposit_instr(
             vault_0_mint: token_0_mint,
             vault_1_mint: token_1_mint,
             // lp_mint: token_lp_mint,
+            system_program: system_program::id(),
         })
         .args(gamma_instructions::Deposit {
             lp_token_amount,
@@ -306,6 +348,7 @@ pub fn withdraw_instr(
             vault_1_mint: token_1_mint,
             // lp_mint: token_lp_mint,
             memo_program: spl_memo::id(),
+            system_program: system_program::id(),
         })
         .args(gamma_instructions::Withdraw {
             lp_token_amount,
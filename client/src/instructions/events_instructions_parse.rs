@@ -143,6 +143,93 @@ pub fn handle_program_log(
             LpChangeEvent::DISCRIMINATOR => {
                 println!("{:#?}", decode_event::<LpChangeEvent>(&mut slice)?);
             }
+            EmergencyEscrowEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<EmergencyEscrowEvent>(&mut slice)?);
+            }
+            FeeReportEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<FeeReportEvent>(&mut slice)?);
+            }
+            GaugeSettledEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<GaugeSettledEvent>(&mut slice)?);
+            }
+            SolverSlashedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<SolverSlashedEvent>(&mut slice)?);
+            }
+            FlashLoanEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<FlashLoanEvent>(&mut slice)?);
+            }
+            LpFeesClaimEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<LpFeesClaimEvent>(&mut slice)?);
+            }
+            PoolLifecycleEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PoolLifecycleEvent>(&mut slice)?);
+            }
+            ReferralPaidEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<ReferralPaidEvent>(&mut slice)?);
+            }
+            PartnerChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PartnerChangeEvent>(&mut slice)?);
+            }
+            PartnerLpLinkEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PartnerLpLinkEvent>(&mut slice)?);
+            }
+            LpConcentrationEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<LpConcentrationEvent>(&mut slice)?);
+            }
+            RewardClaimEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<RewardClaimEvent>(&mut slice)?);
+            }
+            PositionChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PositionChangeEvent>(&mut slice)?);
+            }
+            SwapBatchEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<SwapBatchEvent>(&mut slice)?);
+            }
+            SurplusSkimEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<SurplusSkimEvent>(&mut slice)?);
+            }
+            DonationEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<DonationEvent>(&mut slice)?);
+            }
+            LiquidityChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<LiquidityChangeEvent>(&mut slice)?);
+            }
+            AmmConfigCreatedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<AmmConfigCreatedEvent>(&mut slice)?);
+            }
+            AmmConfigChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<AmmConfigChangeEvent>(&mut slice)?);
+            }
+            PoolStatusChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<PoolStatusChangeEvent>(&mut slice)?);
+            }
+            BondingCurveTradeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<BondingCurveTradeEvent>(&mut slice)?);
+            }
+            BondingCurveGraduatedEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<BondingCurveGraduatedEvent>(&mut slice)?);
+            }
+            LpLockEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<LpLockEvent>(&mut slice)?);
+            }
+            LpBurnEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<LpBurnEvent>(&mut slice)?);
+            }
+            UserPositionTransferEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<UserPositionTransferEvent>(&mut slice)?);
+            }
+            ConfigRoleChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<ConfigRoleChangeEvent>(&mut slice)?);
+            }
+            ProtocolFeeSweepEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<ProtocolFeeSweepEvent>(&mut slice)?);
+            }
+            FundFeeConversionEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<FundFeeConversionEvent>(&mut slice)?);
+            }
+            VaultStrategyChangeEvent::DISCRIMINATOR => {
+                println!("{:#?}", decode_event::<VaultStrategyChangeEvent>(&mut slice)?);
+            }
             _ => {
                 println!("unknow event: {}", l);
             }
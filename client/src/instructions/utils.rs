@@ -16,6 +16,14 @@ pub fn deserialize_anchor_account<T: AccountDeserialize>(account: &Account) -> R
     T::try_deserialize(&mut data).map_err(Into::into)
 }
 
+/// The observation account can be grown past the struct by `increase_observation_cardinality`,
+/// which zero copy deserialization doesn't expect
+pub fn deserialize_observation_state(account: &Account) -> Result<gamma::states::ObservationState> {
+    let len = std::cmp::min(account.data.len(), gamma::states::ObservationState::LEN);
+    let mut data: &[u8] = &account.data[..len];
+    gamma::states::ObservationState::try_deserialize(&mut data).map_err(Into::into)
+}
+
 #[derive(Debug)]
 pub struct TransferFeeInfo {
     pub mint: Pubkey,
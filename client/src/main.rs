@@ -27,6 +27,8 @@ use spl_token_2022::{
     extension::StateWithExtensionsMut,
     state::{Account, Mint},
 };
+mod snapshot;
+use snapshot::{snapshot_pool, write_pool_snapshot};
 mod test_swaps;
 use test_swaps::run_swap_test;
 
@@ -132,6 +134,14 @@ pub enum GammaCommands {
         name: String,
         default_share_bps: u16,
     },
+    UpdateReferralProject {
+        amm_config: Pubkey,
+        referral_program: Pubkey,
+        #[clap(short, long)]
+        name: Option<String>,
+        #[clap(short, long)]
+        default_share_bps: Option<u16>,
+    },
     InitializePool {
         mint0: Pubkey,
         mint1: Pubkey,
@@ -173,6 +183,11 @@ pub enum GammaCommands {
     TestSwaps {
         user_keypair: String,
     },
+    SnapshotPool {
+        pool_id: Pubkey,
+        #[clap(short, long)]
+        out_file: String,
+    },
 }
 
 fn main() -> Result<()> {
@@ -246,6 +261,31 @@ fn main() -> Result<()> {
             let signature = send_txn(&rpc_client, &txn, true)?;
             println!("{}", signature);
         }
+        GammaCommands::UpdateReferralProject {
+            name,
+            default_share_bps,
+            referral_program,
+            amm_config,
+        } => {
+            let instruction = update_referral_project_instr(
+                &pool_config,
+                payer.pubkey(),
+                amm_config,
+                name,
+                default_share_bps,
+                referral_program,
+            );
+
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &[instruction],
+                Some(&payer.pubkey()),
+                &vec![&payer],
+                recent_hash,
+            );
+            let signature = send_txn(&rpc_client, &txn, true)?;
+            println!("{}", signature);
+        }
         GammaCommands::InitializePool {
             mint0,
             mint1,
@@ -617,18 +657,21 @@ fn main() -> Result<()> {
                 .as_secs();
 
             // here we make a RPC call again, we can optimize this later by making it part of `get_multiple_accounts` call.
-            let observation: gamma::states::ObservationState =
-                program.account(pool_state.observation_key)?;
+            let observation = deserialize_observation_state(
+                &rpc_client.get_account(&pool_state.observation_key)?,
+            )?;
 
             let result = gamma::curve::CurveCalculator::swap_base_input(
                 u128::from(actual_amount_in),
                 u128::from(total_input_token_amount),
                 u128::from(total_output_token_amount),
+                trade_direction,
                 &amm_config_state,
                 &pool_state,
                 current_unix_timestamp,
                 &observation,
                 false,
+                0,
             )?;
 
             let amount_out = u64::try_from(result.destination_amount_swapped).unwrap();
@@ -778,18 +821,21 @@ fn main() -> Result<()> {
                 .as_secs();
 
             // here we make a RPC call again, we can optimize this later by making it part of `get_multiple_accounts` call.
-            let observation: gamma::states::ObservationState =
-                program.account(pool_state.observation_key)?;
+            let observation = deserialize_observation_state(
+                &rpc_client.get_account(&pool_state.observation_key)?,
+            )?;
 
             let result = gamma::curve::CurveCalculator::swap_base_output(
                 u128::from(actual_amount_out),
                 u128::from(total_input_token_amount),
                 u128::from(total_output_token_amount),
+                trade_direction,
                 &amm_config_state,
                 &pool_state,
                 current_unix_timestamp,
                 &observation,
                 false,
+                0,
             )?;
 
             let source_amount_swapped = u64::try_from(result.source_amount_swapped).unwrap();
@@ -881,6 +927,14 @@ fn main() -> Result<()> {
         GammaCommands::TestSwaps { user_keypair } => {
             run_swap_test(&pool_config, user_keypair)?;
         }
+        GammaCommands::SnapshotPool { pool_id, out_file } => {
+            let snapshot = snapshot_pool(&rpc_client, &pool_config.gamma_program, &pool_id)?;
+            write_pool_snapshot(&snapshot, std::path::Path::new(&out_file))?;
+            println!(
+                "pool {} snapshotted at slot {} to {}",
+                pool_id, snapshot.slot, out_file
+            );
+        }
     }
     Ok(())
 }
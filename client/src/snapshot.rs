@@ -0,0 +1,86 @@
+use anyhow::{format_err, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::path::Path;
+
+use crate::instructions::utils::deserialize_anchor_account;
+
+/// A raw account as fetched from the cluster, with base64 encoded data
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AccountSnapshot {
+    pub address: String,
+    pub owner: String,
+    pub lamports: u64,
+    pub executable: bool,
+    pub data: String,
+}
+
+/// Everything needed to reproduce a pool in solana-program-test, loaded by
+/// `TestEnv::new_from_snapshot` in the program tests.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PoolSnapshot {
+    /// The gamma program the pool was created by, the authority PDA is derived from it
+    pub program_id: String,
+    pub pool_id: String,
+    pub slot: u64,
+    pub accounts: Vec<AccountSnapshot>,
+}
+
+impl AccountSnapshot {
+    fn new(address: &Pubkey, account: &Account) -> Self {
+        AccountSnapshot {
+            address: address.to_string(),
+            owner: account.owner.to_string(),
+            lamports: account.lamports,
+            executable: account.executable,
+            data: STANDARD.encode(&account.data),
+        }
+    }
+}
+
+/// Fetches the pool state, its amm config, observation state, vaults and mints at a single slot.
+pub fn snapshot_pool(
+    rpc_client: &RpcClient,
+    gamma_program: &Pubkey,
+    pool_id: &Pubkey,
+) -> Result<PoolSnapshot> {
+    let pool_account = rpc_client.get_account(pool_id)?;
+    let pool_state = deserialize_anchor_account::<gamma::states::PoolState>(&pool_account)?;
+
+    let keys = vec![
+        *pool_id,
+        pool_state.amm_config,
+        pool_state.observation_key,
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+        pool_state.token_0_mint,
+        pool_state.token_1_mint,
+    ];
+    // Refetch the pool with the rest so every account comes from the same slot
+    let response =
+        rpc_client.get_multiple_accounts_with_commitment(&keys, CommitmentConfig::confirmed())?;
+
+    let accounts = keys
+        .iter()
+        .zip(response.value)
+        .map(|(key, account)| {
+            account
+                .map(|account| AccountSnapshot::new(key, &account))
+                .ok_or(format_err!("account {} not found", key))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PoolSnapshot {
+        program_id: gamma_program.to_string(),
+        pool_id: pool_id.to_string(),
+        slot: response.context.slot,
+        accounts,
+    })
+}
+
+pub fn write_pool_snapshot(snapshot: &PoolSnapshot, path: &Path) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(snapshot)?)?;
+    Ok(())
+}
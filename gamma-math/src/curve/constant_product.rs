@@ -0,0 +1,156 @@
+//! The Uniswap invariant, x * y = constant
+
+use super::{RoundDirection, TradingTokenResult};
+use crate::fixed_point::CheckedCeilDiv;
+
+/// Output amount of a swap, rounded down in favor of the pool
+///
+/// This is guaranteed to work for all the values such that
+/// 1 <= swap_source_amount * swap_destination_amount <= u128::MAX
+/// 1 <= source_amount <= u64::MAX
+pub fn swap_base_input_without_fees(
+    source_amount_to_be_swapped: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+) -> Option<u128> {
+    // (x + delta_x) * (y - delta_y) = x * y
+    // delta_y = (delta_x * y) / (x + delta_x)
+    let numerator = source_amount_to_be_swapped.checked_mul(swap_destination_amount)?;
+    let denominator = swap_source_amount.checked_add(source_amount_to_be_swapped)?;
+    numerator.checked_div(denominator)
+}
+
+/// Input amount of a swap, rounded up in favor of the pool
+pub fn swap_base_output_without_fees(
+    destination_amount_to_be_swapped: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+) -> Option<u128> {
+    // (x + delta_x) * (y - delta_y) = x * y
+    // delta_x = (x * delta_y) / (y - delta_y)
+    let numerator = swap_source_amount.checked_mul(destination_amount_to_be_swapped)?;
+    let denominator = swap_destination_amount.checked_sub(destination_amount_to_be_swapped)?;
+    let (source_amount_swapped, _) = numerator.checked_ceil_div(denominator)?;
+    Some(source_amount_swapped)
+}
+
+/// Get the amount of trading tokens(token_0 and token_1) for a given amount of pool tokens(lp_tokens)
+/// provided the total trading tokens and supply of pool tokens
+///
+/// The constant product implementation is a simple ratio calcluations for the amount of trading tokens
+/// corresponding to a certain number of pool tokens.
+pub fn lp_tokens_to_trading_tokens(
+    lp_token_amount: u128,
+    lp_token_supply: u128,
+    swap_token_0_amount: u128,
+    swap_token_1_amount: u128,
+    round_direction: RoundDirection,
+) -> Option<TradingTokenResult> {
+    // token_0_amount = (lp_token_amount * swap_token_0_amount) / lp_token_supply
+    // lp_token_amount - Amount of pool tokens to be exchanged
+    // swap_token_0_amount - Total token_0 amount in the pool
+    let mut token_0_amount = lp_token_amount
+        .checked_mul(swap_token_0_amount)?
+        .checked_div(lp_token_supply)?;
+    let mut token_1_amount = lp_token_amount
+        .checked_mul(swap_token_1_amount)?
+        .checked_div(lp_token_supply)?;
+    let (token_0_amount, token_1_amount) = match round_direction {
+        RoundDirection::Floor => (token_0_amount, token_1_amount),
+        RoundDirection::Ceiling => {
+            let token_0_remainder = lp_token_amount
+                .checked_mul(swap_token_0_amount)?
+                .checked_rem(lp_token_supply)?;
+            // Also check for 0 token A and B amount to avoid taking too much
+            // for tiny amounts of pool tokens.  For example, if someone asks
+            // for 1 pool token, which is worth 0.01 token A, we avoid the
+            // ceiling of taking 1 token A and instead return 0, for it to be
+            // rejected later in processing.
+            if token_0_remainder > 0 && token_0_amount > 0 {
+                token_0_amount = token_0_amount.checked_add(1)?;
+            }
+            let token_1_remainder = lp_token_amount
+                .checked_mul(swap_token_1_amount)?
+                .checked_rem(lp_token_supply)?;
+            if token_1_remainder > 0 && token_1_amount > 0 {
+                token_1_amount = token_1_amount.checked_add(1)?;
+            }
+            (token_0_amount, token_1_amount)
+        }
+    };
+    Some(TradingTokenResult {
+        token_0_amount,
+        token_1_amount,
+    })
+}
+
+/// Get the amount of lp-tokens for a given amount of one of the trading tokens, rounded down
+pub fn trading_token_to_lp_tokens(
+    trading_token_amount: u128,
+    total_token_amount: u128,
+    lp_token_supply: u128,
+) -> Option<u128> {
+    trading_token_amount
+        .checked_mul(lp_token_supply)?
+        .checked_div(total_token_amount)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn swap_base_input_rounds_down() {
+        // 100 * 1_000 / (1_000 + 100) = 90.9
+        assert_eq!(swap_base_input_without_fees(100, 1_000, 1_000), Some(90));
+        assert_eq!(swap_base_input_without_fees(0, 1_000, 1_000), Some(0));
+        assert_eq!(swap_base_input_without_fees(1, 0, 0), Some(0));
+        assert_eq!(swap_base_input_without_fees(0, 0, 1_000), None);
+    }
+
+    #[test]
+    fn swap_base_output_rounds_up() {
+        // 1_000 * 90 / (1_000 - 90) = 98.9
+        assert_eq!(swap_base_output_without_fees(90, 1_000, 1_000), Some(99));
+        // The whole destination reserve can't be bought
+        assert_eq!(swap_base_output_without_fees(1_000, 1_000, 1_000), None);
+        assert_eq!(swap_base_output_without_fees(1_001, 1_000, 1_000), None);
+    }
+
+    #[test]
+    fn lp_tokens_to_trading_tokens_rounding() {
+        let floor = lp_tokens_to_trading_tokens(10, 30, 100, 200, RoundDirection::Floor).unwrap();
+        assert_eq!(
+            floor,
+            TradingTokenResult {
+                token_0_amount: 33,
+                token_1_amount: 66,
+            }
+        );
+        let ceiling =
+            lp_tokens_to_trading_tokens(10, 30, 100, 200, RoundDirection::Ceiling).unwrap();
+        assert_eq!(
+            ceiling,
+            TradingTokenResult {
+                token_0_amount: 34,
+                token_1_amount: 67,
+            }
+        );
+        // Amounts worth less than a unit aren't rounded up to one
+        let dust =
+            lp_tokens_to_trading_tokens(1, 1_000, 100, 200, RoundDirection::Ceiling).unwrap();
+        assert_eq!(dust.token_0_amount, 0);
+        assert_eq!(dust.token_1_amount, 0);
+        assert_eq!(
+            lp_tokens_to_trading_tokens(1, 0, 100, 200, RoundDirection::Floor),
+            None
+        );
+    }
+
+    #[test]
+    fn trading_token_to_lp_tokens_is_proportional() {
+        assert_eq!(trading_token_to_lp_tokens(50, 200, 1_000), Some(250));
+        assert_eq!(trading_token_to_lp_tokens(1, 3, 1), Some(0));
+        assert_eq!(trading_token_to_lp_tokens(1, 0, 1), None);
+    }
+}
@@ -0,0 +1,25 @@
+//! Curve invariant math
+
+pub mod constant_product;
+pub mod stable_swap;
+pub mod weighted;
+
+/// The direction to round.  Used for pool token to trading token conversions to
+/// avoid losing value on any deposit or withdrawal.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RoundDirection {
+    /// Floor the value, ie. 1.9 => 1, 1.1 => 1, 1.5 => 1
+    Floor,
+    /// Ceiling the value, ie. 1.9 => 2, 1.1 => 2, 1.5 => 2
+    Ceiling,
+}
+
+/// Encodes results of depositing both sides at once
+#[derive(Debug, PartialEq)]
+pub struct TradingTokenResult {
+    /// Amount of token A
+    pub token_0_amount: u128,
+    /// Amount of token B
+    pub token_1_amount: u128,
+}
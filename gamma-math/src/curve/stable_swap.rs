@@ -0,0 +1,196 @@
+//! The Curve stable swap invariant for two tokens:
+//! A * n^n * (x + y) + D = A * D * n^n + D^(n+1) / (n^n * x * y), with n = 2
+
+use crate::fixed_point::{u256_to_u128, Q32, U256};
+
+/// Smallest amplification a stable pool can use, close to constant product
+pub const MIN_AMPLIFICATION: u64 = 1;
+/// Largest amplification a stable pool can use, close to constant sum
+pub const MAX_AMPLIFICATION: u64 = 1_000_000;
+
+const N_COINS: u64 = 2;
+/// n^n for two tokens
+const N_COINS_POW: u64 = 4;
+/// Newton's method converges in a handful of rounds for any sane pool
+const MAX_ITERATIONS: u8 = 255;
+
+fn ann(amplification: u64) -> U256 {
+    U256::from(amplification) * U256::from(N_COINS_POW)
+}
+
+fn converged(value: U256, previous: U256) -> bool {
+    if value > previous {
+        value - previous <= U256::one()
+    } else {
+        previous - value <= U256::one()
+    }
+}
+
+/// Computes the invariant D for the given balances
+pub fn compute_d(amplification: u64, amount_0: u128, amount_1: u128) -> Option<U256> {
+    let sum = U256::from(amount_0).checked_add(U256::from(amount_1))?;
+    if sum.is_zero() {
+        return Some(U256::zero());
+    }
+    let ann = ann(amplification);
+    let n_coins = U256::from(N_COINS);
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        // d_p = D^3 / (n^n * x * y)
+        let d_p = d
+            .checked_mul(d)?
+            .checked_div(U256::from(amount_0).checked_mul(n_coins)?)?
+            .checked_mul(d)?
+            .checked_div(U256::from(amount_1).checked_mul(n_coins)?)?;
+        let d_prev = d;
+        // D = (Ann * S + D_P * n) * D / ((Ann - 1) * D + (n + 1) * D_P)
+        let numerator = ann
+            .checked_mul(sum)?
+            .checked_add(d_p.checked_mul(n_coins)?)?
+            .checked_mul(d)?;
+        let denominator = ann
+            .checked_sub(U256::one())?
+            .checked_mul(d)?
+            .checked_add(n_coins.checked_add(U256::one())?.checked_mul(d_p)?)?;
+        d = numerator.checked_div(denominator)?;
+        if converged(d, d_prev) {
+            return Some(d);
+        }
+    }
+    None
+}
+
+/// Computes the balance of the other token keeping D constant when one balance is `amount`
+pub fn compute_y(amplification: u64, amount: u128, d: U256) -> Option<U256> {
+    let ann = ann(amplification);
+    let n_coins = U256::from(N_COINS);
+    let amount = U256::from(amount);
+    // c = D^3 / (n^n * x * Ann)
+    let c = d
+        .checked_mul(d)?
+        .checked_div(amount.checked_mul(n_coins)?)?
+        .checked_mul(d)?
+        .checked_div(ann.checked_mul(n_coins)?)?;
+    let b = amount.checked_add(d.checked_div(ann)?)?;
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        // y = (y^2 + c) / (2y + b - D)
+        y = y
+            .checked_mul(y)?
+            .checked_add(c)?
+            .checked_div(y.checked_mul(n_coins)?.checked_add(b)?.checked_sub(d)?)?;
+        if converged(y, y_prev) {
+            return Some(y);
+        }
+    }
+    None
+}
+
+/// Output amount of a swap, rounded down in favor of the pool
+pub fn swap_base_input_without_fees(
+    source_amount_to_be_swapped: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+    amplification: u64,
+) -> Option<u128> {
+    let d = compute_d(amplification, swap_source_amount, swap_destination_amount)?;
+    let new_swap_source_amount = swap_source_amount.checked_add(source_amount_to_be_swapped)?;
+    let new_swap_destination_amount = compute_y(amplification, new_swap_source_amount, d)?;
+    // One extra unit stays in the pool to absorb the rounding of the iterations
+    let destination_amount_swapped = U256::from(swap_destination_amount)
+        .saturating_sub(new_swap_destination_amount)
+        .saturating_sub(U256::one());
+    u256_to_u128(destination_amount_swapped)
+}
+
+/// Input amount of a swap, rounded up in favor of the pool
+pub fn swap_base_output_without_fees(
+    destination_amount_to_be_swapped: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+    amplification: u64,
+) -> Option<u128> {
+    let d = compute_d(amplification, swap_source_amount, swap_destination_amount)?;
+    let new_swap_destination_amount = swap_destination_amount
+        .checked_sub(destination_amount_to_be_swapped)
+        .filter(|amount| *amount > 0)?;
+    let new_swap_source_amount = compute_y(amplification, new_swap_destination_amount, d)?;
+    let source_amount_swapped = new_swap_source_amount
+        .checked_sub(U256::from(swap_source_amount))?
+        .checked_add(U256::one())?;
+    u256_to_u128(source_amount_swapped)
+}
+
+/// Marginal prices at the given balances, Q32.32. Returns the price of token_0 in token_1
+/// and the price of token_1 in token_0.
+pub fn token_price_x32(amplification: u64, amount_0: u128, amount_1: u128) -> Option<(u128, u128)> {
+    // With F the invariant, the price of x in y is dF/dx / dF/dy
+    // = (4 * Ann * x * y + D^3 / x) / (4 * Ann * x * y + D^3 / y)
+    let d = compute_d(amplification, amount_0, amount_1)?;
+    let d_cubed = d.checked_mul(d)?.checked_mul(d)?;
+    let ann_x_y = ann(amplification)
+        .checked_mul(U256::from(N_COINS_POW))?
+        .checked_mul(U256::from(amount_0))?
+        .checked_mul(U256::from(amount_1))?;
+    let partial_0 = ann_x_y.checked_add(d_cubed.checked_div(U256::from(amount_0))?)?;
+    let partial_1 = ann_x_y.checked_add(d_cubed.checked_div(U256::from(amount_1))?)?;
+
+    let price_x32 = |numerator: U256, denominator: U256| -> Option<u128> {
+        u256_to_u128(
+            numerator
+                .checked_mul(U256::from(Q32))?
+                .checked_div(denominator)?,
+        )
+    };
+    Some((
+        price_x32(partial_0, partial_1)?,
+        price_x32(partial_1, partial_0)?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn d_of_balanced_reserves_is_their_sum() {
+        for amplification in [MIN_AMPLIFICATION, 100, MAX_AMPLIFICATION] {
+            let d = compute_d(amplification, 1_000_000, 1_000_000).unwrap();
+            assert!(converged(d, U256::from(2_000_000u64)));
+        }
+        assert_eq!(compute_d(100, 0, 0), Some(U256::zero()));
+    }
+
+    #[test]
+    fn y_inverts_d() {
+        let d = compute_d(100, 1_000_000, 3_000_000).unwrap();
+        let y = compute_y(100, 1_000_000, d).unwrap();
+        assert!(converged(y, U256::from(3_000_000u64)));
+    }
+
+    #[test]
+    fn balanced_pools_trade_close_to_one_to_one() {
+        let amount_out = swap_base_input_without_fees(1_000, 1_000_000, 1_000_000, 100).unwrap();
+        assert!((990..1_000).contains(&amount_out));
+        let amount_in = swap_base_output_without_fees(1_000, 1_000_000, 1_000_000, 100).unwrap();
+        assert!((1_000..1_010).contains(&amount_in));
+        // The whole destination reserve can't be bought
+        assert_eq!(
+            swap_base_output_without_fees(1_000_000, 1_000_000, 1_000_000, 100),
+            None
+        );
+    }
+
+    #[test]
+    fn balanced_pools_price_at_one() {
+        let (price_0_x32, price_1_x32) = token_price_x32(100, 1_000_000, 1_000_000).unwrap();
+        assert_eq!(price_0_x32, Q32);
+        assert_eq!(price_1_x32, Q32);
+        // The scarcer token is the pricier one
+        let (price_0_x32, price_1_x32) = token_price_x32(100, 1_000_000, 2_000_000).unwrap();
+        assert!(price_0_x32 > Q32);
+        assert!(price_1_x32 < Q32);
+        assert_eq!(token_price_x32(100, 0, 1_000_000), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! The Balancer constant weight invariant for two tokens:
+//! x^w_x * y^w_y = constant, with w_x + w_y = 1
+
+use crate::fixed_point::Q32;
+use rust_decimal::{prelude::*, MathematicalOps};
+
+/// Weights are denominated in bps, the weights of a pool's tokens add up to this
+pub const WEIGHT_DENOMINATOR: u64 = 10_000;
+/// Lightest weight a token can have, so the powers stay in range of the swap math
+pub const MIN_WEIGHT: u64 = 500;
+pub const MAX_WEIGHT: u64 = WEIGHT_DENOMINATOR - MIN_WEIGHT;
+
+/// `exp` stops at 2e-7 by default, far coarser than token amounts
+const EXP_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 27);
+/// The powers are exact to about 1e-22. Amounts are rounded in favor of the pool by one unit
+/// per 10^18 units of the reserve on top, which covers that with room to spare.
+const ROUNDING_MARGIN_DIVISOR: u128 = 1_000_000_000_000_000_000;
+
+fn margin(amount: u128) -> u128 {
+    amount / ROUNDING_MARGIN_DIVISOR + 1
+}
+
+fn pow(base: Decimal, exponent: Decimal) -> Option<Decimal> {
+    if exponent == Decimal::ONE {
+        return Some(base);
+    }
+    base.checked_ln()?
+        .checked_mul(exponent)?
+        .checked_exp_with_tolerance(EXP_TOLERANCE)
+}
+
+fn exponent(numerator_weight: u64, denominator_weight: u64) -> Option<Decimal> {
+    Decimal::from(numerator_weight).checked_div(Decimal::from(denominator_weight))
+}
+
+/// Output amount of a swap, rounded down in favor of the pool
+pub fn swap_base_input_without_fees(
+    source_amount_to_be_swapped: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+    source_weight: u64,
+    destination_weight: u64,
+) -> Option<u128> {
+    // delta_y = y * (1 - (x / (x + delta_x))^(w_x / w_y))
+    let new_swap_source_amount = swap_source_amount.checked_add(source_amount_to_be_swapped)?;
+    let ratio = Decimal::from_u128(swap_source_amount)?
+        .checked_div(Decimal::from_u128(new_swap_source_amount)?)?;
+    let remaining_share = pow(ratio, exponent(source_weight, destination_weight)?)?;
+    let destination_amount_swapped = Decimal::from_u128(swap_destination_amount)?
+        .checked_mul(
+            Decimal::ONE
+                .checked_sub(remaining_share)?
+                .max(Decimal::ZERO),
+        )?
+        .floor()
+        .to_u128()?;
+    Some(destination_amount_swapped.saturating_sub(margin(swap_destination_amount)))
+}
+
+/// Input amount of a swap, rounded up in favor of the pool
+pub fn swap_base_output_without_fees(
+    destination_amount_to_be_swapped: u128,
+    swap_source_amount: u128,
+    swap_destination_amount: u128,
+    source_weight: u64,
+    destination_weight: u64,
+) -> Option<u128> {
+    // delta_x = x * ((y / (y - delta_y))^(w_y / w_x) - 1)
+    let new_swap_destination_amount = swap_destination_amount
+        .checked_sub(destination_amount_to_be_swapped)
+        .filter(|amount| *amount > 0)?;
+    let ratio = Decimal::from_u128(swap_destination_amount)?
+        .checked_div(Decimal::from_u128(new_swap_destination_amount)?)?;
+    let growth = pow(ratio, exponent(destination_weight, source_weight)?)?;
+    let source_amount_swapped = Decimal::from_u128(swap_source_amount)?
+        .checked_mul(growth.checked_sub(Decimal::ONE)?.max(Decimal::ZERO))?
+        .ceil()
+        .to_u128()?;
+    source_amount_swapped.checked_add(margin(
+        swap_source_amount.checked_add(source_amount_swapped)?,
+    ))
+}
+
+/// Marginal prices at the given balances, Q32.32. Returns the price of token_0 in token_1
+/// and the price of token_1 in token_0.
+pub fn token_price_x32(
+    amount_0: u128,
+    amount_1: u128,
+    weight_0: u64,
+    weight_1: u64,
+) -> Option<(u128, u128)> {
+    // The price of x in y is (y / w_y) / (x / w_x)
+    let price_x32 = |x: u128, weight_x: u64, y: u128, weight_y: u64| {
+        y.checked_mul(u128::from(weight_x))?
+            .checked_mul(Q32)?
+            .checked_div(x.checked_mul(u128::from(weight_y))?)
+    };
+    Some((
+        price_x32(amount_0, weight_0, amount_1, weight_1)?,
+        price_x32(amount_1, weight_1, amount_0, weight_0)?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::curve::constant_product;
+
+    #[test]
+    fn even_weights_trade_like_constant_product() {
+        let amount_out = swap_base_input_without_fees(1_000, 1_000_000, 1_000_000, 5_000, 5_000);
+        let constant_product_out =
+            constant_product::swap_base_input_without_fees(1_000, 1_000_000, 1_000_000).unwrap();
+        assert_eq!(amount_out, Some(constant_product_out - 1));
+        let amount_in = swap_base_output_without_fees(1_000, 1_000_000, 1_000_000, 5_000, 5_000);
+        let constant_product_in =
+            constant_product::swap_base_output_without_fees(1_000, 1_000_000, 1_000_000).unwrap();
+        assert_eq!(amount_in, Some(constant_product_in + 1));
+    }
+
+    #[test]
+    fn heavier_source_weight_gets_more_out() {
+        // In an 80/20 pool priced at one, the 80 side trades like a deeper reserve
+        let amount_out = swap_base_input_without_fees(1_000, 4_000_000, 1_000_000, 8_000, 2_000);
+        // 1_000_000 * (1 - (4_000_000 / 4_001_000)^4) = 999.38
+        assert_eq!(amount_out, Some(998));
+        // 4_000_000 * ((1_000_000 / 999_002)^(1/4) - 1) = 998.62
+        let amount_in = swap_base_output_without_fees(998, 4_000_000, 1_000_000, 8_000, 2_000);
+        assert_eq!(amount_in, Some(1_000));
+    }
+
+    #[test]
+    fn weighted_prices() {
+        let (price_0_x32, price_1_x32) =
+            token_price_x32(4_000_000, 1_000_000, 8_000, 2_000).unwrap();
+        assert_eq!(price_0_x32, Q32);
+        assert_eq!(price_1_x32, Q32);
+        let (price_0_x32, price_1_x32) =
+            token_price_x32(1_000_000, 1_000_000, 8_000, 2_000).unwrap();
+        assert_eq!(price_0_x32, 4 * Q32);
+        assert_eq!(price_1_x32, Q32 / 4);
+        assert_eq!(token_price_x32(0, 1_000_000, 8_000, 2_000), None);
+    }
+
+    #[test]
+    fn the_whole_reserve_cant_be_bought() {
+        assert_eq!(
+            swap_base_output_without_fees(1_000_000, 1_000_000, 1_000_000, 8_000, 2_000),
+            None
+        );
+        assert_eq!(
+            swap_base_input_without_fees(1_000, 0, 1_000_000, 8_000, 2_000),
+            None
+        );
+    }
+}
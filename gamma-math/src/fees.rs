@@ -0,0 +1,137 @@
+//! Fee rates are denominated in hundredths of bip (10^-6)
+
+pub const ONE_BASIS_POINT: u64 = 100;
+pub const FEE_RATE_DENOMINATOR_VALUE: u64 = 1_000_000;
+
+pub fn ceil_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+    token_amount
+        .checked_mul(fee_numerator)?
+        .checked_add(fee_denominator)?
+        .checked_sub(1)?
+        .checked_div(fee_denominator)
+}
+
+/// Helper function for calculating swap fee
+pub fn floor_div(token_amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+    token_amount
+        .checked_mul(fee_numerator)?
+        .checked_div(fee_denominator)
+}
+
+pub struct StaticFee {}
+
+impl StaticFee {
+    /// Calculate the trading fee in trading tokens
+    pub fn trading_fee(amount: u128, trade_fee_rate: u64) -> Option<u128> {
+        ceil_div(
+            amount,
+            u128::from(trade_fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    /// Calculate the owner protocol fee in trading tokens
+    pub fn protocol_fee(amount: u128, protocol_fee_rate: u64) -> Option<u128> {
+        floor_div(
+            amount,
+            u128::from(protocol_fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    /// Calculate the fund fee in trading tokens
+    pub fn fund_fee(amount: u128, fund_fee_rate: u64) -> Option<u128> {
+        floor_div(
+            amount,
+            u128::from(fund_fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    /// Calculate the fee of a flash loan in borrowed tokens
+    pub fn flash_loan_fee(amount: u128, flash_loan_fee_rate: u64) -> Option<u128> {
+        ceil_div(
+            amount,
+            u128::from(flash_loan_fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+    }
+
+    pub fn calculate_pre_trade_fee_amount(
+        post_fee_amount: u128,
+        trade_fee_rate: u64,
+    ) -> Option<u128> {
+        if trade_fee_rate == 0 {
+            Some(post_fee_amount)
+        } else {
+            // x = pre_fee_amount (has to be calculated)
+            // y = post_fee_amount
+            // r = trade_fee_rate
+            // D = FEE_RATE_DENOMINATOR_VALUE
+            // y = x * (1 - r/ D)
+            // y = x * ((D -r) / D)
+            // x = y * D / (D - r)
+
+            let numerator = post_fee_amount.checked_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))?;
+            let denominator =
+                u128::from(FEE_RATE_DENOMINATOR_VALUE).checked_sub(u128::from(trade_fee_rate))?;
+
+            numerator
+                .checked_add(denominator)?
+                .checked_sub(1)?
+                .checked_div(denominator)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn trading_fee_rounds_up() {
+        // 0.25% of 1_000 is 2.5
+        assert_eq!(StaticFee::trading_fee(1_000, 2_500), Some(3));
+        assert_eq!(StaticFee::trading_fee(1_000_000, 2_500), Some(2_500));
+        assert_eq!(StaticFee::trading_fee(1, 1), Some(1));
+        assert_eq!(StaticFee::trading_fee(0, 2_500), Some(0));
+        assert_eq!(StaticFee::trading_fee(1_000, 0), Some(0));
+    }
+
+    #[test]
+    fn protocol_and_fund_fees_round_down() {
+        assert_eq!(StaticFee::protocol_fee(2_500, 120_000), Some(300));
+        assert_eq!(StaticFee::protocol_fee(3, 120_000), Some(0));
+        assert_eq!(StaticFee::fund_fee(2_500, 40_000), Some(100));
+        assert_eq!(StaticFee::fund_fee(24, 40_000), Some(0));
+    }
+
+    #[test]
+    fn flash_loan_fee_rounds_up() {
+        assert_eq!(StaticFee::flash_loan_fee(1_000, 900), Some(1));
+        assert_eq!(StaticFee::flash_loan_fee(1_000_000, 900), Some(900));
+    }
+
+    #[test]
+    fn pre_trade_fee_amount_covers_the_fee() {
+        assert_eq!(StaticFee::calculate_pre_trade_fee_amount(997, 0), Some(997));
+        let pre_fee_amount = StaticFee::calculate_pre_trade_fee_amount(997_500, 2_500).unwrap();
+        assert_eq!(pre_fee_amount, 1_000_000);
+        // A rate of 100% has no pre fee amount
+        assert_eq!(
+            StaticFee::calculate_pre_trade_fee_amount(1, FEE_RATE_DENOMINATOR_VALUE),
+            None
+        );
+    }
+
+    #[test]
+    fn overflow_returns_none() {
+        assert_eq!(ceil_div(u128::MAX, 2, 1), None);
+        assert_eq!(floor_div(u128::MAX, 2, 1), None);
+        assert_eq!(floor_div(1, 1, 0), None);
+        assert_eq!(
+            StaticFee::calculate_pre_trade_fee_amount(u128::MAX, 2_500),
+            None
+        );
+    }
+}
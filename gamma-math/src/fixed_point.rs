@@ -0,0 +1,93 @@
+//! 128 and 256 bit numbers and fixed-point constants
+//!
+//! U128 is more efficient that u128
+//! https://github.com/solana-labs/solana/issues/19549
+// The expansion of `construct_uint!` trips these
+#![allow(
+    clippy::assign_op_pattern,
+    clippy::manual_div_ceil,
+    clippy::reversed_empty_ranges
+)]
+use uint::construct_uint;
+construct_uint! {
+    pub struct U128(2);
+}
+
+construct_uint! {
+    pub struct U256(4);
+}
+
+/// One in Q32.32
+pub const Q32: u128 = (u32::MAX as u128) + 1; // 2^32
+/// One in Q64.64
+pub const Q64: u128 = (u64::MAX as u128) + 1; // 2^64
+
+pub trait CheckedCeilDiv: Sized {
+    /// Perform ceiling division
+    fn checked_ceil_div(&self, rhs: Self) -> Option<(Self, Self)>;
+}
+
+impl CheckedCeilDiv for u128 {
+    fn checked_ceil_div(&self, mut rhs: Self) -> Option<(Self, Self)> {
+        let mut quotient = self.checked_div(rhs)?;
+        // Avoid dividing a small number by a big one and returning 1, and instead
+        // fail.
+        if quotient == 0 {
+            // return None;
+            if self.checked_mul(2)? >= rhs {
+                return Some((1, 0));
+            } else {
+                return Some((0, 0));
+            }
+        }
+
+        // Ceiling the destination amount if there's any remainder, which will
+        // almost always be the case.
+        let remainder = self.checked_rem(rhs)?;
+        if remainder > 0 {
+            quotient = quotient.checked_add(1)?;
+            // calculate the minimum amount needed to get the dividend amount to
+            // avoid truncating too much
+            rhs = self.checked_div(quotient)?;
+            let remainder = self.checked_rem(quotient)?;
+            if remainder > 0 {
+                rhs = rhs.checked_add(1)?;
+            }
+        }
+        Some((quotient, rhs))
+    }
+}
+
+/// `value` as a u128, `None` when it doesn't fit
+pub fn u256_to_u128(value: U256) -> Option<u128> {
+    if value > U256::from(u128::MAX) {
+        return None;
+    }
+    Some(value.as_u128())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ceil_div_rounds_the_quotient_up() {
+        assert_eq!(10u128.checked_ceil_div(3), Some((4, 3)));
+        assert_eq!(9u128.checked_ceil_div(3), Some((3, 3)));
+        assert_eq!(1u128.checked_ceil_div(0), None);
+    }
+
+    #[test]
+    fn ceil_div_of_a_small_dividend() {
+        // Half the divisor or more rounds to one, less rounds to zero
+        assert_eq!(5u128.checked_ceil_div(10), Some((1, 0)));
+        assert_eq!(4u128.checked_ceil_div(10), Some((0, 0)));
+        assert_eq!(u128::MAX.checked_ceil_div(u128::MAX), Some((1, u128::MAX)));
+    }
+
+    #[test]
+    fn u256_to_u128_bounds() {
+        assert_eq!(u256_to_u128(U256::from(u128::MAX)), Some(u128::MAX));
+        assert_eq!(u256_to_u128(U256::from(u128::MAX) + 1), None);
+    }
+}
@@ -0,0 +1,12 @@
+//! Curve, fee and fixed-point math of the gamma AMM.
+//!
+//! Everything here is plain checked integer arithmetic without Anchor or Solana types, so the
+//! program, its clients and fuzzers all run the same implementation. Functions return `None`
+//! on overflow, division by zero or non convergence, callers map that to their own errors.
+
+pub mod curve;
+pub mod fees;
+pub mod fixed_point;
+pub mod oracle;
+
+pub use fixed_point::*;
@@ -0,0 +1,168 @@
+//! Price oracle math: the exponential moving average price and the log prices of the
+//! geometric mean twap
+
+use crate::fixed_point::Q32;
+
+/// 2^(-1/2^i) for i in 1..=16, Q32.32. Multiplying the ones of the set bits of a fraction
+/// gives 2 to the minus that fraction.
+const HALF_LIFE_FRACTIONS_X32: [u128; 16] = [
+    3037000500, 3611622603, 3938502376, 4112874773, 4202935003, 4248701965, 4271771996, 4283353945,
+    4289156690, 4292061010, 4293513907, 4294240540, 4294603903, 4294785595, 4294876445, 4294921870,
+];
+
+/// Weight an average keeps after `elapsed` seconds with the given half-life, Q32.32.
+/// `None` for a zero half-life.
+pub fn ema_decay_x32(elapsed: u64, half_life: u64) -> Option<u128> {
+    let halvings = elapsed.checked_div(half_life)?;
+    if halvings >= 32 {
+        return Some(0);
+    }
+    // The rest of a half-life, in 1/2^16 of one
+    let fraction = u128::from(elapsed % half_life) * (1 << 16) / u128::from(half_life);
+    let decay_x32 = HALF_LIFE_FRACTIONS_X32
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| fraction & (1 << (15 - i)) != 0)
+        .fold(Q32, |decay_x32, (_, factor_x32)| {
+            (decay_x32 * factor_x32) >> 32
+        });
+    Some(decay_x32 >> halvings)
+}
+
+/// Moves `ema_x32` towards `price_x32`, the price that held for the `elapsed` seconds since
+/// the average was last updated
+pub fn ema_x32(ema_x32: u128, price_x32: u128, elapsed: u64, half_life: u64) -> Option<u128> {
+    let decay_x32 = ema_decay_x32(elapsed, half_life)?;
+    ema_x32
+        .checked_mul(decay_x32)?
+        .checked_add(price_x32.checked_mul(Q32 - decay_x32)?)
+        .map(|ema| ema >> 32)
+}
+
+/// 2^(1/2^i) for i in 1..=32, Q1.63
+const ROOTS_OF_TWO_X63: [u128; 32] = [
+    13043817825332782212,
+    10968499650544839023,
+    10058158527438640871,
+    9631725603661576981,
+    9425337585438007767,
+    9323807973325342579,
+    9273454034941487980,
+    9248379135337035441,
+    9235867122425417553,
+    9229617465154500136,
+    9226494222562136017,
+    9224932997620185949,
+    9224152484218207415,
+    9223762252282022473,
+    9223567142504825508,
+    9223469589163912706,
+    9223420812880372951,
+    9223396424835331638,
+    9223384230836993049,
+    9223378133843869261,
+    9223375085348818743,
+    9223373561101671328,
+    9223372798978192081,
+    9223372417916476073,
+    9223372227385623972,
+    9223372132120199398,
+    9223372084487487480,
+    9223372060671131613,
+    9223372048762953703,
+    9223372042808864754,
+    9223372039831820280,
+    9223372038343298044,
+];
+
+/// Base 2 logarithm of a Q32.32 value, Q32.32. `None` for zero.
+pub fn log2_x32(value_x32: u128) -> Option<i128> {
+    if value_x32 == 0 {
+        return None;
+    }
+    let msb = 127 - value_x32.leading_zeros();
+    // The value scaled into [1, 2), Q1.63
+    let mut normalized_x63 = if msb >= 63 {
+        value_x32 >> (msb - 63)
+    } else {
+        value_x32 << (63 - msb)
+    };
+    let mut fraction_x32 = 0i128;
+    for bit in (0..32).rev() {
+        normalized_x63 = (normalized_x63 * normalized_x63) >> 63;
+        if normalized_x63 >= 1 << 64 {
+            normalized_x63 >>= 1;
+            fraction_x32 |= 1 << bit;
+        }
+    }
+    Some(((i128::from(msb) - 32) << 32) + fraction_x32)
+}
+
+/// 2 to the power of a Q32.32 exponent, Q32.32. `None` when it doesn't fit, results below the
+/// smallest Q32.32 value are zero.
+pub fn exp2_x32(exponent_x32: i128) -> Option<u128> {
+    let integer = exponent_x32 >> 32;
+    let fraction_x32 = exponent_x32 & (u32::MAX as i128);
+    let power_x63 = ROOTS_OF_TWO_X63
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| fraction_x32 & (1 << (31 - i)) != 0)
+        .fold(1u128 << 63, |power_x63, (_, root_x63)| {
+            (power_x63 * root_x63) >> 63
+        });
+    // power_x63 is in [1, 2) and below 2^64
+    match integer + 32 - 63 {
+        shift if shift >= 64 => None,
+        shift if shift >= 0 => Some(power_x63 << shift),
+        shift if shift > -128 => Some(power_x63 >> -shift),
+        _ => Some(0),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decay_halves_every_half_life() {
+        assert_eq!(ema_decay_x32(0, 60), Some(Q32));
+        assert_eq!(ema_decay_x32(60, 60), Some(Q32 / 2));
+        assert_eq!(ema_decay_x32(120, 60), Some(Q32 / 4));
+        assert_eq!(ema_decay_x32(60 * 32, 60), Some(0));
+        assert_eq!(ema_decay_x32(60, 0), None);
+
+        // Half a half-life keeps 1/sqrt(2)
+        let decay_x32 = ema_decay_x32(30, 60).unwrap();
+        assert!(decay_x32.abs_diff(3037000500) <= 1);
+    }
+
+    #[test]
+    fn ema_moves_towards_the_price() {
+        let price_x32 = 2 * Q32;
+        assert_eq!(ema_x32(Q32, price_x32, 0, 60), Some(Q32));
+        assert_eq!(ema_x32(Q32, price_x32, 60, 60), Some(Q32 + Q32 / 2));
+        assert_eq!(ema_x32(Q32, price_x32, 60 * 32, 60), Some(price_x32));
+    }
+
+    #[test]
+    fn log2_of_powers_of_two() {
+        assert_eq!(log2_x32(Q32), Some(0));
+        assert_eq!(log2_x32(2 * Q32), Some(1 << 32));
+        assert_eq!(log2_x32(Q32 / 8), Some(-3 << 32));
+        assert_eq!(log2_x32(1), Some(-32 << 32));
+        assert_eq!(log2_x32(0), None);
+    }
+
+    #[test]
+    fn exp2_inverts_log2() {
+        assert_eq!(exp2_x32(0), Some(Q32));
+        assert_eq!(exp2_x32(-3 << 32), Some(Q32 / 8));
+        assert_eq!(exp2_x32(-33 << 32), Some(0));
+        assert_eq!(exp2_x32(96 << 32), None);
+        for value_x32 in [3 * Q32 / 7, Q32 + 1, 12_345 * Q32, u128::from(u64::MAX)] {
+            let roundtrip = exp2_x32(log2_x32(value_x32).unwrap()).unwrap();
+            // Within a few parts in 2^32
+            assert!(roundtrip.abs_diff(value_x32) <= value_x32 / (1 << 30) + 1);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Properties every curve and fee computation has to keep for any input. The program relies on
+//! them to never pay out more than a trade is worth.
+
+use gamma_math::{
+    curve::{constant_product, stable_swap, weighted, RoundDirection},
+    fees::{StaticFee, FEE_RATE_DENOMINATOR_VALUE},
+    CheckedCeilDiv, Q32, U256,
+};
+use proptest::prelude::*;
+use rust_decimal::{prelude::*, MathematicalOps};
+
+/// Any token amount
+fn amount() -> impl Strategy<Value = u128> {
+    0..u128::from(u64::MAX)
+}
+
+/// Reserves deep enough for `trade()` amounts
+fn reserve() -> impl Strategy<Value = u128> {
+    u128::from(u32::MAX)..u128::from(u64::MAX)
+}
+
+fn trade() -> impl Strategy<Value = u128> {
+    1..u128::from(u32::MAX)
+}
+
+fn fee_rate() -> impl Strategy<Value = u64> {
+    0..FEE_RATE_DENOMINATOR_VALUE
+}
+
+fn amplification() -> impl Strategy<Value = u64> {
+    stable_swap::MIN_AMPLIFICATION..=stable_swap::MAX_AMPLIFICATION
+}
+
+fn weight() -> impl Strategy<Value = u64> {
+    weighted::MIN_WEIGHT..=weighted::MAX_WEIGHT
+}
+
+/// ln of the weighted invariant, w_x * ln(x) + w_y * ln(y)
+fn weighted_invariant_ln(x: u128, y: u128, weight_x: u64, weight_y: u64) -> Decimal {
+    let term = |amount: u128, weight: u64| {
+        Decimal::from_u128(amount).unwrap().ln() * Decimal::from(weight)
+    };
+    term(x, weight_x) + term(y, weight_y)
+}
+
+proptest! {
+    #[test]
+    fn ceil_div_never_rounds_down(dividend in amount(), divisor in 1..u128::from(u64::MAX)) {
+        let (quotient, _) = dividend.checked_ceil_div(divisor).unwrap();
+        let floor = dividend / divisor;
+        // Dividends under half the divisor are the one exception, they round to zero
+        if floor > 0 {
+            prop_assert!(quotient >= floor);
+            prop_assert!(quotient <= floor + 1);
+        }
+    }
+
+    #[test]
+    fn trading_fee_rounds_up_and_stays_below_the_amount(amount in amount(), rate in fee_rate()) {
+        let fee = StaticFee::trading_fee(amount, rate).unwrap();
+        let denominator = u128::from(FEE_RATE_DENOMINATOR_VALUE);
+        prop_assert!(fee * denominator >= amount * u128::from(rate));
+        prop_assert!(fee <= amount);
+    }
+
+    #[test]
+    fn protocol_and_fund_fees_never_exceed_the_trading_fee(
+        amount in amount(),
+        rate in fee_rate(),
+        share in fee_rate(),
+    ) {
+        let trading_fee = StaticFee::trading_fee(amount, rate).unwrap();
+        prop_assert!(StaticFee::protocol_fee(trading_fee, share).unwrap() <= trading_fee);
+        prop_assert!(StaticFee::fund_fee(trading_fee, share).unwrap() <= trading_fee);
+    }
+
+    #[test]
+    fn pre_trade_fee_amount_covers_the_trading_fee(
+        post_fee_amount in amount(),
+        rate in fee_rate(),
+    ) {
+        let pre_fee_amount =
+            StaticFee::calculate_pre_trade_fee_amount(post_fee_amount, rate).unwrap();
+        let fee = StaticFee::trading_fee(pre_fee_amount, rate).unwrap();
+        prop_assert!(pre_fee_amount - fee >= post_fee_amount);
+    }
+
+    #[test]
+    fn constant_product_swaps_never_lower_the_invariant(
+        amount in 1..u128::from(u64::MAX),
+        source in 1..u128::from(u64::MAX),
+        destination in 1..u128::from(u64::MAX),
+    ) {
+        let amount_out =
+            constant_product::swap_base_input_without_fees(amount, source, destination).unwrap();
+        prop_assert!(amount_out < destination);
+        let invariant = source * destination;
+        let new_invariant = (source + amount) * (destination - amount_out);
+        prop_assert!(new_invariant >= invariant);
+    }
+
+    #[test]
+    fn constant_product_exact_out_costs_at_least_exact_in(
+        amount_out in trade(),
+        source in 1..u128::from(u64::MAX),
+        destination in reserve(),
+    ) {
+        let amount_in =
+            constant_product::swap_base_output_without_fees(amount_out, source, destination)
+                .unwrap();
+        // Selling what a base output swap charges buys about the requested amount
+        if amount_in > 0 {
+            let amount_back =
+                constant_product::swap_base_input_without_fees(amount_in, source, destination)
+                    .unwrap();
+            prop_assert!(amount_back + 1 >= amount_out);
+        }
+    }
+
+    #[test]
+    fn lp_token_round_trips_never_create_value(
+        lp_token_amount in 1..u128::from(u64::MAX),
+        extra_supply in amount(),
+        token_0 in amount(),
+        token_1 in amount(),
+    ) {
+        let supply = lp_token_amount + extra_supply;
+        let to_trading_tokens = |round_direction| {
+            constant_product::lp_tokens_to_trading_tokens(
+                lp_token_amount,
+                supply,
+                token_0,
+                token_1,
+                round_direction,
+            )
+            .unwrap()
+        };
+        let floor = to_trading_tokens(RoundDirection::Floor);
+        let ceiling = to_trading_tokens(RoundDirection::Ceiling);
+        prop_assert!(floor.token_0_amount <= ceiling.token_0_amount);
+        prop_assert!(floor.token_1_amount <= ceiling.token_1_amount);
+        prop_assert!(ceiling.token_0_amount <= token_0);
+        prop_assert!(ceiling.token_1_amount <= token_1);
+        // Depositing what a withdrawal pays out doesn't mint more than was burned
+        if token_0 > 0 {
+            let lp_tokens =
+                constant_product::trading_token_to_lp_tokens(floor.token_0_amount, token_0, supply)
+                    .unwrap();
+            prop_assert!(lp_tokens <= lp_token_amount);
+        }
+    }
+
+    #[test]
+    fn stable_swaps_never_lower_the_invariant(
+        amount in trade(),
+        source in reserve(),
+        destination in reserve(),
+        amplification in amplification(),
+    ) {
+        let amount_out = stable_swap::swap_base_input_without_fees(
+            amount,
+            source,
+            destination,
+            amplification,
+        );
+        if let Some(amount_out) = amount_out {
+            prop_assert!(amount_out < destination);
+            let d = stable_swap::compute_d(amplification, source, destination).unwrap();
+            let new_d =
+                stable_swap::compute_d(amplification, source + amount, destination - amount_out)
+                    .unwrap();
+            prop_assert!(new_d + 1 >= d);
+        }
+    }
+
+    #[test]
+    fn balanced_stable_swaps_trade_at_most_one_for_one(
+        amount in trade(),
+        reserve in reserve(),
+        amplification in amplification(),
+    ) {
+        let amount_out =
+            stable_swap::swap_base_input_without_fees(amount, reserve, reserve, amplification)
+                .unwrap();
+        prop_assert!(amount_out <= amount);
+        let amount_in =
+            stable_swap::swap_base_output_without_fees(amount, reserve, reserve, amplification)
+                .unwrap();
+        prop_assert!(amount_in >= amount);
+    }
+
+    #[test]
+    fn stable_swap_prices_favor_the_scarcer_token(
+        amount_0 in 1..u128::from(u64::MAX),
+        amount_1 in 1..u128::from(u64::MAX),
+        amplification in amplification(),
+    ) {
+        let (price_0_x32, price_1_x32) =
+            stable_swap::token_price_x32(amplification, amount_0, amount_1).unwrap();
+        if amount_0 >= amount_1 {
+            prop_assert!(price_0_x32 <= Q32);
+        } else {
+            prop_assert!(price_1_x32 <= Q32);
+        }
+    }
+
+    #[test]
+    fn weighted_swaps_never_lower_the_invariant(
+        amount in trade(),
+        source in reserve(),
+        destination in reserve(),
+        source_weight in weight(),
+    ) {
+        let destination_weight = weighted::WEIGHT_DENOMINATOR - source_weight;
+        let invariant =
+            weighted_invariant_ln(source, destination, source_weight, destination_weight);
+        let amount_out = weighted::swap_base_input_without_fees(
+            amount,
+            source,
+            destination,
+            source_weight,
+            destination_weight,
+        )
+        .unwrap();
+        prop_assert!(amount_out < destination);
+        let new_invariant = weighted_invariant_ln(
+            source + amount,
+            destination - amount_out,
+            source_weight,
+            destination_weight,
+        );
+        prop_assert!(new_invariant >= invariant);
+
+        let amount_in = weighted::swap_base_output_without_fees(
+            amount,
+            source,
+            destination,
+            source_weight,
+            destination_weight,
+        )
+        .unwrap();
+        let new_invariant = weighted_invariant_ln(
+            source + amount_in,
+            destination - amount,
+            source_weight,
+            destination_weight,
+        );
+        prop_assert!(new_invariant >= invariant);
+    }
+
+    #[test]
+    fn weighted_swaps_pay_at_most_the_marginal_price(
+        amount in trade(),
+        source in reserve(),
+        destination in reserve(),
+        source_weight in weight(),
+    ) {
+        let destination_weight = weighted::WEIGHT_DENOMINATOR - source_weight;
+        let amount_out = weighted::swap_base_input_without_fees(
+            amount,
+            source,
+            destination,
+            source_weight,
+            destination_weight,
+        )
+        .unwrap();
+        // amount_out / amount <= (destination / destination_weight) / (source / source_weight)
+        let paid = U256::from(amount_out) * U256::from(source) * destination_weight;
+        let marginal = U256::from(amount) * U256::from(destination) * source_weight;
+        prop_assert!(paid <= marginal);
+    }
+}
@@ -0,0 +1,423 @@
+//! `jupiter_amm_interface::Amm` for gamma pools, quoting through `client::quote` with the
+//! dynamic fee read from the pool's observations and token 2022 transfer fees.
+
+use crate::client::quote::{quote_exact_in, quote_exact_out, TransferFees};
+use crate::router_metadata::{swap_accounts_len, SwapOptionalAccounts};
+use crate::states::{
+    AmmConfig, ObservationState, PoolState, PriceBand, MINT_EXPOSURE_SEED, POOL_WHITELIST_SEED,
+    PRICE_BAND_EXTENSION, PRICE_BAND_SEED, QUOTE_CACHE_EXTENSION, QUOTE_CACHE_SEED,
+    SWAP_HOOK_EXTENSION, SWAP_WHITELIST_EXTENSION, TOKEN_0_EXPOSURE_EXTENSION,
+    TOKEN_1_EXPOSURE_EXTENSION,
+};
+use crate::AUTH_SEED;
+use anchor_lang::prelude::AccountMeta;
+use anchor_lang::solana_program::{instruction::Instruction, pubkey::Pubkey};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig, transfer_hook, BaseStateWithExtensions,
+        StateWithExtensions, StateWithExtensionsOwned,
+    },
+    state::{Account as TokenAccount, Mint},
+};
+use anyhow::{anyhow, ensure, Context, Result};
+use jupiter_amm_interface::{
+    try_get_account_data, AccountMap, Amm, AmmContext, KeyedAccount, Quote, QuoteParams,
+    SwapAndAccountMetas, SwapMode, SwapParams,
+};
+use rust_decimal::Decimal;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone)]
+struct TokenMints {
+    token_0_mint: StateWithExtensionsOwned<Mint>,
+    token_1_mint: StateWithExtensionsOwned<Mint>,
+}
+
+#[derive(Clone)]
+pub struct Gamma {
+    key: Pubkey,
+    pool_state: PoolState,
+    amm_config: Option<AmmConfig>,
+    observation_state: Option<ObservationState>,
+    token_mints: Option<TokenMints>,
+    vault_0_amount: Option<u64>,
+    vault_1_amount: Option<u64>,
+    /// The oracle of the pool's price band, swaps have to pass it along with the band
+    price_band_oracle: Option<Pubkey>,
+    epoch: Arc<AtomicU64>,
+    timestamp: Arc<AtomicI64>,
+}
+
+fn mint_exposure(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MINT_EXPOSURE_SEED.as_bytes(), mint.as_ref()],
+        &crate::id(),
+    )
+    .0
+}
+
+fn transfer_fee_config(mint: &StateWithExtensionsOwned<Mint>) -> Option<TransferFeeConfig> {
+    mint.get_extension::<TransferFeeConfig>().ok().copied()
+}
+
+impl Gamma {
+    fn authority(&self) -> Pubkey {
+        Pubkey::create_program_address(
+            &[AUTH_SEED.as_bytes(), &[self.pool_state.auth_bump]],
+            &crate::id(),
+        )
+        .expect("auth bump of a pool is valid")
+    }
+
+    fn price_band(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[PRICE_BAND_SEED.as_bytes(), self.key.as_ref()],
+            &crate::id(),
+        )
+        .0
+    }
+
+    fn quote_cache(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[QUOTE_CACHE_SEED.as_bytes(), self.key.as_ref()],
+            &crate::id(),
+        )
+        .0
+    }
+
+    /// The swap instruction built here passes neither a swap hook nor the accounts of transfer
+    /// hooks, so pools needing either aren't routed through
+    fn check_routable(&self) -> Result<()> {
+        ensure!(
+            !self.pool_state.has_extension(SWAP_HOOK_EXTENSION),
+            "Pools with a swap hook aren't supported"
+        );
+        if let Some(token_mints) = &self.token_mints {
+            ensure!(
+                transfer_hook::get_program_id(&token_mints.token_0_mint).is_none()
+                    && transfer_hook::get_program_id(&token_mints.token_1_mint).is_none(),
+                "Mints with a transfer hook aren't supported"
+            );
+        }
+        Ok(())
+    }
+
+    fn optional_accounts(&self) -> SwapOptionalAccounts {
+        SwapOptionalAccounts {
+            price_band: self.pool_state.has_extension(PRICE_BAND_EXTENSION),
+            mint_exposure: self.pool_state.has_extension(TOKEN_0_EXPOSURE_EXTENSION)
+                || self.pool_state.has_extension(TOKEN_1_EXPOSURE_EXTENSION),
+            quote_cache: self.pool_state.has_extension(QUOTE_CACHE_EXTENSION),
+            whitelist_entry: self.pool_state.has_extension(SWAP_WHITELIST_EXTENSION),
+            ..Default::default()
+        }
+    }
+
+    /// The `swap_base_input` or `swap_base_output` instruction of `swap_params`, for routers
+    /// calling gamma directly
+    pub fn swap_instruction(&self, swap_params: &SwapParams) -> Result<Instruction> {
+        let data = match swap_params.swap_mode {
+            SwapMode::ExactIn => crate::instruction::SwapBaseInput {
+                amount_in: swap_params.in_amount,
+                minimum_amount_out: swap_params.out_amount,
+            }
+            .data(),
+            SwapMode::ExactOut => crate::instruction::SwapBaseOutput {
+                max_amount_in: swap_params.in_amount,
+                amount_out: swap_params.out_amount,
+            }
+            .data(),
+        };
+        Ok(Instruction {
+            program_id: crate::id(),
+            accounts: self.swap_account_metas(swap_params)?,
+            data,
+        })
+    }
+
+    fn swap_account_metas(&self, swap_params: &SwapParams) -> Result<Vec<AccountMeta>> {
+        self.check_routable()?;
+        let zero_for_one = swap_params.source_mint == self.pool_state.token_0_mint;
+        let (input, output) = if zero_for_one {
+            (
+                (
+                    self.pool_state.token_0_vault,
+                    self.pool_state.token_0_mint,
+                    self.pool_state.token_0_program,
+                ),
+                (
+                    self.pool_state.token_1_vault,
+                    self.pool_state.token_1_mint,
+                    self.pool_state.token_1_program,
+                ),
+            )
+        } else {
+            (
+                (
+                    self.pool_state.token_1_vault,
+                    self.pool_state.token_1_mint,
+                    self.pool_state.token_1_program,
+                ),
+                (
+                    self.pool_state.token_0_vault,
+                    self.pool_state.token_0_mint,
+                    self.pool_state.token_0_program,
+                ),
+            )
+        };
+        let mut account_metas = crate::accounts::Swap {
+            payer: swap_params.token_transfer_authority,
+            authority: self.authority(),
+            amm_config: self.pool_state.amm_config,
+            pool_state: self.key,
+            input_token_account: swap_params.source_token_account,
+            output_token_account: swap_params.destination_token_account,
+            input_vault: input.0,
+            output_vault: output.0,
+            input_token_program: input.2,
+            output_token_program: output.2,
+            input_token_mint: input.1,
+            output_token_mint: output.1,
+            observation_state: self.pool_state.observation_key,
+        }
+        .to_account_metas(None);
+
+        // Optional accounts are positional, the ones skipped are the program id
+        let optional_accounts = self.optional_accounts();
+        let mut remaining_accounts = vec![
+            AccountMeta::new_readonly(crate::id(), false);
+            optional_accounts.remaining_accounts_len()
+        ];
+        if optional_accounts.price_band {
+            let oracle = self
+                .price_band_oracle
+                .context("Missing price band oracle")?;
+            remaining_accounts[11] = AccountMeta::new_readonly(self.price_band(), false);
+            remaining_accounts[12] = AccountMeta::new_readonly(oracle, false);
+        }
+        if optional_accounts.mint_exposure {
+            let (input_extension, output_extension) = if zero_for_one {
+                (TOKEN_0_EXPOSURE_EXTENSION, TOKEN_1_EXPOSURE_EXTENSION)
+            } else {
+                (TOKEN_1_EXPOSURE_EXTENSION, TOKEN_0_EXPOSURE_EXTENSION)
+            };
+            if self.pool_state.has_extension(input_extension) {
+                remaining_accounts[13] = AccountMeta::new(mint_exposure(&input.1), false);
+            }
+            if self.pool_state.has_extension(output_extension) {
+                remaining_accounts[14] = AccountMeta::new(mint_exposure(&output.1), false);
+            }
+        }
+        if optional_accounts.quote_cache {
+            remaining_accounts[15] = AccountMeta::new(self.quote_cache(), false);
+        }
+        if optional_accounts.whitelist_entry {
+            let whitelist_entry = Pubkey::find_program_address(
+                &[
+                    POOL_WHITELIST_SEED.as_bytes(),
+                    self.key.as_ref(),
+                    swap_params.token_transfer_authority.as_ref(),
+                ],
+                &crate::id(),
+            )
+            .0;
+            remaining_accounts[16] = AccountMeta::new_readonly(whitelist_entry, false);
+        }
+        account_metas.extend(remaining_accounts);
+        Ok(account_metas)
+    }
+}
+
+impl Amm for Gamma {
+    fn from_keyed_account(keyed_account: &KeyedAccount, amm_context: &AmmContext) -> Result<Self> {
+        let pool_state = PoolState::try_deserialize(&mut keyed_account.account.data.as_ref())?;
+
+        Ok(Self {
+            key: keyed_account.key,
+            pool_state,
+            amm_config: None,
+            observation_state: None,
+            token_mints: None,
+            vault_0_amount: None,
+            vault_1_amount: None,
+            price_band_oracle: None,
+            epoch: amm_context.clock_ref.epoch.clone(),
+            timestamp: amm_context.clock_ref.unix_timestamp.clone(),
+        })
+    }
+
+    fn label(&self) -> String {
+        "GAMMA".into()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        crate::id()
+    }
+
+    fn key(&self) -> Pubkey {
+        self.key
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        vec![self.pool_state.token_0_mint, self.pool_state.token_1_mint]
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        let mut keys = vec![
+            self.key,
+            self.pool_state.amm_config,
+            self.pool_state.observation_key,
+            self.pool_state.token_0_vault,
+            self.pool_state.token_1_vault,
+            self.pool_state.token_0_mint,
+            self.pool_state.token_1_mint,
+        ];
+        if self.pool_state.has_extension(PRICE_BAND_EXTENSION) {
+            keys.push(self.price_band());
+        }
+        keys
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        let pool_state_data = try_get_account_data(account_map, &self.key)?;
+        self.pool_state = PoolState::try_deserialize(&mut pool_state_data.as_ref())?;
+
+        let amm_config_data = try_get_account_data(account_map, &self.pool_state.amm_config)?;
+        self.amm_config = Some(AmmConfig::try_deserialize(&mut amm_config_data.as_ref())?);
+
+        // Observation accounts grown by `increase_observation_cardinality` carry an archive
+        // after the ring, the fee only reads the ring
+        let observation_data = try_get_account_data(account_map, &self.pool_state.observation_key)?;
+        let observation_data = observation_data
+            .get(..ObservationState::LEN)
+            .context("Observation account too small")?;
+        self.observation_state = Some(ObservationState::try_deserialize(
+            &mut &observation_data[..],
+        )?);
+
+        let unpack_mint = |mint| -> Result<StateWithExtensionsOwned<Mint>> {
+            let mint_data = try_get_account_data(account_map, mint)?;
+            Ok(StateWithExtensionsOwned::<Mint>::unpack(
+                mint_data.to_vec(),
+            )?)
+        };
+        self.token_mints = Some(TokenMints {
+            token_0_mint: unpack_mint(&self.pool_state.token_0_mint)?,
+            token_1_mint: unpack_mint(&self.pool_state.token_1_mint)?,
+        });
+
+        // A frozen vault can't be swapped against
+        let unfrozen_amount = |vault| {
+            try_get_account_data(account_map, vault)
+                .ok()
+                .and_then(|data| StateWithExtensions::<TokenAccount>::unpack(data).ok())
+                .filter(|vault| !vault.base.is_frozen())
+                .map(|vault| vault.base.amount)
+        };
+        self.vault_0_amount = unfrozen_amount(&self.pool_state.token_0_vault);
+        self.vault_1_amount = unfrozen_amount(&self.pool_state.token_1_vault);
+
+        self.price_band_oracle = if self.pool_state.has_extension(PRICE_BAND_EXTENSION) {
+            let price_band_data = try_get_account_data(account_map, &self.price_band())?;
+            Some(PriceBand::try_deserialize(&mut price_band_data.as_ref())?.oracle)
+        } else {
+            None
+        };
+
+        Ok(())
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        self.check_routable()?;
+        let amm_config = self.amm_config.as_ref().context("Missing AmmConfig")?;
+        let observation_state = self
+            .observation_state
+            .as_ref()
+            .context("Missing observation state")?;
+        let token_mints = self.token_mints.as_ref().context("Missing token mints")?;
+        let (vault_0_amount, vault_1_amount) = self
+            .vault_0_amount
+            .zip(self.vault_1_amount)
+            .context("Missing or frozen vaults")?;
+        let transfer_fees = TransferFees {
+            token_0: transfer_fee_config(&token_mints.token_0_mint),
+            token_1: transfer_fee_config(&token_mints.token_1_mint),
+            epoch: self.epoch.load(Ordering::Relaxed),
+        };
+        let timestamp = self.timestamp.load(Ordering::Relaxed) as u64;
+
+        // Rebasing pools sync their reserves to the vaults before the swap
+        let mut pool_state = self.pool_state;
+        pool_state.sync_rebasing_reserve(true, vault_0_amount)?;
+        pool_state.sync_rebasing_reserve(false, vault_1_amount)?;
+
+        let zero_for_one = quote_params.input_mint == pool_state.token_0_mint;
+        let swap_quote = match quote_params.swap_mode {
+            SwapMode::ExactIn => quote_exact_in(
+                &pool_state,
+                amm_config,
+                observation_state,
+                &transfer_fees,
+                zero_for_one,
+                quote_params.amount,
+                timestamp,
+            )?,
+            SwapMode::ExactOut => quote_exact_out(
+                &pool_state,
+                amm_config,
+                observation_state,
+                &transfer_fees,
+                zero_for_one,
+                quote_params.amount,
+                timestamp,
+            )?,
+        };
+
+        // Past what the output vault holds, the swap recalls the rest from the vault's strategy,
+        // whose accounts the swap instruction doesn't pass
+        let output_vault_amount = if zero_for_one {
+            vault_1_amount
+        } else {
+            vault_0_amount
+        };
+        let payout = swap_quote
+            .amount_out
+            .checked_add(swap_quote.output_transfer_fee)
+            .context("Payout overflow")?;
+        ensure!(
+            payout <= output_vault_amount,
+            "Output vault short of the payout, the swap needs the accounts of its strategy"
+        );
+
+        Ok(Quote {
+            in_amount: swap_quote.amount_in,
+            out_amount: swap_quote.amount_out,
+            fee_mint: quote_params.input_mint,
+            fee_amount: swap_quote.dynamic_fee,
+            fee_pct: Decimal::new(swap_quote.dynamic_fee_rate as i64, 6),
+            ..Default::default()
+        })
+    }
+
+    fn supports_exact_out(&self) -> bool {
+        true
+    }
+
+    fn get_accounts_len(&self) -> usize {
+        swap_accounts_len(self.optional_accounts())
+    }
+
+    /// Jupiter's `Swap` has no gamma variant to route with yet, routers can build the
+    /// instruction with `Gamma::swap_instruction`
+    fn get_swap_and_account_metas(&self, _swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
+        Err(anyhow!(
+            "No jupiter Swap variant for gamma, use Gamma::swap_instruction"
+        ))
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,9 @@
+//! Off-chain helpers for integrators, compiled with the `client` feature only
+pub mod jupiter;
+pub mod position_pnl;
+// Also used on chain by `get_quote`
+pub use crate::utils::quote;
+
+pub use jupiter::*;
+pub use position_pnl::*;
+pub use quote::*;
@@ -0,0 +1,135 @@
+use crate::states::{LpChangeEvent, Observation, ObservationState, PoolState, UserPoolLiquidity};
+use anchor_lang::prelude::*;
+
+const Q32: f64 = (1u64 << 32) as f64;
+
+/// One change of a position: the LpChangeEvent emitted by the owner's transaction, with the
+/// lp the owner held right after it, read from their UserPoolLiquidity
+#[derive(Clone, Debug)]
+pub struct PositionChange {
+    pub event: LpChangeEvent,
+    pub lp_tokens_owned_after: u128,
+}
+
+/// P&L of an lp position, every value in raw token_1 units at the valuation price
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PositionPnl {
+    /// Price of token_0 in token_1 the position is valued at, Q32.32
+    pub token_0_price_x32: u128,
+    /// What the position would withdraw now
+    pub position_value: f64,
+    /// What the tokens deposited minus the ones withdrawn would be worth if held instead
+    pub hold_value: f64,
+    /// Trade fees the position earned since it was opened
+    pub fees_earned: f64,
+    /// Value lost to the price moving, negative or zero
+    pub impermanent_loss: f64,
+}
+
+impl PositionPnl {
+    /// Total P&L against holding, fees included
+    pub fn pnl_vs_hold(&self) -> f64 {
+        self.position_value - self.hold_value
+    }
+}
+
+/// Square root of the reserves product per lp token. Swaps only move it through the fees they
+/// leave in the pool, deposits and withdrawals don't move it at all.
+fn liquidity_per_lp(token_0_amount: u64, token_1_amount: u64, lp_supply: u64) -> Option<f64> {
+    if lp_supply == 0 {
+        return None;
+    }
+    Some((token_0_amount as f64 * token_1_amount as f64).sqrt() / lp_supply as f64)
+}
+
+/// Reconstructs the P&L of a position from its changes, given in the order they happened.
+/// The position is valued at the twap of the last `window` seconds rather than at the spot
+/// price, so a single trade can't skew it.
+///
+/// Fees are told apart from impermanent loss by how much the liquidity per lp grew while the
+/// position held it. That is exact for constant product pools only, on stable pools the split
+/// is an approximation while the total stays exact.
+///
+/// # Arguments
+///
+/// * `user_pool_liquidity` - The current UserPoolLiquidity of the position
+/// * `changes` - Every deposit and withdrawal of the position, oldest first
+/// * `pool_state` - The current pool state
+/// * `observation_state` - The pool's observations, with `archive` the ones archived after them
+/// * `current_time` - The current timestamp
+/// * `window` - The number of seconds of the twap the position is valued at
+///
+pub fn position_pnl(
+    user_pool_liquidity: &UserPoolLiquidity,
+    changes: &[PositionChange],
+    pool_state: &PoolState,
+    observation_state: &ObservationState,
+    archive: &[Observation],
+    current_time: u64,
+    window: u64,
+) -> Result<PositionPnl> {
+    let (token_0_amount, token_1_amount) = pool_state.vault_amount_without_fee()?;
+    let (spot_token_0_price_x32, spot_token_1_price_x32) = pool_state.token_price_x32()?;
+    let twap = observation_state.interpolated_twap(
+        archive,
+        current_time,
+        window,
+        spot_token_0_price_x32,
+        spot_token_1_price_x32,
+    )?;
+    let price = twap.token_0_price_x32 as f64 / Q32;
+    let value = |amount_0: f64, amount_1: f64| amount_0 * price + amount_1;
+
+    let lp_supply = pool_state.lp_supply as f64;
+    let lp_tokens_owned = user_pool_liquidity.lp_tokens_owned as f64;
+    let position_value = if pool_state.lp_supply == 0 {
+        0.0
+    } else {
+        value(
+            token_0_amount as f64 * lp_tokens_owned / lp_supply,
+            token_1_amount as f64 * lp_tokens_owned / lp_supply,
+        )
+    };
+    let hold_value = value(
+        user_pool_liquidity.token_0_deposited as f64 - user_pool_liquidity.token_0_withdrawn as f64,
+        user_pool_liquidity.token_1_deposited as f64 - user_pool_liquidity.token_1_withdrawn as f64,
+    );
+
+    // The liquidity the position would have if the pool had never earned a fee
+    let mut liquidity_without_fees = 0.0;
+    let mut lp_tokens_before = 0.0;
+    for change in changes {
+        let event = &change.event;
+        let Some(liquidity_per_lp) = liquidity_per_lp(
+            event.token_0_vault_before,
+            event.token_1_vault_before,
+            event.lp_amount_before,
+        ) else {
+            // The deposit creating the pool owns all of its liquidity
+            lp_tokens_before = change.lp_tokens_owned_after as f64;
+            liquidity_without_fees =
+                (event.token_0_amount as f64 * event.token_1_amount as f64).sqrt();
+            continue;
+        };
+        let lp_tokens_after = change.lp_tokens_owned_after as f64;
+        if lp_tokens_after >= lp_tokens_before {
+            liquidity_without_fees += (lp_tokens_after - lp_tokens_before) * liquidity_per_lp;
+        } else if lp_tokens_before > 0.0 {
+            liquidity_without_fees *= lp_tokens_after / lp_tokens_before;
+        }
+        lp_tokens_before = lp_tokens_after;
+    }
+    let current_liquidity = liquidity_per_lp(token_0_amount, token_1_amount, pool_state.lp_supply)
+        .map_or(0.0, |liquidity_per_lp| liquidity_per_lp * lp_tokens_owned);
+    // Liquidity is worth 2 * sqrt(price) in token_1 on a constant product curve
+    let fees_earned = (2.0 * price.sqrt() * (current_liquidity - liquidity_without_fees)).max(0.0);
+    let impermanent_loss = (position_value - hold_value - fees_earned).min(0.0);
+
+    Ok(PositionPnl {
+        token_0_price_x32: twap.token_0_price_x32,
+        position_value,
+        hold_value,
+        fees_earned,
+        impermanent_loss,
+    })
+}
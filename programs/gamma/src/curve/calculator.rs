@@ -1,12 +1,20 @@
 //! Swap calculations
 
 use crate::error::GammaError;
-use crate::fees::{DynamicFee, FeeType};
+use crate::fees::{discounted_fee_rate, DynamicFee, FeeTrade};
 use crate::states::{AmmConfig, ObservationState, PoolState};
-use crate::{curve::constant_product::ConstantProductCurve, fees::StaticFee};
+use crate::{
+    curve::{
+        constant_product::ConstantProductCurve, stable_swap::StableSwapCurve,
+        weighted::WeightedCurve,
+    },
+    fees::StaticFee,
+};
 use anchor_lang::prelude::*;
 use std::fmt::Debug;
 
+pub use gamma_math::curve::{RoundDirection, TradingTokenResult};
+
 /// Helper function for mapping to GammaError::CalculationFailure
 pub fn map_zero_to_none(x: u128) -> Option<u128> {
     if x == 0 {
@@ -27,6 +35,15 @@ pub enum TradeDirection {
 }
 
 impl TradeDirection {
+    /// Direction of a trade that sells token_0 when `zero_for_one`
+    pub fn from_zero_for_one(zero_for_one: bool) -> TradeDirection {
+        if zero_for_one {
+            TradeDirection::ZeroForOne
+        } else {
+            TradeDirection::OneForZero
+        }
+    }
+
     /// Given a trade direction, gives the opposite direction of the trade, so
     /// A to B becomes B to A, and vice versa
     pub fn opposite(&self) -> TradeDirection {
@@ -37,26 +54,6 @@ impl TradeDirection {
     }
 }
 
-/// The direction to round.  Used for pool token to trading token conversions to
-/// avoid losing value on any deposit or withdrawal.
-#[repr(C)]
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum RoundDirection {
-    /// Floor the value, ie. 1.9 => 1, 1.1 => 1, 1.5 => 1
-    Floor,
-    /// Ceiling the value, ie. 1.9 => 2, 1.1 => 2, 1.5 => 2
-    Ceiling,
-}
-
-/// Encodes results of depositing both sides at once
-#[derive(Debug, PartialEq)]
-pub struct TradingTokenResult {
-    /// Amount of token A
-    pub token_0_amount: u128,
-    /// Amount of token B
-    pub token_1_amount: u128,
-}
-
 /// Encodes all results of swapping from a source token to a destination token
 #[derive(Debug, PartialEq)]
 pub struct SwapResult {
@@ -100,6 +97,7 @@ impl CurveCalculator {
         source_amount_to_be_swapped: u128,
         swap_source_amount: u128,
         swap_destination_amount: u128,
+        trade_direction: TradeDirection,
         amm_config: &AmmConfig,
         pool_state: &PoolState,
         block_timestamp: u64,
@@ -107,31 +105,76 @@ impl CurveCalculator {
         // This is to indicate that the trade is not a toxic trade and is coming to us from a signed dflow segmenter.
         // It is planed to charge an additional fee for this trade if it is false in future.
         is_invoked_by_signed_segmenter: bool,
-        // TODO: add fee type here once that is configurable on pool level/ or we can use it from pool_state
+        // Share of the trade fee rate taken off for the trader, in bps
+        trade_fee_discount_bps: u16,
     ) -> Result<SwapResult> {
-        let (dynamic_fee, dynamic_fee_rate) = DynamicFee::dynamic_fee(
-            source_amount_to_be_swapped,
+        let (model_fee, model_fee_rate) = DynamicFee::dynamic_fee(
+            FeeTrade {
+                amount: source_amount_to_be_swapped,
+                swap_source_amount,
+                trade_direction,
+            },
             block_timestamp,
             observation_state,
-            FeeType::Volatility,
-            amm_config.trade_fee_rate,
+            pool_state.fee_model(block_timestamp)?,
+            discounted_fee_rate(
+                pool_state.trade_fee_rate(amm_config),
+                trade_fee_discount_bps,
+            ),
             pool_state,
             is_invoked_by_signed_segmenter,
         )?;
 
-        let protocol_fee = StaticFee::protocol_fee(dynamic_fee, amm_config.protocol_fee_rate)
+        // The launch fee's surcharge is left to LPs, the protocol and fund fees are shares of the
+        // fee model's fee
+        let protocol_fee = StaticFee::protocol_fee(model_fee, amm_config.protocol_fee_rate)
             .ok_or(GammaError::InvalidFee)?;
-        let fund_fee = StaticFee::fund_fee(dynamic_fee, amm_config.fund_fee_rate)
+        let fund_fee = StaticFee::fund_fee(model_fee, amm_config.fund_fee_rate)
             .ok_or(GammaError::InvalidFee)?;
+        let dynamic_fee_rate = pool_state.launch_fee_rate_at(block_timestamp, model_fee_rate);
+        let dynamic_fee = DynamicFee::fee_amount(source_amount_to_be_swapped, dynamic_fee_rate)?;
 
-        let source_amount_after_fees = source_amount_to_be_swapped
-            .checked_sub(dynamic_fee)
-            .ok_or(GammaError::MathOverflow)?;
-        let destination_amount_swapped = ConstantProductCurve::swap_base_input_without_fees(
-            source_amount_after_fees,
-            swap_source_amount,
-            swap_destination_amount,
-        )?;
+        let Some(source_amount_after_fees) = source_amount_to_be_swapped.checked_sub(dynamic_fee)
+        else {
+            msg!(
+                "dynamic_fee: {} exceeds source_amount: {}",
+                dynamic_fee,
+                source_amount_to_be_swapped
+            );
+            return err!(GammaError::FeeExceedsInput);
+        };
+        let destination_amount_swapped = if pool_state.is_stable() {
+            StableSwapCurve::swap_base_input_without_fees(
+                source_amount_after_fees,
+                swap_source_amount,
+                swap_destination_amount,
+                pool_state.amplification,
+            )?
+        } else if pool_state.is_weighted() {
+            let (source_weight, destination_weight) = pool_state.trade_weights(trade_direction);
+            WeightedCurve::swap_base_input_without_fees(
+                source_amount_after_fees,
+                swap_source_amount,
+                swap_destination_amount,
+                source_weight,
+                destination_weight,
+            )?
+        } else {
+            ConstantProductCurve::swap_base_input_without_fees(
+                source_amount_after_fees,
+                swap_source_amount,
+                swap_destination_amount,
+            )?
+        };
+
+        if destination_amount_swapped >= swap_destination_amount {
+            msg!(
+                "destination_amount_swapped: {} exhausts swap_destination_amount: {}",
+                destination_amount_swapped,
+                swap_destination_amount
+            );
+            return err!(GammaError::InsufficientLiquidity);
+        }
 
         #[cfg(feature = "enable-log")]
         msg!("dynamic_fee: {}", dynamic_fee);
@@ -157,34 +200,74 @@ impl CurveCalculator {
         destination_amount_to_be_swapped: u128,
         swap_source_amount: u128,
         swap_destination_amount: u128,
+        trade_direction: TradeDirection,
         amm_config: &AmmConfig,
         pool_state: &PoolState,
         block_timestamp: u64,
         observation_state: &ObservationState,
         is_invoked_by_signed_segmenter: bool,
+        trade_fee_discount_bps: u16,
     ) -> Result<SwapResult> {
-        let source_amount_swapped = ConstantProductCurve::swap_base_output_without_fees(
-            destination_amount_to_be_swapped,
-            swap_source_amount,
-            swap_destination_amount,
-        )?;
+        if destination_amount_to_be_swapped >= swap_destination_amount {
+            msg!(
+                "destination_amount: {} exhausts swap_destination_amount: {}",
+                destination_amount_to_be_swapped,
+                swap_destination_amount
+            );
+            return err!(GammaError::InsufficientLiquidity);
+        }
+        let source_amount_swapped = if pool_state.is_stable() {
+            StableSwapCurve::swap_base_output_without_fees(
+                destination_amount_to_be_swapped,
+                swap_source_amount,
+                swap_destination_amount,
+                pool_state.amplification,
+            )?
+        } else if pool_state.is_weighted() {
+            let (source_weight, destination_weight) = pool_state.trade_weights(trade_direction);
+            WeightedCurve::swap_base_output_without_fees(
+                destination_amount_to_be_swapped,
+                swap_source_amount,
+                swap_destination_amount,
+                source_weight,
+                destination_weight,
+            )?
+        } else {
+            ConstantProductCurve::swap_base_output_without_fees(
+                destination_amount_to_be_swapped,
+                swap_source_amount,
+                swap_destination_amount,
+            )?
+        };
 
-        let (source_amount, dynamic_fee_rate) = DynamicFee::calculate_pre_fee_amount(
+        let (model_source_amount, model_fee_rate) = DynamicFee::calculate_pre_fee_amount(
             block_timestamp,
-            source_amount_swapped,
+            FeeTrade {
+                amount: source_amount_swapped,
+                swap_source_amount,
+                trade_direction,
+            },
             observation_state,
-            FeeType::Volatility,
-            amm_config.trade_fee_rate,
+            pool_state.fee_model(block_timestamp)?,
+            discounted_fee_rate(
+                pool_state.trade_fee_rate(amm_config),
+                trade_fee_discount_bps,
+            ),
             pool_state,
             is_invoked_by_signed_segmenter,
         )?;
 
-        let dynamic_fee = source_amount
+        let model_fee = model_source_amount
             .checked_sub(source_amount_swapped)
             .ok_or(GammaError::MathOverflow)?;
-        let protocol_fee = StaticFee::protocol_fee(dynamic_fee, amm_config.protocol_fee_rate)
+        let protocol_fee = StaticFee::protocol_fee(model_fee, amm_config.protocol_fee_rate)
+            .ok_or(GammaError::MathOverflow)?;
+        let fund_fee = StaticFee::fund_fee(model_fee, amm_config.fund_fee_rate)
             .ok_or(GammaError::MathOverflow)?;
-        let fund_fee = StaticFee::fund_fee(dynamic_fee, amm_config.fund_fee_rate)
+        let dynamic_fee_rate = pool_state.launch_fee_rate_at(block_timestamp, model_fee_rate);
+        let source_amount = DynamicFee::pre_fee_amount(source_amount_swapped, dynamic_fee_rate)?;
+        let dynamic_fee = source_amount
+            .checked_sub(source_amount_swapped)
             .ok_or(GammaError::MathOverflow)?;
 
         Ok(SwapResult {
@@ -1,11 +1,11 @@
 //! The Uniswap invariantConstantProductCurve::
 
-use crate::utils::math::CheckedCeilDiv;
 use crate::{
     curve::calculator::{RoundDirection, TradingTokenResult},
     error::GammaError,
 };
 use anchor_lang::prelude::*;
+use gamma_math::curve::constant_product;
 
 /// ConstantProductCurve struct implementing CurveCalculator
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -23,18 +23,12 @@ impl ConstantProductCurve {
         swap_source_amount: u128,
         swap_destination_amount: u128,
     ) -> Result<u128> {
-        // (x + delta_x) * (y - delta_y) = x * y
-        // delta_y = (delta_x * y) / (x + delta_x)
-        let numerator = source_amount_to_be_swapped
-            .checked_mul(swap_destination_amount)
-            .ok_or(GammaError::MathOverflow)?;
-        let denominator = swap_source_amount
-            .checked_add(source_amount_to_be_swapped)
-            .ok_or(GammaError::MathOverflow)?;
-        let destination_amount_swapped = numerator
-            .checked_div(denominator)
-            .ok_or(GammaError::MathOverflow)?;
-        Ok(destination_amount_swapped)
+        Ok(constant_product::swap_base_input_without_fees(
+            source_amount_to_be_swapped,
+            swap_source_amount,
+            swap_destination_amount,
+        )
+        .ok_or(GammaError::MathOverflow)?)
     }
 
     pub fn swap_base_output_without_fees(
@@ -42,25 +36,16 @@ impl ConstantProductCurve {
         swap_source_amount: u128,
         swap_destination_amount: u128,
     ) -> Result<u128> {
-        // (x + delta_x) * (y - delta_y) = x * y
-        // delta_x = (x * delta_y) / (y - delta_y)
-        let numerator = swap_source_amount
-            .checked_mul(destination_amount_to_be_swapped)
-            .ok_or(GammaError::MathOverflow)?;
-        let denominator = swap_destination_amount
-            .checked_sub(destination_amount_to_be_swapped)
-            .ok_or(GammaError::MathOverflow)?;
-        let (source_amount_swapped, _) = numerator
-            .checked_ceil_div(denominator)
-            .ok_or(GammaError::MathOverflow)?;
-        Ok(source_amount_swapped)
+        Ok(constant_product::swap_base_output_without_fees(
+            destination_amount_to_be_swapped,
+            swap_source_amount,
+            swap_destination_amount,
+        )
+        .ok_or(GammaError::MathOverflow)?)
     }
 
     /// Get the amount of trading tokens(token_0 and token_1) for a given amount of pool tokens(lp_tokens)
     /// provided the total trading tokens and supply of pool tokens
-    ///
-    /// The constant product implementation is a simple ratio calcluations for the amount of trading tokens
-    /// corresponding to a certain number of pool tokens.
     pub fn lp_tokens_to_trading_tokens(
         lp_token_amount: u128,
         lp_token_supply: u128,
@@ -68,42 +53,13 @@ impl ConstantProductCurve {
         swap_token_1_amount: u128,
         round_direction: RoundDirection,
     ) -> Option<TradingTokenResult> {
-        // token_0_amount = (lp_token_amount * swap_token_0_amount) / lp_token_supply
-        // lp_token_amount - Amount of pool tokens to be exchanged
-        // swap_token_0_amount - Total token_0 amount in the pool
-        let mut token_0_amount = lp_token_amount
-            .checked_mul(swap_token_0_amount)?
-            .checked_div(lp_token_supply)?;
-        let mut token_1_amount = lp_token_amount
-            .checked_mul(swap_token_1_amount)?
-            .checked_div(lp_token_supply)?;
-        let (token_0_amount, token_1_amount) = match round_direction {
-            RoundDirection::Floor => (token_0_amount, token_1_amount),
-            RoundDirection::Ceiling => {
-                let token_0_remainder = lp_token_amount
-                    .checked_mul(swap_token_0_amount)?
-                    .checked_rem(lp_token_supply)?;
-                // Also check for 0 token A and B amount to avoid taking too much
-                // for tiny amounts of pool tokens.  For example, if someone asks
-                // for 1 pool token, which is worth 0.01 token A, we avoid the
-                // ceiling of taking 1 token A and instead return 0, for it to be
-                // rejected later in processing.
-                if token_0_remainder > 0 && token_0_amount > 0 {
-                    token_0_amount = token_0_amount.checked_add(1)?;
-                }
-                let token_1_remainder = lp_token_amount
-                    .checked_mul(swap_token_1_amount)?
-                    .checked_rem(lp_token_supply)?;
-                if token_1_remainder > 0 && token_1_amount > 0 {
-                    token_1_amount = token_1_amount.checked_add(1)?;
-                }
-                (token_0_amount, token_1_amount)
-            }
-        };
-        Some(TradingTokenResult {
-            token_0_amount,
-            token_1_amount,
-        })
+        constant_product::lp_tokens_to_trading_tokens(
+            lp_token_amount,
+            lp_token_supply,
+            swap_token_0_amount,
+            swap_token_1_amount,
+            round_direction,
+        )
     }
 
     /// Get the amount of lp-tokens for a given amount of trading tokens
@@ -112,10 +68,11 @@ impl ConstantProductCurve {
         total_token_0_amount: u128,
         lp_token_supply: u128,
     ) -> Option<u128> {
-        let lp_token_amount = trading_token_0_amount
-            .checked_mul(lp_token_supply)?
-            .checked_div(total_token_0_amount)?;
-        Some(lp_token_amount)
+        constant_product::trading_token_to_lp_tokens(
+            trading_token_0_amount,
+            total_token_0_amount,
+            lp_token_supply,
+        )
     }
 
     pub fn token_1_to_lp_tokens(
@@ -123,9 +80,10 @@ impl ConstantProductCurve {
         total_token_1_amount: u128,
         lp_token_supply: u128,
     ) -> Option<u128> {
-        let lp_token_amount = trading_token_1_amount
-            .checked_mul(lp_token_supply)?
-            .checked_div(total_token_1_amount)?;
-        Some(lp_token_amount)
+        constant_product::trading_token_to_lp_tokens(
+            trading_token_1_amount,
+            total_token_1_amount,
+            lp_token_supply,
+        )
     }
 }
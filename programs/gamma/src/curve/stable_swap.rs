@@ -0,0 +1,69 @@
+//! The Curve stable swap invariant for two tokens:
+//! A * n^n * (x + y) + D = A * D * n^n + D^(n+1) / (n^n * x * y), with n = 2
+
+use crate::{error::GammaError, utils::U256};
+use anchor_lang::prelude::*;
+use gamma_math::curve::stable_swap;
+
+pub use gamma_math::curve::stable_swap::{MAX_AMPLIFICATION, MIN_AMPLIFICATION};
+
+/// StableSwapCurve struct implementing CurveCalculator
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StableSwapCurve;
+
+impl StableSwapCurve {
+    /// Computes the invariant D for the given balances
+    pub fn compute_d(amplification: u64, amount_0: u128, amount_1: u128) -> Option<U256> {
+        stable_swap::compute_d(amplification, amount_0, amount_1)
+    }
+
+    /// Computes the balance of the other token keeping D constant when one balance is `amount`
+    pub fn compute_y(amplification: u64, amount: u128, d: U256) -> Option<U256> {
+        stable_swap::compute_y(amplification, amount, d)
+    }
+
+    /// Output amount of a swap, rounded down in favor of the pool
+    pub fn swap_base_input_without_fees(
+        source_amount_to_be_swapped: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        amplification: u64,
+    ) -> Result<u128> {
+        Ok(stable_swap::swap_base_input_without_fees(
+            source_amount_to_be_swapped,
+            swap_source_amount,
+            swap_destination_amount,
+            amplification,
+        )
+        .ok_or(GammaError::MathOverflow)?)
+    }
+
+    /// Input amount of a swap, rounded up in favor of the pool
+    pub fn swap_base_output_without_fees(
+        destination_amount_to_be_swapped: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        amplification: u64,
+    ) -> Result<u128> {
+        Ok(stable_swap::swap_base_output_without_fees(
+            destination_amount_to_be_swapped,
+            swap_source_amount,
+            swap_destination_amount,
+            amplification,
+        )
+        .ok_or(GammaError::MathOverflow)?)
+    }
+
+    /// Marginal prices at the given balances, Q32.32. Returns the price of token_0 in token_1
+    /// and the price of token_1 in token_0, like `PoolState::token_price_x32`
+    pub fn token_price_x32(
+        amplification: u64,
+        amount_0: u128,
+        amount_1: u128,
+    ) -> Result<(u128, u128)> {
+        Ok(
+            stable_swap::token_price_x32(amplification, amount_0, amount_1)
+                .ok_or(GammaError::MathOverflow)?,
+        )
+    }
+}
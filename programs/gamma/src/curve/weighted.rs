@@ -0,0 +1,66 @@
+//! The Balancer constant weight invariant for two tokens:
+//! x^w_x * y^w_y = constant, with w_x + w_y = 1
+
+use crate::error::GammaError;
+use anchor_lang::prelude::*;
+use gamma_math::curve::weighted;
+
+pub use gamma_math::curve::weighted::{MAX_WEIGHT, MIN_WEIGHT, WEIGHT_DENOMINATOR};
+
+/// WeightedCurve struct implementing CurveCalculator
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WeightedCurve;
+
+impl WeightedCurve {
+    /// Output amount of a swap, rounded down in favor of the pool
+    pub fn swap_base_input_without_fees(
+        source_amount_to_be_swapped: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        source_weight: u64,
+        destination_weight: u64,
+    ) -> Result<u128> {
+        Ok(weighted::swap_base_input_without_fees(
+            source_amount_to_be_swapped,
+            swap_source_amount,
+            swap_destination_amount,
+            source_weight,
+            destination_weight,
+        )
+        .ok_or(GammaError::MathOverflow)?)
+    }
+
+    /// Input amount of a swap, rounded up in favor of the pool
+    pub fn swap_base_output_without_fees(
+        destination_amount_to_be_swapped: u128,
+        swap_source_amount: u128,
+        swap_destination_amount: u128,
+        source_weight: u64,
+        destination_weight: u64,
+    ) -> Result<u128> {
+        Ok(weighted::swap_base_output_without_fees(
+            destination_amount_to_be_swapped,
+            swap_source_amount,
+            swap_destination_amount,
+            source_weight,
+            destination_weight,
+        )
+        .ok_or(GammaError::MathOverflow)?)
+    }
+
+    /// Marginal prices at the given balances, Q32.32. Returns the price of token_0 in token_1
+    /// and the price of token_1 in token_0, like `PoolState::token_price_x32`
+    pub fn token_price_x32(
+        amount_0: u128,
+        amount_1: u128,
+        token_0_weight: u64,
+    ) -> Result<(u128, u128)> {
+        let token_1_weight = WEIGHT_DENOMINATOR
+            .checked_sub(token_0_weight)
+            .ok_or(GammaError::MathOverflow)?;
+        Ok(
+            weighted::token_price_x32(amount_0, amount_1, token_0_weight, token_1_weight)
+                .ok_or(GammaError::MathOverflow)?,
+        )
+    }
+}
@@ -45,4 +45,110 @@ pub enum  GammaError {
     InvalidOpenTime,
     #[msg("Invalid lp token amount")]
     InvalidLpTokenAmount,
+    #[msg("Swap would move the price past the pool's floor or ceiling")]
+    PriceBoundExceeded,
+    #[msg("The pool was already reported in this epoch")]
+    FeeReportNotDue,
+    #[msg("Withdrawals can't be paused during the grace period after pausing swaps or deposits")]
+    WithdrawGracePeriod,
+    #[msg("Output token account is not owned by the required receiver")]
+    InvalidOutputReceiver,
+    #[msg("Invalid amplification, or the mints of a stable pool have different decimals")]
+    InvalidAmplification,
+    #[msg("The gauge was already settled for this epoch")]
+    GaugeAlreadySettled,
+    #[msg("Votes exceed the voter's veGOFX balance for this epoch")]
+    InsufficientVotingPower,
+    #[msg("Route must chain 2 to 3 swaps, each output feeding the next input")]
+    InvalidRoute,
+    #[msg("Solver bond is too small")]
+    InsufficientBond,
+    #[msg("Solver bond can only be withdrawn after the unbond period of a deactivated solver")]
+    SolverBonded,
+    #[msg("The pool only allows one swap per transaction, and needs the instructions sysvar to check it")]
+    DuplicateSwap,
+    #[msg("A flash loan is outstanding, or isn't repaid by a flash_repay later in the transaction")]
+    InvalidFlashLoan,
+    #[msg("The vault didn't get the flash loan back with its fee")]
+    FlashLoanNotRepaid,
+    #[msg("The oracle of the pool's price band is missing, stale or doesn't match the band")]
+    InvalidOracle,
+    #[msg("Swap would move the price further outside the band around the oracle price")]
+    PriceBandExceeded,
+    #[msg("The emergency pause is not active or hasn't expired yet")]
+    PauseNotExpired,
+    #[msg("The pool still has liquidity or owes fees from its vaults")]
+    PoolNotEmpty,
+    #[msg("The config change needs the fee approver's co-signature, propose it instead")]
+    FeeChangeNeedsApproval,
+    #[msg("The co-signed change doesn't match the pending proposal")]
+    FeeChangeMismatch,
+    #[msg("The pool is winding down, deposits and swaps can't be enabled again")]
+    PoolWindingDown,
+    #[msg("The pool tracks the exposure to its mint, and needs the mint's exposure account")]
+    MissingMintExposure,
+    #[msg("The reserves of the mint across the tracked pools would exceed its cap")]
+    MintExposureCapExceeded,
+    #[msg("The pool keeps a quote cache, and needs it passed to change its reserves")]
+    MissingQuoteCache,
+    #[msg("The config delays fee rate changes, propose it with propose_config_update instead")]
+    FeeChangeTimelocked,
+    #[msg("No config update is pending, or its delay hasn't passed yet")]
+    ConfigUpdateNotReady,
+    #[msg("The pool's trade fee is outside the bounds of its config")]
+    PoolFeeOutOfBounds,
+    #[msg("Only whitelisted addresses can use the pool, pass the signer's whitelist entry")]
+    NotWhitelisted,
+    #[msg("The swap exceeds the pool's max trade size")]
+    TradeSizeExceeded,
+    #[msg("The pair index lists as many pools as it can hold")]
+    PairIndexFull,
+    #[msg("The price moved too far from its twap, the twap breaker tripped")]
+    TwapDeviationExceeded,
+    #[msg("The pool has no empty partner slot, remove a partner first")]
+    NoEmptyPartnerSlot,
+    #[msg("The pool has as many reward mints as it can hold")]
+    TooManyRewardMints,
+    #[msg("Staked lp tokens have to be unstaked first")]
+    LpTokensStaked,
+    #[msg("The position is locked until its unlock time")]
+    PositionLocked,
+    #[msg("Invalid token weight, or the pool also sets an amplification")]
+    InvalidWeight,
+    #[msg("The mint's transfer hook program isn't allowlisted, or its entry wasn't passed")]
+    TransferHookNotAllowed,
+    #[msg("The mint has an authority or extension the config's mint safety policy rejects")]
+    UnsafeMint,
+    #[msg("The deposit takes the LP supply over the pool's cap")]
+    LpSupplyCapExceeded,
+    #[msg("The pool doesn't hold enough of the output token for the swap")]
+    InsufficientLiquidity,
+    #[msg("Nothing is left of the amount once transfer and trade fees are taken")]
+    AmountTooSmallAfterFees,
+    #[msg("The trade fee is larger than the amount it is charged on")]
+    FeeExceedsInput,
+    #[msg("A fee rate exceeds the fee denominator, or its bounds don't hold")]
+    InvalidFeeBounds,
+    #[msg("The staking discount or staked token account doesn't belong to the config or payer")]
+    InvalidStakingDiscount,
+    #[msg("The pool's withdraw delay after the last deposit hasn't passed yet")]
+    WithdrawTooSoonAfterDeposit,
+    #[msg("The price moved further within the slot than the pool allows")]
+    SlotPriceImpactExceeded,
+    #[msg("The bonding curve raised its target and only trades in its pool once graduated")]
+    BondingCurveComplete,
+    #[msg("The bonding curve hasn't raised its target yet, or already graduated")]
+    BondingCurveNotComplete,
+    #[msg("The vault strategy or its adapter program isn't the one allowlisted for the vault")]
+    InvalidStrategy,
+    #[msg("The strategy returned less to the vault than was withdrawn")]
+    StrategyShortfall,
+    #[msg("The pool's swap hook or its hook program wasn't passed, or isn't the pool's")]
+    InvalidSwapHook,
+    #[msg("The pool has a swap hook, it only swaps through swap_base_input or swap_base_output")]
+    SwapHookNotSupported,
+    #[msg("The observation state has the layout from before the log price, migrate it first")]
+    ObservationStateNotMigrated,
+    #[msg("LP holders already claimed from the emergency escrow, it can't go back to the pool")]
+    EmergencyEscrowClaimed,
 }
@@ -3,6 +3,11 @@
 pub mod dflow_segmenter;
 pub mod dlmm;
 pub mod kamino;
+pub mod pyth;
 pub mod raydium_clmm;
 pub mod raydium_cp;
+pub mod referral;
+pub mod strategy_adapter;
+pub mod swap_hook;
+pub mod switchboard;
 pub mod whirlpool;
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+/// A `PriceUpdateV2` account of the Pyth solana receiver, decoded by hand to avoid pulling in
+/// the receiver sdk for the few fields read
+pub struct PriceUpdateV2 {
+    pub fully_verified: bool,
+    pub price: i64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl PriceUpdateV2 {
+    pub const PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+    pub const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+    /// Layout: discriminator, write authority, verification level (a borsh enum, `Partial`
+    /// carries the number of signatures), then the price message starting with the feed id
+    pub fn deserialize(account: &AccountInfo<'_>) -> Option<Self> {
+        if *account.owner != Self::PROGRAM_ID {
+            return None;
+        }
+        let data = account.try_borrow_data().ok()?;
+        if data.get(..8)? != Self::DISCRIMINATOR {
+            return None;
+        }
+        let (fully_verified, message_offset) = match data.get(8 + 32)? {
+            0 => (false, 8 + 32 + 2),
+            1 => (true, 8 + 32 + 1),
+            _ => return None,
+        };
+        let price_offset = message_offset + 32;
+        let read_i64 = |offset: usize| {
+            Some(i64::from_le_bytes(
+                data.get(offset..offset + 8)?.try_into().ok()?,
+            ))
+        };
+        Some(PriceUpdateV2 {
+            fully_verified,
+            price: read_i64(price_offset)?,
+            // The confidence sits between the price and the exponent
+            exponent: i32::from_le_bytes(
+                data.get(price_offset + 16..price_offset + 20)?
+                    .try_into()
+                    .ok()?,
+            ),
+            publish_time: read_i64(price_offset + 20)?,
+        })
+    }
+}
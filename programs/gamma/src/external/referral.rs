@@ -0,0 +1,61 @@
+// `update_project` of the referral program, encoded by hand against the deployed program's
+// interface so the call doesn't depend on the `referral` crate exposing it at the pinned revision.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+
+pub const UPDATE_PROJECT_DISCRIMINATOR: [u8; 8] = [2, 196, 131, 92, 28, 139, 179, 94];
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UpdateProjectParams {
+    pub name: Option<String>,
+    pub default_share_bps: Option<u16>,
+}
+
+/// Calls `update_project` of the referral program, `admin` must be the project's admin and sign
+pub fn update_project<'info>(
+    referral_program: &AccountInfo<'info>,
+    admin: &AccountInfo<'info>,
+    project: &AccountInfo<'info>,
+    params: &UpdateProjectParams,
+) -> Result<()> {
+    let mut data = UPDATE_PROJECT_DISCRIMINATOR.to_vec();
+    params.serialize(&mut data)?;
+    invoke(
+        &Instruction {
+            program_id: referral_program.key(),
+            accounts: vec![
+                AccountMeta::new_readonly(admin.key(), true),
+                AccountMeta::new(project.key(), false),
+            ],
+            data,
+        },
+        &[admin.clone(), project.clone(), referral_program.clone()],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    #[test]
+    fn update_project_is_encoded_like_the_referral_program_decodes_it() {
+        assert_eq!(
+            hash(b"global:update_project").to_bytes()[..8],
+            UPDATE_PROJECT_DISCRIMINATOR
+        );
+
+        let mut data = vec![];
+        UpdateProjectParams {
+            name: Some("gamma".to_string()),
+            default_share_bps: None,
+        }
+        .serialize(&mut data)
+        .unwrap();
+        assert_eq!(data, [1, 5, 0, 0, 0, b'g', b'a', b'm', b'm', b'a', 0]);
+    }
+}
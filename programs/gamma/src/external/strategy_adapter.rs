@@ -0,0 +1,110 @@
+// Interface of the adapter programs wrapping a lending protocol, e.g. Kamino, MarginFi or Solend,
+// for vault strategies. An adapter deposits into or withdraws from the protocol on behalf of the
+// strategy authority, moving the tokens from or to its token account, and reports what its
+// position is worth as return data.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::{get_return_data, invoke_signed},
+};
+
+use crate::error::GammaError;
+use crate::states::VaultStrategy;
+
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+
+/// Accounts every adapter instruction takes, ahead of the ones of its lending protocol
+pub struct AdapterAccounts<'a, 'info> {
+    /// The strategy authority, owner of the token account and of the position in the protocol
+    pub strategy_authority: AccountInfo<'info>,
+    /// Token account of the strategy authority
+    pub token_account: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub adapter_program: AccountInfo<'info>,
+    /// Accounts of the lending protocol, passed on as they are
+    pub protocol_accounts: &'a [AccountInfo<'info>],
+}
+
+/// Moves `amount` of the token account into the protocol, returning what the position is worth
+/// after
+pub fn deposit(
+    accounts: &AdapterAccounts,
+    vault_strategy: &VaultStrategy,
+    amount: u64,
+) -> Result<u64> {
+    invoke_adapter(accounts, vault_strategy, DEPOSIT_DISCRIMINATOR, amount)
+}
+
+/// Moves `amount` of the position back into the token account, returning what the position is
+/// worth after. Withdrawing zero only reports the position.
+pub fn withdraw(
+    accounts: &AdapterAccounts,
+    vault_strategy: &VaultStrategy,
+    amount: u64,
+) -> Result<u64> {
+    invoke_adapter(accounts, vault_strategy, WITHDRAW_DISCRIMINATOR, amount)
+}
+
+fn invoke_adapter(
+    accounts: &AdapterAccounts,
+    vault_strategy: &VaultStrategy,
+    discriminator: [u8; 8],
+    amount: u64,
+) -> Result<u64> {
+    let mint = accounts.mint.key();
+    vault_strategy.check_adapter_accounts(
+        accounts.strategy_authority.key,
+        &mint,
+        accounts.token_account.key,
+        accounts.adapter_program.key,
+        accounts.protocol_accounts,
+    )?;
+
+    let mut account_metas = vec![
+        AccountMeta::new_readonly(accounts.strategy_authority.key(), true),
+        AccountMeta::new(accounts.token_account.key(), false),
+        AccountMeta::new_readonly(mint, false),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    let mut account_infos = vec![
+        accounts.strategy_authority.clone(),
+        accounts.token_account.clone(),
+        accounts.mint.clone(),
+        accounts.token_program.clone(),
+    ];
+    for account in accounts.protocol_accounts {
+        account_metas.push(if account.is_writable {
+            AccountMeta::new(account.key(), false)
+        } else {
+            AccountMeta::new_readonly(account.key(), false)
+        });
+        account_infos.push(account.clone());
+    }
+    account_infos.push(accounts.adapter_program.clone());
+
+    let mut data = discriminator.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    invoke_signed(
+        &Instruction {
+            program_id: accounts.adapter_program.key(),
+            accounts: account_metas,
+            data,
+        },
+        &account_infos,
+        &[&vault_strategy.authority_seeds(&mint)],
+    )?;
+
+    // Return data set by anything else than the adapter isn't the position
+    match get_return_data() {
+        Some((program_id, data)) if program_id == accounts.adapter_program.key() => {
+            let position_value: [u8; 8] = data
+                .get(..8)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or(GammaError::InvalidStrategy)?;
+            Ok(u64::from_le_bytes(position_value))
+        }
+        _ => err!(GammaError::InvalidStrategy),
+    }
+}
@@ -0,0 +1,52 @@
+// Interface of the programs a pool calls after each swap. The call carries the `SwapEvent` of the
+// swap and is signed by the pool's `PoolSwapHook`, never by the pool authority owning the vaults.
+// The pool state is written before the call, hooks can read it or the reserves off the event.
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+use crate::states::SwapEvent;
+
+pub const ON_SWAP_DISCRIMINATOR: [u8; 8] = [201, 131, 123, 189, 167, 226, 124, 81];
+
+/// Calls `on_swap` of the hook program with the pool's hook account as signer, the payer of the
+/// swap and the hook's own accounts
+pub fn on_swap<'info>(
+    hook_program: &AccountInfo<'info>,
+    pool_swap_hook: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    hook_accounts: &[AccountInfo<'info>],
+    swap_event: &SwapEvent,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut account_metas = vec![
+        AccountMeta::new_readonly(pool_swap_hook.key(), true),
+        // The payer's signature isn't passed on, it would let the hook move the payer's tokens
+        AccountMeta::new_readonly(payer.key(), false),
+    ];
+    let mut account_infos = vec![pool_swap_hook.clone(), payer.clone()];
+    for account in hook_accounts {
+        account_metas.push(if account.is_writable {
+            AccountMeta::new(account.key(), false)
+        } else {
+            AccountMeta::new_readonly(account.key(), false)
+        });
+        account_infos.push(account.clone());
+    }
+    account_infos.push(hook_program.clone());
+
+    let mut data = ON_SWAP_DISCRIMINATOR.to_vec();
+    swap_event.serialize(&mut data)?;
+    invoke_signed(
+        &Instruction {
+            program_id: hook_program.key(),
+            accounts: account_metas,
+            data,
+        },
+        &account_infos,
+        signer_seeds,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+/// Offset of `last_update_timestamp` in a `PullFeedAccountData`, after the discriminator,
+/// the 32 oracle submissions and the feed configuration
+const LAST_UPDATE_TIMESTAMP_OFFSET: usize = 2216;
+/// Offset of `result.value`, the median of the latest submissions
+const RESULT_VALUE_OFFSET: usize = 2264;
+
+/// The current result of a Switchboard on-demand `PullFeedAccountData`, read at fixed offsets
+/// of the zero copy account
+pub struct PullFeed {
+    /// Scaled by 10^PullFeed::DECIMALS
+    pub value: i128,
+    pub last_update_timestamp: i64,
+}
+
+impl PullFeed {
+    pub const PROGRAM_ID: Pubkey = pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+    pub const DISCRIMINATOR: [u8; 8] = [196, 27, 108, 196, 10, 215, 219, 40];
+    pub const DECIMALS: i32 = 18;
+
+    pub fn deserialize(account: &AccountInfo<'_>) -> Option<Self> {
+        if *account.owner != Self::PROGRAM_ID {
+            return None;
+        }
+        let data = account.try_borrow_data().ok()?;
+        if data.get(..8)? != Self::DISCRIMINATOR {
+            return None;
+        }
+        Some(PullFeed {
+            value: i128::from_le_bytes(
+                data.get(RESULT_VALUE_OFFSET..RESULT_VALUE_OFFSET + 16)?
+                    .try_into()
+                    .ok()?,
+            ),
+            last_update_timestamp: i64::from_le_bytes(
+                data.get(LAST_UPDATE_TIMESTAMP_OFFSET..LAST_UPDATE_TIMESTAMP_OFFSET + 8)?
+                    .try_into()
+                    .ok()?,
+            ),
+        })
+    }
+}
@@ -1,4 +1,7 @@
-use super::{ceil_div, FEE_RATE_DENOMINATOR_VALUE};
+use super::{
+    ceil_div, directional_rate, out_of_range_rebalance_rate, utilization_based_rate, FeeModel,
+    FeeTrade, FEE_RATE_DENOMINATOR_VALUE,
+};
 use crate::{
     error::GammaError,
     fees::ONE_BASIS_POINT,
@@ -14,10 +17,6 @@ pub const VOLATILITY_WINDOW: u64 = 3600; // 1 hour window for volatility calcula
 const DEFAULT_MAX_FEE: u64 = 100000; // 10% max fee
 const DEFAULT_VOLATILITY_FACTOR: u64 = 300_000; // Adjust based on desired sensitivity
 
-pub enum FeeType {
-    Volatility,
-}
-
 struct ObservationWithIndex {
     observation: Observation,
     index: u16,
@@ -29,20 +28,20 @@ impl DynamicFee {
     /// Calculates the fee amount for a given input amount (base_fees + dynamic_fee)
     ///
     /// # Arguments
-    /// * `amount` - The input amount
+    /// * `trade` - The swap, its amount being the input amount
     /// * `block_timestamp` - The current block timestamp
     /// * `observation_state` - Historical price observations
-    /// * `fee_type` - The type of fee calculation to use
+    /// * `fee_model` - The fee model of the pool
     /// * `base_fees` - The base fee rate
     ///
     /// # Returns
     /// The fee amount as a u128, or None if calculation fails
 
     pub fn dynamic_fee(
-        amount: u128,
+        trade: FeeTrade,
         block_timestamp: u64,
         observation_state: &ObservationState,
-        fee_type: FeeType,
+        fee_model: FeeModel,
         base_fees: u64,
         pool_state: &PoolState,
         is_invoked_by_signed_segmenter: bool,
@@ -51,50 +50,107 @@ impl DynamicFee {
         let dynamic_fee_rate = Self::calculate_dynamic_fee(
             block_timestamp,
             observation_state,
-            fee_type,
+            fee_model,
             base_fees,
             pool_state,
             is_invoked_by_signed_segmenter,
+            &trade,
         )?;
 
         Ok((
-            ceil_div(
-                amount,
-                u128::from(dynamic_fee_rate),
-                u128::from(FEE_RATE_DENOMINATOR_VALUE),
-            )
-            .ok_or(GammaError::MathOverflow)?,
+            Self::fee_amount(trade.amount, dynamic_fee_rate)?,
             dynamic_fee_rate,
         ))
     }
 
-    /// Calculates the dynamic fee based on the specified fee type
+    /// Fee charged on `amount` at `fee_rate`, rounded up
+    pub fn fee_amount(amount: u128, fee_rate: u64) -> Result<u128> {
+        Ok(ceil_div(
+            amount,
+            u128::from(fee_rate),
+            u128::from(FEE_RATE_DENOMINATOR_VALUE),
+        )
+        .ok_or(GammaError::MathOverflow)?)
+    }
+
+    /// Calculates the dynamic fee based on the fee model of the pool
     ///
     /// # Arguments
     /// * `pool_state` - The current state of the pool
     /// * `observation_state` - Historical price observations
-    /// * `vault_0` - Amount of token 0 in the vault
-    /// * `vault_1` - Amount of token 1 in the vault
-    /// * `fee_type` - The type of fee calculation to use
+    /// * `fee_model` - The fee model of the pool
+    /// * `trade` - The swap, for the models pricing the swap itself
     ///
     /// # Returns
     /// A fee rate as a u64, where 10000 represents 1%
     fn calculate_dynamic_fee(
         block_timestamp: u64,
         observation_state: &ObservationState,
-        fee_type: FeeType,
+        fee_model: FeeModel,
         base_fees: u64,
         pool_state: &PoolState,
         is_invoked_by_signed_segmenter: bool,
+        trade: &FeeTrade,
     ) -> Result<u64> {
-        match fee_type {
-            FeeType::Volatility => Self::calculate_volatile_fee(
+        match fee_model {
+            FeeModel::VolatilityBased => Self::calculate_volatile_fee(
                 block_timestamp,
                 observation_state,
                 base_fees,
                 pool_state,
                 is_invoked_by_signed_segmenter,
             ),
+            FeeModel::Constant => Ok(base_fees),
+            FeeModel::UtilizationBased {
+                utilization_fee_rate,
+            } => Ok(std::cmp::min(
+                utilization_based_rate(base_fees, utilization_fee_rate, trade)?,
+                Self::max_fee(pool_state),
+            )),
+            FeeModel::OutOfRangeRebalance {
+                range_bps,
+                out_of_range_fee_rate,
+            } => Ok(std::cmp::min(
+                out_of_range_rebalance_rate(
+                    base_fees,
+                    range_bps,
+                    out_of_range_fee_rate,
+                    pool_state.ema_token_0_price_x32,
+                    pool_state.token_price_x32()?.0,
+                    trade.trade_direction,
+                ),
+                Self::max_fee(pool_state),
+            )),
+            FeeModel::Directional {
+                surcharge_rate,
+                rebate_rate,
+            } => {
+                let reference_price_x32 = if pool_state.ema_token_0_price_x32 != 0 {
+                    pool_state.ema_token_0_price_x32
+                } else {
+                    Self::get_price_range(observation_state, block_timestamp, VOLATILITY_WINDOW)?.2
+                };
+                Ok(std::cmp::min(
+                    directional_rate(
+                        base_fees,
+                        surcharge_rate,
+                        rebate_rate,
+                        reference_price_x32,
+                        pool_state.token_price_x32()?.0,
+                        trade.trade_direction,
+                    ),
+                    Self::max_fee(pool_state),
+                ))
+            }
+        }
+    }
+
+    /// Cap of the dynamic fee rates, the pool's max trade fee rate or `DEFAULT_MAX_FEE`
+    fn max_fee(pool_state: &PoolState) -> u64 {
+        if pool_state.max_trade_fee_rate == 0 {
+            DEFAULT_MAX_FEE
+        } else {
+            pool_state.max_trade_fee_rate
         }
     }
 
@@ -190,11 +246,7 @@ impl DynamicFee {
             .checked_add(volatility_component_calculated)
             .ok_or(GammaError::MathOverflow)?;
 
-        let max_fee = if pool_state.max_trade_fee_rate == 0 {
-            DEFAULT_MAX_FEE
-        } else {
-            pool_state.max_trade_fee_rate
-        };
+        let max_fee = Self::max_fee(pool_state);
 
         #[cfg(feature = "enable-log")]
         msg!("dynamic_fee: {}", dynamic_fee);
@@ -329,24 +381,23 @@ impl DynamicFee {
     /// Calculates the pre-fee amount given a post-fee amount
     ///
     /// # Arguments
-    /// * `post_fee_amount` - The amount after fees have been deducted
+    /// * `trade` - The swap, its amount being the amount after fees have been deducted
     /// * `pool_state` - The current state of the pool
     /// * `observation_state` - Historical price observations
-    /// * `vault_0` - Amount of token 0 in the vault
-    /// * `vault_1` - Amount of token 1 in the vault
-    /// * `fee_type` - The type of fee calculation to use
+    /// * `fee_model` - The fee model of the pool
     ///
     /// # Returns
     /// The pre-fee amount as a u128, or None if calculation fails
     pub fn calculate_pre_fee_amount(
         block_timestamp: u64,
-        post_fee_amount: u128,
+        trade: FeeTrade,
         observation_state: &ObservationState,
-        fee_type: FeeType,
+        fee_model: FeeModel,
         base_fees: u64,
         pool_state: &PoolState,
         is_invoked_by_signed_segmenter: bool,
     ) -> Result<(u128, u64)> {
+        let post_fee_amount = trade.amount;
         // x = pre_fee_amount (has to be calculated)
         // y = post_fee_amount
         // r = trade_fee_rate
@@ -365,30 +416,36 @@ impl DynamicFee {
         let dynamic_fee_rate = Self::calculate_dynamic_fee(
             block_timestamp,
             observation_state,
-            fee_type,
+            fee_model,
             base_fees,
             pool_state,
             is_invoked_by_signed_segmenter,
+            &trade,
         )?;
-        if dynamic_fee_rate == 0 {
-            Ok((post_fee_amount, 0))
-        } else {
-            let numerator = post_fee_amount
-                .checked_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))
-                .ok_or(GammaError::MathOverflow)?;
-            let denominator = u128::from(FEE_RATE_DENOMINATOR_VALUE)
-                .checked_sub(u128::from(dynamic_fee_rate))
-                .ok_or(GammaError::MathOverflow)?;
-
-            let result = numerator
-                .checked_add(denominator)
-                .ok_or(GammaError::MathOverflow)?
-                .checked_sub(1)
-                .ok_or(GammaError::MathOverflow)?
-                .checked_div(denominator)
-                .ok_or(GammaError::MathOverflow)?;
+        Ok((
+            Self::pre_fee_amount(post_fee_amount, dynamic_fee_rate)?,
+            dynamic_fee_rate,
+        ))
+    }
 
-            Ok((result, dynamic_fee_rate))
+    /// Amount that is left with `post_fee_amount` once the fee at `fee_rate` is taken from it
+    pub fn pre_fee_amount(post_fee_amount: u128, fee_rate: u64) -> Result<u128> {
+        if fee_rate == 0 {
+            return Ok(post_fee_amount);
         }
+        let numerator = post_fee_amount
+            .checked_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))
+            .ok_or(GammaError::MathOverflow)?;
+        let denominator = u128::from(FEE_RATE_DENOMINATOR_VALUE)
+            .checked_sub(u128::from(fee_rate))
+            .ok_or(GammaError::MathOverflow)?;
+
+        Ok(numerator
+            .checked_add(denominator)
+            .ok_or(GammaError::MathOverflow)?
+            .checked_sub(1)
+            .ok_or(GammaError::MathOverflow)?
+            .checked_div(denominator)
+            .ok_or(GammaError::MathOverflow)?)
     }
 }
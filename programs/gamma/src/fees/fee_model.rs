@@ -0,0 +1,245 @@
+use super::FEE_RATE_DENOMINATOR_VALUE;
+use crate::curve::TradeDirection;
+use crate::error::GammaError;
+use anchor_lang::prelude::*;
+
+/// Seconds between `update_fee_model` and swaps pricing with the new model
+pub const FEE_MODEL_SWITCH_DELAY: u64 = 24 * 60 * 60;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// How swaps of a pool derive their fee rate from the pool's trade fee rate. Stored in
+/// `PoolState` as a kind byte and two params, zero being `VolatilityBased` so pools created
+/// before fee models keep their fees.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeModel {
+    /// The trade fee plus a component following the price volatility of the observations
+    VolatilityBased,
+    /// The trade fee, whatever the market does
+    Constant,
+    /// The trade fee plus `utilization_fee_rate` scaled by the share of the input reserve the
+    /// swap adds, so large swaps pay more
+    UtilizationBased { utilization_fee_rate: u64 },
+    /// While the price is more than `range_bps` away from the pool's EMA price, swaps moving it
+    /// further away pay `out_of_range_fee_rate` and swaps moving it back pay the trade fee
+    OutOfRangeRebalance {
+        range_bps: u64,
+        out_of_range_fee_rate: u64,
+    },
+    /// Swaps moving the price away from the pool's EMA price pay `surcharge_rate` on top of the
+    /// trade fee, swaps moving it back pay `rebate_rate` less. Pools without an EMA price use the
+    /// twap of the volatility window.
+    Directional {
+        surcharge_rate: u64,
+        rebate_rate: u64,
+    },
+}
+
+impl FeeModel {
+    /// Decodes the model stored in `PoolState`
+    pub fn from_parts(kind: u8, params: [u64; 2]) -> Result<Self> {
+        Ok(match kind {
+            0 => FeeModel::VolatilityBased,
+            1 => FeeModel::Constant,
+            2 => FeeModel::UtilizationBased {
+                utilization_fee_rate: params[0],
+            },
+            3 => FeeModel::OutOfRangeRebalance {
+                range_bps: params[0],
+                out_of_range_fee_rate: params[1],
+            },
+            4 => FeeModel::Directional {
+                surcharge_rate: params[0],
+                rebate_rate: params[1],
+            },
+            _ => return err!(GammaError::InvalidInput),
+        })
+    }
+
+    /// The kind byte and params `PoolState` stores the model as
+    pub fn to_parts(self) -> (u8, [u64; 2]) {
+        match self {
+            FeeModel::VolatilityBased => (0, [0, 0]),
+            FeeModel::Constant => (1, [0, 0]),
+            FeeModel::UtilizationBased {
+                utilization_fee_rate,
+            } => (2, [utilization_fee_rate, 0]),
+            FeeModel::OutOfRangeRebalance {
+                range_bps,
+                out_of_range_fee_rate,
+            } => (3, [range_bps, out_of_range_fee_rate]),
+            FeeModel::Directional {
+                surcharge_rate,
+                rebate_rate,
+            } => (4, [surcharge_rate, rebate_rate]),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            FeeModel::VolatilityBased | FeeModel::Constant => {}
+            FeeModel::UtilizationBased {
+                utilization_fee_rate,
+            } => require_gt!(
+                FEE_RATE_DENOMINATOR_VALUE,
+                utilization_fee_rate,
+                GammaError::InvalidFeeBounds
+            ),
+            FeeModel::OutOfRangeRebalance {
+                range_bps,
+                out_of_range_fee_rate,
+            } => {
+                require_gt!(BPS_DENOMINATOR, range_bps, GammaError::InvalidInput);
+                require_gt!(
+                    FEE_RATE_DENOMINATOR_VALUE,
+                    out_of_range_fee_rate,
+                    GammaError::InvalidFeeBounds
+                );
+            }
+            FeeModel::Directional {
+                surcharge_rate,
+                rebate_rate,
+            } => {
+                require_gt!(
+                    FEE_RATE_DENOMINATOR_VALUE,
+                    surcharge_rate,
+                    GammaError::InvalidFeeBounds
+                );
+                require_gt!(
+                    FEE_RATE_DENOMINATOR_VALUE,
+                    rebate_rate,
+                    GammaError::InvalidFeeBounds
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The swap a fee rate is set for, the models other than `VolatilityBased` price the swap itself
+#[derive(Clone, Copy, Debug)]
+pub struct FeeTrade {
+    /// Amount the fee is charged on, or the amount after fees when it is added on top
+    pub amount: u128,
+    /// Reserve of the input token before the swap
+    pub swap_source_amount: u128,
+    pub trade_direction: TradeDirection,
+}
+
+/// `fee_rate` with `discount_bps` of it taken off, e.g. a trader's staking discount
+pub fn discounted_fee_rate(fee_rate: u64, discount_bps: u16) -> u64 {
+    let discount = u128::from(fee_rate) * u128::from(discount_bps) / u128::from(BPS_DENOMINATOR);
+    fee_rate.saturating_sub(u64::try_from(discount).unwrap_or(u64::MAX))
+}
+
+/// Rate of `FeeModel::UtilizationBased`, before the pool's max trade fee rate caps it
+pub fn utilization_based_rate(
+    base_fees: u64,
+    utilization_fee_rate: u64,
+    trade: &FeeTrade,
+) -> Result<u64> {
+    if trade.swap_source_amount == 0 {
+        return Ok(base_fees);
+    }
+    let utilization_component = u128::from(utilization_fee_rate)
+        .checked_mul(trade.amount)
+        .ok_or(GammaError::MathOverflow)?
+        / trade.swap_source_amount;
+    let utilization_component = u64::try_from(utilization_component).unwrap_or(u64::MAX);
+    Ok(base_fees.saturating_add(utilization_component))
+}
+
+/// Rate of `FeeModel::OutOfRangeRebalance`, `ema_price_x32` and `price_x32` being token_0
+/// prices before the swap
+pub fn out_of_range_rebalance_rate(
+    base_fees: u64,
+    range_bps: u64,
+    out_of_range_fee_rate: u64,
+    ema_price_x32: u128,
+    price_x32: u128,
+    trade_direction: TradeDirection,
+) -> u64 {
+    if ema_price_x32 == 0 {
+        return base_fees;
+    }
+    let range_x32 =
+        ema_price_x32.saturating_mul(u128::from(range_bps)) / u128::from(BPS_DENOMINATOR);
+    let moves_further_out = match trade_direction {
+        // Selling token_0 lowers its price
+        TradeDirection::ZeroForOne => price_x32 < ema_price_x32.saturating_sub(range_x32),
+        TradeDirection::OneForZero => price_x32 > ema_price_x32.saturating_add(range_x32),
+    };
+    if moves_further_out {
+        std::cmp::max(base_fees, out_of_range_fee_rate)
+    } else {
+        base_fees
+    }
+}
+
+/// Rate of `FeeModel::Directional`, `reference_price_x32` and `price_x32` being token_0 prices
+/// before the swap. Without a reference price both directions pay the trade fee.
+pub fn directional_rate(
+    base_fees: u64,
+    surcharge_rate: u64,
+    rebate_rate: u64,
+    reference_price_x32: u128,
+    price_x32: u128,
+    trade_direction: TradeDirection,
+) -> u64 {
+    if reference_price_x32 == 0 {
+        return base_fees;
+    }
+    let moves_away = match trade_direction {
+        // Selling token_0 lowers its price
+        TradeDirection::ZeroForOne => price_x32 <= reference_price_x32,
+        TradeDirection::OneForZero => price_x32 >= reference_price_x32,
+    };
+    if moves_away {
+        base_fees.saturating_add(surcharge_rate)
+    } else {
+        base_fees.saturating_sub(rebate_rate)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PRICE_X32: u128 = 1 << 32;
+
+    #[test]
+    fn directional_fee_charges_moves_away_from_the_reference_price() {
+        let below = PRICE_X32 * 9 / 10;
+        let charge = |price_x32, trade_direction| {
+            directional_rate(2_500, 1_000, 2_000, PRICE_X32, price_x32, trade_direction)
+        };
+        assert_eq!(charge(below, TradeDirection::ZeroForOne), 3_500);
+        assert_eq!(charge(below, TradeDirection::OneForZero), 500);
+        assert_eq!(charge(2 * PRICE_X32, TradeDirection::ZeroForOne), 500);
+        // At the reference price either direction moves away
+        assert_eq!(charge(PRICE_X32, TradeDirection::OneForZero), 3_500);
+        // The rebate never takes the rate below zero, and no reference leaves the trade fee
+        assert_eq!(
+            directional_rate(
+                1_000,
+                0,
+                2_000,
+                PRICE_X32,
+                below,
+                TradeDirection::OneForZero
+            ),
+            0
+        );
+        assert_eq!(
+            directional_rate(1_000, 500, 500, 0, below, TradeDirection::ZeroForOne),
+            1_000
+        );
+    }
+
+    #[test]
+    fn discounted_fee_rate_takes_the_discount_off_the_trade_fee() {
+        assert_eq!(discounted_fee_rate(2_500, 2_500), 1_875);
+        assert_eq!(discounted_fee_rate(2_500, 10_000), 0);
+        assert_eq!(discounted_fee_rate(u64::MAX, 0), u64::MAX);
+    }
+}
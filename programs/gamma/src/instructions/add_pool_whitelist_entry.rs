@@ -0,0 +1,40 @@
+use crate::states::{PoolState, PoolWhitelistEntry, POOL_WHITELIST_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct AddPoolWhitelistEntry<'info> {
+    /// Only the pool creator can manage their whitelist
+    #[account(mut)]
+    pub pool_creator: Signer<'info>,
+
+    #[account(has_one = pool_creator)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [
+            POOL_WHITELIST_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            address.as_ref(),
+        ],
+        bump,
+        payer = pool_creator,
+        space = PoolWhitelistEntry::LEN,
+    )]
+    pub whitelist_entry: Box<Account<'info, PoolWhitelistEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_pool_whitelist_entry(
+    ctx: Context<AddPoolWhitelistEntry>,
+    address: Pubkey,
+) -> Result<()> {
+    let whitelist_entry = &mut ctx.accounts.whitelist_entry;
+    whitelist_entry.bump = ctx.bumps.whitelist_entry;
+    whitelist_entry.pool_id = ctx.accounts.pool_state.key();
+    whitelist_entry.address = address;
+    whitelist_entry.added_at = Clock::get()?.unix_timestamp as u64;
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::error::GammaError;
+use crate::states::{PartnerChangeEvent, PartnerInfo, PartnerType, PoolState};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddPartner<'info> {
+    /// Only admin can add partners
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Takes an empty partner slot of the pool, swap fees are attributed to the partner from then on
+pub fn add_partner(ctx: Context<AddPartner>, partner_id: u64) -> Result<()> {
+    let partner = PartnerType::new(partner_id);
+    require_eq!(partner as u64, partner_id, GammaError::InvalidInput);
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let mut partners = pool_state.partners;
+    require!(
+        !partners.iter().any(|info| info.links(partner)),
+        GammaError::InvalidInput
+    );
+    let slot = partners
+        .iter_mut()
+        .find(|info| info.is_empty())
+        .ok_or(GammaError::NoEmptyPartnerSlot)?;
+    *slot = PartnerInfo {
+        partner_id,
+        ..Default::default()
+    };
+    pool_state.partners = partners;
+
+    emit!(PartnerChangeEvent {
+        version: PartnerChangeEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        partner_id,
+        lp_token_linked_with_partner: 0,
+        cumulative_fee_total_times_tvl_share_token_0: 0,
+        cumulative_fee_total_times_tvl_share_token_1: 0,
+        change_type: 0,
+    });
+    Ok(())
+}
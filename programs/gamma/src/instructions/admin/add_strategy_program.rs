@@ -0,0 +1,43 @@
+use crate::error::GammaError;
+use crate::states::{LendingProtocol, StrategyAllowlistEntry, STRATEGY_ALLOWLIST_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddStrategyProgram<'info> {
+    /// Only admin can allowlist a strategy adapter program
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: The adapter program wrapping the lending protocol
+    #[account(executable)]
+    pub adapter_program: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            STRATEGY_ALLOWLIST_SEED.as_bytes(),
+            adapter_program.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = StrategyAllowlistEntry::LEN,
+    )]
+    pub allowlist_entry: Box<Account<'info, StrategyAllowlistEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_strategy_program(
+    ctx: Context<AddStrategyProgram>,
+    protocol: LendingProtocol,
+) -> Result<()> {
+    let allowlist_entry = &mut ctx.accounts.allowlist_entry;
+    allowlist_entry.bump = ctx.bumps.allowlist_entry;
+    allowlist_entry.program_id = ctx.accounts.adapter_program.key();
+    allowlist_entry.protocol = protocol as u8;
+    allowlist_entry.added_at = Clock::get()?.unix_timestamp as u64;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::error::GammaError;
+use crate::states::{SwapHookAllowlistEntry, SWAP_HOOK_ALLOWLIST_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddSwapHookProgram<'info> {
+    /// Only admin can allowlist a swap hook program
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: The swap hook program
+    #[account(executable)]
+    pub hook_program: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            SWAP_HOOK_ALLOWLIST_SEED.as_bytes(),
+            hook_program.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = SwapHookAllowlistEntry::LEN,
+    )]
+    pub allowlist_entry: Box<Account<'info, SwapHookAllowlistEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_swap_hook_program(ctx: Context<AddSwapHookProgram>) -> Result<()> {
+    let allowlist_entry = &mut ctx.accounts.allowlist_entry;
+    allowlist_entry.bump = ctx.bumps.allowlist_entry;
+    allowlist_entry.program_id = ctx.accounts.hook_program.key();
+    allowlist_entry.added_at = Clock::get()?.unix_timestamp as u64;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::error::GammaError;
+use crate::states::{TransferHookAllowlistEntry, TRANSFER_HOOK_ALLOWLIST_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddTransferHookProgram<'info> {
+    /// Only admin can allowlist a transfer hook program
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: The transfer hook program
+    #[account(executable)]
+    pub hook_program: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            TRANSFER_HOOK_ALLOWLIST_SEED.as_bytes(),
+            hook_program.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = TransferHookAllowlistEntry::LEN,
+    )]
+    pub allowlist_entry: Box<Account<'info, TransferHookAllowlistEntry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_transfer_hook_program(ctx: Context<AddTransferHookProgram>) -> Result<()> {
+    let allowlist_entry = &mut ctx.accounts.allowlist_entry;
+    allowlist_entry.bump = ctx.bumps.allowlist_entry;
+    allowlist_entry.program_id = ctx.accounts.hook_program.key();
+    allowlist_entry.added_at = Clock::get()?.unix_timestamp as u64;
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::error::GammaError;
+use crate::instructions::apply_amm_config_update;
+use crate::states::{has_config_role, AmmConfig, AmmConfigRoles, ConfigRole, PendingConfigUpdate};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ApplyConfigUpdate<'info> {
+    /// Only admin or the config authority can apply a config update
+    #[account(
+        constraint = has_config_role(
+            config_roles.as_deref(),
+            ConfigRole::Config,
+            owner.key()
+        ) @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The config's roles, for a config authority other than the admin
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+}
+
+pub fn apply_config_update(ctx: Context<ApplyConfigUpdate>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    let pending_update = amm_config.pending_update;
+    require!(
+        pending_update.is_pending()
+            && Clock::get()?.unix_timestamp as u64 >= pending_update.apply_after,
+        GammaError::ConfigUpdateNotReady
+    );
+
+    amm_config.pending_update = PendingConfigUpdate::default();
+    apply_amm_config_update(
+        amm_config,
+        pending_update.param,
+        pending_update.value,
+        None,
+        ctx.accounts.owner.key(),
+    )
+}
@@ -0,0 +1,61 @@
+use crate::error::GammaError;
+use crate::instructions::{apply_amm_config_update, is_timelocked, schedule_config_update};
+use crate::states::{AmmConfig, PendingFeeChange, PENDING_FEE_CHANGE_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ApproveFeeChange<'info> {
+    /// The second signer of the config
+    #[account(address = amm_config.fee_approver @ GammaError::InvalidOwner)]
+    pub fee_approver: Signer<'info>,
+
+    /// CHECK: the admin who paid for the proposal gets the rent back
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub admin: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            PENDING_FEE_CHANGE_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump = pending_fee_change.bump,
+        close = admin,
+    )]
+    pub pending_fee_change: Box<Account<'info, PendingFeeChange>>,
+}
+
+/// The approver restates the change, so the admin can't swap the proposal for another one
+/// before the approval lands. Timelocked changes are scheduled instead of applied.
+pub fn approve_fee_change(
+    ctx: Context<ApproveFeeChange>,
+    param: u16,
+    value: u64,
+    new_key: Pubkey,
+) -> Result<()> {
+    let pending_fee_change = &ctx.accounts.pending_fee_change;
+    require!(
+        pending_fee_change.param == param
+            && pending_fee_change.value == value
+            && pending_fee_change.new_key == new_key,
+        GammaError::FeeChangeMismatch
+    );
+
+    let amm_config = &mut ctx.accounts.amm_config;
+    if is_timelocked(amm_config, param, value) {
+        return schedule_config_update(amm_config, param, value);
+    }
+    apply_amm_config_update(
+        amm_config,
+        param,
+        value,
+        Some(new_key),
+        ctx.accounts.fee_approver.key(),
+    )
+}
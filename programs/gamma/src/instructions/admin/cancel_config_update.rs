@@ -0,0 +1,33 @@
+use crate::error::GammaError;
+use crate::states::{has_config_role, AmmConfig, AmmConfigRoles, ConfigRole, PendingConfigUpdate};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelConfigUpdate<'info> {
+    /// Only admin or the config authority can cancel a config update
+    #[account(
+        constraint = has_config_role(
+            config_roles.as_deref(),
+            ConfigRole::Config,
+            owner.key()
+        ) @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The config's roles, for a config authority other than the admin
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+}
+
+pub fn cancel_config_update(ctx: Context<CancelConfigUpdate>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    require!(
+        amm_config.pending_update.is_pending(),
+        GammaError::ConfigUpdateNotReady
+    );
+    amm_config.pending_update = PendingConfigUpdate::default();
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::{
     error::GammaError,
-    states::{AmmConfig, PoolState},
+    states::{has_config_role, AmmConfig, AmmConfigRoles, ConfigRole, PoolState},
     utils::transfer_from_pool_vault_to_user,
 };
 use anchor_lang::prelude::*;
@@ -9,8 +9,13 @@ use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
 
 #[derive(Accounts)]
 pub struct CollectFundFee<'info> {
-    /// Only admin or fund_owner can collect fee now
-    #[account(constraint = (owner.key() == amm_config.fund_owner || owner.key() == crate::admin::id()) @ GammaError::InvalidOwner)]
+    /// Only admin, fund_owner or the fee collect authority can collect fee now
+    #[account(
+        constraint = (
+            owner.key() == amm_config.fund_owner
+                || has_config_role(config_roles.as_deref(), ConfigRole::FeeCollect, owner.key())
+        ) @ GammaError::InvalidOwner
+    )]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault authority
@@ -30,6 +35,10 @@ pub struct CollectFundFee<'info> {
     #[account(address = pool_state.load()?.amm_config)]
     pub amm_config: Account<'info, AmmConfig>,
 
+    /// Roles of the config, when a fee collect authority other than the admin is set
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+
     /// The address that holds pool tokens for token_0
     #[account(
         mut,
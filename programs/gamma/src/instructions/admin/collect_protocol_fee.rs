@@ -9,8 +9,13 @@ use anchor_spl::token_interface::TokenAccount;
 
 #[derive(Accounts)]
 pub struct CollectProtocolFee<'info> {
-    /// Only admin or owner can collect fee now
-    #[account(constraint = (owner.key() == amm_config.protocol_owner || owner.key() == crate::admin::id()) @ GammaError::InvalidOwner)]
+    /// Only admin, owner or the fee collect authority can collect fee now
+    #[account(
+        constraint = (
+            owner.key() == amm_config.protocol_owner
+                || has_config_role(config_roles.as_deref(), ConfigRole::FeeCollect, owner.key())
+        ) @ GammaError::InvalidOwner
+    )]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault authority
@@ -30,6 +35,10 @@ pub struct CollectProtocolFee<'info> {
     #[account(address = pool_state.load()?.amm_config)]
     pub amm_config: Account<'info, AmmConfig>,
 
+    /// Roles of the config, when a fee collect authority other than the admin is set
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+
     /// The address that holds pool tokens for token_0
     #[account(
         mut,
@@ -0,0 +1,196 @@
+use crate::error::GammaError;
+use crate::instructions::{execute_swap_base_input, Swap, SwapBumps};
+use crate::router_metadata::SWAP_REQUIRED_ACCOUNTS;
+use crate::states::{
+    has_config_role, AmmConfig, AmmConfigRoles, ConfigRole, FundFeeConversion,
+    FundFeeConversionEvent, PoolState,
+};
+use crate::utils::{
+    check_duplicate_swap, check_no_swap_hook, get_transfer_fee, transfer_from_pool_vault_to_user,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use std::collections::BTreeSet;
+
+#[derive(Accounts)]
+pub struct ConvertFundFees<'info> {
+    /// Only fund_owner or a holder of the fee collect role, the admin or the config's fee
+    /// collect authority, can convert fees, swapping them through their own token accounts
+    #[account(
+        constraint = (
+            owner.key() == amm_config.fund_owner
+                || has_config_role(config_roles.as_deref(), ConfigRole::FeeCollect, owner.key())
+        ) @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state stores accumulated fund fee amount
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores fund_owner
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Roles of the config, when a fee collect authority other than the admin is set
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+
+    #[account(has_one = amm_config)]
+    pub fund_fee_conversion: Box<Account<'info, FundFeeConversion>>,
+
+    /// The vault of the pool holding the fees to convert
+    #[account(
+        mut,
+        constraint = fee_vault.key() == pool_state.load()?.token_0_vault
+            || fee_vault.key() == pool_state.load()?.token_1_vault
+            @ GammaError::InvalidVault
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of the fees to convert
+    #[account(
+        address = fee_vault.mint,
+        constraint = fee_mint.key() != fund_fee_conversion.target_mint @ GammaError::InvalidInput,
+        mint::token_program = fee_token_program,
+    )]
+    pub fee_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The owner's account the fees go through, the input token account of the swap
+    #[account(
+        mut,
+        token::mint = fee_mint,
+        token::authority = owner,
+    )]
+    pub owner_fee_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the fee mint
+    pub fee_token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: the instructions sysvar, required to swap through pools that only allow one swap
+    /// per transaction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+/// Collects the fund fees the pool holds in the fee vault's mint and sells them for the target
+/// mint. The pool swapping them, the pool itself or a sibling pool pairing the fee mint with the
+/// target mint, is passed as remaining accounts laid out like the accounts of `swap_base_input`.
+/// Its output token account, a token account of the fund owner, receives the converted fees.
+pub fn convert_fund_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ConvertFundFees<'info>>,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    let remaining_accounts = ctx.remaining_accounts;
+    require_eq!(
+        remaining_accounts.len(),
+        SWAP_REQUIRED_ACCOUNTS,
+        GammaError::InvalidInput
+    );
+
+    let fee_amount;
+    let auth_bump;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        if ctx.accounts.fee_vault.key() == pool_state.token_0_vault {
+            fee_amount = pool_state.fund_fees_token_0;
+            pool_state.fund_fees_token_0 = 0;
+        } else {
+            fee_amount = pool_state.fund_fees_token_1;
+            pool_state.fund_fees_token_1 = 0;
+        }
+        require_gt!(fee_amount, 0, GammaError::InvalidInput);
+        auth_bump = pool_state.auth_bump;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+    }
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.fee_vault.to_account_info(),
+        ctx.accounts.owner_fee_token_account.to_account_info(),
+        ctx.accounts.fee_mint.to_account_info(),
+        ctx.accounts.fee_token_program.to_account_info(),
+        fee_amount,
+        ctx.accounts.fee_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+    let amount_in = fee_amount
+        .checked_sub(get_transfer_fee(
+            &ctx.accounts.fee_mint.to_account_info(),
+            fee_amount,
+        )?)
+        .ok_or(GammaError::MathOverflow)?;
+
+    // Loaded after the fees are collected, the swap may go through the same pool
+    let mut swap_accounts = remaining_accounts;
+    let mut accounts = Swap::try_accounts(
+        &crate::id(),
+        &mut swap_accounts,
+        &[],
+        &mut SwapBumps::default(),
+        &mut BTreeSet::new(),
+    )?;
+    require_keys_eq!(
+        accounts.payer.key(),
+        ctx.accounts.owner.key(),
+        GammaError::InvalidInput
+    );
+    require_keys_eq!(
+        accounts.input_token_account.key(),
+        ctx.accounts.owner_fee_token_account.key(),
+        GammaError::InvalidInput
+    );
+    require_keys_eq!(
+        accounts.output_token_mint.key(),
+        ctx.accounts.fund_fee_conversion.target_mint,
+        GammaError::InvalidInput
+    );
+    // The fee collect authority converts the fees for the fund owner, never to itself
+    let output_token_account =
+        TokenAccount::try_deserialize(&mut &accounts.output_token_account.try_borrow_data()?[..])?;
+    require_keys_eq!(
+        output_token_account.owner,
+        ctx.accounts.amm_config.fund_owner,
+        GammaError::InvalidOwner
+    );
+    require_keys_eq!(
+        output_token_account.mint,
+        ctx.accounts.fund_fee_conversion.target_mint,
+        GammaError::InvalidInput
+    );
+    {
+        let swap_pool_state = accounts.pool_state.load()?;
+        check_no_swap_hook(&swap_pool_state)?;
+        check_duplicate_swap(
+            &swap_pool_state,
+            &accounts.pool_state.key(),
+            ctx.accounts
+                .instructions_sysvar
+                .as_ref()
+                .map(|instructions_sysvar| instructions_sysvar.as_ref()),
+        )?;
+    }
+    let amount_out =
+        execute_swap_base_input(&mut accounts, &[], amount_in, minimum_amount_out, true)?
+            .amount_out;
+
+    emit!(FundFeeConversionEvent {
+        version: FundFeeConversionEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        swap_pool_id: accounts.pool_state.key(),
+        fee_mint: ctx.accounts.fee_mint.key(),
+        target_mint: accounts.output_token_mint.key(),
+        fee_amount,
+        amount_out,
+    });
+    Ok(())
+}
@@ -2,7 +2,7 @@ use std::ops::DerefMut;
 
 use crate::{
     error::GammaError,
-    states::{validate_config_rates, AmmConfig, AMM_CONFIG_SEED},
+    states::{validate_config_rates, AmmConfig, AmmConfigCreatedEvent, AMM_CONFIG_SEED},
 };
 use anchor_lang::prelude::*;
 
@@ -56,5 +56,16 @@ pub fn create_amm_config(
 
     validate_config_rates(amm_config)?;
 
+    emit!(AmmConfigCreatedEvent {
+        version: AmmConfigCreatedEvent::VERSION,
+        amm_config: ctx.accounts.amm_config.key(),
+        signer: ctx.accounts.owner.key(),
+        index,
+        trade_fee_rate,
+        protocol_fee_rate,
+        fund_fee_rate,
+        create_pool_fee,
+        max_open_time,
+    });
     Ok(())
 }
@@ -0,0 +1,48 @@
+use crate::error::GammaError;
+use crate::states::{GaugeController, GAUGE_CONTROLLER_SEED};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct CreateGaugeController<'info> {
+    /// Only admin can create the gauge controller
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// veGOFX, the voting power of a voter is its balance of this mint
+    pub ve_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [GAUGE_CONTROLLER_SEED.as_bytes()],
+        bump,
+        payer = owner,
+        space = GaugeController::LEN,
+    )]
+    pub gauge_controller: Box<Account<'info, GaugeController>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_gauge_controller(
+    ctx: Context<CreateGaugeController>,
+    epoch_duration: u64,
+    emission_per_epoch: u64,
+) -> Result<()> {
+    require_gt!(epoch_duration, 0, GammaError::InvalidInput);
+
+    let gauge_controller = &mut ctx.accounts.gauge_controller;
+    gauge_controller.bump = ctx.bumps.gauge_controller;
+    gauge_controller.ve_mint = ctx.accounts.ve_mint.key();
+    gauge_controller.start_time = Clock::get()?.unix_timestamp as u64;
+    gauge_controller.epoch_duration = epoch_duration;
+    gauge_controller.emission_per_epoch = emission_per_epoch;
+    gauge_controller.vote_epoch = 0;
+    gauge_controller.total_votes = 0;
+    gauge_controller.previous_total_votes = 0;
+    gauge_controller.padding = [0u64; 4];
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, QuoteCache, QUOTE_CACHE_EXTENSION, QUOTE_CACHE_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateQuoteCache<'info> {
+    /// Only admin can make a pool's swaps pass a quote cache
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [
+            QUOTE_CACHE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = QuoteCache::LEN,
+    )]
+    pub quote_cache: Box<Account<'info, QuoteCache>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_quote_cache(ctx: Context<CreateQuoteCache>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let quote_cache = &mut ctx.accounts.quote_cache;
+    quote_cache.bump = ctx.bumps.quote_cache;
+    quote_cache.pool_id = ctx.accounts.pool_state.key();
+    quote_cache.refresh(&pool_state, Clock::get()?.slot)?;
+    pool_state.set_extension(QUOTE_CACHE_EXTENSION, true);
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use crate::error::GammaError;
+use crate::states::{PoolRewards, PoolState, POOL_REWARDS_SEED, REWARD_VAULT_SEED};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct CreateRewardVault<'info> {
+    /// Only admin can add rewards to a pool
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority, owns the reward vaults too
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            POOL_REWARDS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PoolRewards::LEN,
+    )]
+    pub pool_rewards: Box<Account<'info, PoolRewards>>,
+
+    #[account(
+        mint::token_program = reward_token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Holds the rewards, funded with plain transfers to it
+    #[account(
+        init,
+        seeds = [
+            REWARD_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            reward_mint.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        token::mint = reward_mint,
+        token::authority = authority,
+        token::token_program = reward_token_program,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds a reward mint emitted to the LP staked in the pool at `emissions_per_second`
+pub fn create_reward_vault(
+    ctx: Context<CreateRewardVault>,
+    emissions_per_second: u64,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_rewards = &mut ctx.accounts.pool_rewards;
+    if pool_rewards.pool_state == Pubkey::default() {
+        pool_rewards.bump = ctx.bumps.pool_rewards;
+        pool_rewards.pool_state = ctx.accounts.pool_state.key();
+        pool_rewards.last_update_time = block_timestamp;
+    }
+    // Rewards added later don't earn anything for the time before
+    pool_rewards.update(block_timestamp)?;
+    pool_rewards.add_reward(
+        ctx.accounts.reward_mint.key(),
+        ctx.accounts.reward_vault.key(),
+        emissions_per_second,
+    )
+}
@@ -0,0 +1,72 @@
+use crate::error::GammaError;
+use crate::states::{
+    emit_lifecycle_change, has_config_role, AmmConfigRoles, ConfigRole, PauseExpiry, PoolState,
+    PAUSE_EXPIRY_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct EmergencyPause<'info> {
+    /// Only admin or the pause authority of the pool's config can pause a pool
+    #[account(
+        mut,
+        constraint = has_config_role(
+            config_roles.as_deref(),
+            ConfigRole::Pause,
+            owner.key(),
+        ) @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(constraint = config_roles.amm_config == pool_state.load()?.amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            PAUSE_EXPIRY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PauseExpiry::LEN,
+    )]
+    pub pause_expiry: Box<Account<'info, PauseExpiry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pausing again while the pause is active renews it, keeping the status from before the
+/// first pause to restore
+pub fn emergency_pause(ctx: Context<EmergencyPause>, status: u8, duration: u64) -> Result<()> {
+    require_gt!(duration, 0, GammaError::InvalidInput);
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let pause_expiry = &mut ctx.accounts.pause_expiry;
+
+    // A pause the admin overrode through update_pool no longer has a status to go back to
+    let renewal = pause_expiry.active && pool_state.status == pause_expiry.paused_status;
+    if !renewal {
+        pause_expiry.previous_status = pool_state.status;
+    }
+    let lifecycle_before = pool_state.lifecycle(block_timestamp);
+    pool_state.update_status(status, block_timestamp)?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    emit_lifecycle_change(
+        ctx.accounts.pool_state.key(),
+        lifecycle_before,
+        pool_state.lifecycle(block_timestamp),
+    );
+
+    pause_expiry.bump = ctx.bumps.pause_expiry;
+    pause_expiry.pool_state = ctx.accounts.pool_state.key();
+    pause_expiry.paused_status = status;
+    pause_expiry.expires_at = block_timestamp
+        .checked_add(duration)
+        .ok_or(GammaError::MathOverflow)?;
+    pause_expiry.active = true;
+    Ok(())
+}
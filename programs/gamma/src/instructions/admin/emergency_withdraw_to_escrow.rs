@@ -0,0 +1,259 @@
+use crate::{
+    error::GammaError,
+    states::{
+        emit_lifecycle_change, EmergencyEscrow, EmergencyEscrowEvent, PoolState, PoolStatusBitIndex,
+        EMERGENCY_ESCROW_SEED, EMERGENCY_ESCROW_TIMELOCK, EMERGENCY_ESCROW_VAULT_SEED,
+    },
+    utils::{
+        create_token_account, recall_for_payout, transfer_between_pool_accounts, StrategyAccounts,
+    },
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct EmergencyWithdrawToEscrow<'info> {
+    /// Only admin can move the liquidity into escrow
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority, also owns the escrow vaults
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [
+            EMERGENCY_ESCROW_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = EmergencyEscrow::LEN,
+    )]
+    pub emergency_escrow: Box<Account<'info, EmergencyEscrow>>,
+
+    /// CHECK: escrow vault for token_0, created in the instruction
+    #[account(
+        mut,
+        seeds = [
+            EMERGENCY_ESCROW_VAULT_SEED.as_bytes(),
+            emergency_escrow.key().as_ref(),
+            vault_0_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub escrow_token_0_vault: UncheckedAccount<'info>,
+
+    /// CHECK: escrow vault for token_1, created in the instruction
+    #[account(
+        mut,
+        seeds = [
+            EMERGENCY_ESCROW_VAULT_SEED.as_bytes(),
+            emergency_escrow.key().as_ref(),
+            vault_1_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub escrow_token_1_vault: UncheckedAccount<'info>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint,
+        mint::token_program = token_0_program,
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint,
+        mint::token_program = token_1_program,
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub token_0_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub token_1_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Remaining accounts: the strategies of the token_0 and token_1 vaults, see `StrategyAccounts`.
+/// The deployed reserve is recalled first, so all of it goes into the escrow.
+pub fn emergency_withdraw_to_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, EmergencyWithdrawToEscrow<'info>>,
+) -> Result<()> {
+    let escrow_key = ctx.accounts.emergency_escrow.key();
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.escrow_token_0_vault.to_account_info(),
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_0_program.to_account_info(),
+        &[&[
+            EMERGENCY_ESCROW_VAULT_SEED.as_bytes(),
+            escrow_key.as_ref(),
+            ctx.accounts.vault_0_mint.key().as_ref(),
+            &[ctx.bumps.escrow_token_0_vault][..],
+        ][..]],
+    )?;
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.escrow_token_1_vault.to_account_info(),
+        &ctx.accounts.vault_1_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_1_program.to_account_info(),
+        &[&[
+            EMERGENCY_ESCROW_VAULT_SEED.as_bytes(),
+            escrow_key.as_ref(),
+            ctx.accounts.vault_1_mint.key().as_ref(),
+            &[ctx.bumps.escrow_token_1_vault][..],
+        ][..]],
+    )?;
+
+    let pool_id = ctx.accounts.pool_state.key();
+    let amount_0: u64;
+    let amount_1: u64;
+    let vault_amount_0: u64;
+    let vault_amount_1: u64;
+    let lp_supply: u64;
+    let pool_status: u8;
+    let auth_bump: u8;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        let block_timestamp = Clock::get()?.unix_timestamp as u64;
+        let lifecycle_before = pool_state.lifecycle(block_timestamp);
+        // Nothing can happen on the pool while its liquidity is in escrow. Disabling withdrawals
+        // goes through the same checks as any status change, so LPs keep the withdraw grace
+        // period after trading got paused, and withdraw only mode, to exit first.
+        pool_status = pool_state.status;
+        let status = pool_status
+            | 1 << PoolStatusBitIndex::Deposit as u8
+            | 1 << PoolStatusBitIndex::Withdraw as u8
+            | 1 << PoolStatusBitIndex::Swap as u8;
+        pool_state.update_status(status, block_timestamp)?;
+        emit_lifecycle_change(
+            pool_id,
+            lifecycle_before,
+            pool_state.lifecycle(block_timestamp),
+        );
+
+        // Only the reserve owned by LPs moves. The LP, protocol and fund fees and the kickbacks
+        // stay in the vaults, where their owners collect them whatever the pool status
+        amount_0 = pool_state.token_0_vault_amount;
+        amount_1 = pool_state.token_1_vault_amount;
+        vault_amount_0 = pool_state
+            .vault_accounted_amount(true)
+            .ok_or(GammaError::MathOverflow)?;
+        vault_amount_1 = pool_state
+            .vault_accounted_amount(false)
+            .ok_or(GammaError::MathOverflow)?;
+        pool_state.token_0_vault_amount = 0;
+        pool_state.token_1_vault_amount = 0;
+        // The LP locked at creation and the burned LP have no owner to claim their share, the
+        // holders that can split it
+        lp_supply = pool_state.lp_supply - pool_state.unclaimable_lp();
+        auth_bump = pool_state.auth_bump;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+    }
+
+    // The vaults have to hold the fees as well as the reserve, the strategies return the rest
+    let (token_0_strategy, remaining_accounts) = StrategyAccounts::split(ctx.remaining_accounts);
+    let (token_1_strategy, _) = StrategyAccounts::split(remaining_accounts);
+    recall_for_payout(
+        token_0_strategy.as_ref(),
+        pool_id,
+        &ctx.accounts.token_0_vault.to_account_info(),
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        &ctx.accounts.token_0_program.to_account_info(),
+        vault_amount_0,
+    )?;
+    recall_for_payout(
+        token_1_strategy.as_ref(),
+        pool_id,
+        &ctx.accounts.token_1_vault.to_account_info(),
+        &ctx.accounts.vault_1_mint.to_account_info(),
+        &ctx.accounts.token_1_program.to_account_info(),
+        vault_amount_1,
+    )?;
+
+    // The escrow accounts for what actually arrived, net of any transfer fee that couldn't be
+    // recovered
+    let escrowed_amount_0 = transfer_between_pool_accounts(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.escrow_token_0_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.token_0_program.to_account_info(),
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+    let escrowed_amount_1 = transfer_between_pool_accounts(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.escrow_token_1_vault.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        ctx.accounts.token_1_program.to_account_info(),
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+
+    let escrowed_at = Clock::get()?.unix_timestamp as u64;
+    let emergency_escrow = &mut ctx.accounts.emergency_escrow;
+    emergency_escrow.bump = ctx.bumps.emergency_escrow;
+    emergency_escrow.pool_state = pool_id;
+    emergency_escrow.token_0_vault = ctx.accounts.escrow_token_0_vault.key();
+    emergency_escrow.token_1_vault = ctx.accounts.escrow_token_1_vault.key();
+    emergency_escrow.token_0_amount = escrowed_amount_0;
+    emergency_escrow.token_1_amount = escrowed_amount_1;
+    emergency_escrow.lp_supply = lp_supply;
+    emergency_escrow.escrowed_at = escrowed_at;
+    emergency_escrow.unlock_time = escrowed_at
+        .checked_add(EMERGENCY_ESCROW_TIMELOCK)
+        .ok_or(GammaError::MathOverflow)?;
+    emergency_escrow.pool_status = pool_status;
+
+    emit!(EmergencyEscrowEvent {
+        version: EmergencyEscrowEvent::VERSION,
+        pool_id,
+        escrow: escrow_key,
+        authority: ctx.accounts.owner.key(),
+        token_0_amount: escrowed_amount_0,
+        token_1_amount: escrowed_amount_1,
+        lp_amount: lp_supply,
+        unlock_time: emergency_escrow.unlock_time,
+        change_type: 0,
+    });
+
+    Ok(())
+}
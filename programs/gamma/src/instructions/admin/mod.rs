@@ -1,13 +1,93 @@
+pub mod add_partner;
+pub mod add_strategy_program;
+pub mod add_swap_hook_program;
+pub mod add_transfer_hook_program;
+pub mod apply_config_update;
+pub mod approve_fee_change;
+pub mod cancel_config_update;
 pub mod collect_fund_fee;
 pub mod collect_protocol_fee;
+pub mod convert_fund_fees;
 pub mod create_config;
+pub mod create_gauge_controller;
+pub mod create_quote_cache;
 pub mod create_referral_project;
+pub mod create_reward_vault;
+pub mod emergency_pause;
+pub mod emergency_withdraw_to_escrow;
+pub mod propose_config_update;
+pub mod propose_fee_change;
+pub mod rebuild_observation_state;
+pub mod register_solver;
+pub mod remove_partner;
+pub mod remove_strategy_program;
+pub mod remove_swap_hook_program;
+pub mod remove_transfer_hook_program;
+pub mod restore_escrow_to_pool;
+pub mod set_config_role;
+pub mod set_fee_treasury;
+pub mod set_fund_fee_target_mint;
+pub mod set_mint_exposure_cap;
+pub mod set_price_band;
+pub mod set_reward_emissions;
+pub mod set_segmenter_registry;
+pub mod set_staking_discount;
+pub mod set_token_badge;
+pub mod set_vault_strategy;
+pub mod set_withdraw_only;
+pub mod skim_surplus;
+pub mod slash_solver;
+pub mod track_mint_exposure;
 pub mod update_config;
+pub mod update_fee_model;
+pub mod update_gauge_controller;
 pub mod update_pool;
+pub mod update_referral_project;
+pub mod update_solver;
 
+pub use add_partner::*;
+pub use add_strategy_program::*;
+pub use add_swap_hook_program::*;
+pub use add_transfer_hook_program::*;
+pub use apply_config_update::*;
+pub use approve_fee_change::*;
+pub use cancel_config_update::*;
 pub use collect_fund_fee::*;
 pub use collect_protocol_fee::*;
+pub use convert_fund_fees::*;
 pub use create_config::*;
+pub use create_gauge_controller::*;
+pub use create_quote_cache::*;
 pub use create_referral_project::*;
+pub use create_reward_vault::*;
+pub use emergency_pause::*;
+pub use emergency_withdraw_to_escrow::*;
+pub use propose_config_update::*;
+pub use propose_fee_change::*;
+pub use rebuild_observation_state::*;
+pub use register_solver::*;
+pub use remove_partner::*;
+pub use remove_strategy_program::*;
+pub use remove_swap_hook_program::*;
+pub use remove_transfer_hook_program::*;
+pub use restore_escrow_to_pool::*;
+pub use set_config_role::*;
+pub use set_fee_treasury::*;
+pub use set_fund_fee_target_mint::*;
+pub use set_mint_exposure_cap::*;
+pub use set_price_band::*;
+pub use set_reward_emissions::*;
+pub use set_segmenter_registry::*;
+pub use set_staking_discount::*;
+pub use set_token_badge::*;
+pub use set_vault_strategy::*;
+pub use set_withdraw_only::*;
+pub use skim_surplus::*;
+pub use slash_solver::*;
+pub use track_mint_exposure::*;
 pub use update_config::*;
+pub use update_fee_model::*;
+pub use update_gauge_controller::*;
 pub use update_pool::*;
+pub use update_referral_project::*;
+pub use update_solver::*;
@@ -0,0 +1,59 @@
+use crate::error::GammaError;
+use crate::instructions::{is_timelocked, needs_fee_approval};
+use crate::states::{has_config_role, AmmConfig, AmmConfigRoles, ConfigRole, PendingConfigUpdate};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeConfigUpdate<'info> {
+    /// Only admin or the config authority can propose a config update
+    #[account(
+        constraint = has_config_role(
+            config_roles.as_deref(),
+            ConfigRole::Config,
+            owner.key()
+        ) @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The config's roles, for a config authority other than the admin
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+}
+
+/// Replaces any update still waiting out its delay, the replacement waits out the full delay
+/// again. Changes needing the fee approver's co-signature are scheduled by `approve_fee_change`.
+pub fn propose_config_update(
+    ctx: Context<ProposeConfigUpdate>,
+    param: u16,
+    value: u64,
+) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    require!(
+        is_timelocked(amm_config, param, value),
+        GammaError::InvalidInput
+    );
+    require!(
+        !needs_fee_approval(amm_config, param, value),
+        GammaError::FeeChangeNeedsApproval
+    );
+    schedule_config_update(amm_config, param, value)
+}
+
+pub(crate) fn schedule_config_update(
+    amm_config: &mut Account<AmmConfig>,
+    param: u16,
+    value: u64,
+) -> Result<()> {
+    let apply_after = (Clock::get()?.unix_timestamp as u64)
+        .checked_add(amm_config.fee_change_delay)
+        .ok_or(GammaError::MathOverflow)?;
+    amm_config.pending_update = PendingConfigUpdate {
+        param,
+        value,
+        apply_after,
+    };
+    Ok(())
+}
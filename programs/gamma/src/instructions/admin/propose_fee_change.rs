@@ -0,0 +1,51 @@
+use crate::error::GammaError;
+use crate::states::{AmmConfig, PendingFeeChange, PENDING_FEE_CHANGE_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeFeeChange<'info> {
+    /// Only admin can propose a config change
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            PENDING_FEE_CHANGE_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PendingFeeChange::LEN,
+    )]
+    pub pending_fee_change: Box<Account<'info, PendingFeeChange>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Replaces any change of the config still waiting for approval. Params taking an account take
+/// it as the first remaining account, like `update_amm_config`.
+pub fn propose_fee_change(ctx: Context<ProposeFeeChange>, param: u16, value: u64) -> Result<()> {
+    let new_key = match param {
+        3 | 4 | 12 => ctx
+            .remaining_accounts
+            .first()
+            .ok_or(GammaError::InvalidInput)?
+            .key(),
+        _ => Pubkey::default(),
+    };
+
+    let pending_fee_change = &mut ctx.accounts.pending_fee_change;
+    pending_fee_change.bump = ctx.bumps.pending_fee_change;
+    pending_fee_change.amm_config = ctx.accounts.amm_config.key();
+    pending_fee_change.param = param;
+    pending_fee_change.value = value;
+    pending_fee_change.new_key = new_key;
+    pending_fee_change.proposed_at = Clock::get()?.unix_timestamp as u64;
+    Ok(())
+}
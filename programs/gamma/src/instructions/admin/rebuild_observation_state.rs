@@ -0,0 +1,77 @@
+use crate::{
+    error::GammaError,
+    states::{ObservationState, PoolState, OBSERVATION_STATE_VERSION},
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+#[derive(Accounts)]
+pub struct RebuildObservationState<'info> {
+    /// Only admin can rebuild the oracle of a pool, pays the rent if the account has to grow
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: an AccountLoader would fail for accounts of an older layout, checked in the
+    /// instruction
+    #[account(
+        mut,
+        owner = crate::id(),
+        address = pool_state.load()?.observation_key,
+    )]
+    pub observation_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Starts the observations of a pool over, from the newest one that isn't in the future. Its
+/// cumulative prices carry over so the oracle stays continuous for readers that kept them, while
+/// the other observations, archived ones included, are cleared. Accounts of the layout from
+/// before observations kept the log price are resized to the current one first.
+pub fn rebuild_observation_state(ctx: Context<RebuildObservationState>) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let account_info = ctx.accounts.observation_state.to_account_info();
+    let latest_observation = {
+        let data = account_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == ObservationState::DISCRIMINATOR,
+            GammaError::InvalidInput
+        );
+        ObservationState::latest_observation(&data, block_timestamp)
+    };
+
+    // Legacy accounts shrink to the whole observations of the current layout they can keep
+    let new_len = ObservationState::space(ObservationState::cardinality(account_info.data_len()));
+    if account_info.data_len() != new_len {
+        let minimum_balance = Rent::get()?.minimum_balance(new_len);
+        let lamports_needed = minimum_balance.saturating_sub(account_info.lamports());
+        if lamports_needed > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.owner.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                ),
+                lamports_needed,
+            )?;
+        }
+        account_info.realloc(new_len, true)?;
+    }
+
+    // Zeroed in place, a default ObservationState wouldn't fit on the stack
+    account_info.try_borrow_mut_data()?[8..].fill(0);
+    let (mut observation_state, _) = ObservationState::load_mut_with_archive(&account_info)?;
+    observation_state.pool_id = ctx.accounts.pool_state.key();
+    observation_state.version = OBSERVATION_STATE_VERSION;
+    // Without an observation to start from, the next update initializes the oracle
+    if let Some(latest_observation) = latest_observation {
+        observation_state.initialized = true;
+        observation_state.observations[0] = latest_observation;
+    }
+    Ok(())
+}
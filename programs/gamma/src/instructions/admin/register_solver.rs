@@ -0,0 +1,92 @@
+use crate::{
+    error::GammaError,
+    states::{Solver, SOLVER_BOND_VAULT_SEED, SOLVER_SEED},
+    utils::create_token_account,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+#[derive(Accounts)]
+pub struct RegisterSolver<'info> {
+    /// Only admin can register a solver
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority, also owns the bond vaults
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: the solver's signing key
+    pub solver_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            SOLVER_SEED.as_bytes(),
+            solver_authority.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = Solver::LEN,
+    )]
+    pub solver: Box<Account<'info, Solver>>,
+
+    /// CHECK: bond vault, created in the instruction
+    #[account(
+        mut,
+        seeds = [
+            SOLVER_BOND_VAULT_SEED.as_bytes(),
+            solver.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub bond_vault: UncheckedAccount<'info>,
+
+    #[account(mint::token_program = bond_token_program)]
+    pub bond_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub bond_token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_solver(ctx: Context<RegisterSolver>, min_bond: u64) -> Result<()> {
+    let solver_key = ctx.accounts.solver.key();
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.bond_vault.to_account_info(),
+        &ctx.accounts.bond_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.bond_token_program.to_account_info(),
+        &[&[
+            SOLVER_BOND_VAULT_SEED.as_bytes(),
+            solver_key.as_ref(),
+            &[ctx.bumps.bond_vault][..],
+        ][..]],
+    )?;
+
+    let solver = &mut ctx.accounts.solver;
+    solver.bump = ctx.bumps.solver;
+    solver.authority = ctx.accounts.solver_authority.key();
+    solver.bond_mint = ctx.accounts.bond_mint.key();
+    solver.bond_vault = ctx.accounts.bond_vault.key();
+    solver.bonded_amount = 0;
+    solver.min_bond = min_bond;
+    solver.slashed_amount = 0;
+    solver.failed_settlements = 0;
+    solver.active = true;
+    solver.deactivated_at = 0;
+    solver.registered_at = Clock::get()?.unix_timestamp as u64;
+    solver.padding = [0u64; 4];
+    Ok(())
+}
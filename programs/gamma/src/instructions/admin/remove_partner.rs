@@ -0,0 +1,44 @@
+use crate::error::GammaError;
+use crate::states::{PartnerChangeEvent, PartnerInfo, PartnerType, PoolState, PARTNER_SLOT_EMPTY};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RemovePartner<'info> {
+    /// Only admin can remove partners
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Frees the partner's slot. Positions linked with the partner stay linked but stop counting
+/// towards any slot until the partner is added again.
+pub fn remove_partner(ctx: Context<RemovePartner>, partner_id: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let mut partners = pool_state.partners;
+    let slot = partners
+        .iter_mut()
+        .find(|info| info.links(PartnerType::new(partner_id)))
+        .ok_or(GammaError::InvalidInput)?;
+
+    emit!(PartnerChangeEvent {
+        version: PartnerChangeEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        partner_id: slot.partner_id,
+        lp_token_linked_with_partner: slot.lp_token_linked_with_partner,
+        cumulative_fee_total_times_tvl_share_token_0: slot
+            .cumulative_fee_total_times_tvl_share_token_0,
+        cumulative_fee_total_times_tvl_share_token_1: slot
+            .cumulative_fee_total_times_tvl_share_token_1,
+        change_type: 1,
+    });
+    *slot = PartnerInfo {
+        partner_id: PARTNER_SLOT_EMPTY,
+        ..Default::default()
+    };
+    pool_state.partners = partners;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::error::GammaError;
+use crate::states::{StrategyAllowlistEntry, STRATEGY_ALLOWLIST_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RemoveStrategyProgram<'info> {
+    /// Only admin can remove a strategy adapter program, and gets the rent back
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            STRATEGY_ALLOWLIST_SEED.as_bytes(),
+            allowlist_entry.program_id.as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+        close = owner,
+    )]
+    pub allowlist_entry: Box<Account<'info, StrategyAllowlistEntry>>,
+}
+
+/// The program is never called again, neither to rebalance nor to recall. Vault strategies using
+/// it have to be unwound first, with a zero target and a rebalance.
+pub fn remove_strategy_program(_ctx: Context<RemoveStrategyProgram>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::error::GammaError;
+use crate::states::{SwapHookAllowlistEntry, SWAP_HOOK_ALLOWLIST_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RemoveSwapHookProgram<'info> {
+    /// Only admin can remove a swap hook program, and gets the rent back
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            SWAP_HOOK_ALLOWLIST_SEED.as_bytes(),
+            allowlist_entry.program_id.as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+        close = owner,
+    )]
+    pub allowlist_entry: Box<Account<'info, SwapHookAllowlistEntry>>,
+}
+
+/// Pools can no longer attach the program, those already calling it keep doing so until their
+/// hook is cleared with `clear_pool_swap_hook`
+pub fn remove_swap_hook_program(_ctx: Context<RemoveSwapHookProgram>) -> Result<()> {
+    Ok(())
+}
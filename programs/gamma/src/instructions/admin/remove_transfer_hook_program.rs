@@ -0,0 +1,29 @@
+use crate::error::GammaError;
+use crate::states::{TransferHookAllowlistEntry, TRANSFER_HOOK_ALLOWLIST_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RemoveTransferHookProgram<'info> {
+    /// Only admin can remove a transfer hook program, and gets the rent back
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            TRANSFER_HOOK_ALLOWLIST_SEED.as_bytes(),
+            allowlist_entry.program_id.as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+        close = owner,
+    )]
+    pub allowlist_entry: Box<Account<'info, TransferHookAllowlistEntry>>,
+}
+
+/// Transfers of mints using the program fail from now on, until it is added back
+pub fn remove_transfer_hook_program(_ctx: Context<RemoveTransferHookProgram>) -> Result<()> {
+    Ok(())
+}
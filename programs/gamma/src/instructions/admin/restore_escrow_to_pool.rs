@@ -0,0 +1,184 @@
+use crate::{
+    error::GammaError,
+    states::{emit_lifecycle_change, EmergencyEscrow, EmergencyEscrowEvent, PoolState},
+    utils::{harvest_withheld_fees, transfer_between_pool_accounts},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct RestoreEscrowToPool<'info> {
+    /// Only admin can restore the liquidity, receives the rent of the closed escrow accounts
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority, also owns the escrow vaults
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        has_one = pool_state,
+        close = owner,
+    )]
+    pub emergency_escrow: Box<Account<'info, EmergencyEscrow>>,
+
+    #[account(
+        mut,
+        address = emergency_escrow.token_0_vault
+    )]
+    pub escrow_token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = emergency_escrow.token_1_vault
+    )]
+    pub escrow_token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault, receives the transfer fees withheld in the escrow
+    #[account(
+        mut,
+        address = token_0_vault.mint,
+        mint::token_program = token_0_program,
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault, receives the transfer fees withheld in the escrow
+    #[account(
+        mut,
+        address = token_1_vault.mint,
+        mint::token_program = token_1_program,
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub token_0_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub token_1_program: Interface<'info, TokenInterface>,
+}
+
+/// Moves the escrowed liquidity back into the pool and gives the pool its status from before the
+/// escrow back. Once LP holders started claiming, the escrow can only pay the others out: their
+/// LP no longer backs the pool.
+pub fn restore_escrow_to_pool(ctx: Context<RestoreEscrowToPool>) -> Result<()> {
+    require_eq!(
+        ctx.accounts.emergency_escrow.lp_claimed,
+        0,
+        GammaError::EmergencyEscrowClaimed
+    );
+    let pool_id = ctx.accounts.pool_state.key();
+    let amount_0 = ctx.accounts.escrow_token_0_vault.amount;
+    let amount_1 = ctx.accounts.escrow_token_1_vault.amount;
+    let auth_bump = ctx.accounts.pool_state.load()?.auth_bump;
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]];
+
+    let restored_amount_0 = transfer_between_pool_accounts(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.escrow_token_0_vault.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.token_0_program.to_account_info(),
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        signer_seeds,
+    )?;
+    let restored_amount_1 = transfer_between_pool_accounts(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.escrow_token_1_vault.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        ctx.accounts.token_1_program.to_account_info(),
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        signer_seeds,
+    )?;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        let block_timestamp = Clock::get()?.unix_timestamp as u64;
+        let lifecycle_before = pool_state.lifecycle(block_timestamp);
+        pool_state.update_status(ctx.accounts.emergency_escrow.pool_status, block_timestamp)?;
+        emit_lifecycle_change(
+            pool_id,
+            lifecycle_before,
+            pool_state.lifecycle(block_timestamp),
+        );
+        pool_state.token_0_vault_amount = pool_state
+            .token_0_vault_amount
+            .checked_add(restored_amount_0)
+            .ok_or(GammaError::MathOverflow)?;
+        pool_state.token_1_vault_amount = pool_state
+            .token_1_vault_amount
+            .checked_add(restored_amount_1)
+            .ok_or(GammaError::MathOverflow)?;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+    }
+
+    // Token 2022 accounts still holding withheld transfer fees can't be closed
+    harvest_withheld_fees(
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.token_0_program.to_account_info(),
+        vec![ctx.accounts.escrow_token_0_vault.to_account_info()],
+    )?;
+    harvest_withheld_fees(
+        ctx.accounts.vault_1_mint.to_account_info(),
+        ctx.accounts.token_1_program.to_account_info(),
+        vec![ctx.accounts.escrow_token_1_vault.to_account_info()],
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_0_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_0_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_1_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_1_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit!(EmergencyEscrowEvent {
+        version: EmergencyEscrowEvent::VERSION,
+        pool_id,
+        escrow: ctx.accounts.emergency_escrow.key(),
+        authority: ctx.accounts.owner.key(),
+        token_0_amount: restored_amount_0,
+        token_1_amount: restored_amount_1,
+        lp_amount: ctx.accounts.emergency_escrow.lp_supply,
+        unlock_time: ctx.accounts.emergency_escrow.unlock_time,
+        change_type: 1,
+    });
+
+    Ok(())
+}
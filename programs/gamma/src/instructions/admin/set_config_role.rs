@@ -0,0 +1,53 @@
+use crate::error::GammaError;
+use crate::states::{
+    AmmConfig, AmmConfigRoles, ConfigRole, ConfigRoleChangeEvent, AMM_CONFIG_ROLES_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetConfigRole<'info> {
+    /// Only admin can hand out roles
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            AMM_CONFIG_ROLES_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = AmmConfigRoles::LEN,
+    )]
+    pub config_roles: Box<Account<'info, AmmConfigRoles>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// The default pubkey as `authority` takes the role back to the admin alone
+pub fn set_config_role(
+    ctx: Context<SetConfigRole>,
+    role: ConfigRole,
+    authority: Pubkey,
+) -> Result<()> {
+    let config_roles = &mut ctx.accounts.config_roles;
+    config_roles.bump = ctx.bumps.config_roles;
+    config_roles.amm_config = ctx.accounts.amm_config.key();
+    let old_authority = config_roles.authority(role);
+    config_roles.set_authority(role, authority);
+
+    emit!(ConfigRoleChangeEvent {
+        version: ConfigRoleChangeEvent::VERSION,
+        amm_config: ctx.accounts.amm_config.key(),
+        role: role as u8,
+        old_authority,
+        new_authority: authority,
+    });
+    Ok(())
+}
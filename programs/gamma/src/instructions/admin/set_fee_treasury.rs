@@ -0,0 +1,58 @@
+use crate::error::GammaError;
+use crate::states::{
+    has_config_role, AmmConfig, AmmConfigRoles, ConfigRole, FeeTreasury, FEE_TREASURY_SEED,
+    MAX_SWEEP_BOUNTY_RATE,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetFeeTreasury<'info> {
+    /// Only admin or the fee collect authority can route the protocol fees
+    #[account(
+        mut,
+        constraint = has_config_role(
+            config_roles.as_deref(),
+            ConfigRole::FeeCollect,
+            owner.key(),
+        ) @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            FEE_TREASURY_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = FeeTreasury::LEN,
+    )]
+    pub fee_treasury: Box<Account<'info, FeeTreasury>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_fee_treasury(
+    ctx: Context<SetFeeTreasury>,
+    treasury: Pubkey,
+    bounty_rate: u64,
+) -> Result<()> {
+    require_keys_neq!(treasury, Pubkey::default(), GammaError::InvalidInput);
+    require_gte!(
+        MAX_SWEEP_BOUNTY_RATE,
+        bounty_rate,
+        GammaError::InvalidFeeBounds
+    );
+    let fee_treasury = &mut ctx.accounts.fee_treasury;
+    fee_treasury.bump = ctx.bumps.fee_treasury;
+    fee_treasury.amm_config = ctx.accounts.amm_config.key();
+    fee_treasury.treasury = treasury;
+    fee_treasury.bounty_rate = bounty_rate;
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::error::GammaError;
+use crate::states::{
+    has_config_role, AmmConfig, AmmConfigRoles, ConfigRole, FundFeeConversion,
+    FUND_FEE_CONVERSION_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct SetFundFeeTargetMint<'info> {
+    /// Only admin, fund_owner or the fee collect authority can pick the mint
+    #[account(
+        mut,
+        constraint = (
+            owner.key() == amm_config.fund_owner
+                || has_config_role(config_roles.as_deref(), ConfigRole::FeeCollect, owner.key())
+        ) @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
+
+    /// The mint the fund fees are converted into
+    pub target_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            FUND_FEE_CONVERSION_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = FundFeeConversion::LEN,
+    )]
+    pub fund_fee_conversion: Box<Account<'info, FundFeeConversion>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_fund_fee_target_mint(ctx: Context<SetFundFeeTargetMint>) -> Result<()> {
+    let fund_fee_conversion = &mut ctx.accounts.fund_fee_conversion;
+    fund_fee_conversion.bump = ctx.bumps.fund_fee_conversion;
+    fund_fee_conversion.amm_config = ctx.accounts.amm_config.key();
+    fund_fee_conversion.target_mint = ctx.accounts.target_mint.key();
+    Ok(())
+}
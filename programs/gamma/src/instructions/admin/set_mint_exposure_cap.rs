@@ -0,0 +1,39 @@
+use crate::error::GammaError;
+use crate::states::{MintExposure, MINT_EXPOSURE_SEED};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct SetMintExposureCap<'info> {
+    /// Only admin can cap a mint
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            MINT_EXPOSURE_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = MintExposure::LEN,
+    )]
+    pub mint_exposure: Box<Account<'info, MintExposure>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// A cap below the current total only blocks the tracked pools from taking in more of the mint
+pub fn set_mint_exposure_cap(ctx: Context<SetMintExposureCap>, cap: u64) -> Result<()> {
+    let mint_exposure = &mut ctx.accounts.mint_exposure;
+    mint_exposure.bump = ctx.bumps.mint_exposure;
+    mint_exposure.mint = ctx.accounts.mint.key();
+    mint_exposure.cap = cap;
+    Ok(())
+}
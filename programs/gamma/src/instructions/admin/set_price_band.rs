@@ -0,0 +1,79 @@
+use crate::error::GammaError;
+use crate::states::{
+    PoolState, PriceBand, ORACLE_SOURCE_SWITCHBOARD, PRICE_BAND_BPS_DENOMINATOR,
+    PRICE_BAND_EXTENSION, PRICE_BAND_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPriceBand<'info> {
+    /// Only admin can set the band of a pool
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            PRICE_BAND_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = PriceBand::LEN,
+    )]
+    pub price_band: Box<Account<'info, PriceBand>>,
+
+    /// CHECK: decoded according to `oracle_source`, it has to give a fresh price right away
+    pub oracle: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Zero `max_deviation_bps` turns the band off, swaps then no longer need the oracle
+pub fn set_price_band(
+    ctx: Context<SetPriceBand>,
+    oracle_source: u8,
+    invert: bool,
+    max_deviation_bps: u64,
+    max_staleness: u64,
+) -> Result<()> {
+    require_gte!(
+        ORACLE_SOURCE_SWITCHBOARD,
+        oracle_source,
+        GammaError::InvalidInput
+    );
+    require_gte!(
+        PRICE_BAND_BPS_DENOMINATOR,
+        max_deviation_bps,
+        GammaError::InvalidInput
+    );
+
+    let price_band = &mut ctx.accounts.price_band;
+    price_band.bump = ctx.bumps.price_band;
+    price_band.pool_state = ctx.accounts.pool_state.key();
+    price_band.oracle = ctx.accounts.oracle.key();
+    price_band.oracle_source = oracle_source;
+    price_band.invert = invert;
+    price_band.max_deviation_bps = max_deviation_bps;
+    price_band.max_staleness = max_staleness;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let enabled = max_deviation_bps != 0;
+    if enabled {
+        // A band the oracle can't serve would halt every swap
+        price_band.oracle_price_x32(
+            &ctx.accounts.oracle,
+            Clock::get()?.unix_timestamp as u64,
+            pool_state.mint_0_decimals,
+            pool_state.mint_1_decimals,
+        )?;
+    }
+    pool_state.set_extension(PRICE_BAND_EXTENSION, enabled);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::error::GammaError;
+use crate::states::{PoolRewards, POOL_REWARDS_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRewardEmissions<'info> {
+    /// Only admin can change emission rates
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_REWARDS_SEED.as_bytes(),
+            pool_rewards.pool_state.as_ref(),
+        ],
+        bump = pool_rewards.bump,
+    )]
+    pub pool_rewards: Box<Account<'info, PoolRewards>>,
+}
+
+/// Changes the emission rate of the pool's reward at `reward_index`, zero stops the emissions.
+/// What was emitted up to now keeps accruing to the stakes at the old rate.
+pub fn set_reward_emissions(
+    ctx: Context<SetRewardEmissions>,
+    reward_index: u8,
+    emissions_per_second: u64,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_rewards = &mut ctx.accounts.pool_rewards;
+    require_gt!(
+        pool_rewards.reward_count,
+        reward_index,
+        GammaError::InvalidInput
+    );
+    pool_rewards.update(block_timestamp)?;
+    pool_rewards.rewards[usize::from(reward_index)].emissions_per_second = emissions_per_second;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::error::GammaError;
+use crate::states::{
+    SegmenterRegistry, SEGMENTER_REGISTRY_MAX_SEGMENTERS, SEGMENTER_REGISTRY_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(partner_id: u16)]
+pub struct SetSegmenterRegistry<'info> {
+    /// Only admin can onboard order-flow partners
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            SEGMENTER_REGISTRY_SEED.as_bytes(),
+            &partner_id.to_le_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = SegmenterRegistry::LEN,
+    )]
+    pub segmenter_registry: Box<Account<'info, SegmenterRegistry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Replaces the partner's segmenters and kickback rate, an empty list offboards the partner
+pub fn set_segmenter_registry(
+    ctx: Context<SetSegmenterRegistry>,
+    partner_id: u16,
+    kickback_bps: u64,
+    mut segmenters: Vec<Pubkey>,
+) -> Result<()> {
+    require_gte!(10_000, kickback_bps, GammaError::InvalidInput);
+    segmenters.sort();
+    segmenters.dedup();
+    require_gte!(
+        SEGMENTER_REGISTRY_MAX_SEGMENTERS,
+        segmenters.len(),
+        GammaError::InvalidInput
+    );
+
+    let segmenter_registry = &mut ctx.accounts.segmenter_registry;
+    segmenter_registry.bump = ctx.bumps.segmenter_registry;
+    segmenter_registry.partner_id = partner_id;
+    segmenter_registry.kickback_bps = kickback_bps;
+    segmenter_registry.segmenters = [Pubkey::default(); SEGMENTER_REGISTRY_MAX_SEGMENTERS];
+    segmenter_registry.segmenters[..segmenters.len()].copy_from_slice(&segmenters);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::error::GammaError;
+use crate::states::{
+    AmmConfig, StakingDiscount, StakingDiscountTier, STAKING_DISCOUNT_SEED,
+    STAKING_DISCOUNT_TIER_NUM,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct SetStakingDiscount<'info> {
+    /// Only admin can set the discounts
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Receipt token of the GooseFX staking program for staked GOFX
+    pub staked_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            STAKING_DISCOUNT_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = StakingDiscount::LEN,
+    )]
+    pub staking_discount: Box<Account<'info, StakingDiscount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Tiers have to be sorted by their minimum stake, with discounts of at most the whole trade fee.
+/// Zeroed tiers turn the discount off.
+pub fn set_staking_discount(
+    ctx: Context<SetStakingDiscount>,
+    tiers: [StakingDiscountTier; STAKING_DISCOUNT_TIER_NUM],
+) -> Result<()> {
+    for tier in tiers.iter() {
+        require_gte!(10_000, tier.discount_bps, GammaError::InvalidInput);
+    }
+    require!(
+        tiers
+            .windows(2)
+            .all(|pair| pair[0].min_staked_amount <= pair[1].min_staked_amount),
+        GammaError::InvalidInput
+    );
+
+    let staking_discount = &mut ctx.accounts.staking_discount;
+    staking_discount.bump = ctx.bumps.staking_discount;
+    staking_discount.amm_config = ctx.accounts.amm_config.key();
+    staking_discount.staked_mint = ctx.accounts.staked_mint.key();
+    staking_discount.tiers = tiers;
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::error::GammaError;
+use crate::states::{TokenBadge, BRIDGE_ORIGIN_OTHER, TOKEN_BADGE_SEED};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(Accounts)]
+pub struct SetTokenBadge<'info> {
+    /// Only admin can verify a mint
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            TOKEN_BADGE_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = TokenBadge::LEN,
+    )]
+    pub token_badge: Box<Account<'info, TokenBadge>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_token_badge(
+    ctx: Context<SetTokenBadge>,
+    canonical: bool,
+    bridge_origin: u8,
+    origin_chain_id: u16,
+    origin_address: [u8; 32],
+) -> Result<()> {
+    require_gte!(BRIDGE_ORIGIN_OTHER, bridge_origin, GammaError::InvalidInput);
+
+    let token_badge = &mut ctx.accounts.token_badge;
+    token_badge.bump = ctx.bumps.token_badge;
+    token_badge.mint = ctx.accounts.mint.key();
+    token_badge.canonical = canonical;
+    token_badge.bridge_origin = bridge_origin;
+    token_badge.origin_chain_id = origin_chain_id;
+    token_badge.origin_address = origin_address;
+    token_badge.verified_at = Clock::get()?.unix_timestamp as u64;
+    Ok(())
+}
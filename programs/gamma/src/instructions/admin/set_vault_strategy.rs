@@ -0,0 +1,155 @@
+use crate::error::GammaError;
+use crate::states::{
+    PoolState, StrategyAllowlistEntry, VaultStrategy, MAX_STRATEGY_PROTOCOL_ACCOUNTS,
+    MAX_STRATEGY_TARGET_RATE, REBASING_SHARES_EXTENSION, STRATEGY_ALLOWLIST_SEED, STRATEGY_SEED,
+    VAULT_STRATEGY_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct SetVaultStrategy<'info> {
+    /// Only admin can deploy a vault to a lending protocol
+    #[account(
+        mut,
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The vault of the pool whose idle reserve is deployed
+    #[account(
+        constraint = vault.key() == pool_state.load()?.token_0_vault
+            || vault.key() == pool_state.load()?.token_1_vault @ GammaError::InvalidVault
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = vault.mint,
+        mint::token_program = token_program,
+    )]
+    pub vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: The adapter program, checked against the allowlist
+    pub adapter_program: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            STRATEGY_ALLOWLIST_SEED.as_bytes(),
+            adapter_program.key().as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Box<Account<'info, StrategyAllowlistEntry>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            VAULT_STRATEGY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            vault.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = VaultStrategy::LEN,
+    )]
+    pub vault_strategy: Box<Account<'info, VaultStrategy>>,
+
+    /// CHECK: strategy authority, sole owner of the strategy's token account and position
+    #[account(
+        seeds = [
+            STRATEGY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            vault_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub strategy_authority: UncheckedAccount<'info>,
+
+    /// The reserve moves through it between the vault and the protocol
+    #[account(
+        init_if_needed,
+        seeds = [
+            STRATEGY_SEED.as_bytes(),
+            strategy_authority.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        token::mint = vault_mint,
+        token::authority = strategy_authority,
+        token::token_program = token_program,
+    )]
+    pub strategy_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the share of the vault's reserve `rebalance_strategy` keeps deployed. The lending
+/// protocol accounts of the adapter are the remaining accounts, every adapter call has to pass
+/// the same. A strategy can only move to another adapter or other protocol accounts once
+/// everything is recalled, and a zero target unwinds it.
+pub fn set_vault_strategy<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetVaultStrategy<'info>>,
+    target_rate: u64,
+) -> Result<()> {
+    require_gte!(
+        MAX_STRATEGY_PROTOCOL_ACCOUNTS,
+        ctx.remaining_accounts.len(),
+        GammaError::InvalidStrategy
+    );
+    require_gte!(
+        MAX_STRATEGY_TARGET_RATE,
+        target_rate,
+        GammaError::InvalidFeeBounds
+    );
+    {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        // The rebasing reserve and the Kamino rebalance both read the vault balance without the
+        // strategy's deployed amount
+        require!(
+            !pool_state.has_extension(REBASING_SHARES_EXTENSION),
+            GammaError::InvalidStrategy
+        );
+        let (in_kamino, max_shared) = if ctx.accounts.vault.key() == pool_state.token_0_vault {
+            (
+                pool_state.token_0_amount_in_kamino,
+                pool_state.max_shared_token0,
+            )
+        } else {
+            (
+                pool_state.token_1_amount_in_kamino,
+                pool_state.max_shared_token1,
+            )
+        };
+        require!(
+            in_kamino == 0 && max_shared == 0,
+            GammaError::InvalidStrategy
+        );
+    }
+
+    let mut protocol_accounts = [Pubkey::default(); MAX_STRATEGY_PROTOCOL_ACCOUNTS];
+    for (key, account) in protocol_accounts.iter_mut().zip(ctx.remaining_accounts) {
+        *key = account.key();
+    }
+    let vault_strategy = &mut ctx.accounts.vault_strategy;
+    if vault_strategy.deployed_amount > 0 {
+        require!(
+            vault_strategy.adapter_program == ctx.accounts.adapter_program.key()
+                && usize::from(vault_strategy.adapter_accounts_len) == ctx.remaining_accounts.len()
+                && vault_strategy.protocol_accounts == protocol_accounts,
+            GammaError::InvalidStrategy
+        );
+    }
+    vault_strategy.bump = ctx.bumps.vault_strategy;
+    vault_strategy.pool_state = ctx.accounts.pool_state.key();
+    vault_strategy.vault = ctx.accounts.vault.key();
+    vault_strategy.adapter_program = ctx.accounts.adapter_program.key();
+    vault_strategy.adapter_accounts_len = ctx.remaining_accounts.len() as u8;
+    vault_strategy.authority_bump = ctx.bumps.strategy_authority;
+    vault_strategy.token_account = ctx.accounts.strategy_token_account.key();
+    vault_strategy.protocol_accounts = protocol_accounts;
+    vault_strategy.target_rate = target_rate;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::error::GammaError;
+use crate::states::{emit_lifecycle_change, PoolState, PoolStatusBitIndex};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetWithdrawOnly<'info> {
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Entering the mode disables deposits and swaps and enables withdrawals, which then can't be
+/// disabled. Exiting enables deposits and swaps again, the directional swap bits stay as they
+/// were. Either way the pool moves to or from `PoolLifecycle::WithdrawOnly`, which is emitted.
+pub fn set_withdraw_only(ctx: Context<SetWithdrawOnly>, enabled: bool) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::WindDown) {
+        return err!(GammaError::PoolWindingDown);
+    }
+    let withdraw_only = !pool_state.get_status_by_bit(PoolStatusBitIndex::WithdrawOnly);
+    require!(enabled != withdraw_only, GammaError::InvalidInput);
+
+    let mode_bits = 1 << PoolStatusBitIndex::WithdrawOnly as u8
+        | 1 << PoolStatusBitIndex::Deposit as u8
+        | 1 << PoolStatusBitIndex::Swap as u8;
+    let status = if enabled {
+        (pool_state.status | mode_bits) & !(1 << PoolStatusBitIndex::Withdraw as u8)
+    } else {
+        pool_state.status & !mode_bits
+    };
+    let lifecycle_before = pool_state.lifecycle(block_timestamp);
+    pool_state.update_status(status, block_timestamp)?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    emit_lifecycle_change(
+        ctx.accounts.pool_state.key(),
+        lifecycle_before,
+        pool_state.lifecycle(block_timestamp),
+    );
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use crate::{
+    error::GammaError,
+    states::{AmmConfig, PoolState, SurplusSkimEvent},
+    utils::transfer_from_pool_vault_to_user,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SkimSurplus<'info> {
+    /// Only admin or fund_owner can skim the surplus
+    #[account(constraint = (owner.key() == amm_config.fund_owner || owner.key() == crate::admin::id()) @ GammaError::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores fund_owner
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The treasury account receiving the token_0 surplus
+    #[account(mut)]
+    pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The treasury account receiving the token_1 surplus
+    #[account(mut)]
+    pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+/// Sweeps the tokens the vaults hold beyond what the pool accounts for to the treasury.
+///
+/// Tokens sent to a vault directly were never priced into the reserves, so they aren't credited
+/// to the LPs: that would move the price without a swap. The reserves, fees, kickbacks and LP
+/// fees held by the vaults are left untouched. Pools of rebasing mints first take the vault
+/// balances into their reserves, so what a rebase adds stays with the LPs and only dust beyond
+/// that can be skimmed.
+pub fn skim_surplus(ctx: Context<SkimSurplus>) -> Result<()> {
+    let (amount_0, amount_1, auth_bump) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.sync_rebasing_reserve(true, ctx.accounts.token_0_vault.amount)?;
+        pool_state.sync_rebasing_reserve(false, ctx.accounts.token_1_vault.amount)?;
+        (
+            pool_state.vault_surplus(true, ctx.accounts.token_0_vault.amount)?,
+            pool_state.vault_surplus(false, ctx.accounts.token_1_vault.amount)?,
+            pool_state.auth_bump,
+        )
+    };
+
+    for (vault, mint, recipient, amount) in [
+        (
+            &ctx.accounts.token_0_vault,
+            &ctx.accounts.vault_0_mint,
+            &ctx.accounts.recipient_token_0_account,
+            amount_0,
+        ),
+        (
+            &ctx.accounts.token_1_vault,
+            &ctx.accounts.vault_1_mint,
+            &ctx.accounts.recipient_token_1_account,
+            amount_1,
+        ),
+    ] {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            vault.to_account_info(),
+            recipient.to_account_info(),
+            mint.to_account_info(),
+            if mint.to_account_info().owner == ctx.accounts.token_program.key {
+                ctx.accounts.token_program.to_account_info()
+            } else {
+                ctx.accounts.token_program_2022.to_account_info()
+            },
+            amount,
+            mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        )?;
+    }
+
+    emit!(SurplusSkimEvent {
+        version: SurplusSkimEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        amount_0,
+        amount_1,
+    });
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::{
+    error::GammaError,
+    states::{Solver, SolverSlashedEvent, SOLVER_SEED},
+    utils::transfer_from_pool_vault_to_user,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct SlashSolver<'info> {
+    /// Only admin can slash a solver
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority, owns the bond vaults
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            SOLVER_SEED.as_bytes(),
+            solver.authority.as_ref(),
+        ],
+        bump = solver.bump,
+    )]
+    pub solver: Box<Account<'info, Solver>>,
+
+    #[account(mut, address = solver.bond_vault)]
+    pub bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = solver.bond_mint)]
+    pub bond_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Receives the slashed bond, e.g. the counterparty of the failed settlement
+    #[account(mut, token::mint = bond_mint)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022
+    pub bond_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Slashes `amount` of the bond for a settlement the solver failed
+pub fn slash_solver(ctx: Context<SlashSolver>, amount: u64) -> Result<()> {
+    let solver = &mut ctx.accounts.solver;
+    solver.bonded_amount = solver
+        .bonded_amount
+        .checked_sub(amount)
+        .ok_or(GammaError::InsufficientBond)?;
+    solver.slashed_amount = solver
+        .slashed_amount
+        .checked_add(amount)
+        .ok_or(GammaError::MathOverflow)?;
+    solver.failed_settlements = solver
+        .failed_settlements
+        .checked_add(1)
+        .ok_or(GammaError::MathOverflow)?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.bond_vault.to_account_info(),
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.bond_mint.to_account_info(),
+        ctx.accounts.bond_token_program.to_account_info(),
+        amount,
+        ctx.accounts.bond_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    emit!(SolverSlashedEvent {
+        version: SolverSlashedEvent::VERSION,
+        solver: solver.key(),
+        recipient: ctx.accounts.recipient_token_account.key(),
+        amount,
+        bonded_amount: solver.bonded_amount,
+        failed_settlements: solver.failed_settlements,
+    });
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::error::GammaError;
+use crate::states::{
+    MintExposure, PoolState, MINT_EXPOSURE_SEED, TOKEN_0_EXPOSURE_EXTENSION,
+    TOKEN_1_EXPOSURE_EXTENSION,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TrackMintExposure<'info> {
+    /// Only admin can change which pools count towards an exposure
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Exposure of the pool's token_0 or token_1 mint
+    #[account(
+        mut,
+        seeds = [
+            MINT_EXPOSURE_SEED.as_bytes(),
+            mint_exposure.mint.as_ref(),
+        ],
+        bump = mint_exposure.bump,
+    )]
+    pub mint_exposure: Box<Account<'info, MintExposure>>,
+}
+
+/// Adds the pool's reserve of the mint to its exposure, or takes it out again. Swaps, deposits
+/// and withdrawals of a tracked pool have to pass the exposure account from then on.
+pub fn track_mint_exposure(ctx: Context<TrackMintExposure>, tracked: bool) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let mint_exposure = &mut ctx.accounts.mint_exposure;
+    let (reserve_0, reserve_1) = pool_state.vault_amount_without_fee()?;
+    let (extension, reserve) = if mint_exposure.mint == pool_state.token_0_mint {
+        (TOKEN_0_EXPOSURE_EXTENSION, reserve_0)
+    } else if mint_exposure.mint == pool_state.token_1_mint {
+        (TOKEN_1_EXPOSURE_EXTENSION, reserve_1)
+    } else {
+        return err!(GammaError::InvalidInput);
+    };
+    require!(
+        pool_state.has_extension(extension) != tracked,
+        GammaError::InvalidInput
+    );
+
+    if tracked {
+        mint_exposure.apply_reserve_change(0, reserve)?;
+    } else {
+        mint_exposure.apply_reserve_change(reserve, 0)?;
+    }
+    pool_state.set_extension(extension, tracked);
+    Ok(())
+}
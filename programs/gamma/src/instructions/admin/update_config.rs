@@ -3,66 +3,213 @@ use anchor_lang::prelude::*;
 use crate::{
     error::GammaError,
     fees::FEE_RATE_DENOMINATOR_VALUE,
-    states::{validate_config_rates, AmmConfig},
+    states::{
+        has_config_role, validate_config_rates, AmmConfig, AmmConfigChangeEvent, AmmConfigRoles,
+        ConfigRole,
+    },
 };
 
 #[derive(Accounts)]
 pub struct UpdateAmmConfig<'info> {
-    /// The amm config owner or admin
-    #[account( address = crate::admin::id() @GammaError::InvalidOwner)]
+    /// The admin or the config authority
+    #[account(
+        constraint = has_config_role(
+            config_roles.as_deref(),
+            ConfigRole::Config,
+            owner.key()
+        ) @ GammaError::InvalidOwner
+    )]
     pub owner: Signer<'info>,
 
     /// The amm config account to update
     #[account(mut)]
     pub amm_config: Account<'info, AmmConfig>,
+
+    /// The config's roles, for a config authority other than the admin
+    #[account(has_one = amm_config)]
+    pub config_roles: Option<Account<'info, AmmConfigRoles>>,
 }
 
 pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u16, value: u64) -> Result<()> {
+    let new_key = ctx.remaining_accounts.first().map(|account| account.key());
+    // The fee owners and the fee approver stay with the admin, the config authority can't
+    // redirect the fees
+    if ctx.accounts.owner.key() != crate::admin::id() {
+        require!(!matches!(param, 3 | 4 | 12), GammaError::InvalidOwner);
+    }
     let amm_config = &mut ctx.accounts.amm_config;
+    require!(
+        !needs_fee_approval(amm_config, param, value),
+        GammaError::FeeChangeNeedsApproval
+    );
+    require!(
+        !is_timelocked(amm_config, param, value),
+        GammaError::FeeChangeTimelocked
+    );
+    apply_amm_config_update(amm_config, param, value, new_key, ctx.accounts.owner.key())
+}
+
+/// Whether the update has to wait out the config's `fee_change_delay`: fee rate changes, the
+/// bounds of pool fees, and shortening the delay itself
+pub fn is_timelocked(amm_config: &AmmConfig, param: u16, value: u64) -> bool {
+    if amm_config.fee_change_delay == 0 {
+        return false;
+    }
+    match param {
+        0 | 1 | 2 | 14 | 15 => true,
+        13 => value < amm_config.fee_change_delay,
+        _ => false,
+    }
+}
+
+/// Whether the update has to be proposed and co-signed by the config's fee approver: fee rate
+/// changes larger than the approval threshold, and any change to the approver or threshold
+pub fn needs_fee_approval(amm_config: &AmmConfig, param: u16, value: u64) -> bool {
+    if amm_config.fee_approver == Pubkey::default() {
+        return false;
+    }
+    let current_rate = match param {
+        0 => amm_config.trade_fee_rate,
+        1 => amm_config.protocol_fee_rate,
+        2 => amm_config.fund_fee_rate,
+        10 => amm_config.flash_loan_fee_rate,
+        11 | 12 => return true,
+        _ => return false,
+    };
+    current_rate.abs_diff(value) > amm_config.fee_approval_threshold
+}
+
+/// `new_key` is the new owner of params 3 and 4, or the new fee approver of param 12
+pub(crate) fn apply_amm_config_update(
+    amm_config: &mut Account<AmmConfig>,
+    param: u16,
+    value: u64,
+    new_key: Option<Pubkey>,
+    signer: Pubkey,
+) -> Result<()> {
+    let (old_value, old_key) = config_param_value(amm_config, param);
     match param {
-        0 => update_trade_fee_rate(amm_config, value),
-        1 => update_protocol_fee_rate(amm_config, value),
-        2 => update_fund_fee_rate(amm_config, value),
+        0 => update_trade_fee_rate(amm_config, value)?,
+        1 => update_protocol_fee_rate(amm_config, value)?,
+        2 => update_fund_fee_rate(amm_config, value)?,
         3 => {
-            let new_protocol_owner = match ctx.remaining_accounts.iter().next() {
-                Some(account) => account.key(),
-                None => return err!(GammaError::InvalidInput),
-            };
+            let new_protocol_owner = new_key.ok_or(GammaError::InvalidInput)?;
             set_new_protocol_owner(amm_config, new_protocol_owner)?;
         }
         4 => {
-            let new_fund_owner = match ctx.remaining_accounts.iter().next() {
-                Some(account) => account.key(),
-                None => return err!(GammaError::InvalidInput),
-            };
+            let new_fund_owner = new_key.ok_or(GammaError::InvalidInput)?;
             set_new_fund_owner(amm_config, new_fund_owner)?;
         }
         5 => amm_config.create_pool_fee = value,
         6 => amm_config.disable_create_pool = if value == 0 { false } else { true },
         7 => amm_config.max_open_time = value,
+        8 => amm_config.segmenter_kickback_bps = value,
+        9 => amm_config.lp_referral_share_bps = value,
+        10 => amm_config.flash_loan_fee_rate = value,
+        11 => amm_config.fee_approval_threshold = value,
+        // Passing the system program, i.e. the default pubkey, removes the approver
+        12 => amm_config.fee_approver = new_key.ok_or(GammaError::InvalidInput)?,
+        13 => amm_config.fee_change_delay = value,
+        14 => amm_config.min_pool_trade_fee_rate = value,
+        15 => amm_config.max_pool_trade_fee_rate = value,
+        16 => amm_config.min_open_delay = value,
+        17 => amm_config.default_open_delay = value,
+        18 => amm_config.disable_referrals = value != 0,
+        19 => amm_config.referral_payout_in_output = value != 0,
+        20 => {
+            amm_config.mint_safety_policy =
+                u8::try_from(value).map_err(|_| GammaError::InvalidInput)?
+        }
+        21 => {
+            amm_config.max_mutable_transfer_fee_bps =
+                u16::try_from(value).map_err(|_| GammaError::InvalidInput)?
+        }
         _ => return err!(GammaError::InvalidInput),
     }
 
     validate_config_rates(amm_config)?;
 
+    let (new_value, new_key) = config_param_value(amm_config, param);
+    emit!(AmmConfigChangeEvent {
+        version: AmmConfigChangeEvent::VERSION,
+        amm_config: amm_config.key(),
+        signer,
+        param,
+        old_value,
+        new_value,
+        old_key,
+        new_key,
+    });
     Ok(())
 }
 
-fn update_trade_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u64) {
-    assert!(trade_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+/// Value of `param` as `apply_amm_config_update` sets it, or the key of params 3, 4 and 12
+fn config_param_value(amm_config: &AmmConfig, param: u16) -> (u64, Pubkey) {
+    let value = match param {
+        0 => amm_config.trade_fee_rate,
+        1 => amm_config.protocol_fee_rate,
+        2 => amm_config.fund_fee_rate,
+        3 => return (0, amm_config.protocol_owner),
+        4 => return (0, amm_config.fund_owner),
+        5 => amm_config.create_pool_fee,
+        6 => u64::from(amm_config.disable_create_pool),
+        7 => amm_config.max_open_time,
+        8 => amm_config.segmenter_kickback_bps,
+        9 => amm_config.lp_referral_share_bps,
+        10 => amm_config.flash_loan_fee_rate,
+        11 => amm_config.fee_approval_threshold,
+        12 => return (0, amm_config.fee_approver),
+        13 => amm_config.fee_change_delay,
+        14 => amm_config.min_pool_trade_fee_rate,
+        15 => amm_config.max_pool_trade_fee_rate,
+        16 => amm_config.min_open_delay,
+        17 => amm_config.default_open_delay,
+        18 => u64::from(amm_config.disable_referrals),
+        19 => u64::from(amm_config.referral_payout_in_output),
+        20 => u64::from(amm_config.mint_safety_policy),
+        21 => u64::from(amm_config.max_mutable_transfer_fee_bps),
+        _ => 0,
+    };
+    (value, Pubkey::default())
+}
+
+fn update_trade_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u64) -> Result<()> {
+    require_gte!(
+        FEE_RATE_DENOMINATOR_VALUE,
+        trade_fee_rate,
+        GammaError::InvalidFeeBounds
+    );
     amm_config.trade_fee_rate = trade_fee_rate;
+    Ok(())
 }
 
-fn update_protocol_fee_rate(amm_config: &mut Account<AmmConfig>, protocol_fee_rate: u64) {
-    assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(protocol_fee_rate + amm_config.fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+fn update_protocol_fee_rate(
+    amm_config: &mut Account<AmmConfig>,
+    protocol_fee_rate: u64,
+) -> Result<()> {
+    check_fee_share_rates(protocol_fee_rate, amm_config.fund_fee_rate)?;
     amm_config.protocol_fee_rate = protocol_fee_rate;
+    Ok(())
 }
 
-fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u64) {
-    assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(fund_fee_rate + amm_config.protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
+fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u64) -> Result<()> {
+    check_fee_share_rates(amm_config.protocol_fee_rate, fund_fee_rate)?;
     amm_config.fund_fee_rate = fund_fee_rate;
+    Ok(())
+}
+
+/// The protocol and fund fees are shares of the trade fee, together at most all of it
+fn check_fee_share_rates(protocol_fee_rate: u64, fund_fee_rate: u64) -> Result<()> {
+    if protocol_fee_rate.saturating_add(fund_fee_rate) > FEE_RATE_DENOMINATOR_VALUE {
+        msg!(
+            "protocol_fee_rate: {} and fund_fee_rate: {} exceed {}",
+            protocol_fee_rate,
+            fund_fee_rate,
+            FEE_RATE_DENOMINATOR_VALUE
+        );
+        return err!(GammaError::InvalidFeeBounds);
+    }
+    Ok(())
 }
 
 fn set_new_protocol_owner(
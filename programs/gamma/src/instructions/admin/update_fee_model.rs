@@ -0,0 +1,30 @@
+use crate::error::GammaError;
+use crate::fees::{FeeModel, FEE_MODEL_SWITCH_DELAY};
+use crate::states::PoolState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFeeModel<'info> {
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Schedules `fee_model` for the pool's swaps after `FEE_MODEL_SWITCH_DELAY`, so traders see
+/// a model change coming. Passing the current model cancels a pending change.
+pub fn update_fee_model(ctx: Context<UpdateFeeModel>, fee_model: FeeModel) -> Result<()> {
+    fee_model.validate()?;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.schedule_fee_model(
+        fee_model,
+        block_timestamp,
+        block_timestamp
+            .checked_add(FEE_MODEL_SWITCH_DELAY)
+            .ok_or(GammaError::MathOverflow)?,
+    )
+}
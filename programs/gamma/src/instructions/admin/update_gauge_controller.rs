@@ -0,0 +1,28 @@
+use crate::error::GammaError;
+use crate::states::{GaugeController, GAUGE_CONTROLLER_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateGaugeController<'info> {
+    /// Only admin can update the emission
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAUGE_CONTROLLER_SEED.as_bytes()],
+        bump = gauge_controller.bump,
+    )]
+    pub gauge_controller: Box<Account<'info, GaugeController>>,
+}
+
+/// The new emission applies to every gauge settled from now on
+pub fn update_gauge_controller(
+    ctx: Context<UpdateGaugeController>,
+    emission_per_epoch: u64,
+) -> Result<()> {
+    ctx.accounts.gauge_controller.emission_per_epoch = emission_per_epoch;
+    Ok(())
+}
@@ -1,4 +1,11 @@
-use crate::{error::GammaError, fees::FEE_RATE_DENOMINATOR_VALUE, states::PoolState};
+use crate::{
+    error::GammaError,
+    fees::FEE_RATE_DENOMINATOR_VALUE,
+    states::{
+        emit_lifecycle_change, PoolState, PoolStatusChangeEvent, LP_FEE_ACCRUAL_EXTENSION,
+        MAX_WITHDRAW_DELAY_SLOTS, REBASING_SHARES_EXTENSION,
+    },
+};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock;
 
@@ -22,6 +29,17 @@ pub fn update_pool(ctx: Context<UpdatePool>, param: u32, value: u64) -> Result<(
         3 => update_max_shared_token0(ctx, value),
         4 => update_max_shared_token1(ctx, value),
         5 => update_open_time(ctx),
+        6 => update_price_floor(ctx, value),
+        7 => update_price_ceiling(ctx, value),
+        8 => enable_lp_fee_accrual(ctx),
+        9 => update_rebasing_shares(ctx, value != 0),
+        10 => update_twap_breaker_window(ctx, value),
+        11 => update_twap_breaker_max_deviation(ctx, value),
+        12 => override_twap_breaker(ctx, value),
+        13 => update_lp_concentration_alert(ctx, value),
+        14 => update_ema_half_life(ctx, value),
+        15 => update_withdraw_delay_slots(ctx, value),
+        16 => update_max_slot_price_impact(ctx, value),
         _ => Err(GammaError::InvalidInput.into()),
     }
 }
@@ -29,7 +47,110 @@ pub fn update_pool(ctx: Context<UpdatePool>, param: u32, value: u64) -> Result<(
 fn update_open_time(ctx: Context<UpdatePool>) -> Result<()> {
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
     let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
+    let lifecycle_before = pool_state.lifecycle(block_timestamp);
     pool_state.open_time = block_timestamp;
+    emit_lifecycle_change(
+        ctx.accounts.pool_state.key(),
+        lifecycle_before,
+        pool_state.lifecycle(block_timestamp),
+    );
+    Ok(())
+}
+
+fn update_price_floor(ctx: Context<UpdatePool>, price_floor_x32: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    if price_floor_x32 != 0 && pool_state.price_ceiling_x32 != 0 {
+        require_gt!(pool_state.price_ceiling_x32, price_floor_x32);
+    }
+    pool_state.price_floor_x32 = price_floor_x32;
+    Ok(())
+}
+
+fn update_price_ceiling(ctx: Context<UpdatePool>, price_ceiling_x32: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    if price_ceiling_x32 != 0 && pool_state.price_floor_x32 != 0 {
+        require_gt!(price_ceiling_x32, pool_state.price_floor_x32);
+    }
+    pool_state.price_ceiling_x32 = price_ceiling_x32;
+    Ok(())
+}
+
+fn enable_lp_fee_accrual(ctx: Context<UpdatePool>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        !pool_state.has_extension(LP_FEE_ACCRUAL_EXTENSION),
+        GammaError::InvalidInput
+    );
+    pool_state.enable_lp_fee_accrual();
+    Ok(())
+}
+
+/// The reserves follow the vault balances from the next swap, deposit or withdrawal on
+fn update_rebasing_shares(ctx: Context<UpdatePool>, enabled: bool) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.set_extension(REBASING_SHARES_EXTENSION, enabled);
+    Ok(())
+}
+
+fn update_twap_breaker_window(ctx: Context<UpdatePool>, window: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.twap_breaker_window = window;
+    Ok(())
+}
+
+fn update_twap_breaker_max_deviation(
+    ctx: Context<UpdatePool>,
+    max_deviation_bps: u64,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.twap_breaker_max_deviation_bps = max_deviation_bps;
+    Ok(())
+}
+
+/// Lets the price move freely for `duration` seconds, e.g. to follow a legitimate repricing.
+/// Zero re-arms the breaker right away.
+fn override_twap_breaker(ctx: Context<UpdatePool>, duration: u64) -> Result<()> {
+    let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.twap_breaker_override_until = block_timestamp
+        .checked_add(duration)
+        .ok_or(GammaError::MathOverflow)?;
+    Ok(())
+}
+
+fn update_lp_concentration_alert(ctx: Context<UpdatePool>, alert_bps: u64) -> Result<()> {
+    require_gte!(10_000, alert_bps, GammaError::InvalidInput);
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.lp_concentration_alert_bps = alert_bps;
+    Ok(())
+}
+
+/// Zero disables the moving average prices, enabling them again starts them over
+fn update_ema_half_life(ctx: Context<UpdatePool>, ema_half_life: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.ema_half_life = ema_half_life;
+    if ema_half_life == 0 {
+        pool_state.ema_updated_at = 0;
+    }
+    Ok(())
+}
+
+fn update_withdraw_delay_slots(ctx: Context<UpdatePool>, withdraw_delay_slots: u64) -> Result<()> {
+    require_gte!(
+        MAX_WITHDRAW_DELAY_SLOTS,
+        withdraw_delay_slots,
+        GammaError::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.withdraw_delay_slots = withdraw_delay_slots;
+    Ok(())
+}
+
+fn update_max_slot_price_impact(ctx: Context<UpdatePool>, max_bps: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.max_slot_price_impact_bps = max_bps;
+    // The next swap starts the slot from the current price
+    pool_state.slot_start = 0;
     Ok(())
 }
 
@@ -62,8 +183,23 @@ fn update_volatility_factor(ctx: Context<UpdatePool>, volatility_factor: u64) ->
 
 fn update_pool_status(ctx: Context<UpdatePool>, status: u8) -> Result<()> {
     require_gte!(255, status);
+    let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
-    pool_state.set_status(status);
+    let lifecycle_before = pool_state.lifecycle(block_timestamp);
+    let old_status = pool_state.status;
+    pool_state.update_status(status, block_timestamp)?;
     pool_state.recent_epoch = Clock::get()?.epoch;
+    emit!(PoolStatusChangeEvent {
+        version: PoolStatusChangeEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        signer: ctx.accounts.authority.key(),
+        old_status,
+        new_status: pool_state.status,
+    });
+    emit_lifecycle_change(
+        ctx.accounts.pool_state.key(),
+        lifecycle_before,
+        pool_state.lifecycle(block_timestamp),
+    );
     Ok(())
 }
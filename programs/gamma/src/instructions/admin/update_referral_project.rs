@@ -0,0 +1,42 @@
+use crate::error::GammaError;
+use crate::external::referral::{update_project, UpdateProjectParams};
+use crate::states::AmmConfig;
+use anchor_lang::prelude::*;
+use referral::program::Referral;
+
+#[derive(Accounts)]
+pub struct UpdateReferralProject<'info> {
+    /// The admin that created the project is its admin in the referral program
+    #[account(address = crate::admin::id() @ GammaError::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// CHECK: The config's project, checked by the referral program
+    #[account(mut, address = amm_config.referral_project @ GammaError::InvalidInput)]
+    pub project: UncheckedAccount<'info>,
+
+    pub referral_program: Program<'info, Referral>,
+}
+
+/// Changes the name or the default share of the config's referral project, `None` keeps the
+/// current value
+pub fn update_referral_project(
+    ctx: Context<UpdateReferralProject>,
+    name: Option<String>,
+    default_share_bps: Option<u16>,
+) -> Result<()> {
+    require!(
+        name.is_some() || default_share_bps.is_some(),
+        GammaError::InvalidInput
+    );
+    update_project(
+        &ctx.accounts.referral_program.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.project.to_account_info(),
+        &UpdateProjectParams {
+            name,
+            default_share_bps,
+        },
+    )
+}
@@ -0,0 +1,32 @@
+use crate::error::GammaError;
+use crate::states::{Solver, SOLVER_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateSolver<'info> {
+    /// Only admin can update a solver
+    #[account(
+        address = crate::admin::id() @ GammaError::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            SOLVER_SEED.as_bytes(),
+            solver.authority.as_ref(),
+        ],
+        bump = solver.bump,
+    )]
+    pub solver: Box<Account<'info, Solver>>,
+}
+
+pub fn update_solver(ctx: Context<UpdateSolver>, active: bool, min_bond: u64) -> Result<()> {
+    let solver = &mut ctx.accounts.solver;
+    if solver.active && !active {
+        solver.deactivated_at = Clock::get()?.unix_timestamp as u64;
+    }
+    solver.active = active;
+    solver.min_bond = min_bond;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::{
+    error::GammaError,
+    states::{Solver, SOLVER_SEED},
+    utils::{get_transfer_fee, transfer_from_pool_vault_to_user, transfer_from_user_to_pool_vault},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct BondSolver<'info> {
+    pub solver_authority: Signer<'info>,
+
+    /// CHECK: pool vault authority, owns the bond vaults
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            SOLVER_SEED.as_bytes(),
+            solver_authority.key().as_ref(),
+        ],
+        bump = solver.bump,
+    )]
+    pub solver: Box<Account<'info, Solver>>,
+
+    #[account(mut, address = solver.bond_vault)]
+    pub bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = solver.bond_mint)]
+    pub bond_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The solver's token account the bond is paid from, or returned to
+    #[account(mut, token::mint = bond_mint)]
+    pub solver_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022
+    pub bond_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn bond_solver(ctx: Context<BondSolver>, amount: u64) -> Result<()> {
+    require_gt!(amount, 0, GammaError::InvalidInput);
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.solver_authority.to_account_info(),
+        ctx.accounts.solver_token_account.to_account_info(),
+        ctx.accounts.bond_vault.to_account_info(),
+        ctx.accounts.bond_mint.to_account_info(),
+        ctx.accounts.bond_token_program.to_account_info(),
+        amount,
+        ctx.accounts.bond_mint.decimals,
+    )?;
+    let bonded = amount
+        .checked_sub(get_transfer_fee(
+            &ctx.accounts.bond_mint.to_account_info(),
+            amount,
+        )?)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let solver = &mut ctx.accounts.solver;
+    solver.bonded_amount = solver
+        .bonded_amount
+        .checked_add(bonded)
+        .ok_or(GammaError::MathOverflow)?;
+    Ok(())
+}
+
+/// Withdraws `amount` of the bond once the solver was deactivated for the unbond period
+pub fn withdraw_solver_bond(ctx: Context<BondSolver>, amount: u64) -> Result<()> {
+    let solver = &mut ctx.accounts.solver;
+    require!(
+        solver.can_unbond(Clock::get()?.unix_timestamp as u64),
+        GammaError::SolverBonded
+    );
+    solver.bonded_amount = solver
+        .bonded_amount
+        .checked_sub(amount)
+        .ok_or(GammaError::InsufficientBond)?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.bond_vault.to_account_info(),
+        ctx.accounts.solver_token_account.to_account_info(),
+        ctx.accounts.bond_mint.to_account_info(),
+        ctx.accounts.bond_token_program.to_account_info(),
+        amount,
+        ctx.accounts.bond_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )
+}
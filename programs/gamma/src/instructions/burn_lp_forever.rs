@@ -0,0 +1,107 @@
+use crate::error::GammaError;
+use crate::states::{
+    LpBurnEvent, LpBurnProof, PoolState, UserPoolLiquidity, LP_BURN_PROOF_SEED,
+    USER_POOL_LIQUIDITY_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct BurnLpForever<'info> {
+    /// Owner of the liquidity the LP is burned from
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    /// Burned LP of the pool, created with its first burn
+    #[account(
+        init_if_needed,
+        seeds = [
+            LP_BURN_PROOF_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = LpBurnProof::LEN,
+    )]
+    pub lp_burn_proof: Box<Account<'info, LpBurnProof>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Burns `lp_token_amount` lp tokens of the owner's liquidity. They stay in the LP supply, so
+/// the reserves backing them are locked in the pool for good, and add up in the pool's
+/// `burned_lp` and `LpBurnProof`.
+pub fn burn_lp_forever(ctx: Context<BurnLpForever>, lp_token_amount: u64) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    require_gte!(
+        user_pool_liquidity.unstaked_lp_tokens(),
+        u128::from(lp_token_amount),
+        GammaError::LpTokensStaked
+    );
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
+    user_pool_liquidity.lp_tokens_owned = user_pool_liquidity
+        .lp_tokens_owned
+        .checked_sub(u128::from(lp_token_amount))
+        .ok_or(GammaError::MathOverflow)?;
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
+
+    if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
+        let mut pool_state_partners = pool_state.partners;
+        if let Some(partner) = pool_state_partners
+            .iter_mut()
+            .find(|p| p.links(user_pool_liquidity_partner))
+        {
+            partner.lp_token_linked_with_partner = partner
+                .lp_token_linked_with_partner
+                .saturating_sub(lp_token_amount);
+        }
+        pool_state.partners = pool_state_partners;
+    }
+
+    pool_state.burned_lp = pool_state
+        .burned_lp
+        .checked_add(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let lp_burn_proof = &mut ctx.accounts.lp_burn_proof;
+    lp_burn_proof.bump = ctx.bumps.lp_burn_proof;
+    lp_burn_proof.pool_state = pool_id;
+    lp_burn_proof.burned_lp_tokens = lp_burn_proof
+        .burned_lp_tokens
+        .checked_add(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    lp_burn_proof.burn_count += 1;
+    lp_burn_proof.last_burn_at = block_timestamp;
+
+    emit!(LpBurnEvent {
+        version: LpBurnEvent::VERSION,
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        lp_tokens: lp_token_amount,
+        total_burned_lp_tokens: lp_burn_proof.burned_lp_tokens,
+        lp_supply: pool_state.lp_supply,
+    });
+    Ok(())
+}
@@ -0,0 +1,336 @@
+use crate::{
+    error::GammaError,
+    instructions::decode_account_info,
+    states::{
+        EmergencyEscrow, EmergencyEscrowEvent, PoolState, Position, UserPoolLiquidity,
+        POOL_LP_MINT_SEED, USER_POOL_LIQUIDITY_SEED,
+    },
+    utils::{get_transfer_fee, token_balance, token_burn, transfer_from_pool_vault_to_user},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClaimEmergencyEscrow<'info> {
+    /// Owner of the liquidity
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority, also owns the escrow vaults
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Account<'info, UserPoolLiquidity>,
+
+    #[account(
+        mut,
+        has_one = pool_state,
+    )]
+    pub emergency_escrow: Box<Account<'info, EmergencyEscrow>>,
+
+    #[account(
+        mut,
+        address = emergency_escrow.token_0_vault
+    )]
+    pub escrow_token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = emergency_escrow.token_1_vault
+    )]
+    pub escrow_token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's token account to receive token_0
+    #[account(
+        mut,
+        token::mint = escrow_token_0_vault.mint,
+        token::authority = owner
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The owner's token account to receive token_1
+    #[account(
+        mut,
+        token::mint = escrow_token_1_vault.mint,
+        token::authority = owner
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0
+    #[account(
+        address = escrow_token_0_vault.mint,
+        mint::token_program = token_0_program,
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1
+    #[account(
+        address = escrow_token_1_vault.mint,
+        mint::token_program = token_1_program,
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub token_0_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub token_1_program: Interface<'info, TokenInterface>,
+}
+
+/// Pays out the owner's pro-rata share of an emergency escrow once the timelock has passed,
+/// giving up all of the owner's LP in the pool: the LP of their liquidity, of the positions they
+/// own, locked or not, and their LP tokens. Staked LP has to be unstaked first, so the staking
+/// rewards stop accruing to LP that is gone.
+///
+/// Remaining accounts: the pool's LP mint, the owner's LP token account and the token 2022
+/// program, the gamma program id taking their place for owners without LP tokens, then the
+/// owner's positions.
+pub fn claim_emergency_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimEmergencyEscrow<'info>>,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    require_gte!(
+        block_timestamp,
+        ctx.accounts.emergency_escrow.unlock_time,
+        GammaError::NotApproved
+    );
+
+    let pool_id = ctx.accounts.pool_state.key();
+    let owner = ctx.accounts.owner.key();
+    let tokenized_lp = burn_lp_tokens(
+        ctx.remaining_accounts,
+        &ctx.accounts.owner.to_account_info(),
+        pool_id,
+    )?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    require_eq!(
+        user_pool_liquidity.extension.lp_tokens_staked,
+        0,
+        GammaError::LpTokensStaked
+    );
+    let liquidity_lp =
+        u64::try_from(user_pool_liquidity.lp_tokens_owned).or(err!(GammaError::MathOverflow))?;
+    let lp_token_amount: u64;
+    let auth_bump: u8;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        user_pool_liquidity.accrue_lp_fees(&pool_state)?;
+        let position_lp = empty_positions(
+            ctx.remaining_accounts.get(3..).unwrap_or_default(),
+            &pool_state,
+            pool_id,
+            owner,
+            user_pool_liquidity,
+        )?;
+        lp_token_amount = liquidity_lp
+            .checked_add(position_lp)
+            .and_then(|amount| amount.checked_add(tokenized_lp))
+            .ok_or(GammaError::MathOverflow)?;
+        require_gt!(lp_token_amount, 0, GammaError::InvalidLpTokenAmount);
+
+        pool_state.lp_supply = pool_state
+            .lp_supply
+            .checked_sub(lp_token_amount)
+            .ok_or(GammaError::MathOverflow)?;
+        pool_state.lp_tokenized = pool_state
+            .lp_tokenized
+            .checked_sub(tokenized_lp)
+            .ok_or(GammaError::MathOverflow)?;
+        // Only the LP of the liquidity is linked with its partner
+        if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
+            let mut pool_state_partners = pool_state.partners;
+            let partner: Option<&mut crate::states::PartnerInfo> = pool_state_partners
+                .iter_mut()
+                .find(|p| p.links(user_pool_liquidity_partner));
+            if let Some(partner) = partner {
+                partner.lp_token_linked_with_partner = partner
+                    .lp_token_linked_with_partner
+                    .saturating_sub(liquidity_lp);
+            }
+            pool_state.partners = pool_state_partners;
+        }
+        auth_bump = pool_state.auth_bump;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+    }
+
+    let emergency_escrow = &mut ctx.accounts.emergency_escrow;
+    let share_of = |amount: u64| -> Result<u64> {
+        u64::try_from(
+            u128::from(amount)
+                .checked_mul(u128::from(lp_token_amount))
+                .ok_or(GammaError::MathOverflow)?
+                .checked_div(u128::from(emergency_escrow.lp_supply))
+                .ok_or(GammaError::MathOverflow)?,
+        )
+        .or(err!(GammaError::MathOverflow))
+    };
+    let amount_0 = share_of(emergency_escrow.token_0_amount)?;
+    let amount_1 = share_of(emergency_escrow.token_1_amount)?;
+
+    emergency_escrow.token_0_amount = emergency_escrow
+        .token_0_amount
+        .checked_sub(amount_0)
+        .ok_or(GammaError::MathOverflow)?;
+    emergency_escrow.token_1_amount = emergency_escrow
+        .token_1_amount
+        .checked_sub(amount_1)
+        .ok_or(GammaError::MathOverflow)?;
+    emergency_escrow.lp_supply = emergency_escrow
+        .lp_supply
+        .checked_sub(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    emergency_escrow.lp_claimed = emergency_escrow
+        .lp_claimed
+        .checked_add(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.lp_tokens_owned = 0;
+    user_pool_liquidity.token_0_withdrawn = user_pool_liquidity
+        .token_0_withdrawn
+        .checked_add(u128::from(amount_0))
+        .ok_or(GammaError::MathOverflow)?;
+    user_pool_liquidity.token_1_withdrawn = user_pool_liquidity
+        .token_1_withdrawn
+        .checked_add(u128::from(amount_1))
+        .ok_or(GammaError::MathOverflow)?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.escrow_token_0_vault.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.token_0_program.to_account_info(),
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.escrow_token_1_vault.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        ctx.accounts.token_1_program.to_account_info(),
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+
+    emit!(EmergencyEscrowEvent {
+        version: EmergencyEscrowEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        escrow: ctx.accounts.emergency_escrow.key(),
+        authority: ctx.accounts.owner.key(),
+        token_0_amount: amount_0
+            .checked_sub(get_transfer_fee(
+                &ctx.accounts.vault_0_mint.to_account_info(),
+                amount_0,
+            )?)
+            .ok_or(GammaError::MathOverflow)?,
+        token_1_amount: amount_1
+            .checked_sub(get_transfer_fee(
+                &ctx.accounts.vault_1_mint.to_account_info(),
+                amount_1,
+            )?)
+            .ok_or(GammaError::MathOverflow)?,
+        lp_amount: lp_token_amount,
+        unlock_time: ctx.accounts.emergency_escrow.unlock_time,
+        change_type: 2,
+    });
+
+    Ok(())
+}
+
+/// Burns all the LP tokens of the owner's LP token account, if the claim passes one. Returns the
+/// LP burned.
+fn burn_lp_tokens<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    owner: &AccountInfo<'info>,
+    pool_id: Pubkey,
+) -> Result<u64> {
+    let (Some(lp_mint), Some(lp_token_account), Some(token_program)) = (
+        decode_account_info(remaining_accounts, 0),
+        decode_account_info(remaining_accounts, 1),
+        decode_account_info(remaining_accounts, 2),
+    ) else {
+        return Ok(0);
+    };
+    let (lp_mint_key, _) = Pubkey::find_program_address(
+        &[POOL_LP_MINT_SEED.as_bytes(), pool_id.as_ref()],
+        &crate::id(),
+    );
+    require_keys_eq!(lp_mint.key(), lp_mint_key, GammaError::InvalidInput);
+    require_keys_eq!(
+        token_program.key(),
+        Token2022::id(),
+        GammaError::InvalidInput
+    );
+
+    let amount = token_balance(&lp_token_account)?;
+    if amount > 0 {
+        token_burn(
+            owner.clone(),
+            token_program,
+            lp_mint,
+            lp_token_account,
+            amount,
+            &[],
+        )?;
+    }
+    Ok(amount)
+}
+
+/// Takes the LP out of the owner's positions, locked or not, and moves their LP fees owed into
+/// the owner's liquidity. Returns the LP the positions held.
+fn empty_positions(
+    position_infos: &[AccountInfo<'_>],
+    pool_state: &PoolState,
+    pool_id: Pubkey,
+    owner: Pubkey,
+    user_pool_liquidity: &mut UserPoolLiquidity,
+) -> Result<u64> {
+    let mut lp_tokens = 0u64;
+    for position_info in position_infos {
+        require!(position_info.is_writable, GammaError::InvalidInput);
+        // A position passed twice reads as empty the second time
+        let mut position = Position::load_checked(position_info, pool_id)?;
+        require_keys_eq!(position.owner, owner, GammaError::InvalidOwner);
+        position.accrue_lp_fees(pool_state)?;
+        user_pool_liquidity.extension.lp_fees_owed_token_0 = user_pool_liquidity
+            .extension
+            .lp_fees_owed_token_0
+            .checked_add(position.lp_fees_owed_token_0)
+            .ok_or(GammaError::MathOverflow)?;
+        user_pool_liquidity.extension.lp_fees_owed_token_1 = user_pool_liquidity
+            .extension
+            .lp_fees_owed_token_1
+            .checked_add(position.lp_fees_owed_token_1)
+            .ok_or(GammaError::MathOverflow)?;
+        lp_tokens = lp_tokens
+            .checked_add(position.lp_tokens)
+            .ok_or(GammaError::MathOverflow)?;
+
+        position.lp_tokens = 0;
+        position.vesting_lp_tokens = 0;
+        position.lp_fees_owed_token_0 = 0;
+        position.lp_fees_owed_token_1 = 0;
+        position.try_serialize(&mut &mut position_info.try_borrow_mut_data()?[..])?;
+    }
+    Ok(lp_tokens)
+}
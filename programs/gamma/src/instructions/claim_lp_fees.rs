@@ -0,0 +1,266 @@
+use crate::{
+    error::GammaError,
+    states::{
+        AmmConfig, LpFeesClaimEvent, PoolState, ReferralPaidEvent, UserPoolLiquidity,
+        USER_POOL_LIQUIDITY_SEED,
+    },
+    utils::transfer_from_pool_vault_to_user,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+use referral::REFERRAL_ATA_SEED;
+
+#[derive(Accounts)]
+pub struct ClaimLpFees<'info> {
+    /// Owner of the position
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The referrer's share of the claim is set by the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Pool state stores the LP fees held for positions
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The address that receives the token_0 fees
+    #[account(mut)]
+    pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that receives the token_1 fees
+    #[account(mut)]
+    pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+/// Share of `amount` going to the referrer, who only gets paid once their referral token
+/// account for the mint exists
+fn referral_share(
+    referral_token_account: Option<&AccountInfo<'_>>,
+    referrer: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+    share_bps: u64,
+) -> Result<u64> {
+    let referral_token_account = referral_token_account.ok_or(GammaError::InvalidInput)?;
+    let expected_key = Pubkey::find_program_address(
+        &[REFERRAL_ATA_SEED, referrer.as_ref(), mint.as_ref()],
+        &referral::ID,
+    )
+    .0;
+    require_keys_eq!(referral_token_account.key(), expected_key);
+    if **referral_token_account.try_borrow_lamports()? == 0 {
+        return Ok(0);
+    }
+    u64::try_from(u128::from(amount) * u128::from(share_bps) / 10_000)
+        .or(err!(GammaError::MathOverflow))
+}
+
+/// Positions with a referrer pass the referrer's token_0 and token_1 referral token accounts
+/// as remaining accounts
+pub fn claim_lp_fees<'info>(ctx: Context<'_, '_, '_, 'info, ClaimLpFees<'info>>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    let auth_bump: u8;
+    let (amount_0, amount_1) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        user_pool_liquidity.accrue_lp_fees(&pool_state)?;
+        let amount_0 = user_pool_liquidity.extension.lp_fees_owed_token_0;
+        let amount_1 = user_pool_liquidity.extension.lp_fees_owed_token_1;
+        pool_state.lp_fees_token_0 = pool_state
+            .lp_fees_token_0
+            .checked_sub(amount_0)
+            .ok_or(GammaError::MathOverflow)?;
+        pool_state.lp_fees_token_1 = pool_state
+            .lp_fees_token_1
+            .checked_sub(amount_1)
+            .ok_or(GammaError::MathOverflow)?;
+        auth_bump = pool_state.auth_bump;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+        (amount_0, amount_1)
+    };
+    require!(amount_0 > 0 || amount_1 > 0, GammaError::InvalidInput);
+
+    user_pool_liquidity.extension.lp_fees_owed_token_0 = 0;
+    user_pool_liquidity.extension.lp_fees_owed_token_1 = 0;
+    user_pool_liquidity.extension.lp_fees_claimed_token_0 = user_pool_liquidity
+        .extension
+        .lp_fees_claimed_token_0
+        .checked_add(amount_0)
+        .ok_or(GammaError::MathOverflow)?;
+    user_pool_liquidity.extension.lp_fees_claimed_token_1 = user_pool_liquidity
+        .extension
+        .lp_fees_claimed_token_1
+        .checked_add(amount_1)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let share_bps = if ctx.accounts.amm_config.disable_referrals {
+        0
+    } else {
+        ctx.accounts.amm_config.lp_referral_share_bps
+    };
+    let (referrer, referral_amount_0, referral_amount_1) =
+        match user_pool_liquidity.extension.referrer {
+            Some(referrer) if share_bps > 0 => (
+                referrer,
+                referral_share(
+                    ctx.remaining_accounts.first(),
+                    referrer,
+                    ctx.accounts.vault_0_mint.key(),
+                    amount_0,
+                    share_bps,
+                )?,
+                referral_share(
+                    ctx.remaining_accounts.get(1),
+                    referrer,
+                    ctx.accounts.vault_1_mint.key(),
+                    amount_1,
+                    share_bps,
+                )?,
+            ),
+            _ => (Pubkey::default(), 0, 0),
+        };
+
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]];
+    let token_0_program =
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+    let token_1_program =
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.recipient_token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        token_0_program.clone(),
+        amount_0 - referral_amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        signer_seeds,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.recipient_token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        token_1_program.clone(),
+        amount_1 - referral_amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        signer_seeds,
+    )?;
+
+    if referral_amount_0 > 0 {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_0_vault.to_account_info(),
+            ctx.remaining_accounts[0].to_account_info(),
+            ctx.accounts.vault_0_mint.to_account_info(),
+            token_0_program,
+            referral_amount_0,
+            ctx.accounts.vault_0_mint.decimals,
+            signer_seeds,
+        )?;
+        emit!(ReferralPaidEvent {
+            version: ReferralPaidEvent::VERSION,
+            pool_id,
+            referrer,
+            project: ctx.accounts.amm_config.referral_project,
+            mint: ctx.accounts.vault_0_mint.key(),
+            amount: referral_amount_0,
+            trade_amount: amount_0,
+        });
+    }
+    if referral_amount_1 > 0 {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_1_vault.to_account_info(),
+            ctx.remaining_accounts[1].to_account_info(),
+            ctx.accounts.vault_1_mint.to_account_info(),
+            token_1_program,
+            referral_amount_1,
+            ctx.accounts.vault_1_mint.decimals,
+            signer_seeds,
+        )?;
+        emit!(ReferralPaidEvent {
+            version: ReferralPaidEvent::VERSION,
+            pool_id,
+            referrer,
+            project: ctx.accounts.amm_config.referral_project,
+            mint: ctx.accounts.vault_1_mint.key(),
+            amount: referral_amount_1,
+            trade_amount: amount_1,
+        });
+    }
+
+    emit!(LpFeesClaimEvent {
+        version: LpFeesClaimEvent::VERSION,
+        pool_id,
+        owner: ctx.accounts.owner.key(),
+        token_0_amount: amount_0 - referral_amount_0,
+        token_1_amount: amount_1 - referral_amount_1,
+        referrer,
+        referral_token_0_amount: referral_amount_0,
+        referral_token_1_amount: referral_amount_1,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use crate::error::GammaError;
+use crate::states::{
+    PoolRewards, RewardClaimEvent, UserPoolLiquidity, UserStake, POOL_REWARDS_SEED,
+    USER_POOL_LIQUIDITY_SEED, USER_STAKE_SEED,
+};
+use crate::utils::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(reward_index: u8)]
+pub struct ClaimRewards<'info> {
+    /// Owner of the position
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority, owns the reward vaults too
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_REWARDS_SEED.as_bytes(),
+            pool_rewards.pool_state.as_ref(),
+        ],
+        bump = pool_rewards.bump,
+    )]
+    pub pool_rewards: Box<Account<'info, PoolRewards>>,
+
+    #[account(
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_rewards.pool_state.as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_STAKE_SEED.as_bytes(),
+            pool_rewards.pool_state.as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Box<Account<'info, UserStake>>,
+
+    /// The vault of the reward at `reward_index`
+    #[account(
+        mut,
+        constraint = pool_rewards
+            .rewards()
+            .get(usize::from(reward_index))
+            .is_some_and(|reward| reward.reward_vault == reward_vault.key())
+            @ GammaError::InvalidInput,
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = reward_vault.mint,
+        mint::token_program = reward_token_program,
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The address that receives the rewards
+    #[account(
+        mut,
+        token::mint = reward_mint,
+    )]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pays out the rewards of `reward_index` owed to the stake. Rewards the vault can't cover yet
+/// stay owed.
+pub fn claim_rewards(ctx: Context<ClaimRewards>, reward_index: u8) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_rewards = &mut ctx.accounts.pool_rewards;
+    pool_rewards.update(block_timestamp)?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    user_stake.accrue(
+        pool_rewards,
+        ctx.accounts.user_pool_liquidity.extension.lp_tokens_staked,
+    )?;
+
+    let index = usize::from(reward_index);
+    let amount = std::cmp::min(
+        user_stake.rewards_owed[index],
+        ctx.accounts.reward_vault.amount,
+    );
+    user_stake.rewards_owed[index] -= amount;
+
+    emit!(RewardClaimEvent {
+        version: RewardClaimEvent::VERSION,
+        pool_id: pool_rewards.pool_state,
+        owner: ctx.accounts.owner.key(),
+        reward_mint: ctx.accounts.reward_mint.key(),
+        amount,
+    });
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.reward_vault.to_account_info(),
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.reward_mint.to_account_info(),
+        ctx.accounts.reward_token_program.to_account_info(),
+        amount,
+        ctx.accounts.reward_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )
+}
@@ -0,0 +1,137 @@
+use crate::{
+    error::GammaError,
+    states::{PoolState, SegmenterKickback},
+    utils::transfer_from_pool_vault_to_user,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ClaimSegmenterKickback<'info> {
+    /// The segmenter the kickback was accrued to
+    pub segmenter: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state stores the total kickback owed to segmenters
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        has_one = segmenter @ GammaError::InvalidOwner,
+        has_one = pool_state,
+    )]
+    pub segmenter_kickback: Box<Account<'info, SegmenterKickback>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The address that receives the token_0 kickback
+    #[account(mut)]
+    pub recipient_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that receives the token_1 kickback
+    #[account(mut)]
+    pub recipient_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn claim_segmenter_kickback(ctx: Context<ClaimSegmenterKickback>) -> Result<()> {
+    let segmenter_kickback = &mut ctx.accounts.segmenter_kickback;
+    let amount_0 = segmenter_kickback.token_0_accrued;
+    let amount_1 = segmenter_kickback.token_1_accrued;
+    let auth_bump: u8;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        pool_state.segmenter_kickbacks_token_0 = pool_state
+            .segmenter_kickbacks_token_0
+            .checked_sub(amount_0)
+            .ok_or(GammaError::MathOverflow)?;
+        pool_state.segmenter_kickbacks_token_1 = pool_state
+            .segmenter_kickbacks_token_1
+            .checked_sub(amount_1)
+            .ok_or(GammaError::MathOverflow)?;
+        auth_bump = pool_state.auth_bump;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+    }
+
+    segmenter_kickback.token_0_accrued = 0;
+    segmenter_kickback.token_1_accrued = 0;
+    segmenter_kickback.token_0_claimed = segmenter_kickback
+        .token_0_claimed
+        .checked_add(amount_0)
+        .ok_or(GammaError::MathOverflow)?;
+    segmenter_kickback.token_1_claimed = segmenter_kickback
+        .token_1_claimed
+        .checked_add(amount_1)
+        .ok_or(GammaError::MathOverflow)?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.recipient_token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.recipient_token_1_account.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+
+    Ok(())
+}
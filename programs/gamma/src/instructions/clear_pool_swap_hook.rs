@@ -0,0 +1,37 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, PoolSwapHook, POOL_SWAP_HOOK_SEED, SWAP_HOOK_EXTENSION};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClearPoolSwapHook<'info> {
+    /// The admin or the pool creator, gets the rent of the hook account back
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_SWAP_HOOK_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = pool_swap_hook.bump,
+        close = authority,
+    )]
+    pub pool_swap_hook: Box<Account<'info, PoolSwapHook>>,
+}
+
+/// Swaps of the pool stop calling its hook. The hook program doesn't have to be allowlisted
+/// anymore, so a misbehaving one can't hold the pool's swaps hostage
+pub fn clear_pool_swap_hook(ctx: Context<ClearPoolSwapHook>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == crate::admin::id() || authority == pool_state.pool_creator,
+        GammaError::InvalidOwner
+    );
+    pool_state.set_extension(SWAP_HOOK_EXTENSION, false);
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::{
+    error::GammaError,
+    instructions::LOCKED_LP_AMOUNT,
+    states::{
+        emit_lifecycle_change, ObservationState, PairIndex, PoolLifecycle, PoolState,
+        PAIR_INDEX_SEED,
+    },
+    utils::{harvest_withheld_fees, transfer_from_pool_vault_to_user},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = pool_creator,
+        has_one = token_0_vault,
+        has_one = token_1_vault,
+        close = pool_creator,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: paid the rent of the pool accounts, gets it back
+    #[account(mut)]
+    pub pool_creator: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = pool_state.load()?.observation_key,
+        close = pool_creator,
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    #[account(mut)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault, receives the transfer fees withheld in the vault
+    #[account(
+        mut,
+        address = token_0_vault.mint,
+        mint::token_program = token_0_program,
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault, receives the transfer fees withheld in the vault
+    #[account(
+        mut,
+        address = token_1_vault.mint,
+        mint::token_program = token_1_program,
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The creator's token_0 account, receives the dust left in the vault
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+        token::authority = pool_creator,
+    )]
+    pub creator_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The creator's token_1 account, receives the dust left in the vault
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+        token::authority = pool_creator,
+    )]
+    pub creator_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Index of the pool's mint pair, the pool is taken out of it. Pools created before pair
+    /// indexes existed may not have one.
+    #[account(
+        mut,
+        seeds = [
+            PAIR_INDEX_SEED.as_bytes(),
+            vault_0_mint.key().as_ref(),
+            vault_1_mint.key().as_ref(),
+        ],
+        bump = pair_index.bump,
+    )]
+    pub pair_index: Option<Box<Account<'info, PairIndex>>>,
+
+    /// Spl token program or token program 2022
+    pub token_0_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub token_1_program: Interface<'info, TokenInterface>,
+}
+
+/// Permissionless once every LP withdrew and nothing is owed from the vaults anymore. Only the LP
+/// locked at pool creation may be left, the dust it holds in the vaults goes to the creator along
+/// with the rent.
+pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let (auth_bump, lifecycle) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        require_gte!(
+            LOCKED_LP_AMOUNT,
+            pool_state.lp_supply,
+            GammaError::PoolNotEmpty
+        );
+        for token_0 in [true, false] {
+            let owed = pool_state
+                .vault_accounted_amount(token_0)
+                .ok_or(GammaError::MathOverflow)?;
+            let reserve = if token_0 {
+                pool_state.token_0_vault_amount
+            } else {
+                pool_state.token_1_vault_amount
+            };
+            require_eq!(owed, reserve, GammaError::PoolNotEmpty);
+        }
+        require!(
+            pool_state.token_0_amount_in_kamino == 0 && pool_state.token_1_amount_in_kamino == 0,
+            GammaError::PoolNotEmpty
+        );
+        (pool_state.auth_bump, pool_state.lifecycle(block_timestamp))
+    };
+    let signer_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]];
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.creator_token_0.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.token_0_program.to_account_info(),
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.vault_0_mint.decimals,
+        signer_seeds,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.creator_token_1.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        ctx.accounts.token_1_program.to_account_info(),
+        ctx.accounts.token_1_vault.amount,
+        ctx.accounts.vault_1_mint.decimals,
+        signer_seeds,
+    )?;
+
+    // Token 2022 accounts still holding withheld transfer fees can't be closed
+    harvest_withheld_fees(
+        ctx.accounts.vault_0_mint.to_account_info(),
+        ctx.accounts.token_0_program.to_account_info(),
+        vec![ctx.accounts.token_0_vault.to_account_info()],
+    )?;
+    harvest_withheld_fees(
+        ctx.accounts.vault_1_mint.to_account_info(),
+        ctx.accounts.token_1_program.to_account_info(),
+        vec![ctx.accounts.token_1_vault.to_account_info()],
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_0_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.token_0_vault.to_account_info(),
+            destination: ctx.accounts.pool_creator.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_1_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.token_1_vault.to_account_info(),
+            destination: ctx.accounts.pool_creator.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    if let Some(pair_index) = ctx.accounts.pair_index.as_mut() {
+        pair_index.remove_pool(ctx.accounts.pool_state.key());
+    }
+
+    emit_lifecycle_change(
+        ctx.accounts.pool_state.key(),
+        lifecycle,
+        PoolLifecycle::Closed,
+    );
+    Ok(())
+}
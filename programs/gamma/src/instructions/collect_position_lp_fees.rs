@@ -0,0 +1,55 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, Position, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CollectPositionLpFees<'info> {
+    /// Owner of the position
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The owner's liquidity, the LP fees of the position move into it
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    #[account(
+        mut,
+        has_one = owner @ GammaError::InvalidOwner,
+        has_one = pool_state,
+    )]
+    pub position: Box<Account<'info, Position>>,
+}
+
+/// Moves the LP fees accrued to a position into the owner's liquidity, where `claim_lp_fees`
+/// pays them out, without closing the position. The LP stays in the position, locked or not,
+/// which keeps the fees of positions that never unlock, like the one of a graduated bonding
+/// curve, claimable.
+pub fn collect_position_lp_fees(ctx: Context<CollectPositionLpFees>) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let position = &mut ctx.accounts.position;
+    position.accrue_lp_fees(&pool_state)?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.extension.lp_fees_owed_token_0 = user_pool_liquidity
+        .extension
+        .lp_fees_owed_token_0
+        .checked_add(position.lp_fees_owed_token_0)
+        .ok_or(GammaError::MathOverflow)?;
+    user_pool_liquidity.extension.lp_fees_owed_token_1 = user_pool_liquidity
+        .extension
+        .lp_fees_owed_token_1
+        .checked_add(position.lp_fees_owed_token_1)
+        .ok_or(GammaError::MathOverflow)?;
+    position.lp_fees_owed_token_0 = 0;
+    position.lp_fees_owed_token_1 = 0;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::error::GammaError;
+use crate::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::states::{
+    AmmConfig, FeeApr, FeeAprSample, PoolState, FEE_APR_SEED, FEE_APR_WINDOW_24H,
+    FEE_APR_WINDOW_7D,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CrankFeeApr<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Its fee rates decide the LP share of the trade fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            FEE_APR_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub fee_apr: AccountLoader<'info, FeeApr>,
+}
+
+pub fn crank_fee_apr(ctx: Context<CrankFeeApr>) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let mut fee_apr = ctx.accounts.fee_apr.load_mut()?;
+
+    let current = FeeAprSample {
+        block_timestamp,
+        cumulative_trade_fees_token_0: pool_state.cumulative_trade_fees_token_0,
+        cumulative_trade_fees_token_1: pool_state.cumulative_trade_fees_token_1,
+    };
+    let (_, token_1_price_x32) = pool_state.token_price_x32()?;
+    let tvl_token_0 = u128::from(pool_state.token_1_vault_amount)
+        .checked_mul(token_1_price_x32)
+        .ok_or(GammaError::MathOverflow)?
+        .checked_shr(32)
+        .ok_or(GammaError::MathOverflow)?
+        .checked_add(u128::from(pool_state.token_0_vault_amount))
+        .ok_or(GammaError::MathOverflow)?;
+    let amm_config = &ctx.accounts.amm_config;
+    let lp_fee_share = FEE_RATE_DENOMINATOR_VALUE
+        .saturating_sub(amm_config.protocol_fee_rate)
+        .saturating_sub(amm_config.fund_fee_rate);
+
+    fee_apr.apr_24h_bps = fee_apr.apr_bps(
+        block_timestamp,
+        FEE_APR_WINDOW_24H,
+        &current,
+        token_1_price_x32,
+        tvl_token_0,
+        lp_fee_share,
+    )?;
+    fee_apr.apr_7d_bps = fee_apr.apr_bps(
+        block_timestamp,
+        FEE_APR_WINDOW_7D,
+        &current,
+        token_1_price_x32,
+        tvl_token_0,
+        lp_fee_share,
+    )?;
+    fee_apr.updated_at = block_timestamp;
+    fee_apr.sample(
+        block_timestamp,
+        current.cumulative_trade_fees_token_0,
+        current.cumulative_trade_fees_token_1,
+    );
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use crate::error::GammaError;
+use crate::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::states::{FeeReport, FeeReportEvent, PoolState, FEE_REPORT_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CrankFeeReport<'info> {
+    /// Anyone can crank the report, pays for the report account on the first crank
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            FEE_REPORT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = FeeReport::LEN,
+    )]
+    pub fee_report: Box<Account<'info, FeeReport>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn crank_fee_report(ctx: Context<CrankFeeReport>) -> Result<()> {
+    let clock = Clock::get()?;
+    let fee_report = &mut ctx.accounts.fee_report;
+    // A fresh report account has epoch zero, so the first report covers the pool's whole history
+    require_gt!(
+        clock.epoch,
+        fee_report.last_report_epoch,
+        GammaError::FeeReportNotDue
+    );
+
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let trade_fees_token_0 = pool_state
+        .cumulative_trade_fees_token_0
+        .checked_sub(fee_report.cumulative_trade_fees_token_0)
+        .ok_or(GammaError::MathOverflow)?;
+    let trade_fees_token_1 = pool_state
+        .cumulative_trade_fees_token_1
+        .checked_sub(fee_report.cumulative_trade_fees_token_1)
+        .ok_or(GammaError::MathOverflow)?;
+    let volume_token_0 = pool_state
+        .cumulative_volume_token_0
+        .checked_sub(fee_report.cumulative_volume_token_0)
+        .ok_or(GammaError::MathOverflow)?;
+    let volume_token_1 = pool_state
+        .cumulative_volume_token_1
+        .checked_sub(fee_report.cumulative_volume_token_1)
+        .ok_or(GammaError::MathOverflow)?;
+
+    // The buckets are what swaps split off at their own rates, not today's config rates
+    let protocol_fees_token_0 = pool_state
+        .cumulative_protocol_fees_token_0
+        .checked_sub(fee_report.cumulative_protocol_fees_token_0)
+        .ok_or(GammaError::MathOverflow)?;
+    let protocol_fees_token_1 = pool_state
+        .cumulative_protocol_fees_token_1
+        .checked_sub(fee_report.cumulative_protocol_fees_token_1)
+        .ok_or(GammaError::MathOverflow)?;
+    let fund_fees_token_0 = pool_state
+        .cumulative_fund_fees_token_0
+        .checked_sub(fee_report.cumulative_fund_fees_token_0)
+        .ok_or(GammaError::MathOverflow)?;
+    let fund_fees_token_1 = pool_state
+        .cumulative_fund_fees_token_1
+        .checked_sub(fee_report.cumulative_fund_fees_token_1)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let average_fee_rate = |fees: u128, volume: u128| -> Result<u64> {
+        if volume == 0 {
+            return Ok(0);
+        }
+        u64::try_from(
+            fees.checked_mul(u128::from(FEE_RATE_DENOMINATOR_VALUE))
+                .ok_or(GammaError::MathOverflow)?
+                / volume,
+        )
+        .or(err!(GammaError::MathOverflow))
+    };
+
+    let end_timestamp = clock.unix_timestamp as u64;
+    emit!(FeeReportEvent {
+        version: FeeReportEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        epoch: clock.epoch,
+        start_timestamp: fee_report.last_report_timestamp,
+        end_timestamp,
+        trade_fees_token_0,
+        trade_fees_token_1,
+        protocol_fees_token_0,
+        protocol_fees_token_1,
+        fund_fees_token_0,
+        fund_fees_token_1,
+        lp_fees_token_0: trade_fees_token_0
+            .saturating_sub(protocol_fees_token_0)
+            .saturating_sub(fund_fees_token_0),
+        lp_fees_token_1: trade_fees_token_1
+            .saturating_sub(protocol_fees_token_1)
+            .saturating_sub(fund_fees_token_1),
+        volume_token_0,
+        volume_token_1,
+        average_dynamic_fee_rate_token_0: average_fee_rate(trade_fees_token_0, volume_token_0)?,
+        average_dynamic_fee_rate_token_1: average_fee_rate(trade_fees_token_1, volume_token_1)?,
+    });
+
+    fee_report.bump = ctx.bumps.fee_report;
+    fee_report.pool_state = ctx.accounts.pool_state.key();
+    fee_report.last_report_epoch = clock.epoch;
+    fee_report.last_report_timestamp = end_timestamp;
+    fee_report.cumulative_trade_fees_token_0 = pool_state.cumulative_trade_fees_token_0;
+    fee_report.cumulative_trade_fees_token_1 = pool_state.cumulative_trade_fees_token_1;
+    fee_report.cumulative_volume_token_0 = pool_state.cumulative_volume_token_0;
+    fee_report.cumulative_volume_token_1 = pool_state.cumulative_volume_token_1;
+    fee_report.cumulative_protocol_fees_token_0 = pool_state.cumulative_protocol_fees_token_0;
+    fee_report.cumulative_protocol_fees_token_1 = pool_state.cumulative_protocol_fees_token_1;
+    fee_report.cumulative_fund_fees_token_0 = pool_state.cumulative_fund_fees_token_0;
+    fee_report.cumulative_fund_fees_token_1 = pool_state.cumulative_fund_fees_token_1;
+
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use std::ops::Deref;
+
+use crate::{
+    error::GammaError,
+    states::{AmmConfig, BondingCurve, BONDING_CURVE_SEED, BONDING_CURVE_VAULT_SEED},
+    utils::{
+        check_mint_safety, create_token_account, is_supported_mint,
+        transfer_from_user_to_pool_vault,
+    },
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+use anchor_spl::{
+    token::Token,
+    token_2022::spl_token_2022,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct CreateBondingCurve<'info> {
+    /// Creator of the launch, deposits the whole supply of the token, for sale and for the pool
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Config of the pool the curve graduates into
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: pda authority of the pools, owns the vaults of the curve
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            BONDING_CURVE_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            token_mint.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        payer = creator,
+        space = BondingCurve::LEN,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    /// The token launched, no more of it can be minted or frozen after the launch
+    #[account(
+        constraint = token_mint.key() != quote_mint.key() @ GammaError::InvalidInput,
+        constraint = token_mint.mint_authority.is_none() @ GammaError::NotSupportMint,
+        constraint = token_mint.freeze_authority.is_none() @ GammaError::NotSupportMint,
+        mint::token_program = token_mint_program,
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token the launch raises, e.g. wrapped SOL or USDC
+    #[account(
+        mint::token_program = quote_mint_program,
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = creator,
+    )]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: token vault of the curve
+    #[account(
+        mut,
+        seeds = [
+            BONDING_CURVE_VAULT_SEED.as_bytes(),
+            bonding_curve.key().as_ref(),
+            token_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_vault: UncheckedAccount<'info>,
+
+    /// CHECK: quote vault of the curve
+    #[account(
+        mut,
+        seeds = [
+            BONDING_CURVE_VAULT_SEED.as_bytes(),
+            bonding_curve.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub quote_vault: UncheckedAccount<'info>,
+
+    /// create pool fee account, the launch pays for the pool it graduates into
+    #[account(
+        mut,
+        address = crate::create_pool_fee_reveiver::id(),
+    )]
+    pub create_pool_fee: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Program to sync the create pool fee account
+    pub token_program: Program<'info, Token>,
+    /// Spl token program or token program 2022 of the launched token
+    pub token_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022 of the quote token
+    pub quote_mint_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens a launch of the whole supply of the token. Part of it is sold along a constant product
+/// curve on the virtual reserves, the rest is put aside so that once `graduation_quote_amount`
+/// is raised, the raised quote, the unsold tokens and the tokens put aside seed the pool at the
+/// curve's last price, see `BondingCurve::sale_token_amount`.
+pub fn create_bonding_curve(
+    ctx: Context<CreateBondingCurve>,
+    virtual_token_reserve: u64,
+    virtual_quote_reserve: u64,
+    graduation_quote_amount: u64,
+    max_trade_fee_rate: u64,
+    volatility_factor: u64,
+) -> Result<()> {
+    if !(is_supported_mint(&ctx.accounts.token_mint)?
+        && is_supported_mint(&ctx.accounts.quote_mint)?)
+    {
+        return err!(GammaError::NotSupportMint);
+    }
+    check_mint_safety(&ctx.accounts.token_mint, &ctx.accounts.amm_config)?;
+    check_mint_safety(&ctx.accounts.quote_mint, &ctx.accounts.amm_config)?;
+    if ctx.accounts.amm_config.disable_create_pool {
+        return err!(GammaError::NotApproved);
+    }
+    // Without a virtual quote reserve the first buy would take every token for sale
+    require_gt!(virtual_quote_reserve, 0, GammaError::InvalidInput);
+    require_gt!(graduation_quote_amount, 0, GammaError::InvalidInput);
+    let supply = ctx.accounts.token_mint.supply;
+    let sale_token_amount = BondingCurve::sale_token_amount(
+        supply,
+        virtual_token_reserve,
+        virtual_quote_reserve,
+        graduation_quote_amount,
+    )?;
+    let pool_token_amount = supply - sale_token_amount;
+    require_gt!(pool_token_amount, 0, GammaError::InvalidInput);
+
+    let bonding_curve_key = ctx.accounts.bonding_curve.key();
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.token_vault.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_mint_program.to_account_info(),
+        &[&[
+            BONDING_CURVE_VAULT_SEED.as_bytes(),
+            bonding_curve_key.as_ref(),
+            ctx.accounts.token_mint.key().as_ref(),
+            &[ctx.bumps.token_vault][..],
+        ][..]],
+    )?;
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.quote_vault.to_account_info(),
+        &ctx.accounts.quote_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.quote_mint_program.to_account_info(),
+        &[&[
+            BONDING_CURVE_VAULT_SEED.as_bytes(),
+            bonding_curve_key.as_ref(),
+            ctx.accounts.quote_mint.key().as_ref(),
+            &[ctx.bumps.quote_vault][..],
+        ][..]],
+    )?;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.creator_token_account.to_account_info(),
+        ctx.accounts.token_vault.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_mint_program.to_account_info(),
+        supply,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    let token_vault_amount =
+        spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+            ctx.accounts
+                .token_vault
+                .to_account_info()
+                .try_borrow_data()?
+                .deref(),
+        )?
+        .base
+        .amount;
+
+    // Charge the fee to create a pool
+    if ctx.accounts.amm_config.create_pool_fee != 0 {
+        invoke(
+            &system_instruction::transfer(
+                ctx.accounts.creator.key,
+                &ctx.accounts.create_pool_fee.key(),
+                u64::from(ctx.accounts.amm_config.create_pool_fee),
+            ),
+            &[
+                ctx.accounts.creator.to_account_info(),
+                ctx.accounts.create_pool_fee.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+        invoke(
+            &spl_token::instruction::sync_native(
+                ctx.accounts.token_program.key,
+                &ctx.accounts.create_pool_fee.key(),
+            )?,
+            &[
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.create_pool_fee.to_account_info(),
+            ],
+        )?;
+    }
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.bump = ctx.bumps.bonding_curve;
+    bonding_curve.creator = ctx.accounts.creator.key();
+    bonding_curve.amm_config = ctx.accounts.amm_config.key();
+    bonding_curve.token_mint = ctx.accounts.token_mint.key();
+    bonding_curve.quote_mint = ctx.accounts.quote_mint.key();
+    bonding_curve.token_vault = ctx.accounts.token_vault.key();
+    bonding_curve.quote_vault = ctx.accounts.quote_vault.key();
+    bonding_curve.virtual_token_reserve = virtual_token_reserve;
+    bonding_curve.virtual_quote_reserve = virtual_quote_reserve;
+    // Transfer fees of the launched token come out of the tokens for sale
+    bonding_curve.token_reserve = token_vault_amount
+        .checked_sub(pool_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    bonding_curve.quote_reserve = 0;
+    bonding_curve.graduation_quote_amount = graduation_quote_amount;
+    bonding_curve.pool_token_amount = pool_token_amount;
+    bonding_curve.max_trade_fee_rate = max_trade_fee_rate;
+    bonding_curve.volatility_factor = volatility_factor;
+    bonding_curve.pool_state = Pubkey::default();
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::states::{Gauge, PoolState, GAUGE_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CreateGauge<'info> {
+    /// Anyone can create the gauge of a pool
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [
+            GAUGE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = Gauge::LEN,
+    )]
+    pub gauge: Box<Account<'info, Gauge>>,
+
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_gauge(ctx: Context<CreateGauge>) -> Result<()> {
+    ctx.accounts
+        .gauge
+        .initialize(ctx.bumps.gauge, ctx.accounts.pool_state.key());
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, POOL_LP_MINT_SEED};
+use crate::utils::fund_token_metadata;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::TokenMetadata, token_metadata_initialize, Mint, Token2022,
+    TokenMetadataInitialize,
+};
+
+/// Decimals of the pool LP mints
+pub const LP_MINT_DECIMALS: u8 = 9;
+
+#[derive(Accounts)]
+pub struct CreateLpMint<'info> {
+    /// The admin or the pool creator, pays for the mint
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority, mints the LP and updates its metadata
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Token-2022 LP mint of the pool, its metadata pointer points at itself
+    #[account(
+        init,
+        seeds = [
+            POOL_LP_MINT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        mint::decimals = LP_MINT_DECIMALS,
+        mint::authority = pool_authority,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = pool_authority,
+        extensions::metadata_pointer::metadata_address = lp_mint,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Program<'info, Token2022>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the pool's LP mint with its name "Gamma LP <SYM0>-<SYM1>" and symbol "<SYM0>-<SYM1>"
+/// written on-chain, so wallets can show the tokens `tokenize_lp` mints
+pub fn create_lp_mint(
+    ctx: Context<CreateLpMint>,
+    token_0_symbol: String,
+    token_1_symbol: String,
+    uri: String,
+) -> Result<()> {
+    let auth_bump = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == crate::admin::id() || authority == pool_state.pool_creator,
+            GammaError::InvalidOwner
+        );
+        pool_state.auth_bump
+    };
+
+    let symbol = format!("{}-{}", token_0_symbol, token_1_symbol);
+    let name = format!("Gamma LP {}", symbol);
+    let lp_mint = ctx.accounts.lp_mint.to_account_info();
+    fund_token_metadata(
+        ctx.accounts.authority.to_account_info(),
+        lp_mint.clone(),
+        ctx.accounts.system_program.to_account_info(),
+        &TokenMetadata {
+            update_authority: OptionalNonZeroPubkey(ctx.accounts.pool_authority.key()),
+            mint: lp_mint.key(),
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            additional_metadata: vec![],
+        },
+    )?;
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TokenMetadataInitialize {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                metadata: lp_mint.clone(),
+                update_authority: ctx.accounts.pool_authority.to_account_info(),
+                mint_authority: ctx.accounts.pool_authority.to_account_info(),
+                mint: lp_mint,
+            },
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        ),
+        name,
+        symbol,
+        uri,
+    )
+}
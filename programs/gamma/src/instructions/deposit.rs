@@ -1,11 +1,14 @@
 use crate::{
-    curve::{CurveCalculator, RoundDirection},
     error::GammaError,
+    instructions::{decode_account_info, deposit_amounts, LiquidityPreview},
     states::{
-        LpChangeEvent, PartnerType, PoolState, PoolStatusBitIndex, UserPoolLiquidity,
-        USER_POOL_LIQUIDITY_SEED,
+        AmmConfig, LiquidityChangeEvent, LpChangeEvent, PoolState, PoolStatusBitIndex,
+        UserPoolLiquidity, DEPOSIT_WHITELIST_EXTENSION, USER_POOL_LIQUIDITY_SEED,
+    },
+    utils::{
+        check_whitelisted, load_referral_account, refresh_quote_cache,
+        transfer_from_user_to_pool_vault_with_hook, update_mint_exposures,
     },
-    utils::{get_transfer_inverse_fee, transfer_from_user_to_pool_vault},
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
@@ -15,7 +18,8 @@ use anchor_spl::{
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
-    /// Owner of the liquidity provided
+    /// Owner of the liquidity provided, pays the rent of growing an older liquidity account
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault authority
@@ -39,6 +43,9 @@ pub struct Deposit<'info> {
             owner.key().as_ref(),
         ],
         bump,
+        realloc = UserPoolLiquidity::LEN,
+        realloc::payer = owner,
+        realloc::zero = false,
     )]
     pub user_pool_liquidity: Account<'info, UserPoolLiquidity>,
 
@@ -88,74 +95,122 @@ pub struct Deposit<'info> {
         address = token_1_vault.mint
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
 }
 
-pub fn deposit(
-    ctx: Context<Deposit>,
+pub fn deposit<'info>(
+    ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
     lp_token_amount: u64,
     maximum_token_0_amount: u64,
     maximum_token_1_amount: u64,
 ) -> Result<()> {
+    // Optional remaining accounts [amm_config, referral_account] attribute the liquidity to a referrer
+    if let (Some(amm_config_info), Some(referral_account)) = (
+        decode_account_info(ctx.remaining_accounts, 0),
+        decode_account_info(ctx.remaining_accounts, 1),
+    ) {
+        require_keys_eq!(
+            amm_config_info.key(),
+            ctx.accounts.pool_state.load()?.amm_config
+        );
+        require_keys_eq!(*amm_config_info.owner, crate::id());
+        let amm_config = AmmConfig::try_deserialize(&mut &amm_config_info.data.borrow()[..])?;
+        load_referral_account(amm_config.referral_project, &referral_account)?;
+
+        // The first referrer keeps the attribution
+        let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+        if user_pool_liquidity.extension.referrer.is_none() && !amm_config.disable_referrals {
+            user_pool_liquidity.extension.referrer = Some(referral_account.key());
+        }
+    }
+
     deposit_to_gamma_pool(
         ctx.accounts,
         lp_token_amount,
         maximum_token_0_amount,
         maximum_token_1_amount,
+        DepositRemainingAccounts::new(ctx.remaining_accounts),
     )
 }
 
-pub fn deposit_to_gamma_pool(
-    accounts: &mut Deposit,
+/// Remaining accounts of a deposit at fixed positions, the referrer accounts and the
+/// `DepositRemainingAccounts` up to the whitelist entry. The transfer hook accounts follow them.
+pub const DEPOSIT_FIXED_REMAINING_ACCOUNTS: usize = 6;
+
+/// Optional accounts of a deposit that pools with the matching extension require, following the
+/// referrer accounts
+#[derive(Default)]
+pub struct DepositRemainingAccounts<'a, 'info> {
+    /// `MintExposure` of token_0 and token_1, remaining accounts 2 and 3
+    pub token_0_mint_exposure: Option<&'a AccountInfo<'info>>,
+    pub token_1_mint_exposure: Option<&'a AccountInfo<'info>>,
+    /// The pool's `QuoteCache`, remaining account 4
+    pub quote_cache: Option<&'a AccountInfo<'info>>,
+    /// The owner's `PoolWhitelistEntry`, remaining account 5
+    pub whitelist_entry: Option<&'a AccountInfo<'info>>,
+    /// Remaining accounts `DEPOSIT_FIXED_REMAINING_ACCOUNTS` onwards, the accounts of the
+    /// transfer hooks of token_0 and token_1 with their `TransferHookAllowlistEntry`
+    pub transfer_hook_accounts: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> DepositRemainingAccounts<'a, 'info> {
+    pub fn new(remaining_accounts: &'a [AccountInfo<'info>]) -> Self {
+        Self {
+            token_0_mint_exposure: remaining_accounts.get(2),
+            token_1_mint_exposure: remaining_accounts.get(3),
+            quote_cache: remaining_accounts.get(4),
+            whitelist_entry: remaining_accounts.get(5),
+            transfer_hook_accounts: remaining_accounts
+                .get(DEPOSIT_FIXED_REMAINING_ACCOUNTS..)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+pub fn deposit_to_gamma_pool<'info>(
+    accounts: &mut Deposit<'info>,
     lp_token_amount: u64,
     maximum_token_0_amount: u64,
     maximum_token_1_amount: u64,
+    remaining_accounts: DepositRemainingAccounts<'_, 'info>,
 ) -> Result<()> {
     let pool_id = accounts.pool_state.key();
     let pool_state = &mut accounts.pool_state.load_mut()?;
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit) {
         return err!(GammaError::NotApproved);
     }
+    check_whitelisted(
+        pool_state,
+        pool_id,
+        DEPOSIT_WHITELIST_EXTENSION,
+        accounts.owner.key(),
+        remaining_accounts.whitelist_entry,
+    )?;
+    let reserves_before = pool_state.vault_amount_without_fee()?;
+    pool_state.sync_rebasing_reserve(true, accounts.token_0_vault.amount)?;
+    pool_state.sync_rebasing_reserve(false, accounts.token_1_vault.amount)?;
     let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee()?;
-    let results = CurveCalculator::lp_tokens_to_trading_tokens(
-        u128::from(lp_token_amount),
-        u128::from(pool_state.lp_supply),
-        u128::from(total_token_0_amount),
-        u128::from(total_token_1_amount),
-        RoundDirection::Ceiling,
-    )
-    .ok_or(GammaError::ZeroTradingTokens)?;
-
-    let token_0_amount =
-        u64::try_from(results.token_0_amount).map_err(|_| GammaError::MathOverflow)?;
-    let (transfer_token_0_amount, transfer_token_0_fee) = {
-        let transfer_fee =
-            get_transfer_inverse_fee(&accounts.vault_0_mint.to_account_info(), token_0_amount)?;
-        (
-            token_0_amount
-                .checked_add(transfer_fee)
-                .ok_or(GammaError::MathOverflow)?,
-            transfer_fee,
-        )
-    };
-
-    let token_1_amount =
-        u64::try_from(results.token_1_amount).map_err(|_| GammaError::MathOverflow)?;
-    let (transfer_token_1_amount, transfer_token_1_fee) = {
-        let transfer_fee =
-            get_transfer_inverse_fee(&accounts.vault_1_mint.to_account_info(), token_1_amount)?;
-        (
-            token_1_amount
-                .checked_add(transfer_fee)
-                .ok_or(GammaError::MathOverflow)?,
-            transfer_fee,
-        )
-    };
+    let LiquidityPreview {
+        token_0_amount,
+        token_1_amount,
+        token_0_user_amount: transfer_token_0_amount,
+        token_1_user_amount: transfer_token_1_amount,
+        token_0_transfer_fee: transfer_token_0_fee,
+        token_1_transfer_fee: transfer_token_1_fee,
+        ..
+    } = deposit_amounts(
+        pool_state,
+        lp_token_amount,
+        &accounts.vault_0_mint.to_account_info(),
+        &accounts.vault_1_mint.to_account_info(),
+    )?;
     #[cfg(feature = "enable-log")]
     msg!(
-        "results.token_0_amount;{}, results.token_1_amount:{},transfer_token_0_amount:{},transfer_token_0_fee:{},
+        "token_0_amount;{}, token_1_amount:{},transfer_token_0_amount:{},transfer_token_0_fee:{},
             transfer_token_1_amount:{},transfer_token_1_fee:{}",
-        results.token_0_amount,
-        results.token_1_amount,
+        token_0_amount,
+        token_1_amount,
         transfer_token_0_amount,
         transfer_token_0_fee,
         transfer_token_1_amount,
@@ -163,6 +218,7 @@ pub fn deposit_to_gamma_pool(
     );
 
     emit!(LpChangeEvent {
+        version: LpChangeEvent::VERSION,
         pool_id,
         lp_amount_before: pool_state.lp_supply,
         token_0_vault_before: total_token_0_amount,
@@ -180,7 +236,7 @@ pub fn deposit_to_gamma_pool(
         return Err(GammaError::ExceededSlippage.into());
     }
 
-    transfer_from_user_to_pool_vault(
+    transfer_from_user_to_pool_vault_with_hook(
         accounts.owner.to_account_info(),
         accounts.token_0_account.to_account_info(),
         accounts.token_0_vault.to_account_info(),
@@ -190,11 +246,12 @@ pub fn deposit_to_gamma_pool(
         } else {
             accounts.token_program_2022.to_account_info()
         },
+        remaining_accounts.transfer_hook_accounts,
         transfer_token_0_amount,
         accounts.vault_0_mint.decimals,
     )?;
 
-    transfer_from_user_to_pool_vault(
+    transfer_from_user_to_pool_vault_with_hook(
         accounts.owner.to_account_info(),
         accounts.token_1_account.to_account_info(),
         accounts.token_1_vault.to_account_info(),
@@ -204,6 +261,7 @@ pub fn deposit_to_gamma_pool(
         } else {
             accounts.token_program_2022.to_account_info()
         },
+        remaining_accounts.transfer_hook_accounts,
         transfer_token_1_amount,
         accounts.vault_1_mint.decimals,
     )?;
@@ -216,12 +274,20 @@ pub fn deposit_to_gamma_pool(
         .token_1_vault_amount
         .checked_add(token_1_amount)
         .ok_or(GammaError::MathOverflow)?;
+    update_mint_exposures(
+        pool_state,
+        reserves_before,
+        remaining_accounts.token_0_mint_exposure,
+        remaining_accounts.token_1_mint_exposure,
+    )?;
 
     pool_state.lp_supply = pool_state
         .lp_supply
         .checked_add(lp_token_amount)
         .ok_or(GammaError::MathOverflow)?;
+    pool_state.check_lp_supply_cap(pool_state.lp_supply)?;
     let user_pool_liquidity = &mut accounts.user_pool_liquidity;
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
     user_pool_liquidity.token_0_deposited = user_pool_liquidity
         .token_0_deposited
         .checked_add(u128::from(token_0_amount))
@@ -234,13 +300,32 @@ pub fn deposit_to_gamma_pool(
         .lp_tokens_owned
         .checked_add(u128::from(lp_token_amount))
         .ok_or(GammaError::MathOverflow)?;
+    user_pool_liquidity.extension.last_deposit_slot = Clock::get()?.slot;
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
+    emit!(LiquidityChangeEvent {
+        version: LiquidityChangeEvent::VERSION,
+        pool_id,
+        user: user_pool_liquidity.user,
+        lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee: transfer_token_0_fee,
+        token_1_transfer_fee: transfer_token_1_fee,
+        lp_supply: pool_state.lp_supply,
+        user_lp_tokens: user_pool_liquidity.lp_tokens_owned,
+        change_type: 0,
+    });
     pool_state.recent_epoch = Clock::get()?.epoch;
 
     if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
         let mut pool_state_partners = pool_state.partners;
         let partner: Option<&mut crate::states::PartnerInfo> = pool_state_partners
             .iter_mut()
-            .find(|p| PartnerType::new(p.partner_id) == user_pool_liquidity_partner);
+            .find(|p| p.links(user_pool_liquidity_partner));
         if let Some(partner) = partner {
             partner.lp_token_linked_with_partner = partner
                 .lp_token_linked_with_partner
@@ -249,5 +334,5 @@ pub fn deposit_to_gamma_pool(
         }
         pool_state.partners = pool_state_partners;
     }
-    Ok(())
+    refresh_quote_cache(pool_state, pool_id, remaining_accounts.quote_cache)
 }
@@ -0,0 +1,465 @@
+use crate::{
+    curve::{CurveCalculator, RoundDirection, TradeDirection},
+    error::GammaError,
+    states::{
+        oracle, AmmConfig, LpChangeEvent, ObservationState, PoolLifecycle, PoolState,
+        PoolStatusBitIndex, SwapEvent, UserPoolLiquidity, DEPOSIT_WHITELIST_EXTENSION,
+        REBASING_SHARES_EXTENSION, USER_POOL_LIQUIDITY_SEED,
+    },
+    utils::{
+        check_price_band, check_whitelisted, get_transfer_fee, get_transfer_inverse_fee,
+        get_ui_adjusted_amount, refresh_quote_cache, transfer_from_user_to_pool_vault,
+        update_mint_exposures, U256,
+    },
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct DepositSingleToken<'info> {
+    /// Owner of the liquidity provided, pays the rent of growing an older liquidity account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Pool state the owner is depositing into
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        realloc = UserPoolLiquidity::LEN,
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub user_pool_liquidity: Account<'info, UserPoolLiquidity>,
+
+    /// The owner's token account of the deposited token
+    #[account(
+        mut,
+        token::mint = input_vault.mint,
+        token::authority = owner
+    )]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool vault of the deposited token
+    #[account(
+        mut,
+        constraint = input_vault.key() == pool_state.load()?.token_0_vault || input_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for the deposited token
+    pub input_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of the deposited token
+    #[account(
+        address = input_vault.mint
+    )]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Swapping `sqrt(x * (x + a)) - x` of an amount `a` leaves the rest in the pool's ratio when
+/// the swap charges no fee. Fees make the swap output slightly short, the LP amount is then
+/// bounded by the output and the unused input stays with the owner.
+fn zap_swap_amount(reserve: u64, amount: u64) -> Result<u64> {
+    let reserve = U256::from(reserve);
+    let swap_amount = reserve
+        .checked_mul(reserve + U256::from(amount))
+        .ok_or(GammaError::MathOverflow)?
+        .integer_sqrt()
+        .checked_sub(reserve)
+        .ok_or(GammaError::MathOverflow)?;
+    if swap_amount > U256::from(u64::MAX) {
+        return err!(GammaError::MathOverflow);
+    }
+    Ok(swap_amount.as_u64())
+}
+
+pub fn deposit_single_token(
+    ctx: Context<DepositSingleToken>,
+    maximum_amount_in: u64,
+    minimum_lp_token_amount: u64,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit)
+        || pool_state.lifecycle(block_timestamp) != PoolLifecycle::Open
+    {
+        return err!(GammaError::NotApproved);
+    }
+    // Gated pools take the owner's `PoolWhitelistEntry` as remaining account 5
+    check_whitelisted(
+        pool_state,
+        pool_id,
+        DEPOSIT_WHITELIST_EXTENSION,
+        ctx.accounts.owner.key(),
+        ctx.remaining_accounts.get(5),
+    )?;
+    // Only one vault is passed, the other reserve of a rebasing pool couldn't be synced
+    if pool_state.has_extension(REBASING_SHARES_EXTENSION) {
+        return err!(GammaError::NotApproved);
+    }
+    pool_state.update_weights(block_timestamp);
+
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee()?;
+    let (trade_direction, input_reserve, output_reserve, output_mint) =
+        if ctx.accounts.input_vault.key() == pool_state.token_0_vault {
+            (
+                TradeDirection::ZeroForOne,
+                total_token_0_amount,
+                total_token_1_amount,
+                pool_state.token_1_mint,
+            )
+        } else {
+            (
+                TradeDirection::OneForZero,
+                total_token_1_amount,
+                total_token_0_amount,
+                pool_state.token_0_mint,
+            )
+        };
+    // Half of the deposit is swapped, so it's paused along with the pool's swaps
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
+        || !pool_state.swap_direction_enabled(trade_direction)
+    {
+        return err!(GammaError::NotApproved);
+    }
+    let (token_0_price_x32_before_swap, token_1_price_x32_before_swap) =
+        pool_state.token_price_x32()?;
+
+    let input_mint_info = ctx.accounts.input_token_mint.to_account_info();
+    let available_amount = maximum_amount_in
+        .checked_sub(get_transfer_fee(&input_mint_info, maximum_amount_in)?)
+        .ok_or(GammaError::MathOverflow)?;
+    let swap_amount = zap_swap_amount(input_reserve, available_amount)?;
+    require_gt!(swap_amount, 0, GammaError::ZeroTradingTokens);
+    pool_state.check_trade_size(swap_amount, input_reserve)?;
+
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let (mut observation_state, mut observation_archive) =
+        ObservationState::load_mut_with_archive(&observation_info)?;
+    let result = CurveCalculator::swap_base_input(
+        u128::from(swap_amount),
+        u128::from(input_reserve),
+        u128::from(output_reserve),
+        trade_direction,
+        &ctx.accounts.amm_config,
+        &pool_state,
+        block_timestamp,
+        &observation_state,
+        false,
+        0,
+    )?;
+    let swap_output = u64::try_from(result.destination_amount_swapped)
+        .map_err(|_| GammaError::MathOverflow)?;
+    let protocol_fee = u64::try_from(result.protocol_fee).or(err!(GammaError::MathOverflow))?;
+    let fund_fee = u64::try_from(result.fund_fee).or(err!(GammaError::MathOverflow))?;
+
+    // Reserves once the swap leg is done, the LP share of the fee stays in the pool
+    let input_reserve_after_swap = input_reserve
+        .checked_add(swap_amount)
+        .and_then(|amount| amount.checked_sub(protocol_fee))
+        .and_then(|amount| amount.checked_sub(fund_fee))
+        .ok_or(GammaError::MathOverflow)?;
+    let output_reserve_after_swap = output_reserve
+        .checked_sub(swap_output)
+        .ok_or(GammaError::MathOverflow)?;
+
+    // The LP amount the swapped output and the remaining input can both pay for
+    let lp_amount_for = |amount: u64, reserve: u64| -> Result<u64> {
+        let lp_amount = u128::from(amount)
+            .checked_mul(u128::from(pool_state.lp_supply))
+            .ok_or(GammaError::MathOverflow)?
+            .checked_div(u128::from(reserve))
+            .ok_or(GammaError::MathOverflow)?;
+        u64::try_from(lp_amount).or(err!(GammaError::MathOverflow))
+    };
+    let lp_token_amount = std::cmp::min(
+        lp_amount_for(swap_output, output_reserve_after_swap)?,
+        lp_amount_for(
+            available_amount
+                .checked_sub(swap_amount)
+                .ok_or(GammaError::MathOverflow)?,
+            input_reserve_after_swap,
+        )?,
+    );
+    require_gt!(lp_token_amount, 0, GammaError::ZeroTradingTokens);
+    require_gte!(
+        lp_token_amount,
+        minimum_lp_token_amount,
+        GammaError::ExceededSlippage
+    );
+
+    let (token_0_reserve_after_swap, token_1_reserve_after_swap) = match trade_direction {
+        TradeDirection::ZeroForOne => (input_reserve_after_swap, output_reserve_after_swap),
+        TradeDirection::OneForZero => (output_reserve_after_swap, input_reserve_after_swap),
+    };
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(token_0_reserve_after_swap),
+        u128::from(token_1_reserve_after_swap),
+        RoundDirection::Ceiling,
+    )
+    .ok_or(GammaError::ZeroTradingTokens)?;
+    let (input_deposit, output_deposit) = match trade_direction {
+        TradeDirection::ZeroForOne => (results.token_0_amount, results.token_1_amount),
+        TradeDirection::OneForZero => (results.token_1_amount, results.token_0_amount),
+    };
+    let input_deposit = u64::try_from(input_deposit).or(err!(GammaError::MathOverflow))?;
+    let output_deposit = u64::try_from(output_deposit).or(err!(GammaError::MathOverflow))?;
+    require_gte!(swap_output, output_deposit, GammaError::ExceededSlippage);
+
+    let input_amount = swap_amount
+        .checked_add(input_deposit)
+        .ok_or(GammaError::MathOverflow)?;
+    let input_transfer_fee = get_transfer_inverse_fee(&input_mint_info, input_amount)?;
+    let input_transfer_amount = input_amount
+        .checked_add(input_transfer_fee)
+        .ok_or(GammaError::MathOverflow)?;
+    require_gte!(
+        maximum_amount_in,
+        input_transfer_amount,
+        GammaError::ExceededSlippage
+    );
+
+    emit!(SwapEvent {
+        version: SwapEvent::VERSION,
+        pool_id,
+        input_vault_before: input_reserve,
+        output_vault_before: output_reserve,
+        input_amount: swap_amount,
+        output_amount: swap_output,
+        input_mint: ctx.accounts.input_token_mint.key(),
+        output_mint,
+        input_transfer_fee,
+        output_transfer_fee: 0,
+        base_input: true,
+        dynamic_fee: result.dynamic_fee,
+        input_ui_adjusted_amount: get_ui_adjusted_amount(
+            pool_state,
+            trade_direction == TradeDirection::ZeroForOne,
+            Some(&input_mint_info),
+            swap_amount,
+        )?,
+        output_ui_adjusted_amount: get_ui_adjusted_amount(
+            pool_state,
+            trade_direction == TradeDirection::OneForZero,
+            None,
+            swap_output,
+        )?,
+        vault_0_after: token_0_reserve_after_swap,
+        vault_1_after: token_1_reserve_after_swap,
+        lp_supply: pool_state.lp_supply,
+        dynamic_fee_rate: result.dynamic_fee_rate,
+        amm_config_index: ctx.accounts.amm_config.index,
+    });
+    emit!(LpChangeEvent {
+        version: LpChangeEvent::VERSION,
+        pool_id,
+        lp_amount_before: pool_state.lp_supply,
+        token_0_vault_before: total_token_0_amount,
+        token_1_vault_before: total_token_1_amount,
+        token_0_amount: if trade_direction == TradeDirection::ZeroForOne {
+            input_amount
+        } else {
+            0
+        },
+        token_1_amount: if trade_direction == TradeDirection::OneForZero {
+            input_amount
+        } else {
+            0
+        },
+        token_0_transfer_fee: if trade_direction == TradeDirection::ZeroForOne {
+            input_transfer_fee
+        } else {
+            0
+        },
+        token_1_transfer_fee: if trade_direction == TradeDirection::OneForZero {
+            input_transfer_fee
+        } else {
+            0
+        },
+        change_type: 0
+    });
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        input_mint_info,
+        ctx.accounts.input_token_program.to_account_info(),
+        input_transfer_amount,
+        ctx.accounts.input_token_mint.decimals,
+    )?;
+
+    // The swap output never leaves the vault, any of it the deposit doesn't use goes to the LPs
+    let input_vault_amount = input_reserve_after_swap
+        .checked_add(input_deposit)
+        .ok_or(GammaError::MathOverflow)?;
+    match trade_direction {
+        TradeDirection::ZeroForOne => {
+            pool_state.token_0_vault_amount = input_vault_amount;
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.fund_fees_token_0 = pool_state
+                .fund_fees_token_0
+                .checked_add(fund_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_trade_fees_token_0 = pool_state
+                .cumulative_trade_fees_token_0
+                .checked_add(result.dynamic_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_protocol_fees_token_0 = pool_state
+                .cumulative_protocol_fees_token_0
+                .checked_add(u128::from(protocol_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_fund_fees_token_0 = pool_state
+                .cumulative_fund_fees_token_0
+                .checked_add(u128::from(fund_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_volume_token_0 = pool_state
+                .cumulative_volume_token_0
+                .checked_add(u128::from(swap_amount))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        TradeDirection::OneForZero => {
+            pool_state.token_1_vault_amount = input_vault_amount;
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.fund_fees_token_1 = pool_state
+                .fund_fees_token_1
+                .checked_add(fund_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_trade_fees_token_1 = pool_state
+                .cumulative_trade_fees_token_1
+                .checked_add(result.dynamic_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_protocol_fees_token_1 = pool_state
+                .cumulative_protocol_fees_token_1
+                .checked_add(u128::from(protocol_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_fund_fees_token_1 = pool_state
+                .cumulative_fund_fees_token_1
+                .checked_add(u128::from(fund_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_volume_token_1 = pool_state
+                .cumulative_volume_token_1
+                .checked_add(u128::from(swap_amount))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+    }
+    let lp_fee = u64::try_from(result.dynamic_fee)
+        .or(err!(GammaError::MathOverflow))?
+        .saturating_sub(protocol_fee)
+        .saturating_sub(fund_fee);
+    pool_state.accrue_lp_fee(trade_direction == TradeDirection::ZeroForOne, lp_fee)?;
+    pool_state.check_price_bounds(token_0_price_x32_before_swap)?;
+    pool_state.check_slot_price_impact(Clock::get()?.slot, token_0_price_x32_before_swap)?;
+    // Pools with a price band take the band and its oracle as remaining accounts
+    check_price_band(
+        pool_state,
+        pool_id,
+        ctx.remaining_accounts.first(),
+        ctx.remaining_accounts.get(1),
+        token_0_price_x32_before_swap,
+    )?;
+    pool_state.check_twap_deviation(
+        &observation_state,
+        &observation_archive,
+        block_timestamp,
+        token_0_price_x32_before_swap,
+        token_1_price_x32_before_swap,
+    )?;
+    // Followed by the `MintExposure` of token_0 and token_1 for pools tracking their mints
+    update_mint_exposures(
+        pool_state,
+        (total_token_0_amount, total_token_1_amount),
+        ctx.remaining_accounts.get(2),
+        ctx.remaining_accounts.get(3),
+    )?;
+    pool_state.latest_dynamic_fee_rate = result.dynamic_fee_rate;
+    // And the `QuoteCache` of pools keeping one
+    refresh_quote_cache(pool_state, pool_id, ctx.remaining_accounts.get(4))?;
+    pool_state.lp_supply = pool_state
+        .lp_supply
+        .checked_add(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    pool_state.check_lp_supply_cap(pool_state.lp_supply)?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
+    match trade_direction {
+        TradeDirection::ZeroForOne => {
+            user_pool_liquidity.token_0_deposited = user_pool_liquidity
+                .token_0_deposited
+                .checked_add(u128::from(input_amount))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        TradeDirection::OneForZero => {
+            user_pool_liquidity.token_1_deposited = user_pool_liquidity
+                .token_1_deposited
+                .checked_add(u128::from(input_amount))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+    }
+    user_pool_liquidity.lp_tokens_owned = user_pool_liquidity
+        .lp_tokens_owned
+        .checked_add(u128::from(lp_token_amount))
+        .ok_or(GammaError::MathOverflow)?;
+    user_pool_liquidity.extension.last_deposit_slot = Clock::get()?.slot;
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
+
+    if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
+        let mut pool_state_partners = pool_state.partners;
+        if let Some(partner) = pool_state_partners
+            .iter_mut()
+            .find(|p| p.links(user_pool_liquidity_partner))
+        {
+            partner.lp_token_linked_with_partner = partner
+                .lp_token_linked_with_partner
+                .checked_add(lp_token_amount)
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        pool_state.partners = pool_state_partners;
+    }
+
+    observation_state.update_with_archive(
+        &mut observation_archive,
+        oracle::block_timestamp()?,
+        token_0_price_x32_before_swap,
+        token_1_price_x32_before_swap,
+    )?;
+    pool_state.update_ema_price(
+        oracle::block_timestamp()?,
+        token_0_price_x32_before_swap,
+        token_1_price_x32_before_swap,
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::{
+    error::GammaError,
+    states::{DonationEvent, PoolState, PoolStatusBitIndex},
+    utils::{
+        get_transfer_fee, refresh_quote_cache, transfer_from_user_to_pool_vault,
+        update_mint_exposures,
+    },
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, Token2022, TokenAccount},
+};
+
+#[derive(Accounts)]
+pub struct Donate<'info> {
+    pub donor: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The donor's token account to donate token_0 from
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+        token::authority = donor
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The donor's token account to donate token_1 from
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+        token::authority = donor
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Adds the donated tokens to the reserves without minting LP, so they go to the LPs pro rata,
+/// accrued to their liquidity in pools with LP fee accrual.
+/// A donation out of proportion with the reserves moves the price like a swap would, within
+/// the pool's price floor and ceiling.
+///
+/// Optional remaining accounts: the `MintExposure` of token_0 and token_1 and the pool's
+/// `QuoteCache`, required by pools with the matching extension like for a deposit
+pub fn donate(ctx: Context<Donate>, amount_0: u64, amount_1: u64) -> Result<()> {
+    require!(amount_0 > 0 || amount_1 > 0, GammaError::InvalidInput);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit) {
+        return err!(GammaError::NotApproved);
+    }
+    // Without LPs there is no one to donate to, the tokens would be stuck in the vaults
+    require_gt!(pool_state.lp_supply, 0, GammaError::InvalidLpTokenAmount);
+
+    let reserves_before = pool_state.vault_amount_without_fee()?;
+    pool_state.sync_rebasing_reserve(true, ctx.accounts.token_0_vault.amount)?;
+    pool_state.sync_rebasing_reserve(false, ctx.accounts.token_1_vault.amount)?;
+    let (token_0_price_x32_before, _) = pool_state.token_price_x32()?;
+
+    let vault_0_mint_info = ctx.accounts.vault_0_mint.to_account_info();
+    let vault_1_mint_info = ctx.accounts.vault_1_mint.to_account_info();
+    // What the vaults receive, net of transfer fees
+    let token_0_amount = amount_0.saturating_sub(get_transfer_fee(&vault_0_mint_info, amount_0)?);
+    let token_1_amount = amount_1.saturating_sub(get_transfer_fee(&vault_1_mint_info, amount_1)?);
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.donor.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        vault_0_mint_info.clone(),
+        if vault_0_mint_info.owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_0,
+        ctx.accounts.vault_0_mint.decimals,
+    )?;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.donor.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        vault_1_mint_info.clone(),
+        if vault_1_mint_info.owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        amount_1,
+        ctx.accounts.vault_1_mint.decimals,
+    )?;
+
+    pool_state.token_0_vault_amount = pool_state
+        .token_0_vault_amount
+        .checked_add(token_0_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    pool_state.token_1_vault_amount = pool_state
+        .token_1_vault_amount
+        .checked_add(token_1_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    // With LP fee accrual the donation is paid out like the LP part of a swap fee
+    pool_state.accrue_lp_fee(true, token_0_amount)?;
+    pool_state.accrue_lp_fee(false, token_1_amount)?;
+    pool_state.check_price_bounds(token_0_price_x32_before)?;
+    update_mint_exposures(
+        pool_state,
+        reserves_before,
+        ctx.remaining_accounts.first(),
+        ctx.remaining_accounts.get(1),
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    emit!(DonationEvent {
+        version: DonationEvent::VERSION,
+        pool_id,
+        donor: ctx.accounts.donor.key(),
+        token_0_amount,
+        token_1_amount,
+    });
+    refresh_quote_cache(pool_state, pool_id, ctx.remaining_accounts.get(2))
+}
@@ -0,0 +1,46 @@
+use crate::error::GammaError;
+use crate::states::{emit_lifecycle_change, PauseExpiry, PoolState, PAUSE_EXPIRY_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExpirePause<'info> {
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            PAUSE_EXPIRY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = pause_expiry.bump,
+    )]
+    pub pause_expiry: Box<Account<'info, PauseExpiry>>,
+}
+
+/// Permissionless, restores the status bits from before an emergency pause the admin didn't
+/// renew in time
+pub fn expire_pause(ctx: Context<ExpirePause>) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pause_expiry = &mut ctx.accounts.pause_expiry;
+    require!(
+        pause_expiry.active && block_timestamp >= pause_expiry.expires_at,
+        GammaError::PauseNotExpired
+    );
+    pause_expiry.active = false;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // The admin changed the status since the pause, that status stays
+    if pool_state.status != pause_expiry.paused_status {
+        return Ok(());
+    }
+    let lifecycle_before = pool_state.lifecycle(block_timestamp);
+    pool_state.update_status(pause_expiry.previous_status, block_timestamp)?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    emit_lifecycle_change(
+        ctx.accounts.pool_state.key(),
+        lifecycle_before,
+        pool_state.lifecycle(block_timestamp),
+    );
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use crate::error::GammaError;
+use crate::fees::StaticFee;
+use crate::states::{
+    AmmConfig, FlashLoanEvent, FlashLoanState, PoolLifecycle, PoolState, FLASH_LOAN_SEED,
+    REBASING_SHARES_EXTENSION,
+};
+use crate::utils::{
+    get_transfer_inverse_fee, transfer_from_pool_vault_to_user, transfer_from_user_to_pool_vault,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::{
+    self,
+    instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Position of the flash loan state in the accounts of `flash_repay`
+const FLASH_REPAY_FLASH_LOAN_STATE_INDEX: usize = 2;
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read the flash loan fee
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            FLASH_LOAN_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = borrower,
+        space = FlashLoanState::LEN,
+    )]
+    pub flash_loan_state: Box<Account<'info, FlashLoanState>>,
+
+    /// The vault to borrow from
+    #[account(
+        mut,
+        constraint = vault.key() == pool_state.load()?.token_0_vault || vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The borrower's token account receiving the loan
+    #[account(
+        mut,
+        token::mint = vault.mint,
+    )]
+    pub borrower_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(address = vault.mint)]
+    pub vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: the instructions sysvar, to find the repayment
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    pub borrower: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            FLASH_LOAN_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = flash_loan_state.bump,
+        constraint = flash_loan_state.borrower == borrower.key() @ GammaError::InvalidFlashLoan,
+    )]
+    pub flash_loan_state: Box<Account<'info, FlashLoanState>>,
+
+    #[account(mut, address = flash_loan_state.vault)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The borrower's token account paying back the loan and its fee
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = borrower,
+    )]
+    pub borrower_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(address = vault.mint)]
+    pub vault_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Whether an instruction after the current one repays the loan of `flash_loan_state`
+fn is_repaid_later(
+    instructions_sysvar: &AccountInfo<'_>,
+    flash_loan_state: &Pubkey,
+) -> Result<bool> {
+    let current_index = usize::from(load_current_index_checked(instructions_sysvar)?);
+    let mut index = current_index + 1;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        index += 1;
+        if instruction.program_id == crate::id()
+            && instruction.data.get(..8) == Some(&crate::instruction::FlashRepay::DISCRIMINATOR[..])
+            && instruction
+                .accounts
+                .get(FLASH_REPAY_FLASH_LOAN_STATE_INDEX)
+                .is_some_and(|account| account.pubkey == *flash_loan_state)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+pub fn flash_loan(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+    require_gt!(amount, 0, GammaError::InvalidInput);
+    let flash_loan_fee_rate = ctx.accounts.amm_config.flash_loan_fee_rate;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_state = ctx.accounts.pool_state.load()?;
+    if flash_loan_fee_rate == 0 || pool_state.lifecycle(block_timestamp) != PoolLifecycle::Open {
+        return err!(GammaError::NotApproved);
+    }
+    // Reserves synced to the vault balance while the loan is out would take the loan for a loss
+    if pool_state.has_extension(REBASING_SHARES_EXTENSION) {
+        return err!(GammaError::NotApproved);
+    }
+
+    let flash_loan_state = &mut ctx.accounts.flash_loan_state;
+    require!(!flash_loan_state.active, GammaError::InvalidFlashLoan);
+    require!(
+        is_repaid_later(&ctx.accounts.instructions_sysvar, &flash_loan_state.key())?,
+        GammaError::InvalidFlashLoan
+    );
+
+    // Only the reserve can be lent, fees owed to the protocol stay put
+    let is_token_0 = ctx.accounts.vault.key() == pool_state.token_0_vault;
+    let reserve = if is_token_0 {
+        pool_state.token_0_vault_amount
+    } else {
+        pool_state.token_1_vault_amount
+    };
+    require_gte!(reserve, amount, GammaError::InvalidInput);
+    let fee = StaticFee::flash_loan_fee(u128::from(amount), flash_loan_fee_rate)
+        .and_then(|fee| u64::try_from(fee).ok())
+        .ok_or(GammaError::MathOverflow)?;
+
+    flash_loan_state.bump = ctx.bumps.flash_loan_state;
+    flash_loan_state.pool_state = ctx.accounts.pool_state.key();
+    flash_loan_state.borrower = ctx.accounts.borrower.key();
+    flash_loan_state.vault = ctx.accounts.vault.key();
+    flash_loan_state.amount = amount;
+    flash_loan_state.fee = fee;
+    flash_loan_state.vault_balance_before = ctx.accounts.vault.amount;
+    flash_loan_state.accounted_amount_before = pool_state
+        .vault_accounted_amount(is_token_0)
+        .ok_or(GammaError::MathOverflow)?;
+    flash_loan_state.active = true;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.borrower_token_account.to_account_info(),
+        ctx.accounts.vault_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+        ctx.accounts.vault_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )
+}
+
+pub fn flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+    let flash_loan_state = &mut ctx.accounts.flash_loan_state;
+    require!(flash_loan_state.active, GammaError::InvalidFlashLoan);
+
+    let repay_amount = flash_loan_state
+        .amount
+        .checked_add(flash_loan_state.fee)
+        .ok_or(GammaError::MathOverflow)?;
+    let vault_mint_info = ctx.accounts.vault_mint.to_account_info();
+    let transfer_fee = get_transfer_inverse_fee(&vault_mint_info, repay_amount)?;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.borrower.to_account_info(),
+        ctx.accounts.borrower_token_account.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        vault_mint_info,
+        ctx.accounts.token_program.to_account_info(),
+        repay_amount
+            .checked_add(transfer_fee)
+            .ok_or(GammaError::MathOverflow)?,
+        ctx.accounts.vault_mint.decimals,
+    )?;
+
+    // Whatever else moved the vault since the loan must be matched by the pool's accounting
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    let is_token_0 = ctx.accounts.vault.key() == pool_state.token_0_vault;
+    let accounted_amount = pool_state
+        .vault_accounted_amount(is_token_0)
+        .ok_or(GammaError::MathOverflow)?;
+    let expected_balance = i128::from(flash_loan_state.vault_balance_before)
+        + i128::from(flash_loan_state.fee)
+        + i128::from(accounted_amount)
+        - i128::from(flash_loan_state.accounted_amount_before);
+    ctx.accounts.vault.reload()?;
+    require!(
+        i128::from(ctx.accounts.vault.amount) >= expected_balance,
+        GammaError::FlashLoanNotRepaid
+    );
+
+    // The fee goes to the LPs, accrued to their positions like the LP part of a swap fee
+    if is_token_0 {
+        pool_state.token_0_vault_amount = pool_state
+            .token_0_vault_amount
+            .checked_add(flash_loan_state.fee)
+            .ok_or(GammaError::MathOverflow)?;
+    } else {
+        pool_state.token_1_vault_amount = pool_state
+            .token_1_vault_amount
+            .checked_add(flash_loan_state.fee)
+            .ok_or(GammaError::MathOverflow)?;
+    }
+    pool_state.accrue_lp_fee(is_token_0, flash_loan_state.fee)?;
+    flash_loan_state.active = false;
+
+    emit!(FlashLoanEvent {
+        version: FlashLoanEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        borrower: flash_loan_state.borrower,
+        mint: ctx.accounts.vault.mint,
+        amount: flash_loan_state.amount,
+        fee: flash_loan_state.fee,
+    });
+    Ok(())
+}
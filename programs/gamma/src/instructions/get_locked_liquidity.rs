@@ -0,0 +1,140 @@
+use super::initialize::LOCKED_LP_AMOUNT;
+use crate::error::GammaError;
+use crate::instructions::decode_account_info;
+use crate::states::{LpBurnProof, PoolState, Position};
+use anchor_lang::prelude::*;
+
+pub const LOCKED_LIQUIDITY_BPS_DENOMINATOR: u64 = 10_000;
+
+#[derive(Accounts)]
+pub struct GetLockedLiquidity<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockedLiquidity {
+    pub lp_supply: u64,
+    /// LP locked forever when the pool was created
+    pub permanently_locked_lp: u64,
+    /// LP burned with `burn_lp_forever`
+    pub burned_lp: u64,
+    /// LP the positions passed in keep locked, see `Position::locked_lp_tokens`
+    pub position_locked_lp: u64,
+    /// Total LP that can't be withdrawn, for good or until the positions unlock
+    pub locked_lp: u64,
+    /// `locked_lp` as a share of `lp_supply`, in bps
+    pub locked_bps: u64,
+}
+
+/// Optional remaining account 0 is the `LpBurnProof` of the pool, without it burned LP isn't
+/// counted. The remaining accounts after it are positions of the pool, `lock_lp` locks and the
+/// graduation position of a bonding curve among them, whose locked LP is counted too.
+pub fn get_locked_liquidity(ctx: Context<GetLockedLiquidity>) -> Result<LockedLiquidity> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let burned_lp = burned_lp(ctx.remaining_accounts, pool_id)?;
+    let position_locked_lp = position_locked_lp(
+        ctx.remaining_accounts.get(1..).unwrap_or_default(),
+        pool_id,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
+    locked_liquidity(
+        &*ctx.accounts.pool_state.load()?,
+        burned_lp,
+        position_locked_lp,
+    )
+}
+
+/// LP of the pool burned so far, read from its `LpBurnProof` in the remaining accounts
+pub fn burned_lp(remaining_accounts: &[AccountInfo], pool_id: Pubkey) -> Result<u64> {
+    match decode_account_info(remaining_accounts, 0) {
+        Some(proof_info) => Ok(LpBurnProof::load_checked(&proof_info, pool_id)?.burned_lp_tokens),
+        None => Ok(0),
+    }
+}
+
+/// LP the `Position`s of the pool in `position_infos` keep locked at `block_timestamp`. A
+/// position passed twice would count twice, so it's rejected.
+pub fn position_locked_lp(
+    position_infos: &[AccountInfo],
+    pool_id: Pubkey,
+    block_timestamp: u64,
+) -> Result<u64> {
+    let mut counted: Vec<Pubkey> = Vec::with_capacity(position_infos.len());
+    let mut locked_lp = 0u64;
+    for position_info in position_infos {
+        require!(
+            !counted.contains(position_info.key),
+            GammaError::InvalidInput
+        );
+        counted.push(*position_info.key);
+        let position = Position::load_checked(position_info, pool_id)?;
+        locked_lp = locked_lp
+            .checked_add(position.locked_lp_tokens(block_timestamp))
+            .ok_or(GammaError::MathOverflow)?;
+    }
+    Ok(locked_lp)
+}
+
+/// The lp of `pool_state` that can't be withdrawn
+pub fn locked_liquidity(
+    pool_state: &PoolState,
+    burned_lp: u64,
+    position_locked_lp: u64,
+) -> Result<LockedLiquidity> {
+    let lp_supply = pool_state.lp_supply;
+
+    let permanently_locked_lp = LOCKED_LP_AMOUNT.min(lp_supply);
+    let burned_lp = burned_lp.min(lp_supply - permanently_locked_lp);
+    let position_locked_lp = position_locked_lp.min(lp_supply - permanently_locked_lp - burned_lp);
+    let locked_lp = permanently_locked_lp + burned_lp + position_locked_lp;
+    let locked_bps = if lp_supply == 0 {
+        0
+    } else {
+        u64::try_from(
+            u128::from(locked_lp)
+                .checked_mul(u128::from(LOCKED_LIQUIDITY_BPS_DENOMINATOR))
+                .ok_or(GammaError::MathOverflow)?
+                .checked_div(u128::from(lp_supply))
+                .ok_or(GammaError::MathOverflow)?,
+        )
+        .or(err!(GammaError::MathOverflow))?
+    };
+
+    Ok(LockedLiquidity {
+        lp_supply,
+        permanently_locked_lp,
+        burned_lp,
+        position_locked_lp,
+        locked_lp,
+        locked_bps,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn burned_lp_counts_as_locked_but_never_beyond_the_supply() {
+        let mut pool_state = PoolState::default();
+        pool_state.lp_supply = 10_100;
+        let locked = locked_liquidity(&pool_state, 0, 0).unwrap();
+        assert_eq!(locked.locked_lp, 100);
+
+        let locked = locked_liquidity(&pool_state, 5_000, 0).unwrap();
+        assert_eq!(locked.burned_lp, 5_000);
+        assert_eq!(locked.locked_lp, 5_100);
+        assert_eq!(locked.locked_bps, 5_049);
+
+        // Positions add their locked LP, within what is left of the supply
+        let locked = locked_liquidity(&pool_state, 5_000, 2_000).unwrap();
+        assert_eq!(locked.position_locked_lp, 2_000);
+        assert_eq!(locked.locked_lp, 7_100);
+        let locked = locked_liquidity(&pool_state, 5_000, 9_000).unwrap();
+        assert_eq!(locked.position_locked_lp, 5_000);
+
+        let locked = locked_liquidity(&pool_state, u64::MAX, 0).unwrap();
+        assert_eq!(locked.locked_lp, 10_100);
+        assert_eq!(locked.locked_bps, 10_000);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::GammaError;
+use crate::states::{PartnerType, PoolState};
+use anchor_lang::prelude::*;
+
+// Same precision the swap path uses when attributing fees to partners.
+pub const PARTNER_TVL_SHARE_DECIMALS: u64 = 100000;
+
+#[derive(Accounts)]
+pub struct GetPartnerDashboard<'info> {
+    /// The pool the partner is linked to
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartnerDashboard {
+    pub partner_id: u64,
+    /// LP tokens owned by customers of the partner
+    pub lp_token_linked_with_partner: u64,
+    pub lp_supply: u64,
+    /// Share of the pool tvl, scaled by `PARTNER_TVL_SHARE_DECIMALS`
+    pub tvl_share: u64,
+    /// Value of the linked LP tokens at the current vault balances
+    pub token_0_tvl: u64,
+    pub token_1_tvl: u64,
+    /// Protocol fees attributed to the partner since it was added to the pool. Partners are paid
+    /// off chain, so this is a running total that doesn't go down with payouts
+    pub accumulated_fees_token_0: u64,
+    pub accumulated_fees_token_1: u64,
+}
+
+pub fn get_partner_dashboard(
+    ctx: Context<GetPartnerDashboard>,
+    partner: u64,
+) -> Result<PartnerDashboard> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let partner_id = PartnerType::new(partner) as u64;
+
+    let partners = pool_state.partners;
+    let partner_info = partners
+        .iter()
+        .find(|info| { info.partner_id } == partner_id)
+        .ok_or(GammaError::InvalidInput)?;
+
+    let lp_supply = pool_state.lp_supply;
+    let lp_token_linked_with_partner = partner_info.lp_token_linked_with_partner;
+    let (tvl_share, token_0_tvl, token_1_tvl) = if lp_supply == 0 {
+        (0, 0, 0)
+    } else {
+        let share_of = |amount: u64| -> Result<u64> {
+            let value = u128::from(amount)
+                .checked_mul(u128::from(lp_token_linked_with_partner))
+                .ok_or(GammaError::MathOverflow)?
+                .checked_div(u128::from(lp_supply))
+                .ok_or(GammaError::MathOverflow)?;
+            u64::try_from(value).or(err!(GammaError::MathOverflow))
+        };
+        (
+            share_of(PARTNER_TVL_SHARE_DECIMALS)?,
+            share_of(pool_state.token_0_vault_amount)?,
+            share_of(pool_state.token_1_vault_amount)?,
+        )
+    };
+
+    Ok(PartnerDashboard {
+        partner_id,
+        lp_token_linked_with_partner,
+        lp_supply,
+        tvl_share,
+        token_0_tvl,
+        token_1_tvl,
+        accumulated_fees_token_0: partner_info.cumulative_fee_total_times_tvl_share_token_0,
+        accumulated_fees_token_1: partner_info.cumulative_fee_total_times_tvl_share_token_1,
+    })
+}
@@ -0,0 +1,158 @@
+use super::get_locked_liquidity::{burned_lp, locked_liquidity};
+use crate::error::GammaError;
+use crate::states::{FeeReport, ObservationState, PoolState, FEE_REPORT_SEED};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+pub const HEALTH_SCORE_DENOMINATOR: u64 = 10_000;
+/// The oracle scores full up to this age and nothing past `ORACLE_STALE_SECONDS`
+pub const ORACLE_FRESH_SECONDS: u64 = 60 * 60;
+pub const ORACLE_STALE_SECONDS: u64 = 24 * 60 * 60;
+/// Share of the lp supply, in bps, that needs to be locked for a full locked lp score
+pub const LOCKED_LP_TARGET_BPS: u64 = 1_000;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+/// Shortest stretch since the last fee report the daily volume is extrapolated from
+const MIN_VOLUME_WINDOW: u64 = 60 * 60;
+
+#[derive(Accounts)]
+pub struct GetPoolHealth<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// The volume since the last report gives the daily volume
+    #[account(
+        seeds = [
+            FEE_REPORT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = fee_report.bump,
+    )]
+    pub fee_report: Box<Account<'info, FeeReport>>,
+
+    #[account(address = pool_state.load()?.token_0_vault)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.load()?.token_1_vault)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Each score is in bps of HEALTH_SCORE_DENOMINATOR, the higher the healthier
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolHealth {
+    /// Average of the four scores below
+    pub score: u64,
+    /// Seconds since the newest observation
+    pub oracle_age: u64,
+    pub oracle_score: u64,
+    /// Reserves valued in token_0
+    pub tvl_token_0: u128,
+    /// Swap volume valued in token_0, extrapolated to a day from the volume since the last fee
+    /// report
+    pub volume_24h_token_0: u128,
+    /// Full once the reserves are worth a day of volume
+    pub depth_score: u64,
+    /// Whether both vaults hold at least what the pool accounts for
+    pub reconciled: bool,
+    pub reconciliation_score: u64,
+    /// Share of the lp supply that can never be withdrawn, in bps
+    pub locked_bps: u64,
+    pub locked_lp_score: u64,
+}
+
+/// `part` as a share of `whole` in HEALTH_SCORE_DENOMINATOR, capped at full
+fn capped_ratio(part: u128, whole: u128) -> u64 {
+    if whole == 0 || part >= whole {
+        return HEALTH_SCORE_DENOMINATOR;
+    }
+    (part * u128::from(HEALTH_SCORE_DENOMINATOR) / whole) as u64
+}
+
+/// Optional remaining account 0 is the `LpBurnProof` of the pool, counted in the locked lp
+pub fn get_pool_health(ctx: Context<GetPoolHealth>) -> Result<PoolHealth> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_state = ctx.accounts.pool_state.load()?;
+
+    let observation_state = ctx.accounts.observation_state.load()?;
+    let newest_observation =
+        observation_state.observations[usize::from(observation_state.observation_index)];
+    let oracle_age = if observation_state.initialized {
+        block_timestamp.saturating_sub(newest_observation.block_timestamp)
+    } else {
+        u64::MAX
+    };
+    let oracle_score = if oracle_age <= ORACLE_FRESH_SECONDS {
+        HEALTH_SCORE_DENOMINATOR
+    } else {
+        HEALTH_SCORE_DENOMINATOR
+            - capped_ratio(
+                u128::from(oracle_age - ORACLE_FRESH_SECONDS),
+                u128::from(ORACLE_STALE_SECONDS - ORACLE_FRESH_SECONDS),
+            )
+    };
+
+    let (_, token_1_price_x32) = pool_state.token_price_x32()?;
+    let value_in_token_0 = |amount_0: u128, amount_1: u128| {
+        amount_1
+            .checked_mul(token_1_price_x32)?
+            .checked_shr(32)?
+            .checked_add(amount_0)
+    };
+    let tvl_token_0 = value_in_token_0(
+        u128::from(pool_state.token_0_vault_amount),
+        u128::from(pool_state.token_1_vault_amount),
+    )
+    .ok_or(GammaError::MathOverflow)?;
+    let fee_report = &ctx.accounts.fee_report;
+    let volume_token_0 = value_in_token_0(
+        pool_state
+            .cumulative_volume_token_0
+            .saturating_sub(fee_report.cumulative_volume_token_0),
+        pool_state
+            .cumulative_volume_token_1
+            .saturating_sub(fee_report.cumulative_volume_token_1),
+    )
+    .ok_or(GammaError::MathOverflow)?;
+    let volume_window = block_timestamp
+        .saturating_sub(fee_report.last_report_timestamp)
+        .max(MIN_VOLUME_WINDOW);
+    let volume_24h_token_0 = volume_token_0
+        .checked_mul(u128::from(SECONDS_PER_DAY))
+        .ok_or(GammaError::MathOverflow)?
+        / u128::from(volume_window);
+    let depth_score = capped_ratio(tvl_token_0, volume_24h_token_0);
+
+    let reconciled = ctx.accounts.token_0_vault.amount
+        >= pool_state
+            .vault_accounted_amount(true)
+            .ok_or(GammaError::MathOverflow)?
+        && ctx.accounts.token_1_vault.amount
+            >= pool_state
+                .vault_accounted_amount(false)
+                .ok_or(GammaError::MathOverflow)?;
+    let reconciliation_score = if reconciled {
+        HEALTH_SCORE_DENOMINATOR
+    } else {
+        0
+    };
+
+    let burned_lp = burned_lp(ctx.remaining_accounts, ctx.accounts.pool_state.key())?;
+    // Only LP that can never be withdrawn counts towards the score, positions unlock eventually
+    let locked_bps = locked_liquidity(&pool_state, burned_lp, 0)?.locked_bps;
+    let locked_lp_score = capped_ratio(u128::from(locked_bps), u128::from(LOCKED_LP_TARGET_BPS));
+
+    Ok(PoolHealth {
+        score: (oracle_score + depth_score + reconciliation_score + locked_lp_score) / 4,
+        oracle_age,
+        oracle_score,
+        tvl_token_0,
+        volume_24h_token_0,
+        depth_score,
+        reconciled,
+        reconciliation_score,
+        locked_bps,
+        locked_lp_score,
+    })
+}
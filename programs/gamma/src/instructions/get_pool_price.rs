@@ -0,0 +1,43 @@
+use crate::states::PoolState;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct GetPoolPrice<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Rebasing pools read their reserves from the vault balances
+    #[account(address = pool_state.load()?.token_0_vault)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.load()?.token_1_vault)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Prices of each token in the other, as fixed point with 32 fractional bits
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolPrice {
+    /// Spot price of the reserves, the price the next swap starts at
+    pub token_0_price_x32: u128,
+    pub token_1_price_x32: u128,
+    /// Exponential moving averages, 0 until the pool has an EMA half-life
+    pub ema_token_0_price_x32: u128,
+    pub ema_token_1_price_x32: u128,
+    /// When the moving averages were last updated
+    pub ema_updated_at: u64,
+}
+
+pub fn get_pool_price(ctx: Context<GetPoolPrice>) -> Result<PoolPrice> {
+    let mut pool_state = *ctx.accounts.pool_state.load()?;
+    pool_state.sync_rebasing_reserve(true, ctx.accounts.token_0_vault.amount)?;
+    pool_state.sync_rebasing_reserve(false, ctx.accounts.token_1_vault.amount)?;
+    pool_state.update_weights(Clock::get()?.unix_timestamp as u64);
+    let (token_0_price_x32, token_1_price_x32) = pool_state.token_price_x32()?;
+    Ok(PoolPrice {
+        token_0_price_x32,
+        token_1_price_x32,
+        ema_token_0_price_x32: pool_state.ema_token_0_price_x32,
+        ema_token_1_price_x32: pool_state.ema_token_1_price_x32,
+        ema_updated_at: pool_state.ema_updated_at,
+    })
+}
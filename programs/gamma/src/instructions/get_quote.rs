@@ -0,0 +1,75 @@
+use crate::states::{AmmConfig, ObservationState, PoolState};
+use crate::utils::{quote_exact_in, quote_exact_out, SwapQuote, TransferFees};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct GetQuote<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The dynamic fee follows the volatility of the observations
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Rebasing pools read their reserves from the vault balances
+    #[account(address = pool_state.load()?.token_0_vault)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.load()?.token_1_vault)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mints of the vaults, for their transfer fees
+    #[account(address = token_0_vault.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = token_1_vault.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Quotes a swap of `amount` without moving any tokens. `amount` is the input of
+/// `swap_base_input` if `base_input`, otherwise the output of `swap_base_output`.
+pub fn get_quote(
+    ctx: Context<GetQuote>,
+    zero_for_one: bool,
+    amount: u64,
+    base_input: bool,
+) -> Result<SwapQuote> {
+    let clock = Clock::get()?;
+    let block_timestamp = clock.unix_timestamp as u64;
+    let mut pool_state = *ctx.accounts.pool_state.load()?;
+    pool_state.sync_rebasing_reserve(true, ctx.accounts.token_0_vault.amount)?;
+    pool_state.sync_rebasing_reserve(false, ctx.accounts.token_1_vault.amount)?;
+    pool_state.update_weights(block_timestamp);
+
+    let transfer_fees = TransferFees::from_mints(
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        &ctx.accounts.vault_1_mint.to_account_info(),
+        clock.epoch,
+    )?;
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let (observation_state, _) = ObservationState::load_with_archive(&observation_info)?;
+    if base_input {
+        quote_exact_in(
+            &pool_state,
+            &ctx.accounts.amm_config,
+            &observation_state,
+            &transfer_fees,
+            zero_for_one,
+            amount,
+            block_timestamp,
+        )
+    } else {
+        quote_exact_out(
+            &pool_state,
+            &ctx.accounts.amm_config,
+            &observation_state,
+            &transfer_fees,
+            zero_for_one,
+            amount,
+            block_timestamp,
+        )
+    }
+}
@@ -0,0 +1,39 @@
+use crate::states::{ObservationState, PoolState, Twap};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+}
+
+pub fn get_twap(ctx: Context<GetTwap>, seconds_ago: u32) -> Result<Twap> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let (observation_state, observation_archive) =
+        ObservationState::load_with_archive(&observation_info)?;
+    let (token_0_price_x32, token_1_price_x32) = pool_state.token_price_x32()?;
+    observation_state.interpolated_twap(
+        &observation_archive,
+        Clock::get()?.unix_timestamp as u64,
+        u64::from(seconds_ago),
+        token_0_price_x32,
+        token_1_price_x32,
+    )
+}
+
+pub fn get_geometric_twap(ctx: Context<GetTwap>, seconds_ago: u32) -> Result<Twap> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let (observation_state, observation_archive) =
+        ObservationState::load_with_archive(&observation_info)?;
+    let (token_0_price_x32, _) = pool_state.token_price_x32()?;
+    observation_state.geometric_twap(
+        &observation_archive,
+        Clock::get()?.unix_timestamp as u64,
+        u64::from(seconds_ago),
+        token_0_price_x32,
+    )
+}
@@ -0,0 +1,372 @@
+use std::ops::Deref;
+
+use crate::{
+    curve::CurveCalculator,
+    error::GammaError,
+    instructions::LOCKED_LP_AMOUNT,
+    states::{
+        emit_lifecycle_change, AmmConfig, BondingCurve, BondingCurveGraduatedEvent, LpLockEvent,
+        ObservationState, PairIndex, PoolLifecycle, PoolState, Position, PositionChangeEvent,
+        OBSERVATION_SEED, OBSERVATION_STATE_VERSION, PAIR_INDEX_SEED, POOL_SEED, POOL_VAULT_SEED,
+        POSITION_SEED, TOKEN_0_INTEREST_BEARING_EXTENSION, TOKEN_1_INTEREST_BEARING_EXTENSION,
+    },
+    utils::{
+        create_token_account, is_interest_bearing_mint, transfer_from_pool_vault_to_user, U128,
+    },
+};
+use anchor_lang::{accounts::interface_account::InterfaceAccount, prelude::*};
+use anchor_spl::{
+    token_2022::spl_token_2022,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+/// Id of the position holding the LP of a graduated bonding curve
+pub const GRADUATION_POSITION_ID: u64 = 0;
+
+#[derive(Accounts)]
+pub struct Graduate<'info> {
+    /// Address paying to create the pool. It can be anyone.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: pda authority of the pools, owns the vaults of the curve
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = amm_config,
+        constraint = bonding_curve.token_vault == curve_token_vault.key() @ GammaError::InvalidVault,
+        constraint = bonding_curve.quote_vault == curve_quote_vault.key() @ GammaError::InvalidVault,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(mut)]
+    pub curve_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub curve_quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [
+            POOL_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PoolState::LEN,
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Token_0 mint, the key must smaller than token_1 mint.
+    #[account(
+        constraint = token_0_mint.key() < token_1_mint.key(),
+        mint::token_program = token_0_program,
+    )]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token_1 mint, the key must greater than token_0 mint.
+    #[account(
+        mint::token_program = token_1_program,
+    )]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: token 0 vault for the pool
+    #[account(
+        mut,
+        seeds = [
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            token_0_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_0_vault: UncheckedAccount<'info>,
+
+    /// CHECK: token 1 vault for the pool
+    #[account(
+        mut,
+        seeds = [
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_1_vault: UncheckedAccount<'info>,
+
+    /// an account to store oracle observations
+    #[account(
+        init,
+        seeds = [
+            OBSERVATION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ObservationState::LEN,
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Lists the pools of the mint pair across configs, created with the pair's first pool
+    #[account(
+        init_if_needed,
+        seeds = [
+            PAIR_INDEX_SEED.as_bytes(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PairIndex::LEN,
+    )]
+    pub pair_index: Box<Account<'info, PairIndex>>,
+
+    /// Position of the curve creator holding the LP, locked for good
+    #[account(
+        init,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            bonding_curve.creator.as_ref(),
+            &GRADUATION_POSITION_ID.to_le_bytes(),
+        ],
+        bump,
+        payer = payer,
+        space = Position::LEN,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    /// Spl token program or token program 2022
+    pub token_0_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub token_1_program: Interface<'info, TokenInterface>,
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+}
+
+/// Seeds a pool of the curve's config with the quote a completed bonding curve raised and its
+/// tokens left, then locks the LP in a position of the curve creator that can never be closed.
+/// The creator collects the LP fees of the position with `collect_position_lp_fees`. The pool
+/// opens as soon as the config allows.
+pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    if !bonding_curve.is_complete() || bonding_curve.is_graduated() {
+        return err!(GammaError::BondingCurveNotComplete);
+    }
+    if ctx.accounts.amm_config.disable_create_pool {
+        return err!(GammaError::NotApproved);
+    }
+    let token_0_mint_key = ctx.accounts.token_0_mint.key();
+    let token_1_mint_key = ctx.accounts.token_1_mint.key();
+    // The curve vault feeding each pool vault
+    let (curve_vault_0, curve_vault_1) = if bonding_curve.token_mint == token_0_mint_key
+        && bonding_curve.quote_mint == token_1_mint_key
+    {
+        (
+            &ctx.accounts.curve_token_vault,
+            &ctx.accounts.curve_quote_vault,
+        )
+    } else if bonding_curve.quote_mint == token_0_mint_key
+        && bonding_curve.token_mint == token_1_mint_key
+    {
+        (
+            &ctx.accounts.curve_quote_vault,
+            &ctx.accounts.curve_token_vault,
+        )
+    } else {
+        return err!(GammaError::InvalidInput);
+    };
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let open_time = block_timestamp + std::cmp::max(ctx.accounts.amm_config.min_open_delay, 1);
+    let pool_id = ctx.accounts.pool_state.key();
+
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.token_0_vault.to_account_info(),
+        &ctx.accounts.token_0_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_0_program.to_account_info(),
+        &[&[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_id.as_ref(),
+            token_0_mint_key.as_ref(),
+            &[ctx.bumps.token_0_vault][..],
+        ][..]],
+    )?;
+    create_token_account(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.token_1_vault.to_account_info(),
+        &ctx.accounts.token_1_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.token_1_program.to_account_info(),
+        &[&[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_id.as_ref(),
+            token_1_mint_key.as_ref(),
+            &[ctx.bumps.token_1_vault][..],
+        ][..]],
+    )?;
+
+    let mut observation_state = ctx.accounts.observation_state.load_init()?;
+    observation_state.pool_id = pool_id;
+    observation_state.version = OBSERVATION_STATE_VERSION;
+
+    // Everything the curve holds moves into the pool
+    let auth_seeds: &[&[&[u8]]] = &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        curve_vault_0.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_0_mint.to_account_info(),
+        ctx.accounts.token_0_program.to_account_info(),
+        curve_vault_0.amount,
+        ctx.accounts.token_0_mint.decimals,
+        auth_seeds,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        curve_vault_1.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.token_1_mint.to_account_info(),
+        ctx.accounts.token_1_program.to_account_info(),
+        curve_vault_1.amount,
+        ctx.accounts.token_1_mint.decimals,
+        auth_seeds,
+    )?;
+
+    let token_0_vault =
+        spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+            ctx.accounts
+                .token_0_vault
+                .to_account_info()
+                .try_borrow_data()?
+                .deref(),
+        )?
+        .base;
+    let token_1_vault =
+        spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+            ctx.accounts
+                .token_1_vault
+                .to_account_info()
+                .try_borrow_data()?
+                .deref(),
+        )?
+        .base;
+
+    CurveCalculator::validate_supply(token_0_vault.amount, token_1_vault.amount)?;
+
+    let liquidity = U128::from(token_0_vault.amount)
+        .checked_mul(token_1_vault.amount.into())
+        .ok_or(GammaError::MathOverflow)?
+        .integer_sqrt()
+        .as_u64();
+
+    let pool_state = &mut ctx.accounts.pool_state.load_init()?;
+    pool_state.initialize(
+        token_0_vault.amount,
+        token_1_vault.amount,
+        ctx.bumps.authority,
+        liquidity,
+        open_time,
+        bonding_curve.max_trade_fee_rate,
+        bonding_curve.volatility_factor,
+        bonding_curve.creator,
+        ctx.accounts.amm_config.key(),
+        ctx.accounts.token_0_vault.key(),
+        ctx.accounts.token_1_vault.key(),
+        &ctx.accounts.token_0_mint,
+        &ctx.accounts.token_1_mint,
+        ctx.accounts.observation_state.key(),
+    )?;
+    emit_lifecycle_change(
+        pool_id,
+        PoolLifecycle::Created,
+        pool_state.lifecycle(block_timestamp),
+    );
+    pool_state.set_extension(
+        TOKEN_0_INTEREST_BEARING_EXTENSION,
+        is_interest_bearing_mint(&ctx.accounts.token_0_mint.to_account_info())?,
+    );
+    pool_state.set_extension(
+        TOKEN_1_INTEREST_BEARING_EXTENSION,
+        is_interest_bearing_mint(&ctx.accounts.token_1_mint.to_account_info())?,
+    );
+
+    let pair_index = &mut ctx.accounts.pair_index;
+    pair_index.bump = ctx.bumps.pair_index;
+    pair_index.token_0_mint = token_0_mint_key;
+    pair_index.token_1_mint = token_1_mint_key;
+    pair_index.add_pool(pool_id)?;
+
+    let lp_tokens = liquidity
+        .checked_sub(LOCKED_LP_AMOUNT)
+        .ok_or(GammaError::MathOverflow)?;
+    let position = &mut ctx.accounts.position;
+    position.bump = ctx.bumps.position;
+    position.pool_state = pool_id;
+    position.owner = bonding_curve.creator;
+    position.creator = bonding_curve.creator;
+    position.position_id = GRADUATION_POSITION_ID;
+    position.open_time = block_timestamp;
+    position.unlock_time = u64::MAX;
+    position.last_deposit_slot = Clock::get()?.slot;
+    position.accrue_lp_fees(pool_state)?;
+    position.lp_tokens = lp_tokens;
+
+    emit!(PositionChangeEvent {
+        version: PositionChangeEvent::VERSION,
+        pool_id,
+        position: position.key(),
+        from_owner: Pubkey::default(),
+        to_owner: position.owner,
+        lp_tokens,
+        unlock_time: position.unlock_time,
+        change_type: 0,
+    });
+    emit!(LpLockEvent {
+        version: LpLockEvent::VERSION,
+        pool_id,
+        position: position.key(),
+        owner: position.owner,
+        locked_lp_tokens: lp_tokens,
+        released_lp_tokens: 0,
+        vesting_start: 0,
+        unlock_time: position.unlock_time,
+    });
+
+    let (token_amount, quote_amount) = if bonding_curve.token_mint == token_0_mint_key {
+        (token_0_vault.amount, token_1_vault.amount)
+    } else {
+        (token_1_vault.amount, token_0_vault.amount)
+    };
+    let position_key = position.key();
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.pool_state = pool_id;
+    bonding_curve.token_reserve = 0;
+    bonding_curve.quote_reserve = 0;
+
+    emit!(BondingCurveGraduatedEvent {
+        version: BondingCurveGraduatedEvent::VERSION,
+        bonding_curve: bonding_curve.key(),
+        pool_id,
+        position: position_key,
+        token_amount,
+        quote_amount,
+        lp_tokens,
+    });
+    Ok(())
+}
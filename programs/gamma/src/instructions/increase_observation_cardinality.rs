@@ -0,0 +1,72 @@
+use crate::{
+    error::GammaError,
+    states::{ObservationState, PoolState, MAX_OBSERVATION_CARDINALITY},
+};
+use anchor_lang::{
+    prelude::*, solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE, system_program,
+};
+
+#[derive(Accounts)]
+pub struct IncreaseObservationCardinality<'info> {
+    /// Pays the rent for the extra observations, it can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: an AccountLoader would not see the archived observations, checked in the instruction
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows the observation account to keep `observation_cardinality` observations, so twaps can
+/// look further back. A single call can grow the account by MAX_PERMITTED_DATA_INCREASE bytes,
+/// larger cardinalities take several calls.
+pub fn increase_observation_cardinality(
+    ctx: Context<IncreaseObservationCardinality>,
+    observation_cardinality: u16,
+) -> Result<()> {
+    let observation_cardinality = usize::from(observation_cardinality);
+    let account_info = ctx.accounts.observation_state.to_account_info();
+    // Validates the account before it grows
+    drop(ObservationState::load_with_archive(&account_info)?);
+
+    let current_cardinality = ObservationState::cardinality(account_info.data_len());
+    require_gt!(
+        observation_cardinality,
+        current_cardinality,
+        GammaError::InvalidInput
+    );
+    require_gte!(
+        MAX_OBSERVATION_CARDINALITY,
+        observation_cardinality,
+        GammaError::InvalidInput
+    );
+    let new_len = ObservationState::space(observation_cardinality);
+    require_gte!(
+        account_info.data_len() + MAX_PERMITTED_DATA_INCREASE,
+        new_len,
+        GammaError::InvalidInput
+    );
+
+    let minimum_balance = Rent::get()?.minimum_balance(new_len);
+    let lamports_needed = minimum_balance.saturating_sub(account_info.lamports());
+    if lamports_needed > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account_info.clone(),
+                },
+            ),
+            lamports_needed,
+        )?;
+    }
+    // Empty observations are skipped by the twaps until the archive wraps into them
+    account_info.realloc(new_len, true)?;
+
+    Ok(())
+}
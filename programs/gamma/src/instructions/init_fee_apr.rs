@@ -0,0 +1,32 @@
+use crate::states::{FeeApr, PoolState, FEE_APR_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitFeeApr<'info> {
+    /// Anyone can create the fee apr account of a pool
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [
+            FEE_APR_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = FeeApr::LEN,
+    )]
+    pub fee_apr: AccountLoader<'info, FeeApr>,
+
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_fee_apr(ctx: Context<InitFeeApr>) -> Result<()> {
+    let mut fee_apr = ctx.accounts.fee_apr.load_init()?;
+    fee_apr.pool_state = ctx.accounts.pool_state.key();
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, SegmenterKickback, SEGMENTER_KICKBACK_SEED};
+use crate::utils::registered_segmenter_kickback_bps;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitSegmenterKickback<'info> {
+    /// The registered segmenter that routes order flow to the pool
+    #[account(mut)]
+    pub segmenter: Signer<'info>,
+
+    /// CHECK: the DFlow registry or a `SegmenterRegistry`, the segmenter must be registered in it
+    pub registry: UncheckedAccount<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [
+            SEGMENTER_KICKBACK_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            segmenter.key().as_ref(),
+        ],
+        bump,
+        payer = segmenter,
+        space = SegmenterKickback::LEN,
+    )]
+    pub segmenter_kickback: Box<Account<'info, SegmenterKickback>>,
+
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_segmenter_kickback(ctx: Context<InitSegmenterKickback>) -> Result<()> {
+    // Only whether the segmenter is registered matters here, swaps pick the kickback rate
+    require!(
+        registered_segmenter_kickback_bps(
+            &ctx.accounts.registry.to_account_info(),
+            &ctx.accounts.segmenter.to_account_info(),
+            0,
+        )
+        .is_some(),
+        GammaError::InvalidOwner
+    );
+
+    ctx.accounts.segmenter_kickback.initialize(
+        ctx.bumps.segmenter_kickback,
+        ctx.accounts.segmenter.key(),
+        ctx.accounts.pool_state.key(),
+    );
+    Ok(())
+}
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 
-use crate::states::{PartnerType, PoolState, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED};
+use crate::states::{
+    LiquidityChangeEvent, PartnerType, PoolState, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED,
+};
 
 #[derive(Accounts)]
 pub struct InitUserPoolLiquidity<'info> {
@@ -45,5 +47,18 @@ pub fn init_user_pool_liquidity(
         ctx.accounts.pool_state.key(),
         partner,
     );
+    emit!(LiquidityChangeEvent {
+        version: LiquidityChangeEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        user: ctx.accounts.user.key(),
+        lp_token_amount: 0,
+        token_0_amount: 0,
+        token_1_amount: 0,
+        token_0_transfer_fee: 0,
+        token_1_transfer_fee: 0,
+        lp_supply: ctx.accounts.pool_state.load()?.lp_supply,
+        user_lp_tokens: 0,
+        change_type: 2,
+    });
     Ok(())
 }
@@ -0,0 +1,33 @@
+use crate::states::{PoolState, VolumeTracker, VOLUME_TRACKER_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitVolumeTracker<'info> {
+    /// Anyone can create the tracker of a pool
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [
+            VOLUME_TRACKER_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = VolumeTracker::LEN,
+    )]
+    pub volume_tracker: Box<Account<'info, VolumeTracker>>,
+
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_volume_tracker(ctx: Context<InitVolumeTracker>) -> Result<()> {
+    ctx.accounts
+        .volume_tracker
+        .initialize(ctx.bumps.volume_tracker, ctx.accounts.pool_state.key());
+    Ok(())
+}
@@ -1,13 +1,20 @@
 use std::ops::Deref;
 
 use crate::{
-    curve::CurveCalculator,
+    curve::{CurveCalculator, MAX_AMPLIFICATION, MAX_WEIGHT, MIN_AMPLIFICATION, MIN_WEIGHT},
     error::GammaError,
+    instructions::decode_account_info,
     states::{
-        AmmConfig, ObservationState, PoolState, UserPoolLiquidity, OBSERVATION_SEED, POOL_SEED,
-        POOL_VAULT_SEED, USER_POOL_LIQUIDITY_SEED,
+        emit_lifecycle_change, AmmConfig, ObservationState, PairIndex, PoolLifecycle, PoolState,
+        TokenBadge, UserPoolLiquidity, OBSERVATION_SEED, OBSERVATION_STATE_VERSION,
+        PAIR_INDEX_SEED, POOL_SEED, POOL_VAULT_SEED, TOKEN_0_BADGED, TOKEN_0_CANONICAL,
+        TOKEN_0_INTEREST_BEARING_EXTENSION, TOKEN_1_BADGED, TOKEN_1_CANONICAL,
+        TOKEN_1_INTEREST_BEARING_EXTENSION, USER_POOL_LIQUIDITY_SEED,
+    },
+    utils::{
+        check_mint_safety, create_token_account, is_interest_bearing_mint, is_supported_mint,
+        transfer_from_user_to_pool_vault_with_hook, U128,
     },
-    utils::{create_token_account, is_supported_mint, transfer_from_user_to_pool_vault, U128},
 };
 use anchor_lang::{
     accounts::interface_account::InterfaceAccount,
@@ -21,6 +28,9 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
+/// LP minted on pool creation that is never credited to anyone, so the pool can't be fully drained
+pub const LOCKED_LP_AMOUNT: u64 = 100;
+
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     /// Address paying to create the pool. It can be anyone.
@@ -142,6 +152,20 @@ pub struct Initialize<'info> {
     )]
     pub observation_state: AccountLoader<'info, ObservationState>,
 
+    /// Lists the pools of the mint pair across configs, created with the pair's first pool
+    #[account(
+        init_if_needed,
+        seeds = [
+            PAIR_INDEX_SEED.as_bytes(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+        payer = creator,
+        space = PairIndex::LEN,
+    )]
+    pub pair_index: Box<Account<'info, PairIndex>>,
+
     /// Program to create mint account and mint tokens
     pub token_program: Program<'info, Token>,
     /// Spl token program or token program 2022
@@ -156,28 +180,67 @@ pub struct Initialize<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn initialize(
-    ctx: Context<Initialize>,
+pub fn initialize<'info>(
+    ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
     init_amount_0: u64,
     init_amount_1: u64,
     mut open_time: u64,
     max_trade_fee_rate: u64,
     volatility_factor: u64,
+    amplification: u64,
+    token_0_weight: u64,
 ) -> Result<()> {
     if !(is_supported_mint(&ctx.accounts.token_0_mint)?
         && is_supported_mint(&ctx.accounts.token_1_mint)?)
     {
         return err!(GammaError::NotSupportMint);
     }
+    // Optional remaining accounts 0 and 1 are the token badges of token_0 and token_1, mints
+    // the admin badged are exempt from the mint safety policy
+    let token_0_badge = decode_account_info(ctx.remaining_accounts, 0);
+    let token_1_badge = decode_account_info(ctx.remaining_accounts, 1);
+    for (mint, badge_info) in [
+        (&ctx.accounts.token_0_mint, &token_0_badge),
+        (&ctx.accounts.token_1_mint, &token_1_badge),
+    ] {
+        if badge_info.is_none() {
+            check_mint_safety(mint, &ctx.accounts.amm_config)?;
+        }
+    }
+    if amplification != 0 {
+        require!(
+            (MIN_AMPLIFICATION..=MAX_AMPLIFICATION).contains(&amplification),
+            GammaError::InvalidAmplification
+        );
+        // The stable swap curve prices the raw amounts 1:1
+        require_eq!(
+            ctx.accounts.token_0_mint.decimals,
+            ctx.accounts.token_1_mint.decimals,
+            GammaError::InvalidAmplification
+        );
+    }
+    if token_0_weight != 0 {
+        require!(
+            (MIN_WEIGHT..=MAX_WEIGHT).contains(&token_0_weight),
+            GammaError::InvalidWeight
+        );
+        require_eq!(amplification, 0, GammaError::InvalidWeight);
+    }
 
     if ctx.accounts.amm_config.disable_create_pool {
         return err!(GammaError::NotApproved);
     }
     let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
+    let amm_config = &ctx.accounts.amm_config;
+    if open_time == 0 {
+        open_time = block_timestamp + amm_config.default_open_delay;
+    }
     if open_time <= block_timestamp {
         open_time = block_timestamp + 1;
     }
-    if open_time > block_timestamp + ctx.accounts.amm_config.max_open_time {
+    if open_time < block_timestamp + amm_config.min_open_delay
+        || open_time > block_timestamp + amm_config.max_open_time
+    {
         return err!(GammaError::InvalidOpenTime);
     }
     // due to stack/heap limitations, we have to create redundant new token vault accounts ourselves
@@ -213,26 +276,31 @@ pub fn initialize(
 
     let mut observation_state = ctx.accounts.observation_state.load_init()?;
     observation_state.pool_id = ctx.accounts.pool_state.key();
+    observation_state.version = OBSERVATION_STATE_VERSION;
 
     let pool_state = &mut ctx.accounts.pool_state.load_init()?;
 
     // transfer from user to pool vault
-    transfer_from_user_to_pool_vault(
+    // Remaining accounts 2 onwards are the accounts of the transfer hooks of token_0 and token_1
+    let transfer_hook_accounts = ctx.remaining_accounts.get(2..).unwrap_or_default();
+    transfer_from_user_to_pool_vault_with_hook(
         ctx.accounts.creator.to_account_info(),
         ctx.accounts.creator_token_0.to_account_info(),
         ctx.accounts.token_0_vault.to_account_info(),
         ctx.accounts.token_0_mint.to_account_info(),
         ctx.accounts.token_0_program.to_account_info(),
+        transfer_hook_accounts,
         init_amount_0,
         ctx.accounts.token_0_mint.decimals,
     )?;
 
-    transfer_from_user_to_pool_vault(
+    transfer_from_user_to_pool_vault_with_hook(
         ctx.accounts.creator.to_account_info(),
         ctx.accounts.creator_token_1.to_account_info(),
         ctx.accounts.token_1_vault.to_account_info(),
         ctx.accounts.token_1_mint.to_account_info(),
         ctx.accounts.token_1_program.to_account_info(),
+        transfer_hook_accounts,
         init_amount_1,
         ctx.accounts.token_1_mint.decimals,
     )?;
@@ -263,7 +331,6 @@ pub fn initialize(
         .ok_or(GammaError::MathOverflow)?
         .integer_sqrt()
         .as_u64();
-    let lock_lp_amount = 100;
     #[cfg(feature = "enable-log")]
     msg!(
         "liquidity: {}, vault_0_amount: {}, vault_1_amount: {}",
@@ -314,6 +381,52 @@ pub fn initialize(
         &ctx.accounts.token_1_mint,
         ctx.accounts.observation_state.key(),
     )?;
+    pool_state.amplification = amplification;
+    pool_state.token_0_weight = token_0_weight;
+    emit_lifecycle_change(
+        ctx.accounts.pool_state.key(),
+        PoolLifecycle::Created,
+        pool_state.lifecycle(block_timestamp),
+    );
+
+    let pair_index = &mut ctx.accounts.pair_index;
+    pair_index.bump = ctx.bumps.pair_index;
+    pair_index.token_0_mint = ctx.accounts.token_0_mint.key();
+    pair_index.token_1_mint = ctx.accounts.token_1_mint.key();
+    pair_index.add_pool(ctx.accounts.pool_state.key())?;
+
+    let badges = [
+        (
+            token_0_badge,
+            ctx.accounts.token_0_mint.key(),
+            TOKEN_0_BADGED,
+            TOKEN_0_CANONICAL,
+        ),
+        (
+            token_1_badge,
+            ctx.accounts.token_1_mint.key(),
+            TOKEN_1_BADGED,
+            TOKEN_1_CANONICAL,
+        ),
+    ];
+    for (badge_info, mint, badged_bit, canonical_bit) in badges {
+        if let Some(badge_info) = badge_info {
+            let token_badge = TokenBadge::load_checked(&badge_info, mint)?;
+            pool_state.token_badges |= badged_bit;
+            if token_badge.canonical {
+                pool_state.token_badges |= canonical_bit;
+            }
+        }
+    }
+
+    pool_state.set_extension(
+        TOKEN_0_INTEREST_BEARING_EXTENSION,
+        is_interest_bearing_mint(&ctx.accounts.token_0_mint.to_account_info())?,
+    );
+    pool_state.set_extension(
+        TOKEN_1_INTEREST_BEARING_EXTENSION,
+        is_interest_bearing_mint(&ctx.accounts.token_1_mint.to_account_info())?,
+    );
 
     let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
     user_pool_liquidity.initialize(
@@ -324,8 +437,13 @@ pub fn initialize(
     user_pool_liquidity.token_0_deposited = u128::from(init_amount_0);
     user_pool_liquidity.token_1_deposited = u128::from(init_amount_1);
     user_pool_liquidity.lp_tokens_owned = u128::from(liquidity)
-        .checked_sub(lock_lp_amount)
+        .checked_sub(u128::from(LOCKED_LP_AMOUNT))
         .ok_or(GammaError::MathOverflow)?;
+    pool_state.track_lp_concentration(
+        ctx.accounts.pool_state.key(),
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
 
     Ok(())
 }
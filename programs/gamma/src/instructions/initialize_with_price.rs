@@ -0,0 +1,268 @@
+use super::initialize::*;
+use crate::{
+    error::GammaError,
+    states::Q32,
+    utils::{TransferFees, U256},
+};
+use anchor_lang::prelude::*;
+
+/// Raw units of a whole token, mints can declare more decimals than a U256 can scale by
+fn decimals_scale(decimals: u8) -> Result<U256> {
+    Ok(U256::from(10u64)
+        .checked_pow(U256::from(decimals))
+        .ok_or(GammaError::MathOverflow)?)
+}
+
+/// Amount of token_1 matching `amount_0` at `price_x32`, where the price is the number of whole
+/// token_1 paid for one whole token_0 as a Q32.32 fixed point number. The mint decimals are
+/// applied here so callers never have to scale the price themselves.
+pub fn token_1_amount_at_price(
+    amount_0: u64,
+    price_x32: u128,
+    mint_0_decimals: u8,
+    mint_1_decimals: u8,
+) -> Result<u64> {
+    let numerator = U256::from(amount_0)
+        .checked_mul(U256::from(price_x32))
+        .ok_or(GammaError::MathOverflow)?
+        .checked_mul(decimals_scale(mint_1_decimals)?)
+        .ok_or(GammaError::MathOverflow)?;
+    let denominator = U256::from(Q32)
+        .checked_mul(decimals_scale(mint_0_decimals)?)
+        .ok_or(GammaError::MathOverflow)?;
+    let amount_1 = numerator
+        .checked_div(denominator)
+        .ok_or(GammaError::MathOverflow)?;
+    if amount_1 > U256::from(u64::MAX) {
+        return err!(GammaError::MathOverflow);
+    }
+    Ok(amount_1.as_u64())
+}
+
+/// Inverse of `token_1_amount_at_price`
+pub fn token_0_amount_at_price(
+    amount_1: u64,
+    price_x32: u128,
+    mint_0_decimals: u8,
+    mint_1_decimals: u8,
+) -> Result<u64> {
+    require_gt!(price_x32, 0, GammaError::InvalidInput);
+    let numerator = U256::from(amount_1)
+        .checked_mul(U256::from(Q32))
+        .ok_or(GammaError::MathOverflow)?
+        .checked_mul(decimals_scale(mint_0_decimals)?)
+        .ok_or(GammaError::MathOverflow)?;
+    let denominator = U256::from(price_x32)
+        .checked_mul(decimals_scale(mint_1_decimals)?)
+        .ok_or(GammaError::MathOverflow)?;
+    let amount_0 = numerator
+        .checked_div(denominator)
+        .ok_or(GammaError::MathOverflow)?;
+    if amount_0 > U256::from(u64::MAX) {
+        return err!(GammaError::MathOverflow);
+    }
+    Ok(amount_0.as_u64())
+}
+
+/// Amounts of token_0 and token_1 to send so the vaults start at `price_x32`, `amount` being
+/// what is sent of the token given by `amount_is_token_0`. The price is set by what lands in
+/// the vaults, so transfer fees are taken out of `amount` and added on top of the counterpart.
+pub fn initial_amounts_at_price(
+    amount: u64,
+    amount_is_token_0: bool,
+    price_x32: u128,
+    mint_0_decimals: u8,
+    mint_1_decimals: u8,
+    transfer_fees: &TransferFees,
+) -> Result<(u64, u64)> {
+    if amount_is_token_0 {
+        let vault_amount_0 = amount
+            .checked_sub(transfer_fees.transfer_fee(true, amount)?)
+            .ok_or(GammaError::MathOverflow)?;
+        let vault_amount_1 =
+            token_1_amount_at_price(vault_amount_0, price_x32, mint_0_decimals, mint_1_decimals)?;
+        require_gt!(vault_amount_1, 0, GammaError::InvalidInput);
+        let amount_1 = vault_amount_1
+            .checked_add(transfer_fees.transfer_inverse_fee(false, vault_amount_1)?)
+            .ok_or(GammaError::MathOverflow)?;
+        Ok((amount, amount_1))
+    } else {
+        let vault_amount_1 = amount
+            .checked_sub(transfer_fees.transfer_fee(false, amount)?)
+            .ok_or(GammaError::MathOverflow)?;
+        let vault_amount_0 =
+            token_0_amount_at_price(vault_amount_1, price_x32, mint_0_decimals, mint_1_decimals)?;
+        require_gt!(vault_amount_0, 0, GammaError::InvalidInput);
+        let amount_0 = vault_amount_0
+            .checked_add(transfer_fees.transfer_inverse_fee(true, vault_amount_0)?)
+            .ok_or(GammaError::MathOverflow)?;
+        Ok((amount_0, amount))
+    }
+}
+
+pub fn initialize_with_price<'info>(
+    ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
+    initial_price_x32: u128,
+    amount: u64,
+    amount_is_token_0: bool,
+    open_time: u64,
+    max_trade_fee_rate: u64,
+    volatility_factor: u64,
+) -> Result<()> {
+    require_gt!(initial_price_x32, 0, GammaError::InvalidInput);
+    let mint_0_decimals = ctx.accounts.token_0_mint.decimals;
+    let mint_1_decimals = ctx.accounts.token_1_mint.decimals;
+    let transfer_fees = TransferFees::from_mints(
+        &ctx.accounts.token_0_mint.to_account_info(),
+        &ctx.accounts.token_1_mint.to_account_info(),
+        Clock::get()?.epoch,
+    )?;
+    let (init_amount_0, init_amount_1) = initial_amounts_at_price(
+        amount,
+        amount_is_token_0,
+        initial_price_x32,
+        mint_0_decimals,
+        mint_1_decimals,
+        &transfer_fees,
+    )?;
+
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "initial_price_x32: {}, init_amount_0: {}, init_amount_1: {}",
+        initial_price_x32,
+        init_amount_0,
+        init_amount_1,
+    );
+
+    initialize(
+        ctx,
+        init_amount_0,
+        init_amount_1,
+        open_time,
+        max_trade_fee_rate,
+        volatility_factor,
+        0,
+        0,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+        TransferFee, TransferFeeConfig,
+    };
+
+    /// Two whole token_1 for one whole token_0
+    const PRICE_2_X32: u128 = 2 << 32;
+
+    fn transfer_fee_config(transfer_fee_basis_points: u16) -> TransferFeeConfig {
+        let transfer_fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: u64::MAX.into(),
+            transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        };
+        TransferFeeConfig {
+            older_transfer_fee: transfer_fee,
+            newer_transfer_fee: transfer_fee,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn amounts_at_price_scale_by_the_mint_decimals() {
+        assert_eq!(
+            token_1_amount_at_price(1_000_000, PRICE_2_X32, 6, 9).unwrap(),
+            2_000_000_000
+        );
+        assert_eq!(
+            token_1_amount_at_price(1_000_000_000, PRICE_2_X32, 9, 6).unwrap(),
+            2_000_000
+        );
+        assert_eq!(
+            token_0_amount_at_price(2_000_000_000, PRICE_2_X32, 6, 9).unwrap(),
+            1_000_000
+        );
+        assert_eq!(
+            token_0_amount_at_price(2_000_000, PRICE_2_X32, 9, 6).unwrap(),
+            1_000_000_000
+        );
+    }
+
+    #[test]
+    fn amounts_at_price_round_down() {
+        // A third of a token_1 per token_0, itself rounded down
+        let price_x32 = Q32 / 3;
+        assert_eq!(token_1_amount_at_price(10, price_x32, 6, 6).unwrap(), 3);
+        assert_eq!(token_0_amount_at_price(1, 3 * Q32, 6, 6).unwrap(), 0);
+        // 1999 raw units of a 9 decimals token_0 are 1.999 raw units of a 6 decimals token_1
+        assert_eq!(token_1_amount_at_price(1999, Q32, 9, 6).unwrap(), 1);
+
+        // Converting back never gives more than what was converted
+        for amount_0 in [1, 7, 999, 1_000_001, u64::MAX / 3] {
+            let amount_1 = token_1_amount_at_price(amount_0, price_x32, 9, 6).unwrap();
+            assert!(token_0_amount_at_price(amount_1, price_x32, 9, 6).unwrap() <= amount_0);
+        }
+    }
+
+    #[test]
+    fn amounts_at_extreme_prices_fail_instead_of_truncating() {
+        assert_eq!(
+            token_1_amount_at_price(u64::MAX, Q32, 6, 6).unwrap(),
+            u64::MAX
+        );
+        assert!(token_1_amount_at_price(u64::MAX, Q32 + 1, 6, 6).is_err());
+        assert!(token_1_amount_at_price(u64::MAX, u128::MAX, 0, 18).is_err());
+        assert!(token_0_amount_at_price(u64::MAX, 1, 18, 0).is_err());
+        assert!(token_0_amount_at_price(1, 0, 6, 6).is_err());
+
+        // Mints can declare more decimals than the scale fits in a U256
+        assert!(token_1_amount_at_price(1, Q32, 6, u8::MAX).is_err());
+        assert!(token_0_amount_at_price(1, Q32, u8::MAX, 6).is_err());
+    }
+
+    #[test]
+    fn initial_amounts_at_price_add_the_transfer_fees() {
+        let transfer_fees = TransferFees {
+            token_0: Some(transfer_fee_config(100)),
+            token_1: Some(transfer_fee_config(200)),
+            epoch: 0,
+        };
+
+        // 990_000_000 token_0 land in the vault, matching 1_980_000 token_1
+        let (amount_0, amount_1) =
+            initial_amounts_at_price(1_000_000_000, true, PRICE_2_X32, 9, 6, &transfer_fees)
+                .unwrap();
+        assert_eq!(amount_0, 1_000_000_000);
+        assert_eq!(
+            amount_1 - transfer_fees.transfer_fee(false, amount_1).unwrap(),
+            1_980_000
+        );
+
+        // 1_960_000 token_1 land in the vault, matching 980_000_000 token_0
+        let (amount_0, amount_1) =
+            initial_amounts_at_price(2_000_000, false, PRICE_2_X32, 9, 6, &transfer_fees).unwrap();
+        assert_eq!(amount_1, 2_000_000);
+        assert_eq!(
+            amount_0 - transfer_fees.transfer_fee(true, amount_0).unwrap(),
+            980_000_000
+        );
+
+        assert_eq!(
+            initial_amounts_at_price(
+                1_000_000_000,
+                true,
+                PRICE_2_X32,
+                9,
+                6,
+                &TransferFees::default()
+            )
+            .unwrap(),
+            (1_000_000_000, 2_000_000)
+        );
+        // A counterpart rounding down to nothing can't open the pool
+        assert!(
+            initial_amounts_at_price(1, true, PRICE_2_X32, 9, 6, &TransferFees::default()).is_err()
+        );
+    }
+}
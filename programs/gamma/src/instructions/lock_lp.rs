@@ -0,0 +1,43 @@
+use super::{open_position, OpenPosition};
+use crate::error::GammaError;
+use crate::states::LpLockEvent;
+use anchor_lang::prelude::*;
+
+/// Moves `lp_token_amount` lp tokens of the owner's liquidity into a new position locked until
+/// `unlock_time`. With a `vesting_start`, the LP vests linearly from then to `unlock_time` and
+/// `unlock_lp` releases it as it does, otherwise all of it unlocks at `unlock_time`.
+pub fn lock_lp(
+    ctx: Context<OpenPosition>,
+    position_id: u64,
+    lp_token_amount: u64,
+    vesting_start: u64,
+    unlock_time: u64,
+) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    require_gt!(unlock_time, block_timestamp, GammaError::InvalidInput);
+    if vesting_start != 0 {
+        require_gte!(vesting_start, block_timestamp, GammaError::InvalidInput);
+        require_gt!(unlock_time, vesting_start, GammaError::InvalidInput);
+    }
+
+    // `open_position` leaves the vesting schedule as it is set here
+    let position = &mut ctx.accounts.position;
+    position.vesting_start = vesting_start;
+    position.vesting_lp_tokens = lp_token_amount;
+    let pool_id = ctx.accounts.pool_state.key();
+    let position_key = ctx.accounts.position.key();
+    let owner = ctx.accounts.owner.key();
+    open_position(ctx, position_id, lp_token_amount, unlock_time)?;
+
+    emit!(LpLockEvent {
+        version: LpLockEvent::VERSION,
+        pool_id,
+        position: position_key,
+        owner,
+        locked_lp_tokens: lp_token_amount,
+        released_lp_tokens: 0,
+        vesting_start,
+        unlock_time,
+    });
+    Ok(())
+}
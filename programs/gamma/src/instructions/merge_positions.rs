@@ -0,0 +1,53 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, Position, PositionChangeEvent};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MergePositions<'info> {
+    /// Owner of both positions, receives the rent of the merged one
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The position the other one merges into
+    #[account(
+        mut,
+        has_one = owner @ GammaError::InvalidOwner,
+        has_one = pool_state,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        mut,
+        has_one = owner @ GammaError::InvalidOwner,
+        has_one = pool_state,
+        constraint = merged_position.key() != position.key() @ GammaError::InvalidInput,
+        close = owner,
+    )]
+    pub merged_position: Box<Account<'info, Position>>,
+}
+
+/// Moves the lp tokens and lp fees of `merged_position` into `position` and closes it. The
+/// merged position keeps the later unlock time, so merging never shortens a lock.
+pub fn merge_positions(ctx: Context<MergePositions>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let merged_position = &mut ctx.accounts.merged_position;
+    merged_position.accrue_lp_fees(&pool_state)?;
+    let position = &mut ctx.accounts.position;
+    position.accrue_lp_fees(&pool_state)?;
+    position.merge(merged_position)?;
+
+    emit!(PositionChangeEvent {
+        version: PositionChangeEvent::VERSION,
+        pool_id,
+        position: merged_position.key(),
+        from_owner: merged_position.owner,
+        to_owner: position.owner,
+        lp_tokens: merged_position.lp_tokens,
+        unlock_time: position.unlock_time,
+        change_type: 4,
+    });
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use crate::{
+    error::GammaError,
+    states::{ObservationState, PoolState},
+};
+use anchor_lang::{
+    prelude::*, solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE, system_program,
+    Discriminator,
+};
+
+#[derive(Accounts)]
+pub struct MigrateObservationState<'info> {
+    /// Pays the rent for the extra space, it can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: an AccountLoader would fail for accounts of the older layout, checked in the
+    /// instruction
+    #[account(
+        mut,
+        owner = crate::id(),
+        address = pool_state.load()?.observation_key,
+    )]
+    pub observation_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows an observation account of the layout from before observations kept the log price to
+/// the current one, keeping its observations and archive. Accounts already on the current layout
+/// are left as they are. Archives too large to grow in a single call can only be rebuilt.
+pub fn migrate_observation_state(ctx: Context<MigrateObservationState>) -> Result<()> {
+    let account_info = ctx.accounts.observation_state.to_account_info();
+    {
+        let data = account_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == ObservationState::DISCRIMINATOR,
+            GammaError::InvalidInput
+        );
+        if !ObservationState::is_legacy(&data) {
+            return Ok(());
+        }
+    }
+
+    let cardinality = ObservationState::legacy_cardinality(account_info.data_len());
+    let new_len = ObservationState::space(cardinality);
+    require_gte!(
+        account_info.data_len() + MAX_PERMITTED_DATA_INCREASE,
+        new_len,
+        GammaError::InvalidInput
+    );
+    let minimum_balance = Rent::get()?.minimum_balance(new_len);
+    let lamports_needed = minimum_balance.saturating_sub(account_info.lamports());
+    if lamports_needed > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account_info.clone(),
+                },
+            ),
+            lamports_needed,
+        )?;
+    }
+    account_info.realloc(new_len, true)?;
+
+    let mut data = account_info.try_borrow_mut_data()?;
+    ObservationState::migrate_legacy(&mut data, cardinality)
+}
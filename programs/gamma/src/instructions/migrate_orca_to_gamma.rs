@@ -141,6 +141,7 @@ pub fn migrate(ctx: Context<MigrateOrcaToGamma>, liquidity: u64) -> Result<()> {
     );
 
     emit!(LpChangeEvent {
+        version: LpChangeEvent::VERSION,
         pool_id,
         lp_amount_before: pool_state.lp_supply,
         token_0_vault_before: total_token_0_amount,
@@ -188,6 +189,7 @@ pub fn migrate(ctx: Context<MigrateOrcaToGamma>, liquidity: u64) -> Result<()> {
 
     pool_state.lp_supply = pool_state.lp_supply.checked_add(lp_token_amount).ok_or(GammaError::MathOverflow)?;
     let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
     user_pool_liquidity.token_0_deposited = user_pool_liquidity
         .token_0_deposited
         .checked_add(u128::from(transfer_token_0_amount))
@@ -200,6 +202,7 @@ pub fn migrate(ctx: Context<MigrateOrcaToGamma>, liquidity: u64) -> Result<()> {
         .lp_tokens_owned
         .checked_add(u128::from(lp_token_amount))
         .ok_or(GammaError::MathOverflow)?;
+    user_pool_liquidity.extension.last_deposit_slot = Clock::get()?.slot;
     pool_state.recent_epoch = Clock::get()?.epoch;
 
     Ok(())
@@ -0,0 +1,78 @@
+use crate::{
+    error::GammaError,
+    states::{LpBurnProof, PoolState, LP_BURN_PROOF_SEED, POOL_STATE_VERSION},
+};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+#[derive(Accounts)]
+pub struct MigratePoolState<'info> {
+    /// Pays the rent for the extra space, it can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: an AccountLoader would fail for pools with the old size, checked in the instruction
+    #[account(mut, owner = crate::id())]
+    pub pool_state: UncheckedAccount<'info>,
+
+    /// CHECK: the pool's burn proof, uninitialized for pools that never burned LP
+    #[account(
+        seeds = [
+            LP_BURN_PROOF_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_burn_proof: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a pool created with an older layout to `PoolState::LEN` and sets its new fields to
+/// their defaults. Pools already on `POOL_STATE_VERSION` are left as they are. The LP burned
+/// before version 9 is taken from the pool's burn proof.
+pub fn migrate_pool_state(ctx: Context<MigratePoolState>) -> Result<()> {
+    let account_info = ctx.accounts.pool_state.to_account_info();
+    {
+        let data = account_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == PoolState::DISCRIMINATOR,
+            GammaError::InvalidInput
+        );
+    }
+
+    if account_info.data_len() < PoolState::LEN {
+        let minimum_balance = Rent::get()?.minimum_balance(PoolState::LEN);
+        let lamports_needed = minimum_balance.saturating_sub(account_info.lamports());
+        if lamports_needed > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                ),
+                lamports_needed,
+            )?;
+        }
+        account_info.realloc(PoolState::LEN, true)?;
+    }
+
+    let lp_burn_proof_info = ctx.accounts.lp_burn_proof.to_account_info();
+    let burned_lp = if *lp_burn_proof_info.owner == crate::id() {
+        LpBurnProof::load_checked(&lp_burn_proof_info, account_info.key())?.burned_lp_tokens
+    } else {
+        0
+    };
+
+    let mut data = account_info.try_borrow_mut_data()?;
+    let pool_state: &mut PoolState =
+        bytemuck::from_bytes_mut(&mut data[8..8 + std::mem::size_of::<PoolState>()]);
+    if pool_state.version < POOL_STATE_VERSION {
+        if pool_state.version < 9 {
+            pool_state.burned_lp = burned_lp;
+        }
+        pool_state.migrate();
+    }
+    Ok(())
+}
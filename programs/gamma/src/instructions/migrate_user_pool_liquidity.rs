@@ -0,0 +1,50 @@
+use crate::{error::GammaError, states::UserPoolLiquidity};
+use anchor_lang::{prelude::*, system_program, Discriminator};
+
+#[derive(Accounts)]
+pub struct MigrateUserPoolLiquidity<'info> {
+    /// Pays the rent for the extra space, it can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: deserializing would fail for accounts with the old size, checked in the instruction
+    #[account(mut, owner = crate::id())]
+    pub user_pool_liquidity: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a UserPoolLiquidity account created before the account gained new fields, the new
+/// fields are zero initialized. Deposits and withdrawals grow the account on their own, the
+/// other instructions writing it need it grown first.
+pub fn migrate_user_pool_liquidity(ctx: Context<MigrateUserPoolLiquidity>) -> Result<()> {
+    let account_info = ctx.accounts.user_pool_liquidity.to_account_info();
+    {
+        let data = account_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == UserPoolLiquidity::DISCRIMINATOR,
+            GammaError::InvalidInput
+        );
+    }
+    if account_info.data_len() >= UserPoolLiquidity::LEN {
+        return Ok(());
+    }
+
+    let minimum_balance = Rent::get()?.minimum_balance(UserPoolLiquidity::LEN);
+    let lamports_needed = minimum_balance.saturating_sub(account_info.lamports());
+    if lamports_needed > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account_info.clone(),
+                },
+            ),
+            lamports_needed,
+        )?;
+    }
+    account_info.realloc(UserPoolLiquidity::LEN, true)?;
+
+    Ok(())
+}
@@ -1,21 +1,155 @@
+pub mod add_pool_whitelist_entry;
 pub mod admin;
+pub mod bond_solver;
+pub mod burn_lp_forever;
+pub mod claim_emergency_escrow;
+pub mod claim_lp_fees;
+pub mod claim_rewards;
+pub mod claim_segmenter_kickback;
+pub mod clear_pool_swap_hook;
+pub mod close_pool;
+pub mod close_position;
+pub mod collect_position_lp_fees;
+pub mod crank_fee_apr;
+pub mod crank_fee_report;
+pub mod create_bonding_curve;
+pub mod create_gauge;
+pub mod create_lp_mint;
 pub mod deposit;
+pub mod deposit_single_token;
+pub mod donate;
+pub mod expire_pause;
+pub mod flash_loan;
+pub mod get_locked_liquidity;
+pub mod get_partner_dashboard;
+pub mod get_pool_health;
+pub mod get_pool_price;
+pub mod get_quote;
+pub mod get_twap;
+pub mod graduate;
+pub mod increase_observation_cardinality;
+pub mod init_fee_apr;
+pub mod init_segmenter_kickback;
 pub mod init_user_pool_liquidity;
 pub mod initialize;
+pub mod init_volume_tracker;
+pub mod initialize_with_price;
+pub mod lock_lp;
+pub mod merge_positions;
+pub mod migrate_observation_state;
+pub mod migrate_pool_state;
+pub mod migrate_user_pool_liquidity;
+pub mod open_position;
 // pub mod migrate_orca_to_gamma;
 // pub mod migrate_raydium_to_gamma;
+pub mod preview_liquidity;
 pub mod rebalance;
+pub mod rebalance_strategy;
+pub mod redeem_lp;
+pub mod register_pair_index;
+pub mod remove_pool_whitelist_entry;
+pub mod set_launch_fee;
+pub mod set_pool_swap_hook;
+pub mod set_pool_whitelist_mode;
+pub mod set_weight_schedule;
+pub mod settle_gauge;
+pub mod split_position;
+pub mod stake_lp;
 pub mod swap_base_input;
 pub mod swap_base_output;
+pub mod swap_batch;
+pub mod swap_route;
+pub mod sweep_protocol_fees;
+pub mod tokenize_lp;
+pub mod trade_bonding_curve;
+pub mod transfer_position;
+pub mod transfer_user_position;
+pub mod unlock_lp;
+pub mod unstake_lp;
+pub mod update_lp_metadata;
+pub mod update_max_lp_supply;
+pub mod update_max_trade_size;
+pub mod update_partner_lp_link;
+pub mod update_pool_fee;
+pub mod update_pool_open_time;
+pub mod vote_gauge;
 pub mod withdraw;
+pub mod withdraw_single_token;
 
+pub use add_pool_whitelist_entry::*;
 pub use admin::*;
+pub use bond_solver::*;
+pub use burn_lp_forever::*;
+pub use claim_emergency_escrow::*;
+pub use claim_lp_fees::*;
+pub use claim_rewards::*;
+pub use claim_segmenter_kickback::*;
+pub use clear_pool_swap_hook::*;
+pub use close_pool::*;
+pub use close_position::*;
+pub use collect_position_lp_fees::*;
+pub use crank_fee_apr::*;
+pub use crank_fee_report::*;
+pub use create_bonding_curve::*;
+pub use create_gauge::*;
+pub use create_lp_mint::*;
 pub use deposit::*;
+pub use deposit_single_token::*;
+pub use donate::*;
+pub use expire_pause::*;
+pub use flash_loan::*;
+pub use get_locked_liquidity::*;
+pub use get_partner_dashboard::*;
+pub use get_pool_health::*;
+pub use get_pool_price::*;
+pub use get_quote::*;
+pub use get_twap::*;
+pub use graduate::*;
+pub use increase_observation_cardinality::*;
+pub use init_fee_apr::*;
+pub use init_segmenter_kickback::*;
 pub use init_user_pool_liquidity::*;
 pub use initialize::*;
+pub use init_volume_tracker::*;
+pub use initialize_with_price::*;
+pub use lock_lp::*;
+pub use merge_positions::*;
+pub use migrate_observation_state::*;
+pub use migrate_pool_state::*;
+pub use migrate_user_pool_liquidity::*;
+pub use open_position::*;
 // pub use migrate_orca_to_gamma::*;
 // pub use migrate_raydium_to_gamma::*;
+pub use preview_liquidity::*;
 pub use rebalance::*;
+pub use rebalance_strategy::*;
+pub use redeem_lp::*;
+pub use register_pair_index::*;
+pub use remove_pool_whitelist_entry::*;
+pub use set_launch_fee::*;
+pub use set_pool_swap_hook::*;
+pub use set_pool_whitelist_mode::*;
+pub use set_weight_schedule::*;
+pub use settle_gauge::*;
+pub use split_position::*;
+pub use stake_lp::*;
 pub use swap_base_input::*;
 pub use swap_base_output::*;
+pub use swap_batch::*;
+pub use swap_route::*;
+pub use sweep_protocol_fees::*;
+pub use tokenize_lp::*;
+pub use trade_bonding_curve::*;
+pub use transfer_position::*;
+pub use transfer_user_position::*;
+pub use unlock_lp::*;
+pub use unstake_lp::*;
+pub use update_lp_metadata::*;
+pub use update_max_lp_supply::*;
+pub use update_max_trade_size::*;
+pub use update_partner_lp_link::*;
+pub use update_pool_fee::*;
+pub use update_pool_open_time::*;
+pub use vote_gauge::*;
 pub use withdraw::*;
+pub use withdraw_single_token::*;
@@ -0,0 +1,114 @@
+use crate::error::GammaError;
+use crate::states::{
+    PoolState, Position, PositionChangeEvent, UserPoolLiquidity, POSITION_SEED,
+    USER_POOL_LIQUIDITY_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(position_id: u64)]
+pub struct OpenPosition<'info> {
+    /// Owner of the liquidity moved into the position
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    #[account(
+        init,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &position_id.to_le_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = Position::LEN,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves `lp_token_amount` lp tokens of the owner's liquidity into a new position. The position
+/// can't be closed before `unlock_time`, 0 leaves it unlocked.
+pub fn open_position(
+    ctx: Context<OpenPosition>,
+    position_id: u64,
+    lp_token_amount: u64,
+    unlock_time: u64,
+) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    require_gte!(
+        user_pool_liquidity.unstaked_lp_tokens(),
+        u128::from(lp_token_amount),
+        GammaError::LpTokensStaked
+    );
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
+    user_pool_liquidity.lp_tokens_owned = user_pool_liquidity
+        .lp_tokens_owned
+        .checked_sub(u128::from(lp_token_amount))
+        .ok_or(GammaError::MathOverflow)?;
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
+
+    // Positions aren't linked with partners, their LP leaves the partner of the owner's liquidity
+    if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
+        let mut pool_state_partners = pool_state.partners;
+        if let Some(partner) = pool_state_partners
+            .iter_mut()
+            .find(|p| p.links(user_pool_liquidity_partner))
+        {
+            partner.lp_token_linked_with_partner = partner
+                .lp_token_linked_with_partner
+                .saturating_sub(lp_token_amount);
+        }
+        pool_state.partners = pool_state_partners;
+    }
+
+    let position = &mut ctx.accounts.position;
+    position.bump = ctx.bumps.position;
+    position.pool_state = pool_id;
+    position.owner = ctx.accounts.owner.key();
+    position.creator = ctx.accounts.owner.key();
+    position.position_id = position_id;
+    position.open_time = block_timestamp;
+    position.unlock_time = unlock_time;
+    position.last_deposit_slot = user_pool_liquidity.extension.last_deposit_slot;
+    // Checkpoints the pool's LP fee growth before the position holds any LP
+    position.accrue_lp_fees(pool_state)?;
+    position.lp_tokens = lp_token_amount;
+
+    emit!(PositionChangeEvent {
+        version: PositionChangeEvent::VERSION,
+        pool_id,
+        position: position.key(),
+        from_owner: Pubkey::default(),
+        to_owner: position.owner,
+        lp_tokens: lp_token_amount,
+        unlock_time,
+        change_type: 0,
+    });
+    Ok(())
+}
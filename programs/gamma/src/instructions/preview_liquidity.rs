@@ -0,0 +1,158 @@
+use crate::curve::{CurveCalculator, RoundDirection};
+use crate::error::GammaError;
+use crate::states::{PoolState, PoolStatusBitIndex};
+use crate::utils::{get_transfer_fee, get_transfer_inverse_fee};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+#[derive(Accounts)]
+pub struct PreviewLiquidity<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Rebasing pools read their reserves from the vault balances
+    #[account(address = pool_state.load()?.token_0_vault)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.load()?.token_1_vault)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mints of the vaults, for their transfer fees
+    #[account(address = token_0_vault.mint)]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = token_1_vault.mint)]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Amounts of a deposit or withdrawal of `lp_token_amount`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LiquidityPreview {
+    pub lp_token_amount: u64,
+    /// What enters or leaves the vaults
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    /// What leaves the user's accounts on deposits, transfer fee included, or arrives in them on
+    /// withdrawals, transfer fee excluded
+    pub token_0_user_amount: u64,
+    pub token_1_user_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+}
+
+/// Amounts `deposit` moves for `lp_token_amount`, with the reserves of `pool_state` synced
+pub fn deposit_amounts(
+    pool_state: &PoolState,
+    lp_token_amount: u64,
+    vault_0_mint: &AccountInfo<'_>,
+    vault_1_mint: &AccountInfo<'_>,
+) -> Result<LiquidityPreview> {
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee()?;
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+        RoundDirection::Ceiling,
+    )
+    .ok_or(GammaError::ZeroTradingTokens)?;
+
+    let token_0_amount =
+        u64::try_from(results.token_0_amount).map_err(|_| GammaError::MathOverflow)?;
+    let token_0_transfer_fee = get_transfer_inverse_fee(vault_0_mint, token_0_amount)?;
+    let token_1_amount =
+        u64::try_from(results.token_1_amount).map_err(|_| GammaError::MathOverflow)?;
+    let token_1_transfer_fee = get_transfer_inverse_fee(vault_1_mint, token_1_amount)?;
+    Ok(LiquidityPreview {
+        lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_user_amount: token_0_amount
+            .checked_add(token_0_transfer_fee)
+            .ok_or(GammaError::MathOverflow)?,
+        token_1_user_amount: token_1_amount
+            .checked_add(token_1_transfer_fee)
+            .ok_or(GammaError::MathOverflow)?,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+    })
+}
+
+/// Amounts `withdraw` moves for `lp_token_amount`, with the reserves of `pool_state` synced
+pub fn withdraw_amounts(
+    pool_state: &PoolState,
+    lp_token_amount: u64,
+    vault_0_mint: &AccountInfo<'_>,
+    vault_1_mint: &AccountInfo<'_>,
+) -> Result<LiquidityPreview> {
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee()?;
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+        RoundDirection::Floor,
+    )
+    .ok_or(GammaError::ZeroTradingTokens)?;
+
+    let token_0_amount =
+        u64::try_from(results.token_0_amount).map_err(|_| GammaError::MathOverflow)?;
+    let token_0_amount = std::cmp::min(total_token_0_amount, token_0_amount);
+    let token_0_transfer_fee = get_transfer_fee(vault_0_mint, token_0_amount)?;
+    let token_1_amount =
+        u64::try_from(results.token_1_amount).map_err(|_| GammaError::MathOverflow)?;
+    let token_1_amount = std::cmp::min(total_token_1_amount, token_1_amount);
+    let token_1_transfer_fee = get_transfer_fee(vault_1_mint, token_1_amount)?;
+    Ok(LiquidityPreview {
+        lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_user_amount: token_0_amount
+            .checked_sub(token_0_transfer_fee)
+            .ok_or(GammaError::MathOverflow)?,
+        token_1_user_amount: token_1_amount
+            .checked_sub(token_1_transfer_fee)
+            .ok_or(GammaError::MathOverflow)?,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+    })
+}
+
+/// A copy of the pool with its reserves synced like `deposit` and `withdraw` do
+fn synced_pool_state(ctx: &Context<PreviewLiquidity>) -> Result<PoolState> {
+    let mut pool_state = *ctx.accounts.pool_state.load()?;
+    pool_state.sync_rebasing_reserve(true, ctx.accounts.token_0_vault.amount)?;
+    pool_state.sync_rebasing_reserve(false, ctx.accounts.token_1_vault.amount)?;
+    Ok(pool_state)
+}
+
+pub fn preview_deposit(
+    ctx: Context<PreviewLiquidity>,
+    lp_token_amount: u64,
+) -> Result<LiquidityPreview> {
+    let pool_state = synced_pool_state(&ctx)?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit) {
+        return err!(GammaError::NotApproved);
+    }
+    deposit_amounts(
+        &pool_state,
+        lp_token_amount,
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        &ctx.accounts.vault_1_mint.to_account_info(),
+    )
+}
+
+pub fn preview_withdraw(
+    ctx: Context<PreviewLiquidity>,
+    lp_token_amount: u64,
+) -> Result<LiquidityPreview> {
+    let pool_state = synced_pool_state(&ctx)?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
+        return err!(GammaError::NotApproved);
+    }
+    withdraw_amounts(
+        &pool_state,
+        lp_token_amount,
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        &ctx.accounts.vault_1_mint.to_account_info(),
+    )
+}
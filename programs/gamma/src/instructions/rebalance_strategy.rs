@@ -0,0 +1,212 @@
+use crate::{
+    error::GammaError,
+    external::strategy_adapter::AdapterAccounts,
+    states::{
+        PoolState, StrategyAllowlistEntry, VaultStrategy, VaultStrategyChangeEvent,
+        STRATEGY_ALLOWLIST_SEED, STRATEGY_SEED, VAULT_STRATEGY_SEED,
+    },
+    utils::{deploy_to_strategy, token_balance, withdraw_from_strategy},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct RebalanceStrategy<'info> {
+    /// Anyone can rebalance, the amounts follow from the target the admin set
+    pub signer: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == pool_state.load()?.token_0_vault
+            || vault.key() == pool_state.load()?.token_1_vault @ GammaError::InvalidVault
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = vault.mint)]
+    pub vault_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        constraint = token_program.key() == *vault_mint.to_account_info().owner
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_STRATEGY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            vault.key().as_ref(),
+        ],
+        bump = vault_strategy.bump,
+        has_one = adapter_program,
+    )]
+    pub vault_strategy: Box<Account<'info, VaultStrategy>>,
+
+    /// CHECK: The adapter program of the strategy
+    pub adapter_program: UncheckedAccount<'info>,
+
+    /// CHECK: Allowlist entry of the adapter, an adapter removed from the allowlist isn't called
+    #[account(
+        seeds = [
+            STRATEGY_ALLOWLIST_SEED.as_bytes(),
+            adapter_program.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub allowlist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: strategy authority, signs the adapter calls
+    #[account(
+        seeds = [
+            STRATEGY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            vault_mint.key().as_ref(),
+        ],
+        bump = vault_strategy.authority_bump,
+    )]
+    pub strategy_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = vault_strategy.token_account @ GammaError::InvalidStrategy,
+    )]
+    pub strategy_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Moves the deployed part of the vault's reserve towards its target, then harvests the interest
+/// the position earned into the reserve. A position worth less than what was deployed takes the
+/// loss out of the reserve, the position is never trusted to be worth more than what was
+/// deployed until its interest reaches the vault. The lending protocol accounts of the adapter
+/// are the remaining accounts.
+pub fn rebalance_strategy<'info>(
+    ctx: Context<'_, '_, '_, 'info, RebalanceStrategy<'info>>,
+) -> Result<()> {
+    let vault_strategy = &ctx.accounts.vault_strategy;
+    let vault_key = ctx.accounts.vault.key();
+    let (is_token_0, reserve, auth_bump) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let is_token_0 = vault_key == pool_state.token_0_vault;
+        let reserve = if is_token_0 {
+            pool_state.token_0_vault_amount
+        } else {
+            pool_state.token_1_vault_amount
+        };
+        (is_token_0, reserve, pool_state.auth_bump)
+    };
+    require!(
+        StrategyAllowlistEntry::is_allowlisted(
+            &ctx.accounts.allowlist_entry,
+            ctx.accounts.adapter_program.key(),
+        )?,
+        GammaError::InvalidStrategy
+    );
+    let target = vault_strategy.target_amount(reserve)?;
+
+    let adapter_accounts = AdapterAccounts {
+        strategy_authority: ctx.accounts.strategy_authority.to_account_info(),
+        token_account: ctx.accounts.strategy_token_account.to_account_info(),
+        mint: ctx.accounts.vault_mint.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        adapter_program: ctx.accounts.adapter_program.to_account_info(),
+        protocol_accounts: ctx.remaining_accounts,
+    };
+    let vault_info = ctx.accounts.vault.to_account_info();
+    let mut deployed_amount = vault_strategy.deployed_amount;
+    let balance_before = token_balance(&vault_info)?;
+    let (change_type, amount, mut position_value) = if deployed_amount < target {
+        let position_value = deploy_to_strategy(
+            &adapter_accounts,
+            vault_strategy,
+            &ctx.accounts.authority.to_account_info(),
+            &vault_info,
+            auth_bump,
+            target - deployed_amount,
+        )?;
+        // Transfer fees of the vault mint are deployed along, the vault balance is what left
+        let deposited = balance_before.saturating_sub(token_balance(&vault_info)?);
+        deployed_amount = deployed_amount
+            .checked_add(deposited)
+            .ok_or(GammaError::MathOverflow)?;
+        (0, deposited, position_value)
+    } else {
+        // Withdrawing zero still reports the position for the harvest below
+        let withdraw_amount = deployed_amount - target;
+        let position_value = withdraw_from_strategy(
+            &adapter_accounts,
+            vault_strategy,
+            &vault_info,
+            withdraw_amount,
+        )?;
+        require_gte!(
+            token_balance(&vault_info)?.saturating_sub(balance_before),
+            withdraw_amount,
+            GammaError::StrategyShortfall
+        );
+        deployed_amount -= withdraw_amount;
+        (1, withdraw_amount, position_value)
+    };
+
+    let mut yield_amount = 0;
+    let mut loss_amount = 0;
+    if position_value > deployed_amount {
+        let balance_before = token_balance(&vault_info)?;
+        position_value = withdraw_from_strategy(
+            &adapter_accounts,
+            vault_strategy,
+            &vault_info,
+            position_value - deployed_amount,
+        )?;
+        yield_amount = token_balance(&vault_info)?.saturating_sub(balance_before);
+    } else if position_value < deployed_amount {
+        loss_amount = deployed_amount - position_value;
+        deployed_amount = position_value;
+    }
+    position_value = position_value.min(deployed_amount);
+
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        let reserve = reserve
+            .checked_add(yield_amount)
+            .ok_or(GammaError::MathOverflow)?
+            .checked_sub(loss_amount)
+            .ok_or(GammaError::MathOverflow)?;
+        if is_token_0 {
+            pool_state.token_0_vault_amount = reserve;
+        } else {
+            pool_state.token_1_vault_amount = reserve;
+        }
+    }
+
+    let vault_strategy = &mut ctx.accounts.vault_strategy;
+    vault_strategy.deployed_amount = deployed_amount;
+    vault_strategy.position_value = position_value;
+    vault_strategy.total_yield = vault_strategy
+        .total_yield
+        .checked_add(yield_amount)
+        .ok_or(GammaError::MathOverflow)?;
+
+    emit!(VaultStrategyChangeEvent {
+        version: VaultStrategyChangeEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        vault: vault_key,
+        change_type,
+        amount,
+        deployed_amount,
+        position_value,
+        yield_amount,
+        loss_amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, UserPoolLiquidity, POOL_LP_MINT_SEED, USER_POOL_LIQUIDITY_SEED};
+use crate::utils::token_burn;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RedeemLp<'info> {
+    /// Holder of the LP tokens, the LP moves into their liquidity
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_LP_MINT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The LP tokens are burned from it
+    #[account(mut, token::mint = lp_mint)]
+    pub lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// Burns `lp_token_amount` LP tokens and credits the LP to the owner's liquidity, where it can be
+/// withdrawn
+pub fn redeem_lp(ctx: Context<RedeemLp>, lp_token_amount: u64) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    token_burn(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.lp_token_account.to_account_info(),
+        lp_token_amount,
+        &[],
+    )?;
+
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    pool_state.lp_tokenized = pool_state
+        .lp_tokenized
+        .checked_sub(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
+    user_pool_liquidity.lp_tokens_owned = user_pool_liquidity
+        .lp_tokens_owned
+        .checked_add(u128::from(lp_token_amount))
+        .ok_or(GammaError::MathOverflow)?;
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
+
+    if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
+        let mut pool_state_partners = pool_state.partners;
+        if let Some(partner) = pool_state_partners
+            .iter_mut()
+            .find(|p| p.links(user_pool_liquidity_partner))
+        {
+            partner.lp_token_linked_with_partner = partner
+                .lp_token_linked_with_partner
+                .checked_add(lp_token_amount)
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        pool_state.partners = pool_state_partners;
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::states::{PairIndex, PoolState, PAIR_INDEX_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RegisterPairIndex<'info> {
+    /// Anyone can list a pool in the index of its pair
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: the pool's token_0 mint, only used for the index seeds
+    #[account(address = pool_state.load()?.token_0_mint)]
+    pub token_0_mint: UncheckedAccount<'info>,
+
+    /// CHECK: the pool's token_1 mint, only used for the index seeds
+    #[account(address = pool_state.load()?.token_1_mint)]
+    pub token_1_mint: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            PAIR_INDEX_SEED.as_bytes(),
+            token_0_mint.key().as_ref(),
+            token_1_mint.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PairIndex::LEN,
+    )]
+    pub pair_index: Box<Account<'info, PairIndex>>,
+
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+}
+
+/// Lists a pool created before pair indexes existed, new pools are listed by `initialize`
+pub fn register_pair_index(ctx: Context<RegisterPairIndex>) -> Result<()> {
+    let pair_index = &mut ctx.accounts.pair_index;
+    pair_index.bump = ctx.bumps.pair_index;
+    pair_index.token_0_mint = ctx.accounts.token_0_mint.key();
+    pair_index.token_1_mint = ctx.accounts.token_1_mint.key();
+    pair_index.add_pool(ctx.accounts.pool_state.key())
+}
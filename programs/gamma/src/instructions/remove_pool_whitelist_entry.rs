@@ -0,0 +1,28 @@
+use crate::states::{PoolState, PoolWhitelistEntry, POOL_WHITELIST_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RemovePoolWhitelistEntry<'info> {
+    /// Only the pool creator can manage their whitelist, and gets the rent back
+    #[account(mut)]
+    pub pool_creator: Signer<'info>,
+
+    #[account(has_one = pool_creator)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_WHITELIST_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            whitelist_entry.address.as_ref(),
+        ],
+        bump = whitelist_entry.bump,
+        close = pool_creator,
+    )]
+    pub whitelist_entry: Box<Account<'info, PoolWhitelistEntry>>,
+}
+
+pub fn remove_pool_whitelist_entry(_ctx: Context<RemovePoolWhitelistEntry>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::error::GammaError;
+use crate::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::states::{PoolState, MAX_LAUNCH_FEE_DURATION};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetLaunchFee<'info> {
+    pub pool_creator: Signer<'info>,
+
+    #[account(mut, has_one = pool_creator)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Sets the launch fee of a pool that hasn't opened yet. A `launch_fee_rate` of zero removes it.
+pub fn set_launch_fee(
+    ctx: Context<SetLaunchFee>,
+    launch_fee_rate: u64,
+    launch_fee_duration: u64,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    // Once open, swappers rely on the fee schedule they traded with
+    require_gt!(
+        pool_state.open_time,
+        block_timestamp,
+        GammaError::InvalidOpenTime
+    );
+    require_gt!(
+        FEE_RATE_DENOMINATOR_VALUE,
+        launch_fee_rate,
+        GammaError::InvalidFeeBounds
+    );
+    require_gte!(
+        MAX_LAUNCH_FEE_DURATION,
+        launch_fee_duration,
+        GammaError::InvalidInput
+    );
+    pool_state.launch_fee_rate = launch_fee_rate;
+    pool_state.launch_fee_duration = launch_fee_duration;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::error::GammaError;
+use crate::states::{
+    PoolState, PoolSwapHook, SwapHookAllowlistEntry, POOL_SWAP_HOOK_SEED, SWAP_HOOK_ALLOWLIST_SEED,
+    SWAP_HOOK_EXTENSION,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolSwapHook<'info> {
+    /// The admin or the pool creator
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: The swap hook program, checked against the allowlist
+    pub hook_program: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            SWAP_HOOK_ALLOWLIST_SEED.as_bytes(),
+            hook_program.key().as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Box<Account<'info, SwapHookAllowlistEntry>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            POOL_SWAP_HOOK_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = authority,
+        space = PoolSwapHook::LEN,
+    )]
+    pub pool_swap_hook: Box<Account<'info, PoolSwapHook>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Swaps of the pool call the hook program from now on, and fail without its accounts
+pub fn set_pool_swap_hook(ctx: Context<SetPoolSwapHook>, hook_accounts_len: u8) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == crate::admin::id() || authority == pool_state.pool_creator,
+        GammaError::InvalidOwner
+    );
+    pool_state.set_extension(SWAP_HOOK_EXTENSION, true);
+
+    let pool_swap_hook = &mut ctx.accounts.pool_swap_hook;
+    pool_swap_hook.bump = ctx.bumps.pool_swap_hook;
+    pool_swap_hook.pool_state = ctx.accounts.pool_state.key();
+    pool_swap_hook.hook_program = ctx.accounts.hook_program.key();
+    pool_swap_hook.hook_accounts_len = hook_accounts_len;
+    Ok(())
+}
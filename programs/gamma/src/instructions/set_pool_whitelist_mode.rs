@@ -0,0 +1,23 @@
+use crate::states::{PoolState, DEPOSIT_WHITELIST_EXTENSION, SWAP_WHITELIST_EXTENSION};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPoolWhitelistMode<'info> {
+    /// Only the pool creator can gate their pool
+    pub pool_creator: Signer<'info>,
+
+    #[account(mut, has_one = pool_creator)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Withdrawals are never gated, so liquidity of an address taken off the whitelist can leave
+pub fn set_pool_whitelist_mode(
+    ctx: Context<SetPoolWhitelistMode>,
+    gate_deposits: bool,
+    gate_swaps: bool,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.set_extension(DEPOSIT_WHITELIST_EXTENSION, gate_deposits);
+    pool_state.set_extension(SWAP_WHITELIST_EXTENSION, gate_swaps);
+    Ok(())
+}
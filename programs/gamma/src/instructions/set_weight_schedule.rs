@@ -0,0 +1,52 @@
+use crate::curve::{MAX_WEIGHT, MIN_WEIGHT};
+use crate::error::GammaError;
+use crate::states::PoolState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetWeightSchedule<'info> {
+    pub pool_creator: Signer<'info>,
+
+    #[account(mut, has_one = pool_creator)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Turns a weighted pool that hasn't opened yet into a liquidity bootstrapping pool. Its
+/// token_0 weight moves from the weight it was created with to `token_0_end_weight` between
+/// `weight_schedule_start` and `weight_schedule_end`, e.g. from 95% to 50% for a fair launch.
+pub fn set_weight_schedule(
+    ctx: Context<SetWeightSchedule>,
+    token_0_end_weight: u64,
+    weight_schedule_start: u64,
+    weight_schedule_end: u64,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    // Once open, swappers rely on the weights they traded at
+    require_gt!(
+        pool_state.open_time,
+        block_timestamp,
+        GammaError::InvalidOpenTime
+    );
+    require!(pool_state.is_weighted(), GammaError::InvalidWeight);
+    require!(
+        (MIN_WEIGHT..=MAX_WEIGHT).contains(&token_0_end_weight),
+        GammaError::InvalidWeight
+    );
+    require_gte!(
+        weight_schedule_start,
+        block_timestamp,
+        GammaError::InvalidInput
+    );
+    require_gt!(
+        weight_schedule_end,
+        weight_schedule_start,
+        GammaError::InvalidInput
+    );
+
+    pool_state.token_0_start_weight = pool_state.token_0_weight;
+    pool_state.token_0_end_weight = token_0_end_weight;
+    pool_state.weight_schedule_start = weight_schedule_start;
+    pool_state.weight_schedule_end = weight_schedule_end;
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use crate::states::{Gauge, GaugeController, GaugeSettledEvent, GAUGE_CONTROLLER_SEED, GAUGE_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SettleGauge<'info> {
+    #[account(
+        mut,
+        seeds = [GAUGE_CONTROLLER_SEED.as_bytes()],
+        bump = gauge_controller.bump,
+    )]
+    pub gauge_controller: Box<Account<'info, GaugeController>>,
+
+    #[account(
+        mut,
+        seeds = [
+            GAUGE_SEED.as_bytes(),
+            gauge.pool_state.as_ref(),
+        ],
+        bump = gauge.bump,
+    )]
+    pub gauge: Box<Account<'info, Gauge>>,
+}
+
+pub fn settle_gauge(ctx: Context<SettleGauge>) -> Result<()> {
+    let gauge_controller = &mut ctx.accounts.gauge_controller;
+    let epoch = gauge_controller.epoch_at(Clock::get()?.unix_timestamp as u64);
+    gauge_controller.roll(epoch);
+
+    let gauge = &mut ctx.accounts.gauge;
+    gauge.roll(epoch);
+    gauge.settle(gauge_controller, epoch)?;
+
+    emit!(GaugeSettledEvent {
+        version: GaugeSettledEvent::VERSION,
+        pool_id: gauge.pool_state,
+        epoch,
+        votes: gauge.previous_votes,
+        total_votes: gauge_controller.previous_votes(epoch),
+        emission_rate: gauge.emission_rate,
+    });
+    Ok(())
+}
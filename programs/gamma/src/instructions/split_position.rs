@@ -0,0 +1,66 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, Position, PositionChangeEvent, POSITION_SEED};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(new_position_id: u64)]
+pub struct SplitPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        has_one = owner @ GammaError::InvalidOwner,
+        has_one = pool_state,
+    )]
+    pub position: Box<Account<'info, Position>>,
+
+    #[account(
+        init,
+        seeds = [
+            POSITION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+            &new_position_id.to_le_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = Position::LEN,
+    )]
+    pub new_position: Box<Account<'info, Position>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Carves `lp_token_amount` lp tokens of a position into a new position with the same lock, e.g.
+/// to hand one tranche over and keep the other
+pub fn split_position(
+    ctx: Context<SplitPosition>,
+    new_position_id: u64,
+    lp_token_amount: u64,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let position = &mut ctx.accounts.position;
+    position.accrue_lp_fees(&pool_state)?;
+
+    let new_position = &mut ctx.accounts.new_position;
+    new_position.bump = ctx.bumps.new_position;
+    new_position.creator = ctx.accounts.owner.key();
+    new_position.position_id = new_position_id;
+    position.split_into(new_position, lp_token_amount)?;
+
+    emit!(PositionChangeEvent {
+        version: PositionChangeEvent::VERSION,
+        pool_id,
+        position: new_position.key(),
+        from_owner: position.owner,
+        to_owner: new_position.owner,
+        lp_tokens: lp_token_amount,
+        unlock_time: new_position.unlock_time,
+        change_type: 3,
+    });
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::error::GammaError;
+use crate::states::{
+    PoolRewards, PoolState, UserPoolLiquidity, UserStake, POOL_REWARDS_SEED,
+    USER_POOL_LIQUIDITY_SEED, USER_STAKE_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    /// Owner of the position
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_REWARDS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = pool_rewards.bump,
+    )]
+    pub pool_rewards: Box<Account<'info, PoolRewards>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            USER_STAKE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = UserStake::LEN,
+    )]
+    pub user_stake: Box<Account<'info, UserStake>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Stakes `lp_token_amount` lp tokens of the position for the pool's rewards
+pub fn stake_lp(ctx: Context<StakeLp>, lp_token_amount: u64) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    require_gte!(
+        user_pool_liquidity.unstaked_lp_tokens(),
+        u128::from(lp_token_amount),
+        GammaError::InvalidInput
+    );
+
+    let pool_rewards = &mut ctx.accounts.pool_rewards;
+    pool_rewards.update(block_timestamp)?;
+    let user_stake = &mut ctx.accounts.user_stake;
+    if user_stake.owner == Pubkey::default() {
+        user_stake.bump = ctx.bumps.user_stake;
+        user_stake.pool_state = ctx.accounts.pool_state.key();
+        user_stake.owner = ctx.accounts.owner.key();
+    }
+    user_stake.accrue(pool_rewards, user_pool_liquidity.extension.lp_tokens_staked)?;
+
+    user_pool_liquidity.extension.lp_tokens_staked = user_pool_liquidity
+        .extension
+        .lp_tokens_staked
+        .checked_add(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    pool_rewards.total_staked = pool_rewards
+        .total_staked
+        .checked_add(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    Ok(())
+}
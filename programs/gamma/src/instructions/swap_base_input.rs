@@ -1,14 +1,19 @@
 use crate::curve::calculator::CurveCalculator;
 use crate::curve::TradeDirection;
 use crate::error::GammaError;
-use crate::external::dflow_segmenter::is_invoked_by_segmenter;
 use crate::states::oracle;
 use crate::states::AmmConfig;
 use crate::states::ObservationState;
 use crate::states::PoolState;
-use crate::states::PoolStatusBitIndex;
+use crate::states::PoolLifecycle;
+use crate::states::ReferralPaidEvent;
 use crate::states::SwapEvent;
-use crate::utils::{swap_referral::*, token::*};
+use crate::states::SWAP_HOOK_EXTENSION;
+use crate::states::SWAP_WHITELIST_EXTENSION;
+use crate::utils::{
+    quote_cache::*, segmenter_kickback::*, strategy::*, swap_hook::*, swap_referral::*,
+    token::*, volume_tracker::*,
+};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
@@ -39,9 +44,11 @@ pub struct Swap<'info> {
     #[account(mut)]
     pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// The user token account for output token
+    /// CHECK: The user token account for output token, checked by the token program on transfer.
+    /// Created as the associated token account of the receiver when missing and a rent payer is
+    /// passed, see `SwapRemainingAccounts`
     #[account(mut)]
-    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub output_token_account: UncheckedAccount<'info>,
 
     /// The vault token account for input token
     #[account(
@@ -79,11 +86,52 @@ pub struct Swap<'info> {
     pub observation_state: AccountLoader<'info, ObservationState>,
 }
 
+/// Remaining accounts of a swap at fixed positions, from the registered segmenter to the staked
+/// token account. The accounts of variable length, from the output strategy on, follow them.
+pub const SWAP_FIXED_REMAINING_ACCOUNTS: usize = 19;
+
 pub struct SwapRemainingAccounts<'info> {
     pub registered_segmenter: Option<AccountInfo<'info>>,
+    /// The DFlow registry, or the `SegmenterRegistry` of another order-flow partner
     pub registry: Option<AccountInfo<'info>>,
     pub referral_account: Option<AccountInfo<'info>>,
     pub referral_token_account: Option<AccountInfo<'info>>,
+    pub segmenter_kickback: Option<AccountInfo<'info>>,
+    /// When passed, the output token account must be owned by this account, e.g. the payer
+    pub output_receiver: Option<AccountInfo<'info>>,
+    /// The pool's `VolumeTracker`, the swap only counts towards its volume when passed
+    pub volume_tracker: Option<AccountInfo<'info>>,
+    /// The instructions sysvar, required by pools that only allow one swap per transaction
+    pub instructions_sysvar: Option<AccountInfo<'info>>,
+    /// Signer paying the rent of the output token account when the swap has to create it,
+    /// followed by the associated token program and the system program
+    pub output_account_rent_payer: Option<AccountInfo<'info>>,
+    pub associated_token_program: Option<AccountInfo<'info>>,
+    pub system_program: Option<AccountInfo<'info>>,
+    /// The pool's `PriceBand` and its oracle, required by pools with a price band
+    pub price_band: Option<AccountInfo<'info>>,
+    pub oracle: Option<AccountInfo<'info>>,
+    /// `MintExposure` of the input and output mint, required by pools tracking the mint
+    pub input_mint_exposure: Option<AccountInfo<'info>>,
+    pub output_mint_exposure: Option<AccountInfo<'info>>,
+    /// The pool's `QuoteCache`, required by pools keeping one
+    pub quote_cache: Option<AccountInfo<'info>>,
+    /// The payer's `PoolWhitelistEntry`, required by pools limiting swaps to whitelisted addresses
+    pub whitelist_entry: Option<AccountInfo<'info>>,
+    /// The config's `StakingDiscount` and the payer's token account of its staked mint, for the
+    /// payer's staking discount on the trade fee
+    pub staking_discount: Option<AccountInfo<'info>>,
+    pub staked_token_account: Option<AccountInfo<'info>>,
+    /// Remaining accounts `SWAP_FIXED_REMAINING_ACCOUNTS` onwards: the strategy of the output
+    /// vault, to recall what the vault is missing for the payout, see `StrategyAccounts`
+    pub output_strategy: Option<StrategyAccounts<'info>>,
+    /// The accounts after the output strategy: the pool's swap hook, called with the
+    /// `SwapEvent` once the swap is done, see `SwapHookAccounts`
+    pub swap_hook: Option<SwapHookAccounts<'info>>,
+    /// The accounts after the swap hook: the transfer hook programs of the input and output
+    /// mint, their `TransferHookAllowlistEntry`, validation accounts and the extra accounts
+    /// they list
+    pub transfer_hook_accounts: Vec<AccountInfo<'info>>,
 }
 
 pub fn decode_account_info<'info>(
@@ -105,43 +153,244 @@ pub fn decode_account_info<'info>(
 
 impl<'info> SwapRemainingAccounts<'info> {
     pub fn new(remaining_accounts: &[AccountInfo<'info>]) -> Self {
+        let (output_strategy, after_strategy) = StrategyAccounts::split(
+            remaining_accounts
+                .get(SWAP_FIXED_REMAINING_ACCOUNTS..)
+                .unwrap_or_default(),
+        );
+        let (swap_hook, transfer_hook_accounts) = SwapHookAccounts::split(after_strategy);
         Self {
             registered_segmenter: decode_account_info(remaining_accounts, 0),
             registry: decode_account_info(remaining_accounts, 1),
             referral_account: decode_account_info(remaining_accounts, 2),
             referral_token_account: decode_account_info(remaining_accounts, 3),
+            segmenter_kickback: decode_account_info(remaining_accounts, 4),
+            output_receiver: decode_account_info(remaining_accounts, 5),
+            volume_tracker: decode_account_info(remaining_accounts, 6),
+            instructions_sysvar: decode_account_info(remaining_accounts, 7),
+            output_account_rent_payer: decode_account_info(remaining_accounts, 8),
+            associated_token_program: decode_account_info(remaining_accounts, 9),
+            system_program: decode_account_info(remaining_accounts, 10),
+            price_band: decode_account_info(remaining_accounts, 11),
+            oracle: decode_account_info(remaining_accounts, 12),
+            input_mint_exposure: decode_account_info(remaining_accounts, 13),
+            output_mint_exposure: decode_account_info(remaining_accounts, 14),
+            quote_cache: decode_account_info(remaining_accounts, 15),
+            whitelist_entry: decode_account_info(remaining_accounts, 16),
+            staking_discount: decode_account_info(remaining_accounts, 17),
+            staked_token_account: decode_account_info(remaining_accounts, 18),
+            output_strategy,
+            swap_hook,
+            transfer_hook_accounts: transfer_hook_accounts.to_vec(),
+        }
+    }
+
+    /// Lets custodial or compliance-bound integrators pin who a swap can pay out to
+    pub fn check_output_receiver(&self, output_token_account: &AccountInfo<'info>) -> Result<()> {
+        if let Some(output_receiver) = &self.output_receiver {
+            let output_token_account =
+                TokenAccount::try_deserialize(&mut &output_token_account.try_borrow_data()?[..])?;
+            require_keys_eq!(
+                *output_receiver.key,
+                output_token_account.owner,
+                GammaError::InvalidOutputReceiver
+            );
         }
+        Ok(())
+    }
+
+    /// Creates the output token account when it doesn't exist yet and a rent payer is passed,
+    /// so routers don't need a separate create account instruction. It is created as the
+    /// associated token account of the output receiver, or of the payer.
+    pub fn create_output_token_account(&self, accounts: &Swap<'info>) -> Result<()> {
+        let output_token_account = accounts.output_token_account.to_account_info();
+        let Some(rent_payer) = &self.output_account_rent_payer else {
+            return Ok(());
+        };
+        if !output_token_account.data_is_empty() {
+            return Ok(());
+        }
+        let (Some(associated_token_program), Some(system_program)) =
+            (&self.associated_token_program, &self.system_program)
+        else {
+            return err!(GammaError::InvalidInput);
+        };
+        let owner = match &self.output_receiver {
+            Some(output_receiver) => output_receiver.clone(),
+            None => accounts.payer.to_account_info(),
+        };
+        create_associated_token_account_idempotent(
+            rent_payer,
+            &output_token_account,
+            &owner,
+            &accounts.output_token_mint.to_account_info(),
+            system_program,
+            &accounts.output_token_program.to_account_info(),
+            associated_token_program,
+        )
+    }
+
+    /// Blunts sandwiches bundled as separate swaps of a single transaction
+    pub fn check_duplicate_swap(&self, pool_state: &AccountLoader<'info, PoolState>) -> Result<()> {
+        crate::utils::check_duplicate_swap(
+            &*pool_state.load()?,
+            &pool_state.key(),
+            self.instructions_sysvar.as_ref(),
+        )
+    }
+
+    /// Lets pool creators limit swaps to addresses they approved
+    pub fn check_whitelisted(
+        &self,
+        pool_state: &PoolState,
+        pool_id: Pubkey,
+        payer: Pubkey,
+    ) -> Result<()> {
+        crate::utils::check_whitelisted(
+            pool_state,
+            pool_id,
+            SWAP_WHITELIST_EXTENSION,
+            payer,
+            self.whitelist_entry.as_ref(),
+        )
+    }
+
+    /// Kickback rate of the registered segmenter that signed the swap, if any, see
+    /// `registered_segmenter_kickback_bps`
+    pub fn registered_segmenter_kickback_bps(&self, config_kickback_bps: u64) -> Option<u64> {
+        let (Some(registry), Some(segmenter)) = (&self.registry, &self.registered_segmenter) else {
+            return None;
+        };
+        crate::utils::registered_segmenter_kickback_bps(registry, segmenter, config_kickback_bps)
+    }
+
+    /// Trade fee discount of the payer's GOFX staking tier
+    pub fn staking_discount_bps(&self, amm_config: Pubkey, payer: Pubkey) -> Result<u16> {
+        crate::utils::staking_discount_bps(
+            amm_config,
+            payer,
+            self.staking_discount.as_ref(),
+            self.staked_token_account.as_ref(),
+        )
+    }
+
+    /// Protects pools with a price band from being pushed far off the oracle price
+    pub fn check_price_band(
+        &self,
+        pool_state: &PoolState,
+        pool_id: Pubkey,
+        token_0_price_x32_before: u128,
+    ) -> Result<()> {
+        crate::utils::check_price_band(
+            pool_state,
+            pool_id,
+            self.price_band.as_ref(),
+            self.oracle.as_ref(),
+            token_0_price_x32_before,
+        )
+    }
+
+    /// Keeps the reserves of capped mints held across the pools tracking them under the cap
+    pub fn update_mint_exposures(
+        &self,
+        pool_state: &PoolState,
+        input_is_token_0: bool,
+        reserves_before: (u64, u64),
+    ) -> Result<()> {
+        let (token_0_mint_exposure, token_1_mint_exposure) = if input_is_token_0 {
+            (&self.input_mint_exposure, &self.output_mint_exposure)
+        } else {
+            (&self.output_mint_exposure, &self.input_mint_exposure)
+        };
+        crate::utils::update_mint_exposures(
+            pool_state,
+            reserves_before,
+            token_0_mint_exposure.as_ref(),
+            token_1_mint_exposure.as_ref(),
+        )
     }
 }
 
+/// Exact results of a swap, returned via return data so programs swapping by CPI don't have to
+/// parse logs or diff token balances
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapOutcome {
+    /// Taken from the input token account, transfer fees and referral payouts included
+    pub amount_in: u64,
+    /// Received by the output token account, net of transfer fees
+    pub amount_out: u64,
+    /// Trade fee charged on the input, before the referral share
+    pub dynamic_fee: u64,
+    /// Price of token_0 in token_1 after the swap, Q32.32
+    pub token_0_price_x32: u128,
+}
+
 pub fn swap_base_input<'c, 'info>(
     ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
     amount_in: u64,
     minimum_amount_out: u64,
-) -> Result<()> {
-    let swap_remaining_accounts = SwapRemainingAccounts::new(&ctx.remaining_accounts);
-    let referral_info = extract_referral_info(
-        ctx.accounts.input_token_mint.key(),
-        ctx.accounts.amm_config.referral_project,
-        &swap_remaining_accounts.referral_account,
-        &swap_remaining_accounts.referral_token_account,
-    )?;
+) -> Result<SwapOutcome> {
+    SwapRemainingAccounts::new(ctx.remaining_accounts)
+        .check_duplicate_swap(&ctx.accounts.pool_state)?;
+    execute_swap_base_input(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        amount_in,
+        minimum_amount_out,
+        true,
+    )
+}
+
+/// Swaps `amount_in`, `SwapOutcome::amount_out` being what the output token account received.
+/// Callers reporting the swap with an event of their own skip the `SwapEvent`.
+pub fn execute_swap_base_input<'info>(
+    accounts: &mut Swap<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount_in: u64,
+    minimum_amount_out: u64,
+    emit_swap_event: bool,
+) -> Result<SwapOutcome> {
+    let swap_remaining_accounts = SwapRemainingAccounts::new(remaining_accounts);
+    swap_remaining_accounts.create_output_token_account(accounts)?;
+    swap_remaining_accounts.check_output_receiver(&accounts.output_token_account)?;
+    let referral_info = if accounts.amm_config.disable_referrals {
+        None
+    } else {
+        let referral_mint = if accounts.amm_config.referral_payout_in_output {
+            accounts.output_token_mint.key()
+        } else {
+            accounts.input_token_mint.key()
+        };
+        extract_referral_info(
+            referral_mint,
+            accounts.amm_config.referral_project,
+            &swap_remaining_accounts.referral_account,
+            &swap_remaining_accounts.referral_token_account,
+        )?
+    };
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
-    let pool_id = ctx.accounts.pool_state.key();
-    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
-        || block_timestamp < pool_state.open_time
-    {
+    let pool_id = accounts.pool_state.key();
+    let mut pool_state_data = accounts.pool_state.load_mut()?;
+    let pool_state = &mut *pool_state_data;
+    if pool_state.lifecycle(block_timestamp) != PoolLifecycle::Open {
         return err!(GammaError::NotApproved);
     }
+    swap_remaining_accounts.check_whitelisted(pool_state, pool_id, accounts.payer.key())?;
+
+    let reserves_before = pool_state.vault_amount_without_fee()?;
+    // Pools of rebasing mints first take in what the vaults gained or lost since the last update
+    let input_is_token_0 = accounts.input_vault.key() == pool_state.token_0_vault;
+    pool_state.sync_rebasing_reserve(input_is_token_0, accounts.input_vault.amount)?;
+    pool_state.sync_rebasing_reserve(!input_is_token_0, accounts.output_vault.amount)?;
+    pool_state.update_weights(block_timestamp);
 
     let (token_0_price_x64_before_swap, token_1_price_x64_before_swap) =
-        if ctx.accounts.input_vault.key() == pool_state.token_0_vault
-            && ctx.accounts.output_vault.key() == pool_state.token_1_vault
+        if accounts.input_vault.key() == pool_state.token_0_vault
+            && accounts.output_vault.key() == pool_state.token_1_vault
         {
             pool_state.token_price_x32()?
-        } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
-            && ctx.accounts.output_vault.key() == pool_state.token_0_vault
+        } else if accounts.input_vault.key() == pool_state.token_1_vault
+            && accounts.output_vault.key() == pool_state.token_0_vault
         {
             pool_state.token_price_x32()?
         } else {
@@ -149,15 +398,15 @@ pub fn swap_base_input<'c, 'info>(
         };
 
     let transfer_fee =
-        get_transfer_fee(&ctx.accounts.input_token_mint.to_account_info(), amount_in)?;
+        get_transfer_fee(&accounts.input_token_mint.to_account_info(), amount_in)?;
     // Take transfer fees into account for actual amount transferred in
     let mut actual_amount_in = amount_in.saturating_sub(transfer_fee);
-    require_gt!(actual_amount_in, 0);
+    require_gt!(actual_amount_in, 0, GammaError::AmountTooSmallAfterFees);
 
     // Calculate the trade amounts
     let (trade_direction, total_input_token_amount, total_output_token_amount) =
-        if ctx.accounts.input_vault.key() == pool_state.token_0_vault
-            && ctx.accounts.output_vault.key() == pool_state.token_1_vault
+        if accounts.input_vault.key() == pool_state.token_0_vault
+            && accounts.output_vault.key() == pool_state.token_1_vault
         {
             let (total_input_token_amount, total_output_token_amount) =
                 pool_state.vault_amount_without_fee()?;
@@ -167,8 +416,8 @@ pub fn swap_base_input<'c, 'info>(
                 total_input_token_amount,
                 total_output_token_amount,
             )
-        } else if ctx.accounts.input_vault.key() == pool_state.token_1_vault
-            && ctx.accounts.output_vault.key() == pool_state.token_0_vault
+        } else if accounts.input_vault.key() == pool_state.token_1_vault
+            && accounts.output_vault.key() == pool_state.token_0_vault
         {
             let (total_output_token_amount, total_input_token_amount) =
                 pool_state.vault_amount_without_fee()?;
@@ -181,39 +430,37 @@ pub fn swap_base_input<'c, 'info>(
         } else {
             return err!(GammaError::InvalidVault);
         };
+    if !pool_state.swap_direction_enabled(trade_direction) {
+        return err!(GammaError::NotApproved);
+    }
+    pool_state.check_trade_size(actual_amount_in, total_input_token_amount)?;
     let constant_before = u128::from(total_input_token_amount)
         .checked_mul(u128::from(total_output_token_amount))
         .ok_or(GammaError::MathOverflow)?;
 
-    let mut observation_state = ctx.accounts.observation_state.load_mut()?;
+    let observation_info = accounts.observation_state.to_account_info();
+    let (mut observation_state, mut observation_archive) =
+        ObservationState::load_mut_with_archive(&observation_info)?;
 
-    let mut is_invoked_by_signed_segmenter = false;
+    let segmenter_kickback_bps = swap_remaining_accounts
+        .registered_segmenter_kickback_bps(accounts.amm_config.segmenter_kickback_bps);
+    let is_invoked_by_signed_segmenter = segmenter_kickback_bps.is_some();
 
-    if swap_remaining_accounts.registered_segmenter.is_some()
-        && swap_remaining_accounts.registry.is_some()
-    {
-        is_invoked_by_signed_segmenter = is_invoked_by_segmenter(
-            &swap_remaining_accounts.registry.as_ref().unwrap(),
-            &swap_remaining_accounts
-                .registered_segmenter
-                .as_ref()
-                .unwrap(),
-        );
-    }
+    let trade_fee_discount_bps = swap_remaining_accounts
+        .staking_discount_bps(accounts.amm_config.key(), accounts.payer.key())?;
 
-    let result = match CurveCalculator::swap_base_input(
+    let result = CurveCalculator::swap_base_input(
         u128::from(actual_amount_in),
         u128::from(total_input_token_amount),
         u128::from(total_output_token_amount),
-        &ctx.accounts.amm_config,
+        trade_direction,
+        &accounts.amm_config,
         &pool_state,
         block_timestamp,
         &observation_state,
         is_invoked_by_signed_segmenter,
-    ) {
-        Ok(value) => value,
-        Err(_) => return err!(GammaError::ZeroTradingTokens),
-    };
+        trade_fee_discount_bps,
+    )?;
 
     let constant_after = u128::from(
         result
@@ -245,13 +492,13 @@ pub fn swap_base_input<'c, 'info>(
             Err(_) => return err!(GammaError::MathOverflow),
         };
         let transfer_fee = get_transfer_fee(
-            &ctx.accounts.output_token_mint.to_account_info(),
+            &accounts.output_token_mint.to_account_info(),
             amount_out,
         )?;
         let amount_received = amount_out
             .checked_sub(transfer_fee)
             .ok_or(GammaError::MathOverflow)?;
-        require_gt!(amount_received, 0);
+        require_gt!(amount_received, 0, GammaError::AmountTooSmallAfterFees);
         require_gte!(
             amount_received,
             minimum_amount_out,
@@ -259,12 +506,15 @@ pub fn swap_base_input<'c, 'info>(
         );
         (amount_out, transfer_fee)
     };
+    let amount_received = output_transfer_amount - output_transfer_fee;
 
     let protocol_fee = u64::try_from(result.protocol_fee).or(err!(GammaError::MathOverflow))?;
     let fund_fee = u64::try_from(result.fund_fee).or(err!(GammaError::MathOverflow))?;
     let mut dynamic_fee = u64::try_from(result.dynamic_fee).or(err!(GammaError::MathOverflow))?;
 
     let mut transfer_referral_amount = None;
+    // What the output vault pays the referrer when referrers are paid in the output token
+    let mut output_referral_amount = 0;
     if let Some(ref info) = referral_info {
         let referral_amount = dynamic_fee
             .saturating_sub(protocol_fee)
@@ -273,28 +523,66 @@ pub fn swap_base_input<'c, 'info>(
             .ok_or(GammaError::MathOverflow)?
             .checked_div(10_000)
             .unwrap_or(0);
+        let payout_in_output = accounts.amm_config.referral_payout_in_output;
+        let (referral_mint, referral_payout) = if payout_in_output {
+            (
+                &accounts.output_token_mint,
+                referral_amount_in_output(
+                    referral_amount,
+                    source_amount_swapped,
+                    output_transfer_amount,
+                )?,
+            )
+        } else {
+            (&accounts.input_token_mint, referral_amount)
+        };
 
-        let referral_transfer_fee = get_transfer_fee(
-            &ctx.accounts.input_token_mint.to_account_info(),
-            referral_amount,
-        )?;
+        let referral_transfer_fee =
+            get_transfer_fee(&referral_mint.to_account_info(), referral_payout)?;
 
         // We are aware of the fact that when referral fees are very small the referee will not get any tokens
-        if referral_amount != 0 && referral_transfer_fee < referral_amount {
+        if referral_payout != 0 && referral_transfer_fee < referral_payout {
+            emit!(ReferralPaidEvent {
+                version: ReferralPaidEvent::VERSION,
+                pool_id,
+                referrer: info.referrer,
+                project: accounts.amm_config.referral_project,
+                mint: referral_mint.key(),
+                amount: referral_payout,
+                trade_amount: source_amount_swapped,
+            });
             // subtract referral amount from dynamic fee and transfer amount
             dynamic_fee = dynamic_fee
                 .checked_sub(referral_amount)
                 .ok_or(GammaError::MathError)?;
-            input_transfer_amount = input_transfer_amount
-                .checked_sub(referral_amount)
-                .ok_or(GammaError::MathError)?;
-            actual_amount_in = actual_amount_in
-                .checked_sub(referral_amount)
-                .ok_or(GammaError::MathError)?;
+            if payout_in_output {
+                // The whole input goes to the vault, which pays the referrer out of the output side
+                output_referral_amount = referral_payout;
+            } else {
+                input_transfer_amount = input_transfer_amount
+                    .checked_sub(referral_amount)
+                    .ok_or(GammaError::MathError)?;
+                actual_amount_in = actual_amount_in
+                    .checked_sub(referral_amount)
+                    .ok_or(GammaError::MathError)?;
+            }
 
-            transfer_referral_amount = Some(referral_amount)
+            transfer_referral_amount = Some(referral_payout)
         }
     }
+    // Registered segmenters routing the swap earn a share of the dynamic fee, paid out of its LP
+    // portion
+    let mut segmenter_kickback_amount = 0;
+    if is_invoked_by_signed_segmenter && swap_remaining_accounts.segmenter_kickback.is_some() {
+        segmenter_kickback_amount = calculate_segmenter_kickback(
+            dynamic_fee,
+            dynamic_fee
+                .saturating_sub(protocol_fee)
+                .saturating_sub(fund_fee),
+            segmenter_kickback_bps.unwrap_or_default(),
+        )?;
+    }
+
     // Save fees metric for the pool partners.
     let mut partners = pool_state.partners;
     for partner in partners.iter_mut() {
@@ -344,6 +632,14 @@ pub fn swap_base_input<'c, 'info>(
                 .cumulative_trade_fees_token_0
                 .checked_add((dynamic_fee) as u128)
                 .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_protocol_fees_token_0 = pool_state
+                .cumulative_protocol_fees_token_0
+                .checked_add(u128::from(protocol_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_fund_fees_token_0 = pool_state
+                .cumulative_fund_fees_token_0
+                .checked_add(u128::from(fund_fee))
+                .ok_or(GammaError::MathOverflow)?;
             pool_state.cumulative_volume_token_0 = pool_state
                 .cumulative_volume_token_0
                 .checked_add(actual_amount_in as u128)
@@ -356,10 +652,18 @@ pub fn swap_base_input<'c, 'info>(
                 .checked_sub(fund_fee)
                 .ok_or(GammaError::MathOverflow)?
                 .checked_sub(protocol_fee)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_sub(segmenter_kickback_amount)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.segmenter_kickbacks_token_0 = pool_state
+                .segmenter_kickbacks_token_0
+                .checked_add(segmenter_kickback_amount)
                 .ok_or(GammaError::MathOverflow)?;
             pool_state.token_1_vault_amount = pool_state
                 .token_1_vault_amount
                 .checked_sub(output_transfer_amount)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_sub(output_referral_amount)
                 .ok_or(GammaError::MathOverflow)?;
         }
         TradeDirection::OneForZero => {
@@ -375,6 +679,14 @@ pub fn swap_base_input<'c, 'info>(
                 .cumulative_trade_fees_token_1
                 .checked_add((dynamic_fee) as u128)
                 .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_protocol_fees_token_1 = pool_state
+                .cumulative_protocol_fees_token_1
+                .checked_add(u128::from(protocol_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_fund_fees_token_1 = pool_state
+                .cumulative_fund_fees_token_1
+                .checked_add(u128::from(fund_fee))
+                .ok_or(GammaError::MathOverflow)?;
             pool_state.cumulative_volume_token_1 = pool_state
                 .cumulative_volume_token_1
                 .checked_add(actual_amount_in as u128)
@@ -387,52 +699,155 @@ pub fn swap_base_input<'c, 'info>(
                 .checked_sub(fund_fee)
                 .ok_or(GammaError::MathOverflow)?
                 .checked_sub(protocol_fee)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_sub(segmenter_kickback_amount)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.segmenter_kickbacks_token_1 = pool_state
+                .segmenter_kickbacks_token_1
+                .checked_add(segmenter_kickback_amount)
                 .ok_or(GammaError::MathOverflow)?;
             pool_state.token_0_vault_amount = pool_state
                 .token_0_vault_amount
                 .checked_sub(output_transfer_amount)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_sub(output_referral_amount)
                 .ok_or(GammaError::MathOverflow)?;
         }
     };
+    let lp_fee = dynamic_fee
+        .saturating_sub(protocol_fee)
+        .saturating_sub(fund_fee)
+        .saturating_sub(segmenter_kickback_amount);
+    pool_state.accrue_lp_fee(trade_direction == TradeDirection::ZeroForOne, lp_fee)?;
+    pool_state.check_price_bounds(token_0_price_x64_before_swap)?;
+    pool_state.check_slot_price_impact(Clock::get()?.slot, token_0_price_x64_before_swap)?;
+    swap_remaining_accounts.check_price_band(
+        &pool_state,
+        pool_id,
+        token_0_price_x64_before_swap,
+    )?;
+    pool_state.check_twap_deviation(
+        &observation_state,
+        &observation_archive,
+        block_timestamp,
+        token_0_price_x64_before_swap,
+        token_1_price_x64_before_swap,
+    )?;
+    swap_remaining_accounts.update_mint_exposures(&pool_state, input_is_token_0, reserves_before)?;
     pool_state.latest_dynamic_fee_rate = result.dynamic_fee_rate;
+    refresh_quote_cache(
+        &pool_state,
+        pool_id,
+        swap_remaining_accounts.quote_cache.as_ref(),
+    )?;
 
-    emit!(SwapEvent {
+    if segmenter_kickback_amount > 0 {
+        let (token_0_kickback, token_1_kickback) = match trade_direction {
+            TradeDirection::ZeroForOne => (segmenter_kickback_amount, 0),
+            TradeDirection::OneForZero => (0, segmenter_kickback_amount),
+        };
+        accrue_segmenter_kickback(
+            swap_remaining_accounts.segmenter_kickback.as_ref().unwrap(),
+            pool_id,
+            swap_remaining_accounts
+                .registered_segmenter
+                .as_ref()
+                .unwrap()
+                .key(),
+            token_0_kickback,
+            token_1_kickback,
+        )?;
+    }
+
+    if let Some(volume_tracker) = &swap_remaining_accounts.volume_tracker {
+        record_tracked_volume(
+            volume_tracker,
+            pool_id,
+            accounts.payer.key(),
+            trade_direction == TradeDirection::ZeroForOne,
+            actual_amount_in,
+            block_timestamp,
+        )?;
+    }
+
+    let input_amount = match u64::try_from(result.source_amount_swapped) {
+        Ok(value) => value,
+        Err(_) => return err!(GammaError::MathOverflow),
+    };
+    let output_amount = match u64::try_from(result.destination_amount_swapped) {
+        Ok(value) => value,
+        Err(_) => return err!(GammaError::MathOverflow),
+    };
+    let (vault_0_after, vault_1_after) = pool_state.vault_amount_without_fee()?;
+    // Built without the event too, it is what the pool's swap hook is called with
+    let swap_event = SwapEvent {
+        version: SwapEvent::VERSION,
         pool_id,
         input_vault_before: total_input_token_amount,
         output_vault_before: total_output_token_amount,
-        input_amount: match u64::try_from(result.source_amount_swapped) {
-            Ok(value) => value,
-            Err(_) => return err!(GammaError::MathOverflow),
-        },
-        output_amount: match u64::try_from(result.destination_amount_swapped) {
-            Ok(value) => value,
-            Err(_) => return err!(GammaError::MathOverflow),
-        },
-        input_mint: ctx.accounts.input_vault.mint,
-        output_mint: ctx.accounts.output_vault.mint,
+        input_amount,
+        output_amount,
+        input_mint: accounts.input_vault.mint,
+        output_mint: accounts.output_vault.mint,
         input_transfer_fee,
         output_transfer_fee,
         base_input: true,
-        dynamic_fee: result.dynamic_fee
-    });
-    require_gte!(constant_after, constant_before);
-    transfer_from_user_to_pool_vault(
-        ctx.accounts.payer.to_account_info(),
-        ctx.accounts.input_token_account.to_account_info(),
-        ctx.accounts.input_vault.to_account_info(),
-        ctx.accounts.input_token_mint.to_account_info(),
-        ctx.accounts.input_token_program.to_account_info(),
+        dynamic_fee: result.dynamic_fee,
+        input_ui_adjusted_amount: get_ui_adjusted_amount(
+            &pool_state,
+            input_is_token_0,
+            Some(&accounts.input_token_mint.to_account_info()),
+            input_amount,
+        )?,
+        output_ui_adjusted_amount: get_ui_adjusted_amount(
+            &pool_state,
+            !input_is_token_0,
+            Some(&accounts.output_token_mint.to_account_info()),
+            output_amount,
+        )?,
+        vault_0_after,
+        vault_1_after,
+        lp_supply: pool_state.lp_supply,
+        dynamic_fee_rate: result.dynamic_fee_rate,
+        amm_config_index: accounts.amm_config.index,
+    };
+    if emit_swap_event {
+        emit!(swap_event);
+    }
+    // The stable and weighted curves keep invariants of their own, which x * y doesn't follow
+    if pool_state.is_constant_product() {
+        require_gte!(constant_after, constant_before);
+    }
+    // Whatever the output vault pays out beyond its buffer comes back from its strategy first
+    recall_for_payout(
+        swap_remaining_accounts.output_strategy.as_ref(),
+        pool_id,
+        &accounts.output_vault.to_account_info(),
+        &accounts.output_token_mint.to_account_info(),
+        &accounts.output_token_program.to_account_info(),
+        output_transfer_amount
+            .checked_add(output_referral_amount)
+            .ok_or(GammaError::MathOverflow)?,
+    )?;
+    transfer_from_user_to_pool_vault_with_hook(
+        accounts.payer.to_account_info(),
+        accounts.input_token_account.to_account_info(),
+        accounts.input_vault.to_account_info(),
+        accounts.input_token_mint.to_account_info(),
+        accounts.input_token_program.to_account_info(),
+        &swap_remaining_accounts.transfer_hook_accounts,
         input_transfer_amount,
-        ctx.accounts.input_token_mint.decimals,
+        accounts.input_token_mint.decimals,
     )?;
-    transfer_from_pool_vault_to_user(
-        ctx.accounts.authority.to_account_info(),
-        ctx.accounts.output_vault.to_account_info(),
-        ctx.accounts.output_token_account.to_account_info(),
-        ctx.accounts.output_token_mint.to_account_info(),
-        ctx.accounts.output_token_program.to_account_info(),
+    transfer_from_pool_vault_to_user_with_hook(
+        accounts.authority.to_account_info(),
+        accounts.output_vault.to_account_info(),
+        accounts.output_token_account.to_account_info(),
+        accounts.output_token_mint.to_account_info(),
+        accounts.output_token_program.to_account_info(),
+        &swap_remaining_accounts.transfer_hook_accounts,
         output_transfer_amount,
-        ctx.accounts.output_token_mint.decimals,
+        accounts.output_token_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
     )?;
 
@@ -442,31 +857,64 @@ pub fn swap_base_input<'c, 'info>(
     // Hence:
     // (0) is user->vault token transfer, 
     // (1) is vault->user token transfer, 
-    // (2) is(optionally) user->referrer token transfer
+    // (2) is(optionally) user->referrer, or vault->referrer, token transfer
     if let Some(amount) = transfer_referral_amount {
         let info = referral_info.expect("referral_info to be non-null");
-        anchor_spl::token_2022::transfer_checked(
-            CpiContext::new(
-                ctx.accounts.input_token_program.to_account_info(),
-                anchor_spl::token_2022::TransferChecked {
-                    from: ctx.accounts.input_token_account.to_account_info(),
-                    to: info.referral_token_account.to_account_info(),
-                    authority: ctx.accounts.payer.to_account_info(),
-                    mint: ctx.accounts.input_token_mint.to_account_info(),
-                },
-            ),
-            amount,
-            ctx.accounts.input_token_mint.decimals,
-        )?;
+        if output_referral_amount > 0 {
+            transfer_from_pool_vault_to_user_with_hook(
+                accounts.authority.to_account_info(),
+                accounts.output_vault.to_account_info(),
+                info.referral_token_account.to_account_info(),
+                accounts.output_token_mint.to_account_info(),
+                accounts.output_token_program.to_account_info(),
+                &swap_remaining_accounts.transfer_hook_accounts,
+                amount,
+                accounts.output_token_mint.decimals,
+                &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+            )?;
+        } else {
+            transfer_from_user_to_pool_vault_with_hook(
+                accounts.payer.to_account_info(),
+                accounts.input_token_account.to_account_info(),
+                info.referral_token_account.to_account_info(),
+                accounts.input_token_mint.to_account_info(),
+                accounts.input_token_program.to_account_info(),
+                &swap_remaining_accounts.transfer_hook_accounts,
+                amount,
+                accounts.input_token_mint.decimals,
+            )?;
+        }
     }
 
-    observation_state.update(
+    observation_state.update_with_archive(
+        &mut observation_archive,
+        oracle::block_timestamp()?,
+        token_0_price_x64_before_swap,
+        token_1_price_x64_before_swap,
+    )?;
+    pool_state.update_ema_price(
         oracle::block_timestamp()?,
         token_0_price_x64_before_swap,
         token_1_price_x64_before_swap,
     )?;
 
     pool_state.recent_epoch = Clock::get()?.epoch;
+    let token_0_price_x32 = pool_state.token_price_x32()?.0;
+    let pool_has_swap_hook = pool_state.has_extension(SWAP_HOOK_EXTENSION);
+    // The hook can take the pool state as one of its accounts, it is released before the call
+    drop(pool_state_data);
+    call_swap_hook(
+        swap_remaining_accounts.swap_hook.as_ref(),
+        pool_has_swap_hook,
+        pool_id,
+        &accounts.payer.to_account_info(),
+        &swap_event,
+    )?;
 
-    Ok(())
+    Ok(SwapOutcome {
+        amount_in,
+        amount_out: amount_received,
+        dynamic_fee: u64::try_from(result.dynamic_fee).or(err!(GammaError::MathOverflow))?,
+        token_0_price_x32,
+    })
 }
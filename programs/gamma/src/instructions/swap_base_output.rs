@@ -1,9 +1,13 @@
-use super::swap_base_input::Swap;
+use super::swap_base_input::{Swap, SwapOutcome};
 use crate::curve::{calculator::CurveCalculator, TradeDirection};
 use crate::error::GammaError;
-use crate::external::dflow_segmenter::is_invoked_by_segmenter;
-use crate::states::{oracle, PoolStatusBitIndex, SwapEvent};
-use crate::utils::{swap_referral::*, token::*};
+use crate::states::{
+    oracle, ObservationState, PoolLifecycle, ReferralPaidEvent, SwapEvent, SWAP_HOOK_EXTENSION,
+};
+use crate::utils::{
+    quote_cache::*, segmenter_kickback::*, strategy::*, swap_hook::*, swap_referral::*,
+    token::*, volume_tracker::*,
+};
 use crate::SwapRemainingAccounts;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
@@ -12,22 +16,41 @@ pub fn swap_base_output<'c, 'info>(
     ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
     max_amount_in: u64,
     amount_out_less_fee: u64,
-) -> Result<()> {
+) -> Result<SwapOutcome> {
     let swap_remaining_accounts = SwapRemainingAccounts::new(&ctx.remaining_accounts);
-    let referral_info = extract_referral_info(
-        ctx.accounts.input_token_mint.key(),
-        ctx.accounts.amm_config.referral_project,
-        &swap_remaining_accounts.referral_account,
-        &swap_remaining_accounts.referral_token_account,
-    )?;
+    swap_remaining_accounts.create_output_token_account(&ctx.accounts)?;
+    swap_remaining_accounts.check_output_receiver(&ctx.accounts.output_token_account)?;
+    swap_remaining_accounts.check_duplicate_swap(&ctx.accounts.pool_state)?;
+    let referral_info = if ctx.accounts.amm_config.disable_referrals {
+        None
+    } else {
+        let referral_mint = if ctx.accounts.amm_config.referral_payout_in_output {
+            ctx.accounts.output_token_mint.key()
+        } else {
+            ctx.accounts.input_token_mint.key()
+        };
+        extract_referral_info(
+            referral_mint,
+            ctx.accounts.amm_config.referral_project,
+            &swap_remaining_accounts.referral_account,
+            &swap_remaining_accounts.referral_token_account,
+        )?
+    };
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
     let pool_id = ctx.accounts.pool_state.key();
-    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
-        || block_timestamp < pool_state.open_time
-    {
+    let mut pool_state_data = ctx.accounts.pool_state.load_mut()?;
+    let pool_state = &mut *pool_state_data;
+    if pool_state.lifecycle(block_timestamp) != PoolLifecycle::Open {
         return err!(GammaError::NotApproved);
     }
+    swap_remaining_accounts.check_whitelisted(pool_state, pool_id, ctx.accounts.payer.key())?;
+
+    let reserves_before = pool_state.vault_amount_without_fee()?;
+    // Pools of rebasing mints first take in what the vaults gained or lost since the last update
+    let input_is_token_0 = ctx.accounts.input_vault.key() == pool_state.token_0_vault;
+    pool_state.sync_rebasing_reserve(input_is_token_0, ctx.accounts.input_vault.amount)?;
+    pool_state.sync_rebasing_reserve(!input_is_token_0, ctx.accounts.output_vault.amount)?;
+    pool_state.update_weights(block_timestamp);
 
     let (token_0_price_x64_before_swap, token_1_price_x64_before_swap) =
         if ctx.accounts.input_vault.key() == pool_state.token_0_vault
@@ -77,38 +100,37 @@ pub fn swap_base_output<'c, 'info>(
         } else {
             return err!(GammaError::InvalidVault);
         };
+    if !pool_state.swap_direction_enabled(trade_direction) {
+        return err!(GammaError::NotApproved);
+    }
+    pool_state.check_trade_size(actual_amount_out, total_output_token_amount)?;
     let constant_before = u128::from(total_input_token_amount)
         .checked_mul(u128::from(total_output_token_amount))
         .ok_or(GammaError::MathOverflow)?;
 
-    let mut observation_state = ctx.accounts.observation_state.load_mut()?;
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let (mut observation_state, mut observation_archive) =
+        ObservationState::load_mut_with_archive(&observation_info)?;
 
-    let mut is_invoked_by_signed_segmenter = false;
-    if swap_remaining_accounts.registered_segmenter.is_some()
-        && swap_remaining_accounts.registry.is_some()
-    {
-        is_invoked_by_signed_segmenter = is_invoked_by_segmenter(
-            &swap_remaining_accounts.registry.as_ref().unwrap(),
-            &swap_remaining_accounts
-                .registered_segmenter
-                .as_ref()
-                .unwrap(),
-        );
-    }
+    let segmenter_kickback_bps = swap_remaining_accounts
+        .registered_segmenter_kickback_bps(ctx.accounts.amm_config.segmenter_kickback_bps);
+    let is_invoked_by_signed_segmenter = segmenter_kickback_bps.is_some();
 
-    let result = match CurveCalculator::swap_base_output(
+    let trade_fee_discount_bps = swap_remaining_accounts
+        .staking_discount_bps(ctx.accounts.amm_config.key(), ctx.accounts.payer.key())?;
+
+    let result = CurveCalculator::swap_base_output(
         u128::from(actual_amount_out),
         u128::from(total_input_token_amount),
         u128::from(total_output_token_amount),
+        trade_direction,
         &ctx.accounts.amm_config,
         &pool_state,
         block_timestamp,
         &observation_state,
         is_invoked_by_signed_segmenter,
-    ) {
-        Ok(value) => value,
-        Err(_) => return err!(GammaError::ZeroTradingTokens),
-    };
+        trade_fee_discount_bps,
+    )?;
 
     let constant_after = u128::from(
         result
@@ -156,6 +178,8 @@ pub fn swap_base_output<'c, 'info>(
     };
     require_eq!(destination_amount_swapped, actual_amount_out);
     let (output_transfer_amount, output_transfer_fee) = (actual_amount_out, out_transfer_fee);
+    // What the user pays in total, part of it may go to the referrer below
+    let amount_in = input_transfer_amount;
 
     let protocol_fee = u64::try_from(result.protocol_fee).or(err!(GammaError::MathOverflow))?;
     let fund_fee = u64::try_from(result.fund_fee).or(err!(GammaError::MathOverflow))?;
@@ -165,6 +189,8 @@ pub fn swap_base_output<'c, 'info>(
         u64::try_from(result.source_amount_swapped).or(err!(GammaError::MathOverflow))?;
 
     let mut transfer_referral_amount = None;
+    // What the output vault pays the referrer when referrers are paid in the output token
+    let mut output_referral_amount = 0;
     if let Some(ref info) = referral_info {
         let referral_amount = dynamic_fee
             .saturating_sub(protocol_fee)
@@ -173,29 +199,67 @@ pub fn swap_base_output<'c, 'info>(
             .ok_or(GammaError::MathOverflow)?
             .checked_div(10_000)
             .unwrap_or(0);
+        let payout_in_output = ctx.accounts.amm_config.referral_payout_in_output;
+        let (referral_mint, referral_payout) = if payout_in_output {
+            (
+                &ctx.accounts.output_token_mint,
+                referral_amount_in_output(
+                    referral_amount,
+                    source_amount_swapped,
+                    output_transfer_amount,
+                )?,
+            )
+        } else {
+            (&ctx.accounts.input_token_mint, referral_amount)
+        };
 
-        let referral_transfer_fee = get_transfer_fee(
-            &ctx.accounts.input_token_mint.to_account_info(),
-            referral_amount,
-        )?;
+        let referral_transfer_fee =
+            get_transfer_fee(&referral_mint.to_account_info(), referral_payout)?;
 
         // We are aware of the fact that when referral fees are very small the referee will not get any tokens
-        if referral_amount != 0 && referral_transfer_fee < referral_amount {
+        if referral_payout != 0 && referral_transfer_fee < referral_payout {
+            emit!(ReferralPaidEvent {
+                version: ReferralPaidEvent::VERSION,
+                pool_id,
+                referrer: info.referrer,
+                project: ctx.accounts.amm_config.referral_project,
+                mint: referral_mint.key(),
+                amount: referral_payout,
+                trade_amount: source_amount_swapped,
+            });
             // subtract referral amount from dynamic fee and transfer amount
             dynamic_fee = dynamic_fee
                 .checked_sub(referral_amount)
                 .ok_or(GammaError::MathError)?;
-            input_transfer_amount = input_transfer_amount
-                .checked_sub(referral_amount)
-                .ok_or(GammaError::MathError)?;
-            source_amount_swapped = source_amount_swapped
-                .checked_sub(referral_amount)
-                .ok_or(GammaError::MathError)?;
+            if payout_in_output {
+                // The whole input goes to the vault, which pays the referrer out of the output side
+                output_referral_amount = referral_payout;
+            } else {
+                input_transfer_amount = input_transfer_amount
+                    .checked_sub(referral_amount)
+                    .ok_or(GammaError::MathError)?;
+                source_amount_swapped = source_amount_swapped
+                    .checked_sub(referral_amount)
+                    .ok_or(GammaError::MathError)?;
+            }
 
-            transfer_referral_amount = Some(referral_amount)
+            transfer_referral_amount = Some(referral_payout)
         }
     }
 
+    // Registered segmenters routing the swap earn a share of the dynamic fee, paid out of its LP
+    // portion
+    let mut segmenter_kickback_amount = 0;
+    if is_invoked_by_signed_segmenter && swap_remaining_accounts.segmenter_kickback.is_some() {
+        segmenter_kickback_amount = calculate_segmenter_kickback(
+            dynamic_fee,
+            dynamic_fee
+                .saturating_sub(protocol_fee)
+                .saturating_sub(fund_fee),
+            segmenter_kickback_bps.unwrap_or_default(),
+        )?;
+    }
+
     // Save fees metric for the pool partners.
     let mut partners = pool_state.partners;
     for partner in partners.iter_mut() {
@@ -245,6 +309,14 @@ pub fn swap_base_output<'c, 'info>(
                 .cumulative_trade_fees_token_0
                 .checked_add(dynamic_fee as u128)
                 .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_protocol_fees_token_0 = pool_state
+                .cumulative_protocol_fees_token_0
+                .checked_add(u128::from(protocol_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_fund_fees_token_0 = pool_state
+                .cumulative_fund_fees_token_0
+                .checked_add(u128::from(fund_fee))
+                .ok_or(GammaError::MathOverflow)?;
             pool_state.cumulative_volume_token_0 = pool_state
                 .cumulative_volume_token_0
                 .checked_add(source_amount_swapped as u128)
@@ -257,11 +329,19 @@ pub fn swap_base_output<'c, 'info>(
                 .checked_sub(fund_fee)
                 .ok_or(GammaError::MathOverflow)?
                 .checked_sub(protocol_fee)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_sub(segmenter_kickback_amount)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.segmenter_kickbacks_token_0 = pool_state
+                .segmenter_kickbacks_token_0
+                .checked_add(segmenter_kickback_amount)
                 .ok_or(GammaError::MathOverflow)?;
 
             pool_state.token_1_vault_amount = pool_state
                 .token_1_vault_amount
                 .checked_sub(output_transfer_amount)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_sub(output_referral_amount)
                 .ok_or(GammaError::MathOverflow)?;
         }
         TradeDirection::OneForZero => {
@@ -277,6 +357,14 @@ pub fn swap_base_output<'c, 'info>(
                 .cumulative_trade_fees_token_1
                 .checked_add(dynamic_fee as u128)
                 .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_protocol_fees_token_1 = pool_state
+                .cumulative_protocol_fees_token_1
+                .checked_add(u128::from(protocol_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_fund_fees_token_1 = pool_state
+                .cumulative_fund_fees_token_1
+                .checked_add(u128::from(fund_fee))
+                .ok_or(GammaError::MathOverflow)?;
             pool_state.cumulative_volume_token_1 = pool_state
                 .cumulative_volume_token_1
                 .checked_add(source_amount_swapped as u128)
@@ -289,53 +377,153 @@ pub fn swap_base_output<'c, 'info>(
                 .checked_sub(fund_fee)
                 .ok_or(GammaError::MathOverflow)?
                 .checked_sub(protocol_fee)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_sub(segmenter_kickback_amount)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.segmenter_kickbacks_token_1 = pool_state
+                .segmenter_kickbacks_token_1
+                .checked_add(segmenter_kickback_amount)
                 .ok_or(GammaError::MathOverflow)?;
 
             pool_state.token_0_vault_amount = pool_state
                 .token_0_vault_amount
                 .checked_sub(output_transfer_amount)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_sub(output_referral_amount)
                 .ok_or(GammaError::MathOverflow)?;
         }
     };
+    let lp_fee = dynamic_fee
+        .saturating_sub(protocol_fee)
+        .saturating_sub(fund_fee)
+        .saturating_sub(segmenter_kickback_amount);
+    pool_state.accrue_lp_fee(trade_direction == TradeDirection::ZeroForOne, lp_fee)?;
+    pool_state.check_price_bounds(token_0_price_x64_before_swap)?;
+    pool_state.check_slot_price_impact(Clock::get()?.slot, token_0_price_x64_before_swap)?;
+    swap_remaining_accounts.check_price_band(
+        &pool_state,
+        pool_id,
+        token_0_price_x64_before_swap,
+    )?;
+    pool_state.check_twap_deviation(
+        &observation_state,
+        &observation_archive,
+        block_timestamp,
+        token_0_price_x64_before_swap,
+        token_1_price_x64_before_swap,
+    )?;
+    swap_remaining_accounts.update_mint_exposures(&pool_state, input_is_token_0, reserves_before)?;
     pool_state.latest_dynamic_fee_rate = result.dynamic_fee_rate;
+    refresh_quote_cache(
+        &pool_state,
+        pool_id,
+        swap_remaining_accounts.quote_cache.as_ref(),
+    )?;
+
+    if segmenter_kickback_amount > 0 {
+        let (token_0_kickback, token_1_kickback) = match trade_direction {
+            TradeDirection::ZeroForOne => (segmenter_kickback_amount, 0),
+            TradeDirection::OneForZero => (0, segmenter_kickback_amount),
+        };
+        accrue_segmenter_kickback(
+            swap_remaining_accounts.segmenter_kickback.as_ref().unwrap(),
+            pool_id,
+            swap_remaining_accounts
+                .registered_segmenter
+                .as_ref()
+                .unwrap()
+                .key(),
+            token_0_kickback,
+            token_1_kickback,
+        )?;
+    }
 
-    emit!(SwapEvent {
+    if let Some(volume_tracker) = &swap_remaining_accounts.volume_tracker {
+        record_tracked_volume(
+            volume_tracker,
+            pool_id,
+            ctx.accounts.payer.key(),
+            trade_direction == TradeDirection::ZeroForOne,
+            source_amount_swapped,
+            block_timestamp,
+        )?;
+    }
+
+    let input_amount = match u64::try_from(result.source_amount_swapped) {
+        Ok(value) => value,
+        Err(_) => return err!(GammaError::MathOverflow),
+    };
+    let output_amount = match u64::try_from(result.destination_amount_swapped) {
+        Ok(value) => value,
+        Err(_) => return err!(GammaError::MathOverflow),
+    };
+    let (vault_0_after, vault_1_after) = pool_state.vault_amount_without_fee()?;
+    let swap_event = SwapEvent {
+        version: SwapEvent::VERSION,
         pool_id,
         input_vault_before: total_input_token_amount,
         output_vault_before: total_output_token_amount,
-        input_amount: match u64::try_from(result.source_amount_swapped) {
-            Ok(value) => value,
-            Err(_) => return err!(GammaError::MathOverflow),
-        },
-        output_amount: match u64::try_from(result.destination_amount_swapped) {
-            Ok(value) => value,
-            Err(_) => return err!(GammaError::MathOverflow),
-        },
+        input_amount,
+        output_amount,
         input_mint: ctx.accounts.input_vault.mint,
         output_mint: ctx.accounts.output_vault.mint,
         input_transfer_fee,
         output_transfer_fee,
         base_input: false,
         dynamic_fee: result.dynamic_fee,
-    });
-    require_gte!(constant_after, constant_before);
+        input_ui_adjusted_amount: get_ui_adjusted_amount(
+            &pool_state,
+            input_is_token_0,
+            Some(&ctx.accounts.input_token_mint.to_account_info()),
+            input_amount,
+        )?,
+        output_ui_adjusted_amount: get_ui_adjusted_amount(
+            &pool_state,
+            !input_is_token_0,
+            Some(&ctx.accounts.output_token_mint.to_account_info()),
+            output_amount,
+        )?,
+        vault_0_after,
+        vault_1_after,
+        lp_supply: pool_state.lp_supply,
+        dynamic_fee_rate: result.dynamic_fee_rate,
+        amm_config_index: ctx.accounts.amm_config.index,
+    };
+    emit!(swap_event);
+    // The stable and weighted curves keep invariants of their own, which x * y doesn't follow
+    if pool_state.is_constant_product() {
+        require_gte!(constant_after, constant_before);
+    }
 
-    transfer_from_user_to_pool_vault(
+    // Whatever the output vault pays out beyond its buffer comes back from its strategy first
+    recall_for_payout(
+        swap_remaining_accounts.output_strategy.as_ref(),
+        pool_id,
+        &ctx.accounts.output_vault.to_account_info(),
+        &ctx.accounts.output_token_mint.to_account_info(),
+        &ctx.accounts.output_token_program.to_account_info(),
+        output_transfer_amount
+            .checked_add(output_referral_amount)
+            .ok_or(GammaError::MathOverflow)?,
+    )?;
+    transfer_from_user_to_pool_vault_with_hook(
         ctx.accounts.payer.to_account_info(),
         ctx.accounts.input_token_account.to_account_info(),
         ctx.accounts.input_vault.to_account_info(),
         ctx.accounts.input_token_mint.to_account_info(),
         ctx.accounts.input_token_program.to_account_info(),
+        &swap_remaining_accounts.transfer_hook_accounts,
         input_transfer_amount,
         ctx.accounts.input_token_mint.decimals,
     )?;
 
-    transfer_from_pool_vault_to_user(
+    transfer_from_pool_vault_to_user_with_hook(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.output_vault.to_account_info(),
         ctx.accounts.output_token_account.to_account_info(),
         ctx.accounts.output_token_mint.to_account_info(),
         ctx.accounts.output_token_program.to_account_info(),
+        &swap_remaining_accounts.transfer_hook_accounts,
         output_transfer_amount,
         ctx.accounts.output_token_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
@@ -347,30 +535,63 @@ pub fn swap_base_output<'c, 'info>(
     // Hence:
     // (0) is user->vault token transfer, 
     // (1) is vault->user token transfer, 
-    // (2) is(optionally) user->referrer token transfer
+    // (2) is(optionally) user->referrer, or vault->referrer, token transfer
     if let Some(amount) = transfer_referral_amount {
         let info = referral_info.expect("referral_info to be non-null");
-        anchor_spl::token_2022::transfer_checked(
-            CpiContext::new(
+        if output_referral_amount > 0 {
+            transfer_from_pool_vault_to_user_with_hook(
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.output_vault.to_account_info(),
+                info.referral_token_account.to_account_info(),
+                ctx.accounts.output_token_mint.to_account_info(),
+                ctx.accounts.output_token_program.to_account_info(),
+                &swap_remaining_accounts.transfer_hook_accounts,
+                amount,
+                ctx.accounts.output_token_mint.decimals,
+                &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+            )?;
+        } else {
+            transfer_from_user_to_pool_vault_with_hook(
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.input_token_account.to_account_info(),
+                info.referral_token_account.to_account_info(),
+                ctx.accounts.input_token_mint.to_account_info(),
                 ctx.accounts.input_token_program.to_account_info(),
-                anchor_spl::token_2022::TransferChecked {
-                    from: ctx.accounts.input_token_account.to_account_info(),
-                    to: info.referral_token_account.to_account_info(),
-                    authority: ctx.accounts.payer.to_account_info(),
-                    mint: ctx.accounts.input_token_mint.to_account_info(),
-                },
-            ),
-            amount,
-            ctx.accounts.input_token_mint.decimals,
-        )?;
+                &swap_remaining_accounts.transfer_hook_accounts,
+                amount,
+                ctx.accounts.input_token_mint.decimals,
+            )?;
+        }
     }
 
-    observation_state.update(
+    observation_state.update_with_archive(
+        &mut observation_archive,
+        oracle::block_timestamp()?,
+        token_0_price_x64_before_swap,
+        token_1_price_x64_before_swap,
+    )?;
+    pool_state.update_ema_price(
         oracle::block_timestamp()?,
         token_0_price_x64_before_swap,
         token_1_price_x64_before_swap,
     )?;
     pool_state.recent_epoch = Clock::get()?.epoch;
+    let token_0_price_x32 = pool_state.token_price_x32()?.0;
+    let pool_has_swap_hook = pool_state.has_extension(SWAP_HOOK_EXTENSION);
+    // The hook can take the pool state as one of its accounts, it is released before the call
+    drop(pool_state_data);
+    call_swap_hook(
+        swap_remaining_accounts.swap_hook.as_ref(),
+        pool_has_swap_hook,
+        pool_id,
+        &ctx.accounts.payer.to_account_info(),
+        &swap_event,
+    )?;
 
-    Ok(())
+    Ok(SwapOutcome {
+        amount_in,
+        amount_out: amount_out_less_fee,
+        dynamic_fee: u64::try_from(result.dynamic_fee).or(err!(GammaError::MathOverflow))?,
+        token_0_price_x32,
+    })
 }
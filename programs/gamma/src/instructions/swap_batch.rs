@@ -0,0 +1,143 @@
+use crate::error::GammaError;
+use crate::instructions::{execute_swap_base_input, Swap, SwapBumps};
+use crate::router_metadata::SWAP_REQUIRED_ACCOUNTS;
+use crate::states::SwapBatchEvent;
+use crate::utils::{check_duplicate_swap, check_no_swap_hook};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use std::collections::BTreeSet;
+
+pub const MAX_SWAP_BATCH_LEGS: usize = 8;
+
+/// One swap of a `swap_batch`, like a `swap_base_input`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapBatchLeg {
+    /// Sells token_0 for token_1 when true, token_1 for token_0 otherwise
+    pub zero_for_one: bool,
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+#[derive(Accounts)]
+pub struct SwapBatch<'info> {
+    /// The user performing the swaps, must be the payer of the pool accounts
+    pub payer: Signer<'info>,
+
+    /// CHECK: the instructions sysvar, required by pools that only allow one swap per
+    /// transaction. Those pools only take batches of a single leg.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+/// The pool is passed as remaining accounts laid out like the accounts of a `swap_base_input`
+/// selling token_0 for token_1. Legs selling token_1 use the same accounts with the input and
+/// output sides switched.
+pub fn swap_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapBatch<'info>>,
+    legs: Vec<SwapBatchLeg>,
+) -> Result<()> {
+    require!(
+        (1..=MAX_SWAP_BATCH_LEGS).contains(&legs.len()),
+        GammaError::InvalidInput
+    );
+    let remaining_accounts = ctx.remaining_accounts;
+    require_eq!(
+        remaining_accounts.len(),
+        SWAP_REQUIRED_ACCOUNTS,
+        GammaError::InvalidInput
+    );
+
+    // The constraints of `Swap` are checked once, switching the sides of every pair keeps them
+    let mut pool_accounts = remaining_accounts;
+    let accounts = Swap::try_accounts(
+        &crate::id(),
+        &mut pool_accounts,
+        &[],
+        &mut SwapBumps::default(),
+        &mut BTreeSet::new(),
+    )?;
+    require_keys_eq!(
+        accounts.payer.key(),
+        ctx.accounts.payer.key(),
+        GammaError::InvalidInput
+    );
+    let pool_id = accounts.pool_state.key();
+    {
+        let pool_state = accounts.pool_state.load()?;
+        require_keys_eq!(
+            accounts.input_vault.key(),
+            pool_state.token_0_vault,
+            GammaError::InvalidVault
+        );
+        check_no_swap_hook(&pool_state)?;
+        check_duplicate_swap(
+            &pool_state,
+            &pool_id,
+            ctx.accounts
+                .instructions_sysvar
+                .as_ref()
+                .map(|instructions_sysvar| instructions_sysvar.as_ref()),
+        )?;
+    }
+
+    let mut event = SwapBatchEvent {
+        version: SwapBatchEvent::VERSION,
+        pool_id,
+        payer: ctx.accounts.payer.key(),
+        legs: legs.len() as u8,
+        token_0_amount_in: 0,
+        token_1_amount_in: 0,
+        token_0_amount_out: 0,
+        token_1_amount_out: 0,
+    };
+    for leg in legs {
+        // Each leg reloads the accounts, to see the balances left by the previous one
+        let mut accounts = leg_accounts(remaining_accounts, leg.zero_for_one)?;
+        let amount_out = execute_swap_base_input(
+            &mut accounts,
+            &[],
+            leg.amount_in,
+            leg.minimum_amount_out,
+            false,
+        )?
+        .amount_out;
+        let (amount_in_total, amount_out_total) = if leg.zero_for_one {
+            (&mut event.token_0_amount_in, &mut event.token_1_amount_out)
+        } else {
+            (&mut event.token_1_amount_in, &mut event.token_0_amount_out)
+        };
+        *amount_in_total = amount_in_total
+            .checked_add(leg.amount_in)
+            .ok_or(GammaError::MathOverflow)?;
+        *amount_out_total = amount_out_total
+            .checked_add(amount_out)
+            .ok_or(GammaError::MathOverflow)?;
+    }
+
+    emit!(event);
+    Ok(())
+}
+
+/// `Swap` accounts of a leg, out of the accounts of a swap selling token_0
+fn leg_accounts<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    zero_for_one: bool,
+) -> Result<Swap<'info>> {
+    // Offsets of the input and output side within each token_0, token_1 pair
+    let (input, output) = if zero_for_one { (0, 1) } else { (1, 0) };
+    Ok(Swap {
+        payer: Signer::try_from(&accounts[0])?,
+        authority: UncheckedAccount::try_from(&accounts[1]),
+        amm_config: Box::new(Account::try_from(&accounts[2])?),
+        pool_state: AccountLoader::try_from(&accounts[3])?,
+        input_token_account: Box::new(InterfaceAccount::try_from(&accounts[4 + input])?),
+        output_token_account: UncheckedAccount::try_from(&accounts[4 + output]),
+        input_vault: Box::new(InterfaceAccount::try_from(&accounts[6 + input])?),
+        output_vault: Box::new(InterfaceAccount::try_from(&accounts[6 + output])?),
+        input_token_program: Interface::try_from(&accounts[8 + input])?,
+        output_token_program: Interface::try_from(&accounts[8 + output])?,
+        input_token_mint: Box::new(InterfaceAccount::try_from(&accounts[10 + input])?),
+        output_token_mint: Box::new(InterfaceAccount::try_from(&accounts[10 + output])?),
+        observation_state: AccountLoader::try_from(&accounts[12])?,
+    })
+}
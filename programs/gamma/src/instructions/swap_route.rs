@@ -0,0 +1,90 @@
+use crate::error::GammaError;
+use crate::instructions::{execute_swap_base_input, Swap, SwapBumps};
+use crate::router_metadata::SWAP_REQUIRED_ACCOUNTS;
+use crate::utils::{check_duplicate_swap, check_no_swap_hook};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use std::collections::BTreeSet;
+
+pub const MIN_ROUTE_HOPS: usize = 2;
+pub const MAX_ROUTE_HOPS: usize = 3;
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    /// The user performing the swaps, must be the payer of every hop
+    pub payer: Signer<'info>,
+
+    /// CHECK: the instructions sysvar, required to route through pools that only allow one swap
+    /// per transaction
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+/// Each hop is passed as remaining accounts laid out like the accounts of `swap_base_input`.
+/// The output token account of a hop must be the input token account of the next one.
+pub fn swap_route<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    let remaining_accounts = ctx.remaining_accounts;
+    require_eq!(
+        remaining_accounts.len() % SWAP_REQUIRED_ACCOUNTS,
+        0,
+        GammaError::InvalidRoute
+    );
+    let hops = remaining_accounts.len() / SWAP_REQUIRED_ACCOUNTS;
+    require!(
+        (MIN_ROUTE_HOPS..=MAX_ROUTE_HOPS).contains(&hops),
+        GammaError::InvalidRoute
+    );
+
+    let mut hop_amount_in = amount_in;
+    let mut previous_output_token_account = None;
+    for hop_accounts in remaining_accounts.chunks(SWAP_REQUIRED_ACCOUNTS) {
+        // Hops are loaded one at a time so each sees the token balances left by the previous one
+        let mut hop_accounts = hop_accounts;
+        let mut accounts = Swap::try_accounts(
+            &crate::id(),
+            &mut hop_accounts,
+            &[],
+            &mut SwapBumps::default(),
+            &mut BTreeSet::new(),
+        )?;
+        require_keys_eq!(
+            accounts.payer.key(),
+            ctx.accounts.payer.key(),
+            GammaError::InvalidRoute
+        );
+        if let Some(previous_output_token_account) = previous_output_token_account {
+            require_keys_eq!(
+                accounts.input_token_account.key(),
+                previous_output_token_account,
+                GammaError::InvalidRoute
+            );
+        }
+        previous_output_token_account = Some(accounts.output_token_account.key());
+        let pool_state = accounts.pool_state.load()?;
+        check_no_swap_hook(&pool_state)?;
+        check_duplicate_swap(
+            &pool_state,
+            &accounts.pool_state.key(),
+            ctx.accounts
+                .instructions_sysvar
+                .as_ref()
+                .map(|instructions_sysvar| instructions_sysvar.as_ref()),
+        )?;
+        drop(pool_state);
+
+        // Only the final output is checked against the slippage limit
+        hop_amount_in =
+            execute_swap_base_input(&mut accounts, &[], hop_amount_in, 0, true)?.amount_out;
+    }
+
+    require_gte!(
+        hop_amount_in,
+        minimum_amount_out,
+        GammaError::ExceededSlippage
+    );
+    Ok(())
+}
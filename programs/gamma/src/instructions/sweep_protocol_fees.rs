@@ -0,0 +1,166 @@
+use crate::error::GammaError;
+use crate::states::{AmmConfig, FeeTreasury, PoolState, ProtocolFeeSweepEvent};
+use crate::utils::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// Accounts of each pool swept, see `sweep_protocol_fees`
+pub const SWEEP_POOL_ACCOUNTS: usize = 9;
+
+#[derive(Accounts)]
+pub struct SweepProtocolFees<'info> {
+    /// Anyone can sweep, for the bounty of the treasury
+    pub cranker: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Config of every pool swept
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(has_one = amm_config)]
+    pub fee_treasury: Box<Account<'info, FeeTreasury>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+/// Sends the protocol fees of every pool passed to the treasury's associated token accounts,
+/// less the cranker's bounty. Each pool is passed as remaining accounts: the pool state, its
+/// token_0 and token_1 vaults, their mints, the treasury's token_0 and token_1 accounts and the
+/// cranker's token_0 and token_1 accounts.
+pub fn sweep_protocol_fees<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SweepProtocolFees<'info>>,
+) -> Result<()> {
+    let remaining_accounts = ctx.remaining_accounts;
+    require!(
+        !remaining_accounts.is_empty() && remaining_accounts.len() % SWEEP_POOL_ACCOUNTS == 0,
+        GammaError::InvalidInput
+    );
+
+    for pool_accounts in remaining_accounts.chunks(SWEEP_POOL_ACCOUNTS) {
+        let pool_loader = AccountLoader::<PoolState>::try_from(&pool_accounts[0])?;
+        let token_0_vault = InterfaceAccount::<TokenAccount>::try_from(&pool_accounts[1])?;
+        let token_1_vault = InterfaceAccount::<TokenAccount>::try_from(&pool_accounts[2])?;
+        let vault_0_mint = InterfaceAccount::<Mint>::try_from(&pool_accounts[3])?;
+        let vault_1_mint = InterfaceAccount::<Mint>::try_from(&pool_accounts[4])?;
+
+        let (amount_0, amount_1, auth_bump) = {
+            let mut pool_state = pool_loader.load_mut()?;
+            require_keys_eq!(
+                pool_state.amm_config,
+                ctx.accounts.amm_config.key(),
+                GammaError::InvalidInput
+            );
+            require_keys_eq!(
+                token_0_vault.key(),
+                pool_state.token_0_vault,
+                GammaError::InvalidVault
+            );
+            require_keys_eq!(
+                token_1_vault.key(),
+                pool_state.token_1_vault,
+                GammaError::InvalidVault
+            );
+            let swept = (
+                pool_state.protocol_fees_token_0,
+                pool_state.protocol_fees_token_1,
+                pool_state.auth_bump,
+            );
+            pool_state.protocol_fees_token_0 = 0;
+            pool_state.protocol_fees_token_1 = 0;
+            pool_state.recent_epoch = Clock::get()?.epoch;
+            swept
+        };
+
+        let (token_0_bounty, token_0_amount) = sweep_token(
+            ctx.accounts,
+            &token_0_vault,
+            &vault_0_mint,
+            &pool_accounts[5],
+            &pool_accounts[7],
+            amount_0,
+            auth_bump,
+        )?;
+        let (token_1_bounty, token_1_amount) = sweep_token(
+            ctx.accounts,
+            &token_1_vault,
+            &vault_1_mint,
+            &pool_accounts[6],
+            &pool_accounts[8],
+            amount_1,
+            auth_bump,
+        )?;
+
+        emit!(ProtocolFeeSweepEvent {
+            version: ProtocolFeeSweepEvent::VERSION,
+            pool_id: pool_loader.key(),
+            treasury: ctx.accounts.fee_treasury.treasury,
+            cranker: ctx.accounts.cranker.key(),
+            token_0_amount,
+            token_1_amount,
+            token_0_bounty,
+            token_1_bounty,
+        });
+    }
+    Ok(())
+}
+
+/// Pays `amount` of protocol fees out of `vault`, the bounty to the cranker and the rest to the
+/// treasury, returning both
+fn sweep_token<'info>(
+    accounts: &SweepProtocolFees<'info>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    treasury_token_account: &AccountInfo<'info>,
+    cranker_token_account: &AccountInfo<'info>,
+    amount: u64,
+    auth_bump: u8,
+) -> Result<(u64, u64)> {
+    require_keys_eq!(vault.mint, mint.key(), GammaError::InvalidInput);
+    let mint_info = mint.to_account_info();
+    let token_program = if mint_info.owner == accounts.token_program.key {
+        accounts.token_program.to_account_info()
+    } else {
+        accounts.token_program_2022.to_account_info()
+    };
+    // The fees only go to the treasury's own account of the mint
+    require_keys_eq!(
+        treasury_token_account.key(),
+        accounts
+            .fee_treasury
+            .treasury_token_account(&mint.key(), token_program.key),
+        GammaError::InvalidInput
+    );
+
+    let (bounty, treasury_amount) = accounts.fee_treasury.split_bounty(amount)?;
+    for (recipient, amount) in [
+        (treasury_token_account, treasury_amount),
+        (cranker_token_account, bounty),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        transfer_from_pool_vault_to_user(
+            accounts.authority.to_account_info(),
+            vault.to_account_info(),
+            recipient.clone(),
+            mint_info.clone(),
+            token_program.clone(),
+            amount,
+            mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+        )?;
+    }
+    Ok((bounty, treasury_amount))
+}
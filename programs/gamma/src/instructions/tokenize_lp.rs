@@ -0,0 +1,104 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, UserPoolLiquidity, POOL_LP_MINT_SEED, USER_POOL_LIQUIDITY_SEED};
+use crate::utils::token_mint_to;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct TokenizeLp<'info> {
+    /// Owner of the liquidity turned into LP tokens
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_LP_MINT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Receives the LP tokens
+    #[account(mut, token::mint = lp_mint)]
+    pub lp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// Moves `lp_token_amount` lp tokens of the owner's liquidity out of their UserPoolLiquidity and
+/// mints them as tokens of the pool's LP mint. The LP fees accrued so far stay claimable, the
+/// tokens earn theirs through the reserves.
+pub fn tokenize_lp(ctx: Context<TokenizeLp>, lp_token_amount: u64) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    require_gte!(
+        user_pool_liquidity.unstaked_lp_tokens(),
+        u128::from(lp_token_amount),
+        GammaError::LpTokensStaked
+    );
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
+    user_pool_liquidity.lp_tokens_owned = user_pool_liquidity
+        .lp_tokens_owned
+        .checked_sub(u128::from(lp_token_amount))
+        .ok_or(GammaError::MathOverflow)?;
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
+
+    // LP tokens aren't linked with partners, their LP leaves the partner of the owner's liquidity
+    if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
+        let mut pool_state_partners = pool_state.partners;
+        if let Some(partner) = pool_state_partners
+            .iter_mut()
+            .find(|p| p.links(user_pool_liquidity_partner))
+        {
+            partner.lp_token_linked_with_partner = partner
+                .lp_token_linked_with_partner
+                .saturating_sub(lp_token_amount);
+        }
+        pool_state.partners = pool_state_partners;
+    }
+
+    pool_state.lp_tokenized = pool_state
+        .lp_tokenized
+        .checked_add(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+
+    token_mint_to(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.lp_token_account.to_account_info(),
+        lp_token_amount,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )
+}
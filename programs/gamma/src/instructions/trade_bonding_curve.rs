@@ -0,0 +1,197 @@
+use crate::{
+    error::GammaError,
+    states::{BondingCurve, BondingCurveTradeEvent},
+    utils::{get_transfer_fee, transfer_from_pool_vault_to_user, transfer_from_user_to_pool_vault},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct TradeBondingCurve<'info> {
+    pub trader: Signer<'info>,
+
+    /// CHECK: pda authority of the pools, owns the vaults of the curve
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = token_mint,
+        has_one = quote_mint,
+        has_one = token_vault,
+        has_one = quote_vault,
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(mut)]
+    pub token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = trader,
+    )]
+    pub trader_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = trader,
+    )]
+    pub trader_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = token_mint_program)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = quote_mint_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022 of the launched token
+    pub token_mint_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022 of the quote token
+    pub quote_mint_program: Interface<'info, TokenInterface>,
+}
+
+/// Buys tokens of the bonding curve with `quote_amount_in` quote, until it raised its target
+pub fn buy_bonding_curve(
+    ctx: Context<TradeBondingCurve>,
+    quote_amount_in: u64,
+    minimum_token_amount_out: u64,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    require!(
+        !accounts.bonding_curve.is_complete(),
+        GammaError::BondingCurveComplete
+    );
+    let transfer_fee = get_transfer_fee(&accounts.quote_mint.to_account_info(), quote_amount_in)?;
+    let quote_amount_received = quote_amount_in
+        .checked_sub(transfer_fee)
+        .ok_or(GammaError::AmountTooSmallAfterFees)?;
+    let token_amount_out = accounts
+        .bonding_curve
+        .buy_amount_out(quote_amount_received)?;
+    require_gt!(token_amount_out, 0, GammaError::AmountTooSmallAfterFees);
+    require_gte!(
+        token_amount_out,
+        minimum_token_amount_out,
+        GammaError::ExceededSlippage
+    );
+
+    transfer_from_user_to_pool_vault(
+        accounts.trader.to_account_info(),
+        accounts.trader_quote_account.to_account_info(),
+        accounts.quote_vault.to_account_info(),
+        accounts.quote_mint.to_account_info(),
+        accounts.quote_mint_program.to_account_info(),
+        quote_amount_in,
+        accounts.quote_mint.decimals,
+    )?;
+    transfer_from_pool_vault_to_user(
+        accounts.authority.to_account_info(),
+        accounts.token_vault.to_account_info(),
+        accounts.trader_token_account.to_account_info(),
+        accounts.token_mint.to_account_info(),
+        accounts.token_mint_program.to_account_info(),
+        token_amount_out,
+        accounts.token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.token_reserve = bonding_curve
+        .token_reserve
+        .checked_sub(token_amount_out)
+        .ok_or(GammaError::MathOverflow)?;
+    bonding_curve.quote_reserve = bonding_curve
+        .quote_reserve
+        .checked_add(quote_amount_received)
+        .ok_or(GammaError::MathOverflow)?;
+
+    emit!(BondingCurveTradeEvent {
+        version: BondingCurveTradeEvent::VERSION,
+        bonding_curve: bonding_curve.key(),
+        trader: ctx.accounts.trader.key(),
+        is_buy: true,
+        token_amount: token_amount_out,
+        quote_amount: quote_amount_received,
+        token_reserve: bonding_curve.token_reserve,
+        quote_reserve: bonding_curve.quote_reserve,
+    });
+    Ok(())
+}
+
+/// Sells `token_amount_in` tokens back to the bonding curve, until it raised its target
+pub fn sell_bonding_curve(
+    ctx: Context<TradeBondingCurve>,
+    token_amount_in: u64,
+    minimum_quote_amount_out: u64,
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    require!(
+        !accounts.bonding_curve.is_complete(),
+        GammaError::BondingCurveComplete
+    );
+    let transfer_fee = get_transfer_fee(&accounts.token_mint.to_account_info(), token_amount_in)?;
+    let token_amount_received = token_amount_in
+        .checked_sub(transfer_fee)
+        .ok_or(GammaError::AmountTooSmallAfterFees)?;
+    let quote_amount_out = accounts
+        .bonding_curve
+        .sell_amount_out(token_amount_received)?;
+    require_gt!(quote_amount_out, 0, GammaError::AmountTooSmallAfterFees);
+    require_gte!(
+        quote_amount_out,
+        minimum_quote_amount_out,
+        GammaError::ExceededSlippage
+    );
+
+    transfer_from_user_to_pool_vault(
+        accounts.trader.to_account_info(),
+        accounts.trader_token_account.to_account_info(),
+        accounts.token_vault.to_account_info(),
+        accounts.token_mint.to_account_info(),
+        accounts.token_mint_program.to_account_info(),
+        token_amount_in,
+        accounts.token_mint.decimals,
+    )?;
+    transfer_from_pool_vault_to_user(
+        accounts.authority.to_account_info(),
+        accounts.quote_vault.to_account_info(),
+        accounts.trader_quote_account.to_account_info(),
+        accounts.quote_mint.to_account_info(),
+        accounts.quote_mint_program.to_account_info(),
+        quote_amount_out,
+        accounts.quote_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
+    )?;
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.token_reserve = bonding_curve
+        .token_reserve
+        .checked_add(token_amount_received)
+        .ok_or(GammaError::MathOverflow)?;
+    bonding_curve.quote_reserve = bonding_curve
+        .quote_reserve
+        .checked_sub(quote_amount_out)
+        .ok_or(GammaError::MathOverflow)?;
+
+    emit!(BondingCurveTradeEvent {
+        version: BondingCurveTradeEvent::VERSION,
+        bonding_curve: bonding_curve.key(),
+        trader: ctx.accounts.trader.key(),
+        is_buy: false,
+        token_amount: token_amount_received,
+        quote_amount: quote_amount_out,
+        token_reserve: bonding_curve.token_reserve,
+        quote_reserve: bonding_curve.quote_reserve,
+    });
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use crate::error::GammaError;
+use crate::states::{Position, PositionChangeEvent};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TransferPosition<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ GammaError::InvalidOwner,
+    )]
+    pub position: Box<Account<'info, Position>>,
+}
+
+/// Hands the position to `new_owner`, with its lock and the LP fees it accrued
+pub fn transfer_position(ctx: Context<TransferPosition>, new_owner: Pubkey) -> Result<()> {
+    require_keys_neq!(new_owner, Pubkey::default(), GammaError::InvalidInput);
+    let position = &mut ctx.accounts.position;
+    let from_owner = position.owner;
+    position.owner = new_owner;
+
+    emit!(PositionChangeEvent {
+        version: PositionChangeEvent::VERSION,
+        pool_id: position.pool_state,
+        position: position.key(),
+        from_owner,
+        to_owner: new_owner,
+        lp_tokens: position.lp_tokens,
+        unlock_time: position.unlock_time,
+        change_type: 1,
+    });
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::error::GammaError;
+use crate::states::{
+    PoolState, UserPoolLiquidity, UserPositionTransferEvent, USER_POOL_LIQUIDITY_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TransferUserPosition<'info> {
+    /// Owner of the liquidity the LP moves out of, pays for the new owner's liquidity account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: any wallet can receive liquidity
+    #[account(constraint = new_owner.key() != owner.key() @ GammaError::InvalidInput)]
+    pub new_owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    /// Liquidity of the new owner, created if they have none in the pool
+    #[account(
+        init_if_needed,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            new_owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = UserPoolLiquidity::LEN,
+    )]
+    pub new_owner_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves `lp_token_amount` lp tokens of the owner's liquidity to the new owner's, with their
+/// share of the deposit history and unclaimed LP fees, without withdrawing from the pool
+pub fn transfer_user_position(
+    ctx: Context<TransferUserPosition>,
+    lp_token_amount: u64,
+) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+
+    let new_owner_pool_liquidity = &mut ctx.accounts.new_owner_pool_liquidity;
+    if new_owner_pool_liquidity.user == Pubkey::default() {
+        new_owner_pool_liquidity.initialize(ctx.accounts.new_owner.key(), pool_id, None);
+    }
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
+    new_owner_pool_liquidity.accrue_lp_fees(pool_state)?;
+    user_pool_liquidity.transfer_lp_to(new_owner_pool_liquidity, u128::from(lp_token_amount))?;
+    if new_owner_pool_liquidity.extension.referrer.is_none() {
+        new_owner_pool_liquidity.extension.referrer = user_pool_liquidity.extension.referrer;
+    }
+
+    for liquidity in [&user_pool_liquidity, &new_owner_pool_liquidity] {
+        pool_state.track_lp_concentration(pool_id, liquidity.user, liquidity.lp_tokens_owned)?;
+    }
+
+    let mut pool_state_partners = pool_state.partners;
+    if let Some(from_partner) = user_pool_liquidity.partner {
+        if let Some(info) = pool_state_partners
+            .iter_mut()
+            .find(|info| info.links(from_partner))
+        {
+            info.lp_token_linked_with_partner = info
+                .lp_token_linked_with_partner
+                .saturating_sub(lp_token_amount);
+        }
+    }
+    if let Some(to_partner) = new_owner_pool_liquidity.partner {
+        if let Some(info) = pool_state_partners
+            .iter_mut()
+            .find(|info| info.links(to_partner))
+        {
+            info.lp_token_linked_with_partner = info
+                .lp_token_linked_with_partner
+                .checked_add(lp_token_amount)
+                .ok_or(GammaError::MathOverflow)?;
+        }
+    }
+    pool_state.partners = pool_state_partners;
+
+    emit!(UserPositionTransferEvent {
+        version: UserPositionTransferEvent::VERSION,
+        pool_id,
+        from_owner: user_pool_liquidity.user,
+        to_owner: new_owner_pool_liquidity.user,
+        lp_tokens: lp_token_amount,
+        from_lp_tokens: u64::try_from(user_pool_liquidity.lp_tokens_owned)
+            .or(err!(GammaError::MathOverflow))?,
+        to_lp_tokens: u64::try_from(new_owner_pool_liquidity.lp_tokens_owned)
+            .or(err!(GammaError::MathOverflow))?,
+    });
+    Ok(())
+}
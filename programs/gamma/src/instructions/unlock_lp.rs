@@ -0,0 +1,91 @@
+use crate::error::GammaError;
+use crate::states::{
+    LpLockEvent, PoolState, Position, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UnlockLp<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The owner's liquidity, the vested LP moves into it
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    #[account(
+        mut,
+        has_one = owner @ GammaError::InvalidOwner,
+        has_one = pool_state,
+    )]
+    pub position: Box<Account<'info, Position>>,
+}
+
+/// Moves the LP of a position that vested since the last release back into the owner's
+/// liquidity, where it can be withdrawn. The LP fees of the position stay in it until it is
+/// closed.
+pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    let position = &mut ctx.accounts.position;
+    let released_lp_tokens = position.lp_tokens - position.locked_lp_tokens(block_timestamp);
+    if released_lp_tokens == 0 {
+        return err!(GammaError::PositionLocked);
+    }
+    position.accrue_lp_fees(pool_state)?;
+    position.lp_tokens -= released_lp_tokens;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
+    user_pool_liquidity.lp_tokens_owned = user_pool_liquidity
+        .lp_tokens_owned
+        .checked_add(u128::from(released_lp_tokens))
+        .ok_or(GammaError::MathOverflow)?;
+    // Moving LP back doesn't skip the withdraw delay of its deposit
+    user_pool_liquidity.extension.last_deposit_slot = user_pool_liquidity
+        .extension
+        .last_deposit_slot
+        .max(position.last_deposit_slot);
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
+
+    if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
+        let mut pool_state_partners = pool_state.partners;
+        if let Some(partner) = pool_state_partners
+            .iter_mut()
+            .find(|p| p.links(user_pool_liquidity_partner))
+        {
+            partner.lp_token_linked_with_partner = partner
+                .lp_token_linked_with_partner
+                .checked_add(released_lp_tokens)
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        pool_state.partners = pool_state_partners;
+    }
+
+    emit!(LpLockEvent {
+        version: LpLockEvent::VERSION,
+        pool_id,
+        position: position.key(),
+        owner: position.owner,
+        locked_lp_tokens: position.lp_tokens,
+        released_lp_tokens,
+        vesting_start: position.vesting_start,
+        unlock_time: position.unlock_time,
+    });
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use crate::error::GammaError;
+use crate::states::{
+    PoolRewards, PoolState, UserPoolLiquidity, UserStake, POOL_REWARDS_SEED,
+    USER_POOL_LIQUIDITY_SEED, USER_STAKE_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UnstakeLp<'info> {
+    /// Owner of the position
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_REWARDS_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = pool_rewards.bump,
+    )]
+    pub pool_rewards: Box<Account<'info, PoolRewards>>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_STAKE_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump = user_stake.bump,
+    )]
+    pub user_stake: Box<Account<'info, UserStake>>,
+}
+
+/// Unstakes `lp_token_amount` lp tokens of the position, the rewards earned so far stay owed
+/// to it until they're claimed
+pub fn unstake_lp(ctx: Context<UnstakeLp>, lp_token_amount: u64) -> Result<()> {
+    require_gt!(lp_token_amount, 0);
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    require_gte!(
+        user_pool_liquidity.extension.lp_tokens_staked,
+        lp_token_amount,
+        GammaError::InvalidInput
+    );
+
+    let pool_rewards = &mut ctx.accounts.pool_rewards;
+    pool_rewards.update(block_timestamp)?;
+    ctx.accounts
+        .user_stake
+        .accrue(pool_rewards, user_pool_liquidity.extension.lp_tokens_staked)?;
+
+    user_pool_liquidity.extension.lp_tokens_staked -= lp_token_amount;
+    pool_rewards.total_staked = pool_rewards
+        .total_staked
+        .checked_sub(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, POOL_LP_MINT_SEED};
+use crate::utils::fund_token_metadata;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    spl_token_2022::{
+        extension::{BaseStateWithExtensions, StateWithExtensions},
+        state::Mint as MintState,
+    },
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+    token_metadata_update_field, Mint, Token2022, TokenMetadataUpdateField,
+};
+
+#[derive(Accounts)]
+pub struct UpdateLpMetadata<'info> {
+    /// The admin or the pool creator, pays the rent of longer metadata
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority, the update authority of the LP metadata
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub pool_authority: UncheckedAccount<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_LP_MINT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Program<'info, Token2022>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Rewrites the name, symbol and uri of the pool's LP mint metadata
+pub fn update_lp_metadata(
+    ctx: Context<UpdateLpMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let auth_bump = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == crate::admin::id() || authority == pool_state.pool_creator,
+            GammaError::InvalidOwner
+        );
+        pool_state.auth_bump
+    };
+
+    let lp_mint = ctx.accounts.lp_mint.to_account_info();
+    let mut metadata = {
+        let mint_data = lp_mint.try_borrow_data()?;
+        StateWithExtensions::<MintState>::unpack(&mint_data)?
+            .get_variable_len_extension::<TokenMetadata>()?
+    };
+    let fields = [
+        (Field::Name, name),
+        (Field::Symbol, symbol),
+        (Field::Uri, uri),
+    ];
+    for (field, value) in fields.iter() {
+        metadata.update(field.clone(), value.clone());
+    }
+    fund_token_metadata(
+        ctx.accounts.authority.to_account_info(),
+        lp_mint.clone(),
+        ctx.accounts.system_program.to_account_info(),
+        &metadata,
+    )?;
+
+    for (field, value) in fields {
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TokenMetadataUpdateField {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: lp_mint.clone(),
+                    update_authority: ctx.accounts.pool_authority.to_account_info(),
+                },
+                &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+            ),
+            field,
+            value,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::error::GammaError;
+use crate::states::PoolState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateMaxLpSupply<'info> {
+    /// The admin or the pool creator
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// A `max_lp_supply` of zero lifts the cap. A cap below the current LP supply only stops further
+/// deposits, withdrawals aren't affected.
+pub fn update_max_lp_supply(ctx: Context<UpdateMaxLpSupply>, max_lp_supply: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == crate::admin::id() || authority == pool_state.pool_creator,
+        GammaError::InvalidOwner
+    );
+    pool_state.max_lp_supply = max_lp_supply;
+    Ok(())
+}
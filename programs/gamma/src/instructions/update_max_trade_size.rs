@@ -0,0 +1,28 @@
+use crate::error::GammaError;
+use crate::states::PoolState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateMaxTradeSize<'info> {
+    /// The admin or the pool creator
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// A `max_trade_size_bps` of zero lifts the limit
+pub fn update_max_trade_size(
+    ctx: Context<UpdateMaxTradeSize>,
+    max_trade_size_bps: u64,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == crate::admin::id() || authority == pool_state.pool_creator,
+        GammaError::InvalidOwner
+    );
+    require_gte!(10_000, max_trade_size_bps, GammaError::InvalidInput);
+    pool_state.max_trade_size_bps = max_trade_size_bps;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::error::GammaError;
+use crate::states::{
+    PartnerLpLinkEvent, PartnerType, PoolState, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePartnerLpLink<'info> {
+    /// Owner of the position
+    pub user: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub user_pool_liquidity: Box<Account<'info, UserPoolLiquidity>>,
+}
+
+/// Moves the LP of the position from the partner it's linked with to `partner`, which has to be
+/// a partner of the pool. `None` unlinks the position.
+pub fn update_partner_lp_link(
+    ctx: Context<UpdatePartnerLpLink>,
+    partner: Option<u64>,
+) -> Result<()> {
+    let to_partner = match partner {
+        Some(partner_id) => {
+            let partner = PartnerType::new(partner_id);
+            require_eq!(partner as u64, partner_id, GammaError::InvalidInput);
+            Some(partner)
+        }
+        None => None,
+    };
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    let from_partner = user_pool_liquidity.partner;
+    let lp_amount =
+        u64::try_from(user_pool_liquidity.lp_tokens_owned).or(err!(GammaError::MathOverflow))?;
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let mut partners = pool_state.partners;
+    if let Some(from_partner) = from_partner {
+        if let Some(info) = partners.iter_mut().find(|info| info.links(from_partner)) {
+            info.lp_token_linked_with_partner =
+                info.lp_token_linked_with_partner.saturating_sub(lp_amount);
+        }
+    }
+    if let Some(to_partner) = to_partner {
+        let info = partners
+            .iter_mut()
+            .find(|info| info.links(to_partner))
+            .ok_or(GammaError::InvalidInput)?;
+        info.lp_token_linked_with_partner = info
+            .lp_token_linked_with_partner
+            .checked_add(lp_amount)
+            .ok_or(GammaError::MathOverflow)?;
+    }
+    pool_state.partners = partners;
+    user_pool_liquidity.partner = to_partner;
+
+    emit!(PartnerLpLinkEvent {
+        version: PartnerLpLinkEvent::VERSION,
+        pool_id: ctx.accounts.pool_state.key(),
+        user: ctx.accounts.user.key(),
+        from_partner: from_partner.map(|partner| partner as u64),
+        to_partner: to_partner.map(|partner| partner as u64),
+        lp_amount,
+    });
+    Ok(())
+}
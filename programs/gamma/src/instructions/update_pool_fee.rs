@@ -0,0 +1,40 @@
+use crate::error::GammaError;
+use crate::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::states::{AmmConfig, PoolState};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolFee<'info> {
+    /// The admin, or the pool creator within the config's bounds
+    pub authority: Signer<'info>,
+
+    /// The config of the pool, holding the bounds
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(mut, has_one = amm_config)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// A `trade_fee_rate` of zero goes back to the config's rate
+pub fn update_pool_fee(ctx: Context<UpdatePoolFee>, trade_fee_rate: u64) -> Result<()> {
+    let amm_config = &ctx.accounts.amm_config;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    let is_admin = authority == crate::admin::id();
+    require!(
+        is_admin || authority == pool_state.pool_creator,
+        GammaError::InvalidOwner
+    );
+    require_gt!(FEE_RATE_DENOMINATOR_VALUE, trade_fee_rate);
+
+    // The bounds of a config with a fee timelock hold the admin too, they only move through it
+    if trade_fee_rate != 0 && (!is_admin || amm_config.fee_change_delay != 0) {
+        require!(
+            (amm_config.min_pool_trade_fee_rate..=amm_config.max_pool_trade_fee_rate)
+                .contains(&trade_fee_rate),
+            GammaError::PoolFeeOutOfBounds
+        );
+    }
+    pool_state.trade_fee_rate_override = trade_fee_rate;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::error::GammaError;
+use crate::states::{AmmConfig, PoolState};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolOpenTime<'info> {
+    pub pool_creator: Signer<'info>,
+
+    /// The config of the pool, holding the open delay bounds
+    pub amm_config: Account<'info, AmmConfig>,
+
+    #[account(mut, has_one = pool_creator, has_one = amm_config)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Moves the open time of a pool that hasn't opened yet, within the same bounds as at creation
+pub fn update_pool_open_time(ctx: Context<UpdatePoolOpenTime>, open_time: u64) -> Result<()> {
+    let amm_config = &ctx.accounts.amm_config;
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    // Once open, swappers rely on the pool staying open
+    require_gt!(
+        pool_state.open_time,
+        block_timestamp,
+        GammaError::InvalidOpenTime
+    );
+    if open_time <= block_timestamp
+        || open_time < block_timestamp + amm_config.min_open_delay
+        || open_time > block_timestamp + amm_config.max_open_time
+    {
+        return err!(GammaError::InvalidOpenTime);
+    }
+    pool_state.open_time = open_time;
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use crate::error::GammaError;
+use crate::states::{
+    Gauge, GaugeController, GaugeVoter, GAUGE_CONTROLLER_SEED, GAUGE_SEED, GAUGE_VOTER_SEED,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct VoteGauge<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAUGE_CONTROLLER_SEED.as_bytes()],
+        bump = gauge_controller.bump,
+    )]
+    pub gauge_controller: Box<Account<'info, GaugeController>>,
+
+    #[account(
+        mut,
+        seeds = [
+            GAUGE_SEED.as_bytes(),
+            gauge.pool_state.as_ref(),
+        ],
+        bump = gauge.bump,
+    )]
+    pub gauge: Box<Account<'info, Gauge>>,
+
+    /// The voter's veGOFX, its balance is the voting power of each epoch
+    #[account(
+        token::mint = gauge_controller.ve_mint,
+        token::authority = voter,
+    )]
+    pub ve_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            GAUGE_VOTER_SEED.as_bytes(),
+            voter.key().as_ref(),
+        ],
+        bump,
+        payer = voter,
+        space = GaugeVoter::LEN,
+    )]
+    pub gauge_voter: Box<Account<'info, GaugeVoter>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn vote_gauge(ctx: Context<VoteGauge>, power: u64) -> Result<()> {
+    require_gt!(power, 0, GammaError::InvalidInput);
+    let gauge_controller = &mut ctx.accounts.gauge_controller;
+    let epoch = gauge_controller.epoch_at(Clock::get()?.unix_timestamp as u64);
+
+    let gauge_voter = &mut ctx.accounts.gauge_voter;
+    gauge_voter.bump = ctx.bumps.gauge_voter;
+    gauge_voter.owner = ctx.accounts.voter.key();
+    gauge_voter.spend(epoch, power, ctx.accounts.ve_token_account.amount)?;
+
+    gauge_controller.roll(epoch);
+    gauge_controller.total_votes = gauge_controller
+        .total_votes
+        .checked_add(power)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let gauge = &mut ctx.accounts.gauge;
+    gauge.roll(epoch);
+    gauge.votes = gauge
+        .votes
+        .checked_add(power)
+        .ok_or(GammaError::MathOverflow)?;
+    Ok(())
+}
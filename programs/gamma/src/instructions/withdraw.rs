@@ -2,16 +2,21 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
 
-use crate::curve::{CurveCalculator, RoundDirection};
+use crate::instructions::{decode_account_info, withdraw_amounts, LiquidityPreview};
 use crate::states::{
-    LpChangeEvent, PartnerType, PoolStatusBitIndex, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED,
+    LiquidityChangeEvent, LpChangeEvent, PoolStatusBitIndex, UserPoolLiquidity,
+    USER_POOL_LIQUIDITY_SEED,
+};
+use crate::utils::{
+    recall_for_payout, refresh_quote_cache, transfer_from_pool_vault_to_user_with_hook,
+    update_mint_exposures, StrategyAccounts,
 };
-use crate::utils::{get_transfer_fee, transfer_from_pool_vault_to_user};
 use crate::{error::GammaError, states::PoolState};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
-    /// Owner of the liquidity provided
+    /// Owner of the liquidity provided, pays the rent of growing an older liquidity account
+    #[account(mut)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault authority
@@ -36,22 +41,23 @@ pub struct Withdraw<'info> {
             owner.key().as_ref(),
         ],
         bump,
+        realloc = UserPoolLiquidity::LEN,
+        realloc::payer = owner,
+        realloc::zero = false,
     )]
     pub user_pool_liquidity: Account<'info, UserPoolLiquidity>,
 
-    /// The owner's token account for receive token_0
+    /// The token account receiving token_0, of the owner or of the recipient
     #[account(
         mut,
         token::mint = token_0_vault.mint,
-        token::authority = owner
     )]
     pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// The owner's token account for receive token_1
+    /// The token account receiving token_1, of the owner or of the recipient
     #[account(
         mut,
         token::mint = token_1_vault.mint,
-        token::authority = owner
     )]
     pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -93,74 +99,89 @@ pub struct Withdraw<'info> {
         address = spl_memo::id()
     )]
     pub memo_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
 
-pub fn withdraw(
-    ctx: Context<Withdraw>,
+/// Remaining accounts of a withdrawal at fixed positions: the `MintExposure` of token_0 and
+/// token_1, the `QuoteCache` and the recipient of the proceeds. The strategies of the vaults and
+/// the transfer hook accounts follow them.
+pub const WITHDRAW_FIXED_REMAINING_ACCOUNTS: usize = 4;
+/// Position of the recipient of the proceeds in the remaining accounts
+pub const WITHDRAW_RECIPIENT_INDEX: usize = 3;
+
+/// The owner of the token accounts withdrawn to: the recipient passed as an optional account,
+/// e.g. a custody wallet, or else the owner of the liquidity
+pub fn withdraw_recipient(owner: Pubkey, recipient: Option<AccountInfo<'_>>) -> Pubkey {
+    recipient.map_or(owner, |recipient| recipient.key())
+}
+
+pub fn withdraw<'info>(
+    ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
     lp_token_amount: u64,
     minimum_token_0_amount: u64,
     minimum_token_1_amount: u64,
 ) -> Result<()> {
     // require_gt!(ctx.accounts.lp_mint.supply, 0);
+    let recipient = withdraw_recipient(
+        ctx.accounts.owner.key(),
+        decode_account_info(ctx.remaining_accounts, WITHDRAW_RECIPIENT_INDEX),
+    );
+    require_keys_eq!(
+        ctx.accounts.token_0_account.owner,
+        recipient,
+        GammaError::InvalidOutputReceiver
+    );
+    require_keys_eq!(
+        ctx.accounts.token_1_account.owner,
+        recipient,
+        GammaError::InvalidOutputReceiver
+    );
+    require_gte!(
+        ctx.accounts.user_pool_liquidity.unstaked_lp_tokens(),
+        u128::from(lp_token_amount),
+        GammaError::LpTokensStaked
+    );
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    ctx.accounts
+        .user_pool_liquidity
+        .check_withdraw_delay(pool_state, Clock::get()?.slot)?;
     if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
         return err!(GammaError::NotApproved);
     }
+    let reserves_before = pool_state.vault_amount_without_fee()?;
+    pool_state.sync_rebasing_reserve(true, ctx.accounts.token_0_vault.amount)?;
+    pool_state.sync_rebasing_reserve(false, ctx.accounts.token_1_vault.amount)?;
     let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee()?;
-    let results = CurveCalculator::lp_tokens_to_trading_tokens(
-        u128::from(lp_token_amount),
-        u128::from(pool_state.lp_supply),
-        u128::from(total_token_0_amount),
-        u128::from(total_token_1_amount),
-        RoundDirection::Floor,
-    )
-    .ok_or(GammaError::ZeroTradingTokens)?;
-
-    let token_0_amount = match u64::try_from(results.token_0_amount) {
-        Ok(value) => value,
-        Err(_) => return err!(GammaError::MathOverflow),
-    };
-    let token_0_amount = std::cmp::min(total_token_0_amount, token_0_amount);
-    let (receive_token_0_amount, token_0_transfer_fee) = {
-        let transfer_fee =
-            get_transfer_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
-        (
-            token_0_amount
-                .checked_sub(transfer_fee)
-                .ok_or(GammaError::MathOverflow)?,
-            transfer_fee,
-        )
-    };
-
-    let token_1_amount = match u64::try_from(results.token_1_amount) {
-        Ok(value) => value,
-        Err(_) => return err!(GammaError::MathOverflow),
-    };
-    let token_1_amount = std::cmp::min(total_token_1_amount, token_1_amount);
-    let (receive_token_1_amount, token_1_transfer_fee) = {
-        let transfer_fee =
-            get_transfer_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
-        (
-            token_1_amount
-                .checked_sub(transfer_fee)
-                .ok_or(GammaError::MathOverflow)?,
-            transfer_fee,
-        )
-    };
+    let LiquidityPreview {
+        token_0_amount,
+        token_1_amount,
+        token_0_user_amount: receive_token_0_amount,
+        token_1_user_amount: receive_token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        ..
+    } = withdraw_amounts(
+        pool_state,
+        lp_token_amount,
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        &ctx.accounts.vault_1_mint.to_account_info(),
+    )?;
 
     #[cfg(feature = "enable-log")]
     msg!(
-        "results.token_0_amount;{}, results.token_1_amount:{},receive_token_0_amount:{},token_0_transfer_fee:{},
+        "token_0_amount;{}, token_1_amount:{},receive_token_0_amount:{},token_0_transfer_fee:{},
             receive_token_1_amount:{},token_1_transfer_fee:{}",
-        results.token_0_amount,
-        results.token_1_amount,
+        token_0_amount,
+        token_1_amount,
         receive_token_0_amount,
         token_0_transfer_fee,
         receive_token_1_amount,
         token_1_transfer_fee
     );
     emit!(LpChangeEvent {
+        version: LpChangeEvent::VERSION,
         pool_id,
         lp_amount_before: pool_state.lp_supply,
         token_0_vault_before: total_token_0_amount,
@@ -183,10 +204,16 @@ pub fn withdraw(
         .checked_sub(lp_token_amount)
         .ok_or(GammaError::MathOverflow)?;
     let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
     user_pool_liquidity.lp_tokens_owned = user_pool_liquidity
         .lp_tokens_owned
         .checked_sub(u128::from(lp_token_amount))
         .ok_or(GammaError::MathOverflow)?;
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
     user_pool_liquidity.token_0_withdrawn = user_pool_liquidity
         .token_0_withdrawn
         .checked_add(u128::from(receive_token_0_amount))
@@ -195,46 +222,91 @@ pub fn withdraw(
         .token_1_withdrawn
         .checked_add(u128::from(receive_token_1_amount))
         .ok_or(GammaError::MathOverflow)?;
+    emit!(LiquidityChangeEvent {
+        version: LiquidityChangeEvent::VERSION,
+        pool_id,
+        user: user_pool_liquidity.user,
+        lp_token_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        lp_supply: pool_state.lp_supply,
+        user_lp_tokens: user_pool_liquidity.lp_tokens_owned,
+        change_type: 1,
+    });
 
     if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
         let mut pool_state_partners = pool_state.partners;
         let partner: Option<&mut crate::states::PartnerInfo> = pool_state_partners
             .iter_mut()
-            .find(|p| PartnerType::new(p.partner_id) == user_pool_liquidity_partner);
+            .find(|p| p.links(user_pool_liquidity_partner));
         if let Some(partner) = partner {
+            // A partner added again starts from zero while LP linked before stays linked
             partner.lp_token_linked_with_partner = partner
                 .lp_token_linked_with_partner
-                .checked_sub(lp_token_amount)
-                .ok_or(GammaError::MathOverflow)?;
+                .saturating_sub(lp_token_amount);
         }
         pool_state.partners = pool_state_partners;
     }
 
-    transfer_from_pool_vault_to_user(
-        ctx.accounts.authority.to_account_info(),
-        ctx.accounts.token_0_vault.to_account_info(),
-        ctx.accounts.token_0_account.to_account_info(),
-        ctx.accounts.vault_0_mint.to_account_info(),
+    // The remaining accounts after the WITHDRAW_FIXED_REMAINING_ACCOUNTS are the strategies of
+    // the token_0 and token_1 vaults, to recall what the vaults are missing, then the accounts of
+    // the transfer hooks of token_0 and token_1
+    let (token_0_strategy, remaining_accounts) = StrategyAccounts::split(
+        ctx.remaining_accounts
+            .get(WITHDRAW_FIXED_REMAINING_ACCOUNTS..)
+            .unwrap_or_default(),
+    );
+    let (token_1_strategy, transfer_hook_accounts) = StrategyAccounts::split(remaining_accounts);
+    let token_0_program =
         if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
             ctx.accounts.token_program.to_account_info()
         } else {
             ctx.accounts.token_program_2022.to_account_info()
-        },
+        };
+    let token_1_program =
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+    recall_for_payout(
+        token_0_strategy.as_ref(),
+        pool_id,
+        &ctx.accounts.token_0_vault.to_account_info(),
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        &token_0_program,
+        token_0_amount,
+    )?;
+    recall_for_payout(
+        token_1_strategy.as_ref(),
+        pool_id,
+        &ctx.accounts.token_1_vault.to_account_info(),
+        &ctx.accounts.vault_1_mint.to_account_info(),
+        &token_1_program,
+        token_1_amount,
+    )?;
+
+    transfer_from_pool_vault_to_user_with_hook(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        token_0_program,
+        transfer_hook_accounts,
         token_0_amount,
         ctx.accounts.vault_0_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
     )?;
 
-    transfer_from_pool_vault_to_user(
+    transfer_from_pool_vault_to_user_with_hook(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.token_1_vault.to_account_info(),
         ctx.accounts.token_1_account.to_account_info(),
         ctx.accounts.vault_1_mint.to_account_info(),
-        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
-            ctx.accounts.token_program.to_account_info()
-        } else {
-            ctx.accounts.token_program_2022.to_account_info()
-        },
+        token_1_program,
+        transfer_hook_accounts,
         token_1_amount,
         ctx.accounts.vault_1_mint.decimals,
         &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
@@ -248,6 +320,15 @@ pub fn withdraw(
         .token_1_vault_amount
         .checked_sub(token_1_amount)
         .ok_or(GammaError::MathOverflow)?;
+    // Pools tracking the exposure to their mints take the `MintExposure` of token_0 and token_1
+    // as remaining accounts, followed by the `QuoteCache` of pools keeping one
+    update_mint_exposures(
+        pool_state,
+        reserves_before,
+        ctx.remaining_accounts.first(),
+        ctx.remaining_accounts.get(1),
+    )?;
+    refresh_quote_cache(pool_state, pool_id, ctx.remaining_accounts.get(2))?;
 
     pool_state.recent_epoch = Clock::get()?.epoch;
 
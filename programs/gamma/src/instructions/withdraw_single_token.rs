@@ -0,0 +1,461 @@
+use crate::{
+    curve::{CurveCalculator, RoundDirection, TradeDirection},
+    error::GammaError,
+    instructions::{decode_account_info, withdraw_recipient},
+    states::{
+        oracle, AmmConfig, LpChangeEvent, ObservationState, PoolLifecycle, PoolState,
+        PoolStatusBitIndex, SwapEvent, UserPoolLiquidity, REBASING_SHARES_EXTENSION,
+        USER_POOL_LIQUIDITY_SEED,
+    },
+    utils::{
+        check_price_band, get_transfer_fee, get_ui_adjusted_amount, recall_for_payout,
+        refresh_quote_cache, transfer_from_pool_vault_to_user, update_mint_exposures,
+        StrategyAccounts,
+    },
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct WithdrawSingleToken<'info> {
+    /// Owner of the liquidity provided, pays the rent of growing an older liquidity account
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Pool state the owner is withdrawing from
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        realloc = UserPoolLiquidity::LEN,
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub user_pool_liquidity: Account<'info, UserPoolLiquidity>,
+
+    /// The token account receiving the withdrawn token, of the owner or of the recipient
+    #[account(
+        mut,
+        token::mint = output_vault.mint,
+    )]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Pool vault of the withdrawn token
+    #[account(
+        mut,
+        constraint = output_vault.key() == pool_state.load()?.token_0_vault || output_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program for the withdrawn token
+    pub output_token_program: Interface<'info, TokenInterface>,
+
+    /// The mint of the withdrawn token
+    #[account(
+        address = output_vault.mint
+    )]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Burns the lp like `withdraw`, then swaps the share of the other token against the pool left
+/// after the burn. That token never leaves the vault, only the withdrawn token is transferred.
+pub fn withdraw_single_token<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawSingleToken<'info>>,
+    lp_token_amount: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    // The recipient of the proceeds is remaining account 5
+    let recipient = withdraw_recipient(
+        ctx.accounts.owner.key(),
+        decode_account_info(ctx.remaining_accounts, 5),
+    );
+    require_keys_eq!(
+        ctx.accounts.output_token_account.owner,
+        recipient,
+        GammaError::InvalidOutputReceiver
+    );
+    require_gte!(
+        ctx.accounts.user_pool_liquidity.unstaked_lp_tokens(),
+        u128::from(lp_token_amount),
+        GammaError::LpTokensStaked
+    );
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    ctx.accounts
+        .user_pool_liquidity
+        .check_withdraw_delay(pool_state, Clock::get()?.slot)?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw)
+        || pool_state.lifecycle(block_timestamp) != PoolLifecycle::Open
+    {
+        return err!(GammaError::NotApproved);
+    }
+    // Only one vault is passed, the other reserve of a rebasing pool couldn't be synced
+    if pool_state.has_extension(REBASING_SHARES_EXTENSION) {
+        return err!(GammaError::NotApproved);
+    }
+    pool_state.update_weights(block_timestamp);
+
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee()?;
+    let (token_0_price_x32_before_swap, token_1_price_x32_before_swap) =
+        pool_state.token_price_x32()?;
+    let results = CurveCalculator::lp_tokens_to_trading_tokens(
+        u128::from(lp_token_amount),
+        u128::from(pool_state.lp_supply),
+        u128::from(total_token_0_amount),
+        u128::from(total_token_1_amount),
+        RoundDirection::Floor,
+    )
+    .ok_or(GammaError::ZeroTradingTokens)?;
+    let token_0_amount = std::cmp::min(
+        total_token_0_amount,
+        u64::try_from(results.token_0_amount).or(err!(GammaError::MathOverflow))?,
+    );
+    let token_1_amount = std::cmp::min(
+        total_token_1_amount,
+        u64::try_from(results.token_1_amount).or(err!(GammaError::MathOverflow))?,
+    );
+
+    // The swap goes from the token that is not withdrawn to the withdrawn one
+    let (trade_direction, input_share, output_share, input_reserve, output_reserve, input_mint) =
+        if ctx.accounts.output_vault.key() == pool_state.token_1_vault {
+            (
+                TradeDirection::ZeroForOne,
+                token_0_amount,
+                token_1_amount,
+                total_token_0_amount,
+                total_token_1_amount,
+                pool_state.token_0_mint,
+            )
+        } else {
+            (
+                TradeDirection::OneForZero,
+                token_1_amount,
+                token_0_amount,
+                total_token_1_amount,
+                total_token_0_amount,
+                pool_state.token_1_mint,
+            )
+        };
+    // Half of the withdrawal is swapped, so it's paused along with the pool's swaps
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
+        || !pool_state.swap_direction_enabled(trade_direction)
+    {
+        return err!(GammaError::NotApproved);
+    }
+    require_gt!(input_share, 0, GammaError::ZeroTradingTokens);
+    let input_reserve_after_burn = input_reserve
+        .checked_sub(input_share)
+        .ok_or(GammaError::MathOverflow)?;
+    let output_reserve_after_burn = output_reserve
+        .checked_sub(output_share)
+        .ok_or(GammaError::MathOverflow)?;
+    pool_state.check_trade_size(input_share, input_reserve_after_burn)?;
+
+    let observation_info = ctx.accounts.observation_state.to_account_info();
+    let (mut observation_state, mut observation_archive) =
+        ObservationState::load_mut_with_archive(&observation_info)?;
+    let result = CurveCalculator::swap_base_input(
+        u128::from(input_share),
+        u128::from(input_reserve_after_burn),
+        u128::from(output_reserve_after_burn),
+        trade_direction,
+        &ctx.accounts.amm_config,
+        &pool_state,
+        block_timestamp,
+        &observation_state,
+        false,
+        0,
+    )?;
+    let swap_output =
+        u64::try_from(result.destination_amount_swapped).map_err(|_| GammaError::MathOverflow)?;
+    let protocol_fee = u64::try_from(result.protocol_fee).or(err!(GammaError::MathOverflow))?;
+    let fund_fee = u64::try_from(result.fund_fee).or(err!(GammaError::MathOverflow))?;
+
+    let output_amount = output_share
+        .checked_add(swap_output)
+        .ok_or(GammaError::MathOverflow)?;
+    let output_mint_info = ctx.accounts.output_token_mint.to_account_info();
+    let output_transfer_fee = get_transfer_fee(&output_mint_info, output_amount)?;
+    let receive_amount = output_amount
+        .checked_sub(output_transfer_fee)
+        .ok_or(GammaError::MathOverflow)?;
+    require_gte!(
+        receive_amount,
+        minimum_amount_out,
+        GammaError::ExceededSlippage
+    );
+
+    // The swapped share stays in the vault, less the protocol and fund fees it paid
+    let input_vault_amount = input_reserve
+        .checked_sub(protocol_fee)
+        .and_then(|amount| amount.checked_sub(fund_fee))
+        .ok_or(GammaError::MathOverflow)?;
+    let output_vault_amount = output_reserve
+        .checked_sub(output_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    let (vault_0_after, vault_1_after) = match trade_direction {
+        TradeDirection::ZeroForOne => (input_vault_amount, output_vault_amount),
+        TradeDirection::OneForZero => (output_vault_amount, input_vault_amount),
+    };
+
+    emit!(SwapEvent {
+        version: SwapEvent::VERSION,
+        pool_id,
+        input_vault_before: input_reserve_after_burn,
+        output_vault_before: output_reserve_after_burn,
+        input_amount: input_share,
+        output_amount: swap_output,
+        input_mint,
+        output_mint: ctx.accounts.output_token_mint.key(),
+        input_transfer_fee: 0,
+        output_transfer_fee: 0,
+        base_input: true,
+        dynamic_fee: result.dynamic_fee,
+        input_ui_adjusted_amount: get_ui_adjusted_amount(
+            pool_state,
+            trade_direction == TradeDirection::ZeroForOne,
+            None,
+            input_share,
+        )?,
+        output_ui_adjusted_amount: get_ui_adjusted_amount(
+            pool_state,
+            trade_direction == TradeDirection::OneForZero,
+            Some(&output_mint_info),
+            swap_output,
+        )?,
+        vault_0_after,
+        vault_1_after,
+        lp_supply: pool_state
+            .lp_supply
+            .checked_sub(lp_token_amount)
+            .ok_or(GammaError::MathOverflow)?,
+        dynamic_fee_rate: result.dynamic_fee_rate,
+        amm_config_index: ctx.accounts.amm_config.index,
+    });
+    emit!(LpChangeEvent {
+        version: LpChangeEvent::VERSION,
+        pool_id,
+        lp_amount_before: pool_state.lp_supply,
+        token_0_vault_before: total_token_0_amount,
+        token_1_vault_before: total_token_1_amount,
+        token_0_amount: if trade_direction == TradeDirection::OneForZero {
+            receive_amount
+        } else {
+            0
+        },
+        token_1_amount: if trade_direction == TradeDirection::ZeroForOne {
+            receive_amount
+        } else {
+            0
+        },
+        token_0_transfer_fee: if trade_direction == TradeDirection::OneForZero {
+            output_transfer_fee
+        } else {
+            0
+        },
+        token_1_transfer_fee: if trade_direction == TradeDirection::ZeroForOne {
+            output_transfer_fee
+        } else {
+            0
+        },
+        change_type: 1
+    });
+
+    match trade_direction {
+        TradeDirection::ZeroForOne => {
+            pool_state.token_0_vault_amount = input_vault_amount;
+            pool_state.token_1_vault_amount = output_vault_amount;
+            pool_state.protocol_fees_token_0 = pool_state
+                .protocol_fees_token_0
+                .checked_add(protocol_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.fund_fees_token_0 = pool_state
+                .fund_fees_token_0
+                .checked_add(fund_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_trade_fees_token_0 = pool_state
+                .cumulative_trade_fees_token_0
+                .checked_add(result.dynamic_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_protocol_fees_token_0 = pool_state
+                .cumulative_protocol_fees_token_0
+                .checked_add(u128::from(protocol_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_fund_fees_token_0 = pool_state
+                .cumulative_fund_fees_token_0
+                .checked_add(u128::from(fund_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_volume_token_0 = pool_state
+                .cumulative_volume_token_0
+                .checked_add(u128::from(input_share))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        TradeDirection::OneForZero => {
+            pool_state.token_1_vault_amount = input_vault_amount;
+            pool_state.token_0_vault_amount = output_vault_amount;
+            pool_state.protocol_fees_token_1 = pool_state
+                .protocol_fees_token_1
+                .checked_add(protocol_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.fund_fees_token_1 = pool_state
+                .fund_fees_token_1
+                .checked_add(fund_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_trade_fees_token_1 = pool_state
+                .cumulative_trade_fees_token_1
+                .checked_add(result.dynamic_fee)
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_protocol_fees_token_1 = pool_state
+                .cumulative_protocol_fees_token_1
+                .checked_add(u128::from(protocol_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_fund_fees_token_1 = pool_state
+                .cumulative_fund_fees_token_1
+                .checked_add(u128::from(fund_fee))
+                .ok_or(GammaError::MathOverflow)?;
+            pool_state.cumulative_volume_token_1 = pool_state
+                .cumulative_volume_token_1
+                .checked_add(u128::from(input_share))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+    }
+    let lp_fee = u64::try_from(result.dynamic_fee)
+        .or(err!(GammaError::MathOverflow))?
+        .saturating_sub(protocol_fee)
+        .saturating_sub(fund_fee);
+    pool_state.accrue_lp_fee(trade_direction == TradeDirection::ZeroForOne, lp_fee)?;
+    pool_state.check_price_bounds(token_0_price_x32_before_swap)?;
+    pool_state.check_slot_price_impact(Clock::get()?.slot, token_0_price_x32_before_swap)?;
+    // Pools with a price band take the band and its oracle as remaining accounts
+    check_price_band(
+        pool_state,
+        pool_id,
+        ctx.remaining_accounts.first(),
+        ctx.remaining_accounts.get(1),
+        token_0_price_x32_before_swap,
+    )?;
+    pool_state.check_twap_deviation(
+        &observation_state,
+        &observation_archive,
+        block_timestamp,
+        token_0_price_x32_before_swap,
+        token_1_price_x32_before_swap,
+    )?;
+    // Followed by the `MintExposure` of token_0 and token_1 for pools tracking their mints
+    update_mint_exposures(
+        pool_state,
+        (total_token_0_amount, total_token_1_amount),
+        ctx.remaining_accounts.get(2),
+        ctx.remaining_accounts.get(3),
+    )?;
+    pool_state.latest_dynamic_fee_rate = result.dynamic_fee_rate;
+    // And the `QuoteCache` of pools keeping one
+    refresh_quote_cache(pool_state, pool_id, ctx.remaining_accounts.get(4))?;
+    pool_state.lp_supply = pool_state
+        .lp_supply
+        .checked_sub(lp_token_amount)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let user_pool_liquidity = &mut ctx.accounts.user_pool_liquidity;
+    user_pool_liquidity.accrue_lp_fees(pool_state)?;
+    user_pool_liquidity.lp_tokens_owned = user_pool_liquidity
+        .lp_tokens_owned
+        .checked_sub(u128::from(lp_token_amount))
+        .ok_or(GammaError::MathOverflow)?;
+    pool_state.track_lp_concentration(
+        pool_id,
+        user_pool_liquidity.user,
+        user_pool_liquidity.lp_tokens_owned,
+    )?;
+    match trade_direction {
+        TradeDirection::ZeroForOne => {
+            user_pool_liquidity.token_1_withdrawn = user_pool_liquidity
+                .token_1_withdrawn
+                .checked_add(u128::from(receive_amount))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        TradeDirection::OneForZero => {
+            user_pool_liquidity.token_0_withdrawn = user_pool_liquidity
+                .token_0_withdrawn
+                .checked_add(u128::from(receive_amount))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+    }
+
+    if let Some(user_pool_liquidity_partner) = user_pool_liquidity.partner {
+        let mut pool_state_partners = pool_state.partners;
+        if let Some(partner) = pool_state_partners
+            .iter_mut()
+            .find(|p| p.links(user_pool_liquidity_partner))
+        {
+            // A partner added again starts from zero while LP linked before stays linked
+            partner.lp_token_linked_with_partner = partner
+                .lp_token_linked_with_partner
+                .saturating_sub(lp_token_amount);
+        }
+        pool_state.partners = pool_state_partners;
+    }
+
+    // Remaining accounts 6 onwards are the strategy of the output vault
+    let (output_strategy, _) =
+        StrategyAccounts::split(ctx.remaining_accounts.get(6..).unwrap_or_default());
+    recall_for_payout(
+        output_strategy.as_ref(),
+        pool_id,
+        &ctx.accounts.output_vault.to_account_info(),
+        &output_mint_info,
+        &ctx.accounts.output_token_program.to_account_info(),
+        output_amount,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.output_token_account.to_account_info(),
+        output_mint_info,
+        ctx.accounts.output_token_program.to_account_info(),
+        output_amount,
+        ctx.accounts.output_token_mint.decimals,
+        &[&[crate::AUTH_SEED.as_bytes(), &[pool_state.auth_bump]]],
+    )?;
+
+    observation_state.update_with_archive(
+        &mut observation_archive,
+        oracle::block_timestamp()?,
+        token_0_price_x32_before_swap,
+        token_1_price_x32_before_swap,
+    )?;
+    pool_state.update_ema_price(
+        oracle::block_timestamp()?,
+        token_0_price_x32_before_swap,
+        token_1_price_x32_before_swap,
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
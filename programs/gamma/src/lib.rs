@@ -1,9 +1,12 @@
+#[cfg(feature = "client")]
+pub mod client;
 pub mod curve;
 pub mod error;
 mod external;
 pub mod fees;
 pub mod instructions;
 pub mod migration;
+pub mod router_metadata;
 pub mod states;
 pub mod utils;
 
@@ -92,179 +95,1713 @@ pub mod gamma {
         instructions::create_referral_project(ctx, name, default_share_bps)
     }
 
+    /// Updates the config's referral project, set up by `create_swap_referral`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `name` - The new project name, `None` keeps the current one
+    /// * `default_share_bps` - The new share of fees to referrers, `None` keeps the current one
+    ///
+    pub fn update_swap_referral(
+        ctx: Context<UpdateReferralProject>,
+        name: Option<String>,
+        default_share_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::update_referral_project(ctx, name, default_share_bps)
+    }
+
     /// Updates the owner of the amm config
-    /// Must be called by the current owner or admin
+    /// Must be called by the current owner or admin. Once the config has a fee approver, large
+    /// fee rate changes go through `propose_fee_change` instead, and once it has a
+    /// `fee_change_delay` fee rate changes go through `propose_config_update`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `trade_fee_rate`- The new trade fee rate of amm config, be set when `param` is 0
+    /// * `protocol_fee_rate`- The new protocol fee rate of amm config, be set when `param` is 1
+    /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
+    /// * `new_owner`- The config's new owner, be set when `param` is 3
+    /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
+    /// * `param`- The vaule can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    ///
+    pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u16, value: u64) -> Result<()> {
+        instructions::update_amm_config(ctx, param, value)
+    }
+
+    /// Proposes a config change that needs the fee approver's co-signature, see
+    /// `AmmConfig::fee_approver`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param`- The `update_amm_config` param to change
+    /// * `value`- The new value of the param
+    ///
+    pub fn propose_fee_change(ctx: Context<ProposeFeeChange>, param: u16, value: u64) -> Result<()> {
+        instructions::propose_fee_change(ctx, param, value)
+    }
+
+    /// Applies the pending config change, signed by the fee approver
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param`- The param of the pending change
+    /// * `value`- The value of the pending change
+    /// * `new_key`- The account of the pending change, the default pubkey if it takes none
+    ///
+    pub fn approve_fee_change(
+        ctx: Context<ApproveFeeChange>,
+        param: u16,
+        value: u64,
+        new_key: Pubkey,
+    ) -> Result<()> {
+        instructions::approve_fee_change(ctx, param, value, new_key)
+    }
+
+    /// Schedules a timelocked config change, applied by `apply_config_update` once the config's
+    /// `fee_change_delay` has passed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param`- The `update_amm_config` param to change, 0 | 1 | 2 | 14 | 15 or 13 shortening
+    /// the delay
+    /// * `value`- The new value of the param
+    ///
+    pub fn propose_config_update(
+        ctx: Context<ProposeConfigUpdate>,
+        param: u16,
+        value: u64,
+    ) -> Result<()> {
+        instructions::propose_config_update(ctx, param, value)
+    }
+
+    /// Applies the config's pending update after its delay
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn apply_config_update(ctx: Context<ApplyConfigUpdate>) -> Result<()> {
+        instructions::apply_config_update(ctx)
+    }
+
+    /// Drops the config's pending update
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn cancel_config_update(ctx: Context<CancelConfigUpdate>) -> Result<()> {
+        instructions::cancel_config_update(ctx)
+    }
+
+    /// Update pool status for given vaule
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `param`- The param of pool status
+    /// * `status` - The value
+    ///
+    pub fn update_pool(ctx: Context<UpdatePool>, param: u32, value: u64) -> Result<()> {
+        instructions::update_pool(ctx, param, value)
+    }
+
+    /// Sets the pool's own trade fee in place of its config's. The admin sets any rate, the pool
+    /// creator one within the config's `min_pool_trade_fee_rate` and `max_pool_trade_fee_rate`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `trade_fee_rate` - The pool's trade fee rate, zero to use the config's again
+    ///
+    pub fn update_pool_fee(ctx: Context<UpdatePoolFee>, trade_fee_rate: u64) -> Result<()> {
+        instructions::update_pool_fee(ctx, trade_fee_rate)
+    }
+
+    /// Moves the open time of a pool that hasn't opened yet, earlier or later. Must be called by
+    /// the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `open_time` - The new timestamp allowed for swap, within the config's open delay bounds
+    ///
+    pub fn update_pool_open_time(ctx: Context<UpdatePoolOpenTime>, open_time: u64) -> Result<()> {
+        instructions::update_pool_open_time(ctx, open_time)
+    }
+
+    /// Caps the size of a single swap of the pool, as a share of the reserve it trades against.
+    /// Must be called by the admin or the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_trade_size_bps` - The cap in bps, zero to lift it
+    ///
+    pub fn update_max_trade_size(
+        ctx: Context<UpdateMaxTradeSize>,
+        max_trade_size_bps: u64,
+    ) -> Result<()> {
+        instructions::update_max_trade_size(ctx, max_trade_size_bps)
+    }
+
+    /// Caps the LP supply deposits can mint, for guarded launches and capacity-limited pools.
+    /// Must be called by the admin or the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_lp_supply` - The cap in lp tokens, zero to lift it
+    ///
+    pub fn update_max_lp_supply(ctx: Context<UpdateMaxLpSupply>, max_lp_supply: u64) -> Result<()> {
+        instructions::update_max_lp_supply(ctx, max_lp_supply)
+    }
+
+    /// Limits deposits and/or swaps of the pool to the addresses its creator whitelisted.
+    /// Must be called by the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `gate_deposits` - Whether only whitelisted addresses can deposit
+    /// * `gate_swaps` - Whether only whitelisted addresses can swap
+    ///
+    pub fn set_pool_whitelist_mode(
+        ctx: Context<SetPoolWhitelistMode>,
+        gate_deposits: bool,
+        gate_swaps: bool,
+    ) -> Result<()> {
+        instructions::set_pool_whitelist_mode(ctx, gate_deposits, gate_swaps)
+    }
+
+    /// Attaches an allowlisted swap hook program to the pool, called with the `SwapEvent` after
+    /// each swap. Must be called by the admin or the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `hook_accounts_len` - Number of accounts of its own the hook program takes
+    ///
+    pub fn set_pool_swap_hook(ctx: Context<SetPoolSwapHook>, hook_accounts_len: u8) -> Result<()> {
+        instructions::set_pool_swap_hook(ctx, hook_accounts_len)
+    }
+
+    /// Detaches the swap hook program of the pool. Must be called by the admin or the pool
+    /// creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn clear_pool_swap_hook(ctx: Context<ClearPoolSwapHook>) -> Result<()> {
+        instructions::clear_pool_swap_hook(ctx)
+    }
+
+    /// Sets a trade fee falling from `launch_fee_rate` at the pool's open time to the fee model's
+    /// rate, so snipers of a launch pay LPs. Must be called by the pool creator before the pool
+    /// opens
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `launch_fee_rate` - The trade fee rate at the open time, zero to remove the launch fee
+    /// * `launch_fee_duration` - Seconds the launch fee takes to fall to the fee model's rate
+    ///
+    pub fn set_launch_fee(
+        ctx: Context<SetLaunchFee>,
+        launch_fee_rate: u64,
+        launch_fee_duration: u64,
+    ) -> Result<()> {
+        instructions::set_launch_fee(ctx, launch_fee_rate, launch_fee_duration)
+    }
+
+    /// Shifts the token weights of a weighted pool linearly over time, making it a liquidity
+    /// bootstrapping pool. Must be called by the pool creator before the pool opens
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `token_0_end_weight` - Weight of token_0 in bps once the schedule ends
+    /// * `weight_schedule_start` - Timestamp the weights start moving at
+    /// * `weight_schedule_end` - Timestamp the weights reach the end weights at
+    ///
+    pub fn set_weight_schedule(
+        ctx: Context<SetWeightSchedule>,
+        token_0_end_weight: u64,
+        weight_schedule_start: u64,
+        weight_schedule_end: u64,
+    ) -> Result<()> {
+        instructions::set_weight_schedule(
+            ctx,
+            token_0_end_weight,
+            weight_schedule_start,
+            weight_schedule_end,
+        )
+    }
+
+    /// Opens a launch selling a new token for a quote token along a bonding curve, which
+    /// graduates into a pool of the config once it raised its target. The creator deposits the
+    /// whole supply of a token that can't be minted or frozen anymore
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `virtual_token_reserve` - Token reserve priced on top of the tokens for sale
+    /// * `virtual_quote_reserve` - Quote reserve priced on top of the quote raised
+    /// * `graduation_quote_amount` - Quote raised at which the curve stops trading
+    /// * `max_trade_fee_rate` - The maximum trade fee of the pool the curve graduates into
+    /// * `volatility_factor` - The volatility factor of the pool the curve graduates into
+    ///
+    pub fn create_bonding_curve(
+        ctx: Context<CreateBondingCurve>,
+        virtual_token_reserve: u64,
+        virtual_quote_reserve: u64,
+        graduation_quote_amount: u64,
+        max_trade_fee_rate: u64,
+        volatility_factor: u64,
+    ) -> Result<()> {
+        instructions::create_bonding_curve(
+            ctx,
+            virtual_token_reserve,
+            virtual_quote_reserve,
+            graduation_quote_amount,
+            max_trade_fee_rate,
+            volatility_factor,
+        )
+    }
+
+    /// Buys tokens of a bonding curve that hasn't raised its target
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `quote_amount_in` - Quote paid for the tokens
+    /// * `minimum_token_amount_out` - Minimum tokens to receive, prevents excessive slippage
+    ///
+    pub fn buy_bonding_curve(
+        ctx: Context<TradeBondingCurve>,
+        quote_amount_in: u64,
+        minimum_token_amount_out: u64,
+    ) -> Result<()> {
+        instructions::buy_bonding_curve(ctx, quote_amount_in, minimum_token_amount_out)
+    }
+
+    /// Sells tokens back to a bonding curve that hasn't raised its target
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `token_amount_in` - Tokens sold
+    /// * `minimum_quote_amount_out` - Minimum quote to receive, prevents excessive slippage
+    ///
+    pub fn sell_bonding_curve(
+        ctx: Context<TradeBondingCurve>,
+        token_amount_in: u64,
+        minimum_quote_amount_out: u64,
+    ) -> Result<()> {
+        instructions::sell_bonding_curve(ctx, token_amount_in, minimum_quote_amount_out)
+    }
+
+    /// Seeds a pool with what a bonding curve that raised its target holds and locks the LP in
+    /// a position of the curve creator. Can be called by anyone
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+        instructions::graduate(ctx)
+    }
+
+    /// Whitelists an address in the pool. Must be called by the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `address` - The signer to whitelist, the owner of deposits or payer of swaps
+    ///
+    pub fn add_pool_whitelist_entry(
+        ctx: Context<AddPoolWhitelistEntry>,
+        address: Pubkey,
+    ) -> Result<()> {
+        instructions::add_pool_whitelist_entry(ctx, address)
+    }
+
+    /// Takes an address off the pool's whitelist. Must be called by the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn remove_pool_whitelist_entry(ctx: Context<RemovePoolWhitelistEntry>) -> Result<()> {
+        instructions::remove_pool_whitelist_entry(ctx)
+    }
+
+    /// Collect the protocol fee accrued to the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    ///
+    pub fn collect_protocol_fee(
+        ctx: Context<CollectProtocolFee>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_protocol_fee(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Sets where `sweep_protocol_fees` sends the protocol fees of a config's pools
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `treasury` - Owner of the associated token accounts receiving the fees
+    /// * `bounty_rate` - Share of the swept fees paid to the cranker, at most 1%
+    ///
+    pub fn set_fee_treasury(
+        ctx: Context<SetFeeTreasury>,
+        treasury: Pubkey,
+        bounty_rate: u64,
+    ) -> Result<()> {
+        instructions::set_fee_treasury(ctx, treasury, bounty_rate)
+    }
+
+    /// Permissionless crank sending the protocol fees of many pools of a config to its treasury
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, each pool passed as remaining accounts
+    ///
+    pub fn sweep_protocol_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepProtocolFees<'info>>,
+    ) -> Result<()> {
+        instructions::sweep_protocol_fees(ctx)
+    }
+
+    /// Collect the fund fee accrued to the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
+    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    ///
+    pub fn collect_fund_fee(
+        ctx: Context<CollectFundFee>,
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    ) -> Result<()> {
+        instructions::collect_fund_fee(ctx, amount_0_requested, amount_1_requested)
+    }
+
+    /// Sets the mint `convert_fund_fees` converts the fund fees of a config's pools into
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn set_fund_fee_target_mint(ctx: Context<SetFundFeeTargetMint>) -> Result<()> {
+        instructions::set_fund_fee_target_mint(ctx)
+    }
+
+    /// Collects the fund fees of one token of the pool and swaps them into the target mint,
+    /// through the pool itself or a sibling pool, which can't have a swap hook
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, the swapping pool passed as remaining accounts
+    /// * `minimum_amount_out` - The minimum amount of the target mint to receive
+    ///
+    pub fn convert_fund_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ConvertFundFees<'info>>,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::convert_fund_fees(ctx, minimum_amount_out)
+    }
+
+    /// Sweep the tokens the vaults hold beyond the pool's accounting, e.g. airdrops or direct
+    /// transfers, to the treasury
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn skim_surplus(ctx: Context<SkimSurplus>) -> Result<()> {
+        instructions::skim_surplus(ctx)
+    }
+
+    /// Moves the LP owned liquidity of a pool into a timelocked escrow and disables the pool.
+    /// Only for catastrophic scenarios, the funds can only go back to the pool or to its LP holders
+    ///
+    /// Like withdrawals, it waits for the grace period after trading got paused and doesn't
+    /// happen in withdraw only mode
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn emergency_withdraw_to_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, EmergencyWithdrawToEscrow<'info>>,
+    ) -> Result<()> {
+        instructions::emergency_withdraw_to_escrow(ctx)
+    }
+
+    /// Moves the liquidity in an emergency escrow back into the pool with the status it had
+    /// before, and closes the escrow. Not possible once LP holders claimed from the escrow
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn restore_escrow_to_pool(ctx: Context<RestoreEscrowToPool>) -> Result<()> {
+        instructions::restore_escrow_to_pool(ctx)
+    }
+
+    /// Registers a solver allowed to fill RFQ orders and creates the vault holding its bond
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `min_bond` - Bond the solver needs to quote
+    ///
+    pub fn register_solver(ctx: Context<RegisterSolver>, min_bond: u64) -> Result<()> {
+        instructions::register_solver(ctx, min_bond)
+    }
+
+    /// Activate or deactivate a solver and update its minimum bond
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `active` - Whether the solver can quote, deactivating it starts its unbond period
+    /// * `min_bond` - Bond the solver needs to quote
+    ///
+    pub fn update_solver(ctx: Context<UpdateSolver>, active: bool, min_bond: u64) -> Result<()> {
+        instructions::update_solver(ctx, active, min_bond)
+    }
+
+    /// Slash a solver's bond for a failed settlement, paying it to the given token account
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Bond amount to slash
+    ///
+    pub fn slash_solver(ctx: Context<SlashSolver>, amount: u64) -> Result<()> {
+        instructions::slash_solver(ctx, amount)
+    }
+
+    /// Creates the gauge controller directing reward emissions with veGOFX votes
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `epoch_duration` - Length of a voting epoch in seconds, can't be changed later
+    /// * `emission_per_epoch` - Rewards emitted across all gauges per epoch
+    ///
+    pub fn create_gauge_controller(
+        ctx: Context<CreateGaugeController>,
+        epoch_duration: u64,
+        emission_per_epoch: u64,
+    ) -> Result<()> {
+        instructions::create_gauge_controller(ctx, epoch_duration, emission_per_epoch)
+    }
+
+    /// Update the rewards emitted across all gauges per epoch
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `emission_per_epoch` - Rewards emitted across all gauges per epoch
+    ///
+    pub fn update_gauge_controller(
+        ctx: Context<UpdateGaugeController>,
+        emission_per_epoch: u64,
+    ) -> Result<()> {
+        instructions::update_gauge_controller(ctx, emission_per_epoch)
+    }
+
+    /// Creates or updates the admin verified badge of a mint, recording its bridge origin and
+    /// whether it is canonical
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `canonical` - Whether the mint is the canonical representation of its asset
+    /// * `bridge_origin` - 0: native, 1: wormhole, 2: other bridge
+    /// * `origin_chain_id` - Chain id of the origin chain, zero for native mints
+    /// * `origin_address` - Token address on the origin chain
+    ///
+    pub fn set_token_badge(
+        ctx: Context<SetTokenBadge>,
+        canonical: bool,
+        bridge_origin: u8,
+        origin_chain_id: u16,
+        origin_address: [u8; 32],
+    ) -> Result<()> {
+        instructions::set_token_badge(
+            ctx,
+            canonical,
+            bridge_origin,
+            origin_chain_id,
+            origin_address,
+        )
+    }
+
+    /// Allowlists a transfer hook program, so mints using it can be traded in the pools
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn add_transfer_hook_program(ctx: Context<AddTransferHookProgram>) -> Result<()> {
+        instructions::add_transfer_hook_program(ctx)
+    }
+
+    /// Removes a transfer hook program from the allowlist
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn remove_transfer_hook_program(ctx: Context<RemoveTransferHookProgram>) -> Result<()> {
+        instructions::remove_transfer_hook_program(ctx)
+    }
+
+    /// Allowlists a strategy adapter program wrapping a lending protocol, so vaults can deploy
+    /// their idle reserve through it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `protocol` - The lending protocol the adapter wraps
+    ///
+    pub fn add_strategy_program(
+        ctx: Context<AddStrategyProgram>,
+        protocol: states::LendingProtocol,
+    ) -> Result<()> {
+        instructions::add_strategy_program(ctx, protocol)
+    }
+
+    /// Removes a strategy adapter program from the allowlist
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn remove_strategy_program(ctx: Context<RemoveStrategyProgram>) -> Result<()> {
+        instructions::remove_strategy_program(ctx)
+    }
+
+    /// Allowlists a swap hook program, so pools can call it after their swaps
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn add_swap_hook_program(ctx: Context<AddSwapHookProgram>) -> Result<()> {
+        instructions::add_swap_hook_program(ctx)
+    }
+
+    /// Removes a swap hook program from the allowlist
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn remove_swap_hook_program(ctx: Context<RemoveSwapHookProgram>) -> Result<()> {
+        instructions::remove_swap_hook_program(ctx)
+    }
+
+    /// Sets the strategy a pool vault deploys its idle reserve with, through an allowlisted
+    /// adapter program. The lending protocol accounts of the adapter are the remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `target_rate` - Share of the reserve to keep deployed, at most 90%
+    ///
+    pub fn set_vault_strategy<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetVaultStrategy<'info>>,
+        target_rate: u64,
+    ) -> Result<()> {
+        instructions::set_vault_strategy(ctx, target_rate)
+    }
+
+    /// Sets the band around a Pyth or Switchboard price the pool price has to stay in. Swaps of
+    /// a pool with a band must pass the band and its oracle as remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `oracle_source` - 0: Pyth price update, 1: Switchboard on-demand pull feed
+    /// * `invert` - Whether the oracle quotes token_1 in token_0
+    /// * `max_deviation_bps` - Furthest the pool price can be from the oracle price, zero turns
+    ///   the band off
+    /// * `max_staleness` - Oldest oracle update accepted, in seconds
+    ///
+    pub fn set_price_band(
+        ctx: Context<SetPriceBand>,
+        oracle_source: u8,
+        invert: bool,
+        max_deviation_bps: u64,
+        max_staleness: u64,
+    ) -> Result<()> {
+        instructions::set_price_band(
+            ctx,
+            oracle_source,
+            invert,
+            max_deviation_bps,
+            max_staleness,
+        )
+    }
+
+    /// Sets the pool status like `update_pool`, but the pause lapses after `duration` unless the
+    /// admin or the pause authority renews it by pausing again
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `status` - The status bits to set
+    /// * `duration` - Seconds until anyone can restore the status from before the pause
+    ///
+    pub fn emergency_pause(ctx: Context<EmergencyPause>, status: u8, duration: u64) -> Result<()> {
+        instructions::emergency_pause(ctx, status, duration)
+    }
+
+    /// Enters or exits withdraw only mode, where deposits and swaps are disabled and withdrawals
+    /// are guaranteed to work. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `enabled` - Whether to enter or exit the mode
+    ///
+    pub fn set_withdraw_only(ctx: Context<SetWithdrawOnly>, enabled: bool) -> Result<()> {
+        instructions::set_withdraw_only(ctx, enabled)
+    }
+
+    /// Switches the fee model the pool's swaps price with, after a delay. Must be called by the
+    /// admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `fee_model` - The model and its params, the current model cancels a pending switch
+    ///
+    pub fn update_fee_model(
+        ctx: Context<UpdateFeeModel>,
+        fee_model: fees::FeeModel,
+    ) -> Result<()> {
+        instructions::update_fee_model(ctx, fee_model)
+    }
+
+    /// Sets the trade fee discounts the config's pools give traders staking GOFX. Must be called
+    /// by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `tiers` - Minimum stake and discount of each tier, sorted by the minimum stake
+    ///
+    pub fn set_staking_discount(
+        ctx: Context<SetStakingDiscount>,
+        tiers: [states::StakingDiscountTier; states::STAKING_DISCOUNT_TIER_NUM],
+    ) -> Result<()> {
+        instructions::set_staking_discount(ctx, tiers)
+    }
+
+    /// Onboards an order-flow partner, or updates its segmenters and kickback rate. Must be
+    /// called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `partner_id` - Id of the partner's registry
+    /// * `kickback_bps` - Share of the dynamic fee the partner's segmenters earn
+    /// * `segmenters` - The partner's segmenters, empty to offboard the partner
+    ///
+    pub fn set_segmenter_registry(
+        ctx: Context<SetSegmenterRegistry>,
+        partner_id: u16,
+        kickback_bps: u64,
+        segmenters: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_segmenter_registry(ctx, partner_id, kickback_bps, segmenters)
+    }
+
+    /// Restores the status from before an emergency pause that wasn't renewed in time.
+    /// Permissionless.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn expire_pause(ctx: Context<ExpirePause>) -> Result<()> {
+        instructions::expire_pause(ctx)
+    }
+
+    /// Hands one role of a config to `authority`, the default key taking it back to the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `role` - The role to set, config updates, fee collection or pausing pools
+    /// * `authority` - The new holder of the role
+    ///
+    pub fn set_config_role(
+        ctx: Context<SetConfigRole>,
+        role: states::ConfigRole,
+        authority: Pubkey,
+    ) -> Result<()> {
+        instructions::set_config_role(ctx, role, authority)
+    }
+
+    /// Sets the cap on the reserves of a mint held across the pools tracking it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `cap` - Most reserves of the mint the tracked pools can hold together
+    ///
+    pub fn set_mint_exposure_cap(ctx: Context<SetMintExposureCap>, cap: u64) -> Result<()> {
+        instructions::set_mint_exposure_cap(ctx, cap)
+    }
+
+    /// Starts or stops counting the pool's reserve of a mint towards the mint's exposure
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `tracked` - Whether the pool counts towards the exposure
+    ///
+    pub fn track_mint_exposure(ctx: Context<TrackMintExposure>, tracked: bool) -> Result<()> {
+        instructions::track_mint_exposure(ctx, tracked)
+    }
+
+    /// Creates the small account holding the pool's reserves and fee rate for cheap polling,
+    /// swaps, deposits and withdrawals of the pool have to pass it from then on
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_quote_cache(ctx: Context<CreateQuoteCache>) -> Result<()> {
+        instructions::create_quote_cache(ctx)
+    }
+
+    /// Claim the owner's pro-rata share of an emergency escrow after the timelock, for the LP of
+    /// their liquidity, positions and LP tokens
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn claim_emergency_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimEmergencyEscrow<'info>>,
+    ) -> Result<()> {
+        instructions::claim_emergency_escrow(ctx)
+    }
+
+    /// Creates a pool for the given token pair and the initial price
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `init_amount_0` - the initial amount_0 to deposit
+    /// * `init_amount_1` - the initial amount_1 to deposit
+    /// * `open_time` - the timestamp allowed for swap
+    /// * `max_trade_fee_rate` - The maximum trade fee that can be charged on swaps
+    /// * `volatility_factor` - The volatility factor of the pool to determine the trade fee
+    /// * `amplification` - Amplification coefficient of a stable swap pool, 0 for constant product
+    /// * `token_0_weight` - Weight of token_0 in bps of a weighted pool, token_1 weighs the rest. 0 for an unweighted pool
+    ///
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+        max_trade_fee_rate: u64,
+        volatility_factor: u64,
+        amplification: u64,
+        token_0_weight: u64,
+    ) -> Result<()> {
+        instructions::initialize(
+            ctx,
+            init_amount_0,
+            init_amount_1,
+            open_time,
+            max_trade_fee_rate,
+            volatility_factor,
+            amplification,
+            token_0_weight,
+        )
+    }
+
+    /// Creates a constant product pool at the given initial price, the counterpart amount is computed on-chain
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `initial_price_x32` - Price of one whole token_0 in whole token_1, as a Q32.32 number. Mint decimals are applied on-chain
+    /// * `amount` - The initial amount of the token selected by `amount_is_token_0` to deposit
+    /// * `amount_is_token_0` - Whether `amount` is denominated in token_0 or token_1
+    /// * `open_time` - the timestamp allowed for swap
+    /// * `max_trade_fee_rate` - The maximum trade fee that can be charged on swaps
+    /// * `volatility_factor` - The volatility factor of the pool to determine the trade fee
+    ///
+    pub fn initialize_with_price<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
+        initial_price_x32: u128,
+        amount: u64,
+        amount_is_token_0: bool,
+        open_time: u64,
+        max_trade_fee_rate: u64,
+        volatility_factor: u64,
+    ) -> Result<()> {
+        instructions::initialize_with_price(
+            ctx,
+            initial_price_x32,
+            amount,
+            amount_is_token_0,
+            open_time,
+            max_trade_fee_rate,
+            volatility_factor,
+        )
+    }
+
+    pub fn init_user_pool_liquidity(
+        ctx: Context<InitUserPoolLiquidity>,
+        partner: Option<String>,
+    ) -> Result<()> {
+        instructions::init_user_pool_liquidity(ctx, partner)
+    }
+
+    /// Grows a UserPoolLiquidity account created before it had a referrer to the current size
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn migrate_user_pool_liquidity(ctx: Context<MigrateUserPoolLiquidity>) -> Result<()> {
+        instructions::migrate_user_pool_liquidity(ctx)
+    }
+
+    /// Grows an observation account of the layout from before observations kept the log price
+    /// to the current one, keeping its observations. Anyone can call it, the payer funds the
+    /// extra rent
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn migrate_observation_state(ctx: Context<MigrateObservationState>) -> Result<()> {
+        instructions::migrate_observation_state(ctx)
+    }
+
+    /// Grows a pool created with an older PoolState layout to the current size and version.
+    /// Anyone can call it, the payer funds the extra rent
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn migrate_pool_state(ctx: Context<MigratePoolState>) -> Result<()> {
+        instructions::migrate_pool_state(ctx)
+    }
+
+    /// Clears the observations of a pool whose oracle got corrupted, keeping the cumulative
+    /// prices of the newest valid observation. Must be called by the admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn rebuild_observation_state(ctx: Context<RebuildObservationState>) -> Result<()> {
+        instructions::rebuild_observation_state(ctx)
+    }
+
+    /// Creates a pool for the given token pair and the initial price
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Pool token amount to transfer. token_a and token_b amount are set by the current exchange rate and size of the pool
+    /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, prevents excessive slippage
+    /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, prevents excessive slippage
+    ///
+    /// The amm config and a referral account can be passed as remaining accounts to attribute the
+    /// liquidity to a referrer, who then receives a share of the owner's LP fee claims
+    ///
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::deposit(
+            ctx,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        )
+    }
+
+    /// Deposit only token_0 or token_1, swapping part of it through the pool so the rest
+    /// matches the pool ratio, and credit the owner with the resulting lp
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `maximum_amount_in` - Maximum amount of the deposited token to transfer, the part the
+    ///   deposit doesn't need stays with the owner
+    /// * `minimum_lp_token_amount` - Minimum lp to receive, prevents excessive slippage
+    ///
+    pub fn deposit_single_token(
+        ctx: Context<DepositSingleToken>,
+        maximum_amount_in: u64,
+        minimum_lp_token_amount: u64,
+    ) -> Result<()> {
+        instructions::deposit_single_token(ctx, maximum_amount_in, minimum_lp_token_amount)
+    }
+
+    /// Donate token_0 and token_1 to the pool's reserves without minting lp, raising the value
+    /// of every lp token
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_0` - Amount of token 0 to donate, transfer fees included
+    /// * `amount_1` - Amount of token 1 to donate, transfer fees included
+    ///
+    pub fn donate(ctx: Context<Donate>, amount_0: u64, amount_1: u64) -> Result<()> {
+        instructions::donate(ctx, amount_0, amount_1)
+    }
+
+    /// Withdraw lp for token0 ande token1
+    /// The proceeds can go to token accounts of another wallet, passed as remaining account 3.
+    /// Vaults with a strategy take its accounts from remaining account 4 on, token_0 first
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn. User receives an output of token a and b based on the percentage of the pool tokens that are returned.
+    /// * `minimum_token_0_amount` -  Minimum amount of token 0 to receive, prevents excessive slippage
+    /// * `minimum_token_1_amount` -  Minimum amount of token 1 to receive, prevents excessive slippage
+    ///
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw(
+            ctx,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+        )
+    }
+
+    /// Withdraw lp entirely in token_0 or token_1, swapping the share of the other token through
+    /// the pool. The proceeds can go to a token account of another wallet, passed as remaining
+    /// account 5. An output vault with a strategy takes its accounts from remaining account 6 on
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of pool tokens to burn
+    /// * `minimum_amount_out` - Minimum amount of the withdrawn token to receive, prevents excessive slippage
+    ///
+    pub fn withdraw_single_token<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawSingleToken<'info>>,
+        lp_token_amount: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::withdraw_single_token(ctx, lp_token_amount, minimum_amount_out)
+    }
+
+    /// Swap the tokens in the pool base input amount, returns the `SwapOutcome` via return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate
+    /// * `minimum_amount_out` -  Minimum amount of output token, prevents excessive slippage
+    ///
+    pub fn swap_base_input<'c, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<SwapOutcome> {
+        instructions::swap_base_input(ctx, amount_in, minimum_amount_out)
+    }
+
+    /// Swap the tokens in the pool base output amount, returns the `SwapOutcome` via return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `max_amount_in` -  input amount prevents excessive slippage
+    /// * `amount_out` -  amount of output token
+    ///
+    pub fn swap_base_output<'c, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> Result<SwapOutcome> {
+        instructions::swap_base_output(ctx, max_amount_in, amount_out)
+    }
+
+    /// Swap through 2 to 3 pools atomically, e.g. A -> B -> C, each hop swapping the full
+    /// output of the previous one
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount_in` -  input amount of the first hop
+    /// * `minimum_amount_out` -  Minimum output of the last hop, prevents excessive slippage
+    ///
+    /// Each hop is passed as remaining accounts in the order of the `swap_base_input` accounts,
+    /// pools with a swap hook can't be a hop
+    ///
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        instructions::swap_route(ctx, amount_in, minimum_amount_out)
+    }
+
+    /// Swap against one pool several times, in either direction, e.g. for market makers
+    /// rebalancing in quick succession
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `legs` - Direction, input amount and minimum output of each swap, executed in order
+    ///
+    /// The pool is passed as remaining accounts in the order of the `swap_base_input` accounts
+    /// of a swap selling token_0, it can't have a swap hook
+    ///
+    pub fn swap_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapBatch<'info>>,
+        legs: Vec<SwapBatchLeg>,
+    ) -> Result<()> {
+        instructions::swap_batch(ctx, legs)
+    }
+
+    /// Borrow tokens of a pool vault for the rest of the transaction. A `flash_repay` of the
+    /// same pool must come later in the transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount` - Amount to borrow, the fee is set by the amm config
+    ///
+    pub fn flash_loan(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+        instructions::flash_loan(ctx, amount)
+    }
+
+    /// Repay the outstanding flash loan of a pool with its fee
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn flash_repay(ctx: Context<FlashRepay>) -> Result<()> {
+        instructions::flash_repay(ctx)
+    }
+
+    /// Add to the bond of a registered solver
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount` - Amount of the bond mint to deposit
+    ///
+    pub fn bond_solver(ctx: Context<BondSolver>, amount: u64) -> Result<()> {
+        instructions::bond_solver(ctx, amount)
+    }
+
+    /// Withdraw a solver's bond, only once it was deactivated for the unbond period
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `amount` - Amount of the bond to withdraw
+    ///
+    pub fn withdraw_solver_bond(ctx: Context<BondSolver>, amount: u64) -> Result<()> {
+        instructions::withdraw_solver_bond(ctx, amount)
+    }
+
+    /// Creates the account accruing a registered segmenter's kickback for a pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn init_segmenter_kickback(ctx: Context<InitSegmenterKickback>) -> Result<()> {
+        instructions::init_segmenter_kickback(ctx)
+    }
+
+    /// Claim the kickback accrued to a segmenter for routing swaps through the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn claim_segmenter_kickback(ctx: Context<ClaimSegmenterKickback>) -> Result<()> {
+        instructions::claim_segmenter_kickback(ctx)
+    }
+
+    /// Claim the LP fees accrued to the owner's position in a pool accruing LP fees. Positions
+    /// with a referrer pay the referrer's share to their referral token accounts, passed as
+    /// remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn claim_lp_fees<'info>(ctx: Context<'_, '_, '_, 'info, ClaimLpFees<'info>>) -> Result<()> {
+        instructions::claim_lp_fees(ctx)
+    }
+
+    /// Close a pool every LP withdrew from and whose fees were collected, along with its vaults
+    /// and observation account. Permissionless, the rent goes back to the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        instructions::close_pool(ctx)
+    }
+
+    /// List a pool in the index of its mint pair. Permissionless, for pools created before
+    /// pair indexes existed
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn register_pair_index(ctx: Context<RegisterPairIndex>) -> Result<()> {
+        instructions::register_pair_index(ctx)
+    }
+
+    /// Creates the account tracking a pool's volume without round trips by the same signer
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn init_volume_tracker(ctx: Context<InitVolumeTracker>) -> Result<()> {
+        instructions::init_volume_tracker(ctx)
+    }
+
+    /// Emit the pool's fees by bucket, volume and average dynamic fee since its last report.
+    /// Permissionless, at most once per epoch per pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn crank_fee_report(ctx: Context<CrankFeeReport>) -> Result<()> {
+        instructions::crank_fee_report(ctx)
+    }
+
+    /// Creates the account holding a pool's trailing fee APR
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn init_fee_apr(ctx: Context<InitFeeApr>) -> Result<()> {
+        instructions::init_fee_apr(ctx)
+    }
+
+    /// Recompute a pool's trailing 24h and 7d LP fee APR and sample its cumulative fees.
+    /// Permissionless, a new sample is taken at most once per hour
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn crank_fee_apr(ctx: Context<CrankFeeApr>) -> Result<()> {
+        instructions::crank_fee_apr(ctx)
+    }
+
+    /// Creates the account tracking a pool's gauge votes and emission rate
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_gauge(ctx: Context<CreateGauge>) -> Result<()> {
+        instructions::create_gauge(ctx)
+    }
+
+    /// Vote for a pool's gauge with veGOFX voting power in the running epoch.
+    /// Votes across all gauges can't exceed the voter's veGOFX balance in an epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `power` - Voting power given to the gauge
+    ///
+    pub fn vote_gauge(ctx: Context<VoteGauge>, power: u64) -> Result<()> {
+        instructions::vote_gauge(ctx, power)
+    }
+
+    /// Set a gauge's emission rate for the running epoch from its share of the previous
+    /// epoch's votes. Permissionless, once per epoch per gauge.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn settle_gauge(ctx: Context<SettleGauge>) -> Result<()> {
+        instructions::settle_gauge(ctx)
+    }
+
+    /// Add a reward mint emitted to the LP staked in the pool. The reward vault is funded with
+    /// plain token transfers
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `emissions_per_second` - Rewards emitted per second, shared by all staked LP
+    ///
+    pub fn create_reward_vault(
+        ctx: Context<CreateRewardVault>,
+        emissions_per_second: u64,
+    ) -> Result<()> {
+        instructions::create_reward_vault(ctx, emissions_per_second)
+    }
+
+    /// Update the emission rate of one of the pool's rewards
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - Index of the reward in `PoolRewards::rewards`
+    /// * `emissions_per_second` - Rewards emitted per second, zero stops the emissions
+    ///
+    pub fn set_reward_emissions(
+        ctx: Context<SetRewardEmissions>,
+        reward_index: u8,
+        emissions_per_second: u64,
+    ) -> Result<()> {
+        instructions::set_reward_emissions(ctx, reward_index, emissions_per_second)
+    }
+
+    /// Stake lp tokens of the position for the pool's rewards, they can't be withdrawn until
+    /// they're unstaked
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `trade_fee_rate`- The new trade fee rate of amm config, be set when `param` is 0
-    /// * `protocol_fee_rate`- The new protocol fee rate of amm config, be set when `param` is 1
-    /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
-    /// * `new_owner`- The config's new owner, be set when `param` is 3
-    /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The vaule can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `lp_token_amount` - Amount of lp tokens to stake
     ///
-    pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u16, value: u64) -> Result<()> {
-        instructions::update_amm_config(ctx, param, value)
+    pub fn stake_lp(ctx: Context<StakeLp>, lp_token_amount: u64) -> Result<()> {
+        instructions::stake_lp(ctx, lp_token_amount)
     }
 
-    /// Update pool status for given vaule
+    /// Unstake lp tokens of the position
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `param`- The param of pool status
-    /// * `status` - The value
+    /// * `lp_token_amount` - Amount of lp tokens to unstake
     ///
-    pub fn update_pool(ctx: Context<UpdatePool>, param: u32, value: u64) -> Result<()> {
-        instructions::update_pool(ctx, param, value)
+    pub fn unstake_lp(ctx: Context<UnstakeLp>, lp_token_amount: u64) -> Result<()> {
+        instructions::unstake_lp(ctx, lp_token_amount)
     }
 
-    /// Collect the protocol fee accrued to the pool
+    /// Claim the rewards of one reward mint owed to the position's stake
     ///
     /// # Arguments
     ///
-    /// * `ctx` - The context of accounts
-    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
-    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    /// * `ctx`- The context of accounts
+    /// * `reward_index` - Index of the reward in `PoolRewards::rewards`
     ///
-    pub fn collect_protocol_fee(
-        ctx: Context<CollectProtocolFee>,
-        amount_0_requested: u64,
-        amount_1_requested: u64,
-    ) -> Result<()> {
-        instructions::collect_protocol_fee(ctx, amount_0_requested, amount_1_requested)
+    pub fn claim_rewards(ctx: Context<ClaimRewards>, reward_index: u8) -> Result<()> {
+        instructions::claim_rewards(ctx, reward_index)
     }
 
-    /// Collect the fund fee accrued to the pool
+    /// Move lp tokens of the owner's liquidity into a new position, which can be transferred
     ///
     /// # Arguments
     ///
-    /// * `ctx` - The context of accounts
-    /// * `amount_0_requested` - The maximum amount of token_0 to send, can be 0 to collect fees in only token_1
-    /// * `amount_1_requested` - The maximum amount of token_1 to send, can be 0 to collect fees in only token_0
+    /// * `ctx`- The context of accounts
+    /// * `position_id` - Id of the position among the owner's positions of the pool
+    /// * `lp_token_amount` - Amount of lp tokens to move into the position
+    /// * `unlock_time` - The position can't be closed before, 0 leaves it unlocked
     ///
-    pub fn collect_fund_fee(
-        ctx: Context<CollectFundFee>,
-        amount_0_requested: u64,
-        amount_1_requested: u64,
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        position_id: u64,
+        lp_token_amount: u64,
+        unlock_time: u64,
     ) -> Result<()> {
-        instructions::collect_fund_fee(ctx, amount_0_requested, amount_1_requested)
+        instructions::open_position(ctx, position_id, lp_token_amount, unlock_time)
     }
 
-    /// Creates a pool for the given token pair and the initial price
+    /// Transfer a position to another owner
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `init_amount_0` - the initial amount_0 to deposit
-    /// * `init_amount_1` - the initial amount_1 to deposit
-    /// * `open_time` - the timestamp allowed for swap
-    /// * `max_trade_fee_rate` - The maximum trade fee that can be charged on swaps
-    /// * `volatility_factor` - The volatility factor of the pool to determine the trade fee
+    /// * `new_owner` - The owner the position is transferred to
     ///
-    pub fn initialize(
-        ctx: Context<Initialize>,
-        init_amount_0: u64,
-        init_amount_1: u64,
-        open_time: u64,
-        max_trade_fee_rate: u64,
-        volatility_factor: u64,
-    ) -> Result<()> {
-        instructions::initialize(
-            ctx,
-            init_amount_0,
-            init_amount_1,
-            open_time,
-            max_trade_fee_rate,
-            volatility_factor,
-        )
+    pub fn transfer_position(ctx: Context<TransferPosition>, new_owner: Pubkey) -> Result<()> {
+        instructions::transfer_position(ctx, new_owner)
     }
 
-    pub fn init_user_pool_liquidity(
-        ctx: Context<InitUserPoolLiquidity>,
-        partner: Option<String>,
+    /// Transfer lp tokens of the owner's liquidity to another wallet's liquidity in the pool,
+    /// with their share of the deposit history and unclaimed lp fees
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of lp tokens to transfer
+    ///
+    pub fn transfer_user_position(
+        ctx: Context<TransferUserPosition>,
+        lp_token_amount: u64,
     ) -> Result<()> {
-        instructions::init_user_pool_liquidity(ctx, partner)
+        instructions::transfer_user_position(ctx, lp_token_amount)
     }
 
-    /// Creates a pool for the given token pair and the initial price
+    /// Move the lp tokens and lp fees of an unlocked position back into the owner's liquidity
+    /// and close it
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `lp_token_amount` - Pool token amount to transfer. token_a and token_b amount are set by the current exchange rate and size of the pool
-    /// * `maximum_token_0_amount` -  Maximum token 0 amount to deposit, prevents excessive slippage
-    /// * `maximum_token_1_amount` - Maximum token 1 amount to deposit, prevents excessive slippage
     ///
-    pub fn deposit(
-        ctx: Context<Deposit>,
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        instructions::close_position(ctx)
+    }
+
+    /// Move the lp fees accrued to a position into the owner's liquidity, where they can be
+    /// claimed, without closing it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn collect_position_lp_fees(ctx: Context<CollectPositionLpFees>) -> Result<()> {
+        instructions::collect_position_lp_fees(ctx)
+    }
+
+    /// Move lp tokens of a position into a new position of the owner with the same lock
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `new_position_id` - Id of the new position among the owner's positions of the pool
+    /// * `lp_token_amount` - Amount of lp tokens to move into the new position
+    ///
+    pub fn split_position(
+        ctx: Context<SplitPosition>,
+        new_position_id: u64,
         lp_token_amount: u64,
-        maximum_token_0_amount: u64,
-        maximum_token_1_amount: u64,
     ) -> Result<()> {
-        instructions::deposit(
-            ctx,
-            lp_token_amount,
-            maximum_token_0_amount,
-            maximum_token_1_amount,
-        )
+        instructions::split_position(ctx, new_position_id, lp_token_amount)
     }
 
-    /// Withdraw lp for token0 ande token1
+    /// Move the lp tokens and lp fees of a position into another position of the owner and
+    /// close it
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `lp_token_amount` - Amount of pool tokens to burn. User receives an output of token a and b based on the percentage of the pool tokens that are returned.
-    /// * `minimum_token_0_amount` -  Minimum amount of token 0 to receive, prevents excessive slippage
-    /// * `minimum_token_1_amount` -  Minimum amount of token 1 to receive, prevents excessive slippage
     ///
-    pub fn withdraw(
-        ctx: Context<Withdraw>,
+    pub fn merge_positions(ctx: Context<MergePositions>) -> Result<()> {
+        instructions::merge_positions(ctx)
+    }
+
+    /// Lock lp tokens of the owner's liquidity in a new position, all at once or vesting
+    /// linearly, so anyone can verify the lock on chain
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `position_id` - Id of the position among the owner's positions of the pool
+    /// * `lp_token_amount` - Amount of lp tokens to lock
+    /// * `vesting_start` - Timestamp the lp tokens start vesting at, 0 to unlock all at once
+    /// * `unlock_time` - Timestamp all the lp tokens are unlocked at
+    ///
+    pub fn lock_lp(
+        ctx: Context<OpenPosition>,
+        position_id: u64,
         lp_token_amount: u64,
-        minimum_token_0_amount: u64,
-        minimum_token_1_amount: u64,
+        vesting_start: u64,
+        unlock_time: u64,
     ) -> Result<()> {
-        instructions::withdraw(
-            ctx,
-            lp_token_amount,
-            minimum_token_0_amount,
-            minimum_token_1_amount,
-        )
+        instructions::lock_lp(ctx, position_id, lp_token_amount, vesting_start, unlock_time)
     }
 
-    /// Swap the tokens in the pool base input amount
+    /// Move the vested lp tokens of a locked position back into the owner's liquidity
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate
-    /// * `minimum_amount_out` -  Minimum amount of output token, prevents excessive slippage
     ///
-    pub fn swap_base_input<'c, 'info>(
-        ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
-        amount_in: u64,
-        minimum_amount_out: u64,
+    pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+        instructions::unlock_lp(ctx)
+    }
+
+    /// Burn lp tokens of the owner's liquidity for good, recording them in the pool's burn proof
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of lp tokens to burn
+    ///
+    pub fn burn_lp_forever(ctx: Context<BurnLpForever>, lp_token_amount: u64) -> Result<()> {
+        instructions::burn_lp_forever(ctx, lp_token_amount)
+    }
+
+    /// Creates the pool's token-2022 LP mint, with its metadata pointer and token metadata
+    /// naming it "Gamma LP <SYM0>-<SYM1>". Must be called by the admin or the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `token_0_symbol` - Symbol of token_0 written into the LP name and symbol
+    /// * `token_1_symbol` - Symbol of token_1 written into the LP name and symbol
+    /// * `uri` - Uri of the LP metadata
+    ///
+    pub fn create_lp_mint(
+        ctx: Context<CreateLpMint>,
+        token_0_symbol: String,
+        token_1_symbol: String,
+        uri: String,
     ) -> Result<()> {
-        instructions::swap_base_input(ctx, amount_in, minimum_amount_out)
+        instructions::create_lp_mint(ctx, token_0_symbol, token_1_symbol, uri)
     }
 
-    /// Swap the tokens in the pool base output amount
+    /// Updates the token metadata of the pool's LP mint. Must be called by the admin or the pool
+    /// creator
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `max_amount_in` -  input amount prevents excessive slippage
-    /// * `amount_out` -  amount of output token
+    /// * `name` - The new name of the LP mint
+    /// * `symbol` - The new symbol of the LP mint
+    /// * `uri` - The new uri of the LP metadata
     ///
-    pub fn swap_base_output<'c, 'info>(
-        ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
-        max_amount_in: u64,
-        amount_out: u64,
+    pub fn update_lp_metadata(
+        ctx: Context<UpdateLpMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
     ) -> Result<()> {
-        instructions::swap_base_output(ctx, max_amount_in, amount_out)
+        instructions::update_lp_metadata(ctx, name, symbol, uri)
+    }
+
+    /// Mints lp tokens of the owner's liquidity as tokens of the pool's LP mint
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of lp tokens to tokenize
+    ///
+    pub fn tokenize_lp(ctx: Context<TokenizeLp>, lp_token_amount: u64) -> Result<()> {
+        instructions::tokenize_lp(ctx, lp_token_amount)
+    }
+
+    /// Burns LP mint tokens and credits their lp tokens to the holder's liquidity
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of LP mint tokens to redeem
+    ///
+    pub fn redeem_lp(ctx: Context<RedeemLp>, lp_token_amount: u64) -> Result<()> {
+        instructions::redeem_lp(ctx, lp_token_amount)
+    }
+
+    /// Read-only view of a partner's tvl share and accumulated fees, returned via return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `partner` - The partner id, see `PartnerType`
+    ///
+    pub fn get_partner_dashboard(
+        ctx: Context<GetPartnerDashboard>,
+        partner: u64,
+    ) -> Result<PartnerDashboard> {
+        instructions::get_partner_dashboard(ctx, partner)
+    }
+
+    /// Add a partner to an empty partner slot of the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `partner_id` - The partner id, see `PartnerType`
+    ///
+    pub fn add_partner(ctx: Context<AddPartner>, partner_id: u64) -> Result<()> {
+        instructions::add_partner(ctx, partner_id)
+    }
+
+    /// Remove a partner from the pool, emitting the fees attributed to it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `partner_id` - The partner id, see `PartnerType`
+    ///
+    pub fn remove_partner(ctx: Context<RemovePartner>, partner_id: u64) -> Result<()> {
+        instructions::remove_partner(ctx, partner_id)
+    }
+
+    /// Link the LP of the signer's position with another partner of the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `partner` - The partner id, see `PartnerType`, or `None` to unlink the position
+    ///
+    pub fn update_partner_lp_link(
+        ctx: Context<UpdatePartnerLpLink>,
+        partner: Option<u64>,
+    ) -> Result<()> {
+        instructions::update_partner_lp_link(ctx, partner)
+    }
+
+    /// Read-only view of the share of the LP supply that can't be withdrawn, burned and locked at
+    /// creation or held by the locked positions passed in, returned via return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn get_locked_liquidity(ctx: Context<GetLockedLiquidity>) -> Result<LockedLiquidity> {
+        instructions::get_locked_liquidity(ctx)
+    }
+
+    /// Read-only preview of what a deposit of `lp_token_amount` moves, returned via return data.
+    /// Uses the same math as `deposit`, so simulating it gives the exact amounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of LP tokens to mint
+    ///
+    pub fn preview_deposit(
+        ctx: Context<PreviewLiquidity>,
+        lp_token_amount: u64,
+    ) -> Result<LiquidityPreview> {
+        instructions::preview_deposit(ctx, lp_token_amount)
+    }
+
+    /// Read-only preview of what a withdrawal of `lp_token_amount` pays out, returned via return
+    /// data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `lp_token_amount` - Amount of LP tokens to burn
+    ///
+    pub fn preview_withdraw(
+        ctx: Context<PreviewLiquidity>,
+        lp_token_amount: u64,
+    ) -> Result<LiquidityPreview> {
+        instructions::preview_withdraw(ctx, lp_token_amount)
+    }
+
+    /// Read-only view of the time weighted prices of the pool over the last `seconds_ago`
+    /// seconds, returned via return data. Lets other programs use the pool as an oracle by CPI
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `seconds_ago` - Length of the window ending now, the observations are interpolated at
+    ///   both ends and the current price covers the time since the newest one
+    ///
+    pub fn get_twap(ctx: Context<GetTwap>, seconds_ago: u32) -> Result<states::Twap> {
+        instructions::get_twap(ctx, seconds_ago)
+    }
+
+    /// Read-only view of the geometric mean prices of the pool over the last `seconds_ago`
+    /// seconds, returned via return data. Less sensitive to short price spikes than `get_twap`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `seconds_ago` - Length of the window ending now, interpolated like `get_twap`
+    ///
+    pub fn get_geometric_twap(ctx: Context<GetTwap>, seconds_ago: u32) -> Result<states::Twap> {
+        instructions::get_geometric_twap(ctx, seconds_ago)
+    }
+
+    /// Read-only view of a composite health score of the pool: oracle freshness, reserve depth
+    /// against daily volume, vault reconciliation and locked lp share, returned via return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn get_pool_health(ctx: Context<GetPoolHealth>) -> Result<PoolHealth> {
+        instructions::get_pool_health(ctx)
+    }
+
+    /// Read-only view of the spot and EMA prices of the pool, returned via return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn get_pool_price(ctx: Context<GetPoolPrice>) -> Result<PoolPrice> {
+        instructions::get_pool_price(ctx)
+    }
+
+    /// Read-only quote of a swap with the dynamic fee and transfer fees it would pay, returned
+    /// via return data. Lets other programs price a swap by CPI without simulating it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `zero_for_one` - Whether token_0 is swapped for token_1
+    /// * `amount` - Input amount if `base_input`, otherwise the output amount
+    /// * `base_input` - Quote `swap_base_input` rather than `swap_base_output`
+    ///
+    pub fn get_quote(
+        ctx: Context<GetQuote>,
+        zero_for_one: bool,
+        amount: u64,
+        base_input: bool,
+    ) -> Result<utils::SwapQuote> {
+        instructions::get_quote(ctx, zero_for_one, amount, base_input)
+    }
+
+    /// Grows the observation account of a pool so it keeps more than the default 100
+    /// observations and twaps can look further back. The caller pays the rent
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `observation_cardinality` - Total number of observations to keep, the account can grow
+    ///   by about 250 observations per call
+    ///
+    pub fn increase_observation_cardinality(
+        ctx: Context<IncreaseObservationCardinality>,
+        observation_cardinality: u16,
+    ) -> Result<()> {
+        instructions::increase_observation_cardinality(ctx, observation_cardinality)
     }
 
     /********************* Migration Instructions *********************/
@@ -394,4 +1931,18 @@ pub mod gamma {
     ) -> Result<()> {
         instructions::rebalance_kamino(ctx)
     }
+
+    /// Moves the deployed part of a vault's reserve towards the target of its strategy and
+    /// harvests the interest into the reserve. Anyone can call it, the lending protocol accounts
+    /// of the adapter are the remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn rebalance_strategy<'info>(
+        ctx: Context<'_, '_, '_, 'info, RebalanceStrategy<'info>>,
+    ) -> Result<()> {
+        instructions::rebalance_strategy(ctx)
+    }
 }
@@ -1,7 +1,7 @@
 use crate::external::dlmm::lb_clmm::types::BinLiquidityReduction;
 use crate::{
     calculate_gamma_lp_tokens,
-    instructions::deposit::{deposit_to_gamma_pool, Deposit},
+    instructions::deposit::{deposit_to_gamma_pool, Deposit, DepositRemainingAccounts},
     states::{MigrationEvent, PoolState, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED},
 };
 use anchor_lang::prelude::*;
@@ -121,6 +121,8 @@ pub struct MeteoraDlmmToGamma<'info> {
     /// Token program 2022
     pub token_program_2022: Program<'info, Token2022>,
 
+    pub system_program: Program<'info, System>,
+
     /// CHECK: Mint account of token X
     // pub dlmm_token_x_mint: UncheckedAccount<'info>,
     /// The mint of token_0 vault
@@ -206,6 +208,7 @@ pub fn meteora_dlmm_to_gamma(
         token_program_2022: ctx.accounts.token_program_2022.clone(),
         vault_0_mint: ctx.accounts.gamma_vault_0_mint.clone(),
         vault_1_mint: ctx.accounts.gamma_vault_1_mint.clone(),
+        system_program: ctx.accounts.system_program.clone(),
     };
 
     deposit_to_gamma_pool(
@@ -213,9 +216,11 @@ pub fn meteora_dlmm_to_gamma(
         gamma_lp_tokens as u64,
         maximum_token_0_amount,
         maximum_token_1_amount,
+        DepositRemainingAccounts::default(),
     )?;
 
     emit!(MigrationEvent {
+        version: MigrationEvent::VERSION,
         from_pool: ctx.accounts.dlmm_lb_pair.key(),
         to_pool: ctx.accounts.gamma_pool_state.key(),
         token_0_amount_withdrawn,
@@ -1,7 +1,7 @@
 use crate::external::whirlpool::whirlpool::types::RemainingAccountsInfo;
 use crate::{
     calculate_gamma_lp_tokens,
-    instructions::deposit::{deposit_to_gamma_pool, Deposit},
+    instructions::deposit::{deposit_to_gamma_pool, Deposit, DepositRemainingAccounts},
     states::{MigrationEvent, PoolState, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED},
 };
 use anchor_lang::prelude::*;
@@ -123,6 +123,8 @@ pub struct OrcaWhirlpoolToGammaV2<'info> {
     /// Token program 2022
     pub token_program_2022: Program<'info, Token2022>,
 
+    pub system_program: Program<'info, System>,
+
     // /// CHECK: Token mint A
     // pub whirlpool_token_mint_a: UncheckedAccount<'info>,
     /// The mint of token_0 vault
@@ -217,6 +219,7 @@ pub fn orca_whirlpool_to_gamma_v2<'info>(
         token_program_2022: ctx.accounts.token_program_2022.clone(),
         vault_0_mint: ctx.accounts.gamma_vault_0_mint.clone(),
         vault_1_mint: ctx.accounts.gamma_vault_1_mint.clone(),
+        system_program: ctx.accounts.system_program.clone(),
     };
 
     deposit_to_gamma_pool(
@@ -224,9 +227,11 @@ pub fn orca_whirlpool_to_gamma_v2<'info>(
         gamma_lp_tokens as u64,
         maximum_token_0_amount,
         maximum_token_1_amount,
+        DepositRemainingAccounts::default(),
     )?;
 
     emit!(MigrationEvent {
+        version: MigrationEvent::VERSION,
         from_pool: ctx.accounts.whirlpool.key(),
         to_pool: ctx.accounts.gamma_pool_state.key(),
         token_0_amount_withdrawn,
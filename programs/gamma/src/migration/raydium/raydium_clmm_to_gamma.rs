@@ -1,6 +1,6 @@
 use crate::{
     calculate_gamma_lp_tokens,
-    instructions::deposit::{deposit_to_gamma_pool, Deposit},
+    instructions::deposit::{deposit_to_gamma_pool, Deposit, DepositRemainingAccounts},
     states::{MigrationEvent, PoolState, UserPoolLiquidity, USER_POOL_LIQUIDITY_SEED},
 };
 use anchor_lang::prelude::*;
@@ -127,6 +127,8 @@ pub struct RaydiumClmmToGamma<'info> {
 
     /// Token program 2022
     pub token_program_2022: Program<'info, Token2022>,
+
+    pub system_program: Program<'info, System>,
     // remaining account
     // #[account(
     //     seeds = [
@@ -214,6 +216,7 @@ pub fn raydium_clmm_to_gamma<'a, 'b, 'c, 'info>(
         token_program_2022: ctx.accounts.token_program_2022.clone(),
         vault_0_mint: ctx.accounts.gamma_vault_0_mint.clone(),
         vault_1_mint: ctx.accounts.gamma_vault_1_mint.clone(),
+        system_program: ctx.accounts.system_program.clone(),
     };
 
     deposit_to_gamma_pool(
@@ -221,9 +224,11 @@ pub fn raydium_clmm_to_gamma<'a, 'b, 'c, 'info>(
         gamma_lp_tokens as u64,
         maximum_token_0_amount,
         maximum_token_1_amount,
+        DepositRemainingAccounts::default(),
     )?;
 
     emit!(MigrationEvent {
+        version: MigrationEvent::VERSION,
         from_pool: ctx.accounts.raydium_clmm_pool_state.key(),
         to_pool: ctx.accounts.gamma_pool_state.key(),
         token_0_amount_withdrawn,
@@ -0,0 +1,411 @@
+//! Account counts and compute budgets of the instructions aggregators route through, so a
+//! transaction composing gamma legs can be sized without simulating it first.
+
+use crate::instructions::{
+    DEPOSIT_FIXED_REMAINING_ACCOUNTS, SWAP_FIXED_REMAINING_ACCOUNTS,
+    WITHDRAW_FIXED_REMAINING_ACCOUNTS,
+};
+
+/// Fixed accounts of `swap_base_input` and `swap_base_output`, see `Swap`
+pub const SWAP_REQUIRED_ACCOUNTS: usize = 13;
+/// Optional accounts at fixed positions a swap can take, see `SwapRemainingAccounts`. The
+/// strategy of the output vault, the swap hook and transfer hook accounts can follow them.
+pub const SWAP_OPTIONAL_ACCOUNTS: usize = SWAP_FIXED_REMAINING_ACCOUNTS;
+
+/// Fixed accounts of `swap_route`, each hop adds `SWAP_REQUIRED_ACCOUNTS` remaining accounts.
+/// The instructions sysvar is optional but takes its slot either way.
+pub const SWAP_ROUTE_REQUIRED_ACCOUNTS: usize = 2;
+
+/// Fixed accounts of `swap_batch`, followed by the `SWAP_REQUIRED_ACCOUNTS` of the pool. Like
+/// `swap_route`, the instructions sysvar takes its slot either way.
+pub const SWAP_BATCH_REQUIRED_ACCOUNTS: usize = 2;
+
+/// Fixed accounts of `deposit`, see `Deposit`
+pub const DEPOSIT_REQUIRED_ACCOUNTS: usize = 13;
+/// Optional accounts at fixed positions a deposit can take, see `DepositOptionalAccounts`. The
+/// transfer hook accounts can follow them.
+pub const DEPOSIT_OPTIONAL_ACCOUNTS: usize = DEPOSIT_FIXED_REMAINING_ACCOUNTS;
+
+/// Fixed accounts of `withdraw`, see `Withdraw`
+pub const WITHDRAW_REQUIRED_ACCOUNTS: usize = 14;
+/// The mint exposures of token_0 and token_1, the quote cache, then the recipient of the
+/// proceeds. The strategies of the vaults and the transfer hook accounts can follow them.
+pub const WITHDRAW_OPTIONAL_ACCOUNTS: usize = WITHDRAW_FIXED_REMAINING_ACCOUNTS;
+
+/// Compute units a `swap_base_input` or `swap_base_output` takes at most between token 2022
+/// mints with a transfer fee, as measured by `tests/compute_units.rs`. A strategy, swap hook or
+/// transfer hook adds what its program takes.
+pub const SWAP_MAX_COMPUTE_UNITS: u32 = 150_000;
+/// Compute units `swap_route` takes at most per hop, on top of `SWAP_ROUTE_BASE_COMPUTE_UNITS`
+pub const SWAP_ROUTE_HOP_MAX_COMPUTE_UNITS: u32 = SWAP_MAX_COMPUTE_UNITS;
+/// Compute units `swap_route` takes at most before its hops
+pub const SWAP_ROUTE_BASE_COMPUTE_UNITS: u32 = 10_000;
+/// Compute units a `deposit` takes at most
+pub const DEPOSIT_MAX_COMPUTE_UNITS: u32 = 120_000;
+/// Compute units a `withdraw` takes at most, without the strategies of the vaults
+pub const WITHDRAW_MAX_COMPUTE_UNITS: u32 = 120_000;
+
+/// Which of the optional swap accounts a router passes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapOptionalAccounts {
+    /// Registered segmenter and its registry
+    pub segmenter: bool,
+    /// Referral account and referral token account
+    pub referral: bool,
+    /// Kickback account of the segmenter, only used together with `segmenter`
+    pub segmenter_kickback: bool,
+    /// Required owner of the output token account
+    pub output_receiver: bool,
+    /// Volume tracker of the pool
+    pub volume_tracker: bool,
+    /// Instructions sysvar, required by pools that only allow one swap per transaction
+    pub instructions_sysvar: bool,
+    /// Rent payer, associated token program and system program, to create a missing output
+    /// token account
+    pub create_output_token_account: bool,
+    /// Price band of the pool and its oracle, required by pools with a price band
+    pub price_band: bool,
+    /// Mint exposures of the input and output mint, required by pools tracking either
+    pub mint_exposure: bool,
+    /// Quote cache of the pool, required by pools keeping one
+    pub quote_cache: bool,
+    /// Whitelist entry of the payer, required by pools limiting swaps to whitelisted addresses
+    pub whitelist_entry: bool,
+    /// Staking discount of the config and the payer's staked token account, for the payer's
+    /// discount on the trade fee
+    pub staking_discount: bool,
+}
+
+impl SwapOptionalAccounts {
+    /// Number of remaining accounts to pass. Optional accounts are positional, any skipped
+    /// account before the last one used must be filled with the gamma program id.
+    pub fn remaining_accounts_len(&self) -> usize {
+        if self.staking_discount {
+            SWAP_OPTIONAL_ACCOUNTS
+        } else if self.whitelist_entry {
+            17
+        } else if self.quote_cache {
+            16
+        } else if self.mint_exposure {
+            15
+        } else if self.price_band {
+            13
+        } else if self.create_output_token_account {
+            11
+        } else if self.instructions_sysvar {
+            8
+        } else if self.volume_tracker {
+            7
+        } else if self.output_receiver {
+            6
+        } else if self.segmenter_kickback {
+            5
+        } else if self.referral {
+            4
+        } else if self.segmenter {
+            2
+        } else {
+            0
+        }
+    }
+}
+
+/// Which of the optional deposit accounts a router passes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DepositOptionalAccounts {
+    /// The pool's amm config and the referrer's referral account
+    pub referrer: bool,
+    /// Mint exposures of token_0 and token_1, required by pools tracking either
+    pub mint_exposure: bool,
+    /// Quote cache of the pool, required by pools keeping one
+    pub quote_cache: bool,
+    /// Whitelist entry of the owner, required by pools limiting deposits to whitelisted addresses
+    pub whitelist_entry: bool,
+}
+
+impl DepositOptionalAccounts {
+    /// Number of remaining accounts to pass, skipped accounts before the last one used must be
+    /// filled with the gamma program id
+    pub fn remaining_accounts_len(&self) -> usize {
+        if self.whitelist_entry {
+            DEPOSIT_OPTIONAL_ACCOUNTS
+        } else if self.quote_cache {
+            5
+        } else if self.mint_exposure {
+            4
+        } else if self.referrer {
+            2
+        } else {
+            0
+        }
+    }
+}
+
+/// Which of the optional withdraw accounts a router passes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WithdrawOptionalAccounts {
+    /// Mint exposures of token_0 and token_1, required by pools tracking either
+    pub mint_exposure: bool,
+    /// Quote cache of the pool, required by pools keeping one
+    pub quote_cache: bool,
+    /// Owner of the token accounts the proceeds go to, when it isn't the owner of the liquidity
+    pub recipient: bool,
+}
+
+impl WithdrawOptionalAccounts {
+    /// Number of remaining accounts to pass, skipped accounts before the last one used must be
+    /// filled with the gamma program id
+    pub fn remaining_accounts_len(&self) -> usize {
+        if self.recipient {
+            WITHDRAW_OPTIONAL_ACCOUNTS
+        } else if self.quote_cache {
+            3
+        } else if self.mint_exposure {
+            2
+        } else {
+            0
+        }
+    }
+}
+
+/// Total number of accounts of a swap instruction for the given optional accounts
+pub fn swap_accounts_len(optional_accounts: SwapOptionalAccounts) -> usize {
+    SWAP_REQUIRED_ACCOUNTS + optional_accounts.remaining_accounts_len()
+}
+
+/// Total number of accounts of a `swap_route` through `hops` pools
+pub fn swap_route_accounts_len(hops: usize) -> usize {
+    SWAP_ROUTE_REQUIRED_ACCOUNTS + hops * SWAP_REQUIRED_ACCOUNTS
+}
+
+/// Total number of accounts of a deposit for the given optional accounts
+pub fn deposit_accounts_len(optional_accounts: DepositOptionalAccounts) -> usize {
+    DEPOSIT_REQUIRED_ACCOUNTS + optional_accounts.remaining_accounts_len()
+}
+
+/// Total number of accounts of a withdrawal for the given optional accounts
+pub fn withdraw_accounts_len(optional_accounts: WithdrawOptionalAccounts) -> usize {
+    WITHDRAW_REQUIRED_ACCOUNTS + optional_accounts.remaining_accounts_len()
+}
+
+/// Compute units to request for a `swap_route` through `hops` pools
+pub fn swap_route_max_compute_units(hops: u32) -> u32 {
+    SWAP_ROUTE_BASE_COMPUTE_UNITS + hops * SWAP_ROUTE_HOP_MAX_COMPUTE_UNITS
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::instructions::{
+        decode_account_info, DepositRemainingAccounts, SwapRemainingAccounts,
+        WITHDRAW_RECIPIENT_INDEX,
+    };
+    use crate::utils::StrategyAccounts;
+    use anchor_lang::prelude::{AccountInfo, Pubkey};
+
+    /// Calls `f` with `len` accounts of distinct keys, the decoding only looks at the keys
+    fn with_accounts(len: usize, f: impl FnOnce(&[AccountInfo])) {
+        let keys = (0..len).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        let owner = Pubkey::default();
+        let mut lamports = vec![0u64; len];
+        let mut data = vec![Vec::<u8>::new(); len];
+        let accounts = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .zip(data.iter_mut())
+            .map(|((key, lamports), data)| {
+                AccountInfo::new(key, false, false, lamports, data, &owner, false, 0)
+            })
+            .collect::<Vec<_>>();
+        f(&accounts)
+    }
+
+    #[test]
+    fn swap_optional_accounts_end_with_the_account_the_swap_decodes() {
+        let cases: [(SwapOptionalAccounts, fn(&SwapRemainingAccounts) -> bool); 11] = [
+            (
+                SwapOptionalAccounts {
+                    segmenter: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.registry.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    referral: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.referral_token_account.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    segmenter_kickback: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.segmenter_kickback.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    output_receiver: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.output_receiver.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    volume_tracker: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.volume_tracker.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    instructions_sysvar: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.instructions_sysvar.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    create_output_token_account: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.system_program.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    price_band: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.oracle.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    mint_exposure: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.output_mint_exposure.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    quote_cache: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.quote_cache.is_some(),
+            ),
+            (
+                SwapOptionalAccounts {
+                    whitelist_entry: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.whitelist_entry.is_some(),
+            ),
+        ];
+        for (optional_accounts, is_decoded) in cases {
+            let len = optional_accounts.remaining_accounts_len();
+            with_accounts(len, |accounts| {
+                assert!(is_decoded(&SwapRemainingAccounts::new(accounts)));
+                assert!(!is_decoded(&SwapRemainingAccounts::new(
+                    &accounts[..len - 1]
+                )));
+            });
+        }
+
+        // Every fixed account is decoded before the output strategy
+        let all = SwapOptionalAccounts {
+            staking_discount: true,
+            ..Default::default()
+        };
+        with_accounts(
+            all.remaining_accounts_len() + StrategyAccounts::FIXED_LEN,
+            |accounts| {
+                let remaining_accounts = SwapRemainingAccounts::new(accounts);
+                assert!(remaining_accounts.staked_token_account.is_some());
+                assert_eq!(
+                    remaining_accounts
+                        .output_strategy
+                        .map(|strategy| *strategy.vault_strategy.key),
+                    Some(*accounts[SWAP_OPTIONAL_ACCOUNTS].key)
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn deposit_optional_accounts_end_with_the_account_the_deposit_decodes() {
+        let cases: [(
+            DepositOptionalAccounts,
+            fn(&DepositRemainingAccounts) -> bool,
+        ); 3] = [
+            (
+                DepositOptionalAccounts {
+                    mint_exposure: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.token_1_mint_exposure.is_some(),
+            ),
+            (
+                DepositOptionalAccounts {
+                    quote_cache: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.quote_cache.is_some(),
+            ),
+            (
+                DepositOptionalAccounts {
+                    whitelist_entry: true,
+                    ..Default::default()
+                },
+                |accounts| accounts.whitelist_entry.is_some(),
+            ),
+        ];
+        for (optional_accounts, is_decoded) in cases {
+            let len = optional_accounts.remaining_accounts_len();
+            with_accounts(len, |accounts| {
+                assert!(is_decoded(&DepositRemainingAccounts::new(accounts)));
+                assert!(!is_decoded(&DepositRemainingAccounts::new(
+                    &accounts[..len - 1]
+                )));
+            });
+        }
+        with_accounts(DEPOSIT_OPTIONAL_ACCOUNTS + 1, |accounts| {
+            let remaining_accounts = DepositRemainingAccounts::new(accounts);
+            assert_eq!(remaining_accounts.transfer_hook_accounts.len(), 1);
+        });
+    }
+
+    #[test]
+    fn withdraw_optional_accounts_end_with_the_account_the_withdrawal_decodes() {
+        let cases: [(WithdrawOptionalAccounts, usize); 3] = [
+            (
+                WithdrawOptionalAccounts {
+                    mint_exposure: true,
+                    ..Default::default()
+                },
+                1,
+            ),
+            (
+                WithdrawOptionalAccounts {
+                    quote_cache: true,
+                    ..Default::default()
+                },
+                2,
+            ),
+            (
+                WithdrawOptionalAccounts {
+                    recipient: true,
+                    ..Default::default()
+                },
+                WITHDRAW_RECIPIENT_INDEX,
+            ),
+        ];
+        for (optional_accounts, index) in cases {
+            let len = optional_accounts.remaining_accounts_len();
+            assert_eq!(len, index + 1);
+            with_accounts(len, |accounts| {
+                assert!(decode_account_info(accounts, index).is_some());
+                assert!(decode_account_info(&accounts[..len - 1], index).is_none());
+            });
+        }
+    }
+}
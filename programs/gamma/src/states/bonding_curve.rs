@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+
+use crate::error::GammaError;
+use crate::utils::U256;
+
+pub const BONDING_CURVE_SEED: &str = "bonding_curve";
+pub const BONDING_CURVE_VAULT_SEED: &str = "bonding_curve_vault";
+
+/// Launch of a new token sold for a quote token, SOL or USDC, along a constant product curve on
+/// virtual reserves. Once `graduation_quote_amount` is raised the curve stops trading and
+/// `graduate` seeds a pool of `amm_config` with its vaults, locking the LP.
+#[account]
+#[derive(Default, Debug)]
+pub struct BondingCurve {
+    pub bump: u8,
+    /// Who created the launch, the creator of the pool it graduates into and owner of the locked
+    /// LP position
+    pub creator: Pubkey,
+    /// Config of the pool the curve graduates into
+    pub amm_config: Pubkey,
+    pub token_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Vaults of the curve, owned by the pool authority
+    pub token_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    /// Reserves the curve prices with on top of the real ones, their ratio is the start price
+    pub virtual_token_reserve: u64,
+    pub virtual_quote_reserve: u64,
+    /// Tokens left for sale
+    pub token_reserve: u64,
+    /// Quote raised by the sales
+    pub quote_reserve: u64,
+    /// Quote raised at which the curve completes
+    pub graduation_quote_amount: u64,
+    /// Tokens of the supply put aside for the pool on top of the unsold ones, as many as open
+    /// the pool at the curve's price at graduation
+    pub pool_token_amount: u64,
+    /// Fee params of the pool the curve graduates into
+    pub max_trade_fee_rate: u64,
+    pub volatility_factor: u64,
+    /// The pool the curve graduated into, the default key before it graduates
+    pub pool_state: Pubkey,
+    pub padding: [u64; 4],
+}
+
+impl BondingCurve {
+    pub const LEN: usize = 8 + 1 + 32 * 7 + 8 * 8 + 8 * 4;
+
+    /// Tokens of `supply` the curve sells so that, once `graduation_quote_amount` is raised,
+    /// the pool seeded with the rest of the supply opens at the curve's price. Depends on the
+    /// virtual token reserve only for the split between the tokens for sale and the pool's: the
+    /// curve's token reserve, virtual ones included, is set by the supply and the quote amounts.
+    pub fn sale_token_amount(
+        supply: u64,
+        virtual_token_reserve: u64,
+        virtual_quote_reserve: u64,
+        graduation_quote_amount: u64,
+    ) -> Result<u64> {
+        let raised = U256::from(graduation_quote_amount);
+        let graduation_quote_reserve = U256::from(virtual_quote_reserve) + raised;
+        // Selling x * raised / graduation_quote_reserve of a curve token reserve x leaves the
+        // pool x * raised * virtual_quote_reserve / graduation_quote_reserve^2 tokens, the
+        // supply is what's sold plus what's left
+        let curve_token_reserve = U256::from(supply)
+            .checked_mul(graduation_quote_reserve * graduation_quote_reserve)
+            .ok_or(GammaError::MathOverflow)?
+            .checked_div(raised * (U256::from(virtual_quote_reserve) + graduation_quote_reserve))
+            .ok_or(GammaError::MathOverflow)?;
+        // The curve has to raise its target before it sells out
+        let graduation_token_reserve = curve_token_reserve
+            .checked_mul(U256::from(virtual_quote_reserve))
+            .ok_or(GammaError::MathOverflow)?
+            / graduation_quote_reserve;
+        require!(
+            graduation_token_reserve >= U256::from(virtual_token_reserve),
+            GammaError::InvalidInput
+        );
+        let sale_token_amount = curve_token_reserve - U256::from(virtual_token_reserve);
+        // The pool's tokens can't be negative either
+        require!(
+            sale_token_amount > U256::zero() && sale_token_amount <= U256::from(supply),
+            GammaError::InvalidInput
+        );
+        Ok(sale_token_amount.as_u64())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.quote_reserve >= self.graduation_quote_amount || self.token_reserve == 0
+    }
+
+    pub fn is_graduated(&self) -> bool {
+        self.pool_state != Pubkey::default()
+    }
+
+    fn curve_token_reserve(&self) -> Result<u128> {
+        Ok(u128::from(self.virtual_token_reserve)
+            .checked_add(u128::from(self.token_reserve))
+            .ok_or(GammaError::MathOverflow)?)
+    }
+
+    fn curve_quote_reserve(&self) -> Result<u128> {
+        Ok(u128::from(self.virtual_quote_reserve)
+            .checked_add(u128::from(self.quote_reserve))
+            .ok_or(GammaError::MathOverflow)?)
+    }
+
+    /// Tokens `quote_amount_in` buys, rounded down
+    pub fn buy_amount_out(&self, quote_amount_in: u64) -> Result<u64> {
+        let token_reserve = self.curve_token_reserve()?;
+        let quote_reserve = self.curve_quote_reserve()?;
+        let amount_out = token_reserve
+            .checked_mul(u128::from(quote_amount_in))
+            .ok_or(GammaError::MathOverflow)?
+            .checked_div(
+                quote_reserve
+                    .checked_add(u128::from(quote_amount_in))
+                    .ok_or(GammaError::MathOverflow)?,
+            )
+            .ok_or(GammaError::MathOverflow)?;
+        let amount_out = u64::try_from(amount_out).map_err(|_| GammaError::MathOverflow)?;
+        require_gte!(
+            self.token_reserve,
+            amount_out,
+            GammaError::InsufficientLiquidity
+        );
+        Ok(amount_out)
+    }
+
+    /// Quote `token_amount_in` sells for, rounded down
+    pub fn sell_amount_out(&self, token_amount_in: u64) -> Result<u64> {
+        let token_reserve = self.curve_token_reserve()?;
+        let quote_reserve = self.curve_quote_reserve()?;
+        let amount_out = quote_reserve
+            .checked_mul(u128::from(token_amount_in))
+            .ok_or(GammaError::MathOverflow)?
+            .checked_div(
+                token_reserve
+                    .checked_add(u128::from(token_amount_in))
+                    .ok_or(GammaError::MathOverflow)?,
+            )
+            .ok_or(GammaError::MathOverflow)?;
+        let amount_out = u64::try_from(amount_out).map_err(|_| GammaError::MathOverflow)?;
+        // Only quote raised by sales can be paid out, never the virtual reserve
+        require_gte!(
+            self.quote_reserve,
+            amount_out,
+            GammaError::InsufficientLiquidity
+        );
+        Ok(amount_out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn graduation_seeds_the_pool_at_the_curve_price() {
+        let supply = 1_000_000_000_000_000;
+        let (virtual_token_reserve, virtual_quote_reserve) = (200_000_000_000_000, 30_000_000_000);
+        let graduation_quote_amount = 85_000_000_000;
+        let sale_token_amount = BondingCurve::sale_token_amount(
+            supply,
+            virtual_token_reserve,
+            virtual_quote_reserve,
+            graduation_quote_amount,
+        )
+        .unwrap();
+        let mut bonding_curve = BondingCurve {
+            virtual_token_reserve,
+            virtual_quote_reserve,
+            token_reserve: sale_token_amount,
+            graduation_quote_amount,
+            pool_token_amount: supply - sale_token_amount,
+            ..Default::default()
+        };
+
+        let token_amount_out = bonding_curve
+            .buy_amount_out(graduation_quote_amount)
+            .unwrap();
+        bonding_curve.token_reserve -= token_amount_out;
+        bonding_curve.quote_reserve = graduation_quote_amount;
+        assert!(bonding_curve.is_complete());
+
+        // The pool price, quote over tokens, matches the curve's to the rounding
+        let pool_token_amount =
+            u128::from(bonding_curve.token_reserve + bonding_curve.pool_token_amount);
+        let pool_price =
+            u128::from(graduation_quote_amount) * bonding_curve.curve_token_reserve().unwrap();
+        let curve_price = bonding_curve.curve_quote_reserve().unwrap() * pool_token_amount;
+        assert!(pool_price.abs_diff(curve_price) * 1_000_000 < curve_price);
+    }
+
+    #[test]
+    fn curves_selling_out_before_graduating_are_rejected() {
+        // The virtual token reserve is more than the curve holds at graduation
+        assert!(BondingCurve::sale_token_amount(1_000_000, 900_000, 1_000, 1_000).is_err());
+        // Selling the whole supply leaves no tokens for the pool
+        assert!(BondingCurve::sale_token_amount(1_000_000, 0, 1_000, 1_000).is_err());
+    }
+
+    #[test]
+    fn bonding_curve_buys_sell_back_for_no_more_than_they_paid() {
+        let mut bonding_curve = BondingCurve {
+            virtual_quote_reserve: 1_000,
+            token_reserve: 1_000_000,
+            graduation_quote_amount: 1_000,
+            ..Default::default()
+        };
+        // The virtual quote reserve is never paid out
+        assert!(bonding_curve.sell_amount_out(1_000_000).is_err());
+
+        let token_amount_out = bonding_curve.buy_amount_out(1_000).unwrap();
+        assert_eq!(token_amount_out, 500_000);
+        bonding_curve.token_reserve -= token_amount_out;
+        bonding_curve.quote_reserve += 1_000;
+        assert!(bonding_curve.is_complete());
+        assert!(!bonding_curve.is_graduated());
+        assert_eq!(
+            bonding_curve.sell_amount_out(token_amount_out).unwrap(),
+            1_000
+        );
+        // Without a virtual token reserve no buy takes every token left
+        assert!(bonding_curve.buy_amount_out(u64::MAX).unwrap() < bonding_curve.token_reserve);
+    }
+}
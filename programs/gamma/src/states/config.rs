@@ -1,8 +1,39 @@
 use anchor_lang::prelude::*;
 
+use crate::error::GammaError;
 use crate::fees::FEE_RATE_DENOMINATOR_VALUE;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
+/// Longest `AmmConfig::fee_change_delay`, so a config can't lock its fees for good
+pub const MAX_FEE_CHANGE_DELAY: u64 = 7 * 24 * 60 * 60;
+
+// `AmmConfig::mint_safety_policy` bits, what `initialize` accepts of a mint the admin didn't
+// badge. Zero keeps the checks pools were created with before the policy existed.
+// Rejects mints with a freeze authority
+pub const REJECT_FREEZE_AUTHORITY: u8 = 1 << 0;
+// Rejects mints whose transfer fee authority can still raise a fee above
+// `max_mutable_transfer_fee_bps`
+pub const REJECT_MUTABLE_TRANSFER_FEE: u8 = 1 << 1;
+// Accepts mints with a permanent delegate, who can move tokens out of the vaults
+pub const ALLOW_PERMANENT_DELEGATE: u8 = 1 << 2;
+// Accepts mints whose new token accounts start frozen
+pub const ALLOW_DEFAULT_FROZEN: u8 = 1 << 3;
+
+/// A timelocked change of the config, see `propose_config_update`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct PendingConfigUpdate {
+    /// The `update_amm_config` param to change
+    pub param: u16,
+    pub value: u64,
+    /// Unix timestamp the change can be applied from, zero when no change is pending
+    pub apply_after: u64,
+}
+
+impl PendingConfigUpdate {
+    pub fn is_pending(&self) -> bool {
+        self.apply_after != 0
+    }
+}
 
 #[account]
 #[derive(Default, Debug)]
@@ -30,23 +61,94 @@ pub struct AmmConfig {
     pub referral_project: Pubkey,
     /// Max open time for a pool in seconds
     pub max_open_time: u64,
+    /// Share of the dynamic fee accrued to a registered segmenter routing the swap, in bps. Paid
+    /// out of the LP portion of the fee
+    pub segmenter_kickback_bps: u64,
+    /// Share of a depositor's LP fee claims paid to the referrer that brought the liquidity, in bps
+    pub lp_referral_share_bps: u64,
+    /// Fee of a flash loan, denominated in hundredths of bip (10^-6). Zero disables flash loans
+    pub flash_loan_fee_rate: u64,
+    /// Second signer of fee changes above `fee_approval_threshold`, and of changes to either.
+    /// Unset means the admin alone changes fees
+    pub fee_approver: Pubkey,
+    /// Largest change of a fee rate the admin can make without the fee approver,
+    /// denominated in hundredths of bip (10^-6)
+    pub fee_approval_threshold: u64,
+    /// Seconds between proposing a fee rate change and applying it. Zero lets the admin change
+    /// fee rates right away
+    pub fee_change_delay: u64,
+    pub pending_update: PendingConfigUpdate,
+    /// Bounds of the trade fee a pool creator can set for their pool with `update_pool_fee`,
+    /// denominated in hundredths of bip (10^-6). A zero max keeps creators from setting one
+    pub min_pool_trade_fee_rate: u64,
+    pub max_pool_trade_fee_rate: u64,
+    /// Seconds a new pool has to wait at least before opening, the longest is `max_open_time`
+    pub min_open_delay: u64,
+    /// Seconds until a new pool opens when its creator passes a zero open time
+    pub default_open_delay: u64,
+    /// Pauses referrals: swaps don't pay referrers, deposits aren't attributed to one and LP fee
+    /// claims keep the referrer's share
+    pub disable_referrals: bool,
+    /// Pays swap referrers in the output token, out of the output vault, rather than in the input
+    /// token
+    pub referral_payout_in_output: bool,
+    /// Checks `initialize` runs on the mints of a new pool, see `REJECT_FREEZE_AUTHORITY` and
+    /// friends. Mints with a `TokenBadge` are exempt
+    pub mint_safety_policy: u8,
+    /// Highest transfer fee in bps a mutable transfer fee can be at with
+    /// `REJECT_MUTABLE_TRANSFER_FEE`
+    pub max_mutable_transfer_fee_bps: u16,
     /// padding
-    pub padding: [u64; 11],
+    pub padding: [u8; 3],
 }
 
 impl AmmConfig {
-    pub const LEN: usize = 8 + 1 + 1 + 2 + 4 * 8 + 2 * 32 + 8 * 16;
+    pub const LEN: usize = 8 + 1 + 1 + 2 + 4 * 8 + 4 * 32 + 8 * 10 + 2 + 1 + 2 + 3 + (2 + 8 * 2);
 }
 
 // require all rates to be less than 1 (100%)
 pub fn validate_config_rates(amm_config: &AmmConfig) -> Result<()> {
-    require_gt!(FEE_RATE_DENOMINATOR_VALUE, amm_config.trade_fee_rate);
-    require_gt!(FEE_RATE_DENOMINATOR_VALUE, amm_config.protocol_fee_rate);
-    require_gt!(FEE_RATE_DENOMINATOR_VALUE, amm_config.fund_fee_rate);
     require_gt!(
         FEE_RATE_DENOMINATOR_VALUE,
-        amm_config.fund_fee_rate + amm_config.protocol_fee_rate
+        amm_config.trade_fee_rate,
+        GammaError::InvalidFeeBounds
+    );
+    require_gt!(
+        FEE_RATE_DENOMINATOR_VALUE,
+        amm_config.protocol_fee_rate,
+        GammaError::InvalidFeeBounds
+    );
+    require_gt!(
+        FEE_RATE_DENOMINATOR_VALUE,
+        amm_config.fund_fee_rate,
+        GammaError::InvalidFeeBounds
+    );
+    require_gt!(
+        FEE_RATE_DENOMINATOR_VALUE,
+        amm_config.fund_fee_rate + amm_config.protocol_fee_rate,
+        GammaError::InvalidFeeBounds
+    );
+    require_gte!(10_000, amm_config.segmenter_kickback_bps);
+    require_gte!(10_000, amm_config.lp_referral_share_bps);
+    require_gt!(
+        FEE_RATE_DENOMINATOR_VALUE,
+        amm_config.flash_loan_fee_rate,
+        GammaError::InvalidFeeBounds
+    );
+    require_gte!(MAX_FEE_CHANGE_DELAY, amm_config.fee_change_delay);
+    require_gt!(
+        FEE_RATE_DENOMINATOR_VALUE,
+        amm_config.max_pool_trade_fee_rate,
+        GammaError::InvalidFeeBounds
+    );
+    require_gte!(
+        amm_config.max_pool_trade_fee_rate,
+        amm_config.min_pool_trade_fee_rate,
+        GammaError::InvalidFeeBounds
     );
+    require_gte!(amm_config.max_open_time, amm_config.default_open_delay);
+    require_gte!(amm_config.default_open_delay, amm_config.min_open_delay);
+    require_gte!(10_000, amm_config.max_mutable_transfer_fee_bps);
 
     Ok(())
 }
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+pub const AMM_CONFIG_ROLES_SEED: &str = "amm_config_roles";
+
+/// A part of the admin's powers over an `AmmConfig` and its pools
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigRole {
+    /// Updates the config, directly or through a timelocked update
+    Config,
+    /// Collects the protocol and fund fees of the config's pools
+    FeeCollect,
+    /// Pauses the config's pools with `emergency_pause`
+    Pause,
+}
+
+/// Authorities the admin hands a role to, so e.g. a hot key can pause pools without holding
+/// their fees. The admin keeps every role, unset roles are held by the admin alone.
+#[account]
+#[derive(Default, Debug)]
+pub struct AmmConfigRoles {
+    pub bump: u8,
+    pub amm_config: Pubkey,
+    pub config_authority: Pubkey,
+    pub fee_collect_authority: Pubkey,
+    pub pause_authority: Pubkey,
+    pub padding: [u64; 4],
+}
+
+impl AmmConfigRoles {
+    pub const LEN: usize = 8 + 1 + 32 * 4 + 8 * 4;
+
+    pub fn authority(&self, role: ConfigRole) -> Pubkey {
+        match role {
+            ConfigRole::Config => self.config_authority,
+            ConfigRole::FeeCollect => self.fee_collect_authority,
+            ConfigRole::Pause => self.pause_authority,
+        }
+    }
+
+    pub fn set_authority(&mut self, role: ConfigRole, authority: Pubkey) {
+        match role {
+            ConfigRole::Config => self.config_authority = authority,
+            ConfigRole::FeeCollect => self.fee_collect_authority = authority,
+            ConfigRole::Pause => self.pause_authority = authority,
+        }
+    }
+}
+
+/// Whether `signer` is the admin or the authority of `role` in the config's roles, if passed
+pub fn has_config_role(
+    config_roles: Option<&AmmConfigRoles>,
+    role: ConfigRole,
+    signer: Pubkey,
+) -> bool {
+    if signer == crate::admin::id() {
+        return true;
+    }
+    config_roles.is_some_and(|config_roles| {
+        let authority = config_roles.authority(role);
+        authority != Pubkey::default() && authority == signer
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unset_config_roles_are_held_by_no_one_but_the_admin() {
+        let admin = crate::admin::id();
+        let pause_authority = Pubkey::new_unique();
+        let mut config_roles = AmmConfigRoles::default();
+
+        // An unset role must not match a signer that happens to be the default key
+        for role in [
+            ConfigRole::Config,
+            ConfigRole::FeeCollect,
+            ConfigRole::Pause,
+        ] {
+            assert!(has_config_role(None, role, admin));
+            assert!(has_config_role(Some(&config_roles), role, admin));
+            assert!(!has_config_role(
+                Some(&config_roles),
+                role,
+                Pubkey::default()
+            ));
+        }
+
+        config_roles.set_authority(ConfigRole::Pause, pause_authority);
+        assert!(has_config_role(
+            Some(&config_roles),
+            ConfigRole::Pause,
+            pause_authority
+        ));
+        assert!(!has_config_role(
+            Some(&config_roles),
+            ConfigRole::FeeCollect,
+            pause_authority
+        ));
+        assert!(!has_config_role(None, ConfigRole::Pause, pause_authority));
+    }
+}
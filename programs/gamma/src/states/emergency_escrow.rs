@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+pub const EMERGENCY_ESCROW_SEED: &str = "emergency_escrow";
+pub const EMERGENCY_ESCROW_VAULT_SEED: &str = "emergency_escrow_vault";
+
+/// LP holders can only claim their share once the escrow has been held this long, which leaves
+/// time to restore the funds to the pool instead.
+pub const EMERGENCY_ESCROW_TIMELOCK: u64 = 3 * 24 * 60 * 60;
+
+/// Liquidity moved out of a pool in an emergency. The escrow vaults are owned by the pool vault
+/// authority, so the funds can only go back into the pool or pro-rata to its LP holders.
+#[account]
+#[derive(Default, Debug)]
+pub struct EmergencyEscrow {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    /// Amounts still held by the escrow vaults
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    /// LP supply that hasn't claimed its share yet
+    pub lp_supply: u64,
+    pub escrowed_at: u64,
+    /// LP holders can claim from this timestamp
+    pub unlock_time: u64,
+    /// LP that claimed its share, the escrow can only be restored to the pool while it's zero
+    pub lp_claimed: u64,
+    /// Status of the pool before the escrow, restored with the liquidity
+    pub pool_status: u8,
+    pub padding: [u8; 23],
+}
+
+impl EmergencyEscrow {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 8 * 5 + 8 + 1 + 23;
+}
@@ -1,10 +1,15 @@
+//! Every event starts with its layout version, right after the discriminator. An event's
+//! `VERSION` is bumped whenever its fields change, so decoders can tell the layouts apart.
+//! `tests/event_layouts.rs` pins the discriminators and layouts.
+
+use crate::states::PoolLifecycle;
 use anchor_lang::prelude::*;
 
 /// Emitted when deposit or withdraw
 #[event]
-#[cfg_attr(feature = "client", derive(Debug))]
 #[derive(Clone, Debug)]
 pub struct LpChangeEvent {
+    pub version: u8,
     #[index]
     pub pool_id: Pubkey,
     pub lp_amount_before: u64,
@@ -24,11 +29,15 @@ pub struct LpChangeEvent {
     pub change_type: u8,
 }
 
+impl LpChangeEvent {
+    pub const VERSION: u8 = 1;
+}
+
 // Emitted when swap
 #[event]
-#[cfg_attr(feature = "client", derive(Debug))]
 #[derive(Clone, Debug)]
 pub struct SwapEvent {
+    pub version: u8,
     #[index]
     pub pool_id: Pubkey,
     /// pool vault - trade_fees
@@ -50,16 +59,632 @@ pub struct SwapEvent {
     pub base_input: bool,
     /// dynamic_fees after this swap
     pub dynamic_fee: u128,
+    /// `input_amount` and `output_amount` with the interest of interest-bearing mints applied,
+    /// in base units, the raw amounts for other mints. Zero for an interest-bearing token whose
+    /// mint the swap doesn't pass
+    pub input_ui_adjusted_amount: u64,
+    pub output_ui_adjusted_amount: u64,
+    /// Reserves after the swap, without trade fees like `input_vault_before`. For single token
+    /// deposits and withdrawals, the reserves after their swap leg
+    pub vault_0_after: u64,
+    pub vault_1_after: u64,
+    /// LP supply when the swap happened
+    pub lp_supply: u64,
+    /// Fee rate of the swap, denominated in hundredths of bip (10^-6)
+    pub dynamic_fee_rate: u64,
+    /// Index of the AmmConfig of the pool
+    pub amm_config_index: u16,
+}
+
+impl SwapEvent {
+    pub const VERSION: u8 = 3;
 }
 
 /// Emitted when migration
 #[event]
-#[cfg_attr(feature = "client", derive(Debug))]
 #[derive(Clone, Debug)]
 pub struct MigrationEvent {
+    pub version: u8,
     pub from_pool: Pubkey,
     pub to_pool: Pubkey,
     pub token_0_amount_withdrawn: u64,
     pub token_1_amount_withdrawn: u64,
     pub lp_tokens_migrated: u128,
 }
+
+impl MigrationEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when liquidity moves into, or out of, an emergency escrow
+#[event]
+#[derive(Clone, Debug)]
+pub struct EmergencyEscrowEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub escrow: Pubkey,
+    // owner of the liquidity for claims, the admin otherwise
+    pub authority: Pubkey,
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    pub lp_amount: u64,
+    pub unlock_time: u64,
+    // 0: escrowed, 1: restored to the pool, 2: claimed by an LP holder
+    pub change_type: u8,
+}
+
+impl EmergencyEscrowEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted at most once per epoch per pool, covering everything since the previous report
+#[event]
+#[derive(Clone, Debug)]
+pub struct FeeReportEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub epoch: u64,
+    // last report, or zero for the first report of a pool
+    pub start_timestamp: u64,
+    pub end_timestamp: u64,
+    // total trade fees charged, the sum of the buckets below
+    pub trade_fees_token_0: u128,
+    pub trade_fees_token_1: u128,
+    pub protocol_fees_token_0: u128,
+    pub protocol_fees_token_1: u128,
+    pub fund_fees_token_0: u128,
+    pub fund_fees_token_1: u128,
+    pub lp_fees_token_0: u128,
+    pub lp_fees_token_1: u128,
+    // swap input volume
+    pub volume_token_0: u128,
+    pub volume_token_1: u128,
+    // volume weighted dynamic fee rate per input token, denominated in FEE_RATE_DENOMINATOR_VALUE
+    pub average_dynamic_fee_rate_token_0: u64,
+    pub average_dynamic_fee_rate_token_1: u64,
+}
+
+impl FeeReportEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a gauge's emission rate is settled from the votes of the previous epoch
+#[event]
+#[derive(Clone, Debug)]
+pub struct GaugeSettledEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    // the epoch the emission rate applies to
+    pub epoch: u64,
+    // votes of the pool and of all pools in the previous epoch
+    pub votes: u64,
+    pub total_votes: u64,
+    // rewards per second
+    pub emission_rate: u64,
+}
+
+impl GaugeSettledEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when the admin slashes a solver's bond for a failed settlement
+#[event]
+#[derive(Clone, Debug)]
+pub struct SolverSlashedEvent {
+    pub version: u8,
+    #[index]
+    pub solver: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    // bond left after the slash
+    pub bonded_amount: u64,
+    pub failed_settlements: u64,
+}
+
+impl SolverSlashedEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a flash loan is repaid
+#[event]
+#[derive(Clone, Debug)]
+pub struct FlashLoanEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    // accrued to the LPs
+    pub fee: u64,
+}
+
+impl FlashLoanEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a position claims the LP fees accrued to it
+#[event]
+#[derive(Clone, Debug)]
+pub struct LpFeesClaimEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    // paid to the owner
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    // paid to the referrer of the position, zero without one
+    pub referrer: Pubkey,
+    pub referral_token_0_amount: u64,
+    pub referral_token_1_amount: u64,
+}
+
+impl LpFeesClaimEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted on every payout to a referrer, of a swap's referral fee or of a share of the LP fees
+/// a referred position claims
+#[event]
+#[derive(Clone, Debug)]
+pub struct ReferralPaidEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    /// The referral account of swaps, the referrer recorded on positions
+    pub referrer: Pubkey,
+    /// Referral project of the pool's config
+    pub project: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    /// Swap input, or LP fees claimed, the referral was paid out of
+    pub trade_amount: u64,
+}
+
+impl ReferralPaidEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when an instruction moves a pool to another stage of its lifecycle. Funded pools
+/// open once their open time passes, without an instruction or an event.
+#[event]
+#[derive(Clone, Debug)]
+pub struct PoolLifecycleEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub from: PoolLifecycle,
+    pub to: PoolLifecycle,
+}
+
+impl PoolLifecycleEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when the admin adds a partner to a pool or removes it. The fees of a removed partner
+/// are the final amounts attributed to it.
+#[event]
+#[derive(Clone, Debug)]
+pub struct PartnerChangeEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub partner_id: u64,
+    pub lp_token_linked_with_partner: u64,
+    pub cumulative_fee_total_times_tvl_share_token_0: u64,
+    pub cumulative_fee_total_times_tvl_share_token_1: u64,
+    // 0: added, 1: removed
+    pub change_type: u8,
+}
+
+impl PartnerChangeEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when an LP links their position with another partner or unlinks it
+#[event]
+#[derive(Clone, Debug)]
+pub struct PartnerLpLinkEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub user: Pubkey,
+    pub from_partner: Option<u64>,
+    pub to_partner: Option<u64>,
+    /// LP tokens of the position that moved between the partners
+    pub lp_amount: u64,
+}
+
+impl PartnerLpLinkEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a deposit or withdrawal leaves a single position holding more of the LP supply
+/// than the pool's `lp_concentration_alert_bps`
+#[event]
+#[derive(Clone, Debug)]
+pub struct LpConcentrationEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub lp_tokens: u64,
+    pub lp_supply: u64,
+    /// Share of the LP supply the position holds, in bps
+    pub share_bps: u64,
+}
+
+impl LpConcentrationEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a staker claims rewards, see `PoolRewards`
+#[event]
+#[derive(Clone, Debug)]
+pub struct RewardClaimEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub reward_mint: Pubkey,
+    pub amount: u64,
+}
+
+impl RewardClaimEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a `Position` is opened, transferred, closed, split or merged
+#[event]
+#[derive(Clone, Debug)]
+pub struct PositionChangeEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub position: Pubkey,
+    /// Owner before the change, the default pubkey when the position is opened
+    pub from_owner: Pubkey,
+    /// Owner after the change, the default pubkey when the position is closed
+    pub to_owner: Pubkey,
+    pub lp_tokens: u64,
+    pub unlock_time: u64,
+    // 0: opened, 1: transferred, 2: closed, 3: split off another position, 4: merged into another
+    pub change_type: u8,
+}
+
+impl PositionChangeEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted once per `swap_batch` in place of a `SwapEvent` for every leg
+#[event]
+#[derive(Clone, Debug)]
+pub struct SwapBatchEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub payer: Pubkey,
+    pub legs: u8,
+    /// Sent by the payer over all legs, transfer fees included
+    pub token_0_amount_in: u64,
+    pub token_1_amount_in: u64,
+    /// Received by the payer over all legs, net of transfer fees
+    pub token_0_amount_out: u64,
+    pub token_1_amount_out: u64,
+}
+
+impl SwapBatchEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when `skim_surplus` sweeps what the vaults hold beyond the pool's accounting
+#[event]
+#[derive(Clone, Debug)]
+pub struct SurplusSkimEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+impl SurplusSkimEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when tokens are donated to the pool's reserves
+#[event]
+#[derive(Clone, Debug)]
+pub struct DonationEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub donor: Pubkey,
+    /// Credited to the reserves, net of transfer fees
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+}
+
+impl DonationEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a user deposits, withdraws or creates their `UserPoolLiquidity`, with the LP the
+/// user and the pool hold afterwards
+#[event]
+#[derive(Clone, Debug)]
+pub struct LiquidityChangeEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub user: Pubkey,
+    /// LP tokens minted on deposits and burned on withdrawals
+    pub lp_token_amount: u64,
+    /// What enters or leaves the vaults, without transfer fees
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+    /// LP supply of the pool after the change
+    pub lp_supply: u64,
+    /// LP tokens owned by the user after the change
+    pub user_lp_tokens: u128,
+    /// 0: deposit, 1: withdraw, 2: `UserPoolLiquidity` created
+    pub change_type: u8,
+}
+
+impl LiquidityChangeEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when the admin creates an `AmmConfig`
+#[event]
+#[derive(Clone, Debug)]
+pub struct AmmConfigCreatedEvent {
+    pub version: u8,
+    #[index]
+    pub amm_config: Pubkey,
+    pub signer: Pubkey,
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    pub max_open_time: u64,
+}
+
+impl AmmConfigCreatedEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a param of an `AmmConfig` is updated, directly or through an approved or
+/// scheduled change
+#[event]
+#[derive(Clone, Debug)]
+pub struct AmmConfigChangeEvent {
+    pub version: u8,
+    #[index]
+    pub amm_config: Pubkey,
+    /// Who applied the change
+    pub signer: Pubkey,
+    /// The `param` of `update_amm_config`
+    pub param: u16,
+    /// Values of numeric and flag params, 0 for params holding a key
+    pub old_value: u64,
+    pub new_value: u64,
+    /// Values of the params holding a key, the default pubkey for the others
+    pub old_key: Pubkey,
+    pub new_key: Pubkey,
+}
+
+impl AmmConfigChangeEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when the admin sets the status bits of a pool
+#[event]
+#[derive(Clone, Debug)]
+pub struct PoolStatusChangeEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub signer: Pubkey,
+    pub old_status: u8,
+    pub new_status: u8,
+}
+
+impl PoolStatusChangeEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when tokens are bought from or sold to a bonding curve
+#[event]
+#[derive(Clone, Debug)]
+pub struct BondingCurveTradeEvent {
+    pub version: u8,
+    #[index]
+    pub bonding_curve: Pubkey,
+    pub trader: Pubkey,
+    /// Whether the trader bought tokens with quote
+    pub is_buy: bool,
+    /// Amounts the curve received and paid out, after transfer fees
+    pub token_amount: u64,
+    pub quote_amount: u64,
+    /// Reserves of the curve after the trade
+    pub token_reserve: u64,
+    pub quote_reserve: u64,
+}
+
+impl BondingCurveTradeEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when a bonding curve graduates into a pool
+#[event]
+#[derive(Clone, Debug)]
+pub struct BondingCurveGraduatedEvent {
+    pub version: u8,
+    #[index]
+    pub bonding_curve: Pubkey,
+    pub pool_id: Pubkey,
+    /// Position holding the locked LP
+    pub position: Pubkey,
+    /// Amounts the pool was seeded with
+    pub token_amount: u64,
+    pub quote_amount: u64,
+    pub lp_tokens: u64,
+}
+
+impl BondingCurveGraduatedEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when LP is locked in a position with `lock_lp` or by `graduate`, whose lock has an
+/// `unlock_time` of `u64::MAX`, and when `unlock_lp` releases its vested part
+#[event]
+#[derive(Clone, Debug)]
+pub struct LpLockEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    /// LP of the position still locked after the change
+    pub locked_lp_tokens: u64,
+    /// LP released back into the owner's liquidity, 0 when locking
+    pub released_lp_tokens: u64,
+    pub vesting_start: u64,
+    pub unlock_time: u64,
+}
+
+impl LpLockEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when LP is burned with `burn_lp_forever`
+#[event]
+#[derive(Clone, Debug)]
+pub struct LpBurnEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub lp_tokens: u64,
+    /// LP of the pool burned so far, this burn included
+    pub total_burned_lp_tokens: u64,
+    pub lp_supply: u64,
+}
+
+impl LpBurnEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when `transfer_user_position` moves LP between the liquidity of two owners
+#[event]
+#[derive(Clone, Debug)]
+pub struct UserPositionTransferEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub from_owner: Pubkey,
+    pub to_owner: Pubkey,
+    pub lp_tokens: u64,
+    /// LP of each owner after the transfer
+    pub from_lp_tokens: u64,
+    pub to_lp_tokens: u64,
+}
+
+impl UserPositionTransferEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when the admin hands a role over an `AmmConfig` to an authority, or takes it back
+#[event]
+#[derive(Clone, Debug)]
+pub struct ConfigRoleChangeEvent {
+    pub version: u8,
+    #[index]
+    pub amm_config: Pubkey,
+    /// The `ConfigRole`
+    pub role: u8,
+    /// The default pubkey when the role was unset
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+impl ConfigRoleChangeEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted for each pool `sweep_protocol_fees` sends the protocol fees of to the treasury
+#[event]
+#[derive(Clone, Debug)]
+pub struct ProtocolFeeSweepEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    /// Owner of the token accounts receiving the fees
+    pub treasury: Pubkey,
+    pub cranker: Pubkey,
+    /// Fees sent to the treasury
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    /// Fees paid to the cranker
+    pub token_0_bounty: u64,
+    pub token_1_bounty: u64,
+}
+
+impl ProtocolFeeSweepEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when `convert_fund_fees` sells the fund fees of a pool for the config's target mint
+#[event]
+#[derive(Clone, Debug)]
+pub struct FundFeeConversionEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    /// The pool the fees were sold in, `pool_id` itself or a sibling pool
+    pub swap_pool_id: Pubkey,
+    pub fee_mint: Pubkey,
+    pub target_mint: Pubkey,
+    /// Fund fees collected from the pool
+    pub fee_amount: u64,
+    /// Target mint the fees were sold for
+    pub amount_out: u64,
+}
+
+impl FundFeeConversionEvent {
+    pub const VERSION: u8 = 1;
+}
+
+/// Emitted when part of a vault's reserve moves to or from its lending strategy
+#[event]
+#[derive(Clone, Debug)]
+pub struct VaultStrategyChangeEvent {
+    pub version: u8,
+    #[index]
+    pub pool_id: Pubkey,
+    pub vault: Pubkey,
+    /// 0 deposited by a rebalance, 1 withdrawn by a rebalance, 2 recalled for a swap or
+    /// withdrawal
+    pub change_type: u8,
+    pub amount: u64,
+    /// Part of the reserve deployed after the change
+    pub deployed_amount: u64,
+    /// What the position is worth after the change, as reported by the adapter
+    pub position_value: u64,
+    /// Interest harvested into the reserve
+    pub yield_amount: u64,
+    /// Loss of the position taken out of the reserve
+    pub loss_amount: u64,
+}
+
+impl VaultStrategyChangeEvent {
+    pub const VERSION: u8 = 1;
+}
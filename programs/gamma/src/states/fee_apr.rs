@@ -0,0 +1,159 @@
+use crate::error::GammaError;
+use anchor_lang::prelude::*;
+
+pub const FEE_APR_SEED: &str = "fee_apr";
+/// Hourly samples covering the longest trailing window
+pub const FEE_APR_SAMPLE_NUM: usize = 7 * 24;
+/// A new sample is taken at most once per this many seconds
+pub const FEE_APR_SAMPLE_INTERVAL: u64 = 60 * 60;
+pub const FEE_APR_WINDOW_24H: u64 = 24 * 60 * 60;
+pub const FEE_APR_WINDOW_7D: u64 = 7 * 24 * 60 * 60;
+
+const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+const APR_BPS_DENOMINATOR: u128 = 10_000;
+
+/// The pool's cumulative trade fees at a point in time
+#[zero_copy(unsafe)]
+#[repr(packed)]
+#[derive(Default, Debug)]
+pub struct FeeAprSample {
+    pub block_timestamp: u64,
+    pub cumulative_trade_fees_token_0: u128,
+    pub cumulative_trade_fees_token_1: u128,
+}
+
+impl FeeAprSample {
+    pub const LEN: usize = 8 + 16 + 16;
+}
+
+/// Trailing fee APR earned by the LPs of a pool, kept up to date by a crank so vault products
+/// can compare pools on-chain
+#[account(zero_copy(unsafe))]
+#[repr(packed)]
+#[cfg_attr(any(feature = "client", feature = "test-sbf"), derive(Debug))]
+pub struct FeeApr {
+    pub pool_state: Pubkey,
+    /// LP fee APR in basis points over the trailing 24 hours, valued in token_0
+    pub apr_24h_bps: u64,
+    /// LP fee APR in basis points over the trailing 7 days, valued in token_0
+    pub apr_7d_bps: u64,
+    pub updated_at: u64,
+    /// The most recently written index of `samples`
+    pub sample_index: u16,
+    pub samples: [FeeAprSample; FEE_APR_SAMPLE_NUM],
+    pub padding: [u64; 4],
+}
+
+impl Default for FeeApr {
+    #[inline]
+    fn default() -> FeeApr {
+        FeeApr {
+            pool_state: Pubkey::default(),
+            apr_24h_bps: 0,
+            apr_7d_bps: 0,
+            updated_at: 0,
+            sample_index: 0,
+            samples: [FeeAprSample::default(); FEE_APR_SAMPLE_NUM],
+            padding: [0u64; 4],
+        }
+    }
+}
+
+impl FeeApr {
+    pub const LEN: usize = 8 + 32 + 8 * 3 + 2 + FEE_APR_SAMPLE_NUM * FeeAprSample::LEN + 8 * 4;
+
+    /// Stores the current cumulative fees, unless the latest sample is younger than the interval
+    pub fn sample(
+        &mut self,
+        block_timestamp: u64,
+        cumulative_trade_fees_token_0: u128,
+        cumulative_trade_fees_token_1: u128,
+    ) {
+        let latest = self.samples[usize::from(self.sample_index)];
+        let next_index = if latest.block_timestamp == 0 {
+            self.sample_index
+        } else if block_timestamp < latest.block_timestamp + FEE_APR_SAMPLE_INTERVAL {
+            return;
+        } else {
+            ((usize::from(self.sample_index) + 1) % FEE_APR_SAMPLE_NUM) as u16
+        };
+        self.samples[usize::from(next_index)] = FeeAprSample {
+            block_timestamp,
+            cumulative_trade_fees_token_0,
+            cumulative_trade_fees_token_1,
+        };
+        self.sample_index = next_index;
+    }
+
+    /// The newest sample at least `window` seconds old, or the oldest one while the account is
+    /// younger than the window
+    fn window_start(&self, block_timestamp: u64, window: u64) -> Option<FeeAprSample> {
+        let target = block_timestamp.saturating_sub(window);
+        let samples = self.samples.iter().filter(|s| s.block_timestamp != 0);
+        samples
+            .clone()
+            .filter(|s| s.block_timestamp <= target)
+            .max_by_key(|s| s.block_timestamp)
+            .or_else(|| samples.min_by_key(|s| s.block_timestamp))
+            .copied()
+    }
+
+    /// LP fee APR in basis points over the trailing `window`, fees and tvl valued in token_0
+    ///
+    /// # Arguments
+    ///
+    /// * `block_timestamp` - The current timestamp
+    /// * `window` - The trailing window in seconds
+    /// * `current` - The pool's current cumulative fees
+    /// * `token_1_price_x32` - Price of token_1 in token_0, Q32.32
+    /// * `tvl_token_0` - The LP owned liquidity valued in token_0
+    /// * `lp_fee_share` - Share of the trade fees going to LPs, in FEE_RATE_DENOMINATOR_VALUE
+    ///
+    pub fn apr_bps(
+        &self,
+        block_timestamp: u64,
+        window: u64,
+        current: &FeeAprSample,
+        token_1_price_x32: u128,
+        tvl_token_0: u128,
+        lp_fee_share: u64,
+    ) -> Result<u64> {
+        let start = match self.window_start(block_timestamp, window) {
+            Some(start) => start,
+            None => return Ok(0),
+        };
+        let elapsed = current.block_timestamp.saturating_sub(start.block_timestamp);
+        if elapsed == 0 || tvl_token_0 == 0 {
+            return Ok(0);
+        }
+
+        let fees_token_0 = current
+            .cumulative_trade_fees_token_0
+            .saturating_sub(start.cumulative_trade_fees_token_0);
+        let fees_token_1 = current
+            .cumulative_trade_fees_token_1
+            .saturating_sub(start.cumulative_trade_fees_token_1);
+        let fees_value = fees_token_1
+            .checked_mul(token_1_price_x32)
+            .ok_or(GammaError::MathOverflow)?
+            .checked_shr(32)
+            .ok_or(GammaError::MathOverflow)?
+            .checked_add(fees_token_0)
+            .ok_or(GammaError::MathOverflow)?;
+        let lp_fees_value = fees_value
+            .checked_mul(u128::from(lp_fee_share))
+            .ok_or(GammaError::MathOverflow)?
+            / u128::from(crate::fees::FEE_RATE_DENOMINATOR_VALUE);
+
+        let apr_bps = lp_fees_value
+            .checked_mul(APR_BPS_DENOMINATOR * SECONDS_PER_YEAR)
+            .ok_or(GammaError::MathOverflow)?
+            .checked_div(
+                u128::from(elapsed)
+                    .checked_mul(tvl_token_0)
+                    .ok_or(GammaError::MathOverflow)?,
+            )
+            .ok_or(GammaError::MathOverflow)?;
+        Ok(u64::try_from(apr_bps).unwrap_or(u64::MAX))
+    }
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+pub const FEE_REPORT_SEED: &str = "fee_report";
+
+/// The pool's cumulative fee and volume counters as of its last `FeeReportEvent`, so the next
+/// report only covers what happened since.
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeReport {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    /// Epoch of the last report, a pool is reported at most once per epoch
+    pub last_report_epoch: u64,
+    pub last_report_timestamp: u64,
+    pub cumulative_trade_fees_token_0: u128,
+    pub cumulative_trade_fees_token_1: u128,
+    pub cumulative_volume_token_0: u128,
+    pub cumulative_volume_token_1: u128,
+    pub cumulative_protocol_fees_token_0: u128,
+    pub cumulative_protocol_fees_token_1: u128,
+    pub cumulative_fund_fees_token_0: u128,
+    pub cumulative_fund_fees_token_1: u128,
+    pub padding: [u64; 4],
+}
+
+impl FeeReport {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 2 + 16 * 8 + 8 * 4;
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::{error::GammaError, fees::FEE_RATE_DENOMINATOR_VALUE};
+
+pub const FEE_TREASURY_SEED: &str = "fee_treasury";
+
+/// Most of the swept fees the cranker can be paid, 1%
+pub const MAX_SWEEP_BOUNTY_RATE: u64 = 10_000;
+
+/// Where `sweep_protocol_fees` sends the protocol fees of a config's pools. It lives next to the
+/// `AmmConfig` it is keyed by, the config itself having no room left.
+#[account]
+#[derive(Default, Debug)]
+pub struct FeeTreasury {
+    pub bump: u8,
+    pub amm_config: Pubkey,
+    /// Owner of the associated token accounts receiving the fees
+    pub treasury: Pubkey,
+    /// Share of the swept fees paid to the cranker, in fee rate units
+    pub bounty_rate: u64,
+    pub padding: [u64; 4],
+}
+
+impl FeeTreasury {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 + 8 * 4;
+
+    /// The associated token account of the treasury for `mint`
+    pub fn treasury_token_account(&self, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(&self.treasury, mint, token_program)
+    }
+
+    /// Splits `amount` of swept fees into the cranker's bounty, rounded down, and the rest for
+    /// the treasury
+    pub fn split_bounty(&self, amount: u64) -> Result<(u64, u64)> {
+        let bounty = u128::from(amount)
+            .checked_mul(u128::from(self.bounty_rate))
+            .ok_or(GammaError::MathOverflow)?
+            / u128::from(FEE_RATE_DENOMINATOR_VALUE);
+        let bounty = u64::try_from(bounty).map_err(|_| GammaError::MathOverflow)?;
+        Ok((bounty, amount - bounty))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn swept_fees_split_between_treasury_and_cranker_without_loss() {
+        let fee_treasury = FeeTreasury {
+            bounty_rate: MAX_SWEEP_BOUNTY_RATE,
+            ..Default::default()
+        };
+        for amount in [0, 1, 99, 100, 12_345_678, u64::MAX] {
+            let (bounty, treasury_amount) = fee_treasury.split_bounty(amount).unwrap();
+            assert_eq!(bounty + treasury_amount, amount);
+            // The bounty rounds down, dust always goes to the treasury
+            assert!(bounty <= amount / 100);
+        }
+    }
+}
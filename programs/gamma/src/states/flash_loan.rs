@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+pub const FLASH_LOAN_SEED: &str = "flash_loan";
+
+/// The flash loan of a pool, reused by every loan of the pool. Only one loan can be outstanding
+/// at a time and it must be repaid in the transaction that took it.
+#[account]
+#[derive(Default, Debug)]
+pub struct FlashLoanState {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    pub borrower: Pubkey,
+    /// The vault the loan was taken from
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    /// Balance of the vault before lending
+    pub vault_balance_before: u64,
+    /// What the pool accounted for in the vault before lending, so swaps or deposits made
+    /// between the loan and the repayment are not mistaken for repayment
+    pub accounted_amount_before: u64,
+    pub active: bool,
+    pub padding: [u64; 4],
+}
+
+impl FlashLoanState {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 8 * 4 + 1 + 8 * 4;
+}
@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+pub const FUND_FEE_CONVERSION_SEED: &str = "fund_fee_conversion";
+
+/// Mint `convert_fund_fees` swaps the fund fees of a config's pools into, e.g. USDC, so the fund
+/// owner doesn't collect a dust balance of every mint the pools trade
+#[account]
+#[derive(Default, Debug)]
+pub struct FundFeeConversion {
+    pub bump: u8,
+    pub amm_config: Pubkey,
+    pub target_mint: Pubkey,
+    pub padding: [u64; 4],
+}
+
+impl FundFeeConversion {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 * 4;
+}
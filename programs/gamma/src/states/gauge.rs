@@ -0,0 +1,158 @@
+use crate::error::GammaError;
+use anchor_lang::prelude::*;
+
+pub const GAUGE_CONTROLLER_SEED: &str = "gauge_controller";
+pub const GAUGE_SEED: &str = "gauge";
+pub const GAUGE_VOTER_SEED: &str = "gauge_voter";
+
+/// Global parameters of the gauge system and the vote total of the running epoch.
+/// Voting power is the voter's balance of the vote escrowed GOFX mint.
+#[account]
+#[derive(Default, Debug)]
+pub struct GaugeController {
+    pub bump: u8,
+    /// veGOFX mint, expected to be non-transferable so the same power can't vote twice
+    pub ve_mint: Pubkey,
+    /// Start of epoch zero
+    pub start_time: u64,
+    /// Length of an epoch in seconds, fixed at creation
+    pub epoch_duration: u64,
+    /// Rewards emitted across all gauges over one epoch
+    pub emission_per_epoch: u64,
+    /// The epoch `total_votes` was cast in
+    pub vote_epoch: u64,
+    pub total_votes: u64,
+    /// Total votes of the epoch before `vote_epoch`
+    pub previous_total_votes: u64,
+    pub padding: [u64; 4],
+}
+
+impl GaugeController {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 6 + 8 * 4;
+
+    pub fn epoch_at(&self, block_timestamp: u64) -> u64 {
+        block_timestamp.saturating_sub(self.start_time) / self.epoch_duration
+    }
+
+    /// Moves the vote total to `previous_total_votes` once its epoch is over
+    pub fn roll(&mut self, epoch: u64) {
+        if epoch <= self.vote_epoch {
+            return;
+        }
+        self.previous_total_votes = if epoch == self.vote_epoch + 1 {
+            self.total_votes
+        } else {
+            0
+        };
+        self.total_votes = 0;
+        self.vote_epoch = epoch;
+    }
+
+    /// Total votes cast in the epoch before `epoch`, which must be the rolled epoch
+    pub fn previous_votes(&self, epoch: u64) -> u64 {
+        if epoch == self.vote_epoch {
+            self.previous_total_votes
+        } else {
+            0
+        }
+    }
+}
+
+/// Votes directed at a pool and the emission rate settled from them
+#[account]
+#[derive(Default, Debug)]
+pub struct Gauge {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    /// The epoch `votes` was cast in
+    pub vote_epoch: u64,
+    pub votes: u64,
+    /// Votes of the epoch before `vote_epoch`
+    pub previous_votes: u64,
+    /// The epoch `emission_rate` applies to, zero before the first settlement
+    pub settled_epoch: u64,
+    /// Rewards emitted to the pool per second during `settled_epoch`
+    pub emission_rate: u64,
+    pub padding: [u64; 4],
+}
+
+impl Gauge {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 5 + 8 * 4;
+
+    pub fn initialize(&mut self, bump: u8, pool_state: Pubkey) {
+        self.bump = bump;
+        self.pool_state = pool_state;
+        self.vote_epoch = 0;
+        self.votes = 0;
+        self.previous_votes = 0;
+        self.settled_epoch = 0;
+        self.emission_rate = 0;
+        self.padding = [0u64; 4];
+    }
+
+    /// Moves the votes to `previous_votes` once their epoch is over
+    pub fn roll(&mut self, epoch: u64) {
+        if epoch <= self.vote_epoch {
+            return;
+        }
+        self.previous_votes = if epoch == self.vote_epoch + 1 {
+            self.votes
+        } else {
+            0
+        };
+        self.votes = 0;
+        self.vote_epoch = epoch;
+    }
+
+    /// Sets the emission rate of `epoch` from the votes the gauge got in the epoch before
+    pub fn settle(&mut self, controller: &GaugeController, epoch: u64) -> Result<()> {
+        require_gt!(epoch, self.settled_epoch, GammaError::GaugeAlreadySettled);
+        let total_votes = controller.previous_votes(epoch);
+        let emission_per_epoch = if total_votes == 0 {
+            0
+        } else {
+            u128::from(controller.emission_per_epoch)
+                .checked_mul(u128::from(self.previous_votes))
+                .ok_or(GammaError::MathOverflow)?
+                / u128::from(total_votes)
+        };
+        // The share is at most the whole emission, so it fits in a u64
+        self.emission_rate = (emission_per_epoch / u128::from(controller.epoch_duration)) as u64;
+        self.settled_epoch = epoch;
+        Ok(())
+    }
+}
+
+/// Voting power a voter already spent in the running epoch
+#[account]
+#[derive(Default, Debug)]
+pub struct GaugeVoter {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub vote_epoch: u64,
+    pub used_power: u64,
+    pub padding: [u64; 2],
+}
+
+impl GaugeVoter {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 2 + 8 * 2;
+
+    /// Spends `power` of the voter's `voting_power` in `epoch`
+    pub fn spend(&mut self, epoch: u64, power: u64, voting_power: u64) -> Result<()> {
+        if epoch != self.vote_epoch {
+            self.vote_epoch = epoch;
+            self.used_power = 0;
+        }
+        let used_power = self
+            .used_power
+            .checked_add(power)
+            .ok_or(GammaError::MathOverflow)?;
+        require_gte!(
+            voting_power,
+            used_power,
+            GammaError::InsufficientVotingPower
+        );
+        self.used_power = used_power;
+        Ok(())
+    }
+}
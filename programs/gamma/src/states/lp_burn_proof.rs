@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+pub const LP_BURN_PROOF_SEED: &str = "lp_burn_proof";
+
+/// Running total of the LP of a pool burned with `burn_lp_forever`. The burned LP stays in the
+/// pool's LP supply with no owner, so its share of the reserves can never be withdrawn.
+#[account]
+#[derive(Default, Debug)]
+pub struct LpBurnProof {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    pub burned_lp_tokens: u64,
+    pub burn_count: u64,
+    pub last_burn_at: u64,
+    pub padding: [u64; 4],
+}
+
+impl LpBurnProof {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 3 + 8 * 4;
+
+    /// Reads the proof of `pool_state` passed as an optional account
+    pub fn load_checked(proof_info: &AccountInfo<'_>, pool_state: Pubkey) -> Result<LpBurnProof> {
+        require_keys_eq!(*proof_info.owner, crate::id());
+        let lp_burn_proof = LpBurnProof::try_deserialize(&mut &proof_info.data.borrow()[..])?;
+        require_keys_eq!(lp_burn_proof.pool_state, pool_state);
+        Ok(lp_burn_proof)
+    }
+}
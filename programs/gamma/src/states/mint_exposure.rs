@@ -0,0 +1,45 @@
+use crate::error::GammaError;
+use anchor_lang::prelude::*;
+
+pub const MINT_EXPOSURE_SEED: &str = "mint_exposure";
+
+/// Reserves of a mint held across every pool tracking it, capped so the protocol can limit how
+/// much of a risky asset it takes on. Pools are added with `track_mint_exposure`.
+#[account]
+#[derive(Default, Debug)]
+pub struct MintExposure {
+    pub bump: u8,
+    pub mint: Pubkey,
+    /// Most reserves of the mint the tracked pools can hold together
+    pub cap: u64,
+    /// Reserves of the mint the tracked pools hold
+    pub total_reserves: u64,
+    pub padding: [u64; 4],
+}
+
+impl MintExposure {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 2 + 8 * 4;
+
+    /// Moves the exposure by a change of a tracked pool's reserve, only increases are held to
+    /// the cap so a capped mint can always be withdrawn or sold
+    pub fn apply_reserve_change(&mut self, reserve_before: u64, reserve_after: u64) -> Result<()> {
+        if reserve_after >= reserve_before {
+            self.total_reserves = self
+                .total_reserves
+                .checked_add(reserve_after - reserve_before)
+                .ok_or(GammaError::MathOverflow)?;
+            require_gte!(
+                self.cap,
+                self.total_reserves,
+                GammaError::MintExposureCapExceeded
+            );
+        } else {
+            // Reserves that left a pool without being tracked, e.g. into an emergency escrow,
+            // can't take the total below zero
+            self.total_reserves = self
+                .total_reserves
+                .saturating_sub(reserve_before - reserve_after);
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,67 @@
+pub mod bonding_curve;
 pub mod config;
+pub mod config_roles;
+pub mod emergency_escrow;
 pub mod events;
+pub mod fee_apr;
+pub mod fee_report;
+pub mod fee_treasury;
+pub mod flash_loan;
+pub mod fund_fee_conversion;
+pub mod gauge;
+pub mod lp_burn_proof;
+pub mod mint_exposure;
 pub mod oracle;
+pub mod pair_index;
+pub mod pause_expiry;
+pub mod pending_fee_change;
 pub mod pool;
+pub mod pool_rewards;
+pub mod pool_whitelist;
+pub mod position;
+pub mod price_band;
+pub mod quote_cache;
+pub mod segmenter_kickback;
+pub mod segmenter_registry;
+pub mod solver;
+pub mod staking_discount;
+pub mod swap_hook;
+pub mod token_badge;
+pub mod transfer_hook;
 pub mod user_pool_liquidity;
+pub mod vault_strategy;
+pub mod volume_tracker;
 
+pub use bonding_curve::*;
 pub use config::*;
+pub use config_roles::*;
+pub use emergency_escrow::*;
 pub use events::*;
+pub use fee_apr::*;
+pub use fee_report::*;
+pub use fee_treasury::*;
+pub use flash_loan::*;
+pub use fund_fee_conversion::*;
+pub use gauge::*;
+pub use lp_burn_proof::*;
+pub use mint_exposure::*;
 pub use oracle::*;
+pub use pair_index::*;
+pub use pause_expiry::*;
+pub use pending_fee_change::*;
 pub use pool::*;
+pub use pool_rewards::*;
+pub use pool_whitelist::*;
+pub use position::*;
+pub use price_band::*;
+pub use quote_cache::*;
+pub use segmenter_kickback::*;
+pub use segmenter_registry::*;
+pub use solver::*;
+pub use staking_discount::*;
+pub use swap_hook::*;
+pub use token_badge::*;
+pub use transfer_hook::*;
 pub use user_pool_liquidity::*;
+pub use vault_strategy::*;
+pub use volume_tracker::*;
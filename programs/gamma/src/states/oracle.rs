@@ -2,10 +2,19 @@ use crate::error::GammaError;
 /// Oracle provides price data useful for a wide variety of system designs
 ///
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use gamma_math::oracle::{exp2_x32, log2_x32};
+use std::cell::{Ref, RefMut};
 /// Seed to derive account address and signature
 pub const OBSERVATION_SEED: &str = "observation";
 // Number of ObservationState element
 pub const OBSERVATION_NUM: usize = 100;
+/// Upper bound of the observations an ObservationState account can keep once grown, a day at
+/// the default update duration
+pub const MAX_OBSERVATION_CARDINALITY: usize = 5_760;
+
+/// Layout version of ObservationState, 1 since observations keep the log price
+pub const OBSERVATION_STATE_VERSION: u8 = 1;
 
 /// The duration of observation update in seconds
 pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u64 = 15;
@@ -21,9 +30,19 @@ pub struct Observation {
     pub cumulative_token_0_price_x32: u128,
     /// The cumulative of token1 price during the duration time, Q32.32, the remaining 64 bit for overflow
     pub cumulative_token_1_price_x32: u128,
+    /// The cumulative of the base 2 log of token0 price during the duration time, Q32.32,
+    /// wrapping. Token1's is its negation.
+    pub cumulative_token_0_log_price_x32: i128,
 }
+// SAFETY: the struct is packed, so it has no padding bytes, and every field is plain old data
+unsafe impl bytemuck::Zeroable for Observation {}
+unsafe impl bytemuck::Pod for Observation {}
+const _: () = assert!(std::mem::size_of::<Observation>() == Observation::LEN);
+
 impl Observation {
-    pub const LEN: usize = 8 + 16 + 16;
+    pub const LEN: usize = 8 + 16 + 16 + 16;
+    /// Size of an observation before the log price was kept, see `latest_observation`
+    pub const LEGACY_LEN: usize = 8 + 16 + 16;
 }
 
 #[account(zero_copy(unsafe))]
@@ -37,8 +56,16 @@ pub struct ObservationState {
     pub pool_id: Pubkey,
     /// observation array
     pub observations: [Observation; OBSERVATION_NUM],
+    /// Next index to write in the archive, the observations added after the struct by
+    /// `increase_observation_cardinality`
+    pub archive_index: u32,
+    /// Number of archive observations in use, catches up with the account size when
+    /// `archive_index` wraps so the archive stays in time order
+    pub archive_cardinality: u32,
+    /// Layout version, also keeps the size of current accounts apart from legacy ones
+    pub version: u8,
     /// padding
-    pub padding: [u64; 4],
+    pub padding: [u64; 3],
 }
 
 impl Default for ObservationState {
@@ -49,13 +76,201 @@ impl Default for ObservationState {
             observation_index: 0,
             pool_id: Pubkey::default(),
             observations: [Observation::default(); OBSERVATION_NUM],
-            padding: [0u64; 4],
+            archive_index: 0,
+            archive_cardinality: 0,
+            version: OBSERVATION_STATE_VERSION,
+            padding: [0u64; 3],
         }
     }
 }
 
 impl ObservationState {
-    pub const LEN: usize = 8 + 1 + 2 + 32 + (OBSERVATION_NUM * Observation::LEN) + 4 * 8;
+    pub const LEN: usize =
+        8 + 1 + 2 + 32 + (OBSERVATION_NUM * Observation::LEN) + 4 + 4 + 1 + 3 * 8;
+    /// Size of an account before observations kept the log price, without its archive
+    pub const LEGACY_LEN: usize =
+        8 + 1 + 2 + 32 + (OBSERVATION_NUM * Observation::LEGACY_LEN) + 4 + 4 + 3 * 8;
+
+    /// Account size keeping `cardinality` observations, the ones past OBSERVATION_NUM are archived
+    pub fn space(cardinality: usize) -> usize {
+        Self::LEN + cardinality.saturating_sub(OBSERVATION_NUM) * Observation::LEN
+    }
+
+    /// Number of observations an account of `data_len` bytes keeps
+    pub fn cardinality(data_len: usize) -> usize {
+        OBSERVATION_NUM + data_len.saturating_sub(Self::LEN) / Observation::LEN
+    }
+
+    /// Number of observations a legacy account of `data_len` bytes keeps, see `is_legacy`
+    pub fn legacy_cardinality(data_len: usize) -> usize {
+        OBSERVATION_NUM + data_len.saturating_sub(Self::LEGACY_LEN) / Observation::LEGACY_LEN
+    }
+
+    /// Checks what the AccountLoader would, on the whole account data
+    fn check_account(account_info: &AccountInfo<'_>, data: &[u8]) -> Result<()> {
+        require_keys_eq!(
+            *account_info.owner,
+            crate::id(),
+            ErrorCode::AccountOwnedByWrongProgram
+        );
+        // Legacy accounts are never a whole number of observations past LEN, see `is_legacy`
+        require!(
+            !Self::is_legacy(data),
+            GammaError::ObservationStateNotMigrated
+        );
+        require!(
+            data.len() >= Self::LEN && (data.len() - Self::LEN) % Observation::LEN == 0,
+            ErrorCode::AccountDidNotDeserialize
+        );
+        require!(
+            data[..8] == Self::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        Ok(())
+    }
+
+    /// Whether the account data has the layout from before observations kept the log price.
+    /// Those accounts were `LEGACY_LEN` plus archived observations of `Observation::LEGACY_LEN`
+    /// bytes, which is 3 modulo 8, while current accounts are 4 modulo 8.
+    pub fn is_legacy(data: &[u8]) -> bool {
+        data.len() % 8 == Self::LEGACY_LEN % 8
+    }
+
+    /// Newest observation in the account data that isn't later than `block_timestamp`, read
+    /// straight from the bytes so it works on legacy accounts too. Their observations carry
+    /// no log price, it starts over from zero.
+    pub fn latest_observation(data: &[u8], block_timestamp: u64) -> Option<Observation> {
+        let observation_len = if Self::is_legacy(data) {
+            Observation::LEGACY_LEN
+        } else {
+            Observation::LEN
+        };
+        let start = 8 + 1 + 2 + 32;
+        let observations = data.get(start..start + OBSERVATION_NUM * observation_len)?;
+        observations
+            .chunks_exact(observation_len)
+            .map(|bytes| {
+                // The log price was added at the end
+                let mut observation = Observation::default();
+                bytemuck::bytes_of_mut(&mut observation)[..bytes.len()].copy_from_slice(bytes);
+                observation
+            })
+            .filter(|observation| {
+                observation.block_timestamp != 0 && observation.block_timestamp <= block_timestamp
+            })
+            .max_by_key(|observation| observation.block_timestamp)
+    }
+
+    /// Converts a legacy account to the current layout in place, keeping its observations and
+    /// its archive. `data` has to be grown to `space(cardinality)` first, `cardinality` being
+    /// the `legacy_cardinality` of the account before it grew. The log prices the legacy
+    /// observations lack are rebuilt from the average price between consecutive observations.
+    pub fn migrate_legacy(data: &mut [u8], cardinality: usize) -> Result<()> {
+        require_eq!(data.len(), Self::space(cardinality), GammaError::InvalidInput);
+        let start = 8 + 1 + 2 + 32;
+        let legacy_tail = start + OBSERVATION_NUM * Observation::LEGACY_LEN;
+        let archive_index_and_cardinality = data[legacy_tail..legacy_tail + 8].to_vec();
+
+        // Observations only move towards the end, so going from the last one to the first
+        // never overwrites one that is still to be moved
+        let legacy_positions = (0..OBSERVATION_NUM)
+            .map(|i| start + i * Observation::LEGACY_LEN)
+            .chain(
+                (0..cardinality - OBSERVATION_NUM)
+                    .map(|i| Self::LEGACY_LEN + i * Observation::LEGACY_LEN),
+            );
+        let positions = (0..OBSERVATION_NUM)
+            .map(|i| start + i * Observation::LEN)
+            .chain((0..cardinality - OBSERVATION_NUM).map(|i| Self::LEN + i * Observation::LEN));
+        let moves: Vec<(usize, usize)> = legacy_positions.zip(positions).collect();
+        for &(legacy_position, position) in moves.iter().rev() {
+            data.copy_within(
+                legacy_position..legacy_position + Observation::LEGACY_LEN,
+                position,
+            );
+            data[position + Observation::LEGACY_LEN..position + Observation::LEN].fill(0);
+        }
+        let tail = start + OBSERVATION_NUM * Observation::LEN;
+        data[tail..tail + 8].copy_from_slice(&archive_index_and_cardinality);
+        data[tail + 8] = OBSERVATION_STATE_VERSION;
+        data[tail + 9..Self::LEN].fill(0);
+
+        let (state, archive) = data[8..].split_at_mut(Self::LEN - 8);
+        let state: &mut ObservationState = bytemuck::from_bytes_mut(state);
+        let archive: &mut [Observation] = bytemuck::cast_slice_mut(archive);
+        let mut written: Vec<(u64, usize)> = state
+            .observations
+            .iter()
+            .chain(archive.iter())
+            .enumerate()
+            .filter(|(_, observation)| observation.block_timestamp != 0)
+            .map(|(i, observation)| (observation.block_timestamp, i))
+            .collect();
+        written.sort_unstable();
+
+        let mut previous: Option<Observation> = None;
+        for (_, i) in written {
+            let observation = if i < OBSERVATION_NUM {
+                &mut state.observations[i]
+            } else {
+                &mut archive[i - OBSERVATION_NUM]
+            };
+            if let Some(previous) = previous {
+                let delta_time = observation.block_timestamp - previous.block_timestamp;
+                let cumulative_token_0_price_x32 = observation.cumulative_token_0_price_x32;
+                let token_0_price_x32 = if delta_time == 0 {
+                    0
+                } else {
+                    cumulative_token_0_price_x32
+                        .wrapping_sub(previous.cumulative_token_0_price_x32)
+                        / u128::from(delta_time)
+                };
+                let delta_log_price_x32 = log2_x32(token_0_price_x32.max(1))
+                    .and_then(|log_price_x32| log_price_x32.checked_mul(delta_time.into()))
+                    .ok_or(GammaError::MathOverflow)?;
+                observation.cumulative_token_0_log_price_x32 = previous
+                    .cumulative_token_0_log_price_x32
+                    .wrapping_add(delta_log_price_x32);
+            }
+            previous = Some(*observation);
+        }
+        Ok(())
+    }
+
+    /// Loads the state mutably together with its archived observations. An AccountLoader only
+    /// maps the first `LEN` bytes, the archive grown by `increase_observation_cardinality`
+    /// sits after them.
+    pub fn load_mut_with_archive<'a>(
+        account_info: &'a AccountInfo<'_>,
+    ) -> Result<(RefMut<'a, ObservationState>, RefMut<'a, [Observation]>)> {
+        require!(account_info.is_writable, ErrorCode::AccountNotMutable);
+        let data = account_info.try_borrow_mut_data()?;
+        Self::check_account(account_info, &data)?;
+        Ok(RefMut::map_split(data, |data| {
+            let (state, archive) = data[8..].split_at_mut(Self::LEN - 8);
+            let archive_len = archive.len() / Observation::LEN * Observation::LEN;
+            (
+                bytemuck::from_bytes_mut(state),
+                bytemuck::cast_slice_mut(&mut archive[..archive_len]),
+            )
+        }))
+    }
+
+    /// Loads the state together with its archived observations
+    pub fn load_with_archive<'a>(
+        account_info: &'a AccountInfo<'_>,
+    ) -> Result<(Ref<'a, ObservationState>, Ref<'a, [Observation]>)> {
+        let data = account_info.try_borrow_data()?;
+        Self::check_account(account_info, &data)?;
+        Ok(Ref::map_split(data, |data| {
+            let (state, archive) = data[8..].split_at(Self::LEN - 8);
+            let archive_len = archive.len() / Observation::LEN * Observation::LEN;
+            (
+                bytemuck::from_bytes(state),
+                bytemuck::cast_slice(&archive[..archive_len]),
+            )
+        }))
+    }
 
     // Writes an oracle observation to the account, returning the next observation_index.
     /// Writable at most once per 15 seconds. Index represents the most recently written element.
@@ -74,6 +289,23 @@ impl ObservationState {
         block_timestamp: u64,
         token_0_price_x32: u128,
         token_1_price_x32: u128,
+    ) -> Result<()> {
+        self.update_with_archive(
+            &mut [],
+            block_timestamp,
+            token_0_price_x32,
+            token_1_price_x32,
+        )
+    }
+
+    /// Same as `update`, except the observation about to be overwritten moves to `archive`
+    /// instead of being lost, when the account was grown to keep more than OBSERVATION_NUM.
+    pub fn update_with_archive(
+        &mut self,
+        archive: &mut [Observation],
+        block_timestamp: u64,
+        token_0_price_x32: u128,
+        token_1_price_x32: u128,
     ) -> Result<()> {
         let observation_index = self.observation_index;
         if !self.initialized {
@@ -81,6 +313,7 @@ impl ObservationState {
             self.observations[observation_index as usize].block_timestamp = block_timestamp;
             self.observations[observation_index as usize].cumulative_token_0_price_x32 = 0;
             self.observations[observation_index as usize].cumulative_token_1_price_x32 = 0;
+            self.observations[observation_index as usize].cumulative_token_0_log_price_x32 = 0;
             Ok(())
         } else {
             let last_observation = self.observations[observation_index as usize];
@@ -94,11 +327,26 @@ impl ObservationState {
             let delta_token_1_price_x32 = token_1_price_x32
                 .checked_mul(delta_time.into())
                 .ok_or(GammaError::MathOverflow)?;
+            // A zero price would have no log, it counts as the smallest one
+            let delta_token_0_log_price_x32 = log2_x32(token_0_price_x32.max(1))
+                .and_then(|log_price_x32| log_price_x32.checked_mul(delta_time.into()))
+                .ok_or(GammaError::MathOverflow)?;
             let next_observation_index = if observation_index as usize == OBSERVATION_NUM - 1 {
                 0
             } else {
                 observation_index + 1
             };
+            let overwritten = self.observations[next_observation_index as usize];
+            if !archive.is_empty() && overwritten.block_timestamp != 0 {
+                // A grown archive is only used from its start, once the current one wrapped
+                if self.archive_cardinality == 0 || self.archive_index >= self.archive_cardinality {
+                    self.archive_index = 0;
+                    self.archive_cardinality =
+                        u32::try_from(archive.len()).map_err(|_| GammaError::MathOverflow)?;
+                }
+                archive[self.archive_index as usize] = overwritten;
+                self.archive_index += 1;
+            }
             self.observations[next_observation_index as usize].block_timestamp = block_timestamp;
             // cumulative_token_price_x32 only occupies the first 64 bits, and the remaining 64 bits are used to store overflow data
             self.observations[next_observation_index as usize].cumulative_token_0_price_x32 =
@@ -109,12 +357,256 @@ impl ObservationState {
                 last_observation
                     .cumulative_token_1_price_x32
                     .wrapping_add(delta_token_1_price_x32);
+            self.observations[next_observation_index as usize].cumulative_token_0_log_price_x32 =
+                last_observation
+                    .cumulative_token_0_log_price_x32
+                    .wrapping_add(delta_token_0_log_price_x32);
             self.observation_index = next_observation_index;
             Ok(())
         }
     }
 }
 
+/// Time weighted average prices over the observations inside a window
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Twap {
+    /// Average price of token_0 in token_1 raw amounts, Q32.32
+    pub token_0_price_x32: u128,
+    /// Average price of token_1 in token_0 raw amounts, Q32.32
+    pub token_1_price_x32: u128,
+    /// Oldest observation used, may be later than the start of the requested window
+    pub start_timestamp: u64,
+    /// Newest observation used
+    pub end_timestamp: u64,
+}
+
+impl Twap {
+    pub fn covered_seconds(&self) -> u64 {
+        self.end_timestamp.saturating_sub(self.start_timestamp)
+    }
+}
+
+impl ObservationState {
+    /// Computes the twap between the newest observation and the oldest one that is no older
+    /// than `window` seconds before `current_time`.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_time` - The current timestamp
+    /// * `window` - The number of seconds to look back
+    ///
+    pub fn twap(&self, current_time: u64, window: u64) -> Result<Twap> {
+        require!(self.initialized, GammaError::InvalidInput);
+        let newest = self.observations[self.observation_index as usize];
+        let window_start = current_time.saturating_sub(window);
+
+        let oldest = self
+            .observations
+            .iter()
+            .filter(|observation| {
+                observation.block_timestamp != 0
+                    && observation.block_timestamp >= window_start
+                    && observation.block_timestamp <= newest.block_timestamp
+            })
+            .min_by_key(|observation| observation.block_timestamp)
+            .copied()
+            .ok_or(GammaError::InvalidInput)?;
+
+        let time_delta = newest
+            .block_timestamp
+            .saturating_sub(oldest.block_timestamp) as u128;
+        // Need at least two observations to average over
+        require_gt!(time_delta, 0, GammaError::InvalidInput);
+
+        // The cumulative prices wrap around, see `update`
+        Ok(Twap {
+            token_0_price_x32: newest
+                .cumulative_token_0_price_x32
+                .wrapping_sub(oldest.cumulative_token_0_price_x32)
+                / time_delta,
+            token_1_price_x32: newest
+                .cumulative_token_1_price_x32
+                .wrapping_sub(oldest.cumulative_token_1_price_x32)
+                / time_delta,
+            start_timestamp: oldest.block_timestamp,
+            end_timestamp: newest.block_timestamp,
+        })
+    }
+
+    /// Cumulative prices at `target_time`, interpolated between the observations around it,
+    /// archived ones included. Past the newest observation they are extrapolated with the given
+    /// current prices, the way the next `update` would accumulate them.
+    pub fn cumulative_prices_at(
+        &self,
+        archive: &[Observation],
+        target_time: u64,
+        token_0_price_x32: u128,
+        token_1_price_x32: u128,
+    ) -> Result<(u128, u128)> {
+        let (before, after) = self.observations_around(archive, target_time)?;
+
+        let elapsed = u128::from(target_time - before.block_timestamp);
+        let accumulate = |cumulative_price_x32: u128, delta_price_x32: u128| -> Result<u128> {
+            Ok(cumulative_price_x32.wrapping_add(
+                delta_price_x32
+                    .checked_mul(elapsed)
+                    .ok_or(GammaError::MathOverflow)?,
+            ))
+        };
+        match after {
+            // The average price between the two observations applies over the whole span
+            Some(after) => {
+                let span = u128::from(after.block_timestamp - before.block_timestamp);
+                Ok((
+                    accumulate(
+                        before.cumulative_token_0_price_x32,
+                        after
+                            .cumulative_token_0_price_x32
+                            .wrapping_sub(before.cumulative_token_0_price_x32)
+                            / span,
+                    )?,
+                    accumulate(
+                        before.cumulative_token_1_price_x32,
+                        after
+                            .cumulative_token_1_price_x32
+                            .wrapping_sub(before.cumulative_token_1_price_x32)
+                            / span,
+                    )?,
+                ))
+            }
+            None => Ok((
+                accumulate(before.cumulative_token_0_price_x32, token_0_price_x32)?,
+                accumulate(before.cumulative_token_1_price_x32, token_1_price_x32)?,
+            )),
+        }
+    }
+
+    /// The newest observation at or before `target_time`, and the oldest one after it if any
+    fn observations_around(
+        &self,
+        archive: &[Observation],
+        target_time: u64,
+    ) -> Result<(Observation, Option<Observation>)> {
+        require!(self.initialized, GammaError::InvalidInput);
+        let written = || {
+            self.observations
+                .iter()
+                .chain(archive)
+                .filter(|observation| observation.block_timestamp != 0)
+        };
+        let before = written()
+            .filter(|observation| observation.block_timestamp <= target_time)
+            .max_by_key(|observation| observation.block_timestamp)
+            .copied()
+            .ok_or(GammaError::InvalidInput)?;
+        let after = written()
+            .filter(|observation| observation.block_timestamp > target_time)
+            .min_by_key(|observation| observation.block_timestamp)
+            .copied();
+        Ok((before, after))
+    }
+
+    /// Cumulative log price of token_0 at `target_time`, interpolated and extrapolated like
+    /// `cumulative_prices_at`
+    pub fn cumulative_log_price_at(
+        &self,
+        archive: &[Observation],
+        target_time: u64,
+        token_0_price_x32: u128,
+    ) -> Result<i128> {
+        let (before, after) = self.observations_around(archive, target_time)?;
+        let elapsed = i128::from(target_time - before.block_timestamp);
+        let delta_log_price_x32 = match after {
+            Some(after) => {
+                after
+                    .cumulative_token_0_log_price_x32
+                    .wrapping_sub(before.cumulative_token_0_log_price_x32)
+                    / i128::from(after.block_timestamp - before.block_timestamp)
+            }
+            None => log2_x32(token_0_price_x32.max(1)).ok_or(GammaError::MathOverflow)?,
+        };
+        Ok(before.cumulative_token_0_log_price_x32.wrapping_add(
+            delta_log_price_x32
+                .checked_mul(elapsed)
+                .ok_or(GammaError::MathOverflow)?,
+        ))
+    }
+
+    /// Computes the geometric mean prices over the `window` seconds before `current_time`,
+    /// interpolated like `interpolated_twap`. Price spikes weigh less than in the arithmetic
+    /// twap, and the prices of token_0 and token_1 are exact inverses of each other.
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - The archived observations, see `load_with_archive`
+    /// * `current_time` - The current timestamp
+    /// * `window` - The number of seconds to look back
+    /// * `token_0_price_x32` - The current price of token_0 in token_1
+    ///
+    pub fn geometric_twap(
+        &self,
+        archive: &[Observation],
+        current_time: u64,
+        window: u64,
+        token_0_price_x32: u128,
+    ) -> Result<Twap> {
+        require_gt!(window, 0, GammaError::InvalidInput);
+        let start_timestamp = current_time
+            .checked_sub(window)
+            .ok_or(GammaError::InvalidInput)?;
+        let start_log_x32 =
+            self.cumulative_log_price_at(archive, start_timestamp, token_0_price_x32)?;
+        let end_log_x32 = self.cumulative_log_price_at(archive, current_time, token_0_price_x32)?;
+        let mean_log_x32 = end_log_x32.wrapping_sub(start_log_x32) / i128::from(window);
+        Ok(Twap {
+            token_0_price_x32: exp2_x32(mean_log_x32).ok_or(GammaError::MathOverflow)?,
+            token_1_price_x32: exp2_x32(-mean_log_x32).ok_or(GammaError::MathOverflow)?,
+            start_timestamp,
+            end_timestamp: current_time,
+        })
+    }
+
+    /// Computes the twap over exactly the `window` seconds before `current_time`, interpolating
+    /// between observations at both ends. Unlike `twap`, the window can end after the newest
+    /// observation, the current prices fill the gap.
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - The archived observations, see `load_with_archive`
+    /// * `current_time` - The current timestamp
+    /// * `window` - The number of seconds to look back
+    /// * `token_0_price_x32` - The current price of token_0 in token_1
+    /// * `token_1_price_x32` - The current price of token_1 in token_0
+    ///
+    pub fn interpolated_twap(
+        &self,
+        archive: &[Observation],
+        current_time: u64,
+        window: u64,
+        token_0_price_x32: u128,
+        token_1_price_x32: u128,
+    ) -> Result<Twap> {
+        require_gt!(window, 0, GammaError::InvalidInput);
+        let start_timestamp = current_time
+            .checked_sub(window)
+            .ok_or(GammaError::InvalidInput)?;
+        let (start_token_0_x32, start_token_1_x32) = self.cumulative_prices_at(
+            archive,
+            start_timestamp,
+            token_0_price_x32,
+            token_1_price_x32,
+        )?;
+        let (end_token_0_x32, end_token_1_x32) =
+            self.cumulative_prices_at(archive, current_time, token_0_price_x32, token_1_price_x32)?;
+        Ok(Twap {
+            token_0_price_x32: end_token_0_x32.wrapping_sub(start_token_0_x32) / u128::from(window),
+            token_1_price_x32: end_token_1_x32.wrapping_sub(start_token_1_x32) / u128::from(window),
+            start_timestamp,
+            end_timestamp: current_time,
+        })
+    }
+}
+
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
 ///
 pub fn block_timestamp() -> Result<u64> {
@@ -0,0 +1,48 @@
+use crate::error::GammaError;
+use anchor_lang::prelude::*;
+
+pub const PAIR_INDEX_SEED: &str = "pair_index";
+pub const MAX_PAIR_INDEX_POOLS: usize = 32;
+
+/// Every pool of a mint pair across configs, so routers find all venues for the pair with a
+/// single fetch. Pools are added when they're created and removed when they're closed.
+#[account]
+#[derive(Default, Debug)]
+pub struct PairIndex {
+    pub bump: u8,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub pool_count: u8,
+    /// The first `pool_count` entries are the pools of the pair, in no particular order
+    pub pools: [Pubkey; MAX_PAIR_INDEX_POOLS],
+    pub padding: [u64; 4],
+}
+
+impl PairIndex {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 1 + 32 * MAX_PAIR_INDEX_POOLS + 8 * 4;
+
+    pub fn pools(&self) -> &[Pubkey] {
+        &self.pools[..usize::from(self.pool_count)]
+    }
+
+    pub fn add_pool(&mut self, pool_id: Pubkey) -> Result<()> {
+        require!(!self.pools().contains(&pool_id), GammaError::InvalidInput);
+        let count = usize::from(self.pool_count);
+        require_gt!(MAX_PAIR_INDEX_POOLS, count, GammaError::PairIndexFull);
+        self.pools[count] = pool_id;
+        self.pool_count += 1;
+        Ok(())
+    }
+
+    /// Returns whether the pool was listed
+    pub fn remove_pool(&mut self, pool_id: Pubkey) -> bool {
+        let Some(position) = self.pools().iter().position(|pool| *pool == pool_id) else {
+            return false;
+        };
+        let last = usize::from(self.pool_count) - 1;
+        self.pools[position] = self.pools[last];
+        self.pools[last] = Pubkey::default();
+        self.pool_count -= 1;
+        true
+    }
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+pub const PAUSE_EXPIRY_SEED: &str = "pause_expiry";
+
+/// An emergency pause of a pool that lapses unless the admin renews it, so a lost or
+/// compromised admin key can't freeze the pool for good
+#[account]
+#[derive(Default, Debug)]
+pub struct PauseExpiry {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    /// Status bits of the pool before the pause, restored once it expires
+    pub previous_status: u8,
+    /// Status bits the pause set, the pause is moot once the pool status changed from them
+    pub paused_status: u8,
+    /// When the pause lapses unless renewed
+    pub expires_at: u64,
+    pub active: bool,
+    pub padding: [u64; 4],
+}
+
+impl PauseExpiry {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 1 + 8 + 1 + 8 * 4;
+}
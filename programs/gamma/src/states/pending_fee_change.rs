@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+pub const PENDING_FEE_CHANGE_SEED: &str = "pending_fee_change";
+
+/// A config update proposed by the admin that only takes effect once the config's fee approver
+/// co-signs it, see `AmmConfig::fee_approver`
+#[account]
+#[derive(Default, Debug)]
+pub struct PendingFeeChange {
+    pub bump: u8,
+    pub amm_config: Pubkey,
+    /// `update_amm_config` param and value to apply
+    pub param: u16,
+    pub value: u64,
+    /// The new fund or protocol owner, or the new fee approver, for params taking an account
+    pub new_key: Pubkey,
+    pub proposed_at: u64,
+    pub padding: [u64; 4],
+}
+
+impl PendingFeeChange {
+    pub const LEN: usize = 8 + 1 + 32 + 2 + 8 + 32 + 8 + 8 * 4;
+}
@@ -1,6 +1,13 @@
+use crate::curve::{StableSwapCurve, TradeDirection, WeightedCurve, WEIGHT_DENOMINATOR};
 use crate::error::GammaError;
+use crate::fees::FeeModel;
+use crate::instructions::LOCKED_LP_AMOUNT;
+use crate::states::{
+    AmmConfig, LpConcentrationEvent, Observation, ObservationState, PoolLifecycleEvent,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
+use gamma_math::oracle::ema_x32;
 use std::ops::{BitAnd, BitOr, BitXor};
 
 // Seed to derive account address and signature
@@ -10,12 +17,57 @@ pub const POOL_VAULT_SEED: &str = "pool_vault";
 // This is for deriving the token account where kamino collateral is deposited
 pub const POOL_KAMINO_DEPOSITS_SEED: &str = "pool_kamino_deposits";
 
-pub const Q32: u128 = (u32::MAX as u128) + 1; // 2^32
+pub use gamma_math::Q32;
+
+/// Layout version of new pools. Bump it along with `PoolState::LEN` whenever fields are added,
+/// and give the fields their defaults in `PoolState::migrate` if zero isn't one.
+pub const POOL_STATE_VERSION: u8 = 9;
+
+/// Withdraw delay of new pools, in slots, see `PoolState::withdraw_delay_slots`
+pub const DEFAULT_WITHDRAW_DELAY_SLOTS: u64 = 2;
+/// Longest withdraw delay the admin can set, about a minute, so LPs are never locked in
+pub const MAX_WITHDRAW_DELAY_SLOTS: u64 = 150;
+
+/// Longest a launch fee can take to fall to the fee model's rate, see `launch_fee_rate_at`
+pub const MAX_LAUNCH_FEE_DURATION: u64 = 24 * 60 * 60;
+
+/// Once swaps or deposits are paused, withdrawals stay open for at least this long so users can exit
+pub const WITHDRAW_GRACE_PERIOD: u64 = 2 * 24 * 60 * 60;
+
+// `PoolState::extensions` bits, optional features of the pool
+// Swaps must be passed the pool's `PriceBand` and its oracle
+pub const PRICE_BAND_EXTENSION: u64 = 1 << 0;
+// LP fees are accrued to positions and claimed with `claim_lp_fees` instead of staying in the reserves
+pub const LP_FEE_ACCRUAL_EXTENSION: u64 = 1 << 1;
+// The reserves are the vaults' balances beyond the other amounts the pool holds, for mints whose
+// balances rebase
+pub const REBASING_SHARES_EXTENSION: u64 = 1 << 2;
+// The reserve of token_0 or token_1 counts towards the `MintExposure` of its mint, changing it
+// needs the exposure account
+pub const TOKEN_0_EXPOSURE_EXTENSION: u64 = 1 << 3;
+pub const TOKEN_1_EXPOSURE_EXTENSION: u64 = 1 << 4;
+// Swaps, deposits and withdrawals must be passed the pool's `QuoteCache` to keep it current
+pub const QUOTE_CACHE_EXTENSION: u64 = 1 << 5;
+// Deposits or swaps are limited to the addresses the pool creator whitelisted, see
+// `PoolWhitelistEntry`
+pub const DEPOSIT_WHITELIST_EXTENSION: u64 = 1 << 6;
+pub const SWAP_WHITELIST_EXTENSION: u64 = 1 << 7;
+// token_0 or token_1 is an interest-bearing mint, set at pool creation. Its UI amounts drift from
+// the raw amounts the pool trades, see `get_ui_adjusted_amount`
+pub const TOKEN_0_INTEREST_BEARING_EXTENSION: u64 = 1 << 8;
+pub const TOKEN_1_INTEREST_BEARING_EXTENSION: u64 = 1 << 9;
+// Swaps must be passed the pool's `PoolSwapHook` and its program, which they call after trading
+pub const SWAP_HOOK_EXTENSION: u64 = 1 << 10;
 
 pub enum PoolStatusBitIndex {
     Deposit,
     Withdraw,
     Swap,
+    ComposedSwap,
+    WindDown,
+    SwapZeroForOne,
+    SwapOneForZero,
+    WithdrawOnly,
 }
 
 #[derive(PartialEq, Eq)]
@@ -31,6 +83,43 @@ pub enum PartnerType {
     AssetDash = 0,
 }
 
+/// Stage of a pool, derived from its status bits, open time and liquidity.
+/// Created → Funded → Open → Paused → WindingDown → Closed, a paused pool can open again, and
+/// so can a withdraw-only one.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
+pub enum PoolLifecycle {
+    /// Holds no liquidity
+    #[default]
+    Created,
+    /// Holds liquidity, swaps open at `open_time`
+    Funded,
+    Open,
+    /// Swaps are disabled until the admin enables them again
+    Paused,
+    /// Deposits and swaps are disabled for good, LPs withdraw until the pool can be closed
+    WindingDown,
+    /// The pool account was closed, only ever seen in events
+    Closed,
+    /// Deposits and swaps are disabled and withdrawals are guaranteed until the admin exits the
+    /// mode with `set_withdraw_only`
+    WithdrawOnly,
+}
+
+/// Emits a `PoolLifecycleEvent` when an instruction moved the pool to another stage
+pub fn emit_lifecycle_change(pool_id: Pubkey, from: PoolLifecycle, to: PoolLifecycle) {
+    if from != to {
+        emit!(PoolLifecycleEvent {
+            version: PoolLifecycleEvent::VERSION,
+            pool_id,
+            from,
+            to,
+        });
+    }
+}
+
+/// `PartnerInfo::partner_id` of a slot without a partner, see `remove_partner`
+pub const PARTNER_SLOT_EMPTY: u64 = u64::MAX;
+
 impl PartnerType {
     pub fn new(value: u64) -> Self {
         match value {
@@ -55,6 +144,17 @@ pub struct PartnerInfo {
     pub cumulative_fee_total_times_tvl_share_token_1: u64,
 }
 
+impl PartnerInfo {
+    pub fn is_empty(&self) -> bool {
+        self.partner_id == PARTNER_SLOT_EMPTY
+    }
+
+    /// Whether LP linked with `partner` counts towards this slot
+    pub fn links(&self, partner: PartnerType) -> bool {
+        !self.is_empty() && PartnerType::new(self.partner_id) == partner
+    }
+}
+
 #[account(zero_copy(unsafe))]
 #[repr(packed)]
 #[derive(Default, Debug)]
@@ -71,7 +171,13 @@ pub struct PoolState {
     /// Pool tokens are issued when Token A or Token B are deposited
     /// Pool tokens can be withdrawn back to the original Token A or Token B
     // pub lp_mint: Pubkey,
-    pub _padding1: [u8; 32],
+    // The lp_mint bytes below are only read with `LP_FEE_ACCRUAL_EXTENSION`, which zeroes them
+    /// LP fees earned per lp token since the accrual was enabled, Q32.32, wrapping
+    pub lp_fee_growth_token_0_x32: u64,
+    pub lp_fee_growth_token_1_x32: u64,
+    /// LP fees accrued to positions that are still held in the vaults
+    pub lp_fees_token_0: u64,
+    pub lp_fees_token_1: u64,
     /// Mint info of Token A
     pub token_0_mint: Pubkey,
     /// Mint info of Token B
@@ -91,11 +197,18 @@ pub struct PoolState {
     /// Bit0: 1 - Disable Deposit(value will be 1), 0 - Deposit can be done(normal)
     /// Bit1: 1 - Disable Withdraw(value will be 2), 0 - Withdraw can be done(normal)
     /// Bit2: 1 - Disable Swap(value will be 4), 0 - Swap can be done(normal)
+    /// Bit3: 1 - Disable more than one swap per transaction(value will be 8), 0 - Swaps can be composed(normal)
+    /// Bit4: 1 - Winding down(value will be 16), deposits and swaps stay disabled for good, 0 - normal
+    /// Bit5: 1 - Disable token_0 to token_1 swaps(value will be 32), 0 - normal
+    /// Bit6: 1 - Disable token_1 to token_0 swaps(value will be 64), 0 - normal
+    /// Bit7: 1 - Withdraw only(value will be 128), deposits and swaps disabled and withdrawals can't be, 0 - normal
     pub status: u8,
 
     /// lp_mint decimals
     // pub lp_mint_decimals: u8,
-    pub _padding2: u8,
+    /// Layout version of the account, see `POOL_STATE_VERSION`. Zero for pools created before
+    /// the version was kept, in the byte lp_mint_decimals used to take.
+    pub version: u8,
     /// mint0 and mint1 decimals
     pub mint_0_decimals: u8,
     pub mint_1_decimals: u8,
@@ -150,12 +263,94 @@ pub struct PoolState {
     // To keep track of the profit we made from kamino, in terms of the token0 or token1.
     pub withdrawn_kamino_profit_token_0: u64,
     pub withdrawn_kamino_profit_token_1: u64,
-    /// padding
-    pub padding: [u64; 8],
+    // Kickback owed to segmenters that is still held in the vaults, see `SegmenterKickback`.
+    pub segmenter_kickbacks_token_0: u64,
+    pub segmenter_kickbacks_token_1: u64,
+    // Hard bounds on the token_0 price in token_1 (Q32.32), swaps can't push the price past them.
+    // Zero disables the bound.
+    pub price_floor_x32: u64,
+    pub price_ceiling_x32: u64,
+    // When swaps or deposits were last paused, zero while both are enabled.
+    // Withdrawals can't be paused for WITHDRAW_GRACE_PERIOD after that.
+    pub trading_paused_at: u64,
+    // Token badges found at pool creation, see `TOKEN_0_BADGED` and friends
+    pub token_badges: u64,
+    // Amplification coefficient of the stable swap curve, zero for a constant product pool
+    pub amplification: u64,
+    // Optional features the pool uses, see `PRICE_BAND_EXTENSION` and friends
+    pub extensions: u64,
+    // Base trade fee of the pool in place of the config's, set with `update_pool_fee`.
+    // Zero uses the config's rate.
+    pub trade_fee_rate_override: u64,
+    // Largest swap as a share of the reserve it trades against, in bps. Zero disables the limit.
+    pub max_trade_size_bps: u64,
+    // Seconds of twap the price is compared against by the twap breaker. Zero disables it.
+    pub twap_breaker_window: u64,
+    // Furthest the price can move from the twap, in bps of the twap
+    pub twap_breaker_max_deviation_bps: u64,
+    // Until when the admin lifted the twap breaker
+    pub twap_breaker_override_until: u64,
+    // Largest LP position seen by deposits and withdrawals, see `track_lp_concentration`
+    pub largest_lp_owner: Pubkey,
+    pub largest_lp_tokens: u64,
+    // Share of the LP supply a single position can hold before it's reported with a
+    // `LpConcentrationEvent`, in bps. Zero disables the alert.
+    pub lp_concentration_alert_bps: u64,
+    // Weight of token_0 in the weighted curve, in bps of `WEIGHT_DENOMINATOR`, token_1 weighs the
+    // rest. Zero for a pool that isn't weighted.
+    pub token_0_weight: u64,
+    // LP held as tokens of the pool's LP mint, see `tokenize_lp`. No UserPoolLiquidity or
+    // Position holds it, so its share of the LP fees stays in the reserves the tokens redeem for.
+    pub lp_tokenized: u64,
+    // Most LP supply deposits can mint, set with `update_max_lp_supply`. Zero disables the cap.
+    pub max_lp_supply: u64,
+    // Exponential moving averages of the prices swaps traded at, Q32.32, see `update_ema_price`
+    pub ema_token_0_price_x32: u128,
+    pub ema_token_1_price_x32: u128,
+    pub ema_updated_at: u64,
+    // Seconds after which a price weighs half as much in the averages. Zero disables them.
+    pub ema_half_life: u64,
+    // Kind and params of the `FeeModel` swaps use, see `fee_model`
+    pub fee_model: u8,
+    pub fee_model_params: [u64; 2],
+    // Model `update_fee_model` scheduled, it replaces the current one at
+    // `pending_fee_model_after`. Zero when none is pending.
+    pub pending_fee_model: u8,
+    pub pending_fee_model_params: [u64; 2],
+    pub pending_fee_model_after: u64,
+    // Slots a user has to wait after a deposit before withdrawing, so just-in-time liquidity
+    // can't wrap a large swap to take its fees from the passive LPs. Zero disables the guard.
+    pub withdraw_delay_slots: u64,
+    // Most the swaps of a slot can move the token_0 price away from where the slot started, in
+    // bps, see `check_slot_price_impact`. Zero disables the limit.
+    pub max_slot_price_impact_bps: u64,
+    pub slot_start_token_0_price_x32: u128,
+    pub slot_start: u64,
+    // Trade fee rate of swaps at `open_time`, falling to the fee model's rate over
+    // `launch_fee_duration` seconds, see `launch_fee_rate_at`. Zero disables the launch fee.
+    pub launch_fee_rate: u64,
+    pub launch_fee_duration: u64,
+    // Weight schedule of a liquidity bootstrapping pool, `token_0_weight` moves linearly from
+    // `token_0_start_weight` at `weight_schedule_start` to `token_0_end_weight` at
+    // `weight_schedule_end`, see `update_weights`. Zero end for pools with fixed weights.
+    pub token_0_start_weight: u64,
+    pub token_0_end_weight: u64,
+    pub weight_schedule_start: u64,
+    pub weight_schedule_end: u64,
+    // Protocol and fund fees out of the trade fees, split at swap time with the rates the swaps
+    // charged. They only count from version 8, fees of older swaps are in the trade fees alone.
+    pub cumulative_protocol_fees_token_0: u128,
+    pub cumulative_protocol_fees_token_1: u128,
+    pub cumulative_fund_fees_token_0: u128,
+    pub cumulative_fund_fees_token_1: u128,
+    // LP burned with `burn_lp_forever`. It stays in `lp_supply` with no owner, so it claims
+    // neither LP fees nor a share of an emergency escrow. Pools migrated from before version 9
+    // take it from their `LpBurnProof`.
+    pub burned_lp: u64,
 }
 
 impl PoolState {
-    pub const LEN: usize = 8 + 10 * 32 + 5 * 1 + 7 * 8 + 16 * 4 + 23 * 8;
+    pub const LEN: usize = 8 + 10 * 32 + 7 * 1 + 7 * 8 + 16 * 11 + 54 * 8;
 
     pub fn initialize(
         &mut self,
@@ -210,17 +405,159 @@ impl PoolState {
         self.max_shared_token1 = 0;
         self.token_0_amount_in_kamino = 0;
         self.token_1_amount_in_kamino = 0;
+        self.segmenter_kickbacks_token_0 = 0;
+        self.segmenter_kickbacks_token_1 = 0;
+        self.price_floor_x32 = 0;
+        self.price_ceiling_x32 = 0;
+        self.trading_paused_at = 0;
+        self.token_badges = 0;
+        self.amplification = 0;
+        self.extensions = 0;
+        self.trade_fee_rate_override = 0;
+        self.max_trade_size_bps = 0;
+        self.twap_breaker_window = 0;
+        self.twap_breaker_max_deviation_bps = 0;
+        self.twap_breaker_override_until = 0;
+        self.largest_lp_owner = Pubkey::default();
+        self.largest_lp_tokens = 0;
+        self.lp_concentration_alert_bps = 0;
+        self.token_0_weight = 0;
+        self.lp_tokenized = 0;
+        self.max_lp_supply = 0;
+        self.ema_token_0_price_x32 = 0;
+        self.ema_token_1_price_x32 = 0;
+        self.ema_updated_at = 0;
+        self.ema_half_life = 0;
+        self.fee_model = 0;
+        self.fee_model_params = [0; 2];
+        self.pending_fee_model = 0;
+        self.pending_fee_model_params = [0; 2];
+        self.pending_fee_model_after = 0;
+        self.withdraw_delay_slots = DEFAULT_WITHDRAW_DELAY_SLOTS;
+        self.max_slot_price_impact_bps = 0;
+        self.slot_start_token_0_price_x32 = 0;
+        self.slot_start = 0;
+        self.launch_fee_rate = 0;
+        self.launch_fee_duration = 0;
+        self.token_0_start_weight = 0;
+        self.token_0_end_weight = 0;
+        self.weight_schedule_start = 0;
+        self.weight_schedule_end = 0;
+        self.cumulative_protocol_fees_token_0 = 0;
+        self.cumulative_protocol_fees_token_1 = 0;
+        self.cumulative_fund_fees_token_0 = 0;
+        self.cumulative_fund_fees_token_1 = 0;
+        self.burned_lp = 0;
+        self.version = POOL_STATE_VERSION;
+        self.lp_fee_growth_token_0_x32 = 0;
+        self.lp_fee_growth_token_1_x32 = 0;
+        self.lp_fees_token_0 = 0;
+        self.lp_fees_token_1 = 0;
 
         self.partners = [PartnerInfo::default(); 1];
-
-        self.padding = [0u64; 8];
         Ok(())
     }
 
+    pub fn has_extension(&self, extension: u64) -> bool {
+        self.extensions & extension != 0
+    }
+
+    pub fn is_interest_bearing(&self, token_0: bool) -> bool {
+        self.has_extension(if token_0 {
+            TOKEN_0_INTEREST_BEARING_EXTENSION
+        } else {
+            TOKEN_1_INTEREST_BEARING_EXTENSION
+        })
+    }
+
+    pub fn set_extension(&mut self, extension: u64, enabled: bool) {
+        if enabled {
+            self.extensions |= extension;
+        } else {
+            self.extensions &= !extension;
+        }
+    }
+
+    /// Brings a pool of an older layout version up to date. The fields the account grew by are
+    /// zeroed by the realloc before this runs.
+    pub fn migrate(&mut self) {
+        // Every field but the ones added in version 4 is disabled when zero
+        if self.version < 4 {
+            self.withdraw_delay_slots = DEFAULT_WITHDRAW_DELAY_SLOTS;
+        }
+        self.version = POOL_STATE_VERSION;
+    }
+
     pub fn set_status(&mut self, status: u8) {
         self.status = status
     }
 
+    /// Whether withdrawals are still guaranteed to stay open after swaps or deposits were paused
+    pub fn in_withdraw_grace_period(&self, block_timestamp: u64) -> bool {
+        self.trading_paused_at != 0
+            && block_timestamp < self.trading_paused_at.saturating_add(WITHDRAW_GRACE_PERIOD)
+    }
+
+    /// Sets the status bits, tracking when trading got paused. Withdrawals can't be paused
+    /// together with, or shortly after, trading so users can always exit, nor at all in withdraw
+    /// only mode. Winding down can't be undone, and keeps deposits and swaps disabled.
+    pub fn update_status(&mut self, status: u8, block_timestamp: u64) -> Result<()> {
+        // Both directions are paused with the Swap bit so the pool shows as paused
+        let directions_disabled = (status >> PoolStatusBitIndex::SwapZeroForOne as u8) & 0b11;
+        require!(directions_disabled != 0b11, GammaError::InvalidInput);
+        let trading_was_enabled = self.trading_enabled();
+        let withdraw_was_enabled = self.get_status_by_bit(PoolStatusBitIndex::Withdraw);
+
+        let was_winding_down = !self.get_status_by_bit(PoolStatusBitIndex::WindDown);
+        self.set_status(status);
+        if !self.get_status_by_bit(PoolStatusBitIndex::WindDown) {
+            self.set_status_by_bit(PoolStatusBitIndex::Deposit, PoolStatusBitFlag::Disable);
+            self.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
+        } else if was_winding_down {
+            return err!(GammaError::PoolWindingDown);
+        }
+        if !self.get_status_by_bit(PoolStatusBitIndex::WithdrawOnly) {
+            require!(
+                self.get_status_by_bit(PoolStatusBitIndex::Withdraw),
+                GammaError::InvalidInput
+            );
+            self.set_status_by_bit(PoolStatusBitIndex::Deposit, PoolStatusBitFlag::Disable);
+            self.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
+        }
+        let trading_enabled = self.trading_enabled();
+        if !trading_enabled && trading_was_enabled {
+            self.trading_paused_at = block_timestamp;
+        } else if trading_enabled {
+            self.trading_paused_at = 0;
+        }
+
+        if withdraw_was_enabled
+            && !self.get_status_by_bit(PoolStatusBitIndex::Withdraw)
+            && self.in_withdraw_grace_period(block_timestamp)
+        {
+            return err!(GammaError::WithdrawGracePeriod);
+        }
+        Ok(())
+    }
+
+    /// The stage of the pool at `block_timestamp`, instructions gate on it rather than reading
+    /// the status bits and the open time on their own
+    pub fn lifecycle(&self, block_timestamp: u64) -> PoolLifecycle {
+        if !self.get_status_by_bit(PoolStatusBitIndex::WindDown) {
+            PoolLifecycle::WindingDown
+        } else if !self.get_status_by_bit(PoolStatusBitIndex::WithdrawOnly) {
+            PoolLifecycle::WithdrawOnly
+        } else if self.lp_supply == 0 {
+            PoolLifecycle::Created
+        } else if !self.get_status_by_bit(PoolStatusBitIndex::Swap) {
+            PoolLifecycle::Paused
+        } else if block_timestamp < self.open_time {
+            PoolLifecycle::Funded
+        } else {
+            PoolLifecycle::Open
+        }
+    }
+
     pub fn set_status_by_bit(&mut self, bit: PoolStatusBitIndex, flag: PoolStatusBitFlag) {
         let s = u8::from(1) << (bit as u8);
         if flag == PoolStatusBitFlag::Disable {
@@ -231,21 +568,661 @@ impl PoolState {
         }
     }
 
+    /// Whether swaps in `trade_direction` aren't paused on their own, the Swap bit pauses both
+    pub fn swap_direction_enabled(&self, trade_direction: TradeDirection) -> bool {
+        self.get_status_by_bit(match trade_direction {
+            TradeDirection::ZeroForOne => PoolStatusBitIndex::SwapZeroForOne,
+            TradeDirection::OneForZero => PoolStatusBitIndex::SwapOneForZero,
+        })
+    }
+
+    /// Pausing either swap direction counts as pausing trading, for the withdraw grace period
+    fn trading_enabled(&self) -> bool {
+        self.get_status_by_bit(PoolStatusBitIndex::Swap)
+            && self.get_status_by_bit(PoolStatusBitIndex::Deposit)
+            && self.get_status_by_bit(PoolStatusBitIndex::SwapZeroForOne)
+            && self.get_status_by_bit(PoolStatusBitIndex::SwapOneForZero)
+    }
+
     // Get status by bit, if it is 'normal'/enabled return true
     pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
         let status = u8::from(1) << (bit as u8);
         self.status.bitand(status) == 0
     }
 
+    /// Tokens of a vault the pool accounts for: the reserve and the fees and kickbacks it holds
+    pub fn vault_accounted_amount(&self, token_0: bool) -> Option<u64> {
+        if token_0 {
+            self.token_0_vault_amount
+                .checked_add(self.protocol_fees_token_0)?
+                .checked_add(self.fund_fees_token_0)?
+                .checked_add(self.segmenter_kickbacks_token_0)?
+                .checked_add(self.held_lp_fees(true))
+        } else {
+            self.token_1_vault_amount
+                .checked_add(self.protocol_fees_token_1)?
+                .checked_add(self.fund_fees_token_1)?
+                .checked_add(self.segmenter_kickbacks_token_1)?
+                .checked_add(self.held_lp_fees(false))
+        }
+    }
+
+    fn held_lp_fees(&self, token_0: bool) -> u64 {
+        match (self.has_extension(LP_FEE_ACCRUAL_EXTENSION), token_0) {
+            (false, _) => 0,
+            (true, true) => self.lp_fees_token_0,
+            (true, false) => self.lp_fees_token_1,
+        }
+    }
+
+    /// Starts accruing LP fees to positions, from zero. It can't be turned off again as the
+    /// checkpoints of the positions would no longer match the growth
+    pub fn enable_lp_fee_accrual(&mut self) {
+        self.lp_fee_growth_token_0_x32 = 0;
+        self.lp_fee_growth_token_1_x32 = 0;
+        self.lp_fees_token_0 = 0;
+        self.lp_fees_token_1 = 0;
+        self.set_extension(LP_FEE_ACCRUAL_EXTENSION, true);
+    }
+
+    /// LP in the supply that no liquidity or position holds, so it never claims LP fees: the LP
+    /// locked at pool creation and the burned LP
+    pub fn unclaimable_lp(&self) -> u64 {
+        LOCKED_LP_AMOUNT
+            .saturating_add(self.burned_lp)
+            .min(self.lp_supply)
+    }
+
+    /// With LP fee accrual, moves the LP part of a fee out of the reserves and into the fee
+    /// growth. What doesn't divide evenly over the lp supply stays in the reserves, and so does
+    /// the share of the tokenized LP, which earns through the reserves, and of the unclaimable LP.
+    pub fn accrue_lp_fee(&mut self, token_0: bool, lp_fee: u64) -> Result<()> {
+        if !self.has_extension(LP_FEE_ACCRUAL_EXTENSION) || self.lp_supply == 0 {
+            return Ok(());
+        }
+        let lp_supply = u128::from(self.lp_supply);
+        let growth = ((u128::from(lp_fee) << 32) / lp_supply).min(u128::from(u64::MAX));
+        // Not more than lp_fee as growth was rounded down
+        let accruing_lp = u128::from(
+            self.lp_supply
+                .saturating_sub(self.lp_tokenized)
+                .saturating_sub(self.unclaimable_lp()),
+        );
+        let accrued = ((growth * accruing_lp) >> 32) as u64;
+        let growth = growth as u64;
+        if token_0 {
+            self.lp_fee_growth_token_0_x32 = self.lp_fee_growth_token_0_x32.wrapping_add(growth);
+            self.lp_fees_token_0 = self
+                .lp_fees_token_0
+                .checked_add(accrued)
+                .ok_or(GammaError::MathOverflow)?;
+            self.token_0_vault_amount = self
+                .token_0_vault_amount
+                .checked_sub(accrued)
+                .ok_or(GammaError::MathOverflow)?;
+        } else {
+            self.lp_fee_growth_token_1_x32 = self.lp_fee_growth_token_1_x32.wrapping_add(growth);
+            self.lp_fees_token_1 = self
+                .lp_fees_token_1
+                .checked_add(accrued)
+                .ok_or(GammaError::MathOverflow)?;
+            self.token_1_vault_amount = self
+                .token_1_vault_amount
+                .checked_sub(accrued)
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// With `REBASING_SHARES_EXTENSION`, takes whatever the vault gained or lost since the last
+    /// update into the reserve, so the LPs keep their share of the vault across rebases
+    pub fn sync_rebasing_reserve(&mut self, token_0: bool, vault_balance: u64) -> Result<()> {
+        if !self.has_extension(REBASING_SHARES_EXTENSION) {
+            return Ok(());
+        }
+        let held_for_others = self
+            .vault_accounted_amount(token_0)
+            .ok_or(GammaError::MathOverflow)?
+            - self.reserve(token_0);
+        let in_kamino = if token_0 {
+            self.token_0_amount_in_kamino
+        } else {
+            self.token_1_amount_in_kamino
+        };
+        // A negative rebase is borne by the LPs alone, the fees owed to others are fixed amounts
+        let reserve = vault_balance
+            .checked_add(in_kamino)
+            .ok_or(GammaError::MathOverflow)?
+            .saturating_sub(held_for_others);
+        if token_0 {
+            self.token_0_vault_amount = reserve;
+        } else {
+            self.token_1_vault_amount = reserve;
+        }
+        Ok(())
+    }
+
+    /// Tokens in a vault the pool doesn't account for, e.g. transferred to the vault directly
+    pub fn vault_surplus(&self, token_0: bool, vault_balance: u64) -> Result<u64> {
+        let in_kamino = if token_0 {
+            self.token_0_amount_in_kamino
+        } else {
+            self.token_1_amount_in_kamino
+        };
+        let accounted_amount = self
+            .vault_accounted_amount(token_0)
+            .ok_or(GammaError::MathOverflow)?;
+        Ok(vault_balance
+            .checked_add(in_kamino)
+            .ok_or(GammaError::MathOverflow)?
+            .saturating_sub(accounted_amount))
+    }
+
+    fn reserve(&self, token_0: bool) -> u64 {
+        if token_0 {
+            self.token_0_vault_amount
+        } else {
+            self.token_1_vault_amount
+        }
+    }
+
     pub fn vault_amount_without_fee(&self) -> Result<(u64, u64)> {
         Ok((self.token_0_vault_amount, self.token_1_vault_amount))
     }
 
+    /// Rejects a price that moved past the floor or ceiling of the pool.
+    /// Moving back towards the allowed range is always possible.
+    pub fn check_price_bounds(&self, token_0_price_x32_before: u128) -> Result<()> {
+        if self.price_floor_x32 == 0 && self.price_ceiling_x32 == 0 {
+            return Ok(());
+        }
+        let (token_0_price_x32, _) = self.token_price_x32()?;
+        if self.price_floor_x32 != 0
+            && token_0_price_x32 < u128::from(self.price_floor_x32)
+            && token_0_price_x32 < token_0_price_x32_before
+        {
+            return err!(GammaError::PriceBoundExceeded);
+        }
+        if self.price_ceiling_x32 != 0
+            && token_0_price_x32 > u128::from(self.price_ceiling_x32)
+            && token_0_price_x32 > token_0_price_x32_before
+        {
+            return err!(GammaError::PriceBoundExceeded);
+        }
+        Ok(())
+    }
+
+    /// Holds the swaps of a slot to `max_slot_price_impact_bps` around the price before the slot's
+    /// first swap, so a sandwich or a flash loan can't move the price, and the observations the
+    /// oracle builds on, further within a single slot
+    pub fn check_slot_price_impact(
+        &mut self,
+        slot: u64,
+        token_0_price_x32_before: u128,
+    ) -> Result<()> {
+        if self.max_slot_price_impact_bps == 0 {
+            return Ok(());
+        }
+        if slot != self.slot_start {
+            self.slot_start = slot;
+            self.slot_start_token_0_price_x32 = token_0_price_x32_before;
+        }
+        let slot_start_price_x32 = self.slot_start_token_0_price_x32;
+        if slot_start_price_x32 == 0 {
+            return Ok(());
+        }
+        let (token_0_price_x32, _) = self.token_price_x32()?;
+        let impact_bps = token_0_price_x32
+            .abs_diff(slot_start_price_x32)
+            .checked_mul(10_000)
+            .ok_or(GammaError::MathOverflow)?
+            / slot_start_price_x32;
+        let max_slot_price_impact_bps = self.max_slot_price_impact_bps;
+        if impact_bps > u128::from(max_slot_price_impact_bps) {
+            msg!(
+                "price impact: {} bps in slot: {} exceeds max_slot_price_impact_bps: {}",
+                impact_bps,
+                slot,
+                max_slot_price_impact_bps
+            );
+            return err!(GammaError::SlotPriceImpactExceeded);
+        }
+        Ok(())
+    }
+
+    /// Fee rate of a swap while the launch fee lasts, `fee_rate` being the rate of the fee model.
+    /// It falls linearly from `launch_fee_rate` at `open_time` to `fee_rate` at the end of
+    /// `launch_fee_duration`, so snipers of a token launch's first blocks pay LPs for it.
+    pub fn launch_fee_rate_at(&self, block_timestamp: u64, fee_rate: u64) -> u64 {
+        let launch_end = self.open_time.saturating_add(self.launch_fee_duration);
+        if self.launch_fee_duration == 0
+            || self.launch_fee_rate <= fee_rate
+            || block_timestamp >= launch_end
+        {
+            return fee_rate;
+        }
+        let remaining = launch_end - block_timestamp.max(self.open_time);
+        let surcharge = u128::from(self.launch_fee_rate - fee_rate) * u128::from(remaining)
+            / u128::from(self.launch_fee_duration);
+        // The surcharge is below `launch_fee_rate`, it fits
+        fee_rate + surcharge as u64
+    }
+
+    /// Base trade fee the dynamic fee builds on, denominated in hundredths of bip (10^-6)
+    pub fn trade_fee_rate(&self, amm_config: &AmmConfig) -> u64 {
+        if self.trade_fee_rate_override != 0 {
+            self.trade_fee_rate_override
+        } else {
+            amm_config.trade_fee_rate
+        }
+    }
+
+    /// Bounds the price impact of a single swap, `amount` being swapped in or out of `reserve`
+    pub fn check_trade_size(&self, amount: u64, reserve: u64) -> Result<()> {
+        if self.max_trade_size_bps == 0 {
+            return Ok(());
+        }
+        let max_trade_size = u128::from(reserve) * u128::from(self.max_trade_size_bps) / 10_000;
+        require_gte!(
+            max_trade_size,
+            u128::from(amount),
+            GammaError::TradeSizeExceeded
+        );
+        Ok(())
+    }
+
+    /// The fee model swaps use at `block_timestamp`, the pending one once its delay passed
+    pub fn fee_model(&self, block_timestamp: u64) -> Result<FeeModel> {
+        if self.pending_fee_model_after != 0 && block_timestamp >= self.pending_fee_model_after {
+            FeeModel::from_parts(self.pending_fee_model, self.pending_fee_model_params)
+        } else {
+            FeeModel::from_parts(self.fee_model, self.fee_model_params)
+        }
+    }
+
+    /// Schedules `fee_model` to replace the current model at `apply_after`. A pending model
+    /// whose delay passed becomes the current one first, scheduling the current model cancels
+    /// the pending one.
+    pub fn schedule_fee_model(
+        &mut self,
+        fee_model: FeeModel,
+        block_timestamp: u64,
+        apply_after: u64,
+    ) -> Result<()> {
+        let current_fee_model = self.fee_model(block_timestamp)?;
+        let (kind, params) = current_fee_model.to_parts();
+        self.fee_model = kind;
+        self.fee_model_params = params;
+        self.pending_fee_model = 0;
+        self.pending_fee_model_params = [0; 2];
+        self.pending_fee_model_after = 0;
+        if fee_model != current_fee_model {
+            let (kind, params) = fee_model.to_parts();
+            self.pending_fee_model = kind;
+            self.pending_fee_model_params = params;
+            self.pending_fee_model_after = apply_after;
+        }
+        Ok(())
+    }
+
+    /// Moves the moving averages towards the prices before a swap, which held since the last
+    /// update. The first update after the averages were enabled starts them at those prices.
+    pub fn update_ema_price(
+        &mut self,
+        block_timestamp: u64,
+        token_0_price_x32: u128,
+        token_1_price_x32: u128,
+    ) -> Result<()> {
+        if self.ema_half_life == 0 {
+            return Ok(());
+        }
+        if self.ema_updated_at == 0 {
+            self.ema_token_0_price_x32 = token_0_price_x32;
+            self.ema_token_1_price_x32 = token_1_price_x32;
+        } else {
+            let elapsed = block_timestamp.saturating_sub(self.ema_updated_at);
+            self.ema_token_0_price_x32 = ema_x32(
+                self.ema_token_0_price_x32,
+                token_0_price_x32,
+                elapsed,
+                self.ema_half_life,
+            )
+            .ok_or(GammaError::MathOverflow)?;
+            self.ema_token_1_price_x32 = ema_x32(
+                self.ema_token_1_price_x32,
+                token_1_price_x32,
+                elapsed,
+                self.ema_half_life,
+            )
+            .ok_or(GammaError::MathOverflow)?;
+        }
+        self.ema_updated_at = self.ema_updated_at.max(block_timestamp);
+        Ok(())
+    }
+
+    /// Bounds the LP supply a deposit leaves the pool with
+    pub fn check_lp_supply_cap(&self, lp_supply: u64) -> Result<()> {
+        if self.max_lp_supply == 0 {
+            return Ok(());
+        }
+        require_gte!(
+            self.max_lp_supply,
+            lp_supply,
+            GammaError::LpSupplyCapExceeded
+        );
+        Ok(())
+    }
+
+    /// Rejects a price that moved further from the twap than the twap breaker allows, unless it
+    /// moved back towards the twap. Trades pushing the price further stay rejected until the twap
+    /// catches up or the admin lifts the breaker. Without observations covering the window, e.g.
+    /// for a young pool, nothing is checked.
+    pub fn check_twap_deviation(
+        &self,
+        observation_state: &ObservationState,
+        observation_archive: &[Observation],
+        block_timestamp: u64,
+        token_0_price_x32_before: u128,
+        token_1_price_x32_before: u128,
+    ) -> Result<()> {
+        if self.twap_breaker_window == 0 || block_timestamp < self.twap_breaker_override_until {
+            return Ok(());
+        }
+        // The observations stop at the last trade, the price before this one held since then
+        let Ok(twap) = observation_state.interpolated_twap(
+            observation_archive,
+            block_timestamp,
+            self.twap_breaker_window,
+            token_0_price_x32_before,
+            token_1_price_x32_before,
+        ) else {
+            return Ok(());
+        };
+        let deviation = twap
+            .token_0_price_x32
+            .checked_mul(u128::from(self.twap_breaker_max_deviation_bps))
+            .ok_or(GammaError::MathOverflow)?
+            / 10_000;
+        let lower = twap.token_0_price_x32.saturating_sub(deviation);
+        let upper = twap.token_0_price_x32.saturating_add(deviation);
+        let (token_0_price_x32, _) = self.token_price_x32()?;
+        if (token_0_price_x32 < lower && token_0_price_x32 < token_0_price_x32_before)
+            || (token_0_price_x32 > upper && token_0_price_x32 > token_0_price_x32_before)
+        {
+            return err!(GammaError::TwapDeviationExceeded);
+        }
+        Ok(())
+    }
+
+    /// Updates the largest LP position with the position of `owner` that just changed. Only the
+    /// positions deposits and withdrawals touch are seen, so the largest one is tracked lazily:
+    /// once it shrinks, a larger position only takes its place when it changes too.
+    pub fn track_lp_concentration(
+        &mut self,
+        pool_id: Pubkey,
+        owner: Pubkey,
+        lp_tokens_owned: u128,
+    ) -> Result<()> {
+        let lp_tokens = u64::try_from(lp_tokens_owned).or(err!(GammaError::MathOverflow))?;
+        if owner == self.largest_lp_owner || lp_tokens > self.largest_lp_tokens {
+            self.largest_lp_owner = owner;
+            self.largest_lp_tokens = lp_tokens;
+        }
+        if self.lp_concentration_alert_bps == 0 || self.lp_supply == 0 {
+            return Ok(());
+        }
+        let share_bps = u128::from(lp_tokens) * 10_000 / u128::from(self.lp_supply);
+        if share_bps > u128::from(self.lp_concentration_alert_bps) {
+            emit!(LpConcentrationEvent {
+                version: LpConcentrationEvent::VERSION,
+                pool_id,
+                owner,
+                lp_tokens,
+                lp_supply: self.lp_supply,
+                share_bps: u64::try_from(share_bps).or(err!(GammaError::MathOverflow))?,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.amplification != 0
+    }
+
+    pub fn is_weighted(&self) -> bool {
+        self.token_0_weight != 0
+    }
+
+    pub fn is_constant_product(&self) -> bool {
+        !self.is_stable() && !self.is_weighted()
+    }
+
+    /// Moves `token_0_weight` along the weight schedule to `block_timestamp`, has to run before
+    /// a liquidity bootstrapping pool is priced
+    pub fn update_weights(&mut self, block_timestamp: u64) {
+        if self.weight_schedule_end == 0 {
+            return;
+        }
+        let (start, end) = (self.weight_schedule_start, self.weight_schedule_end);
+        let elapsed = u128::from(block_timestamp.clamp(start, end) - start);
+        let duration = u128::from(end - start);
+        let (start_weight, end_weight) = (self.token_0_start_weight, self.token_0_end_weight);
+        // Both weights are below `WEIGHT_DENOMINATOR`, the steps fit
+        self.token_0_weight = if end_weight >= start_weight {
+            start_weight + (u128::from(end_weight - start_weight) * elapsed / duration) as u64
+        } else {
+            start_weight - (u128::from(start_weight - end_weight) * elapsed / duration) as u64
+        };
+    }
+
+    /// Weights of the source and destination token of a trade on the weighted curve
+    pub fn trade_weights(&self, trade_direction: TradeDirection) -> (u64, u64) {
+        let token_1_weight = WEIGHT_DENOMINATOR.saturating_sub(self.token_0_weight);
+        match trade_direction {
+            TradeDirection::ZeroForOne => (self.token_0_weight, token_1_weight),
+            TradeDirection::OneForZero => (token_1_weight, self.token_0_weight),
+        }
+    }
+
     pub fn token_price_x32(&self) -> Result<(u128, u128)> {
         let (token_0_amount, token_1_amount) = self.vault_amount_without_fee()?;
+        if self.is_stable() {
+            return StableSwapCurve::token_price_x32(
+                self.amplification,
+                u128::from(token_0_amount),
+                u128::from(token_1_amount),
+            );
+        }
+        if self.is_weighted() {
+            return WeightedCurve::token_price_x32(
+                u128::from(token_0_amount),
+                u128::from(token_1_amount),
+                self.token_0_weight,
+            );
+        }
         Ok((
             token_1_amount as u128 * Q32 as u128 / token_0_amount as u128,
             token_0_amount as u128 * Q32 as u128 / token_1_amount as u128,
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rebase_wiping_out_the_reserve_leaves_owed_fees_whole() {
+        let mut pool_state = PoolState::default();
+        pool_state.set_extension(REBASING_SHARES_EXTENSION, true);
+        pool_state.token_0_vault_amount = 1_000;
+        pool_state.protocol_fees_token_0 = 50;
+
+        // Positive rebases go to the LPs
+        pool_state.sync_rebasing_reserve(true, 1_100).unwrap();
+        assert_eq!({ pool_state.token_0_vault_amount }, 1_050);
+        assert_eq!({ pool_state.protocol_fees_token_0 }, 50);
+
+        // A vault shrinking below what is owed to others must not underflow
+        pool_state.sync_rebasing_reserve(true, 20).unwrap();
+        assert_eq!({ pool_state.token_0_vault_amount }, 0);
+        assert_eq!({ pool_state.protocol_fees_token_0 }, 50);
+    }
+
+    #[test]
+    fn vault_surplus_leaves_owed_and_lent_out_tokens_alone() {
+        let mut pool_state = PoolState::default();
+        pool_state.token_0_vault_amount = 1_000;
+        pool_state.protocol_fees_token_0 = 50;
+        pool_state.token_0_amount_in_kamino = 400;
+
+        // The vault holds the reserve not deposited in kamino and the fees, plus an airdrop
+        assert_eq!(pool_state.vault_surplus(true, 650 + 7).unwrap(), 7);
+        // A vault short of what the pool accounts for has no surplus
+        assert_eq!(pool_state.vault_surplus(true, 600).unwrap(), 0);
+    }
+
+    #[test]
+    fn winding_down_pool_never_reopens() {
+        let mut pool_state = PoolState::default();
+        pool_state.lp_supply = 1_000;
+        pool_state.open_time = 100;
+        assert_eq!(pool_state.lifecycle(99), PoolLifecycle::Funded);
+        assert_eq!(pool_state.lifecycle(100), PoolLifecycle::Open);
+
+        // Winding down disables deposits and swaps along with it
+        pool_state.update_status(1 << 4, 100).unwrap();
+        assert_eq!(pool_state.lifecycle(100), PoolLifecycle::WindingDown);
+        assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit));
+        assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw));
+
+        assert!(pool_state.update_status(0, 200).is_err());
+    }
+
+    #[test]
+    fn withdraw_only_pool_keeps_withdrawals_open() {
+        let mut pool_state = PoolState::default();
+        pool_state.lp_supply = 1_000;
+
+        // Withdraw only mode can't be entered with withdrawals disabled
+        assert!(pool_state.update_status(1 << 7 | 1 << 1, 100).is_err());
+
+        pool_state.update_status(1 << 7, 100).unwrap();
+        assert_eq!(pool_state.lifecycle(100), PoolLifecycle::WithdrawOnly);
+        assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit));
+        assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw));
+
+        pool_state.update_status(0, 200).unwrap();
+        assert_eq!(pool_state.lifecycle(200), PoolLifecycle::Open);
+    }
+
+    #[test]
+    fn directional_pause_leaves_other_direction_and_exits_open() {
+        let mut pool_state = PoolState::default();
+
+        // Pausing both directions goes through the Swap bit
+        assert!(pool_state.update_status(0b0110_0000, 100).is_err());
+
+        pool_state.update_status(1 << 5, 100).unwrap();
+        assert!(!pool_state.swap_direction_enabled(TradeDirection::ZeroForOne));
+        assert!(pool_state.swap_direction_enabled(TradeDirection::OneForZero));
+        assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw));
+        assert_eq!({ pool_state.trading_paused_at }, 100);
+
+        pool_state.update_status(1 << 6, 200).unwrap();
+        assert!(pool_state.swap_direction_enabled(TradeDirection::ZeroForOne));
+        assert!(!pool_state.swap_direction_enabled(TradeDirection::OneForZero));
+    }
+
+    #[test]
+    fn lp_supply_cap_bounds_deposits_only_when_set() {
+        let mut pool_state = PoolState::default();
+        pool_state.check_lp_supply_cap(u64::MAX).unwrap();
+
+        pool_state.max_lp_supply = 1_000;
+        pool_state.check_lp_supply_cap(1_000).unwrap();
+        assert!(pool_state.check_lp_supply_cap(1_001).is_err());
+    }
+
+    #[test]
+    fn slot_price_impact_limit_resets_with_each_slot() {
+        let mut pool_state = PoolState::default();
+        pool_state.token_0_vault_amount = 1_000;
+        pool_state.token_1_vault_amount = 1_020;
+        pool_state.check_slot_price_impact(5, Q32).unwrap();
+
+        pool_state.max_slot_price_impact_bps = 100;
+        pool_state.token_1_vault_amount = 1_005;
+        pool_state.check_slot_price_impact(5, Q32).unwrap();
+        // A second swap of the slot is held to the price the slot started at
+        pool_state.token_1_vault_amount = 1_020;
+        let price_x32 = pool_state.token_price_x32().unwrap().0;
+        assert!(pool_state.check_slot_price_impact(5, price_x32).is_err());
+        pool_state.check_slot_price_impact(6, price_x32).unwrap();
+    }
+
+    #[test]
+    fn launch_fee_falls_to_the_fee_model_rate_over_its_duration() {
+        let mut pool_state = PoolState::default();
+        pool_state.open_time = 1_000;
+        assert_eq!(pool_state.launch_fee_rate_at(1_000, 2_500), 2_500);
+
+        pool_state.launch_fee_rate = 502_500;
+        pool_state.launch_fee_duration = 100;
+        assert_eq!(pool_state.launch_fee_rate_at(1_000, 2_500), 502_500);
+        assert_eq!(pool_state.launch_fee_rate_at(1_050, 2_500), 252_500);
+        assert_eq!(pool_state.launch_fee_rate_at(1_100, 2_500), 2_500);
+        // A fee model charging more than the launch fee keeps its rate
+        assert_eq!(pool_state.launch_fee_rate_at(1_000, 600_000), 600_000);
+    }
+
+    #[test]
+    fn bootstrapping_weights_move_linearly_and_stop_at_the_end_weight() {
+        let mut pool_state = PoolState::default();
+        pool_state.token_0_weight = 9_500;
+        pool_state.update_weights(u64::MAX);
+        assert_eq!({ pool_state.token_0_weight }, 9_500);
+
+        pool_state.token_0_start_weight = 9_500;
+        pool_state.token_0_end_weight = 5_000;
+        pool_state.weight_schedule_start = 1_000;
+        pool_state.weight_schedule_end = 2_000;
+        pool_state.update_weights(500);
+        assert_eq!({ pool_state.token_0_weight }, 9_500);
+        pool_state.update_weights(1_500);
+        assert_eq!({ pool_state.token_0_weight }, 7_250);
+        pool_state.update_weights(5_000);
+        assert_eq!({ pool_state.token_0_weight }, 5_000);
+    }
+
+    #[test]
+    fn fee_model_switch_waits_out_its_delay_and_can_be_cancelled() {
+        let mut pool_state = PoolState::default();
+        assert_eq!(pool_state.fee_model(0).unwrap(), FeeModel::VolatilityBased);
+
+        pool_state
+            .schedule_fee_model(FeeModel::Constant, 100, 200)
+            .unwrap();
+        assert_eq!(
+            pool_state.fee_model(199).unwrap(),
+            FeeModel::VolatilityBased
+        );
+        assert_eq!(pool_state.fee_model(200).unwrap(), FeeModel::Constant);
+
+        // Scheduling after the delay passed settles the pending model first
+        let utilization_based = FeeModel::UtilizationBased {
+            utilization_fee_rate: 10_000,
+        };
+        pool_state
+            .schedule_fee_model(utilization_based, 300, 400)
+            .unwrap();
+        assert_eq!(pool_state.fee_model(300).unwrap(), FeeModel::Constant);
+        pool_state
+            .schedule_fee_model(FeeModel::Constant, 350, 450)
+            .unwrap();
+        assert_eq!(pool_state.fee_model(u64::MAX).unwrap(), FeeModel::Constant);
+    }
+}
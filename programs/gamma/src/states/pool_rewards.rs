@@ -0,0 +1,127 @@
+use crate::error::GammaError;
+use anchor_lang::prelude::*;
+
+pub const POOL_REWARDS_SEED: &str = "pool_rewards";
+pub const REWARD_VAULT_SEED: &str = "reward_vault";
+pub const USER_STAKE_SEED: &str = "user_stake";
+pub const MAX_REWARD_MINTS: usize = 3;
+const Q64: u128 = (u64::MAX as u128) + 1; // 2^64
+
+/// Emissions of one reward mint to the LP staked in a pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct RewardInfo {
+    pub reward_mint: Pubkey,
+    /// Token account holding the rewards, owned by the pool authority
+    pub reward_vault: Pubkey,
+    /// Rewards emitted per second, shared by all staked LP
+    pub emissions_per_second: u64,
+    /// Rewards emitted per staked lp token since the reward was added, Q64.64, wrapping
+    pub reward_growth_x64: u128,
+}
+
+/// Rewards of the LP staked in a pool. Staked LP stays in the position and keeps earning fees,
+/// it just can't be withdrawn before it's unstaked.
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolRewards {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    /// LP tokens staked across all positions of the pool
+    pub total_staked: u64,
+    /// When `reward_growth_x64` of the rewards was last brought up to date
+    pub last_update_time: u64,
+    pub reward_count: u8,
+    /// The first `reward_count` entries are the pool's rewards
+    pub rewards: [RewardInfo; MAX_REWARD_MINTS],
+    pub padding: [u64; 4],
+}
+
+impl PoolRewards {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 2 + 1 + (32 * 2 + 8 + 16) * MAX_REWARD_MINTS + 8 * 4;
+
+    pub fn rewards(&self) -> &[RewardInfo] {
+        &self.rewards[..usize::from(self.reward_count)]
+    }
+
+    /// Emits the rewards of the time passed since the last update to the staked LP. Nothing is
+    /// emitted while no LP is staked.
+    pub fn update(&mut self, block_timestamp: u64) -> Result<()> {
+        let elapsed = block_timestamp.saturating_sub(self.last_update_time);
+        self.last_update_time = block_timestamp;
+        if elapsed == 0 || self.total_staked == 0 {
+            return Ok(());
+        }
+        let total_staked = u128::from(self.total_staked);
+        for reward in self.rewards[..usize::from(self.reward_count)].iter_mut() {
+            let growth = u128::from(reward.emissions_per_second)
+                .checked_mul(u128::from(elapsed))
+                .and_then(|emitted| emitted.checked_mul(Q64))
+                .ok_or(GammaError::MathOverflow)?
+                / total_staked;
+            reward.reward_growth_x64 = reward.reward_growth_x64.wrapping_add(growth);
+        }
+        Ok(())
+    }
+
+    pub fn add_reward(
+        &mut self,
+        reward_mint: Pubkey,
+        reward_vault: Pubkey,
+        emissions_per_second: u64,
+    ) -> Result<()> {
+        require!(
+            !self
+                .rewards()
+                .iter()
+                .any(|reward| reward.reward_mint == reward_mint),
+            GammaError::InvalidInput
+        );
+        let count = usize::from(self.reward_count);
+        require_gt!(MAX_REWARD_MINTS, count, GammaError::TooManyRewardMints);
+        self.rewards[count] = RewardInfo {
+            reward_mint,
+            reward_vault,
+            emissions_per_second,
+            reward_growth_x64: 0,
+        };
+        self.reward_count += 1;
+        Ok(())
+    }
+}
+
+/// Rewards of a position's staked LP, `UserPoolLiquidity::lp_tokens_staked`
+#[account]
+#[derive(Default, Debug)]
+pub struct UserStake {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    pub owner: Pubkey,
+    /// `RewardInfo::reward_growth_x64` the stake last accrued at, by reward index
+    pub reward_growth_checkpoints_x64: [u128; MAX_REWARD_MINTS],
+    /// Rewards accrued to the stake and not claimed yet, by reward index
+    pub rewards_owed: [u64; MAX_REWARD_MINTS],
+    pub padding: [u64; 4],
+}
+
+impl UserStake {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + (16 + 8) * MAX_REWARD_MINTS + 8 * 4;
+
+    /// Accrues the rewards `staked` lp tokens earned since the last checkpoint, has to run after
+    /// `PoolRewards::update` and before every change of the stake
+    pub fn accrue(&mut self, pool_rewards: &PoolRewards, staked: u64) -> Result<()> {
+        for (index, reward) in pool_rewards.rewards().iter().enumerate() {
+            let growth = reward
+                .reward_growth_x64
+                .wrapping_sub(self.reward_growth_checkpoints_x64[index]);
+            let earned = u128::from(staked)
+                .checked_mul(growth)
+                .and_then(|earned| u64::try_from(earned >> 64).ok())
+                .ok_or(GammaError::MathOverflow)?;
+            self.rewards_owed[index] = self.rewards_owed[index]
+                .checked_add(earned)
+                .ok_or(GammaError::MathOverflow)?;
+            self.reward_growth_checkpoints_x64[index] = reward.reward_growth_x64;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+pub const POOL_WHITELIST_SEED: &str = "pool_whitelist";
+
+/// An address the pool creator approved to deposit into or swap in their pool, for pools gated
+/// with `DEPOSIT_WHITELIST_EXTENSION` or `SWAP_WHITELIST_EXTENSION`
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolWhitelistEntry {
+    pub bump: u8,
+    pub pool_id: Pubkey,
+    pub address: Pubkey,
+    pub added_at: u64,
+    pub padding: [u64; 4],
+}
+
+impl PoolWhitelistEntry {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 + 8 * 4;
+}
@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+
+use super::{lp_fees_earned, PoolState, LP_FEE_ACCRUAL_EXTENSION};
+use crate::error::GammaError;
+use crate::fees::ceil_div;
+
+pub const POSITION_SEED: &str = "position";
+
+/// LP moved out of a `UserPoolLiquidity` into an account of its own. Unlike the LP of a
+/// `UserPoolLiquidity`, which is tied to its user by the seeds, a position changes hands with
+/// `transfer_position` and keeps its terms and fee checkpoints when it does.
+#[account]
+#[derive(Default, Debug)]
+pub struct Position {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    /// Who can transfer and close the position
+    pub owner: Pubkey,
+    /// Who opened the position, part of the seeds with `position_id`
+    pub creator: Pubkey,
+    pub position_id: u64,
+    pub lp_tokens: u64,
+    pub open_time: u64,
+    /// The position can't be closed before, 0 if it never was locked
+    pub unlock_time: u64,
+    /// Pool LP fee growth the position last accrued at, see `LP_FEE_ACCRUAL_EXTENSION`
+    pub lp_fee_growth_checkpoint_token_0_x32: u64,
+    pub lp_fee_growth_checkpoint_token_1_x32: u64,
+    /// LP fees accrued to the position, they move to the owner's `UserPoolLiquidity` on close
+    pub lp_fees_owed_token_0: u64,
+    pub lp_fees_owed_token_1: u64,
+    /// Timestamp the LP of a `lock_lp` position starts vesting at, linearly until `unlock_time`.
+    /// 0 keeps all of it locked until `unlock_time`.
+    pub vesting_start: u64,
+    /// LP the vesting schedule applies to, `unlock_lp` releases it as it vests
+    pub vesting_lp_tokens: u64,
+    /// Last deposit slot of the liquidity the LP came from, the withdraw delay carries over to
+    /// the liquidity it moves back into
+    pub last_deposit_slot: u64,
+    pub padding: [u64; 1],
+}
+
+impl Position {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 8 * 8 + 8 * 4;
+
+    /// Reads a position of `pool_state` passed in the remaining accounts
+    pub fn load_checked(position_info: &AccountInfo<'_>, pool_state: Pubkey) -> Result<Position> {
+        require_keys_eq!(*position_info.owner, crate::id());
+        let position = Position::try_deserialize(&mut &position_info.data.borrow()[..])?;
+        require_keys_eq!(position.pool_state, pool_state);
+        Ok(position)
+    }
+
+    pub fn is_locked(&self, block_timestamp: u64) -> bool {
+        block_timestamp < self.unlock_time
+    }
+
+    /// LP of the position that hasn't vested at `block_timestamp`, rounded up
+    pub fn locked_lp_tokens(&self, block_timestamp: u64) -> u64 {
+        if !self.is_locked(block_timestamp) {
+            return 0;
+        }
+        if self.vesting_start == 0 {
+            return self.lp_tokens;
+        }
+        let unvested = if block_timestamp <= self.vesting_start {
+            self.vesting_lp_tokens
+        } else {
+            let remaining = u128::from(self.unlock_time - block_timestamp);
+            let duration = u128::from(self.unlock_time - self.vesting_start);
+            ceil_div(u128::from(self.vesting_lp_tokens), remaining, duration)
+                .map_or(u64::MAX, |unvested| {
+                    u64::try_from(unvested).unwrap_or(u64::MAX)
+                })
+        };
+        unvested.min(self.lp_tokens)
+    }
+
+    /// Moves `lp_tokens` of the position into `to`, a new position of the same owner, with their
+    /// share of the vesting LP and of the LP fees owed. `to` takes the lock and fee checkpoints of
+    /// the position, which has to be accrued.
+    pub fn split_into(&mut self, to: &mut Position, lp_tokens: u64) -> Result<()> {
+        require!(
+            lp_tokens > 0 && lp_tokens < self.lp_tokens,
+            GammaError::InvalidInput
+        );
+        let share = |amount: u64| -> Result<u64> {
+            let share = u128::from(amount)
+                .checked_mul(u128::from(lp_tokens))
+                .ok_or(GammaError::MathOverflow)?
+                / u128::from(self.lp_tokens);
+            // A share of a u64 fits in a u64
+            Ok(share as u64)
+        };
+        let vesting_lp_tokens = share(self.vesting_lp_tokens)?;
+        let lp_fees_owed_token_0 = share(self.lp_fees_owed_token_0)?;
+        let lp_fees_owed_token_1 = share(self.lp_fees_owed_token_1)?;
+
+        to.pool_state = self.pool_state;
+        to.owner = self.owner;
+        to.open_time = self.open_time;
+        to.unlock_time = self.unlock_time;
+        to.vesting_start = self.vesting_start;
+        to.vesting_lp_tokens = vesting_lp_tokens;
+        to.last_deposit_slot = self.last_deposit_slot;
+        to.lp_fee_growth_checkpoint_token_0_x32 = self.lp_fee_growth_checkpoint_token_0_x32;
+        to.lp_fee_growth_checkpoint_token_1_x32 = self.lp_fee_growth_checkpoint_token_1_x32;
+        to.lp_fees_owed_token_0 = lp_fees_owed_token_0;
+        to.lp_fees_owed_token_1 = lp_fees_owed_token_1;
+        to.lp_tokens = lp_tokens;
+
+        self.lp_tokens -= lp_tokens;
+        self.vesting_lp_tokens -= vesting_lp_tokens;
+        self.lp_fees_owed_token_0 -= lp_fees_owed_token_0;
+        self.lp_fees_owed_token_1 -= lp_fees_owed_token_1;
+        Ok(())
+    }
+
+    /// Moves the LP and LP fees owed of `from` into the position, both accrued. The merged
+    /// position unlocks with the later of the two, vesting positions only merge with positions
+    /// on the same schedule.
+    pub fn merge(&mut self, from: &Position) -> Result<()> {
+        if self.vesting_start != 0 || from.vesting_start != 0 {
+            require!(
+                self.vesting_start == from.vesting_start && self.unlock_time == from.unlock_time,
+                GammaError::InvalidInput
+            );
+        }
+        self.unlock_time = self.unlock_time.max(from.unlock_time);
+        self.last_deposit_slot = self.last_deposit_slot.max(from.last_deposit_slot);
+        self.lp_tokens = self
+            .lp_tokens
+            .checked_add(from.lp_tokens)
+            .ok_or(GammaError::MathOverflow)?;
+        self.vesting_lp_tokens = self
+            .vesting_lp_tokens
+            .checked_add(from.vesting_lp_tokens)
+            .ok_or(GammaError::MathOverflow)?;
+        self.lp_fees_owed_token_0 = self
+            .lp_fees_owed_token_0
+            .checked_add(from.lp_fees_owed_token_0)
+            .ok_or(GammaError::MathOverflow)?;
+        self.lp_fees_owed_token_1 = self
+            .lp_fees_owed_token_1
+            .checked_add(from.lp_fees_owed_token_1)
+            .ok_or(GammaError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Accrues the LP fees earned since the last checkpoint, like
+    /// `UserPoolLiquidity::accrue_lp_fees`
+    pub fn accrue_lp_fees(&mut self, pool_state: &PoolState) -> Result<()> {
+        if !pool_state.has_extension(LP_FEE_ACCRUAL_EXTENSION) {
+            return Ok(());
+        }
+        let growth_0 = pool_state.lp_fee_growth_token_0_x32;
+        let growth_1 = pool_state.lp_fee_growth_token_1_x32;
+        let lp_tokens = u128::from(self.lp_tokens);
+        let earned_0 = lp_fees_earned(
+            lp_tokens,
+            growth_0,
+            self.lp_fee_growth_checkpoint_token_0_x32,
+        )?;
+        let earned_1 = lp_fees_earned(
+            lp_tokens,
+            growth_1,
+            self.lp_fee_growth_checkpoint_token_1_x32,
+        )?;
+        self.lp_fees_owed_token_0 = self
+            .lp_fees_owed_token_0
+            .checked_add(earned_0)
+            .ok_or(GammaError::MathOverflow)?;
+        self.lp_fees_owed_token_1 = self
+            .lp_fees_owed_token_1
+            .checked_add(earned_1)
+            .ok_or(GammaError::MathOverflow)?;
+        self.lp_fee_growth_checkpoint_token_0_x32 = growth_0;
+        self.lp_fee_growth_checkpoint_token_1_x32 = growth_1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lp_lock_vests_linearly_and_never_releases_more_than_it_holds() {
+        let mut position = Position {
+            lp_tokens: 1_000,
+            vesting_lp_tokens: 1_000,
+            vesting_start: 100,
+            unlock_time: 200,
+            ..Default::default()
+        };
+        assert_eq!(position.locked_lp_tokens(50), 1_000);
+        assert_eq!(position.locked_lp_tokens(150), 500);
+        assert_eq!(position.locked_lp_tokens(200), 0);
+
+        // Released LP leaves the position, what is left stays locked as scheduled
+        position.lp_tokens = 500;
+        assert_eq!(position.locked_lp_tokens(150), 500);
+        assert_eq!(position.locked_lp_tokens(175), 250);
+
+        // Without a vesting start everything unlocks at once
+        position.vesting_start = 0;
+        assert_eq!(position.locked_lp_tokens(199), 500);
+        assert_eq!(position.locked_lp_tokens(200), 0);
+
+        // The position of a graduated bonding curve never unlocks, nor does a lock vesting until
+        // then release more than its share
+        position.unlock_time = u64::MAX;
+        assert_eq!(position.locked_lp_tokens(u64::MAX - 1), 500);
+        position.vesting_start = u64::MAX - 1_000;
+        position.vesting_lp_tokens = 500;
+        assert_eq!(position.locked_lp_tokens(u64::MAX - 500), 250);
+    }
+
+    #[test]
+    fn split_positions_merge_back_into_the_original() {
+        let original = Position {
+            lp_tokens: 1_000,
+            vesting_lp_tokens: 1_000,
+            vesting_start: 100,
+            unlock_time: 200,
+            lp_fees_owed_token_1: 7,
+            ..Default::default()
+        };
+        let mut position = Position { ..original };
+        let mut tranche = Position::default();
+        assert!(position.split_into(&mut tranche, 1_000).is_err());
+
+        position.split_into(&mut tranche, 250).unwrap();
+        assert_eq!((position.lp_tokens, tranche.lp_tokens), (750, 250));
+        assert_eq!(tranche.vesting_lp_tokens, 250);
+        assert_eq!(tranche.unlock_time, 200);
+        assert_eq!(
+            position.locked_lp_tokens(150) + tranche.locked_lp_tokens(150),
+            500
+        );
+
+        // Positions on another schedule don't merge, they'd vest early
+        let unlocked = Position {
+            lp_tokens: 1,
+            ..Default::default()
+        };
+        assert!(position.merge(&unlocked).is_err());
+
+        position.merge(&tranche).unwrap();
+        assert_eq!(position.lp_tokens, original.lp_tokens);
+        assert_eq!(position.vesting_lp_tokens, original.vesting_lp_tokens);
+        assert_eq!(position.lp_fees_owed_token_1, original.lp_fees_owed_token_1);
+    }
+}
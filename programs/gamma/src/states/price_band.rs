@@ -0,0 +1,131 @@
+use crate::error::GammaError;
+use crate::external::{pyth::PriceUpdateV2, switchboard::PullFeed};
+use crate::utils::U256;
+use anchor_lang::prelude::*;
+
+pub const PRICE_BAND_SEED: &str = "price_band";
+pub const PRICE_BAND_BPS_DENOMINATOR: u64 = 10_000;
+
+// `PriceBand::oracle_source` values
+pub const ORACLE_SOURCE_PYTH: u8 = 0;
+pub const ORACLE_SOURCE_SWITCHBOARD: u8 = 1;
+
+/// Band around an external oracle price the pool price has to stay in, protecting thin pools
+/// from being pushed far off the market
+#[account]
+#[derive(Default, Debug)]
+pub struct PriceBand {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    /// Pyth `PriceUpdateV2` or Switchboard on-demand pull feed account
+    pub oracle: Pubkey,
+    /// 0: Pyth, 1: Switchboard on-demand
+    pub oracle_source: u8,
+    /// Whether the oracle quotes token_1 in token_0 rather than token_0 in token_1
+    pub invert: bool,
+    /// Furthest the pool price can be from the oracle price, in bps of the oracle price
+    pub max_deviation_bps: u64,
+    /// Oldest oracle update the band accepts, in seconds
+    pub max_staleness: u64,
+    pub padding: [u64; 4],
+}
+
+impl PriceBand {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 1 + 1 + 8 * 2 + 8 * 4;
+
+    /// Reads the band of `pool_state` passed as an optional account
+    pub fn load_checked(band_info: &AccountInfo<'_>, pool_state: Pubkey) -> Result<PriceBand> {
+        require_keys_eq!(*band_info.owner, crate::id());
+        let price_band = PriceBand::try_deserialize(&mut &band_info.data.borrow()[..])?;
+        require_keys_eq!(price_band.pool_state, pool_state);
+        Ok(price_band)
+    }
+
+    /// Price of token_0 in token_1 raw amounts according to the oracle, Q32.32
+    pub fn oracle_price_x32(
+        &self,
+        oracle: &AccountInfo<'_>,
+        block_timestamp: u64,
+        mint_0_decimals: u8,
+        mint_1_decimals: u8,
+    ) -> Result<u128> {
+        require_keys_eq!(*oracle.key, self.oracle, GammaError::InvalidOracle);
+        let (mantissa, exponent, updated_at) = match self.oracle_source {
+            ORACLE_SOURCE_PYTH => {
+                let price_update =
+                    PriceUpdateV2::deserialize(oracle).ok_or(GammaError::InvalidOracle)?;
+                require!(price_update.fully_verified, GammaError::InvalidOracle);
+                (
+                    i128::from(price_update.price),
+                    price_update.exponent,
+                    price_update.publish_time,
+                )
+            }
+            ORACLE_SOURCE_SWITCHBOARD => {
+                let pull_feed = PullFeed::deserialize(oracle).ok_or(GammaError::InvalidOracle)?;
+                (
+                    pull_feed.value,
+                    -PullFeed::DECIMALS,
+                    pull_feed.last_update_timestamp,
+                )
+            }
+            _ => return err!(GammaError::InvalidOracle),
+        };
+        let updated_at = u64::try_from(updated_at).map_err(|_| GammaError::InvalidOracle)?;
+        require_gte!(
+            updated_at.saturating_add(self.max_staleness),
+            block_timestamp,
+            GammaError::InvalidOracle
+        );
+        let mantissa = u128::try_from(mantissa)
+            .ok()
+            .filter(|mantissa| *mantissa > 0)
+            .ok_or(GammaError::InvalidOracle)?;
+
+        // ui price * 10^(decimals_1 - decimals_0) is the price in raw amounts
+        let decimals = i32::from(mint_1_decimals) - i32::from(mint_0_decimals);
+        let (mut numerator, mut denominator, scale) = if self.invert {
+            (
+                U256::from(1u128 << 32),
+                U256::from(mantissa),
+                decimals - exponent,
+            )
+        } else {
+            (U256::from(mantissa) << 32, U256::one(), decimals + exponent)
+        };
+        require_gte!(38, scale.abs(), GammaError::InvalidOracle);
+        if scale >= 0 {
+            numerator = numerator
+                .checked_mul(U256::exp10(scale as usize))
+                .ok_or(GammaError::MathOverflow)?;
+        } else {
+            denominator = denominator
+                .checked_mul(U256::exp10(scale.unsigned_abs() as usize))
+                .ok_or(GammaError::MathOverflow)?;
+        }
+        let price_x32 = numerator / denominator;
+        require!(price_x32 <= U256::from(u128::MAX), GammaError::MathOverflow);
+        Ok(price_x32.as_u128())
+    }
+
+    /// Rejects a pool price outside the band, unless it moved towards the oracle price
+    pub fn check(
+        &self,
+        oracle_price_x32: u128,
+        token_0_price_x32_before: u128,
+        token_0_price_x32: u128,
+    ) -> Result<()> {
+        let deviation = oracle_price_x32
+            .checked_mul(u128::from(self.max_deviation_bps))
+            .ok_or(GammaError::MathOverflow)?
+            / u128::from(PRICE_BAND_BPS_DENOMINATOR);
+        let lower = oracle_price_x32.saturating_sub(deviation);
+        let upper = oracle_price_x32.saturating_add(deviation);
+        if (token_0_price_x32 < lower && token_0_price_x32 < token_0_price_x32_before)
+            || (token_0_price_x32 > upper && token_0_price_x32 > token_0_price_x32_before)
+        {
+            return err!(GammaError::PriceBandExceeded);
+        }
+        Ok(())
+    }
+}
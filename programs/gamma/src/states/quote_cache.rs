@@ -0,0 +1,32 @@
+use crate::states::PoolState;
+use anchor_lang::prelude::*;
+
+pub const QUOTE_CACHE_SEED: &str = "quote_cache";
+
+/// What a market maker needs to quote a pool, a fraction of the size of `PoolState` so it can be
+/// polled cheaply. Pools with `QUOTE_CACHE_EXTENSION` rewrite it on every change of reserves.
+#[account]
+#[derive(Default, Debug)]
+pub struct QuoteCache {
+    pub bump: u8,
+    pub pool_id: Pubkey,
+    /// Reserves the swap curve sees, fees owed to the protocol and the fund excluded
+    pub token_0_reserve: u64,
+    pub token_1_reserve: u64,
+    /// Dynamic fee rate of the latest swap, denominated in hundredths of bip (10^-6)
+    pub fee_rate: u64,
+    /// Slot the cache was last written at
+    pub last_slot: u64,
+    pub padding: [u64; 4],
+}
+
+impl QuoteCache {
+    pub const LEN: usize = 8 + 1 + 32 + 8 * 4 + 8 * 4;
+
+    pub fn refresh(&mut self, pool_state: &PoolState, slot: u64) -> Result<()> {
+        (self.token_0_reserve, self.token_1_reserve) = pool_state.vault_amount_without_fee()?;
+        self.fee_rate = pool_state.latest_dynamic_fee_rate;
+        self.last_slot = slot;
+        Ok(())
+    }
+}
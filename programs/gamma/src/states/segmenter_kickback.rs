@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+pub const SEGMENTER_KICKBACK_SEED: &str = "segmenter_kickback";
+
+/// Kickback accrued to a registered segmenter for the order flow it routed through a pool.
+/// The tokens stay in the pool vaults until the segmenter claims them.
+#[account]
+#[derive(Default, Debug)]
+pub struct SegmenterKickback {
+    pub bump: u8,
+    pub segmenter: Pubkey,
+    pub pool_state: Pubkey,
+    /// Unclaimed kickback
+    pub token_0_accrued: u64,
+    pub token_1_accrued: u64,
+    /// Lifetime claimed kickback
+    pub token_0_claimed: u64,
+    pub token_1_claimed: u64,
+    pub padding: [u64; 4],
+}
+
+impl SegmenterKickback {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 * 4 + 8 * 4;
+
+    pub fn initialize(&mut self, bump: u8, segmenter: Pubkey, pool_state: Pubkey) {
+        self.bump = bump;
+        self.segmenter = segmenter;
+        self.pool_state = pool_state;
+        self.token_0_accrued = 0;
+        self.token_1_accrued = 0;
+        self.token_0_claimed = 0;
+        self.token_1_claimed = 0;
+        self.padding = [0u64; 4];
+    }
+}
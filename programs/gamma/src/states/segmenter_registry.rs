@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+pub const SEGMENTER_REGISTRY_SEED: &str = "segmenter_registry";
+pub const SEGMENTER_REGISTRY_MAX_SEGMENTERS: usize = 16;
+
+/// Segmenters of an order-flow partner approved by the admin, passed to swaps in place of the
+/// DFlow registry. Swaps signed by one of them count as signed order flow, and the segmenter
+/// earns the partner's kickback rate instead of the config's.
+#[account]
+#[derive(Default, Debug)]
+pub struct SegmenterRegistry {
+    pub bump: u8,
+    /// Part of the seeds, one registry per partner
+    pub partner_id: u16,
+    /// Share of the dynamic fee the partner's segmenters earn, in bps
+    pub kickback_bps: u64,
+    /// Sorted, unused slots are the default pubkey
+    pub segmenters: [Pubkey; SEGMENTER_REGISTRY_MAX_SEGMENTERS],
+    pub padding: [u64; 4],
+}
+
+impl SegmenterRegistry {
+    pub const LEN: usize = 8 + 1 + 2 + 8 + 32 * SEGMENTER_REGISTRY_MAX_SEGMENTERS + 8 * 4;
+
+    pub fn is_segmenter_registered(&self, key: &Pubkey) -> bool {
+        *key != Pubkey::default() && self.segmenters.contains(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn segmenter_registry_never_approves_its_empty_slots() {
+        let segmenter = Pubkey::new_unique();
+        let mut segmenter_registry = SegmenterRegistry::default();
+        segmenter_registry.segmenters[0] = segmenter;
+        assert!(segmenter_registry.is_segmenter_registered(&segmenter));
+        assert!(!segmenter_registry.is_segmenter_registered(&Pubkey::new_unique()));
+        assert!(!segmenter_registry.is_segmenter_registered(&Pubkey::default()));
+    }
+}
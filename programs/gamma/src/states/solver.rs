@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+pub const SOLVER_SEED: &str = "solver";
+pub const SOLVER_BOND_VAULT_SEED: &str = "solver_bond_vault";
+
+/// A deactivated solver can only withdraw its bond after this long, which leaves time to slash
+/// it for settlements that failed before the deactivation.
+pub const SOLVER_UNBOND_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+/// A market maker registered by the admin to fill RFQ and intent orders. Its bond is held by a
+/// vault owned by the pool vault authority and is slashed when it fails to settle a fill.
+#[account]
+#[derive(Default, Debug)]
+pub struct Solver {
+    pub bump: u8,
+    /// Signs the solver's quotes and bond withdrawals
+    pub authority: Pubkey,
+    pub bond_mint: Pubkey,
+    pub bond_vault: Pubkey,
+    /// Bond held by the vault
+    pub bonded_amount: u64,
+    /// The solver can't quote with less bond than this
+    pub min_bond: u64,
+    /// Lifetime slashed bond
+    pub slashed_amount: u64,
+    pub failed_settlements: u64,
+    pub active: bool,
+    /// When the admin last deactivated the solver, starts the unbond period
+    pub deactivated_at: u64,
+    pub registered_at: u64,
+    pub padding: [u64; 4],
+}
+
+impl Solver {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 8 * 4 + 1 + 8 * 2 + 8 * 4;
+
+    /// Whether the solver can currently be routed RFQ fills
+    pub fn can_quote(&self) -> bool {
+        self.active && self.bonded_amount >= self.min_bond
+    }
+
+    pub fn can_unbond(&self, block_timestamp: u64) -> bool {
+        !self.active && block_timestamp >= self.deactivated_at + SOLVER_UNBOND_PERIOD
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+pub const STAKING_DISCOUNT_SEED: &str = "staking_discount";
+pub const STAKING_DISCOUNT_TIER_NUM: usize = 4;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct StakingDiscountTier {
+    /// Staked GOFX a trader needs for the tier
+    pub min_staked_amount: u64,
+    /// Share of the trade fee rate the tier takes off, in bps
+    pub discount_bps: u16,
+}
+
+/// Trade fee discounts the pools of an `AmmConfig` give traders staking GOFX. The stake is the
+/// trader's balance of `staked_mint`, the receipt token the GooseFX staking program mints for
+/// staked GOFX.
+#[account]
+#[derive(Default, Debug)]
+pub struct StakingDiscount {
+    pub bump: u8,
+    pub amm_config: Pubkey,
+    pub staked_mint: Pubkey,
+    /// Sorted by `min_staked_amount`, unused tiers are zero
+    pub tiers: [StakingDiscountTier; STAKING_DISCOUNT_TIER_NUM],
+    pub padding: [u64; 4],
+}
+
+impl StakingDiscount {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + (8 + 2) * STAKING_DISCOUNT_TIER_NUM + 8 * 4;
+
+    /// Discount of the highest tier `staked_amount` reaches, 0 below every tier
+    pub fn discount_bps(&self, staked_amount: u64) -> u16 {
+        self.tiers
+            .iter()
+            .filter(|tier| staked_amount >= tier.min_staked_amount)
+            .map(|tier| tier.discount_bps)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn staking_discount_takes_the_highest_tier_reached() {
+        let tier = |min_staked_amount, discount_bps| StakingDiscountTier {
+            min_staked_amount,
+            discount_bps,
+        };
+        let staking_discount = StakingDiscount {
+            tiers: [
+                tier(100, 1_000),
+                tier(1_000, 2_500),
+                tier(10_000, 5_000),
+                tier(0, 0),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(staking_discount.discount_bps(99), 0);
+        assert_eq!(staking_discount.discount_bps(1_000), 2_500);
+        assert_eq!(staking_discount.discount_bps(u64::MAX), 5_000);
+    }
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::error::GammaError;
+
+pub const SWAP_HOOK_ALLOWLIST_SEED: &str = "swap_hook_allowlist";
+pub const POOL_SWAP_HOOK_SEED: &str = "pool_swap_hook";
+
+/// Admin approval of a swap hook program. Pools can only attach an allowlisted program, removing
+/// the entry leaves the pools already calling it to the admin to clear.
+#[account]
+#[derive(Default, Debug)]
+pub struct SwapHookAllowlistEntry {
+    pub bump: u8,
+    pub program_id: Pubkey,
+    pub added_at: u64,
+    pub padding: [u64; 4],
+}
+
+impl SwapHookAllowlistEntry {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 8 * 4;
+}
+
+/// Program a pool calls after each swap with the `SwapEvent` of the swap, e.g. to award points
+/// or trigger a hedge. The account signs the call, so hooks can tell it came from the pool.
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolSwapHook {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    pub hook_program: Pubkey,
+    /// Number of accounts of its own the hook program takes
+    pub hook_accounts_len: u8,
+    pub padding: [u64; 4],
+}
+
+impl PoolSwapHook {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 1 + 8 * 4;
+
+    /// Reads a hook out of an account passed with the swap hook accounts, which has to be the
+    /// hook account of its pool
+    pub fn load(hook_info: &AccountInfo<'_>) -> Result<PoolSwapHook> {
+        require_keys_eq!(*hook_info.owner, crate::id(), GammaError::InvalidSwapHook);
+        let pool_swap_hook = PoolSwapHook::try_deserialize(&mut &hook_info.try_borrow_data()?[..])?;
+        let hook_key = Pubkey::create_program_address(
+            &[
+                POOL_SWAP_HOOK_SEED.as_bytes(),
+                pool_swap_hook.pool_state.as_ref(),
+                &[pool_swap_hook.bump],
+            ],
+            &crate::id(),
+        )
+        .map_err(|_| GammaError::InvalidSwapHook)?;
+        require_keys_eq!(hook_key, hook_info.key(), GammaError::InvalidSwapHook);
+        Ok(pool_swap_hook)
+    }
+
+    /// Reads the hook of `pool_id` out of an account passed with the swap hook accounts
+    pub fn load_checked(hook_info: &AccountInfo<'_>, pool_id: Pubkey) -> Result<PoolSwapHook> {
+        let pool_swap_hook = PoolSwapHook::load(hook_info)?;
+        require_keys_eq!(
+            pool_swap_hook.pool_state,
+            pool_id,
+            GammaError::InvalidSwapHook
+        );
+        Ok(pool_swap_hook)
+    }
+}
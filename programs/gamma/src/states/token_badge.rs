@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+pub const TOKEN_BADGE_SEED: &str = "token_badge";
+
+// `TokenBadge::bridge_origin` values
+pub const BRIDGE_ORIGIN_NATIVE: u8 = 0;
+pub const BRIDGE_ORIGIN_WORMHOLE: u8 = 1;
+pub const BRIDGE_ORIGIN_OTHER: u8 = 2;
+
+// `PoolState::token_badges` bits
+pub const TOKEN_0_BADGED: u64 = 1 << 0;
+pub const TOKEN_0_CANONICAL: u64 = 1 << 1;
+pub const TOKEN_1_BADGED: u64 = 1 << 2;
+pub const TOKEN_1_CANONICAL: u64 = 1 << 3;
+
+/// Admin verified metadata about a mint, so users can tell canonical bridged assets from
+/// impostor mints straight from chain state
+#[account]
+#[derive(Default, Debug)]
+pub struct TokenBadge {
+    pub bump: u8,
+    pub mint: Pubkey,
+    /// Whether the mint is the canonical representation of its asset on Solana
+    pub canonical: bool,
+    /// 0: native to Solana, 1: wormhole, 2: other bridge
+    pub bridge_origin: u8,
+    /// Chain id of the origin chain in the bridge's numbering, zero for native mints
+    pub origin_chain_id: u16,
+    /// Token address on the origin chain, left padded to 32 bytes
+    pub origin_address: [u8; 32],
+    pub verified_at: u64,
+    pub padding: [u64; 4],
+}
+
+impl TokenBadge {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 1 + 2 + 32 + 8 + 8 * 4;
+
+    /// Reads the badge of `mint` passed as an optional account
+    pub fn load_checked(badge_info: &AccountInfo<'_>, mint: Pubkey) -> Result<TokenBadge> {
+        require_keys_eq!(*badge_info.owner, crate::id());
+        let token_badge = TokenBadge::try_deserialize(&mut &badge_info.data.borrow()[..])?;
+        require_keys_eq!(token_badge.mint, mint);
+        Ok(token_badge)
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::error::GammaError;
+
+pub const TRANSFER_HOOK_ALLOWLIST_SEED: &str = "transfer_hook_allowlist";
+
+/// Admin approval of a transfer hook program. Transfers of a mint with a transfer hook only go
+/// through when the entry of its hook program is passed along with the hook accounts, so
+/// removing the entry stops the pools of every mint using the program.
+#[account]
+#[derive(Default, Debug)]
+pub struct TransferHookAllowlistEntry {
+    pub bump: u8,
+    pub program_id: Pubkey,
+    pub added_at: u64,
+    pub padding: [u64; 4],
+}
+
+impl TransferHookAllowlistEntry {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 8 * 4;
+
+    /// Reads the entry of `program_id` out of an account passed among the hook accounts
+    pub fn load_checked(
+        entry_info: &AccountInfo<'_>,
+        program_id: Pubkey,
+    ) -> Result<TransferHookAllowlistEntry> {
+        require_keys_eq!(*entry_info.owner, crate::id());
+        let entry =
+            TransferHookAllowlistEntry::try_deserialize(&mut &entry_info.try_borrow_data()?[..])?;
+        require_keys_eq!(
+            entry.program_id,
+            program_id,
+            GammaError::TransferHookNotAllowed
+        );
+        Ok(entry)
+    }
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
-use super::PartnerType;
+use super::{PartnerType, PoolState, LP_FEE_ACCRUAL_EXTENSION};
+use crate::error::GammaError;
 
 pub const USER_POOL_LIQUIDITY_SEED: &str = "user-pool-liquidity";
 
@@ -16,10 +17,67 @@ pub struct UserPoolLiquidity {
     pub lp_tokens_owned: u128,
     pub partner: Option<PartnerType>,
     pub padding: [u8; 23],
+    /// Fields added after the first layout, accounts created before read them as default
+    pub extension: UserPoolLiquidityExtension,
+}
+
+/// Fields appended to `UserPoolLiquidity` after its first layout. Accounts created with a
+/// shorter layout deserialize them as default, so deposits and withdrawals can grow the
+/// account in place instead of requiring `migrate_user_pool_liquidity` first.
+#[derive(AnchorSerialize, Clone, Default, Debug)]
+pub struct UserPoolLiquidityExtension {
+    /// Referral account that brought this liquidity, set on the first referred deposit
+    pub referrer: Option<Pubkey>,
+    /// Pool LP fee growth the position last accrued at, see `LP_FEE_ACCRUAL_EXTENSION`
+    pub lp_fee_growth_checkpoint_token_0_x32: u64,
+    pub lp_fee_growth_checkpoint_token_1_x32: u64,
+    /// LP fees accrued to the position and not claimed yet
+    pub lp_fees_owed_token_0: u64,
+    pub lp_fees_owed_token_1: u64,
+    /// LP fees claimed so far, including the referrer's share
+    pub lp_fees_claimed_token_0: u64,
+    pub lp_fees_claimed_token_1: u64,
+    /// LP tokens staked for rewards, see `PoolRewards`. They can't be withdrawn while staked
+    pub lp_tokens_staked: u64,
+    /// Slot of the user's last deposit, see `PoolState::withdraw_delay_slots`
+    pub last_deposit_slot: u64,
+    pub padding2: [u64; 8],
+}
+
+impl AnchorDeserialize for UserPoolLiquidityExtension {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        // The first layout ends before the extension, its accounts only have the few zero
+        // bytes their space reserved past the serialized fields left
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest)?;
+        if rest.len() <= UserPoolLiquidity::LEN_V1 - UserPoolLiquidity::SERIALIZED_LEN_V1
+            && rest.iter().all(|byte| *byte == 0)
+        {
+            return Ok(Self::default());
+        }
+        let reader = &mut rest.as_slice();
+        Ok(Self {
+            referrer: AnchorDeserialize::deserialize_reader(reader)?,
+            lp_fee_growth_checkpoint_token_0_x32: AnchorDeserialize::deserialize_reader(reader)?,
+            lp_fee_growth_checkpoint_token_1_x32: AnchorDeserialize::deserialize_reader(reader)?,
+            lp_fees_owed_token_0: AnchorDeserialize::deserialize_reader(reader)?,
+            lp_fees_owed_token_1: AnchorDeserialize::deserialize_reader(reader)?,
+            lp_fees_claimed_token_0: AnchorDeserialize::deserialize_reader(reader)?,
+            lp_fees_claimed_token_1: AnchorDeserialize::deserialize_reader(reader)?,
+            lp_tokens_staked: AnchorDeserialize::deserialize_reader(reader)?,
+            last_deposit_slot: AnchorDeserialize::deserialize_reader(reader)?,
+            padding2: AnchorDeserialize::deserialize_reader(reader)?,
+        })
+    }
 }
 
 impl UserPoolLiquidity {
-    pub const LEN: usize = 8 + 32 * 2 + 16 * 5 + 32;
+    /// Size of the accounts created before the extension was added
+    pub const LEN_V1: usize = 8 + 32 * 2 + 16 * 5 + 32;
+    /// Bytes the first layout serializes to without a partner, one less than `LEN_V1` reserved
+    /// for the partner and the rest left zero
+    const SERIALIZED_LEN_V1: usize = 8 + 32 * 2 + 16 * 5 + 1 + 23;
+    pub const LEN: usize = Self::LEN_V1 + 33 + 8 * 8 + 8 * 8;
 
     pub fn initialize(&mut self, user: Pubkey, pool_state: Pubkey, partner: Option<PartnerType>) {
         self.user = user;
@@ -31,5 +89,256 @@ impl UserPoolLiquidity {
         self.lp_tokens_owned = 0;
         self.partner = partner;
         self.padding = [0u8; 23];
+        self.extension = UserPoolLiquidityExtension::default();
+    }
+
+    /// LP tokens of the position that can be withdrawn, staked ones have to be unstaked first
+    pub fn unstaked_lp_tokens(&self) -> u128 {
+        self.lp_tokens_owned
+            .saturating_sub(u128::from(self.extension.lp_tokens_staked))
+    }
+
+    /// Keeps liquidity in the pool for the pool's withdraw delay after the user's last deposit
+    pub fn check_withdraw_delay(&self, pool_state: &PoolState, slot: u64) -> Result<()> {
+        let withdrawable_at = self
+            .extension
+            .last_deposit_slot
+            .saturating_add(pool_state.withdraw_delay_slots);
+        if slot < withdrawable_at {
+            msg!(
+                "slot: {} is before the withdraw delay ends at slot: {}",
+                slot,
+                withdrawable_at
+            );
+            return err!(GammaError::WithdrawTooSoonAfterDeposit);
+        }
+        Ok(())
+    }
+
+    /// Moves `lp_tokens` unstaked LP to `to`, with the same share of the deposit and withdraw
+    /// history and of the unclaimed LP fees. Both have to be accrued to the pool's LP fee growth,
+    /// so the fee checkpoints of `to` hold for the LP it receives.
+    pub fn transfer_lp_to(&mut self, to: &mut UserPoolLiquidity, lp_tokens: u128) -> Result<()> {
+        require_gte!(
+            self.unstaked_lp_tokens(),
+            lp_tokens,
+            GammaError::LpTokensStaked
+        );
+        let lp_tokens_owned = self.lp_tokens_owned;
+        let share = |amount: u128| -> Result<u128> {
+            Ok(amount
+                .checked_mul(lp_tokens)
+                .ok_or(GammaError::MathOverflow)?
+                .checked_div(lp_tokens_owned)
+                .unwrap_or(0))
+        };
+        let token_0_deposited = share(self.token_0_deposited)?;
+        let token_1_deposited = share(self.token_1_deposited)?;
+        let token_0_withdrawn = share(self.token_0_withdrawn)?;
+        let token_1_withdrawn = share(self.token_1_withdrawn)?;
+        // The share of a u64 fits in a u64
+        let lp_fees_owed_token_0 = share(u128::from(self.extension.lp_fees_owed_token_0))? as u64;
+        let lp_fees_owed_token_1 = share(u128::from(self.extension.lp_fees_owed_token_1))? as u64;
+
+        self.lp_tokens_owned -= lp_tokens;
+        self.token_0_deposited -= token_0_deposited;
+        self.token_1_deposited -= token_1_deposited;
+        self.token_0_withdrawn -= token_0_withdrawn;
+        self.token_1_withdrawn -= token_1_withdrawn;
+        self.extension.lp_fees_owed_token_0 -= lp_fees_owed_token_0;
+        self.extension.lp_fees_owed_token_1 -= lp_fees_owed_token_1;
+
+        to.lp_tokens_owned = to
+            .lp_tokens_owned
+            .checked_add(lp_tokens)
+            .ok_or(GammaError::MathOverflow)?;
+        to.token_0_deposited = to
+            .token_0_deposited
+            .checked_add(token_0_deposited)
+            .ok_or(GammaError::MathOverflow)?;
+        to.token_1_deposited = to
+            .token_1_deposited
+            .checked_add(token_1_deposited)
+            .ok_or(GammaError::MathOverflow)?;
+        to.token_0_withdrawn = to
+            .token_0_withdrawn
+            .checked_add(token_0_withdrawn)
+            .ok_or(GammaError::MathOverflow)?;
+        to.token_1_withdrawn = to
+            .token_1_withdrawn
+            .checked_add(token_1_withdrawn)
+            .ok_or(GammaError::MathOverflow)?;
+        to.extension.lp_fees_owed_token_0 = to
+            .extension
+            .lp_fees_owed_token_0
+            .checked_add(lp_fees_owed_token_0)
+            .ok_or(GammaError::MathOverflow)?;
+        to.extension.lp_fees_owed_token_1 = to
+            .extension
+            .lp_fees_owed_token_1
+            .checked_add(lp_fees_owed_token_1)
+            .ok_or(GammaError::MathOverflow)?;
+        // Moving liquidity doesn't restart the withdraw delay, nor skip it
+        to.extension.last_deposit_slot = to
+            .extension
+            .last_deposit_slot
+            .max(self.extension.last_deposit_slot);
+        Ok(())
+    }
+
+    /// Accrues the LP fees earned since the last checkpoint, has to run before every change
+    /// of `lp_tokens_owned` in pools accruing LP fees
+    pub fn accrue_lp_fees(&mut self, pool_state: &PoolState) -> Result<()> {
+        if !pool_state.has_extension(LP_FEE_ACCRUAL_EXTENSION) {
+            return Ok(());
+        }
+        let growth_0 = pool_state.lp_fee_growth_token_0_x32;
+        let growth_1 = pool_state.lp_fee_growth_token_1_x32;
+        let earned_0 = lp_fees_earned(
+            self.lp_tokens_owned,
+            growth_0,
+            self.extension.lp_fee_growth_checkpoint_token_0_x32,
+        )?;
+        let earned_1 = lp_fees_earned(
+            self.lp_tokens_owned,
+            growth_1,
+            self.extension.lp_fee_growth_checkpoint_token_1_x32,
+        )?;
+        self.extension.lp_fees_owed_token_0 = self
+            .extension
+            .lp_fees_owed_token_0
+            .checked_add(earned_0)
+            .ok_or(GammaError::MathOverflow)?;
+        self.extension.lp_fees_owed_token_1 = self
+            .extension
+            .lp_fees_owed_token_1
+            .checked_add(earned_1)
+            .ok_or(GammaError::MathOverflow)?;
+        self.extension.lp_fee_growth_checkpoint_token_0_x32 = growth_0;
+        self.extension.lp_fee_growth_checkpoint_token_1_x32 = growth_1;
+        Ok(())
+    }
+}
+
+/// LP fees `lp_tokens` earned while the pool's LP fee growth moved from `checkpoint` to `growth`
+pub fn lp_fees_earned(lp_tokens: u128, growth: u64, checkpoint: u64) -> Result<u64> {
+    Ok(lp_tokens
+        .checked_mul(u128::from(growth.wrapping_sub(checkpoint)))
+        .and_then(|earned| u64::try_from(earned >> 32).ok())
+        .ok_or(GammaError::MathOverflow)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accounts_of_the_first_layout_read_a_default_extension() {
+        let user_pool_liquidity = UserPoolLiquidity {
+            lp_tokens_owned: 1_000,
+            extension: UserPoolLiquidityExtension {
+                lp_tokens_staked: 400,
+                last_deposit_slot: 10,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        user_pool_liquidity.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= UserPoolLiquidity::LEN);
+        data.resize(UserPoolLiquidity::LEN, 0);
+
+        let read = UserPoolLiquidity::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(read.lp_tokens_owned, 1_000);
+        assert_eq!(read.extension.lp_tokens_staked, 400);
+        assert_eq!(read.extension.last_deposit_slot, 10);
+
+        // An account of the first layout, its fields followed by the zeros left in its space
+        for partner in [None, Some(PartnerType::AssetDash)] {
+            let mut first_layout = Vec::new();
+            UserPoolLiquidity {
+                lp_tokens_owned: 1_000,
+                partner,
+                ..Default::default()
+            }
+            .try_serialize(&mut first_layout)
+            .unwrap();
+            // Without the default extension, the referrer tag and 16 u64
+            first_layout.truncate(first_layout.len() - (1 + 16 * 8));
+            first_layout.resize(UserPoolLiquidity::LEN_V1, 0);
+
+            let read = UserPoolLiquidity::try_deserialize(&mut first_layout.as_slice()).unwrap();
+            assert_eq!(read.lp_tokens_owned, 1_000);
+            assert_eq!(read.partner, partner);
+            assert_eq!(read.extension.lp_tokens_staked, 0);
+            assert!(read.extension.referrer.is_none());
+        }
+
+        // A cut inside the extension is corrupt data, not an older layout
+        assert!(
+            UserPoolLiquidity::try_deserialize(&mut &data[..UserPoolLiquidity::LEN_V1 + 9])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn withdraw_delay_holds_fresh_deposits_only() {
+        let mut pool_state = PoolState::default();
+        let mut user_pool_liquidity = UserPoolLiquidity::default();
+        user_pool_liquidity.extension.last_deposit_slot = 100;
+        user_pool_liquidity
+            .check_withdraw_delay(&pool_state, 100)
+            .unwrap();
+
+        pool_state.withdraw_delay_slots = 2;
+        assert!(user_pool_liquidity
+            .check_withdraw_delay(&pool_state, 101)
+            .is_err());
+        user_pool_liquidity
+            .check_withdraw_delay(&pool_state, 102)
+            .unwrap();
+    }
+
+    #[test]
+    fn user_position_transfer_moves_its_share_of_history_and_fees() {
+        let mut from = UserPoolLiquidity {
+            lp_tokens_owned: 1_000,
+            token_0_deposited: 3_000,
+            token_1_deposited: 1_000,
+            extension: UserPoolLiquidityExtension {
+                lp_tokens_staked: 400,
+                lp_fees_owed_token_0: 99,
+                last_deposit_slot: 10,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut to = UserPoolLiquidity {
+            lp_tokens_owned: 50,
+            extension: UserPoolLiquidityExtension {
+                last_deposit_slot: 5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // Staked LP stays put
+        assert!(from.transfer_lp_to(&mut to, 601).is_err());
+
+        from.transfer_lp_to(&mut to, 600).unwrap();
+        assert_eq!((from.lp_tokens_owned, to.lp_tokens_owned), (400, 650));
+        assert_eq!(
+            (from.token_0_deposited, to.token_0_deposited),
+            (1_200, 1_800)
+        );
+        assert_eq!((from.token_1_deposited, to.token_1_deposited), (400, 600));
+        // Rounding leaves the dust with the sender
+        assert_eq!(
+            (
+                from.extension.lp_fees_owed_token_0,
+                to.extension.lp_fees_owed_token_0
+            ),
+            (40, 59)
+        );
+        assert_eq!(to.extension.last_deposit_slot, 10);
     }
 }
@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::GammaError, fees::FEE_RATE_DENOMINATOR_VALUE};
+
+pub const STRATEGY_ALLOWLIST_SEED: &str = "strategy_allowlist";
+pub const VAULT_STRATEGY_SEED: &str = "vault_strategy";
+// Seed of the authority of a strategy, the sole owner of its token account and of its position
+// in the lending protocol. The adapter CPIs are signed with it, never with the pool authority.
+pub const STRATEGY_SEED: &str = "strategy";
+
+/// Most lending protocol accounts an adapter can take
+pub const MAX_STRATEGY_PROTOCOL_ACCOUNTS: usize = 8;
+
+/// Most of a reserve a strategy can deploy, the rest stays in the vault as a buffer for swaps
+/// and withdrawals
+pub const MAX_STRATEGY_TARGET_RATE: u64 = 900_000;
+
+/// Lending protocols an adapter program can wrap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LendingProtocol {
+    Kamino,
+    MarginFi,
+    Solend,
+}
+
+/// Admin approval of a strategy adapter program. Vault strategies can only be set up with an
+/// allowlisted adapter, and the adapter is no longer called once its entry is removed.
+#[account]
+#[derive(Default, Debug)]
+pub struct StrategyAllowlistEntry {
+    pub bump: u8,
+    pub program_id: Pubkey,
+    /// The `LendingProtocol` the adapter wraps
+    pub protocol: u8,
+    pub added_at: u64,
+    pub padding: [u64; 4],
+}
+
+impl StrategyAllowlistEntry {
+    pub const LEN: usize = 8 + 1 + 32 + 1 + 8 + 8 * 4;
+
+    /// Whether `adapter_program` is allowlisted, out of an account passed for its entry. Only
+    /// an empty account, e.g. the closed entry of a removed program, shows it isn't.
+    pub fn is_allowlisted(entry_info: &AccountInfo<'_>, adapter_program: Pubkey) -> Result<bool> {
+        if entry_info.data_is_empty() {
+            require_keys_eq!(*entry_info.owner, System::id(), GammaError::InvalidStrategy);
+            return Ok(false);
+        }
+        require_keys_eq!(*entry_info.owner, crate::id(), GammaError::InvalidStrategy);
+        let allowlist_entry =
+            StrategyAllowlistEntry::try_deserialize(&mut &entry_info.try_borrow_data()?[..])?;
+        let entry_key = Pubkey::create_program_address(
+            &[
+                STRATEGY_ALLOWLIST_SEED.as_bytes(),
+                adapter_program.as_ref(),
+                &[allowlist_entry.bump],
+            ],
+            &crate::id(),
+        )
+        .map_err(|_| GammaError::InvalidStrategy)?;
+        require_keys_eq!(entry_key, entry_info.key(), GammaError::InvalidStrategy);
+        require_keys_eq!(
+            allowlist_entry.program_id,
+            adapter_program,
+            GammaError::InvalidStrategy
+        );
+        Ok(true)
+    }
+}
+
+/// Deployment of part of a pool vault's reserve to a lending protocol through an adapter program.
+/// The deployed amount still counts towards the reserve, swaps and withdrawals needing more than
+/// the vault holds recall the difference. The reserve moves through the token account of the
+/// strategy authority, `[STRATEGY_SEED, pool, vault mint]`, which owns the position.
+#[account]
+#[derive(Default, Debug)]
+pub struct VaultStrategy {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    pub vault: Pubkey,
+    pub adapter_program: Pubkey,
+    /// Number of lending protocol accounts the adapter takes after the common ones
+    pub adapter_accounts_len: u8,
+    pub authority_bump: u8,
+    /// Token account of the strategy authority the adapter deposits from and withdraws to
+    pub token_account: Pubkey,
+    /// The lending protocol accounts, every adapter call has to pass these
+    pub protocol_accounts: [Pubkey; MAX_STRATEGY_PROTOCOL_ACCOUNTS],
+    /// Share of the reserve to keep deployed, in fee rate units
+    pub target_rate: u64,
+    /// Part of the reserve sitting in the protocol
+    pub deployed_amount: u64,
+    /// What the position was worth at the last deposit or withdrawal, interest included
+    pub position_value: u64,
+    /// Interest harvested into the reserve over the life of the strategy
+    pub total_yield: u64,
+    pub padding: [u64; 4],
+}
+
+impl VaultStrategy {
+    pub const LEN: usize =
+        8 + 1 + 32 * 3 + 1 + 1 + 32 + 32 * MAX_STRATEGY_PROTOCOL_ACCOUNTS + 8 * 4 + 8 * 4;
+
+    /// Reads the strategy of `vault` out of an account passed with the strategy accounts
+    pub fn load_checked(
+        strategy_info: &AccountInfo<'_>,
+        pool_id: Pubkey,
+        vault: Pubkey,
+    ) -> Result<VaultStrategy> {
+        require_keys_eq!(
+            *strategy_info.owner,
+            crate::id(),
+            GammaError::InvalidStrategy
+        );
+        require!(strategy_info.is_writable, GammaError::InvalidInput);
+        let vault_strategy =
+            VaultStrategy::try_deserialize(&mut &strategy_info.try_borrow_data()?[..])?;
+        require_keys_eq!(
+            vault_strategy.pool_state,
+            pool_id,
+            GammaError::InvalidStrategy
+        );
+        require_keys_eq!(vault_strategy.vault, vault, GammaError::InvalidStrategy);
+        Ok(vault_strategy)
+    }
+
+    /// Part of `reserve` the strategy aims to keep deployed
+    pub fn target_amount(&self, reserve: u64) -> Result<u64> {
+        let target = u128::from(reserve)
+            .checked_mul(u128::from(self.target_rate))
+            .ok_or(GammaError::MathOverflow)?
+            / u128::from(FEE_RATE_DENOMINATOR_VALUE);
+        Ok(u64::try_from(target).map_err(|_| GammaError::MathOverflow)?)
+    }
+
+    /// Books a withdrawal of deployed reserve, the recalled amount no longer being deployed
+    pub fn record_recall(&mut self, amount: u64, position_value: u64) -> Result<()> {
+        self.deployed_amount = self
+            .deployed_amount
+            .checked_sub(amount)
+            .ok_or(GammaError::InsufficientLiquidity)?;
+        self.position_value = self.clamp_position_value(position_value);
+        Ok(())
+    }
+
+    /// The position value an adapter reports, trusted up to the deployed amount. Interest only
+    /// counts once it is harvested into the vault.
+    pub fn clamp_position_value(&self, position_value: u64) -> u64 {
+        position_value.min(self.deployed_amount)
+    }
+
+    /// Seeds of the strategy authority, signing the adapter calls
+    pub fn authority_seeds<'a>(&'a self, mint: &'a Pubkey) -> [&'a [u8]; 4] {
+        [
+            STRATEGY_SEED.as_bytes(),
+            self.pool_state.as_ref(),
+            mint.as_ref(),
+            std::slice::from_ref(&self.authority_bump),
+        ]
+    }
+
+    /// Checks the accounts passed for an adapter call are the ones of the strategy
+    pub fn check_adapter_accounts(
+        &self,
+        strategy_authority: &Pubkey,
+        mint: &Pubkey,
+        token_account: &Pubkey,
+        adapter_program: &Pubkey,
+        protocol_accounts: &[AccountInfo<'_>],
+    ) -> Result<()> {
+        let expected_authority =
+            Pubkey::create_program_address(&self.authority_seeds(mint), &crate::id())
+                .map_err(|_| GammaError::InvalidStrategy)?;
+        require_keys_eq!(
+            *strategy_authority,
+            expected_authority,
+            GammaError::InvalidStrategy
+        );
+        require_keys_eq!(
+            *token_account,
+            self.token_account,
+            GammaError::InvalidStrategy
+        );
+        require_keys_eq!(
+            *adapter_program,
+            self.adapter_program,
+            GammaError::InvalidStrategy
+        );
+        let protocol_account_keys =
+            &self.protocol_accounts[..usize::from(self.adapter_accounts_len)];
+        require!(
+            protocol_accounts.len() == protocol_account_keys.len()
+                && protocol_accounts
+                    .iter()
+                    .zip(protocol_account_keys)
+                    .all(|(account, key)| account.key == key),
+            GammaError::InvalidStrategy
+        );
+        Ok(())
+    }
+}
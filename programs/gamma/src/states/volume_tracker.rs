@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+pub const VOLUME_TRACKER_SEED: &str = "volume_tracker";
+pub const RECENT_TRADE_NUM: usize = 16;
+/// A swap reversing a trade of the same signer within this many seconds isn't counted
+pub const WASH_TRADE_WINDOW: u64 = 5 * 60;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct RecentTrade {
+    pub trader: Pubkey,
+    pub block_timestamp: u64,
+    pub zero_for_one: bool,
+}
+
+/// Volume of a pool with round trips by the same signer left out, for incentive programs that
+/// shouldn't be farmable by swapping back and forth. Only swaps passing this account are counted.
+#[account]
+#[derive(Default, Debug)]
+pub struct VolumeTracker {
+    pub bump: u8,
+    pub pool_state: Pubkey,
+    /// Swap input volume excluding round trips
+    pub adjusted_volume_token_0: u128,
+    pub adjusted_volume_token_1: u128,
+    /// Swap input volume that was left out as a round trip
+    pub discounted_volume_token_0: u128,
+    pub discounted_volume_token_1: u128,
+    /// Index of the next slot to overwrite in `recent_trades`
+    pub recent_trade_index: u8,
+    pub recent_trades: [RecentTrade; RECENT_TRADE_NUM],
+    pub padding: [u64; 4],
+}
+
+impl VolumeTracker {
+    pub const LEN: usize = 8 + 1 + 32 + 16 * 4 + 1 + (32 + 8 + 1) * RECENT_TRADE_NUM + 8 * 4;
+
+    pub fn initialize(&mut self, bump: u8, pool_state: Pubkey) {
+        self.bump = bump;
+        self.pool_state = pool_state;
+        self.adjusted_volume_token_0 = 0;
+        self.adjusted_volume_token_1 = 0;
+        self.discounted_volume_token_0 = 0;
+        self.discounted_volume_token_1 = 0;
+        self.recent_trade_index = 0;
+        self.recent_trades = [RecentTrade::default(); RECENT_TRADE_NUM];
+        self.padding = [0u64; 4];
+    }
+
+    /// Whether `trader` swapped in the opposite direction within the wash trade window
+    pub fn is_round_trip(&self, trader: Pubkey, zero_for_one: bool, block_timestamp: u64) -> bool {
+        self.recent_trades.iter().any(|trade| {
+            trade.trader == trader
+                && trade.zero_for_one != zero_for_one
+                && block_timestamp.saturating_sub(trade.block_timestamp) < WASH_TRADE_WINDOW
+        })
+    }
+
+    /// Records the trade and returns the volume it counts for, zero for a round trip
+    pub fn record_trade(
+        &mut self,
+        trader: Pubkey,
+        zero_for_one: bool,
+        amount_in: u64,
+        block_timestamp: u64,
+    ) -> Option<u64> {
+        let is_round_trip = self.is_round_trip(trader, zero_for_one, block_timestamp);
+        let (adjusted_volume, discounted_volume) = if zero_for_one {
+            (
+                &mut self.adjusted_volume_token_0,
+                &mut self.discounted_volume_token_0,
+            )
+        } else {
+            (
+                &mut self.adjusted_volume_token_1,
+                &mut self.discounted_volume_token_1,
+            )
+        };
+        let counted_amount = if is_round_trip {
+            *discounted_volume = discounted_volume.checked_add(u128::from(amount_in))?;
+            0
+        } else {
+            *adjusted_volume = adjusted_volume.checked_add(u128::from(amount_in))?;
+            amount_in
+        };
+
+        self.recent_trades[usize::from(self.recent_trade_index)] = RecentTrade {
+            trader,
+            block_timestamp,
+            zero_for_one,
+        };
+        self.recent_trade_index =
+            ((usize::from(self.recent_trade_index) + 1) % RECENT_TRADE_NUM) as u8;
+        Some(counted_amount)
+    }
+}
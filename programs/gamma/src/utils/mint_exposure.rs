@@ -0,0 +1,59 @@
+use crate::error::GammaError;
+use crate::states::{
+    MintExposure, PoolState, TOKEN_0_EXPOSURE_EXTENSION, TOKEN_1_EXPOSURE_EXTENSION,
+};
+use anchor_lang::prelude::*;
+
+/// Moves the `MintExposure` of each mint the pool tracks by how much its reserve changed since
+/// `reserves_before`. Pools tracking a mint can't change their reserve of it without passing
+/// its exposure account.
+pub fn update_mint_exposures(
+    pool_state: &PoolState,
+    reserves_before: (u64, u64),
+    token_0_mint_exposure: Option<&AccountInfo<'_>>,
+    token_1_mint_exposure: Option<&AccountInfo<'_>>,
+) -> Result<()> {
+    let (reserve_0, reserve_1) = pool_state.vault_amount_without_fee()?;
+    if pool_state.has_extension(TOKEN_0_EXPOSURE_EXTENSION) {
+        update_mint_exposure(
+            token_0_mint_exposure,
+            pool_state.token_0_mint,
+            reserves_before.0,
+            reserve_0,
+        )?;
+    }
+    if pool_state.has_extension(TOKEN_1_EXPOSURE_EXTENSION) {
+        update_mint_exposure(
+            token_1_mint_exposure,
+            pool_state.token_1_mint,
+            reserves_before.1,
+            reserve_1,
+        )?;
+    }
+    Ok(())
+}
+
+fn update_mint_exposure(
+    mint_exposure_account: Option<&AccountInfo<'_>>,
+    mint: Pubkey,
+    reserve_before: u64,
+    reserve_after: u64,
+) -> Result<()> {
+    let mint_exposure_account = mint_exposure_account.ok_or(GammaError::MissingMintExposure)?;
+    // check: exposure belongs to this program and to the mint
+    require_keys_eq!(
+        *mint_exposure_account.owner,
+        crate::id(),
+        GammaError::MissingMintExposure
+    );
+    require!(mint_exposure_account.is_writable, GammaError::InvalidInput);
+    let mut mint_exposure =
+        MintExposure::try_deserialize(&mut &mint_exposure_account.data.borrow()[..])?;
+    require_keys_eq!(mint_exposure.mint, mint, GammaError::MissingMintExposure);
+
+    mint_exposure.apply_reserve_change(reserve_before, reserve_after)?;
+
+    let mut data = mint_exposure_account.try_borrow_mut_data()?;
+    mint_exposure.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
@@ -1,7 +1,33 @@
 pub mod math;
+pub mod mint_exposure;
+pub mod pool_whitelist;
+pub mod price_band;
+pub mod quote;
+pub mod quote_cache;
+pub mod segmenter_kickback;
+pub mod segmenter_registry;
+pub mod staking_discount;
+pub mod strategy;
+pub mod swap_guard;
+pub mod swap_hook;
 pub mod swap_referral;
 pub mod token;
+pub mod twap;
+pub mod volume_tracker;
 
 pub use math::*;
+pub use mint_exposure::*;
+pub use pool_whitelist::*;
+pub use price_band::*;
+pub use quote::*;
+pub use quote_cache::*;
+pub use segmenter_kickback::*;
+pub use segmenter_registry::*;
+pub use staking_discount::*;
+pub use strategy::*;
+pub use swap_guard::*;
+pub use swap_hook::*;
 pub use swap_referral::*;
 pub use token::*;
+pub use twap::*;
+pub use volume_tracker::*;
@@ -0,0 +1,31 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, PoolWhitelistEntry};
+use anchor_lang::prelude::*;
+
+/// Checks that `address` is whitelisted in pools gated by `extension`, by the entry passed as an
+/// optional account
+pub fn check_whitelisted(
+    pool_state: &PoolState,
+    pool_id: Pubkey,
+    extension: u64,
+    address: Pubkey,
+    whitelist_entry: Option<&AccountInfo<'_>>,
+) -> Result<()> {
+    if !pool_state.has_extension(extension) {
+        return Ok(());
+    }
+    let whitelist_entry = whitelist_entry.ok_or(GammaError::NotWhitelisted)?;
+    // check: entry belongs to this program, the pool and the address
+    require_keys_eq!(
+        *whitelist_entry.owner,
+        crate::id(),
+        GammaError::NotWhitelisted
+    );
+    let whitelist_entry =
+        PoolWhitelistEntry::try_deserialize(&mut &whitelist_entry.data.borrow()[..])?;
+    require!(
+        whitelist_entry.pool_id == pool_id && whitelist_entry.address == address,
+        GammaError::NotWhitelisted
+    );
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, PriceBand, PRICE_BAND_EXTENSION};
+use anchor_lang::prelude::*;
+
+/// Keeps the pool price inside the band around the external oracle price, for pools with a
+/// `PriceBand`. Those pools can't be traded against without passing the band and its oracle.
+pub fn check_price_band(
+    pool_state: &PoolState,
+    pool_id: Pubkey,
+    price_band: Option<&AccountInfo<'_>>,
+    oracle: Option<&AccountInfo<'_>>,
+    token_0_price_x32_before: u128,
+) -> Result<()> {
+    if !pool_state.has_extension(PRICE_BAND_EXTENSION) {
+        return Ok(());
+    }
+    let (Some(price_band), Some(oracle)) = (price_band, oracle) else {
+        return err!(GammaError::InvalidOracle);
+    };
+    let price_band = PriceBand::load_checked(price_band, pool_id)?;
+    let oracle_price_x32 = price_band.oracle_price_x32(
+        oracle,
+        Clock::get()?.unix_timestamp as u64,
+        pool_state.mint_0_decimals,
+        pool_state.mint_1_decimals,
+    )?;
+    let (token_0_price_x32, _) = pool_state.token_price_x32()?;
+    price_band.check(
+        oracle_price_x32,
+        token_0_price_x32_before,
+        token_0_price_x32,
+    )
+}
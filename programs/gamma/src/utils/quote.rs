@@ -0,0 +1,251 @@
+use crate::curve::{CurveCalculator, SwapResult, TradeDirection};
+use crate::error::GammaError;
+use crate::states::{AmmConfig, ObservationState, PoolLifecycle, PoolState};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::Mint,
+};
+
+/// Transfer fee configs of the pool's mints, `None` for mints without one
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TransferFees {
+    pub token_0: Option<TransferFeeConfig>,
+    pub token_1: Option<TransferFeeConfig>,
+    /// Epoch the swap lands in, transfer fees can change between epochs
+    pub epoch: u64,
+}
+
+impl TransferFees {
+    /// Reads the transfer fee configs of the pool's mint accounts
+    pub fn from_mints(
+        token_0_mint: &AccountInfo,
+        token_1_mint: &AccountInfo,
+        epoch: u64,
+    ) -> Result<Self> {
+        Ok(Self {
+            token_0: transfer_fee_config(token_0_mint)?,
+            token_1: transfer_fee_config(token_1_mint)?,
+            epoch,
+        })
+    }
+
+    fn config(&self, token_0: bool) -> Option<&TransferFeeConfig> {
+        if token_0 {
+            self.token_0.as_ref()
+        } else {
+            self.token_1.as_ref()
+        }
+    }
+
+    /// Fee withheld when sending `pre_fee_amount`, like `get_transfer_fee`
+    pub fn transfer_fee(&self, token_0: bool, pre_fee_amount: u64) -> Result<u64> {
+        match self.config(token_0) {
+            Some(config) => Ok(config
+                .calculate_epoch_fee(self.epoch, pre_fee_amount)
+                .ok_or(GammaError::MathOverflow)?),
+            None => Ok(0),
+        }
+    }
+
+    /// Fee to add so `post_fee_amount` arrives, like `get_transfer_inverse_fee`
+    pub fn transfer_inverse_fee(&self, token_0: bool, post_fee_amount: u64) -> Result<u64> {
+        let Some(config) = self.config(token_0) else {
+            return Ok(0);
+        };
+        require_gt!(post_fee_amount, 0, GammaError::InvalidInput);
+        let transfer_fee = config.get_epoch_fee(self.epoch);
+        if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
+            return Ok(u64::from(transfer_fee.maximum_fee));
+        }
+        Ok(config
+            .calculate_inverse_epoch_fee(self.epoch, post_fee_amount)
+            .ok_or(GammaError::MathOverflow)?)
+    }
+}
+
+/// A swap as the program would execute it, amounts in raw token units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// What leaves the user's input account, transfer fee included
+    pub amount_in: u64,
+    /// What arrives in the user's output account, transfer fee excluded
+    pub amount_out: u64,
+    /// Part of the input the pool keeps as fee
+    pub dynamic_fee: u64,
+    /// Fee rate of the swap, denominated in hundredths of bip (10^-6)
+    pub dynamic_fee_rate: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+}
+
+fn transfer_fee_config(mint_info: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if *mint_info.owner == Token::id() {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+fn check_trading(pool_state: &PoolState, zero_for_one: bool, block_timestamp: u64) -> Result<()> {
+    if pool_state.lifecycle(block_timestamp) != PoolLifecycle::Open
+        || !pool_state.swap_direction_enabled(TradeDirection::from_zero_for_one(zero_for_one))
+    {
+        return err!(GammaError::NotApproved);
+    }
+    Ok(())
+}
+
+fn reserves(pool_state: &PoolState, zero_for_one: bool) -> Result<(u128, u128)> {
+    let (token_0_amount, token_1_amount) = pool_state.vault_amount_without_fee()?;
+    Ok(if zero_for_one {
+        (u128::from(token_0_amount), u128::from(token_1_amount))
+    } else {
+        (u128::from(token_1_amount), u128::from(token_0_amount))
+    })
+}
+
+fn to_u64(amount: u128) -> Result<u64> {
+    u64::try_from(amount).or(err!(GammaError::MathOverflow))
+}
+
+/// Quotes `swap_base_input` of `amount_in`, with the same math and the same errors as the
+/// program. Pools with rebasing shares have to be synced to their vault balances with
+/// `PoolState::sync_rebasing_reserve` first. The price band and the price floor and ceiling
+/// aren't checked.
+///
+/// # Arguments
+///
+/// * `pool_state` - The pool to swap in
+/// * `amm_config` - The config of the pool
+/// * `observation_state` - The pool's observations, the dynamic fee follows their volatility
+/// * `transfer_fees` - Transfer fees of the pool's mints
+/// * `zero_for_one` - Whether token_0 is swapped for token_1
+/// * `amount_in` - What the user sends, transfer fee included
+/// * `block_timestamp` - Timestamp the swap lands at
+///
+pub fn quote_exact_in(
+    pool_state: &PoolState,
+    amm_config: &AmmConfig,
+    observation_state: &ObservationState,
+    transfer_fees: &TransferFees,
+    zero_for_one: bool,
+    amount_in: u64,
+    block_timestamp: u64,
+) -> Result<SwapQuote> {
+    check_trading(pool_state, zero_for_one, block_timestamp)?;
+    let input_transfer_fee = transfer_fees.transfer_fee(zero_for_one, amount_in)?;
+    let actual_amount_in = amount_in.saturating_sub(input_transfer_fee);
+    require_gt!(actual_amount_in, 0, GammaError::AmountTooSmallAfterFees);
+
+    let (input_reserve, output_reserve) = reserves(pool_state, zero_for_one)?;
+    pool_state.check_trade_size(actual_amount_in, to_u64(input_reserve)?)?;
+    let result = CurveCalculator::swap_base_input(
+        u128::from(actual_amount_in),
+        input_reserve,
+        output_reserve,
+        TradeDirection::from_zero_for_one(zero_for_one),
+        amm_config,
+        pool_state,
+        block_timestamp,
+        observation_state,
+        false,
+        0,
+    )?;
+
+    let amount_out = to_u64(result.destination_amount_swapped)?;
+    let output_transfer_fee = transfer_fees.transfer_fee(!zero_for_one, amount_out)?;
+    let amount_received = amount_out
+        .checked_sub(output_transfer_fee)
+        .ok_or(GammaError::MathOverflow)?;
+    require_gt!(amount_received, 0, GammaError::AmountTooSmallAfterFees);
+
+    quote(
+        &result,
+        amount_in,
+        amount_received,
+        input_transfer_fee,
+        output_transfer_fee,
+    )
+}
+
+/// Quotes `swap_base_output` receiving `amount_out`, see `quote_exact_in`
+///
+/// # Arguments
+///
+/// * `pool_state` - The pool to swap in
+/// * `amm_config` - The config of the pool
+/// * `observation_state` - The pool's observations, the dynamic fee follows their volatility
+/// * `transfer_fees` - Transfer fees of the pool's mints
+/// * `zero_for_one` - Whether token_0 is swapped for token_1
+/// * `amount_out` - What the user receives, transfer fee excluded
+/// * `block_timestamp` - Timestamp the swap lands at
+///
+pub fn quote_exact_out(
+    pool_state: &PoolState,
+    amm_config: &AmmConfig,
+    observation_state: &ObservationState,
+    transfer_fees: &TransferFees,
+    zero_for_one: bool,
+    amount_out: u64,
+    block_timestamp: u64,
+) -> Result<SwapQuote> {
+    check_trading(pool_state, zero_for_one, block_timestamp)?;
+    let output_transfer_fee = transfer_fees.transfer_inverse_fee(!zero_for_one, amount_out)?;
+    let actual_amount_out = amount_out
+        .checked_add(output_transfer_fee)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let (input_reserve, output_reserve) = reserves(pool_state, zero_for_one)?;
+    pool_state.check_trade_size(actual_amount_out, to_u64(output_reserve)?)?;
+    let result = CurveCalculator::swap_base_output(
+        u128::from(actual_amount_out),
+        input_reserve,
+        output_reserve,
+        TradeDirection::from_zero_for_one(zero_for_one),
+        amm_config,
+        pool_state,
+        block_timestamp,
+        observation_state,
+        false,
+        0,
+    )?;
+
+    let source_amount_swapped = to_u64(result.source_amount_swapped)?;
+    require_gt!(source_amount_swapped, 0);
+    let input_transfer_fee =
+        transfer_fees.transfer_inverse_fee(zero_for_one, source_amount_swapped)?;
+    let amount_in = source_amount_swapped
+        .checked_add(input_transfer_fee)
+        .ok_or(GammaError::MathOverflow)?;
+
+    quote(
+        &result,
+        amount_in,
+        amount_out,
+        input_transfer_fee,
+        output_transfer_fee,
+    )
+}
+
+fn quote(
+    result: &SwapResult,
+    amount_in: u64,
+    amount_out: u64,
+    input_transfer_fee: u64,
+    output_transfer_fee: u64,
+) -> Result<SwapQuote> {
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        dynamic_fee: to_u64(result.dynamic_fee)?,
+        dynamic_fee_rate: result.dynamic_fee_rate,
+        input_transfer_fee,
+        output_transfer_fee,
+    })
+}
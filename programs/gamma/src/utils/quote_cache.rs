@@ -0,0 +1,31 @@
+use crate::error::GammaError;
+use crate::states::{PoolState, QuoteCache, QUOTE_CACHE_EXTENSION};
+use anchor_lang::prelude::*;
+
+/// Writes the pool's reserves and fee rate to its `QuoteCache`. Pools with a cache can't change
+/// their reserves without passing it.
+pub fn refresh_quote_cache(
+    pool_state: &PoolState,
+    pool_id: Pubkey,
+    quote_cache_account: Option<&AccountInfo<'_>>,
+) -> Result<()> {
+    if !pool_state.has_extension(QUOTE_CACHE_EXTENSION) {
+        return Ok(());
+    }
+    let quote_cache_account = quote_cache_account.ok_or(GammaError::MissingQuoteCache)?;
+    // check: cache belongs to this program and to the pool
+    require_keys_eq!(
+        *quote_cache_account.owner,
+        crate::id(),
+        GammaError::MissingQuoteCache
+    );
+    require!(quote_cache_account.is_writable, GammaError::InvalidInput);
+    let mut quote_cache = QuoteCache::try_deserialize(&mut &quote_cache_account.data.borrow()[..])?;
+    require_keys_eq!(quote_cache.pool_id, pool_id, GammaError::MissingQuoteCache);
+
+    quote_cache.refresh(pool_state, Clock::get()?.slot)?;
+
+    let mut data = quote_cache_account.try_borrow_mut_data()?;
+    quote_cache.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::error::GammaError;
+use crate::states::SegmenterKickback;
+use anchor_lang::prelude::*;
+
+/// Kickback of `kickback_bps` of the dynamic fee of a swap. It is paid out of the LP portion of
+/// the fee, so it never exceeds `lp_fee`.
+pub fn calculate_segmenter_kickback(
+    dynamic_fee: u64,
+    lp_fee: u64,
+    kickback_bps: u64,
+) -> Result<u64> {
+    let kickback = u128::from(dynamic_fee)
+        .checked_mul(u128::from(kickback_bps))
+        .ok_or(GammaError::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or(GammaError::MathOverflow)?;
+    Ok(u64::try_from(kickback)
+        .map_err(|_| GammaError::MathOverflow)?
+        .min(lp_fee))
+}
+
+/// Adds the kickback to the accrual account of `segmenter` for `pool_state`.
+/// The tokens themselves stay in the pool vaults until claimed.
+pub fn accrue_segmenter_kickback(
+    kickback_account: &AccountInfo<'_>,
+    pool_state: Pubkey,
+    segmenter: Pubkey,
+    token_0_amount: u64,
+    token_1_amount: u64,
+) -> Result<()> {
+    // check: kickback account belongs to this program and is the one for the pool and segmenter
+    require_keys_eq!(*kickback_account.owner, crate::id());
+    require!(kickback_account.is_writable, GammaError::InvalidInput);
+    let mut kickback =
+        SegmenterKickback::try_deserialize(&mut &kickback_account.data.borrow()[..])?;
+    require_keys_eq!(kickback.pool_state, pool_state);
+    require_keys_eq!(kickback.segmenter, segmenter);
+
+    kickback.token_0_accrued = kickback
+        .token_0_accrued
+        .checked_add(token_0_amount)
+        .ok_or(GammaError::MathOverflow)?;
+    kickback.token_1_accrued = kickback
+        .token_1_accrued
+        .checked_add(token_1_amount)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let mut data = kickback_account.try_borrow_mut_data()?;
+    kickback.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn kickback_is_a_share_of_the_dynamic_fee() {
+        // 20% of a 1000 fee, of which protocol and fund fees took 300
+        assert_eq!(calculate_segmenter_kickback(1000, 700, 2000).unwrap(), 200);
+        // Rounded down
+        assert_eq!(calculate_segmenter_kickback(999, 700, 2000).unwrap(), 199);
+        assert_eq!(calculate_segmenter_kickback(1000, 700, 0).unwrap(), 0);
+        assert_eq!(
+            calculate_segmenter_kickback(u64::MAX, u64::MAX, 10_000).unwrap(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn kickback_is_capped_at_the_lp_fee() {
+        assert_eq!(
+            calculate_segmenter_kickback(1000, 700, 10_000).unwrap(),
+            700
+        );
+        assert_eq!(calculate_segmenter_kickback(1000, 0, 2000).unwrap(), 0);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::external::dflow_segmenter::is_invoked_by_segmenter;
+use crate::states::SegmenterRegistry;
+use anchor_lang::prelude::*;
+
+/// Kickback rate of `segmenter` when it signed the swap and `registry` approves it, `None` for
+/// order flow that isn't signed by a registered segmenter. `registry` is either the DFlow
+/// registry, whose segmenters earn `config_kickback_bps`, or the `SegmenterRegistry` of another
+/// order-flow partner.
+pub fn registered_segmenter_kickback_bps(
+    registry: &AccountInfo<'_>,
+    segmenter: &AccountInfo<'_>,
+    config_kickback_bps: u64,
+) -> Option<u64> {
+    if *registry.owner != crate::id() {
+        return is_invoked_by_segmenter(registry, segmenter).then_some(config_kickback_bps);
+    }
+    if !segmenter.is_signer {
+        return None;
+    }
+    let registry_data = registry.data.borrow();
+    let segmenter_registry = SegmenterRegistry::try_deserialize(&mut &registry_data[..]).ok()?;
+    segmenter_registry
+        .is_segmenter_registered(segmenter.key)
+        .then_some(segmenter_registry.kickback_bps)
+}
@@ -0,0 +1,41 @@
+use crate::error::GammaError;
+use crate::states::StakingDiscount;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+/// Trade fee discount, in bps, of the tier `payer` reaches in the config's `StakingDiscount`.
+/// Both the discount and the payer's token account of the staked mint are optional accounts, no
+/// discount applies without them.
+pub fn staking_discount_bps(
+    amm_config: Pubkey,
+    payer: Pubkey,
+    staking_discount: Option<&AccountInfo<'_>>,
+    staked_token_account: Option<&AccountInfo<'_>>,
+) -> Result<u16> {
+    let (Some(staking_discount), Some(staked_token_account)) =
+        (staking_discount, staked_token_account)
+    else {
+        return Ok(0);
+    };
+    require_keys_eq!(
+        *staking_discount.owner,
+        crate::id(),
+        GammaError::InvalidStakingDiscount
+    );
+    let staking_discount =
+        StakingDiscount::try_deserialize(&mut &staking_discount.data.borrow()[..])?;
+    require_keys_eq!(
+        staking_discount.amm_config,
+        amm_config,
+        GammaError::InvalidStakingDiscount
+    );
+
+    let staked_token_account =
+        TokenAccount::try_deserialize(&mut &staked_token_account.data.borrow()[..])?;
+    require!(
+        staked_token_account.mint == staking_discount.staked_mint
+            && staked_token_account.owner == payer,
+        GammaError::InvalidStakingDiscount
+    );
+    Ok(staking_discount.discount_bps(staked_token_account.amount))
+}
@@ -0,0 +1,179 @@
+use crate::error::GammaError;
+use crate::external::strategy_adapter::{self, AdapterAccounts};
+use crate::instructions::decode_account_info;
+use crate::states::{StrategyAllowlistEntry, VaultStrategy, VaultStrategyChangeEvent};
+use crate::utils::transfer_from_pool_vault_to_user;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+/// Accounts of a vault's strategy passed along a swap or withdrawal: its `VaultStrategy`, the
+/// allowlist entry of its adapter, the strategy authority and its token account, the adapter
+/// program, then the lending protocol accounts the adapter takes
+pub struct StrategyAccounts<'info> {
+    pub vault_strategy: AccountInfo<'info>,
+    pub allowlist_entry: AccountInfo<'info>,
+    pub strategy_authority: AccountInfo<'info>,
+    pub token_account: AccountInfo<'info>,
+    pub adapter_program: AccountInfo<'info>,
+    pub protocol_accounts: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> StrategyAccounts<'info> {
+    /// Accounts of a strategy ahead of its lending protocol accounts
+    pub const FIXED_LEN: usize = 5;
+
+    /// Splits the accounts of a strategy off the front of `accounts`. The gamma program id
+    /// stands in for the `VaultStrategy` of a vault passing none, taking a single slot.
+    pub fn split<'a>(
+        accounts: &'a [AccountInfo<'info>],
+    ) -> (Option<Self>, &'a [AccountInfo<'info>]) {
+        let (Some(vault_strategy), Some(fixed_accounts)) = (
+            decode_account_info(accounts, 0),
+            accounts.get(1..Self::FIXED_LEN),
+        ) else {
+            return (None, accounts.get(1..).unwrap_or_default());
+        };
+        // Another account than a strategy is rejected once loaded, here it only takes no
+        // protocol accounts
+        let protocol_accounts_len =
+            VaultStrategy::try_deserialize(&mut &vault_strategy.data.borrow()[..])
+                .map(|vault_strategy| usize::from(vault_strategy.adapter_accounts_len))
+                .unwrap_or_default();
+        let end = accounts.len().min(Self::FIXED_LEN + protocol_accounts_len);
+        let strategy = StrategyAccounts {
+            vault_strategy,
+            allowlist_entry: fixed_accounts[0].clone(),
+            strategy_authority: fixed_accounts[1].clone(),
+            token_account: fixed_accounts[2].clone(),
+            adapter_program: fixed_accounts[3].clone(),
+            protocol_accounts: accounts[Self::FIXED_LEN..end].to_vec(),
+        };
+        (Some(strategy), &accounts[end..])
+    }
+
+    pub fn save(&self, vault_strategy: &VaultStrategy) -> Result<()> {
+        let mut data = self.vault_strategy.try_borrow_mut_data()?;
+        vault_strategy.try_serialize(&mut &mut data[..])
+    }
+}
+
+/// Recalls from the vault's strategy what the vault is missing to pay out `amount`, so swaps
+/// and withdrawals aren't limited to the buffer the strategy leaves in the vault. Without the
+/// strategy accounts, the payout fails on the missing balance, as does a recall through an
+/// adapter that is no longer allowlisted.
+pub fn recall_for_payout<'info>(
+    strategy: Option<&StrategyAccounts<'info>>,
+    pool_id: Pubkey,
+    vault: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let balance_before = token_balance(vault)?;
+    if balance_before >= amount {
+        return Ok(());
+    }
+    let Some(strategy) = strategy else {
+        return Ok(());
+    };
+    let mut vault_strategy =
+        VaultStrategy::load_checked(&strategy.vault_strategy, pool_id, vault.key())?;
+    require!(
+        StrategyAllowlistEntry::is_allowlisted(
+            &strategy.allowlist_entry,
+            strategy.adapter_program.key()
+        )?,
+        GammaError::InvalidStrategy
+    );
+
+    let recall_amount = amount - balance_before;
+    let position_value = withdraw_from_strategy(
+        &AdapterAccounts {
+            strategy_authority: strategy.strategy_authority.clone(),
+            token_account: strategy.token_account.clone(),
+            mint: mint.clone(),
+            token_program: token_program.clone(),
+            adapter_program: strategy.adapter_program.clone(),
+            protocol_accounts: &strategy.protocol_accounts,
+        },
+        &vault_strategy,
+        vault,
+        recall_amount,
+    )?;
+    require_gte!(
+        token_balance(vault)?.saturating_sub(balance_before),
+        recall_amount,
+        GammaError::StrategyShortfall
+    );
+    vault_strategy.record_recall(recall_amount, position_value)?;
+    strategy.save(&vault_strategy)?;
+
+    emit!(VaultStrategyChangeEvent {
+        version: VaultStrategyChangeEvent::VERSION,
+        pool_id,
+        vault: vault.key(),
+        change_type: 2,
+        amount: recall_amount,
+        deployed_amount: vault_strategy.deployed_amount,
+        position_value: vault_strategy.position_value,
+        yield_amount: 0,
+        loss_amount: 0,
+    });
+    Ok(())
+}
+
+/// Moves `amount` of the vault to the strategy's token account and deposits what arrives into
+/// the protocol, returning what the position is worth after
+pub fn deploy_to_strategy<'info>(
+    accounts: &AdapterAccounts<'_, 'info>,
+    vault_strategy: &VaultStrategy,
+    authority: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    auth_bump: u8,
+    amount: u64,
+) -> Result<u64> {
+    transfer_from_pool_vault_to_user(
+        authority.clone(),
+        vault.clone(),
+        accounts.token_account.clone(),
+        accounts.mint.clone(),
+        accounts.token_program.clone(),
+        amount,
+        mint_decimals(&accounts.mint)?,
+        &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
+    )?;
+    let deposit_amount = token_balance(&accounts.token_account)?;
+    strategy_adapter::deposit(accounts, vault_strategy, deposit_amount)
+}
+
+/// Withdraws `amount` of the position to the strategy's token account and moves it on to the
+/// vault, returning what the position is worth after
+pub fn withdraw_from_strategy<'info>(
+    accounts: &AdapterAccounts<'_, 'info>,
+    vault_strategy: &VaultStrategy,
+    vault: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    let position_value = strategy_adapter::withdraw(accounts, vault_strategy, amount)?;
+    let mint = accounts.mint.key();
+    transfer_from_pool_vault_to_user(
+        accounts.strategy_authority.clone(),
+        accounts.token_account.clone(),
+        vault.clone(),
+        accounts.mint.clone(),
+        accounts.token_program.clone(),
+        token_balance(&accounts.token_account)?,
+        mint_decimals(&accounts.mint)?,
+        &[&vault_strategy.authority_seeds(&mint)],
+    )?;
+    Ok(position_value)
+}
+
+/// Balance of a token account as it is now, after the transfers of the instruction so far
+pub fn token_balance(token_account: &AccountInfo<'_>) -> Result<u64> {
+    Ok(TokenAccount::try_deserialize(&mut &token_account.try_borrow_data()?[..])?.amount)
+}
+
+fn mint_decimals(mint: &AccountInfo<'_>) -> Result<u8> {
+    Ok(Mint::try_deserialize(&mut &mint.try_borrow_data()?[..])?.decimals)
+}
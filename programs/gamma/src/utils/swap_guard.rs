@@ -0,0 +1,78 @@
+use crate::error::GammaError;
+use crate::router_metadata::{
+    SWAP_BATCH_REQUIRED_ACCOUNTS, SWAP_REQUIRED_ACCOUNTS, SWAP_ROUTE_REQUIRED_ACCOUNTS,
+};
+use crate::states::{PoolState, PoolStatusBitIndex};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+use anchor_lang::Discriminator;
+
+/// Position of the pool state in the accounts of `swap_base_input` and `swap_base_output`
+const SWAP_POOL_STATE_INDEX: usize = 3;
+
+/// Rejects the swap if the transaction swaps through `pool_id` more than once, unless the
+/// pool allows composed swaps. Only top level instructions are seen, a swap made through CPI
+/// is counted by the instruction of the calling program, not by gamma.
+pub fn check_duplicate_swap(
+    pool_state: &PoolState,
+    pool_id: &Pubkey,
+    instructions_sysvar: Option<&AccountInfo<'_>>,
+) -> Result<()> {
+    if pool_state.get_status_by_bit(PoolStatusBitIndex::ComposedSwap) {
+        return Ok(());
+    }
+    let instructions_sysvar = instructions_sysvar.ok_or(GammaError::DuplicateSwap)?;
+    require_gte!(
+        1,
+        count_pool_swaps(instructions_sysvar, pool_id)?,
+        GammaError::DuplicateSwap
+    );
+    Ok(())
+}
+
+/// Number of swaps through `pool_id` made by the top level gamma instructions of the transaction
+fn count_pool_swaps(instructions_sysvar: &AccountInfo<'_>, pool_id: &Pubkey) -> Result<usize> {
+    let mut swaps = 0;
+    let mut index = 0;
+    // Loading past the last instruction fails, which is how the end is found
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        index += 1;
+        if instruction.program_id != crate::id() || instruction.data.len() < 8 {
+            continue;
+        }
+        let discriminator = &instruction.data[..8];
+        if discriminator == crate::instruction::SwapBaseInput::DISCRIMINATOR
+            || discriminator == crate::instruction::SwapBaseOutput::DISCRIMINATOR
+        {
+            if instruction
+                .accounts
+                .get(SWAP_POOL_STATE_INDEX)
+                .is_some_and(|account| account.pubkey == *pool_id)
+            {
+                swaps += 1;
+            }
+        } else if discriminator == crate::instruction::SwapRoute::DISCRIMINATOR {
+            swaps += instruction
+                .accounts
+                .iter()
+                .skip(SWAP_ROUTE_REQUIRED_ACCOUNTS)
+                .skip(SWAP_POOL_STATE_INDEX)
+                .step_by(SWAP_REQUIRED_ACCOUNTS)
+                .filter(|account| account.pubkey == *pool_id)
+                .count();
+        } else if discriminator == crate::instruction::SwapBatch::DISCRIMINATOR
+            && instruction
+                .accounts
+                .get(SWAP_BATCH_REQUIRED_ACCOUNTS + SWAP_POOL_STATE_INDEX)
+                .is_some_and(|account| account.pubkey == *pool_id)
+        {
+            // Every leg counts, the legs are a vec right after the discriminator
+            let legs = instruction
+                .data
+                .get(8..12)
+                .map_or(0, |len| u32::from_le_bytes(len.try_into().unwrap()));
+            swaps += legs as usize;
+        }
+    }
+    Ok(swaps)
+}
@@ -0,0 +1,83 @@
+use crate::error::GammaError;
+use crate::external::swap_hook;
+use crate::instructions::decode_account_info;
+use crate::states::{PoolState, PoolSwapHook, SwapEvent, POOL_SWAP_HOOK_SEED, SWAP_HOOK_EXTENSION};
+use anchor_lang::prelude::*;
+
+/// Accounts of a pool's swap hook passed along a swap: its `PoolSwapHook`, the hook program, then
+/// the accounts the hook program takes
+pub struct SwapHookAccounts<'info> {
+    pub pool_swap_hook: AccountInfo<'info>,
+    pub hook_program: AccountInfo<'info>,
+    pub hook_accounts: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> SwapHookAccounts<'info> {
+    /// Splits the accounts of a swap hook off the front of `accounts`. The gamma program id
+    /// stands in for the `PoolSwapHook` of a pool without a hook, taking a single slot.
+    pub fn split<'a>(
+        accounts: &'a [AccountInfo<'info>],
+    ) -> (Option<Self>, &'a [AccountInfo<'info>]) {
+        let (Some(pool_swap_hook), Some(hook_program)) =
+            (decode_account_info(accounts, 0), accounts.get(1))
+        else {
+            return (None, accounts.get(1..).unwrap_or_default());
+        };
+        // Only the hook account of a pool sizes the hook accounts, another account is rejected
+        // once the hook is called
+        let hook_accounts_len = PoolSwapHook::load(&pool_swap_hook)
+            .map(|pool_swap_hook| usize::from(pool_swap_hook.hook_accounts_len))
+            .unwrap_or_default();
+        let end = accounts.len().min(2 + hook_accounts_len);
+        let swap_hook = SwapHookAccounts {
+            pool_swap_hook,
+            hook_program: hook_program.clone(),
+            hook_accounts: accounts[2..end].to_vec(),
+        };
+        (Some(swap_hook), &accounts[end..])
+    }
+}
+
+/// Calls the pool's swap hook program with the `SwapEvent` of a swap. Pools with
+/// `SWAP_HOOK_EXTENSION` don't swap without their hook, the accounts of other pools are ignored.
+/// The pool state must no longer be borrowed, the hook can take it as one of its accounts.
+pub fn call_swap_hook<'info>(
+    swap_hook: Option<&SwapHookAccounts<'info>>,
+    pool_has_swap_hook: bool,
+    pool_id: Pubkey,
+    payer: &AccountInfo<'info>,
+    swap_event: &SwapEvent,
+) -> Result<()> {
+    if !pool_has_swap_hook {
+        return Ok(());
+    }
+    let swap_hook = swap_hook.ok_or(GammaError::InvalidSwapHook)?;
+    let pool_swap_hook = PoolSwapHook::load_checked(&swap_hook.pool_swap_hook, pool_id)?;
+    require_keys_eq!(
+        pool_swap_hook.hook_program,
+        swap_hook.hook_program.key(),
+        GammaError::InvalidSwapHook
+    );
+    swap_hook::on_swap(
+        &swap_hook.hook_program,
+        &swap_hook.pool_swap_hook,
+        payer,
+        &swap_hook.hook_accounts,
+        swap_event,
+        &[&[
+            POOL_SWAP_HOOK_SEED.as_bytes(),
+            pool_id.as_ref(),
+            &[pool_swap_hook.bump],
+        ]],
+    )
+}
+
+/// Swaps made through `swap_route`, `swap_batch` or `convert_fund_fees` don't pass the accounts of
+/// a swap hook, pools with one only swap through `swap_base_input` and `swap_base_output`
+pub fn check_no_swap_hook(pool_state: &PoolState) -> Result<()> {
+    require!(
+        !pool_state.has_extension(SWAP_HOOK_EXTENSION),
+        GammaError::SwapHookNotSupported
+    );
+    Ok(())
+}
@@ -1,9 +1,12 @@
+use crate::error::GammaError;
 use anchor_lang::prelude::*;
 use referral::ReferralAccount;
 use referral::REFERRAL_ATA_SEED;
 use spl_token::state::{Account as SplTokenAccount, GenericTokenAccount};
 
 pub struct ReferralDetails<'c, 'info> {
+    /// The referral account of the referrer
+    pub referrer: Pubkey,
     pub share_bps: u16,
     pub referral_token_account: &'c AccountInfo<'info>,
 }
@@ -23,10 +26,7 @@ pub fn extract_referral_info<'c, 'info>(
     let referral_account = referral_account.as_ref().unwrap();
     let referral_token_account = referral_token_account.as_ref().unwrap();
 
-    // check: Referral account belongs to referral program and is for project
-    require_keys_eq!(*referral_account.owner, referral::ID);
-    let referral = ReferralAccount::try_deserialize(&mut &referral_account.data.borrow()[..])?;
-    require_keys_eq!(project_key, referral.project);
+    let referral = load_referral_account(project_key, referral_account)?;
 
     // check: Referral token account has the expected seeds
     let expect_token_account_key = Pubkey::find_program_address(
@@ -55,7 +55,34 @@ pub fn extract_referral_info<'c, 'info>(
     require_keys_eq!(project_key, *token_account_owner);
 
     Ok(Some(ReferralDetails {
+        referrer: referral_account.key(),
         share_bps: referral.share_bps, // the referral program guarantees that this is < 10_000
         referral_token_account,
     }))
 }
+
+pub fn load_referral_account(
+    project_key: Pubkey,
+    referral_account: &AccountInfo<'_>,
+) -> Result<ReferralAccount> {
+    // check: Referral account belongs to referral program and is for project
+    require_keys_eq!(*referral_account.owner, referral::ID);
+    let referral = ReferralAccount::try_deserialize(&mut &referral_account.data.borrow()[..])?;
+    require_keys_eq!(project_key, referral.project);
+    Ok(referral)
+}
+
+/// Values a referral fee of `referral_amount` input tokens in output tokens, at the price the
+/// swap executed at
+pub fn referral_amount_in_output(
+    referral_amount: u64,
+    amount_in: u64,
+    amount_out: u64,
+) -> Result<u64> {
+    let amount = u128::from(referral_amount)
+        .checked_mul(u128::from(amount_out))
+        .ok_or(GammaError::MathOverflow)?
+        .checked_div(u128::from(amount_in))
+        .unwrap_or(0);
+    u64::try_from(amount).or(err!(GammaError::MathOverflow))
+}
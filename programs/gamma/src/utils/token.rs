@@ -1,20 +1,31 @@
 use crate::error::GammaError;
-use anchor_lang::prelude::*;
+use crate::states::{
+    AmmConfig, PoolState, TransferHookAllowlistEntry, ALLOW_DEFAULT_FROZEN,
+    ALLOW_PERMANENT_DELEGATE, REJECT_FREEZE_AUTHORITY, REJECT_MUTABLE_TRANSFER_FEE,
+};
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed, system_program};
 use anchor_spl::{
+    associated_token,
     token::{Token, TokenAccount},
     token_2022::{
         self,
         spl_token_2022::{
             self,
             extension::{
-                transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
-                ExtensionType, StateWithExtensions,
+                default_account_state::DefaultAccountState,
+                interest_bearing_mint::{BasisPoints, InterestBearingConfig},
+                permanent_delegate::PermanentDelegate,
+                transfer_fee::{self, TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+                transfer_hook, ExtensionType, StateWithExtensions,
             },
+            onchain,
+            state::AccountState,
         },
     },
+    token_2022_extensions,
     token_interface::{
         initialize_account3, spl_token_2022::extension::BaseStateWithExtensions,
-        InitializeAccount3, Mint,
+        spl_token_metadata_interface::state::TokenMetadata, InitializeAccount3, Mint,
     },
 };
 use std::collections::HashSet;
@@ -34,22 +45,44 @@ pub fn transfer_from_user_to_pool_vault<'a>(
     token_program: AccountInfo<'a>,
     amount: u64,
     mint_decimals: u8,
+) -> Result<()> {
+    transfer_from_user_to_pool_vault_with_hook(
+        authority,
+        from,
+        to_vault,
+        mint,
+        token_program,
+        &[],
+        amount,
+        mint_decimals,
+    )
+}
+
+/// Like `transfer_from_user_to_pool_vault`, resolving the accounts of the mint's transfer hook
+/// out of `hook_accounts`, see `transfer_checked_with_hook`
+pub fn transfer_from_user_to_pool_vault_with_hook<'a>(
+    authority: AccountInfo<'a>,
+    from: AccountInfo<'a>,
+    to_vault: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    hook_accounts: &[AccountInfo<'a>],
+    amount: u64,
+    mint_decimals: u8,
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
-    token_2022::transfer_checked(
-        CpiContext::new(
-            token_program.to_account_info(),
-            token_2022::TransferChecked {
-                from,
-                to: to_vault,
-                authority,
-                mint,
-            },
-        ),
+    transfer_checked_with_hook(
+        authority,
+        from,
+        to_vault,
+        mint,
+        token_program,
+        hook_accounts,
         amount,
         mint_decimals,
+        &[],
     )
 }
 
@@ -62,26 +95,110 @@ pub fn transfer_from_pool_vault_to_user<'a>(
     amount: u64,
     mint_decimals: u8,
     signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    transfer_from_pool_vault_to_user_with_hook(
+        authority,
+        from_vault,
+        to,
+        mint,
+        token_program,
+        &[],
+        amount,
+        mint_decimals,
+        signer_seeds,
+    )
+}
+
+/// Like `transfer_from_pool_vault_to_user`, resolving the accounts of the mint's transfer hook
+/// out of `hook_accounts`, see `transfer_checked_with_hook`
+pub fn transfer_from_pool_vault_to_user_with_hook<'a>(
+    authority: AccountInfo<'a>,
+    from_vault: AccountInfo<'a>,
+    to: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    hook_accounts: &[AccountInfo<'a>],
+    amount: u64,
+    mint_decimals: u8,
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
-    token_2022::transfer_checked(
-        CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            token_2022::TransferChecked {
-                from: from_vault,
-                to,
-                authority,
-                mint,
-            },
-            signer_seeds,
-        ),
+    transfer_checked_with_hook(
+        authority,
+        from_vault,
+        to,
+        mint,
+        token_program,
+        hook_accounts,
         amount,
         mint_decimals,
+        signer_seeds,
     )
 }
 
+/// Program of the mint's transfer hook, if it has one
+pub fn get_transfer_hook_program_id(mint_info: &AccountInfo) -> Result<Option<Pubkey>> {
+    if *mint_info.owner == Token::id() {
+        return Ok(None);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(transfer_hook::get_program_id(&mint))
+}
+
+/// `transfer_checked` that, for a mint with a transfer hook, passes the hook program, its
+/// validation account and the extra accounts it lists, all looked up in `hook_accounts`.
+/// The `TransferHookAllowlistEntry` of the hook program has to be among them too, otherwise
+/// the transfer fails. Mints without a hook ignore `hook_accounts`.
+fn transfer_checked_with_hook<'a>(
+    authority: AccountInfo<'a>,
+    from: AccountInfo<'a>,
+    to: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    hook_accounts: &[AccountInfo<'a>],
+    amount: u64,
+    mint_decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let Some(hook_program_id) = get_transfer_hook_program_id(&mint)? else {
+        return token_2022::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_2022::TransferChecked {
+                    from,
+                    to,
+                    authority,
+                    mint,
+                },
+                signer_seeds,
+            ),
+            amount,
+            mint_decimals,
+        );
+    };
+    if !hook_accounts
+        .iter()
+        .any(|info| TransferHookAllowlistEntry::load_checked(info, hook_program_id).is_ok())
+    {
+        return err!(GammaError::TransferHookNotAllowed);
+    }
+    onchain::invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint,
+        to,
+        authority,
+        hook_accounts,
+        amount,
+        mint_decimals,
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
 /// Issue a spl_token `MintTo` instruction.
 pub fn token_mint_to<'a>(
     authority: AccountInfo<'a>,
@@ -186,9 +303,15 @@ pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool>
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
     let extensions = mint.get_extension_types()?;
     for e in extensions {
+        // Transfers check the hook program against the allowlist, see `transfer_checked_with_hook`
         if e != ExtensionType::TransferFeeConfig
             && e != ExtensionType::MetadataPointer
             && e != ExtensionType::TokenMetadata
+            && e != ExtensionType::TransferHook
+            && e != ExtensionType::InterestBearingConfig
+            // Checked against the config's policy, see `check_mint_safety`
+            && e != ExtensionType::PermanentDelegate
+            && e != ExtensionType::DefaultAccountState
         {
             return Ok(false);
         }
@@ -196,6 +319,110 @@ pub fn is_supported_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool>
     Ok(true)
 }
 
+/// Rejects a mint of a new pool the config's `mint_safety_policy` doesn't accept
+pub fn check_mint_safety(
+    mint_account: &InterfaceAccount<Mint>,
+    amm_config: &AmmConfig,
+) -> Result<()> {
+    let policy = amm_config.mint_safety_policy;
+    if policy & REJECT_FREEZE_AUTHORITY != 0 && mint_account.freeze_authority.is_some() {
+        msg!("{} has a freeze authority", mint_account.key());
+        return err!(GammaError::UnsafeMint);
+    }
+    let mint_info = mint_account.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(());
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+    if policy & ALLOW_PERMANENT_DELEGATE == 0 {
+        if let Ok(permanent_delegate) = mint.get_extension::<PermanentDelegate>() {
+            if Option::<Pubkey>::from(permanent_delegate.delegate).is_some() {
+                msg!("{} has a permanent delegate", mint_account.key());
+                return err!(GammaError::UnsafeMint);
+            }
+        }
+    }
+    if policy & ALLOW_DEFAULT_FROZEN == 0 {
+        if let Ok(default_account_state) = mint.get_extension::<DefaultAccountState>() {
+            if default_account_state.state == AccountState::Frozen as u8 {
+                msg!("{} freezes new token accounts", mint_account.key());
+                return err!(GammaError::UnsafeMint);
+            }
+        }
+    }
+    if policy & REJECT_MUTABLE_TRANSFER_FEE != 0 {
+        if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
+            let mutable =
+                Option::<Pubkey>::from(transfer_fee_config.transfer_fee_config_authority).is_some();
+            // The newer fee can be pending, either could be charged from now on
+            let older_fee = &transfer_fee_config.older_transfer_fee;
+            let newer_fee = &transfer_fee_config.newer_transfer_fee;
+            let fee_bps = u16::from(older_fee.transfer_fee_basis_points)
+                .max(u16::from(newer_fee.transfer_fee_basis_points));
+            if mutable && fee_bps > amm_config.max_mutable_transfer_fee_bps {
+                msg!(
+                    "{} has a mutable transfer fee of {} bps",
+                    mint_account.key(),
+                    fee_bps
+                );
+                return err!(GammaError::UnsafeMint);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn is_interest_bearing_mint(mint_info: &AccountInfo) -> Result<bool> {
+    if *mint_info.owner == Token::id() {
+        return Ok(false);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint.get_extension::<InterestBearingConfig>().is_ok())
+}
+
+/// `amount` of token_0 or token_1 with the interest its mint accrued so far applied, still in
+/// base units, the UI amount is that over 10^decimals. Tokens that don't bear interest return
+/// `amount`, interest-bearing ones zero when their mint isn't passed.
+pub fn get_ui_adjusted_amount(
+    pool_state: &PoolState,
+    token_0: bool,
+    mint_info: Option<&AccountInfo>,
+    amount: u64,
+) -> Result<u64> {
+    if !pool_state.is_interest_bearing(token_0) {
+        return Ok(amount);
+    }
+    let Some(mint_info) = mint_info else {
+        return Ok(0);
+    };
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let interest_bearing_config = mint.get_extension::<InterestBearingConfig>()?;
+    let scale = interest_scale(interest_bearing_config, Clock::get()?.unix_timestamp)
+        .ok_or(GammaError::MathOverflow)?;
+    Ok((amount as f64 * scale) as u64)
+}
+
+/// Interest accrued by an interest-bearing mint as token-2022 computes it for UI amounts,
+/// continuously compounded at the average rate until the last rate update and at the current
+/// rate since
+fn interest_scale(config: &InterestBearingConfig, unix_timestamp: i64) -> Option<f64> {
+    const SECONDS_PER_YEAR: f64 = 60. * 60. * 24. * 365.24;
+    let growth = |rate: BasisPoints, timespan: i64| {
+        (f64::from(i16::from(rate)) * timespan as f64 / SECONDS_PER_YEAR / 10_000.).exp()
+    };
+    let pre_update_timespan = i64::from(config.last_update_timestamp)
+        .checked_sub(config.initialization_timestamp.into())?;
+    let post_update_timespan = unix_timestamp.checked_sub(config.last_update_timestamp.into())?;
+    Some(
+        growth(config.pre_update_average_rate, pre_update_timespan)
+            * growth(config.current_rate, post_update_timespan),
+    )
+}
+
 pub fn create_token_account<'a>(
     authority: &AccountInfo<'a>,
     payer: &AccountInfo<'a>,
@@ -242,3 +469,169 @@ pub fn create_token_account<'a>(
         },
     ))
 }
+
+/// Creates the associated token account of `owner` for `mint_account`, doing nothing if it exists
+pub fn create_associated_token_account_idempotent<'a>(
+    payer: &AccountInfo<'a>,
+    associated_token_account: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+    mint_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    associated_token_program: &AccountInfo<'a>,
+) -> Result<()> {
+    require_keys_eq!(
+        *associated_token_program.key,
+        associated_token::ID,
+        GammaError::InvalidInput
+    );
+    associated_token::create_idempotent(CpiContext::new(
+        associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: payer.to_account_info(),
+            associated_token: associated_token_account.to_account_info(),
+            authority: owner.to_account_info(),
+            mint: mint_account.to_account_info(),
+            system_program: system_program.to_account_info(),
+            token_program: token_program.to_account_info(),
+        },
+    ))
+}
+
+/// Whether `authority` can withdraw the transfer fees the mint withholds, which makes transfers
+/// between accounts of `authority` fee free. Mints without a transfer fee count as well.
+pub fn can_withdraw_withheld_fees(mint_info: &AccountInfo, authority: &Pubkey) -> Result<bool> {
+    if *mint_info.owner == Token::id() {
+        return Ok(true);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let can_withdraw = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => {
+            Option::<Pubkey>::from(transfer_fee_config.withdraw_withheld_authority)
+                == Some(*authority)
+        }
+        Err(_) => true,
+    };
+    Ok(can_withdraw)
+}
+
+/// Moves tokens between two token accounts of the pool authority, e.g. fee sweeps, rebalances
+/// or compounding, and returns the amount `to` was credited with. The transfer fee withheld on
+/// the way is withdrawn back into `to` when the pool authority is the mint's withdraw withheld
+/// authority. Otherwise it is lost to the fee collector, the caller must then account for the
+/// returned amount rather than `amount`.
+pub fn transfer_between_pool_accounts<'a>(
+    authority: AccountInfo<'a>,
+    from: AccountInfo<'a>,
+    to: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    amount: u64,
+    mint_decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    let transfer_fee = get_transfer_fee(&mint, amount)?;
+    transfer_from_pool_vault_to_user(
+        authority.clone(),
+        from,
+        to.clone(),
+        mint.clone(),
+        token_program.clone(),
+        amount,
+        mint_decimals,
+        signer_seeds,
+    )?;
+    if transfer_fee == 0 {
+        return Ok(amount);
+    }
+    if !can_withdraw_withheld_fees(&mint, authority.key)? {
+        return Ok(amount
+            .checked_sub(transfer_fee)
+            .ok_or(GammaError::MathOverflow)?);
+    }
+
+    let ix = transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+        token_program.key,
+        mint.key,
+        to.key,
+        authority.key,
+        &[],
+        &[to.key],
+    )?;
+    invoke_signed(
+        &ix,
+        &[mint, to.clone(), authority, to, token_program],
+        signer_seeds,
+    )?;
+    Ok(amount)
+}
+
+/// Moves the transfer fees withheld in token accounts of the pool authority to their mint, so
+/// the accounts can be closed. Anyone can harvest, only the withdraw withheld authority of the
+/// mint can claim the harvested fees.
+pub fn harvest_withheld_fees<'a>(
+    mint: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    token_accounts: Vec<AccountInfo<'a>>,
+) -> Result<()> {
+    if *mint.owner == Token::id() {
+        return Ok(());
+    }
+    {
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        if mint_state.get_extension::<TransferFeeConfig>().is_err() {
+            return Ok(());
+        }
+    }
+    token_2022_extensions::harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.clone(),
+            token_2022_extensions::HarvestWithheldTokensToMint {
+                token_program_id: token_program,
+                mint,
+            },
+        ),
+        token_accounts,
+    )
+}
+
+/// Tops `mint` up to the rent of its size once `metadata` replaces its token metadata, token-2022
+/// grows the account itself but doesn't fund it
+pub fn fund_token_metadata<'a>(
+    payer: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+    metadata: &TokenMetadata,
+) -> Result<()> {
+    let current_metadata_len = {
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        match mint_state.get_variable_len_extension::<TokenMetadata>() {
+            Ok(current) => current.tlv_size_of()?,
+            Err(_) => 0,
+        }
+    };
+    let new_len = mint
+        .data_len()
+        .checked_add(metadata.tlv_size_of()?)
+        .and_then(|len| len.checked_sub(current_metadata_len))
+        .ok_or(GammaError::MathOverflow)?;
+    let lamports_needed = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(mint.lamports());
+    if lamports_needed > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program,
+                system_program::Transfer {
+                    from: payer,
+                    to: mint,
+                },
+            ),
+            lamports_needed,
+        )?;
+    }
+    Ok(())
+}
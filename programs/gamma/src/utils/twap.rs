@@ -0,0 +1,64 @@
+use crate::error::GammaError;
+use crate::states::{Twap, Q32};
+use anchor_lang::prelude::*;
+
+/// A twap for a pair that has no pool of its own, derived from two pools sharing a token
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComposedTwap {
+    /// Price of the base token in the quote token raw amounts, Q32.32
+    pub price_x32: u128,
+    /// The older of the two newest observations, staleness is measured from here
+    pub end_timestamp: u64,
+    /// Seconds covered by both twaps, confidence drops as this shrinks below the window
+    pub covered_seconds: u64,
+}
+
+impl ComposedTwap {
+    pub fn staleness(&self, current_time: u64) -> u64 {
+        current_time.saturating_sub(self.end_timestamp)
+    }
+}
+
+/// Composes the twaps of a base/common pool and a common/quote pool into a base/quote twap,
+/// e.g. TOKEN/SOL and SOL/USDC into a USDC denominated TOKEN twap.
+///
+/// # Arguments
+///
+/// * `base_pool_twap` - Twap of the pool holding the base token and the common token
+/// * `base_is_token_0` - Whether the base token is token_0 of that pool
+/// * `quote_pool_twap` - Twap of the pool holding the common token and the quote token
+/// * `common_is_token_0` - Whether the common token is token_0 of that pool
+///
+pub fn compose_twaps(
+    base_pool_twap: &Twap,
+    base_is_token_0: bool,
+    quote_pool_twap: &Twap,
+    common_is_token_0: bool,
+) -> Result<ComposedTwap> {
+    let base_in_common_x32 = if base_is_token_0 {
+        base_pool_twap.token_0_price_x32
+    } else {
+        base_pool_twap.token_1_price_x32
+    };
+    let common_in_quote_x32 = if common_is_token_0 {
+        quote_pool_twap.token_0_price_x32
+    } else {
+        quote_pool_twap.token_1_price_x32
+    };
+
+    let price_x32 = base_in_common_x32
+        .checked_mul(common_in_quote_x32)
+        .ok_or(GammaError::MathOverflow)?
+        .checked_div(Q32)
+        .ok_or(GammaError::MathOverflow)?;
+
+    Ok(ComposedTwap {
+        price_x32,
+        end_timestamp: base_pool_twap
+            .end_timestamp
+            .min(quote_pool_twap.end_timestamp),
+        covered_seconds: base_pool_twap
+            .covered_seconds()
+            .min(quote_pool_twap.covered_seconds()),
+    })
+}
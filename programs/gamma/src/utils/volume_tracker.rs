@@ -0,0 +1,28 @@
+use crate::error::GammaError;
+use crate::states::VolumeTracker;
+use anchor_lang::prelude::*;
+
+/// Records a swap in the wash trade resistant volume of `pool_state`
+pub fn record_tracked_volume(
+    volume_tracker_account: &AccountInfo<'_>,
+    pool_state: Pubkey,
+    trader: Pubkey,
+    zero_for_one: bool,
+    amount_in: u64,
+    block_timestamp: u64,
+) -> Result<()> {
+    // check: tracker belongs to this program and to the pool
+    require_keys_eq!(*volume_tracker_account.owner, crate::id());
+    require!(volume_tracker_account.is_writable, GammaError::InvalidInput);
+    let mut volume_tracker =
+        VolumeTracker::try_deserialize(&mut &volume_tracker_account.data.borrow()[..])?;
+    require_keys_eq!(volume_tracker.pool_state, pool_state);
+
+    volume_tracker
+        .record_trade(trader, zero_for_one, amount_in, block_timestamp)
+        .ok_or(GammaError::MathOverflow)?;
+
+    let mut data = volume_tracker_account.try_borrow_mut_data()?;
+    volume_tracker.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use anchor_spl::token::TokenAccount;
+use gamma::curve::TradeDirection;
+use gamma::error::GammaError;
+use gamma::instructions::LOCKED_LP_AMOUNT;
+use gamma::states::{BondingCurve, PoolState, Position};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+const SUPPLY: u64 = 1_000_000_000_000_000;
+const VIRTUAL_TOKEN_RESERVE: u64 = 200_000_000_000_000;
+const VIRTUAL_QUOTE_RESERVE: u64 = 30_000_000_000;
+const GRADUATION_QUOTE_AMOUNT: u64 = 85_000_000_000;
+
+/// A launch of the creator's token raising the test env's token_1, and a buyer holding some
+async fn setup() -> (TestEnv, Keypair, Keypair, Pubkey) {
+    let creator = Keypair::new();
+    let buyer = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![creator.pubkey(), buyer.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    let buyer_quote_account = test_env
+        .get_or_create_associated_token_account(buyer.pubkey(), test_env.token_1_mint, &buyer)
+        .await;
+    test_env
+        .mint_base_tokens(buyer_quote_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let token_mint = test_env.create_launch_mint(&creator, SUPPLY, false).await;
+    test_env
+        .try_create_bonding_curve(
+            &creator,
+            amm_index,
+            token_mint,
+            test_env.token_1_mint,
+            VIRTUAL_TOKEN_RESERVE,
+            VIRTUAL_QUOTE_RESERVE,
+            GRADUATION_QUOTE_AMOUNT,
+        )
+        .await
+        .unwrap_transaction();
+    let bonding_curve = test_env.bonding_curve_key(amm_index, token_mint, test_env.token_1_mint);
+
+    (test_env, creator, buyer, bonding_curve)
+}
+
+#[tokio::test]
+async fn should_put_the_whole_supply_on_the_curve_and_aside_for_the_pool() {
+    let (mut test_env, creator, _, bonding_curve) = setup().await;
+
+    let bonding_curve: BondingCurve = test_env.fetch_account(bonding_curve).await;
+    let sale_token_amount = BondingCurve::sale_token_amount(
+        SUPPLY,
+        VIRTUAL_TOKEN_RESERVE,
+        VIRTUAL_QUOTE_RESERVE,
+        GRADUATION_QUOTE_AMOUNT,
+    )
+    .unwrap();
+    assert_eq!(bonding_curve.token_reserve, sale_token_amount);
+    assert_eq!(bonding_curve.pool_token_amount, SUPPLY - sale_token_amount);
+
+    let token_vault: TokenAccount = test_env.fetch_account(bonding_curve.token_vault).await;
+    assert_eq!(token_vault.amount, SUPPLY);
+    let creator_token_account = test_env
+        .get_or_create_associated_token_account(
+            creator.pubkey(),
+            bonding_curve.token_mint,
+            &creator,
+        )
+        .await;
+    let creator_token_account: TokenAccount = test_env.fetch_account(creator_token_account).await;
+    assert_eq!(creator_token_account.amount, 0);
+}
+
+#[tokio::test]
+async fn should_not_launch_a_token_that_can_still_be_minted() {
+    let creator = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![creator.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    let token_mint = test_env.create_launch_mint(&creator, SUPPLY, true).await;
+
+    let result = test_env
+        .try_create_bonding_curve(
+            &creator,
+            amm_index,
+            token_mint,
+            test_env.token_1_mint,
+            VIRTUAL_TOKEN_RESERVE,
+            VIRTUAL_QUOTE_RESERVE,
+            GRADUATION_QUOTE_AMOUNT,
+        )
+        .await;
+    assert_error!(result, GammaError::NotSupportMint);
+}
+
+#[tokio::test]
+async fn should_sell_back_bought_tokens_for_at_most_what_they_cost() {
+    let (mut test_env, _, buyer, bonding_curve) = setup().await;
+    let quote_amount_in = 1_000_000_000;
+
+    test_env
+        .try_trade_bonding_curve(&buyer, bonding_curve, true, quote_amount_in, 0)
+        .await
+        .unwrap_transaction();
+    let bonding_curve_state: BondingCurve = test_env.fetch_account(bonding_curve).await;
+    assert_eq!(bonding_curve_state.quote_reserve, quote_amount_in);
+    let buyer_token_account = test_env
+        .get_or_create_associated_token_account(
+            buyer.pubkey(),
+            bonding_curve_state.token_mint,
+            &buyer,
+        )
+        .await;
+    let bought: TokenAccount = test_env.fetch_account(buyer_token_account).await;
+    assert!(bought.amount > 0);
+
+    // More than the curve pays for them can't be asked for
+    let result = test_env
+        .try_trade_bonding_curve(&buyer, bonding_curve, false, bought.amount, quote_amount_in)
+        .await;
+    assert_error!(result, GammaError::ExceededSlippage);
+
+    test_env
+        .try_trade_bonding_curve(&buyer, bonding_curve, false, bought.amount, 0)
+        .await
+        .unwrap_transaction();
+    let bonding_curve_state: BondingCurve = test_env.fetch_account(bonding_curve).await;
+    assert!(bonding_curve_state.quote_reserve <= 1);
+    assert_eq!(
+        bonding_curve_state.token_reserve,
+        BondingCurve::sale_token_amount(
+            SUPPLY,
+            VIRTUAL_TOKEN_RESERVE,
+            VIRTUAL_QUOTE_RESERVE,
+            GRADUATION_QUOTE_AMOUNT,
+        )
+        .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn should_graduate_into_a_pool_at_the_curve_price_whose_lp_fees_the_creator_claims() {
+    let (mut test_env, creator, buyer, bonding_curve) = setup().await;
+    let admin = get_admin();
+
+    test_env
+        .try_trade_bonding_curve(&buyer, bonding_curve, true, GRADUATION_QUOTE_AMOUNT, 0)
+        .await
+        .unwrap_transaction();
+    let bonding_curve_state: BondingCurve = test_env.fetch_account(bonding_curve).await;
+    assert!(bonding_curve_state.is_complete());
+    // A complete curve stops trading
+    let result = test_env
+        .try_trade_bonding_curve(&buyer, bonding_curve, true, 1_000_000, 0)
+        .await;
+    assert_error!(result, GammaError::BondingCurveComplete);
+
+    let (pool_id, position) = test_env.graduate(&buyer, bonding_curve).await;
+
+    // The pool opens at the curve's last price, quote over tokens
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let token_is_token_0 = pool_state.token_0_mint == bonding_curve_state.token_mint;
+    let (pool_token_amount, pool_quote_amount) = if token_is_token_0 {
+        (
+            pool_state.token_0_vault_amount,
+            pool_state.token_1_vault_amount,
+        )
+    } else {
+        (
+            pool_state.token_1_vault_amount,
+            pool_state.token_0_vault_amount,
+        )
+    };
+    assert_eq!(
+        pool_token_amount,
+        bonding_curve_state.token_reserve + bonding_curve_state.pool_token_amount
+    );
+    let pool_price = u128::from(pool_quote_amount)
+        * u128::from(VIRTUAL_TOKEN_RESERVE + bonding_curve_state.token_reserve);
+    let curve_price = u128::from(VIRTUAL_QUOTE_RESERVE + bonding_curve_state.quote_reserve)
+        * u128::from(pool_token_amount);
+    assert!(pool_price.abs_diff(curve_price) * 1_000_000 < curve_price);
+
+    // The LP is locked for good in the creator's position
+    let position_state: Position = test_env.fetch_account(position).await;
+    assert_eq!(position_state.owner, creator.pubkey());
+    assert_eq!(position_state.unlock_time, u64::MAX);
+    assert_eq!(
+        position_state.lp_tokens,
+        pool_state.lp_supply - LOCKED_LP_AMOUNT
+    );
+
+    // The position's LP fees still reach the creator
+    test_env.update_pool(&admin, pool_id, 8, 0).await;
+    test_env.jump_seconds(100).await;
+    test_env.token_0_mint = pool_state.token_0_mint;
+    test_env.token_1_mint = pool_state.token_1_mint;
+    let trade_direction = if token_is_token_0 {
+        TradeDirection::OneForZero
+    } else {
+        TradeDirection::ZeroForOne
+    };
+    test_env
+        .swap_base_input(&buyer, pool_id, 0, 1_000_000_000, 0, trade_direction)
+        .await;
+    test_env.init_user_pool_liquidity(&creator, pool_id).await;
+    test_env
+        .collect_position_lp_fees(&creator, pool_id, position)
+        .await;
+
+    let creator_quote_account = test_env
+        .get_or_create_associated_token_account(
+            creator.pubkey(),
+            bonding_curve_state.quote_mint,
+            &creator,
+        )
+        .await;
+    let quote_account_before: TokenAccount = test_env.fetch_account(creator_quote_account).await;
+    test_env
+        .try_claim_lp_fees(&creator, pool_id, 0)
+        .await
+        .unwrap_transaction();
+    let quote_account_after: TokenAccount = test_env.fetch_account(creator_quote_account).await;
+    assert!(quote_account_after.amount > quote_account_before.amount);
+}
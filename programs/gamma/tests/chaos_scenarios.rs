@@ -0,0 +1,573 @@
+//! Pathological sequences replayed as named regression tests. Refactors of the curves or the
+//! oracle have to keep every scenario passing.
+
+use anchor_lang::{prelude::Rent, Discriminator};
+use anchor_spl::token_2022::spl_token_2022::{self, extension::StateWithExtensions};
+use gamma::{
+    curve::{ConstantProductCurve, StableSwapCurve, TradeDirection, WeightedCurve},
+    error::GammaError,
+    states::{
+        Observation, ObservationState, PoolState, OBSERVATION_NUM, OBSERVATION_STATE_VERSION,
+        OBSERVATION_UPDATE_DURATION_DEFAULT,
+    },
+};
+use solana_program_test::tokio;
+use solana_sdk::{
+    account::{AccountSharedData, WritableAccount},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+mod utils;
+
+use utils::*;
+
+const PRICE_X32: u128 = 1 << 32;
+
+#[test]
+fn max_size_swap_against_one_lamport_reserves() {
+    let max = u128::from(u64::MAX);
+
+    // The whole u64 range in can't take out the last unit
+    let amount_out = ConstantProductCurve::swap_base_input_without_fees(max, 1, 1).unwrap();
+    assert_eq!(amount_out, 0);
+    // Nor can a base output swap ask for it
+    assert!(ConstantProductCurve::swap_base_output_without_fees(1, 1, 1).is_err());
+
+    let amount_out = StableSwapCurve::swap_base_input_without_fees(max, 1, 1, 100);
+    assert!(amount_out.map_or(true, |amount_out| amount_out == 0));
+    assert!(StableSwapCurve::swap_base_output_without_fees(1, 1, 1, 100).is_err());
+
+    let amount_out = WeightedCurve::swap_base_input_without_fees(max, 1, 1, 8_000, 2_000);
+    assert!(amount_out.map_or(true, |amount_out| amount_out == 0));
+    assert!(WeightedCurve::swap_base_output_without_fees(1, 1, 1, 8_000, 2_000).is_err());
+
+    // Max size swaps against max size reserves must not overflow
+    let amount_out = ConstantProductCurve::swap_base_input_without_fees(max, max, max).unwrap();
+    assert!(amount_out < max);
+    let amount_out = StableSwapCurve::swap_base_input_without_fees(max, max, max, 100).unwrap();
+    assert!(amount_out < max);
+    let amount_out =
+        WeightedCurve::swap_base_input_without_fees(max, max, max, 8_000, 2_000).unwrap();
+    assert!(amount_out < max);
+}
+
+#[test]
+fn clock_regression_leaves_observations_untouched() {
+    let mut observation_state = ObservationState::default();
+    observation_state.update(1_000, PRICE_X32, PRICE_X32).unwrap();
+    observation_state.update(1_100, PRICE_X32, PRICE_X32).unwrap();
+    let index = observation_state.observation_index;
+    let newest = observation_state.observations[usize::from(index)];
+
+    // A validator clock going backwards must not write an observation, nor underflow
+    observation_state.update(900, 2 * PRICE_X32, 2 * PRICE_X32).unwrap();
+    assert_eq_with_copy!(observation_state.observation_index, index);
+    let unchanged = observation_state.observations[usize::from(index)];
+    assert_eq_with_copy!(unchanged.block_timestamp, newest.block_timestamp);
+    assert_eq_with_copy!(
+        unchanged.cumulative_token_0_price_x32,
+        newest.cumulative_token_0_price_x32
+    );
+
+    // A twap asked for a time before the newest observation still averages what it has
+    let twap = observation_state.twap(900, 1_000).unwrap();
+    assert_eq!(twap.token_0_price_x32, PRICE_X32);
+}
+
+#[test]
+fn rebuild_starts_from_newest_observation_not_in_the_future() {
+    let mut observation_state = ObservationState::default();
+    observation_state.update(1_000, PRICE_X32, PRICE_X32).unwrap();
+    observation_state.update(1_100, PRICE_X32, PRICE_X32).unwrap();
+    // A corrupted timestamp far in the future
+    observation_state.update(u64::MAX / 2, PRICE_X32, PRICE_X32).unwrap();
+
+    let mut data = vec![0u8; 8];
+    data.extend_from_slice(bytemuck::bytes_of(&observation_state));
+    let latest = ObservationState::latest_observation(&data, 2_000).unwrap();
+    assert_eq_with_copy!(latest.block_timestamp, 1_100);
+    assert_eq_with_copy!(latest.cumulative_token_0_price_x32, 100 * PRICE_X32);
+
+    assert!(ObservationState::latest_observation(&data[..100], 2_000).is_none());
+
+    // Accounts from before the log price have shorter observations
+    let mut legacy_data = vec![0u8; ObservationState::LEGACY_LEN];
+    legacy_data[43..51].copy_from_slice(&1_100u64.to_le_bytes());
+    legacy_data[51..67].copy_from_slice(&(100 * PRICE_X32).to_le_bytes());
+    assert!(ObservationState::is_legacy(&legacy_data));
+    let latest = ObservationState::latest_observation(&legacy_data, 2_000).unwrap();
+    assert_eq_with_copy!(latest.block_timestamp, 1_100);
+    assert_eq_with_copy!(latest.cumulative_token_0_price_x32, 100 * PRICE_X32);
+    assert_eq_with_copy!(latest.cumulative_token_0_log_price_x32, 0);
+}
+
+#[test]
+fn observation_wraparound_from_last_index_to_zero() {
+    let mut observation_state = ObservationState::default();
+    let mut block_timestamp = 1_000;
+    observation_state
+        .update(block_timestamp, PRICE_X32, PRICE_X32)
+        .unwrap();
+    for _ in 0..OBSERVATION_NUM - 1 {
+        block_timestamp += OBSERVATION_UPDATE_DURATION_DEFAULT;
+        observation_state
+            .update(block_timestamp, PRICE_X32, PRICE_X32)
+            .unwrap();
+    }
+    assert_eq_with_copy!(
+        observation_state.observation_index,
+        (OBSERVATION_NUM - 1) as u16
+    );
+
+    block_timestamp += OBSERVATION_UPDATE_DURATION_DEFAULT;
+    observation_state
+        .update(block_timestamp, PRICE_X32, PRICE_X32)
+        .unwrap();
+    assert_eq_with_copy!(observation_state.observation_index, 0);
+    let last = observation_state.observations[OBSERVATION_NUM - 1];
+    let first = observation_state.observations[0];
+    assert_eq!(
+        first.cumulative_token_0_price_x32 - last.cumulative_token_0_price_x32,
+        PRICE_X32 * u128::from(OBSERVATION_UPDATE_DURATION_DEFAULT)
+    );
+
+    // A window spanning the wrap still averages to the constant price
+    let twap = observation_state
+        .twap(block_timestamp, 10 * OBSERVATION_UPDATE_DURATION_DEFAULT)
+        .unwrap();
+    assert_eq!(twap.token_0_price_x32, PRICE_X32);
+    assert_eq!(
+        twap.covered_seconds(),
+        10 * OBSERVATION_UPDATE_DURATION_DEFAULT
+    );
+}
+
+#[test]
+fn interpolated_twap_between_and_after_observations() {
+    let mut observation_state = ObservationState::default();
+    observation_state.update(1_000, PRICE_X32, PRICE_X32).unwrap();
+    observation_state
+        .update(1_100, 3 * PRICE_X32, PRICE_X32 / 3)
+        .unwrap();
+
+    // Half the window sits between the observations at price 1, half after the newest one at 3
+    let twap = observation_state
+        .interpolated_twap(&[], 1_150, 100, 3 * PRICE_X32, PRICE_X32 / 3)
+        .unwrap();
+    assert_eq!(twap.token_0_price_x32, 2 * PRICE_X32);
+    assert_eq!(twap.start_timestamp, 1_050);
+    assert_eq!(twap.covered_seconds(), 100);
+
+    // A window starting before the first observation can't be interpolated
+    assert!(observation_state
+        .interpolated_twap(&[], 1_150, 200, 3 * PRICE_X32, PRICE_X32 / 3)
+        .is_err());
+}
+
+#[test]
+fn geometric_twap_weighs_price_spikes_less() {
+    let mut observation_state = ObservationState::default();
+    observation_state.update(1_000, PRICE_X32, PRICE_X32).unwrap();
+    observation_state.update(1_100, PRICE_X32, PRICE_X32).unwrap();
+
+    // Half the window at price 1, half at 4: the arithmetic mean is 2.5, the geometric one 2
+    let twap = observation_state
+        .interpolated_twap(&[], 1_150, 100, 4 * PRICE_X32, PRICE_X32 / 4)
+        .unwrap();
+    assert_eq!(twap.token_0_price_x32, 5 * PRICE_X32 / 2);
+    let geometric_twap = observation_state
+        .geometric_twap(&[], 1_150, 100, 4 * PRICE_X32)
+        .unwrap();
+    assert_eq!(geometric_twap.token_0_price_x32, 2 * PRICE_X32);
+    assert_eq!(geometric_twap.token_1_price_x32, PRICE_X32 / 2);
+}
+
+#[test]
+fn archive_keeps_observations_overwritten_in_the_ring() {
+    let mut observation_state = ObservationState::default();
+    let mut archive = [Observation::default(); 50];
+    let mut block_timestamp = 1_000;
+    observation_state
+        .update_with_archive(&mut archive, block_timestamp, PRICE_X32, PRICE_X32)
+        .unwrap();
+    for _ in 0..OBSERVATION_NUM + 59 {
+        block_timestamp += OBSERVATION_UPDATE_DURATION_DEFAULT;
+        observation_state
+            .update_with_archive(&mut archive, block_timestamp, PRICE_X32, PRICE_X32)
+            .unwrap();
+    }
+
+    // 60 observations were overwritten, the archive wrapped and kept the 50 newest of them
+    assert_eq_with_copy!(observation_state.archive_cardinality, 50);
+    assert_eq_with_copy!(observation_state.archive_index, 10);
+    let oldest_archived = archive[10].block_timestamp;
+    assert_eq!(
+        oldest_archived,
+        1_000 + 10 * OBSERVATION_UPDATE_DURATION_DEFAULT
+    );
+
+    // A window older than the ring alone only averages with the archive
+    let window = (OBSERVATION_NUM as u64 + 20) * OBSERVATION_UPDATE_DURATION_DEFAULT;
+    assert!(observation_state
+        .interpolated_twap(&[], block_timestamp, window, PRICE_X32, PRICE_X32)
+        .is_err());
+    let twap = observation_state
+        .interpolated_twap(&archive, block_timestamp, window, PRICE_X32, PRICE_X32)
+        .unwrap();
+    assert_eq!(twap.token_0_price_x32, PRICE_X32);
+    assert_eq!(twap.covered_seconds(), window);
+}
+
+#[test]
+fn legacy_observations_migrate_in_place() {
+    let mut observation_state = ObservationState::default();
+    let mut archive = [Observation::default(); 20];
+    let mut block_timestamp = 1_000;
+    for _ in 0..OBSERVATION_NUM + 30 {
+        observation_state
+            .update_with_archive(&mut archive, block_timestamp, 2 * PRICE_X32, PRICE_X32 / 2)
+            .unwrap();
+        block_timestamp += OBSERVATION_UPDATE_DURATION_DEFAULT;
+    }
+
+    let mut data = legacy_observation_account(&observation_state, &archive);
+    assert!(ObservationState::is_legacy(&data));
+    let cardinality = ObservationState::legacy_cardinality(data.len());
+    assert_eq!(cardinality, OBSERVATION_NUM + 20);
+    // Not grown yet
+    assert!(ObservationState::migrate_legacy(&mut data, cardinality).is_err());
+    data.resize(ObservationState::space(cardinality), 0);
+    ObservationState::migrate_legacy(&mut data, cardinality).unwrap();
+    assert!(!ObservationState::is_legacy(&data));
+
+    let (migrated, migrated_archive) = data[8..].split_at(ObservationState::LEN - 8);
+    let migrated: &ObservationState = bytemuck::from_bytes(migrated);
+    let migrated_archive: &[Observation] = bytemuck::cast_slice(migrated_archive);
+    assert_eq_with_copy!(
+        migrated.observation_index,
+        observation_state.observation_index
+    );
+    assert_eq_with_copy!(migrated.archive_index, 10);
+    assert_eq_with_copy!(migrated.archive_cardinality, 20);
+    assert_eq_with_copy!(migrated.version, OBSERVATION_STATE_VERSION);
+
+    // The log prices are rebuilt from the oldest observation, which starts them from zero
+    let oldest_log_price_x32 = archive[10].cumulative_token_0_log_price_x32;
+    let observations = migrated.observations.iter().chain(migrated_archive);
+    let originals = observation_state.observations.iter().chain(archive.iter());
+    for (observation, original) in observations.zip(originals) {
+        assert_eq_with_copy!(observation.block_timestamp, original.block_timestamp);
+        assert_eq_with_copy!(
+            observation.cumulative_token_0_price_x32,
+            original.cumulative_token_0_price_x32
+        );
+        assert_eq_with_copy!(
+            observation.cumulative_token_1_price_x32,
+            original.cumulative_token_1_price_x32
+        );
+        assert_eq_with_copy!(
+            observation.cumulative_token_0_log_price_x32,
+            original.cumulative_token_0_log_price_x32 - oldest_log_price_x32
+        );
+    }
+
+    // A window reaching into the archive averages the migrated log prices
+    let window = (OBSERVATION_NUM as u64 + 10) * OBSERVATION_UPDATE_DURATION_DEFAULT;
+    let geometric_twap = migrated
+        .geometric_twap(migrated_archive, block_timestamp, window, 2 * PRICE_X32)
+        .unwrap();
+    assert_eq!(geometric_twap.token_0_price_x32, 2 * PRICE_X32);
+}
+
+#[tokio::test]
+async fn max_size_swap_against_dust_pool() {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 100, 20, 5, 0)
+        .await;
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(
+            user_token_0_account,
+            u64::MAX / 2 + 1000,
+            test_env.token_0_mint,
+        )
+        .await;
+    let user_token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_1_account, 100000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            1000,
+            2000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    let result = test_env
+        .try_swap_base_input(
+            &user,
+            pool_id,
+            amm_index,
+            u64::MAX / 2,
+            0,
+            TradeDirection::ZeroForOne,
+        )
+        .await;
+    // Failing is fine, draining the output reserve is not
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    if result.unwrap().result.is_ok() {
+        assert!({ pool_state.token_1_vault_amount } > 0);
+    } else {
+        assert_eq_with_copy!(pool_state.token_1_vault_amount, 2000);
+    }
+}
+
+#[tokio::test]
+async fn transfer_fee_epoch_change_between_swaps() {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    // Swap one of the pool mints for a token 2022 mint with a 1% transfer fee
+    let fee_mint = Keypair::new();
+    let mint_authority = test_env.mint_authority.pubkey();
+    test_env
+        .create_token_2022_mint_with_transfer_fee(&fee_mint, &mint_authority, 6, 100, u64::MAX)
+        .await;
+    if fee_mint.pubkey() < test_env.token_1_mint {
+        test_env.token_0_mint = fee_mint.pubkey();
+    } else {
+        test_env.token_0_mint = test_env.token_1_mint;
+        test_env.token_1_mint = fee_mint.pubkey();
+    }
+
+    test_env
+        .create_config(&admin, amm_index, 100, 20, 5, 0)
+        .await;
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let user_token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(user_token_account, 100000000000000, mint)
+            .await;
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            20000000000000,
+            10000000000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+    test_env
+        .swap_base_input(
+            &user,
+            pool_id,
+            amm_index,
+            10000000,
+            0,
+            TradeDirection::ZeroForOne,
+        )
+        .await;
+
+    // The new fee kicks in two epochs later, between the swaps below and the ones above
+    test_env.set_transfer_fee(fee_mint.pubkey(), 500, u64::MAX).await;
+    test_env.jump_epochs(2).await;
+    test_env.jump_seconds(100).await;
+
+    for trade_direction in [TradeDirection::ZeroForOne, TradeDirection::OneForZero] {
+        test_env
+            .swap_base_input(&user, pool_id, amm_index, 10000000, 0, trade_direction)
+            .await;
+        test_env.jump_seconds(100).await;
+    }
+
+    // Whatever the fee was at each swap, the vaults must hold everything the pool accounts for
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let vault_0_amount = token_account_amount(&mut test_env, pool_state.token_0_vault).await;
+    let vault_1_amount = token_account_amount(&mut test_env, pool_state.token_1_vault).await;
+    assert!(
+        vault_0_amount
+            >= pool_state.token_0_vault_amount
+                + pool_state.protocol_fees_token_0
+                + pool_state.fund_fees_token_0
+                + pool_state.segmenter_kickbacks_token_0
+    );
+    assert!(
+        vault_1_amount
+            >= pool_state.token_1_vault_amount
+                + pool_state.protocol_fees_token_1
+                + pool_state.fund_fees_token_1
+                + pool_state.segmenter_kickbacks_token_1
+    );
+}
+
+#[tokio::test]
+async fn legacy_observation_account_migrates_before_swapping() {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 100, 20, 5, 0)
+        .await;
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let user_token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(user_token_account, 100000000000000, mint)
+            .await;
+    }
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    for trade_direction in [TradeDirection::ZeroForOne, TradeDirection::OneForZero] {
+        test_env.jump_seconds(100).await;
+        test_env
+            .swap_base_input(&user, pool_id, amm_index, 1000000, 0, trade_direction)
+            .await;
+    }
+
+    // Put the observations back in the layout from before the log price
+    let observation_key = test_env
+        .fetch_account::<PoolState>(pool_id)
+        .await
+        .observation_key;
+    let observation_state: ObservationState = test_env.fetch_account(observation_key).await;
+    let legacy_data = legacy_observation_account(&observation_state, &[]);
+    let mut account = AccountSharedData::new(
+        Rent::default().minimum_balance(legacy_data.len()),
+        legacy_data.len(),
+        &gamma::id(),
+    );
+    account.set_data_from_slice(&legacy_data);
+    test_env
+        .program_test_context
+        .set_account(&observation_key, &account);
+
+    test_env.jump_seconds(100).await;
+    let result = test_env
+        .try_swap_base_input(
+            &user,
+            pool_id,
+            amm_index,
+            1000000,
+            0,
+            TradeDirection::ZeroForOne,
+        )
+        .await;
+    assert_error!(result, GammaError::ObservationStateNotMigrated);
+
+    test_env
+        .try_migrate_observation_state(&user, pool_id)
+        .await
+        .unwrap_transaction();
+    let migrated = test_env
+        .get_account_info(observation_key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(migrated.data.len(), ObservationState::LEN);
+    let migrated: ObservationState = test_env.fetch_account(observation_key).await;
+    assert_eq_with_copy!(
+        migrated.observation_index,
+        observation_state.observation_index
+    );
+    for (observation, original) in migrated
+        .observations
+        .iter()
+        .zip(observation_state.observations.iter())
+    {
+        assert_eq_with_copy!(observation.block_timestamp, original.block_timestamp);
+        assert_eq_with_copy!(
+            observation.cumulative_token_0_price_x32,
+            original.cumulative_token_0_price_x32
+        );
+        assert_eq_with_copy!(
+            observation.cumulative_token_1_price_x32,
+            original.cumulative_token_1_price_x32
+        );
+    }
+
+    // Migrating again leaves the account as it is, and the pool swaps again
+    test_env
+        .try_migrate_observation_state(&user, pool_id)
+        .await
+        .unwrap_transaction();
+    test_env
+        .try_swap_base_input(
+            &user,
+            pool_id,
+            amm_index,
+            1000000,
+            0,
+            TradeDirection::ZeroForOne,
+        )
+        .await
+        .unwrap_transaction();
+    let observation_state: ObservationState = test_env.fetch_account(observation_key).await;
+    let observation_index = observation_state.observation_index;
+    assert_ne!(observation_index, { migrated.observation_index });
+}
+
+/// The account data of the layout from before observations kept the log price, holding the
+/// observations of `observation_state` and `archive`
+fn legacy_observation_account(
+    observation_state: &ObservationState,
+    archive: &[Observation],
+) -> Vec<u8> {
+    let legacy_bytes = |observation: &Observation| {
+        bytemuck::bytes_of(observation)[..Observation::LEGACY_LEN].to_vec()
+    };
+    let mut data = ObservationState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&bytemuck::bytes_of(observation_state)[..1 + 2 + 32]);
+    for observation in observation_state.observations.iter() {
+        data.extend(legacy_bytes(observation));
+    }
+    data.extend({ observation_state.archive_index }.to_le_bytes());
+    data.extend({ observation_state.archive_cardinality }.to_le_bytes());
+    data.extend([0u8; 3 * 8]);
+    for observation in archive {
+        data.extend(legacy_bytes(observation));
+    }
+    data
+}
+
+async fn token_account_amount(test_env: &mut TestEnv, address: Pubkey) -> u64 {
+    let account = test_env.get_account_info(address).await.unwrap().unwrap();
+    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
@@ -0,0 +1,131 @@
+use anchor_spl::token_interface::TokenAccount;
+use gamma::curve::TradeDirection;
+use gamma::error::GammaError;
+use gamma::states::{PoolState, UserPoolLiquidity};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+/// A pool created by the user. Returns the user, another account and the pool.
+async fn setup() -> (TestEnv, Keypair, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let other = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), other.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let user_token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(user_token_account, 100000000000000, mint)
+            .await;
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000000,
+            100000000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    (test_env, user, other, pool_id)
+}
+
+/// Withdraws all the LP the user owns
+async fn withdraw_all(test_env: &mut TestEnv, user: &Keypair, pool_id: Pubkey) {
+    let user_pool_liquidity: UserPoolLiquidity = test_env
+        .fetch_account(test_env.user_pool_liquidity_key(pool_id, user.pubkey()))
+        .await;
+    test_env
+        .withdraw(
+            user,
+            pool_id,
+            0,
+            user_pool_liquidity.lp_tokens_owned.try_into().unwrap(),
+            0,
+            0,
+        )
+        .await;
+}
+
+#[tokio::test]
+async fn should_close_a_pool_every_lp_withdrew_from() {
+    let (mut test_env, user, other, pool_id) = setup().await;
+    withdraw_all(&mut test_env, &user, pool_id).await;
+
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let creator_token_0 = test_env
+        .get_or_create_associated_token_account(user.pubkey(), pool_state.token_0_mint, &user)
+        .await;
+    let creator_token_0_before: TokenAccount = test_env.fetch_account(creator_token_0).await;
+    let vault_0: TokenAccount = test_env.fetch_account(pool_state.token_0_vault).await;
+    assert!(vault_0.amount > 0);
+    let creator_lamports_before = test_env
+        .get_account_info(user.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+
+    // Anyone can close it
+    test_env
+        .try_close_pool(&other, pool_id)
+        .await
+        .unwrap_transaction();
+
+    for account in [
+        pool_id,
+        pool_state.observation_key,
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+    ] {
+        assert!(test_env.get_account_info(account).await.unwrap().is_none());
+    }
+    // The dust of the locked LP and the rent go to the creator
+    let creator_token_0_after: TokenAccount = test_env.fetch_account(creator_token_0).await;
+    assert_eq!(
+        creator_token_0_after.amount - creator_token_0_before.amount,
+        vault_0.amount
+    );
+    let creator_lamports_after = test_env
+        .get_account_info(user.pubkey())
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+    assert!(creator_lamports_after > creator_lamports_before);
+}
+
+#[tokio::test]
+async fn should_not_close_a_pool_with_liquidity() {
+    let (mut test_env, user, _, pool_id) = setup().await;
+
+    let result = test_env.try_close_pool(&user, pool_id).await;
+    assert_error!(result, GammaError::PoolNotEmpty);
+}
+
+#[tokio::test]
+async fn should_not_close_a_pool_owing_fees() {
+    let (mut test_env, user, _, pool_id) = setup().await;
+    test_env
+        .swap_base_input(&user, pool_id, 0, 1000000000, 0, TradeDirection::ZeroForOne)
+        .await;
+    withdraw_all(&mut test_env, &user, pool_id).await;
+
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert!({ pool_state.protocol_fees_token_0 } > 0);
+    let result = test_env.try_close_pool(&user, pool_id).await;
+    assert_error!(result, GammaError::PoolNotEmpty);
+}
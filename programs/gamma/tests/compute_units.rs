@@ -0,0 +1,152 @@
+//! Measures the compute units of the instructions aggregators route through against the budgets
+//! published in `gamma::router_metadata`, so a change making them heavier also moves the budgets.
+
+use anchor_lang::InstructionData;
+use gamma::curve::TradeDirection;
+use gamma::router_metadata::*;
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+/// Compute units a successful transaction consumed
+#[track_caller]
+fn compute_units_consumed(result: ProcessTransactionResult) -> u32 {
+    let result = result.expect("Failed to process transaction");
+    result.result.expect("Failed to perform transaction");
+    result
+        .metadata
+        .expect("Missing transaction metadata")
+        .compute_units_consumed
+        .try_into()
+        .unwrap()
+}
+
+/// A pool between a token 2022 mint with a 1% transfer fee and a token mint, the most expensive
+/// transfers the pool handles without a transfer hook
+async fn setup() -> (TestEnv, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    let fee_mint = Keypair::new();
+    let mint_authority = test_env.mint_authority.pubkey();
+    test_env
+        .create_token_2022_mint_with_transfer_fee(&fee_mint, &mint_authority, 6, 100, u64::MAX)
+        .await;
+    if fee_mint.pubkey() < test_env.token_1_mint {
+        test_env.token_0_mint = fee_mint.pubkey();
+    } else {
+        test_env.token_0_mint = test_env.token_1_mint;
+        test_env.token_1_mint = fee_mint.pubkey();
+    }
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let user_token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(user_token_account, 100000000000000, mint)
+            .await;
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            20000000000000,
+            10000000000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    (test_env, user, pool_id)
+}
+
+#[tokio::test]
+async fn swaps_stay_within_their_compute_budget() {
+    let (mut test_env, user, pool_id) = setup().await;
+
+    for trade_direction in [TradeDirection::ZeroForOne, TradeDirection::OneForZero] {
+        let result = test_env
+            .try_swap_base_input(&user, pool_id, 0, 10000000, 0, trade_direction)
+            .await;
+        let compute_units = compute_units_consumed(result);
+        assert!(compute_units <= SWAP_MAX_COMPUTE_UNITS, "{compute_units}");
+        test_env.jump_seconds(100).await;
+
+        // Same accounts as the base input swap
+        let mut swap_base_output = test_env
+            .swap_base_input_instruction(&user, pool_id, 0, 0, 0, trade_direction)
+            .await;
+        swap_base_output.data = gamma::instruction::SwapBaseOutput {
+            amount_out: 1000000,
+            max_amount_in: u64::MAX,
+        }
+        .data();
+        let result = test_env
+            .process_instructions(&[swap_base_output], &user)
+            .await;
+        let compute_units = compute_units_consumed(result);
+        assert!(compute_units <= SWAP_MAX_COMPUTE_UNITS, "{compute_units}");
+        test_env.jump_seconds(100).await;
+    }
+}
+
+#[tokio::test]
+async fn swap_route_stays_within_its_compute_budget() {
+    let (mut test_env, user, pool_id) = setup().await;
+
+    let mut swap_route = get_instruction(
+        gamma::instruction::SwapRoute {
+            amount_in: 10000000,
+            minimum_amount_out: 0,
+        },
+        gamma::accounts::SwapRoute {
+            payer: user.pubkey(),
+            instructions_sysvar: None,
+        },
+    );
+    for trade_direction in [TradeDirection::ZeroForOne, TradeDirection::OneForZero] {
+        let hop = test_env
+            .swap_base_input_instruction(&user, pool_id, 0, 0, 0, trade_direction)
+            .await;
+        swap_route.accounts.extend(hop.accounts);
+    }
+    let result = test_env.process_instructions(&[swap_route], &user).await;
+    let compute_units = compute_units_consumed(result);
+    assert!(
+        compute_units <= swap_route_max_compute_units(2),
+        "{compute_units}"
+    );
+}
+
+#[tokio::test]
+async fn deposit_and_withdraw_stay_within_their_compute_budget() {
+    let (mut test_env, user, pool_id) = setup().await;
+
+    let result = test_env
+        .try_deposit(&user, pool_id, 0, 1000000000, u64::MAX, u64::MAX)
+        .await;
+    let compute_units = compute_units_consumed(result);
+    assert!(
+        compute_units <= DEPOSIT_MAX_COMPUTE_UNITS,
+        "{compute_units}"
+    );
+
+    let result = test_env
+        .try_withdraw(&user, pool_id, 0, 1000000000, 0, 0, vec![])
+        .await;
+    let compute_units = compute_units_consumed(result);
+    assert!(
+        compute_units <= WITHDRAW_MAX_COMPUTE_UNITS,
+        "{compute_units}"
+    );
+}
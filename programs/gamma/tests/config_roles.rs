@@ -0,0 +1,137 @@
+use anchor_lang::prelude::Pubkey;
+use gamma::error::GammaError;
+use gamma::states::{AmmConfig, ConfigRole, PoolState, PoolStatusBitIndex, AMM_CONFIG_SEED};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+async fn setup(authority: &Keypair) -> (TestEnv, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey(), authority.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_0_account, 100000000000000, test_env.token_0_mint)
+        .await;
+    let user_token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_1_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    (test_env, admin, pool_id)
+}
+
+#[tokio::test]
+async fn should_let_the_pause_authority_pause_pools_and_nothing_else() {
+    let pause_authority = Keypair::new();
+    let (mut test_env, admin, pool_id) = setup(&pause_authority).await;
+
+    // Only the admin hands out roles
+    let result = test_env
+        .try_set_config_role(
+            &pause_authority,
+            0,
+            ConfigRole::Pause,
+            pause_authority.pubkey(),
+        )
+        .await;
+    assert_error!(result, GammaError::InvalidOwner);
+    test_env
+        .try_set_config_role(&admin, 0, ConfigRole::Pause, pause_authority.pubkey())
+        .await
+        .unwrap_transaction();
+
+    // The role is only found through the config's roles
+    let result = test_env
+        .try_emergency_pause(&pause_authority, pool_id, 0, 1 << 2, 100, false)
+        .await;
+    assert_error!(result, GammaError::InvalidOwner);
+    test_env
+        .try_emergency_pause(&pause_authority, pool_id, 0, 1 << 2, 100, true)
+        .await
+        .unwrap_transaction();
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+
+    let result = test_env
+        .try_update_amm_config(&pause_authority, 0, 7, 1000, true)
+        .await;
+    assert_error!(result, GammaError::InvalidOwner);
+}
+
+#[tokio::test]
+async fn should_keep_the_fee_owners_from_the_config_authority() {
+    let config_authority = Keypair::new();
+    let (mut test_env, admin, _) = setup(&config_authority).await;
+    test_env
+        .try_set_config_role(&admin, 0, ConfigRole::Config, config_authority.pubkey())
+        .await
+        .unwrap_transaction();
+
+    test_env
+        .try_update_amm_config(&config_authority, 0, 7, 1000, true)
+        .await
+        .unwrap_transaction();
+    let amm_config_key = Pubkey::find_program_address(
+        &[AMM_CONFIG_SEED.as_bytes(), &0u16.to_be_bytes()],
+        &gamma::ID,
+    )
+    .0;
+    let amm_config: AmmConfig = test_env.fetch_account(amm_config_key).await;
+    assert_eq!(amm_config.max_open_time, 1000);
+
+    // Moving the fee owners would hand the fees to the config authority
+    for param in [3, 4] {
+        let result = test_env
+            .try_update_amm_config(&config_authority, 0, param, 0, true)
+            .await;
+        assert_error!(result, GammaError::InvalidOwner);
+    }
+}
+
+#[tokio::test]
+async fn should_take_a_role_back_to_the_admin_with_the_default_pubkey() {
+    let pause_authority = Keypair::new();
+    let (mut test_env, admin, pool_id) = setup(&pause_authority).await;
+    test_env
+        .try_set_config_role(&admin, 0, ConfigRole::Pause, pause_authority.pubkey())
+        .await
+        .unwrap_transaction();
+    test_env
+        .try_set_config_role(&admin, 0, ConfigRole::Pause, Pubkey::default())
+        .await
+        .unwrap_transaction();
+
+    let result = test_env
+        .try_emergency_pause(&pause_authority, pool_id, 0, 1 << 2, 100, true)
+        .await;
+    assert_error!(result, GammaError::InvalidOwner);
+    test_env
+        .try_emergency_pause(&admin, pool_id, 0, 1 << 2, 100, true)
+        .await
+        .unwrap_transaction();
+}
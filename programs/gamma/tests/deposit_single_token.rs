@@ -0,0 +1,141 @@
+use anchor_spl::token_interface::TokenAccount;
+use gamma::error::GammaError;
+use gamma::states::{PoolState, UserPoolLiquidity};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+/// A pool and a depositor holding both of its tokens. Returns the depositor and the pool.
+async fn setup() -> (TestEnv, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let depositor = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), depositor.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    for owner in [&user, &depositor] {
+        for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+            let token_account = test_env
+                .get_or_create_associated_token_account(owner.pubkey(), mint, owner)
+                .await;
+            test_env
+                .mint_base_tokens(token_account, 100000000000000, mint)
+                .await;
+        }
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000000,
+            100000000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.init_user_pool_liquidity(&depositor, pool_id).await;
+    test_env.jump_seconds(100).await;
+
+    (test_env, depositor, pool_id)
+}
+
+/// Balances of the user's token_0 and token_1 accounts
+async fn token_balances(test_env: &mut TestEnv, user: &Keypair) -> (u64, u64) {
+    let mut balances = [0; 2];
+    for (balance, mint) in balances
+        .iter_mut()
+        .zip([test_env.token_0_mint, test_env.token_1_mint])
+    {
+        let token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, user)
+            .await;
+        let token_account: TokenAccount = test_env.fetch_account(token_account).await;
+        *balance = token_account.amount;
+    }
+    (balances[0], balances[1])
+}
+
+#[tokio::test]
+async fn should_deposit_a_single_token() {
+    let (mut test_env, depositor, pool_id) = setup().await;
+    let maximum_amount_in = 1000000000;
+
+    for token_0 in [true, false] {
+        let pool_state_before: PoolState = test_env.fetch_account(pool_id).await;
+        let user_pool_liquidity_before: UserPoolLiquidity = test_env
+            .fetch_account(test_env.user_pool_liquidity_key(pool_id, depositor.pubkey()))
+            .await;
+        let balances_before = token_balances(&mut test_env, &depositor).await;
+
+        test_env
+            .try_deposit_single_token(&depositor, pool_id, token_0, maximum_amount_in, 1)
+            .await
+            .unwrap_transaction();
+
+        let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+        let user_pool_liquidity: UserPoolLiquidity = test_env
+            .fetch_account(test_env.user_pool_liquidity_key(pool_id, depositor.pubkey()))
+            .await;
+        let balances = token_balances(&mut test_env, &depositor).await;
+        let lp_token_amount = pool_state.lp_supply - pool_state_before.lp_supply;
+        assert!(lp_token_amount > 0);
+        assert_eq!(
+            user_pool_liquidity.lp_tokens_owned - user_pool_liquidity_before.lp_tokens_owned,
+            u128::from(lp_token_amount)
+        );
+
+        // Only the deposited token leaves the owner, at most the maximum
+        let (spent, other_token_spent, deposited) = if token_0 {
+            (
+                balances_before.0 - balances.0,
+                balances_before.1 - balances.1,
+                user_pool_liquidity.token_0_deposited
+                    - user_pool_liquidity_before.token_0_deposited,
+            )
+        } else {
+            (
+                balances_before.1 - balances.1,
+                balances_before.0 - balances.0,
+                user_pool_liquidity.token_1_deposited
+                    - user_pool_liquidity_before.token_1_deposited,
+            )
+        };
+        assert!(spent > 0 && spent <= maximum_amount_in);
+        assert_eq!(other_token_spent, 0);
+        assert_eq!(deposited, u128::from(spent));
+        test_env.jump_seconds(100).await;
+    }
+}
+
+#[tokio::test]
+async fn should_not_deposit_a_single_token_below_the_minimum_lp() {
+    let (mut test_env, depositor, pool_id) = setup().await;
+    let maximum_amount_in = 1000000000;
+
+    let pool_state_before: PoolState = test_env.fetch_account(pool_id).await;
+    test_env
+        .try_deposit_single_token(&depositor, pool_id, true, maximum_amount_in, 1)
+        .await
+        .unwrap_transaction();
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let lp_token_amount = pool_state.lp_supply - pool_state_before.lp_supply;
+    test_env.jump_seconds(100).await;
+
+    // The same deposit can't pay for twice the lp
+    let result = test_env
+        .try_deposit_single_token(
+            &depositor,
+            pool_id,
+            true,
+            maximum_amount_in,
+            lp_token_amount * 2,
+        )
+        .await;
+    assert_error!(result, GammaError::ExceededSlippage);
+}
@@ -0,0 +1,444 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::TokenAccount;
+use gamma::curve::TradeDirection;
+use gamma::error::GammaError;
+use gamma::instructions::LOCKED_LP_AMOUNT;
+use gamma::states::{
+    EmergencyEscrow, PoolState, PoolStatusBitIndex, Position, UserPoolLiquidity,
+    EMERGENCY_ESCROW_TIMELOCK, USER_POOL_LIQUIDITY_SEED, WITHDRAW_GRACE_PERIOD,
+};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+/// Status pausing deposits and swaps, withdrawals stay open
+const TRADING_PAUSED: u64 =
+    1 << PoolStatusBitIndex::Deposit as u64 | 1 << PoolStatusBitIndex::Swap as u64;
+
+/// Pauses trading and waits for the withdraw grace period to pass, after which the pool can go
+/// into escrow
+async fn pause_past_withdraw_grace_period(
+    test_env: &mut TestEnv,
+    admin: &Keypair,
+    pool_id: Pubkey,
+) {
+    test_env
+        .update_pool(admin, pool_id, 0, TRADING_PAUSED)
+        .await;
+    test_env.jump_seconds(WITHDRAW_GRACE_PERIOD as i64).await;
+}
+
+/// A pool of the user's liquidity and the depositor's, which deposited `lp_deposit_amount` LP
+async fn setup(lp_deposit_amount: u64) -> (TestEnv, Keypair, Keypair, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let depositor = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), depositor.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    for owner in [&user, &depositor] {
+        for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+            let token_account = test_env
+                .get_or_create_associated_token_account(owner.pubkey(), mint, owner)
+                .await;
+            test_env
+                .mint_base_tokens(token_account, 100000000000000, mint)
+                .await;
+        }
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    test_env.init_user_pool_liquidity(&depositor, pool_id).await;
+    test_env
+        .deposit(
+            &depositor,
+            pool_id,
+            amm_index,
+            lp_deposit_amount,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await;
+
+    (test_env, admin, user, depositor, pool_id)
+}
+
+#[tokio::test]
+async fn should_pay_the_liquidity_and_locked_positions_out_of_the_escrow() {
+    // Setup
+    let user = Keypair::new();
+    let depositor = Keypair::new();
+
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), depositor.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    for owner in [&user, &depositor] {
+        let token_0_account = test_env
+            .get_or_create_associated_token_account(owner.pubkey(), test_env.token_0_mint, owner)
+            .await;
+        test_env
+            .mint_base_tokens(token_0_account, 100000000000000, test_env.token_0_mint)
+            .await;
+        let token_1_account = test_env
+            .get_or_create_associated_token_account(owner.pubkey(), test_env.token_1_mint, owner)
+            .await;
+        test_env
+            .mint_base_tokens(token_1_account, 100000000000000, test_env.token_1_mint)
+            .await;
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    test_env.init_user_pool_liquidity(&depositor, pool_id).await;
+    let lp_deposit_amount = 100000000;
+    test_env
+        .deposit(
+            &depositor,
+            pool_id,
+            amm_index,
+            lp_deposit_amount,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await;
+    // A year long lock doesn't keep the position out of the escrow
+    let unlock_time = test_env.timestamp_now().await as u64 + 365 * 24 * 60 * 60;
+    let position = test_env
+        .open_position(&depositor, pool_id, 0, 40000000, unlock_time)
+        .await;
+
+    pause_past_withdraw_grace_period(&mut test_env, &admin, pool_id).await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let emergency_escrow_key = test_env.emergency_withdraw_to_escrow(&admin, pool_id).await;
+    let emergency_escrow: EmergencyEscrow = test_env.fetch_account(emergency_escrow_key).await;
+    assert_eq!(
+        emergency_escrow.lp_supply,
+        pool_state.lp_supply - LOCKED_LP_AMOUNT
+    );
+
+    // Claims wait for the timelock
+    let result = test_env
+        .try_claim_emergency_escrow(&depositor, pool_id, &[position])
+        .await;
+    assert_error!(result, GammaError::NotApproved);
+
+    test_env
+        .jump_seconds(EMERGENCY_ESCROW_TIMELOCK as i64)
+        .await;
+
+    let depositor_token_0_account = test_env
+        .get_or_create_associated_token_account(
+            depositor.pubkey(),
+            test_env.token_0_mint,
+            &depositor,
+        )
+        .await;
+    let token_0_account_before: TokenAccount =
+        test_env.fetch_account(depositor_token_0_account).await;
+    test_env
+        .try_claim_emergency_escrow(&depositor, pool_id, &[position])
+        .await
+        .unwrap_transaction();
+    let token_0_account_after: TokenAccount =
+        test_env.fetch_account(depositor_token_0_account).await;
+
+    // The liquidity and the position are both paid
+    let expected_amount_0 = (u128::from(emergency_escrow.token_0_amount)
+        * u128::from(lp_deposit_amount)
+        / u128::from(emergency_escrow.lp_supply)) as u64;
+    assert_eq!(
+        token_0_account_after.amount - token_0_account_before.amount,
+        expected_amount_0
+    );
+    let position_state: Position = test_env.fetch_account(position).await;
+    assert_eq!(position_state.lp_tokens, 0);
+    let user_pool_liquidity_key = Pubkey::find_program_address(
+        &[
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_id.as_ref(),
+            depositor.pubkey().as_ref(),
+        ],
+        &gamma::id(),
+    )
+    .0;
+    let user_pool_liquidity: UserPoolLiquidity =
+        test_env.fetch_account(user_pool_liquidity_key).await;
+    assert_eq!(user_pool_liquidity.lp_tokens_owned, 0);
+
+    // Nothing is left to claim twice
+    let result = test_env
+        .try_claim_emergency_escrow(&depositor, pool_id, &[position])
+        .await;
+    assert_error!(result, GammaError::InvalidLpTokenAmount);
+
+    // The creator's claim empties the escrow
+    test_env
+        .try_claim_emergency_escrow(&user, pool_id, &[])
+        .await
+        .unwrap_transaction();
+    let emergency_escrow: EmergencyEscrow = test_env.fetch_account(emergency_escrow_key).await;
+    assert_eq!(emergency_escrow.lp_supply, 0);
+    assert_eq!(emergency_escrow.token_0_amount, 0);
+    assert_eq!(emergency_escrow.token_1_amount, 0);
+}
+
+#[tokio::test]
+async fn should_not_pay_out_staked_lp() {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    let token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(token_0_account, 100000000000000, test_env.token_0_mint)
+        .await;
+    let token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(token_1_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    test_env
+        .create_reward_vault(&admin, pool_id, test_env.token_1_mint, 10)
+        .await;
+    test_env
+        .try_stake_lp(&user, pool_id, 1000000)
+        .await
+        .unwrap_transaction();
+
+    pause_past_withdraw_grace_period(&mut test_env, &admin, pool_id).await;
+    test_env.emergency_withdraw_to_escrow(&admin, pool_id).await;
+    test_env
+        .jump_seconds(EMERGENCY_ESCROW_TIMELOCK as i64)
+        .await;
+
+    // The stake would keep earning rewards on LP that is gone
+    let result = test_env
+        .try_claim_emergency_escrow(&user, pool_id, &[])
+        .await;
+    assert_error!(result, GammaError::LpTokensStaked);
+
+    test_env
+        .try_unstake_lp(&user, pool_id, 1000000)
+        .await
+        .unwrap_transaction();
+    test_env
+        .try_claim_emergency_escrow(&user, pool_id, &[])
+        .await
+        .unwrap_transaction();
+}
+
+#[tokio::test]
+async fn should_leave_lps_the_withdraw_grace_period_and_withdraw_only_mode_to_exit() {
+    let (mut test_env, admin, _, depositor, pool_id) = setup(100000000).await;
+
+    // Pausing trading starts the grace period, withdrawals can't be disabled during it
+    test_env
+        .update_pool(&admin, pool_id, 0, TRADING_PAUSED)
+        .await;
+    let result = test_env
+        .try_emergency_withdraw_to_escrow(&admin, pool_id)
+        .await;
+    assert_error!(result, GammaError::WithdrawGracePeriod);
+    // LPs can still withdraw, once past the withdraw delay of their deposit
+    test_env.jump_seconds(10).await;
+    test_env
+        .withdraw(&depositor, pool_id, 0, 10000000, 0, 0)
+        .await;
+
+    // Nor at all in withdraw only mode
+    test_env.jump_seconds(WITHDRAW_GRACE_PERIOD as i64).await;
+    let set_withdraw_only = get_instruction(
+        gamma::instruction::SetWithdrawOnly { enabled: true },
+        gamma::accounts::SetWithdrawOnly {
+            owner: admin.pubkey(),
+            pool_state: pool_id,
+        },
+    );
+    test_env
+        .process_instructions(&[set_withdraw_only], &admin)
+        .await
+        .unwrap_transaction();
+    let result = test_env
+        .try_emergency_withdraw_to_escrow(&admin, pool_id)
+        .await;
+    assert_error!(result, GammaError::InvalidInput);
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw));
+}
+
+#[tokio::test]
+async fn should_restore_the_reserve_and_the_status_of_the_pool() {
+    let (mut test_env, admin, _, _, pool_id) = setup(100000000).await;
+    pause_past_withdraw_grace_period(&mut test_env, &admin, pool_id).await;
+    let pool_state_before: PoolState = test_env.fetch_account(pool_id).await;
+
+    let emergency_escrow_key = test_env.emergency_withdraw_to_escrow(&admin, pool_id).await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.token_0_vault_amount, 0);
+    assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw));
+
+    test_env
+        .try_restore_escrow_to_pool(&admin, pool_id)
+        .await
+        .unwrap_transaction();
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(
+        pool_state.token_0_vault_amount,
+        pool_state_before.token_0_vault_amount
+    );
+    assert_eq_with_copy!(
+        pool_state.token_1_vault_amount,
+        pool_state_before.token_1_vault_amount
+    );
+    assert_eq_with_copy!(pool_state.lp_supply, pool_state_before.lp_supply);
+    assert_eq_with_copy!(pool_state.status, pool_state_before.status);
+    assert!(test_env
+        .get_account_info(emergency_escrow_key)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn should_not_restore_an_escrow_lps_claimed_from() {
+    let (mut test_env, admin, _, depositor, pool_id) = setup(100000000).await;
+    pause_past_withdraw_grace_period(&mut test_env, &admin, pool_id).await;
+    let emergency_escrow_key = test_env.emergency_withdraw_to_escrow(&admin, pool_id).await;
+    test_env
+        .jump_seconds(EMERGENCY_ESCROW_TIMELOCK as i64)
+        .await;
+
+    test_env
+        .try_claim_emergency_escrow(&depositor, pool_id, &[])
+        .await
+        .unwrap_transaction();
+    let emergency_escrow: EmergencyEscrow = test_env.fetch_account(emergency_escrow_key).await;
+    assert_eq!(emergency_escrow.lp_claimed, 100000000);
+
+    // The depositor's LP is gone, the rest of the escrow only pays the other LPs out
+    let result = test_env.try_restore_escrow_to_pool(&admin, pool_id).await;
+    assert_error!(result, GammaError::EmergencyEscrowClaimed);
+}
+
+#[tokio::test]
+async fn should_leave_the_fees_collectable_from_the_pool() {
+    let (mut test_env, admin, _, depositor, pool_id) = setup(100000000).await;
+    // Enable LP fee accrual
+    test_env.update_pool(&admin, pool_id, 8, 0).await;
+    test_env
+        .swap_base_input(
+            &depositor,
+            pool_id,
+            0,
+            10000000,
+            0,
+            TradeDirection::ZeroForOne,
+        )
+        .await;
+
+    pause_past_withdraw_grace_period(&mut test_env, &admin, pool_id).await;
+    test_env.emergency_withdraw_to_escrow(&admin, pool_id).await;
+    test_env
+        .jump_seconds(EMERGENCY_ESCROW_TIMELOCK as i64)
+        .await;
+    test_env
+        .try_claim_emergency_escrow(&depositor, pool_id, &[])
+        .await
+        .unwrap_transaction();
+
+    // The LP fees the depositor earned before the escrow are still paid by the pool
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert!({ pool_state.lp_fees_token_0 } > 0);
+    test_env
+        .try_claim_lp_fees(&depositor, pool_id, 0)
+        .await
+        .unwrap_transaction();
+
+    // And so are the protocol fees
+    assert!({ pool_state.protocol_fees_token_0 } > 0);
+    let recipient_token_0_account = test_env
+        .get_or_create_associated_token_account(admin.pubkey(), test_env.token_0_mint, &admin)
+        .await;
+    let recipient_token_1_account = test_env
+        .get_or_create_associated_token_account(admin.pubkey(), test_env.token_1_mint, &admin)
+        .await;
+    let collect_protocol_fee = get_instruction(
+        gamma::instruction::CollectProtocolFee {
+            amount_0_requested: u64::MAX,
+            amount_1_requested: u64::MAX,
+        },
+        gamma::accounts::CollectProtocolFee {
+            owner: admin.pubkey(),
+            authority: Pubkey::find_program_address(&[gamma::AUTH_SEED.as_bytes()], &gamma::id()).0,
+            pool_state: pool_id,
+            amm_config: pool_state.amm_config,
+            config_roles: None,
+            token_0_vault: pool_state.token_0_vault,
+            token_1_vault: pool_state.token_1_vault,
+            vault_0_mint: test_env.token_0_mint,
+            vault_1_mint: test_env.token_1_mint,
+            recipient_token_0_account,
+            recipient_token_1_account,
+            token_program: anchor_spl::token::ID,
+            token_program_2022: anchor_spl::token_2022::ID,
+        },
+    );
+    test_env
+        .process_instructions(&[collect_protocol_fee], &admin)
+        .await
+        .unwrap_transaction();
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.protocol_fees_token_0, 0);
+}
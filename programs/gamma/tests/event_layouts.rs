@@ -0,0 +1,713 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{Discriminator, Event};
+use gamma::states::*;
+
+// Events are decoded off chain by their discriminator and field layout. The struct literals
+// below list every field, so adding one doesn't compile until its layout is pinned here too
+// and its VERSION bumped.
+
+fn assert_layout<T: Event>(event: &T, discriminator: [u8; 8], version: u8, len: usize) {
+    assert_eq!(T::DISCRIMINATOR, discriminator);
+    let data = event.data();
+    assert_eq!(data[..8], discriminator);
+    // The version comes first so decoders can pick the layout before reading the rest
+    assert_eq!(data[8], version);
+    assert_eq!(data.len(), 8 + len);
+}
+
+/// Offset of a field right after the version, e.g. the `pool_id` most events start with
+fn assert_first_field(data: &[u8], key: Pubkey) {
+    assert_eq!(data[9..41], key.to_bytes());
+}
+
+#[test]
+fn lp_change_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = LpChangeEvent {
+        version: LpChangeEvent::VERSION,
+        pool_id,
+        lp_amount_before: 1,
+        token_0_vault_before: 2,
+        token_1_vault_before: 3,
+        token_0_amount: 4,
+        token_1_amount: 5,
+        token_0_transfer_fee: 6,
+        token_1_transfer_fee: 7,
+        change_type: 0,
+    };
+    assert_layout(
+        &event,
+        [121, 163, 205, 201, 57, 218, 117, 60],
+        1,
+        1 + 32 + 7 * 8 + 1,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn swap_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = SwapEvent {
+        version: SwapEvent::VERSION,
+        pool_id,
+        input_vault_before: 1,
+        output_vault_before: 2,
+        input_amount: 3,
+        output_amount: 4,
+        input_mint: Pubkey::new_unique(),
+        output_mint: Pubkey::new_unique(),
+        input_transfer_fee: 5,
+        output_transfer_fee: 6,
+        base_input: true,
+        dynamic_fee: 7,
+        input_ui_adjusted_amount: 8,
+        output_ui_adjusted_amount: 9,
+        vault_0_after: 10,
+        vault_1_after: 11,
+        lp_supply: 12,
+        dynamic_fee_rate: 13,
+        amm_config_index: 14,
+    };
+    assert_layout(
+        &event,
+        [64, 198, 205, 232, 38, 8, 113, 226],
+        3,
+        1 + 32 + 4 * 8 + 2 * 32 + 2 * 8 + 1 + 16 + 2 * 8 + 4 * 8 + 2,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn migration_event_layout() {
+    let from_pool = Pubkey::new_unique();
+    let event = MigrationEvent {
+        version: MigrationEvent::VERSION,
+        from_pool,
+        to_pool: Pubkey::new_unique(),
+        token_0_amount_withdrawn: 1,
+        token_1_amount_withdrawn: 2,
+        lp_tokens_migrated: 3,
+    };
+    assert_layout(
+        &event,
+        [255, 202, 76, 147, 91, 231, 73, 22],
+        1,
+        1 + 2 * 32 + 2 * 8 + 16,
+    );
+    assert_first_field(&event.data(), from_pool);
+}
+
+#[test]
+fn emergency_escrow_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = EmergencyEscrowEvent {
+        version: EmergencyEscrowEvent::VERSION,
+        pool_id,
+        escrow: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        token_0_amount: 1,
+        token_1_amount: 2,
+        lp_amount: 3,
+        unlock_time: 4,
+        change_type: 0,
+    };
+    assert_layout(
+        &event,
+        [35, 240, 45, 112, 80, 247, 17, 129],
+        1,
+        1 + 3 * 32 + 4 * 8 + 1,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn fee_report_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = FeeReportEvent {
+        version: FeeReportEvent::VERSION,
+        pool_id,
+        epoch: 1,
+        start_timestamp: 2,
+        end_timestamp: 3,
+        trade_fees_token_0: 4,
+        trade_fees_token_1: 5,
+        protocol_fees_token_0: 6,
+        protocol_fees_token_1: 7,
+        fund_fees_token_0: 8,
+        fund_fees_token_1: 9,
+        lp_fees_token_0: 10,
+        lp_fees_token_1: 11,
+        volume_token_0: 12,
+        volume_token_1: 13,
+        average_dynamic_fee_rate_token_0: 14,
+        average_dynamic_fee_rate_token_1: 15,
+    };
+    assert_layout(
+        &event,
+        [13, 93, 132, 127, 237, 61, 112, 237],
+        1,
+        1 + 32 + 3 * 8 + 10 * 16 + 2 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn gauge_settled_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = GaugeSettledEvent {
+        version: GaugeSettledEvent::VERSION,
+        pool_id,
+        epoch: 1,
+        votes: 2,
+        total_votes: 3,
+        emission_rate: 4,
+    };
+    assert_layout(
+        &event,
+        [85, 169, 240, 221, 57, 226, 62, 104],
+        1,
+        1 + 32 + 4 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn solver_slashed_event_layout() {
+    let solver = Pubkey::new_unique();
+    let event = SolverSlashedEvent {
+        version: SolverSlashedEvent::VERSION,
+        solver,
+        recipient: Pubkey::new_unique(),
+        amount: 1,
+        bonded_amount: 2,
+        failed_settlements: 3,
+    };
+    assert_layout(
+        &event,
+        [105, 113, 55, 158, 222, 59, 12, 248],
+        1,
+        1 + 2 * 32 + 3 * 8,
+    );
+    assert_first_field(&event.data(), solver);
+}
+
+#[test]
+fn flash_loan_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = FlashLoanEvent {
+        version: FlashLoanEvent::VERSION,
+        pool_id,
+        borrower: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        amount: 1,
+        fee: 2,
+    };
+    assert_layout(
+        &event,
+        [214, 143, 143, 217, 173, 237, 52, 26],
+        1,
+        1 + 3 * 32 + 2 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn lp_fees_claim_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = LpFeesClaimEvent {
+        version: LpFeesClaimEvent::VERSION,
+        pool_id,
+        owner: Pubkey::new_unique(),
+        token_0_amount: 1,
+        token_1_amount: 2,
+        referrer: Pubkey::new_unique(),
+        referral_token_0_amount: 3,
+        referral_token_1_amount: 4,
+    };
+    assert_layout(
+        &event,
+        [50, 188, 221, 211, 172, 42, 195, 26],
+        1,
+        1 + 2 * 32 + 2 * 8 + 32 + 2 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn referral_paid_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = ReferralPaidEvent {
+        version: ReferralPaidEvent::VERSION,
+        pool_id,
+        referrer: Pubkey::new_unique(),
+        project: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        amount: 1,
+        trade_amount: 2,
+    };
+    assert_layout(
+        &event,
+        [30, 170, 23, 66, 51, 224, 41, 32],
+        1,
+        1 + 4 * 32 + 2 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn pool_lifecycle_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = PoolLifecycleEvent {
+        version: PoolLifecycleEvent::VERSION,
+        pool_id,
+        from: PoolLifecycle::Open,
+        to: PoolLifecycle::Paused,
+    };
+    assert_layout(
+        &event,
+        [108, 40, 245, 139, 126, 101, 129, 126],
+        1,
+        1 + 32 + 1 + 1,
+    );
+    let data = event.data();
+    assert_first_field(&data, pool_id);
+    // Stages are encoded by their position in `PoolLifecycle`
+    assert_eq!(data[41..], [2, 3]);
+}
+
+#[test]
+fn partner_change_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = PartnerChangeEvent {
+        version: PartnerChangeEvent::VERSION,
+        pool_id,
+        partner_id: 0,
+        lp_token_linked_with_partner: 1,
+        cumulative_fee_total_times_tvl_share_token_0: 2,
+        cumulative_fee_total_times_tvl_share_token_1: 3,
+        change_type: 1,
+    };
+    assert_layout(
+        &event,
+        [190, 45, 102, 65, 221, 15, 247, 26],
+        1,
+        1 + 32 + 4 * 8 + 1,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn partner_lp_link_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = PartnerLpLinkEvent {
+        version: PartnerLpLinkEvent::VERSION,
+        pool_id,
+        user: Pubkey::new_unique(),
+        from_partner: Some(0),
+        to_partner: None,
+        lp_amount: 1,
+    };
+    // Options take one byte, and eight more when set
+    assert_layout(
+        &event,
+        [222, 243, 195, 111, 166, 234, 160, 34],
+        1,
+        1 + 2 * 32 + (1 + 8) + 1 + 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn lp_concentration_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = LpConcentrationEvent {
+        version: LpConcentrationEvent::VERSION,
+        pool_id,
+        owner: Pubkey::new_unique(),
+        lp_tokens: 1,
+        lp_supply: 2,
+        share_bps: 3,
+    };
+    assert_layout(
+        &event,
+        [140, 101, 237, 253, 124, 20, 1, 83],
+        1,
+        1 + 2 * 32 + 3 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn reward_claim_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = RewardClaimEvent {
+        version: RewardClaimEvent::VERSION,
+        pool_id,
+        owner: Pubkey::new_unique(),
+        reward_mint: Pubkey::new_unique(),
+        amount: 1,
+    };
+    assert_layout(
+        &event,
+        [205, 12, 194, 218, 63, 68, 119, 89],
+        1,
+        1 + 3 * 32 + 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn position_change_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = PositionChangeEvent {
+        version: PositionChangeEvent::VERSION,
+        pool_id,
+        position: Pubkey::new_unique(),
+        from_owner: Pubkey::new_unique(),
+        to_owner: Pubkey::new_unique(),
+        lp_tokens: 1,
+        unlock_time: 2,
+        change_type: 1,
+    };
+    assert_layout(
+        &event,
+        [157, 58, 57, 58, 102, 232, 123, 224],
+        1,
+        1 + 4 * 32 + 2 * 8 + 1,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn swap_batch_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = SwapBatchEvent {
+        version: SwapBatchEvent::VERSION,
+        pool_id,
+        payer: Pubkey::new_unique(),
+        legs: 2,
+        token_0_amount_in: 1,
+        token_1_amount_in: 2,
+        token_0_amount_out: 3,
+        token_1_amount_out: 4,
+    };
+    assert_layout(
+        &event,
+        [118, 229, 60, 128, 58, 247, 94, 255],
+        1,
+        1 + 2 * 32 + 1 + 4 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn surplus_skim_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = SurplusSkimEvent {
+        version: SurplusSkimEvent::VERSION,
+        pool_id,
+        amount_0: 1,
+        amount_1: 2,
+    };
+    assert_layout(
+        &event,
+        [189, 101, 252, 168, 153, 223, 183, 16],
+        1,
+        1 + 32 + 2 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn donation_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = DonationEvent {
+        version: DonationEvent::VERSION,
+        pool_id,
+        donor: Pubkey::new_unique(),
+        token_0_amount: 1,
+        token_1_amount: 2,
+    };
+    assert_layout(
+        &event,
+        [43, 125, 2, 48, 193, 140, 25, 191],
+        1,
+        1 + 2 * 32 + 2 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn liquidity_change_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = LiquidityChangeEvent {
+        version: LiquidityChangeEvent::VERSION,
+        pool_id,
+        user: Pubkey::new_unique(),
+        lp_token_amount: 1,
+        token_0_amount: 2,
+        token_1_amount: 3,
+        token_0_transfer_fee: 4,
+        token_1_transfer_fee: 5,
+        lp_supply: 6,
+        user_lp_tokens: 7,
+        change_type: 0,
+    };
+    assert_layout(
+        &event,
+        [126, 240, 175, 206, 158, 88, 153, 107],
+        1,
+        1 + 2 * 32 + 6 * 8 + 16 + 1,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn amm_config_created_event_layout() {
+    let amm_config = Pubkey::new_unique();
+    let event = AmmConfigCreatedEvent {
+        version: AmmConfigCreatedEvent::VERSION,
+        amm_config,
+        signer: Pubkey::new_unique(),
+        index: 1,
+        trade_fee_rate: 2,
+        protocol_fee_rate: 3,
+        fund_fee_rate: 4,
+        create_pool_fee: 5,
+        max_open_time: 6,
+    };
+    assert_layout(
+        &event,
+        [187, 123, 168, 52, 125, 240, 22, 135],
+        1,
+        1 + 2 * 32 + 2 + 5 * 8,
+    );
+    assert_first_field(&event.data(), amm_config);
+}
+
+#[test]
+fn amm_config_change_event_layout() {
+    let amm_config = Pubkey::new_unique();
+    let event = AmmConfigChangeEvent {
+        version: AmmConfigChangeEvent::VERSION,
+        amm_config,
+        signer: Pubkey::new_unique(),
+        param: 1,
+        old_value: 2,
+        new_value: 3,
+        old_key: Pubkey::new_unique(),
+        new_key: Pubkey::new_unique(),
+    };
+    assert_layout(
+        &event,
+        [40, 111, 216, 173, 44, 57, 146, 247],
+        1,
+        1 + 2 * 32 + 2 + 2 * 8 + 2 * 32,
+    );
+    assert_first_field(&event.data(), amm_config);
+}
+
+#[test]
+fn pool_status_change_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = PoolStatusChangeEvent {
+        version: PoolStatusChangeEvent::VERSION,
+        pool_id,
+        signer: Pubkey::new_unique(),
+        old_status: 1,
+        new_status: 2,
+    };
+    assert_layout(
+        &event,
+        [109, 87, 83, 57, 101, 153, 142, 133],
+        1,
+        1 + 2 * 32 + 2,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn bonding_curve_trade_event_layout() {
+    let bonding_curve = Pubkey::new_unique();
+    let event = BondingCurveTradeEvent {
+        version: BondingCurveTradeEvent::VERSION,
+        bonding_curve,
+        trader: Pubkey::new_unique(),
+        is_buy: true,
+        token_amount: 1,
+        quote_amount: 2,
+        token_reserve: 3,
+        quote_reserve: 4,
+    };
+    assert_layout(
+        &event,
+        [98, 223, 146, 125, 147, 224, 213, 22],
+        1,
+        1 + 2 * 32 + 1 + 4 * 8,
+    );
+    assert_first_field(&event.data(), bonding_curve);
+}
+
+#[test]
+fn bonding_curve_graduated_event_layout() {
+    let bonding_curve = Pubkey::new_unique();
+    let event = BondingCurveGraduatedEvent {
+        version: BondingCurveGraduatedEvent::VERSION,
+        bonding_curve,
+        pool_id: Pubkey::new_unique(),
+        position: Pubkey::new_unique(),
+        token_amount: 1,
+        quote_amount: 2,
+        lp_tokens: 3,
+    };
+    assert_layout(
+        &event,
+        [82, 158, 21, 216, 136, 36, 60, 196],
+        1,
+        1 + 3 * 32 + 3 * 8,
+    );
+    assert_first_field(&event.data(), bonding_curve);
+}
+
+#[test]
+fn lp_lock_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = LpLockEvent {
+        version: LpLockEvent::VERSION,
+        pool_id,
+        position: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        locked_lp_tokens: 1,
+        released_lp_tokens: 2,
+        vesting_start: 3,
+        unlock_time: 4,
+    };
+    assert_layout(
+        &event,
+        [193, 63, 192, 191, 157, 123, 15, 125],
+        1,
+        1 + 3 * 32 + 4 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn lp_burn_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = LpBurnEvent {
+        version: LpBurnEvent::VERSION,
+        pool_id,
+        owner: Pubkey::new_unique(),
+        lp_tokens: 1,
+        total_burned_lp_tokens: 2,
+        lp_supply: 3,
+    };
+    assert_layout(&event, [50, 0, 193, 92, 0, 203, 149, 7], 1, 1 + 2 * 32 + 3 * 8);
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn user_position_transfer_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = UserPositionTransferEvent {
+        version: UserPositionTransferEvent::VERSION,
+        pool_id,
+        from_owner: Pubkey::new_unique(),
+        to_owner: Pubkey::new_unique(),
+        lp_tokens: 1,
+        from_lp_tokens: 2,
+        to_lp_tokens: 3,
+    };
+    assert_layout(
+        &event,
+        [175, 167, 198, 219, 205, 143, 36, 239],
+        1,
+        1 + 3 * 32 + 3 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn config_role_change_event_layout() {
+    let amm_config = Pubkey::new_unique();
+    let event = ConfigRoleChangeEvent {
+        version: ConfigRoleChangeEvent::VERSION,
+        amm_config,
+        role: 2,
+        old_authority: Pubkey::default(),
+        new_authority: Pubkey::new_unique(),
+    };
+    assert_layout(
+        &event,
+        [9, 217, 59, 235, 249, 121, 144, 200],
+        1,
+        1 + 32 + 1 + 2 * 32,
+    );
+    assert_first_field(&event.data(), amm_config);
+}
+
+#[test]
+fn protocol_fee_sweep_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = ProtocolFeeSweepEvent {
+        version: ProtocolFeeSweepEvent::VERSION,
+        pool_id,
+        treasury: Pubkey::new_unique(),
+        cranker: Pubkey::new_unique(),
+        token_0_amount: 1,
+        token_1_amount: 2,
+        token_0_bounty: 3,
+        token_1_bounty: 4,
+    };
+    assert_layout(
+        &event,
+        [106, 74, 141, 228, 62, 227, 149, 72],
+        1,
+        1 + 3 * 32 + 4 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn fund_fee_conversion_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = FundFeeConversionEvent {
+        version: FundFeeConversionEvent::VERSION,
+        pool_id,
+        swap_pool_id: Pubkey::new_unique(),
+        fee_mint: Pubkey::new_unique(),
+        target_mint: Pubkey::new_unique(),
+        fee_amount: 1,
+        amount_out: 2,
+    };
+    assert_layout(
+        &event,
+        [64, 58, 81, 170, 114, 53, 123, 226],
+        1,
+        1 + 4 * 32 + 2 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
+
+#[test]
+fn vault_strategy_change_event_layout() {
+    let pool_id = Pubkey::new_unique();
+    let event = VaultStrategyChangeEvent {
+        version: VaultStrategyChangeEvent::VERSION,
+        pool_id,
+        vault: Pubkey::new_unique(),
+        change_type: 2,
+        amount: 1,
+        deployed_amount: 2,
+        position_value: 3,
+        yield_amount: 4,
+        loss_amount: 5,
+    };
+    assert_layout(
+        &event,
+        [225, 146, 221, 109, 214, 81, 80, 246],
+        1,
+        1 + 2 * 32 + 1 + 5 * 8,
+    );
+    assert_first_field(&event.data(), pool_id);
+}
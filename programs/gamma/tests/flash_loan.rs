@@ -0,0 +1,134 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::system_program;
+use gamma::error::GammaError;
+use gamma::fees::StaticFee;
+use gamma::states::{PoolState, FLASH_LOAN_SEED};
+use solana_program_test::tokio;
+use solana_sdk::{signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+const FLASH_LOAN_FEE_RATE: u64 = 900;
+const LOAN_AMOUNT: u64 = 1000000;
+
+async fn setup() -> (TestEnv, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    test_env
+        .update_amm_config(&admin, amm_index, 10, FLASH_LOAN_FEE_RATE)
+        .await;
+
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_0_account, 100000000000000, test_env.token_0_mint)
+        .await;
+    let user_token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_1_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    (test_env, user, pool_id)
+}
+
+#[tokio::test]
+async fn should_add_the_flash_loan_fee_to_the_reserve_on_repay() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let (flash_loan, flash_repay) = test_env
+        .flash_loan_instructions(&user, pool_id, 0, LOAN_AMOUNT)
+        .await;
+
+    let pool_state_before: PoolState = test_env.fetch_account(pool_id).await;
+    test_env
+        .process_instructions(&[flash_loan, flash_repay], &user)
+        .await
+        .unwrap_transaction();
+
+    let fee = StaticFee::flash_loan_fee(u128::from(LOAN_AMOUNT), FLASH_LOAN_FEE_RATE).unwrap();
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq!(
+        u128::from(pool_state.token_0_vault_amount),
+        u128::from(pool_state_before.token_0_vault_amount) + fee
+    );
+}
+
+#[tokio::test]
+async fn should_not_lend_without_a_repay() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let (flash_loan, _) = test_env
+        .flash_loan_instructions(&user, pool_id, 0, LOAN_AMOUNT)
+        .await;
+
+    let result = test_env.process_instructions(&[flash_loan], &user).await;
+    assert_error!(result, GammaError::InvalidFlashLoan);
+}
+
+#[tokio::test]
+async fn should_not_count_the_repay_of_another_flash_loan_state() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let (flash_loan, mut flash_repay) = test_env
+        .flash_loan_instructions(&user, pool_id, 0, LOAN_AMOUNT)
+        .await;
+    // The flash loan state of another pool
+    let other_pool_id = Pubkey::new_unique();
+    flash_repay.accounts[2].pubkey = Pubkey::find_program_address(
+        &[FLASH_LOAN_SEED.as_bytes(), other_pool_id.as_ref()],
+        &gamma::id(),
+    )
+    .0;
+
+    let result = test_env
+        .process_instructions(&[flash_loan, flash_repay], &user)
+        .await;
+    assert_error!(result, GammaError::InvalidFlashLoan);
+}
+
+#[tokio::test]
+async fn should_not_count_a_repay_made_through_another_program() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let (flash_loan, mut flash_repay) = test_env
+        .flash_loan_instructions(&user, pool_id, 0, LOAN_AMOUNT)
+        .await;
+    // A repay made by CPI only shows as the top level instruction of the calling program
+    flash_repay.program_id = system_program::ID;
+
+    let result = test_env
+        .process_instructions(&[flash_loan, flash_repay], &user)
+        .await;
+    assert_error!(result, GammaError::InvalidFlashLoan);
+}
+
+#[tokio::test]
+async fn should_not_lend_twice_before_the_repay() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let (flash_loan, flash_repay) = test_env
+        .flash_loan_instructions(&user, pool_id, 0, LOAN_AMOUNT)
+        .await;
+
+    let result = test_env
+        .process_instructions(&[flash_loan.clone(), flash_loan, flash_repay], &user)
+        .await;
+    assert_error!(result, GammaError::InvalidFlashLoan);
+}
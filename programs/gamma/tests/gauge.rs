@@ -0,0 +1,154 @@
+use gamma::error::GammaError;
+use gamma::states::{Gauge, GaugeController};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+const EPOCH_DURATION: u64 = 1000;
+const EMISSION_PER_EPOCH: u64 = 1000000000;
+
+/// Two pools with a gauge each, and two voters holding veGOFX. Returns the voters, the pools
+/// and the veGOFX mint.
+async fn setup() -> (TestEnv, [Keypair; 2], [Pubkey; 2], Pubkey) {
+    let user = Keypair::new();
+    let voters = [Keypair::new(), Keypair::new()];
+    let admin = get_admin();
+    let mut test_env = TestEnv::new(vec![
+        user.pubkey(),
+        voters[0].pubkey(),
+        voters[1].pubkey(),
+        admin.pubkey(),
+    ])
+    .await;
+
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let user_token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(user_token_account, 100000000000000, mint)
+            .await;
+    }
+    let mut pools = [Pubkey::default(); 2];
+    for (amm_index, pool_id) in pools.iter_mut().enumerate() {
+        let amm_index = amm_index as u16;
+        test_env
+            .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+            .await;
+        *pool_id = test_env
+            .initialize_pool(
+                &user,
+                amm_index,
+                200000000000,
+                100000000000,
+                0,
+                gamma::create_pool_fee_reveiver::id(),
+            )
+            .await;
+    }
+
+    let ve_mint = Keypair::new();
+    let mint_authority = test_env.mint_authority.pubkey();
+    test_env
+        .create_token_mint(&ve_mint, &mint_authority, 6)
+        .await;
+    for (voter, balance) in voters.iter().zip([300, 100]) {
+        let ve_token_account = test_env
+            .get_or_create_associated_token_account(voter.pubkey(), ve_mint.pubkey(), voter)
+            .await;
+        test_env
+            .mint_base_tokens(ve_token_account, balance, ve_mint.pubkey())
+            .await;
+    }
+
+    test_env
+        .create_gauge_controller(&admin, ve_mint.pubkey(), EPOCH_DURATION, EMISSION_PER_EPOCH)
+        .await;
+    for pool_id in pools {
+        test_env.create_gauge(&user, pool_id).await;
+    }
+
+    (test_env, voters, pools, ve_mint.pubkey())
+}
+
+#[tokio::test]
+async fn should_settle_gauges_from_their_share_of_the_votes() {
+    let (mut test_env, voters, pools, ve_mint) = setup().await;
+
+    // The first voter splits its power, the second puts all of it on the second pool
+    for (voter, pool_id, power) in [
+        (&voters[0], pools[0], 200),
+        (&voters[0], pools[1], 100),
+        (&voters[1], pools[1], 100),
+    ] {
+        test_env
+            .try_vote_gauge(voter, pool_id, ve_mint, power)
+            .await
+            .unwrap_transaction();
+    }
+    let gauge_controller: GaugeController = test_env
+        .fetch_account(test_env.gauge_controller_key())
+        .await;
+    assert_eq!(gauge_controller.total_votes, 400);
+    let gauge: Gauge = test_env.fetch_account(test_env.gauge_key(pools[0])).await;
+    assert_eq!(gauge.votes, 200);
+
+    // Votes of an epoch set the emission rates of the next one
+    test_env.jump_seconds(EPOCH_DURATION as i64).await;
+    for pool_id in pools {
+        test_env
+            .try_settle_gauge(&voters[1], pool_id)
+            .await
+            .unwrap_transaction();
+    }
+    for pool_id in pools {
+        let gauge: Gauge = test_env.fetch_account(test_env.gauge_key(pool_id)).await;
+        assert_eq!(gauge.settled_epoch, 1);
+        assert_eq!(gauge.previous_votes, 200);
+        assert_eq!(gauge.emission_rate, EMISSION_PER_EPOCH / 2 / EPOCH_DURATION);
+    }
+}
+
+#[tokio::test]
+async fn should_not_settle_a_gauge_twice_in_an_epoch() {
+    let (mut test_env, voters, pools, ve_mint) = setup().await;
+    test_env
+        .try_vote_gauge(&voters[0], pools[0], ve_mint, 300)
+        .await
+        .unwrap_transaction();
+    test_env.jump_seconds(EPOCH_DURATION as i64).await;
+
+    test_env
+        .try_settle_gauge(&voters[0], pools[0])
+        .await
+        .unwrap_transaction();
+    let gauge: Gauge = test_env.fetch_account(test_env.gauge_key(pools[0])).await;
+    assert_eq!(gauge.emission_rate, EMISSION_PER_EPOCH / EPOCH_DURATION);
+
+    let result = test_env.try_settle_gauge(&voters[0], pools[0]).await;
+    assert_error!(result, GammaError::GaugeAlreadySettled);
+}
+
+#[tokio::test]
+async fn should_not_vote_with_more_than_the_ve_balance() {
+    let (mut test_env, voters, pools, ve_mint) = setup().await;
+    test_env
+        .try_vote_gauge(&voters[1], pools[0], ve_mint, 60)
+        .await
+        .unwrap_transaction();
+
+    // The power spent on one gauge is gone for the others in the same epoch
+    let result = test_env
+        .try_vote_gauge(&voters[1], pools[1], ve_mint, 41)
+        .await;
+    assert_error!(result, GammaError::InsufficientVotingPower);
+
+    // It comes back in the next epoch
+    test_env.jump_seconds(EPOCH_DURATION as i64).await;
+    test_env
+        .try_vote_gauge(&voters[1], pools[1], ve_mint, 100)
+        .await
+        .unwrap_transaction();
+}
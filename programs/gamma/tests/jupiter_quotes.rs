@@ -1,12 +1,27 @@
 use std::collections::HashMap;
 
-use anchor_spl::token::TokenAccount;
-use gamma::{curve::TradeDirection, states::PoolState};
+use anchor_spl::token::{spl_token, TokenAccount};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_hook::TransferHook, BaseStateWithExtensionsMut, ExtensionType,
+        StateWithExtensionsMut,
+    },
+    state::Mint,
+};
+use gamma::{
+    curve::TradeDirection,
+    states::{PoolState, SWAP_HOOK_EXTENSION},
+};
 use solana_program_test::tokio;
-use solana_sdk::{clock::Clock, signature::Keypair, signer::Signer};
+use solana_sdk::{
+    clock::Clock, program_pack::Pack, pubkey::Pubkey, signature::Keypair, signer::Signer,
+};
 mod utils;
-use jupiter_amm_interface::{AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, SwapMode};
-use utils::jupiter;
+use gamma::client::jupiter;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode,
+};
 
 use utils::*;
 
@@ -331,3 +346,148 @@ async fn jupiter_quotes() {
         test_env.jump_seconds(16).await;
     }
 }
+
+/// A pool that has already been swapped against and its `Amm`
+async fn setup_amm() -> (TestEnv, Pubkey, jupiter::Gamma) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 1000, 20, 5, 0)
+        .await;
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let user_token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(user_token_account, 100000000000000, mint)
+            .await;
+    }
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            20000000000000,
+            10000000000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+    test_env
+        .swap_base_input(
+            &user,
+            pool_id,
+            amm_index,
+            1000,
+            0,
+            TradeDirection::OneForZero,
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    let keyed_account = KeyedAccount {
+        key: pool_id,
+        account: test_env.get_account_info(pool_id).await.unwrap().unwrap(),
+        params: None,
+    };
+    let clock: Clock = test_env
+        .program_test_context
+        .banks_client
+        .get_sysvar()
+        .await
+        .unwrap();
+    let amm_context = AmmContext {
+        clock_ref: ClockRef::from(clock),
+    };
+    let amm = jupiter::Gamma::from_keyed_account(&keyed_account, &amm_context).unwrap();
+
+    (test_env, pool_id, amm)
+}
+
+/// The accounts the `Amm` asks for
+async fn amm_account_map(test_env: &mut TestEnv, amm: &jupiter::Gamma) -> AccountMap {
+    let mut account_map: AccountMap = HashMap::with_hasher(ahash::RandomState::new());
+    for key in amm.get_accounts_to_update() {
+        let account = test_env.get_account_info(key).await.unwrap().unwrap();
+        account_map.insert(key, account);
+    }
+    account_map
+}
+
+fn quote_params(pool_state: &PoolState, amount: u64) -> QuoteParams {
+    QuoteParams {
+        amount,
+        input_mint: pool_state.token_0_mint,
+        output_mint: pool_state.token_1_mint,
+        swap_mode: SwapMode::ExactIn,
+    }
+}
+
+#[tokio::test]
+async fn jupiter_quotes_reject_pools_with_a_swap_hook() {
+    let (mut test_env, pool_id, mut amm) = setup_amm().await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let mut account_map = amm_account_map(&mut test_env, &amm).await;
+    amm.update(&account_map).unwrap();
+    amm.quote(&quote_params(&pool_state, 1000000)).unwrap();
+
+    let pool_account = account_map.get_mut(&pool_id).unwrap();
+    let hooked_pool_state: &mut PoolState =
+        bytemuck::from_bytes_mut(&mut pool_account.data[8..8 + std::mem::size_of::<PoolState>()]);
+    hooked_pool_state.extensions |= SWAP_HOOK_EXTENSION;
+    amm.update(&account_map).unwrap();
+    let error = amm.quote(&quote_params(&pool_state, 1000000)).unwrap_err();
+    assert!(error.to_string().contains("swap hook"), "{error}");
+}
+
+#[tokio::test]
+async fn jupiter_quotes_reject_mints_with_a_transfer_hook() {
+    let (mut test_env, pool_id, mut amm) = setup_amm().await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let mut account_map = amm_account_map(&mut test_env, &amm).await;
+
+    // Stand a token 2022 mint with a transfer hook in for token_0
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook]).unwrap();
+    let mut data = vec![0; space];
+    let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    mint.init_extension::<TransferHook>(true)
+        .unwrap()
+        .program_id
+        .0 = Pubkey::new_unique();
+    mint.base = Mint {
+        decimals: 6,
+        is_initialized: true,
+        ..Default::default()
+    };
+    mint.pack_base();
+    mint.init_account_type().unwrap();
+    let mint_account = account_map.get_mut(&pool_state.token_0_mint).unwrap();
+    mint_account.data = data;
+    mint_account.owner = spl_token_2022::id();
+
+    amm.update(&account_map).unwrap();
+    let error = amm.quote(&quote_params(&pool_state, 1000000)).unwrap_err();
+    assert!(error.to_string().contains("transfer hook"), "{error}");
+}
+
+#[tokio::test]
+async fn jupiter_quotes_reject_payouts_needing_the_vault_strategy() {
+    let (mut test_env, pool_id, mut amm) = setup_amm().await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let mut account_map = amm_account_map(&mut test_env, &amm).await;
+
+    // A strategy holding all but 1000 of the output vault's reserve
+    let vault_account = account_map.get_mut(&pool_state.token_1_vault).unwrap();
+    let mut vault = spl_token::state::Account::unpack(&vault_account.data).unwrap();
+    vault.amount = 1000;
+    spl_token::state::Account::pack(vault, &mut vault_account.data).unwrap();
+
+    amm.update(&account_map).unwrap();
+    amm.quote(&quote_params(&pool_state, 100)).unwrap();
+    let error = amm.quote(&quote_params(&pool_state, 1000000)).unwrap_err();
+    assert!(error.to_string().contains("strategy"), "{error}");
+}
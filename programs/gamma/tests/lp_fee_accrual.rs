@@ -0,0 +1,149 @@
+use anchor_spl::token::TokenAccount;
+use gamma::curve::TradeDirection;
+use gamma::error::GammaError;
+use gamma::instructions::LOCKED_LP_AMOUNT;
+use gamma::states::PoolState;
+use solana_program_test::tokio;
+use solana_sdk::{signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+#[tokio::test]
+async fn should_accrue_the_lp_fee_of_a_swap_to_the_lps_and_let_them_claim_it() {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_0_account, 100000000000000, test_env.token_0_mint)
+        .await;
+    let user_token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_1_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+    // Enable LP fee accrual
+    test_env.update_pool(&admin, pool_id, 8, 0).await;
+
+    test_env
+        .swap_base_input(
+            &user,
+            pool_id,
+            amm_index,
+            10000000,
+            0,
+            TradeDirection::ZeroForOne,
+        )
+        .await;
+
+    // Only the creator's liquidity accrues, the share of the locked LP stays in the reserves
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let accruing_lp = u128::from(pool_state.lp_supply - LOCKED_LP_AMOUNT);
+    let lp_fees_token_0 = pool_state.lp_fees_token_0;
+    assert!(lp_fees_token_0 > 0);
+    assert_eq!(
+        u128::from(lp_fees_token_0),
+        u128::from(pool_state.lp_fee_growth_token_0_x32) * accruing_lp >> 32
+    );
+
+    let token_0_account_before: TokenAccount = test_env.fetch_account(user_token_0_account).await;
+    test_env
+        .try_claim_lp_fees(&user, pool_id, amm_index)
+        .await
+        .unwrap_transaction();
+    let token_0_account_after: TokenAccount = test_env.fetch_account(user_token_0_account).await;
+
+    let claimed = token_0_account_after.amount - token_0_account_before.amount;
+    assert!(claimed > 0);
+    assert!(claimed <= lp_fees_token_0);
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.lp_fees_token_0, lp_fees_token_0 - claimed);
+
+    // Nothing is left to claim twice
+    let result = test_env.try_claim_lp_fees(&user, pool_id, amm_index).await;
+    assert_error!(result, GammaError::InvalidInput);
+}
+
+#[tokio::test]
+async fn should_not_accrue_the_lp_fee_of_burned_lp() {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_0_account, 100000000000000, test_env.token_0_mint)
+        .await;
+    let user_token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_1_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+    test_env.update_pool(&admin, pool_id, 8, 0).await;
+
+    let burned_lp = 50000000;
+    test_env.burn_lp_forever(&user, pool_id, burned_lp).await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.burned_lp, burned_lp);
+
+    test_env
+        .swap_base_input(
+            &user,
+            pool_id,
+            amm_index,
+            10000000,
+            0,
+            TradeDirection::ZeroForOne,
+        )
+        .await;
+
+    // The share of the burned LP stays in the reserves like the locked LP's
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let accruing_lp = u128::from(pool_state.lp_supply - LOCKED_LP_AMOUNT - burned_lp);
+    assert_eq!(
+        u128::from(pool_state.lp_fees_token_0),
+        u128::from(pool_state.lp_fee_growth_token_0_x32) * accruing_lp >> 32
+    );
+}
@@ -0,0 +1,124 @@
+use anchor_lang::{prelude::Rent, Discriminator};
+use gamma::curve::TradeDirection;
+use gamma::error::GammaError;
+use gamma::states::{PoolState, DEFAULT_WITHDRAW_DELAY_SLOTS, POOL_STATE_VERSION};
+use solana_program_test::tokio;
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+/// Bytes the pool account of the older layout in these tests is short of `PoolState::LEN`
+const OLD_LAYOUT_SHORTFALL: usize = 64;
+
+/// A pool created by the user. Returns the user and the pool.
+async fn setup() -> (TestEnv, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let user_token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(user_token_account, 100000000000000, mint)
+            .await;
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000000,
+            100000000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    (test_env, user, pool_id)
+}
+
+/// Puts the pool back in a layout from before versions were kept: shorter, with version zero
+/// and rent for its size only
+async fn downgrade_pool(test_env: &mut TestEnv, pool_id: Pubkey) {
+    let mut pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    pool_state.version = 0;
+    pool_state.withdraw_delay_slots = 0;
+    let mut data = PoolState::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(bytemuck::bytes_of(&pool_state));
+    data.truncate(PoolState::LEN - OLD_LAYOUT_SHORTFALL);
+
+    let mut account = AccountSharedData::new(
+        Rent::default().minimum_balance(data.len()),
+        data.len(),
+        &gamma::id(),
+    );
+    account.set_data_from_slice(&data);
+    test_env
+        .program_test_context
+        .set_account(&pool_id, &account);
+}
+
+#[tokio::test]
+async fn should_migrate_a_pool_of_an_older_layout() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let pool_state_before: PoolState = test_env.fetch_account(pool_id).await;
+    downgrade_pool(&mut test_env, pool_id).await;
+
+    test_env
+        .try_migrate_pool_state(&user, pool_id)
+        .await
+        .unwrap_transaction();
+
+    let account = test_env.get_account_info(pool_id).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), PoolState::LEN);
+    assert!(account.lamports >= Rent::default().minimum_balance(PoolState::LEN));
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.version, POOL_STATE_VERSION);
+    assert_eq_with_copy!(
+        pool_state.withdraw_delay_slots,
+        DEFAULT_WITHDRAW_DELAY_SLOTS
+    );
+    assert_eq_with_copy!(pool_state.burned_lp, 0);
+    assert_eq_with_copy!(pool_state.lp_supply, pool_state_before.lp_supply);
+    assert_eq!(pool_state.token_0_vault, pool_state_before.token_0_vault);
+
+    // The migrated pool trades again
+    test_env
+        .swap_base_input(&user, pool_id, 0, 1000000, 0, TradeDirection::ZeroForOne)
+        .await;
+}
+
+#[tokio::test]
+async fn should_leave_a_pool_of_the_current_version_as_it_is() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let account_before = test_env.get_account_info(pool_id).await.unwrap().unwrap();
+
+    test_env
+        .try_migrate_pool_state(&user, pool_id)
+        .await
+        .unwrap_transaction();
+
+    let account = test_env.get_account_info(pool_id).await.unwrap().unwrap();
+    assert_eq!(account.data, account_before.data);
+    assert_eq!(account.lamports, account_before.lamports);
+}
+
+#[tokio::test]
+async fn should_not_migrate_an_account_that_is_not_a_pool() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+
+    let result = test_env
+        .try_migrate_pool_state(&user, pool_state.observation_key)
+        .await;
+    assert_error!(result, GammaError::InvalidInput);
+}
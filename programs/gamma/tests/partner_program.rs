@@ -3,6 +3,7 @@ use std::u64;
 use gamma::curve::TradeDirection;
 use gamma::states::PartnerType;
 use gamma::states::PoolState;
+use gamma::states::DEFAULT_WITHDRAW_DELAY_SLOTS;
 use solana_program_test::tokio;
 use solana_sdk::{signature::Keypair, signer::Signer};
 mod utils;
@@ -151,6 +152,7 @@ async fn should_track_cumulative_rates_correctly() {
     );
 
     let withdraw_amount = 100000000;
+    test_env.jump_slots(DEFAULT_WITHDRAW_DELAY_SLOTS).await;
     test_env
         .withdraw(
             &lp_depositor_asset_dash,
@@ -295,3 +297,119 @@ async fn should_track_cumulative_rates_correctly() {
             / pool_state.lp_supply
     );
 }
+
+#[tokio::test]
+async fn should_add_remove_and_relink_partners() {
+    let user = Keypair::new();
+    let lp_depositor = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env =
+        TestEnv::new(vec![user.pubkey(), lp_depositor.pubkey(), admin.pubkey()]).await;
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    for owner in [&user, &lp_depositor] {
+        let token_0_account = test_env
+            .get_or_create_associated_token_account(owner.pubkey(), test_env.token_0_mint, owner)
+            .await;
+        test_env
+            .mint_base_tokens(token_0_account, 100000000000000, test_env.token_0_mint)
+            .await;
+        let token_1_account = test_env
+            .get_or_create_associated_token_account(owner.pubkey(), test_env.token_1_mint, owner)
+            .await;
+        test_env
+            .mint_base_tokens(token_1_account, 100000000000000, test_env.token_1_mint)
+            .await;
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    test_env
+        .init_user_pool_liquidity(&lp_depositor, pool_id)
+        .await;
+    let lp_deposit_amount = 200000000;
+    test_env
+        .deposit(
+            &lp_depositor,
+            pool_id,
+            amm_index,
+            lp_deposit_amount,
+            u64::MAX,
+            u64::MAX,
+        )
+        .await;
+
+    // Linking a position moves its LP to the partner
+    let asset_dash = PartnerType::AssetDash as u64;
+    test_env
+        .update_partner_lp_link(&lp_depositor, pool_id, Some(asset_dash))
+        .await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(
+        pool_state.partners[0].lp_token_linked_with_partner,
+        lp_deposit_amount
+    );
+
+    // A removed partner frees its slot and stops accruing fees
+    test_env.remove_partner(&admin, pool_id, asset_dash).await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert!(pool_state.partners[0].is_empty());
+    assert_eq_with_copy!(pool_state.partners[0].lp_token_linked_with_partner, 0);
+
+    test_env
+        .swap_base_input(
+            &user,
+            pool_id,
+            amm_index,
+            1000000000,
+            0,
+            TradeDirection::OneForZero,
+        )
+        .await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(
+        pool_state.partners[0].cumulative_fee_total_times_tvl_share_token_1,
+        0
+    );
+
+    // Added again, it starts from zero and LP linked before can still be withdrawn
+    test_env.add_partner(&admin, pool_id, asset_dash).await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.partners[0].partner_id, asset_dash);
+    assert_eq_with_copy!(pool_state.partners[0].lp_token_linked_with_partner, 0);
+
+    test_env.jump_slots(DEFAULT_WITHDRAW_DELAY_SLOTS).await;
+    test_env
+        .withdraw(&lp_depositor, pool_id, amm_index, 100000000, 0, 0)
+        .await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.partners[0].lp_token_linked_with_partner, 0);
+
+    // Relinking counts the remaining LP again, unlinking takes it out
+    test_env
+        .update_partner_lp_link(&lp_depositor, pool_id, Some(asset_dash))
+        .await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(
+        pool_state.partners[0].lp_token_linked_with_partner,
+        lp_deposit_amount - 100000000
+    );
+    test_env
+        .update_partner_lp_link(&lp_depositor, pool_id, None)
+        .await;
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.partners[0].lp_token_linked_with_partner, 0);
+}
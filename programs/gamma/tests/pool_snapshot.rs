@@ -0,0 +1,122 @@
+use anchor_lang::prelude::Pubkey;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use gamma::curve::TradeDirection;
+use gamma::states::PoolState;
+use gamma::AUTH_SEED;
+use solana_program_test::tokio;
+use solana_sdk::{signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::snapshot::{AccountSnapshot, PoolSnapshot};
+use utils::*;
+
+/// The accounts `gamma-cli snapshot-pool` would write for the pool, had it been created by
+/// `program_id`: the accounts of the program are owned by it, its authority PDA owns the vaults
+/// and the pool keeps the bump of that PDA.
+async fn snapshot_as_created_by(
+    test_env: &mut TestEnv,
+    pool_id: Pubkey,
+    program_id: Pubkey,
+) -> PoolSnapshot {
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let (authority, auth_bump) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &program_id);
+
+    let mut accounts = Vec::new();
+    for address in [
+        pool_id,
+        pool_state.amm_config,
+        pool_state.observation_key,
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+        pool_state.token_0_mint,
+        pool_state.token_1_mint,
+    ] {
+        let mut account = test_env.get_account_info(address).await.unwrap().unwrap();
+        if account.owner == gamma::id() {
+            account.owner = program_id;
+        }
+        if address == pool_id {
+            let pool_state: &mut PoolState = bytemuck::from_bytes_mut(
+                &mut account.data[8..8 + std::mem::size_of::<PoolState>()],
+            );
+            pool_state.auth_bump = auth_bump;
+        }
+        if address == pool_state.token_0_vault || address == pool_state.token_1_vault {
+            account.data[32..64].copy_from_slice(authority.as_ref());
+        }
+        accounts.push(AccountSnapshot {
+            address: address.to_string(),
+            owner: account.owner.to_string(),
+            lamports: account.lamports,
+            executable: account.executable,
+            data: STANDARD.encode(&account.data),
+        });
+    }
+
+    PoolSnapshot {
+        program_id: program_id.to_string(),
+        pool_id: pool_id.to_string(),
+        slot: 0,
+        accounts,
+    }
+}
+
+#[tokio::test]
+async fn should_swap_against_a_pool_loaded_from_a_snapshot() {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(token_account, 100000000000000, mint)
+            .await;
+    }
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    // A pool of another deployment of the program, as on mainnet
+    let snapshot = snapshot_as_created_by(&mut test_env, pool_id, Pubkey::new_unique()).await;
+    let trader = Keypair::new();
+    let mut forked_env = TestEnv::new_from_snapshot(&snapshot, vec![trader.pubkey()]).await;
+    assert_eq!(snapshot.pool_id(), pool_id);
+
+    let trader_token_0_account = forked_env
+        .get_or_create_associated_token_account(trader.pubkey(), forked_env.token_0_mint, &trader)
+        .await;
+    forked_env
+        .mint_base_tokens(trader_token_0_account, 100000000, forked_env.token_0_mint)
+        .await;
+    forked_env.jump_seconds(100).await;
+
+    let pool_state_before: PoolState = forked_env.fetch_account(pool_id).await;
+    forked_env
+        .swap_base_input(
+            &trader,
+            pool_id,
+            amm_index,
+            1000000,
+            0,
+            TradeDirection::ZeroForOne,
+        )
+        .await;
+    let pool_state: PoolState = forked_env.fetch_account(pool_id).await;
+    assert!({ pool_state.token_0_vault_amount } > { pool_state_before.token_0_vault_amount });
+    assert!({ pool_state.token_1_vault_amount } < { pool_state_before.token_1_vault_amount });
+}
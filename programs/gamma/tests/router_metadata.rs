@@ -0,0 +1,149 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::ToAccountMetas;
+use gamma::router_metadata::*;
+
+#[test]
+fn account_counts_match_the_instructions() {
+    let key = Pubkey::new_unique;
+
+    let swap = gamma::accounts::Swap {
+        payer: key(),
+        authority: key(),
+        amm_config: key(),
+        pool_state: key(),
+        input_token_account: key(),
+        output_token_account: key(),
+        input_vault: key(),
+        output_vault: key(),
+        input_token_program: key(),
+        output_token_program: key(),
+        input_token_mint: key(),
+        output_token_mint: key(),
+        observation_state: key(),
+    };
+    assert_eq!(swap.to_account_metas(None).len(), SWAP_REQUIRED_ACCOUNTS);
+    assert_eq!(
+        swap_accounts_len(SwapOptionalAccounts {
+            segmenter: true,
+            referral: true,
+            segmenter_kickback: true,
+            output_receiver: true,
+            volume_tracker: true,
+            instructions_sysvar: true,
+            create_output_token_account: true,
+            price_band: true,
+            mint_exposure: true,
+            quote_cache: true,
+            whitelist_entry: true,
+            staking_discount: true,
+        }),
+        SWAP_REQUIRED_ACCOUNTS + SWAP_OPTIONAL_ACCOUNTS
+    );
+    // Referral accounts sit after the segmenter placeholders
+    assert_eq!(
+        swap_accounts_len(SwapOptionalAccounts {
+            referral: true,
+            ..Default::default()
+        }),
+        SWAP_REQUIRED_ACCOUNTS + 4
+    );
+
+    let swap_route = gamma::accounts::SwapRoute {
+        payer: key(),
+        instructions_sysvar: None,
+    };
+    assert_eq!(
+        swap_route.to_account_metas(None).len(),
+        SWAP_ROUTE_REQUIRED_ACCOUNTS
+    );
+    assert_eq!(
+        swap_route_accounts_len(3),
+        SWAP_ROUTE_REQUIRED_ACCOUNTS + 3 * swap.to_account_metas(None).len()
+    );
+
+    let swap_batch = gamma::accounts::SwapBatch {
+        payer: key(),
+        instructions_sysvar: None,
+    };
+    assert_eq!(
+        swap_batch.to_account_metas(None).len(),
+        SWAP_BATCH_REQUIRED_ACCOUNTS
+    );
+
+    let deposit = gamma::accounts::Deposit {
+        owner: key(),
+        authority: key(),
+        pool_state: key(),
+        user_pool_liquidity: key(),
+        token_0_account: key(),
+        token_1_account: key(),
+        token_0_vault: key(),
+        token_1_vault: key(),
+        token_program: key(),
+        token_program_2022: key(),
+        vault_0_mint: key(),
+        vault_1_mint: key(),
+        system_program: key(),
+    };
+    assert_eq!(
+        deposit.to_account_metas(None).len(),
+        DEPOSIT_REQUIRED_ACCOUNTS
+    );
+    assert_eq!(
+        deposit_accounts_len(DepositOptionalAccounts {
+            referrer: true,
+            mint_exposure: true,
+            quote_cache: true,
+            whitelist_entry: true,
+        }),
+        DEPOSIT_REQUIRED_ACCOUNTS + DEPOSIT_OPTIONAL_ACCOUNTS
+    );
+    assert_eq!(
+        deposit_accounts_len(DepositOptionalAccounts {
+            referrer: true,
+            ..Default::default()
+        }),
+        DEPOSIT_REQUIRED_ACCOUNTS + 2
+    );
+
+    let withdraw = gamma::accounts::Withdraw {
+        owner: key(),
+        authority: key(),
+        pool_state: key(),
+        user_pool_liquidity: key(),
+        token_0_account: key(),
+        token_1_account: key(),
+        token_0_vault: key(),
+        token_1_vault: key(),
+        token_program: key(),
+        token_program_2022: key(),
+        vault_0_mint: key(),
+        vault_1_mint: key(),
+        memo_program: key(),
+        system_program: key(),
+    };
+    assert_eq!(
+        withdraw.to_account_metas(None).len(),
+        WITHDRAW_REQUIRED_ACCOUNTS
+    );
+    assert_eq!(
+        withdraw_accounts_len(WithdrawOptionalAccounts {
+            mint_exposure: true,
+            quote_cache: true,
+            recipient: true,
+        }),
+        WITHDRAW_REQUIRED_ACCOUNTS + WITHDRAW_OPTIONAL_ACCOUNTS
+    );
+    assert_eq!(
+        withdraw_accounts_len(WithdrawOptionalAccounts {
+            quote_cache: true,
+            ..Default::default()
+        }),
+        WITHDRAW_REQUIRED_ACCOUNTS + 3
+    );
+
+    assert_eq!(
+        swap_route_max_compute_units(3),
+        SWAP_ROUTE_BASE_COMPUTE_UNITS + 3 * SWAP_MAX_COMPUTE_UNITS
+    );
+}
@@ -0,0 +1,207 @@
+use gamma::curve::TradeDirection;
+use gamma::error::GammaError;
+use gamma::instructions::SWAP_FIXED_REMAINING_ACCOUNTS;
+use gamma::states::{PoolState, SegmenterKickback};
+use solana_program_test::tokio;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+const PARTNER_ID: u16 = 1;
+const PARTNER_KICKBACK_BPS: u64 = 2000;
+
+/// A pool and the `SegmenterRegistry` of an order-flow partner with one segmenter, both holding
+/// tokens of the pool. Returns the user, the segmenter, the pool and the registry.
+async fn setup() -> (TestEnv, Keypair, Keypair, Pubkey, Pubkey) {
+    let user = Keypair::new();
+    let segmenter = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), segmenter.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    for owner in [&user, &segmenter] {
+        for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+            let token_account = test_env
+                .get_or_create_associated_token_account(owner.pubkey(), mint, owner)
+                .await;
+            test_env
+                .mint_base_tokens(token_account, 100000000000000, mint)
+                .await;
+        }
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000000,
+            100000000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    let registry = test_env
+        .set_segmenter_registry(
+            &admin,
+            PARTNER_ID,
+            PARTNER_KICKBACK_BPS,
+            vec![segmenter.pubkey()],
+        )
+        .await;
+
+    (test_env, user, segmenter, pool_id, registry)
+}
+
+/// Swap of the segmenter passing it, the registry and its kickback account as remaining accounts
+async fn segmenter_swap_instruction(
+    test_env: &mut TestEnv,
+    segmenter: &Keypair,
+    pool_id: Pubkey,
+    registry: Pubkey,
+    trade_direction: TradeDirection,
+) -> Instruction {
+    let mut instruction = test_env
+        .swap_base_input_instruction(segmenter, pool_id, 0, 100000000, 0, trade_direction)
+        .await;
+    let mut remaining_accounts =
+        vec![AccountMeta::new_readonly(gamma::id(), false); SWAP_FIXED_REMAINING_ACCOUNTS];
+    remaining_accounts[0] = AccountMeta::new_readonly(segmenter.pubkey(), true);
+    remaining_accounts[1] = AccountMeta::new_readonly(registry, false);
+    remaining_accounts[4] = AccountMeta::new(
+        test_env.segmenter_kickback_key(pool_id, segmenter.pubkey()),
+        false,
+    );
+    instruction.accounts.extend(remaining_accounts);
+    instruction
+}
+
+#[tokio::test]
+async fn should_init_the_kickback_of_a_segmenter_of_a_segmenter_registry() {
+    let (mut test_env, _, segmenter, pool_id, registry) = setup().await;
+
+    test_env
+        .try_init_segmenter_kickback(&segmenter, registry, pool_id)
+        .await
+        .unwrap_transaction();
+
+    let segmenter_kickback: SegmenterKickback = test_env
+        .fetch_account(test_env.segmenter_kickback_key(pool_id, segmenter.pubkey()))
+        .await;
+    assert_eq!(segmenter_kickback.segmenter, segmenter.pubkey());
+    assert_eq!(segmenter_kickback.pool_state, pool_id);
+    assert_eq!(segmenter_kickback.token_0_accrued, 0);
+    assert_eq!(segmenter_kickback.token_1_accrued, 0);
+}
+
+#[tokio::test]
+async fn should_not_init_the_kickback_of_an_unregistered_segmenter() {
+    let (mut test_env, user, _, pool_id, registry) = setup().await;
+
+    let result = test_env
+        .try_init_segmenter_kickback(&user, registry, pool_id)
+        .await;
+    assert_error!(result, GammaError::InvalidOwner);
+
+    // Nor can another gamma account stand in for a registry
+    let result = test_env
+        .try_init_segmenter_kickback(&user, pool_id, pool_id)
+        .await;
+    assert_error!(result, GammaError::InvalidOwner);
+}
+
+#[tokio::test]
+async fn should_accrue_a_share_of_the_dynamic_fee_to_the_segmenter() {
+    let (mut test_env, _, segmenter, pool_id, registry) = setup().await;
+    test_env
+        .try_init_segmenter_kickback(&segmenter, registry, pool_id)
+        .await
+        .unwrap_transaction();
+    let kickback_key = test_env.segmenter_kickback_key(pool_id, segmenter.pubkey());
+
+    for trade_direction in [TradeDirection::ZeroForOne, TradeDirection::OneForZero] {
+        let pool_state_before: PoolState = test_env.fetch_account(pool_id).await;
+        let kickback_before: SegmenterKickback = test_env.fetch_account(kickback_key).await;
+
+        let instruction = segmenter_swap_instruction(
+            &mut test_env,
+            &segmenter,
+            pool_id,
+            registry,
+            trade_direction,
+        )
+        .await;
+        test_env
+            .process_instructions(&[instruction], &segmenter)
+            .await
+            .unwrap_transaction();
+
+        let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+        let kickback: SegmenterKickback = test_env.fetch_account(kickback_key).await;
+        let (dynamic_fee, accrued, pool_kickbacks) = match trade_direction {
+            TradeDirection::ZeroForOne => (
+                { pool_state.cumulative_trade_fees_token_0 } - {
+                    pool_state_before.cumulative_trade_fees_token_0
+                },
+                kickback.token_0_accrued - kickback_before.token_0_accrued,
+                { pool_state.segmenter_kickbacks_token_0 } - {
+                    pool_state_before.segmenter_kickbacks_token_0
+                },
+            ),
+            TradeDirection::OneForZero => (
+                { pool_state.cumulative_trade_fees_token_1 } - {
+                    pool_state_before.cumulative_trade_fees_token_1
+                },
+                kickback.token_1_accrued - kickback_before.token_1_accrued,
+                { pool_state.segmenter_kickbacks_token_1 } - {
+                    pool_state_before.segmenter_kickbacks_token_1
+                },
+            ),
+        };
+        assert!(dynamic_fee > 0);
+        assert_eq!(
+            u128::from(accrued),
+            dynamic_fee * u128::from(PARTNER_KICKBACK_BPS) / 10000
+        );
+        assert_eq!(pool_kickbacks, accrued);
+        test_env.jump_seconds(100).await;
+    }
+}
+
+#[tokio::test]
+async fn should_not_accrue_a_kickback_to_a_segmenter_that_did_not_sign() {
+    let (mut test_env, user, segmenter, pool_id, registry) = setup().await;
+    test_env
+        .try_init_segmenter_kickback(&segmenter, registry, pool_id)
+        .await
+        .unwrap_transaction();
+    let kickback_key = test_env.segmenter_kickback_key(pool_id, segmenter.pubkey());
+
+    // The user routes the swap through the segmenter's accounts without its signature
+    let mut instruction = segmenter_swap_instruction(
+        &mut test_env,
+        &user,
+        pool_id,
+        registry,
+        TradeDirection::ZeroForOne,
+    )
+    .await;
+    let segmenter_index = instruction.accounts.len() - SWAP_FIXED_REMAINING_ACCOUNTS;
+    instruction.accounts[segmenter_index] = AccountMeta::new_readonly(segmenter.pubkey(), false);
+    instruction.accounts[segmenter_index + 4] = AccountMeta::new(kickback_key, false);
+    test_env
+        .process_instructions(&[instruction], &user)
+        .await
+        .unwrap_transaction();
+
+    let kickback: SegmenterKickback = test_env.fetch_account(kickback_key).await;
+    assert_eq!(kickback.token_0_accrued, 0);
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq_with_copy!(pool_state.segmenter_kickbacks_token_0, 0);
+}
@@ -0,0 +1,133 @@
+use anchor_spl::token_interface::TokenAccount;
+use gamma::error::GammaError;
+use gamma::states::{Solver, SOLVER_UNBOND_PERIOD};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+const MIN_BOND: u64 = 1000;
+const BOND: u64 = 5000;
+
+/// A solver bonding token_0, registered and bonded. Returns the solver's authority and the
+/// counterparty of its fills.
+async fn setup() -> (TestEnv, Keypair, Keypair) {
+    let solver_authority = Keypair::new();
+    let counterparty = Keypair::new();
+    let admin = get_admin();
+    let mut test_env = TestEnv::new(vec![
+        solver_authority.pubkey(),
+        counterparty.pubkey(),
+        admin.pubkey(),
+    ])
+    .await;
+
+    let bond_mint = test_env.token_0_mint;
+    let solver_token_account = test_env
+        .get_or_create_associated_token_account(
+            solver_authority.pubkey(),
+            bond_mint,
+            &solver_authority,
+        )
+        .await;
+    test_env
+        .mint_base_tokens(solver_token_account, BOND, bond_mint)
+        .await;
+    test_env
+        .register_solver(&admin, solver_authority.pubkey(), bond_mint, MIN_BOND)
+        .await;
+    test_env
+        .try_bond_solver(&solver_authority, BOND)
+        .await
+        .unwrap_transaction();
+
+    (test_env, solver_authority, counterparty)
+}
+
+async fn token_balance(test_env: &mut TestEnv, owner: &Keypair) -> u64 {
+    let token_account = test_env
+        .get_or_create_associated_token_account(owner.pubkey(), test_env.token_0_mint, owner)
+        .await;
+    let token_account: TokenAccount = test_env.fetch_account(token_account).await;
+    token_account.amount
+}
+
+async fn fetch_solver(test_env: &mut TestEnv, solver_authority: Pubkey) -> Solver {
+    test_env
+        .fetch_account(test_env.solver_key(solver_authority))
+        .await
+}
+
+#[tokio::test]
+async fn should_slash_the_bond_to_the_counterparty() {
+    let (mut test_env, solver_authority, counterparty) = setup().await;
+    let admin = get_admin();
+    let solver = fetch_solver(&mut test_env, solver_authority.pubkey()).await;
+    assert_eq!(solver.bonded_amount, BOND);
+    assert!(solver.can_quote());
+
+    test_env
+        .try_slash_solver(&admin, solver_authority.pubkey(), &counterparty, 4500)
+        .await
+        .unwrap_transaction();
+
+    assert_eq!(token_balance(&mut test_env, &counterparty).await, 4500);
+    let solver = fetch_solver(&mut test_env, solver_authority.pubkey()).await;
+    assert_eq!(solver.bonded_amount, BOND - 4500);
+    assert_eq!(solver.slashed_amount, 4500);
+    assert_eq!(solver.failed_settlements, 1);
+    // Below its minimum bond, the solver can't quote until it bonds again
+    assert!(!solver.can_quote());
+
+    // Nor can more than the bond be slashed
+    let result = test_env
+        .try_slash_solver(&admin, solver_authority.pubkey(), &counterparty, 501)
+        .await;
+    assert_error!(result, GammaError::InsufficientBond);
+}
+
+#[tokio::test]
+async fn should_not_slash_without_the_admin() {
+    let (mut test_env, solver_authority, counterparty) = setup().await;
+
+    let result = test_env
+        .try_slash_solver(&counterparty, solver_authority.pubkey(), &counterparty, 1)
+        .await;
+    assert_error!(result, GammaError::InvalidOwner);
+}
+
+#[tokio::test]
+async fn should_withdraw_the_bond_after_the_unbond_period() {
+    let (mut test_env, solver_authority, _) = setup().await;
+    let admin = get_admin();
+
+    // An active solver keeps its bond
+    let result = test_env
+        .try_withdraw_solver_bond(&solver_authority, BOND)
+        .await;
+    assert_error!(result, GammaError::SolverBonded);
+
+    // Once deactivated it can still be slashed for the unbond period
+    test_env
+        .update_solver(&admin, solver_authority.pubkey(), false, MIN_BOND)
+        .await;
+    let result = test_env
+        .try_withdraw_solver_bond(&solver_authority, BOND)
+        .await;
+    assert_error!(result, GammaError::SolverBonded);
+
+    test_env.jump_seconds(SOLVER_UNBOND_PERIOD as i64).await;
+    let result = test_env
+        .try_withdraw_solver_bond(&solver_authority, BOND + 1)
+        .await;
+    assert_error!(result, GammaError::InsufficientBond);
+    test_env
+        .try_withdraw_solver_bond(&solver_authority, BOND)
+        .await
+        .unwrap_transaction();
+
+    assert_eq!(token_balance(&mut test_env, &solver_authority).await, BOND);
+    let solver = fetch_solver(&mut test_env, solver_authority.pubkey()).await;
+    assert_eq!(solver.bonded_amount, 0);
+}
@@ -0,0 +1,178 @@
+use anchor_spl::token::TokenAccount;
+use gamma::error::GammaError;
+use gamma::states::{PoolRewards, UserPoolLiquidity, UserStake, USER_POOL_LIQUIDITY_SEED};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+const EMISSIONS_PER_SECOND: u64 = 10;
+const STAKE_AMOUNT: u64 = 1000000;
+
+/// A pool created by the user with a token_1 reward funded for a long time
+async fn setup() -> (TestEnv, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_0_account, 100000000000000, test_env.token_0_mint)
+        .await;
+    let user_token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_1_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    let reward_mint = test_env.token_1_mint;
+    let reward_vault = test_env
+        .create_reward_vault(&admin, pool_id, reward_mint, EMISSIONS_PER_SECOND)
+        .await;
+    test_env
+        .mint_base_tokens(reward_vault, 100000000, reward_mint)
+        .await;
+
+    (test_env, user, pool_id)
+}
+
+fn user_pool_liquidity_key(pool_id: Pubkey, owner: Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            USER_POOL_LIQUIDITY_SEED.as_bytes(),
+            pool_id.as_ref(),
+            owner.as_ref(),
+        ],
+        &gamma::id(),
+    )
+    .0
+}
+
+#[tokio::test]
+async fn should_track_the_staked_lp_of_the_liquidity_and_the_pool() {
+    let (mut test_env, user, pool_id) = setup().await;
+
+    test_env
+        .try_stake_lp(&user, pool_id, STAKE_AMOUNT)
+        .await
+        .unwrap_transaction();
+    let user_pool_liquidity: UserPoolLiquidity = test_env
+        .fetch_account(user_pool_liquidity_key(pool_id, user.pubkey()))
+        .await;
+    assert_eq!(user_pool_liquidity.extension.lp_tokens_staked, STAKE_AMOUNT);
+    let pool_rewards: PoolRewards = test_env
+        .fetch_account(test_env.pool_rewards_key(pool_id))
+        .await;
+    assert_eq!(pool_rewards.total_staked, STAKE_AMOUNT);
+
+    // Only what is staked can be unstaked
+    let result = test_env
+        .try_unstake_lp(&user, pool_id, STAKE_AMOUNT + 1)
+        .await;
+    assert_error!(result, GammaError::InvalidInput);
+
+    test_env
+        .try_unstake_lp(&user, pool_id, STAKE_AMOUNT)
+        .await
+        .unwrap_transaction();
+    let user_pool_liquidity: UserPoolLiquidity = test_env
+        .fetch_account(user_pool_liquidity_key(pool_id, user.pubkey()))
+        .await;
+    assert_eq!(user_pool_liquidity.extension.lp_tokens_staked, 0);
+    let pool_rewards: PoolRewards = test_env
+        .fetch_account(test_env.pool_rewards_key(pool_id))
+        .await;
+    assert_eq!(pool_rewards.total_staked, 0);
+}
+
+#[tokio::test]
+async fn should_not_stake_more_than_the_liquidity_holds() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let user_pool_liquidity: UserPoolLiquidity = test_env
+        .fetch_account(user_pool_liquidity_key(pool_id, user.pubkey()))
+        .await;
+    let lp_tokens_owned = user_pool_liquidity.lp_tokens_owned as u64;
+
+    let result = test_env
+        .try_stake_lp(&user, pool_id, lp_tokens_owned + 1)
+        .await;
+    assert_error!(result, GammaError::InvalidInput);
+}
+
+#[tokio::test]
+async fn should_pay_the_rewards_emitted_to_the_only_stake() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let reward_mint = test_env.token_1_mint;
+
+    test_env
+        .try_stake_lp(&user, pool_id, STAKE_AMOUNT)
+        .await
+        .unwrap_transaction();
+    let pool_rewards: PoolRewards = test_env
+        .fetch_account(test_env.pool_rewards_key(pool_id))
+        .await;
+    let staked_at = pool_rewards.last_update_time;
+    test_env.jump_seconds(100).await;
+
+    let recipient_token_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), reward_mint, &user)
+        .await;
+    let token_account_before: TokenAccount = test_env.fetch_account(recipient_token_account).await;
+    test_env
+        .try_claim_rewards(&user, pool_id, 0, reward_mint)
+        .await
+        .unwrap_transaction();
+    let token_account_after: TokenAccount = test_env.fetch_account(recipient_token_account).await;
+
+    // All of the emissions go to the only stake, less the rounding
+    let pool_rewards: PoolRewards = test_env
+        .fetch_account(test_env.pool_rewards_key(pool_id))
+        .await;
+    let emitted = EMISSIONS_PER_SECOND * (pool_rewards.last_update_time - staked_at);
+    let claimed = token_account_after.amount - token_account_before.amount;
+    assert!(claimed <= emitted);
+    assert!(claimed + 1 >= emitted);
+    let user_stake: UserStake = test_env
+        .fetch_account(test_env.user_stake_key(pool_id, user.pubkey()))
+        .await;
+    assert_eq!(user_stake.rewards_owed[0], 0);
+
+    // Unstaked LP earns nothing more
+    test_env
+        .try_unstake_lp(&user, pool_id, STAKE_AMOUNT)
+        .await
+        .unwrap_transaction();
+    test_env
+        .try_claim_rewards(&user, pool_id, 0, reward_mint)
+        .await
+        .unwrap_transaction();
+    test_env.jump_seconds(100).await;
+    let token_account_before: TokenAccount = test_env.fetch_account(recipient_token_account).await;
+    test_env
+        .try_claim_rewards(&user, pool_id, 0, reward_mint)
+        .await
+        .unwrap_transaction();
+    let token_account_after: TokenAccount = test_env.fetch_account(recipient_token_account).await;
+    assert_eq!(token_account_after.amount, token_account_before.amount);
+}
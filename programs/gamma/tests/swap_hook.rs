@@ -0,0 +1,207 @@
+use anchor_lang::prelude::Pubkey;
+use gamma::curve::TradeDirection;
+use gamma::error::GammaError;
+use gamma::instructions::{SwapBatchLeg, SWAP_FIXED_REMAINING_ACCOUNTS};
+use solana_program_test::{processor, tokio};
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::{signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+/// Hook counting the swaps of the pool in its counter account. It also takes the pool state,
+/// which the pool must no longer borrow when calling it.
+fn process_mock_hook(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [pool_swap_hook, _payer, counter, pool_state] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !pool_swap_hook.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    pool_state.try_borrow_data()?;
+    let mut data = counter.try_borrow_mut_data()?;
+    let count = u64::from_le_bytes(data[..8].try_into().unwrap()) + 1;
+    data[..8].copy_from_slice(&count.to_le_bytes());
+    Ok(())
+}
+
+/// A pool calling the mock hook after each swap. Returns the user, the pool, the hook program
+/// and its counter.
+async fn setup() -> (TestEnv, Keypair, Pubkey, Pubkey, Pubkey) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let hook_program = Pubkey::new_unique();
+    let mut test_env = TestEnv::new_with_config(
+        vec![user.pubkey(), admin.pubkey()],
+        vec![ProgramInfo {
+            program_name: "mock_hook".to_string(),
+            program_id: hook_program,
+            process_instruction: processor!(process_mock_hook),
+        }],
+    )
+    .await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_0_account, 100000000000000, test_env.token_0_mint)
+        .await;
+    let user_token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_1_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    let counter = Pubkey::new_unique();
+    test_env.program_test_context.set_account(
+        &counter,
+        &AccountSharedData::new(1000000000, 8, &hook_program),
+    );
+    test_env.add_swap_hook_program(&admin, hook_program).await;
+    test_env
+        .set_pool_swap_hook(&admin, pool_id, hook_program, 2)
+        .await;
+
+    (test_env, user, pool_id, hook_program, counter)
+}
+
+/// The remaining accounts of a swap passing `pool_swap_hook` as the hook account of the pool
+fn hook_remaining_accounts(
+    pool_swap_hook: Pubkey,
+    hook_program: Pubkey,
+    counter: Pubkey,
+    pool_id: Pubkey,
+) -> Vec<AccountMeta> {
+    // None of the fixed accounts and no strategy for the output vault
+    let mut accounts =
+        vec![AccountMeta::new_readonly(gamma::id(), false); SWAP_FIXED_REMAINING_ACCOUNTS + 1];
+    accounts.extend([
+        AccountMeta::new_readonly(pool_swap_hook, false),
+        AccountMeta::new_readonly(hook_program, false),
+        AccountMeta::new(counter, false),
+        AccountMeta::new(pool_id, false),
+    ]);
+    accounts
+}
+
+async fn swap_instruction(
+    test_env: &mut TestEnv,
+    user: &Keypair,
+    pool_id: Pubkey,
+    trade_direction: TradeDirection,
+) -> Instruction {
+    test_env
+        .swap_base_input_instruction(user, pool_id, 0, 1000000, 0, trade_direction)
+        .await
+}
+
+#[tokio::test]
+async fn should_call_the_hook_after_a_swap() {
+    let (mut test_env, user, pool_id, hook_program, counter) = setup().await;
+
+    // Without the hook accounts, the pool doesn't swap
+    let result = test_env
+        .try_swap_base_input(&user, pool_id, 0, 1000000, 0, TradeDirection::ZeroForOne)
+        .await;
+    assert_error!(result, GammaError::InvalidSwapHook);
+
+    let pool_swap_hook = test_env.pool_swap_hook_key(pool_id);
+    let mut swap =
+        swap_instruction(&mut test_env, &user, pool_id, TradeDirection::ZeroForOne).await;
+    swap.accounts.extend(hook_remaining_accounts(
+        pool_swap_hook,
+        hook_program,
+        counter,
+        pool_id,
+    ));
+    test_env
+        .process_instructions(&[swap], &user)
+        .await
+        .unwrap_transaction();
+
+    let counter_account = test_env.get_account_info(counter).await.unwrap().unwrap();
+    assert_eq!(counter_account.data, 1u64.to_le_bytes());
+}
+
+#[tokio::test]
+async fn should_reject_another_account_as_the_hook_account() {
+    let (mut test_env, user, pool_id, hook_program, counter) = setup().await;
+
+    let mut swap =
+        swap_instruction(&mut test_env, &user, pool_id, TradeDirection::ZeroForOne).await;
+    swap.accounts.extend(hook_remaining_accounts(
+        counter,
+        hook_program,
+        counter,
+        pool_id,
+    ));
+    let result = test_env.process_instructions(&[swap], &user).await;
+    assert_error!(result, GammaError::InvalidSwapHook);
+}
+
+#[tokio::test]
+async fn should_not_route_or_batch_through_a_hooked_pool() {
+    let (mut test_env, user, pool_id, _, _) = setup().await;
+
+    let mut swap_route = get_instruction(
+        gamma::instruction::SwapRoute {
+            amount_in: 1000000,
+            minimum_amount_out: 0,
+        },
+        gamma::accounts::SwapRoute {
+            payer: user.pubkey(),
+            instructions_sysvar: None,
+        },
+    );
+    for trade_direction in [TradeDirection::ZeroForOne, TradeDirection::OneForZero] {
+        let hop = swap_instruction(&mut test_env, &user, pool_id, trade_direction).await;
+        swap_route.accounts.extend(hop.accounts);
+    }
+    let result = test_env.process_instructions(&[swap_route], &user).await;
+    assert_error!(result, GammaError::SwapHookNotSupported);
+
+    let mut swap_batch = get_instruction(
+        gamma::instruction::SwapBatch {
+            legs: vec![SwapBatchLeg {
+                zero_for_one: true,
+                amount_in: 1000000,
+                minimum_amount_out: 0,
+            }],
+        },
+        gamma::accounts::SwapBatch {
+            payer: user.pubkey(),
+            instructions_sysvar: None,
+        },
+    );
+    let pool = swap_instruction(&mut test_env, &user, pool_id, TradeDirection::ZeroForOne).await;
+    swap_batch.accounts.extend(pool.accounts);
+    let result = test_env.process_instructions(&[swap_batch], &user).await;
+    assert_error!(result, GammaError::SwapHookNotSupported);
+}
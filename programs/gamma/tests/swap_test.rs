@@ -1,6 +1,6 @@
 use gamma::{
     curve::TradeDirection,
-    states::{ObservationState, PoolState},
+    states::{ObservationState, PoolState, DEFAULT_WITHDRAW_DELAY_SLOTS},
 };
 use solana_program_test::tokio;
 use solana_sdk::{signature::Keypair, signer::Signer};
@@ -49,6 +49,7 @@ async fn test_withdraw_deposit() {
         .deposit(&user, pool_id, amm_index, 1, 999999, 99999)
         .await;
 
+    test_env.jump_slots(DEFAULT_WITHDRAW_DELAY_SLOTS).await;
     test_env.withdraw(&user, pool_id, amm_index, 1, 0, 0).await;
 }
 
@@ -1,17 +1,25 @@
 #![allow(dead_code)]
-pub mod jupiter;
+pub mod snapshot;
 
-use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
 use anchor_spl::token::TokenAccount;
 use anchor_spl::token_2022::spl_token_2022;
 use gamma::curve::TradeDirection;
+use gamma::instructions::GRADUATION_POSITION_ID;
 use gamma::states::{
-    ObservationState, AMM_CONFIG_SEED, OBSERVATION_NUM, OBSERVATION_SEED, POOL_LP_MINT_SEED,
-    POOL_SEED, POOL_VAULT_SEED, USER_POOL_LIQUIDITY_SEED,
+    BondingCurve, ConfigRole, LendingProtocol, ObservationState, PoolState, Solver,
+    AMM_CONFIG_ROLES_SEED, AMM_CONFIG_SEED, BONDING_CURVE_SEED, BONDING_CURVE_VAULT_SEED,
+    EMERGENCY_ESCROW_SEED, EMERGENCY_ESCROW_VAULT_SEED, FLASH_LOAN_SEED, GAUGE_CONTROLLER_SEED,
+    GAUGE_SEED, GAUGE_VOTER_SEED, LP_BURN_PROOF_SEED, OBSERVATION_NUM, OBSERVATION_SEED,
+    PAIR_INDEX_SEED, PAUSE_EXPIRY_SEED, POOL_LP_MINT_SEED, POOL_REWARDS_SEED, POOL_SEED,
+    POOL_SWAP_HOOK_SEED, POOL_VAULT_SEED, POSITION_SEED, REWARD_VAULT_SEED,
+    SEGMENTER_KICKBACK_SEED, SEGMENTER_REGISTRY_SEED, SOLVER_BOND_VAULT_SEED, SOLVER_SEED,
+    STRATEGY_ALLOWLIST_SEED, STRATEGY_SEED, SWAP_HOOK_ALLOWLIST_SEED, USER_POOL_LIQUIDITY_SEED,
+    USER_STAKE_SEED, VAULT_STRATEGY_SEED,
 };
 use gamma::AUTH_SEED;
 use solana_program_runtime::invoke_context::BuiltinFunctionWithContext;
-use solana_sdk::instruction::Instruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
 use solana_sdk::program_option::COption;
 use solana_sdk::program_pack::Pack;
 
@@ -363,6 +371,111 @@ impl TestEnv {
             .expect("Failed to create token mint");
     }
 
+    /// Creates a token 2022 mint charging `transfer_fee_basis_points` on transfers, capped at
+    /// `maximum_fee`. The mint authority also controls the transfer fee config.
+    pub async fn create_token_2022_mint_with_transfer_fee(
+        &mut self,
+        token_mint: &Keypair,
+        authority: &Pubkey,
+        decimals: u8,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    ) {
+        use spl_token_2022::extension::ExtensionType;
+
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap();
+        let latest_blockhash = self
+            .program_test_context
+            .banks_client
+            .get_latest_blockhash()
+            .await
+            .unwrap();
+        let instructions = [
+            solana_sdk::system_instruction::create_account(
+                &get_wallet().pubkey(),
+                &token_mint.pubkey(),
+                Rent::default().minimum_balance(space),
+                space as u64,
+                &spl_token_2022::id(),
+            ),
+            spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config(
+                &spl_token_2022::id(),
+                &token_mint.pubkey(),
+                Some(authority),
+                Some(authority),
+                transfer_fee_basis_points,
+                maximum_fee,
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::id(),
+                &token_mint.pubkey(),
+                authority,
+                None,
+                decimals,
+            )
+            .unwrap(),
+        ];
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&get_wallet().pubkey()),
+                &[&get_wallet(), token_mint],
+                latest_blockhash,
+            ))
+            .await
+            .expect("Failed to create token 2022 mint");
+    }
+
+    /// Schedules a new transfer fee, token 2022 applies it two epochs later
+    pub async fn set_transfer_fee(
+        &mut self,
+        token_mint: Pubkey,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    ) {
+        let ix = spl_token_2022::extension::transfer_fee::instruction::set_transfer_fee(
+            &spl_token_2022::id(),
+            &token_mint,
+            &self.mint_authority.pubkey(),
+            &[],
+            transfer_fee_basis_points,
+            maximum_fee,
+        )
+        .unwrap();
+        let transaction =
+            get_signed_transaction(&mut self.program_test_context, &[ix], &self.mint_authority)
+                .await;
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("Failed to set transfer fee");
+    }
+
+    /// Warps forward by `epochs` epochs, keeping the unix timestamp moving with the slots
+    pub async fn jump_epochs(&mut self, epochs: u64) {
+        let clock: Clock = self
+            .program_test_context
+            .banks_client
+            .get_sysvar()
+            .await
+            .unwrap();
+        let slots_per_epoch = self
+            .program_test_context
+            .genesis_config()
+            .epoch_schedule
+            .slots_per_epoch;
+        self.program_test_context
+            .warp_to_slot(clock.slot + epochs * slots_per_epoch)
+            .unwrap();
+    }
+
     pub async fn create_token_account(
         &mut self,
         account: &Keypair,
@@ -421,12 +534,14 @@ impl TestEnv {
             .get_latest_blockhash()
             .await
             .unwrap();
-        let associated_token_account = get_associated_token_address(account, mint);
+        let token_program = self.token_program_of(*mint).await;
+        let associated_token_account =
+            get_associated_token_address_with_program_id(account, mint, &token_program);
         let ix = spl_associated_token_account::instruction::create_associated_token_account(
             &payer.pubkey(),
             account,
             mint,
-            &anchor_spl::token::ID,
+            &token_program,
         );
 
         self.program_test_context
@@ -449,7 +564,9 @@ impl TestEnv {
         mint: Pubkey,
         payer: &Keypair,
     ) -> Pubkey {
-        let associated_token_account = get_associated_token_address(&account, &mint);
+        let token_program = self.token_program_of(mint).await;
+        let associated_token_account =
+            get_associated_token_address_with_program_id(&account, &mint, &token_program);
 
         let existing_account: Result<TokenAccount, BanksClientError> =
             self.try_fetch_account(associated_token_account).await;
@@ -495,14 +612,24 @@ impl TestEnv {
             .map_err(|_| BanksClientError::ClientError("Failed to deserialize account"))
     }
 
+    /// The spl token program or token program 2022, whichever owns the mint
+    pub async fn token_program_of(&mut self, mint: Pubkey) -> Pubkey {
+        self.get_account_info(mint)
+            .await
+            .unwrap()
+            .expect("Mint not found")
+            .owner
+    }
+
     pub async fn mint_base_tokens(
         &mut self,
         token_account: Pubkey,
         amount: u64,
         token_mint: Pubkey,
     ) {
-        let mint_ix = anchor_spl::token::spl_token::instruction::mint_to(
-            &anchor_spl::token::spl_token::id(),
+        let token_program = self.token_program_of(token_mint).await;
+        let mint_ix = spl_token_2022::instruction::mint_to(
+            &token_program,
             &token_mint,
             &token_account,
             &self.mint_authority.pubkey(),
@@ -537,6 +664,19 @@ impl TestEnv {
         self.jump_seconds(seconds).await;
     }
 
+    /// Moves past the slots a pool's withdraw delay keeps fresh deposits in
+    pub async fn jump_slots(&mut self, slots: u64) {
+        let clock: Clock = self
+            .program_test_context
+            .banks_client
+            .get_sysvar()
+            .await
+            .unwrap();
+        self.program_test_context
+            .warp_to_slot(clock.slot + slots)
+            .unwrap();
+    }
+
     pub async fn warp_to_timestamp(&mut self, timestamp: i64) {
         const NANOSECONDS_IN_SECOND: i64 = 1_000_000_000;
 
@@ -698,6 +838,15 @@ impl TestEnv {
             &gamma::ID,
         )
         .0;
+        let pair_index = Pubkey::find_program_address(
+            &[
+                PAIR_INDEX_SEED.as_bytes(),
+                self.token_0_mint.to_bytes().as_ref(),
+                self.token_1_mint.to_bytes().as_ref(),
+            ],
+            &gamma::ID,
+        )
+        .0;
 
         let accounts = gamma::accounts::Initialize {
             creator: user.pubkey(),
@@ -713,9 +862,10 @@ impl TestEnv {
             token_1_vault,
             create_pool_fee: create_pool_fee,
             observation_state: observation_key,
+            pair_index,
             token_program: spl_token::id(),
-            token_0_program: spl_token::id(),
-            token_1_program: spl_token::id(),
+            token_0_program: self.token_program_of(self.token_0_mint).await,
+            token_1_program: self.token_program_of(self.token_1_mint).await,
             associated_token_program: spl_associated_token_account::id(),
             system_program: system_program::ID,
             rent: sysvar::rent::id(),
@@ -727,6 +877,8 @@ impl TestEnv {
             open_time,
             max_trade_fee_rate: 0,
             volatility_factor: 0,
+            amplification: 0,
+            token_0_weight: 0,
         };
 
         let transaction = self
@@ -751,6 +903,27 @@ impl TestEnv {
         maximum_token_0_amount: u64,
         maximum_token_1_amount: u64,
     ) {
+        self.try_deposit(
+            user,
+            pool_id,
+            amm_config_index,
+            lp_token_amount,
+            maximum_token_0_amount,
+            maximum_token_1_amount,
+        )
+        .await
+        .unwrap_transaction();
+    }
+
+    pub async fn try_deposit(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        amm_config_index: u16,
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    ) -> ProcessTransactionResult {
         let (amm_config_key, __bump) = Pubkey::find_program_address(
             &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
             &gamma::ID,
@@ -818,6 +991,7 @@ impl TestEnv {
             token_program_2022: spl_token_2022::id(),
             vault_0_mint: self.token_0_mint,
             vault_1_mint: self.token_1_mint,
+            system_program: system_program::ID,
         };
 
         let data = gamma::instruction::Deposit {
@@ -832,9 +1006,8 @@ impl TestEnv {
 
         self.program_test_context
             .banks_client
-            .process_transaction(transaction)
+            .process_transaction_with_metadata(transaction)
             .await
-            .unwrap();
     }
 
     pub async fn withdraw(
@@ -846,6 +1019,31 @@ impl TestEnv {
         minimum_token_0_amount: u64,
         minimum_token_1_amount: u64,
     ) {
+        self.try_withdraw(
+            user,
+            pool_id,
+            amm_config_index,
+            lp_token_amount,
+            minimum_token_0_amount,
+            minimum_token_1_amount,
+            vec![],
+        )
+        .await
+        .unwrap_transaction();
+    }
+
+    /// Withdraws with `remaining_accounts` after the accounts of the instruction, e.g. the
+    /// strategies of the vaults
+    pub async fn try_withdraw(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        amm_config_index: u16,
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+        remaining_accounts: Vec<AccountMeta>,
+    ) -> ProcessTransactionResult {
         let (amm_config_key, __bump) = Pubkey::find_program_address(
             &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
             &gamma::ID,
@@ -911,6 +1109,7 @@ impl TestEnv {
             vault_0_mint: self.token_0_mint,
             vault_1_mint: self.token_1_mint,
             memo_program: spl_memo::id(),
+            system_program: system_program::ID,
         };
 
         let data = gamma::instruction::Withdraw {
@@ -919,15 +1118,108 @@ impl TestEnv {
             minimum_token_1_amount,
         };
 
-        let transaction = self
-            .encode_instruction_and_sign_transaction(data, accounts, user)
+        let mut instruction = get_instruction(data, accounts);
+        instruction.accounts.extend(remaining_accounts);
+        self.process_instructions(&[instruction], user).await
+    }
+
+    /// Deposits only token_0, or token_1, of the pool
+    pub async fn try_deposit_single_token(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        token_0: bool,
+        maximum_amount_in: u64,
+        minimum_lp_token_amount: u64,
+    ) -> ProcessTransactionResult {
+        let pool_state: PoolState = self.fetch_account(pool_id).await;
+        let (input_vault, input_token_mint, input_token_program) = if token_0 {
+            (
+                pool_state.token_0_vault,
+                pool_state.token_0_mint,
+                pool_state.token_0_program,
+            )
+        } else {
+            (
+                pool_state.token_1_vault,
+                pool_state.token_1_mint,
+                pool_state.token_1_program,
+            )
+        };
+        let input_token_account = self
+            .get_or_create_associated_token_account(user.pubkey(), input_token_mint, user)
             .await;
 
-        self.program_test_context
-            .banks_client
-            .process_transaction(transaction)
-            .await
-            .unwrap();
+        let instruction = get_instruction(
+            gamma::instruction::DepositSingleToken {
+                maximum_amount_in,
+                minimum_lp_token_amount,
+            },
+            gamma::accounts::DepositSingleToken {
+                owner: user.pubkey(),
+                amm_config: pool_state.amm_config,
+                pool_state: pool_id,
+                user_pool_liquidity: self.user_pool_liquidity_key(pool_id, user.pubkey()),
+                input_token_account,
+                input_vault,
+                input_token_program,
+                input_token_mint,
+                observation_state: pool_state.observation_key,
+                system_program: system_program::ID,
+            },
+        );
+        self.process_instructions(&[instruction], user).await
+    }
+
+    /// Withdraws lp entirely in token_0, or token_1, of the pool
+    pub async fn try_withdraw_single_token(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        token_0: bool,
+        lp_token_amount: u64,
+        minimum_amount_out: u64,
+    ) -> ProcessTransactionResult {
+        let pool_state: PoolState = self.fetch_account(pool_id).await;
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (output_vault, output_token_mint, output_token_program) = if token_0 {
+            (
+                pool_state.token_0_vault,
+                pool_state.token_0_mint,
+                pool_state.token_0_program,
+            )
+        } else {
+            (
+                pool_state.token_1_vault,
+                pool_state.token_1_mint,
+                pool_state.token_1_program,
+            )
+        };
+        let output_token_account = self
+            .get_or_create_associated_token_account(user.pubkey(), output_token_mint, user)
+            .await;
+
+        let instruction = get_instruction(
+            gamma::instruction::WithdrawSingleToken {
+                lp_token_amount,
+                minimum_amount_out,
+            },
+            gamma::accounts::WithdrawSingleToken {
+                owner: user.pubkey(),
+                authority,
+                amm_config: pool_state.amm_config,
+                pool_state: pool_id,
+                user_pool_liquidity: self.user_pool_liquidity_key(pool_id, user.pubkey()),
+                output_token_account,
+                output_vault,
+                output_token_program,
+                output_token_mint,
+                observation_state: pool_state.observation_key,
+                system_program: system_program::ID,
+            },
+        );
+        self.process_instructions(&[instruction], user).await
     }
 
     pub async fn init_user_pool_liquidity(&mut self, user: &Keypair, pool_id: Pubkey) {
@@ -972,106 +1264,33 @@ impl TestEnv {
             .unwrap();
     }
 
-    pub async fn swap_base_input(
-        &mut self,
-        user: &Keypair,
-        pool_id: Pubkey,
-        amm_config_index: u16,
-        amount_in: u64,
-        minimum_amount_out: u64,
-        trade_direction: TradeDirection,
-    ) {
-        let (authority, __bump) =
-            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
-        let (amm_config_key, __bump) = Pubkey::find_program_address(
-            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
-            &gamma::ID,
-        );
-
-        let user_token_0_account: Pubkey = self
-            .get_or_create_associated_token_account(user.pubkey(), self.token_0_mint.clone(), &user)
-            .await;
+    pub async fn add_partner(&mut self, admin: &Keypair, pool_id: Pubkey, partner_id: u64) {
+        let accounts = gamma::accounts::AddPartner {
+            owner: admin.pubkey(),
+            pool_state: pool_id,
+        };
+        let data = gamma::instruction::AddPartner { partner_id };
 
-        let user_token_1_account = self
-            .get_or_create_associated_token_account(user.pubkey(), self.token_1_mint.clone(), &user)
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
             .await;
 
-        let (token_0_vault, __bump) = Pubkey::find_program_address(
-            &[
-                POOL_VAULT_SEED.as_bytes(),
-                pool_id.to_bytes().as_ref(),
-                self.token_0_mint.to_bytes().as_ref(),
-            ],
-            &gamma::ID,
-        );
-        let (token_1_vault, __bump) = Pubkey::find_program_address(
-            &[
-                POOL_VAULT_SEED.as_bytes(),
-                pool_id.to_bytes().as_ref(),
-                self.token_1_mint.to_bytes().as_ref(),
-            ],
-            &gamma::ID,
-        );
-        let (observation_key, __bump) = Pubkey::find_program_address(
-            &[OBSERVATION_SEED.as_bytes(), pool_id.to_bytes().as_ref()],
-            &gamma::ID,
-        );
-
-        let (
-            input_token_account,
-            output_token_account,
-            input_token_mint,
-            output_token_mint,
-            input_vault,
-            output_vault,
-            input_token_program,
-            output_token_program,
-        ) = match trade_direction {
-            TradeDirection::ZeroForOne => (
-                user_token_0_account,
-                user_token_1_account,
-                self.token_0_mint,
-                self.token_1_mint,
-                token_0_vault,
-                token_1_vault,
-                spl_token::id(),
-                spl_token::id(),
-            ),
-            TradeDirection::OneForZero => (
-                user_token_1_account,
-                user_token_0_account,
-                self.token_1_mint,
-                self.token_0_mint,
-                token_1_vault,
-                token_0_vault,
-                spl_token::id(),
-                spl_token::id(),
-            ),
-        };
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
 
-        let accounts = gamma::accounts::Swap {
-            payer: user.pubkey(),
-            authority,
-            amm_config: amm_config_key,
+    pub async fn remove_partner(&mut self, admin: &Keypair, pool_id: Pubkey, partner_id: u64) {
+        let accounts = gamma::accounts::RemovePartner {
+            owner: admin.pubkey(),
             pool_state: pool_id,
-            observation_state: observation_key,
-            input_token_account,
-            output_token_account,
-            input_vault,
-            output_vault,
-            input_token_program,
-            output_token_program,
-            input_token_mint,
-            output_token_mint,
-        };
-
-        let data = gamma::instruction::SwapBaseInput {
-            amount_in,
-            minimum_amount_out,
         };
+        let data = gamma::instruction::RemovePartner { partner_id };
 
         let transaction = self
-            .encode_instruction_and_sign_transaction(data, accounts, user)
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
             .await;
 
         self.program_test_context
@@ -1081,6 +1300,1933 @@ impl TestEnv {
             .unwrap();
     }
 
+    pub async fn update_partner_lp_link(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        partner: Option<u64>,
+    ) {
+        let user_pool_liquidity = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                user.pubkey().to_bytes().as_ref(),
+            ],
+            &gamma::id(),
+        )
+        .0;
+
+        let accounts = gamma::accounts::UpdatePartnerLpLink {
+            user: user.pubkey(),
+            pool_state: pool_id,
+            user_pool_liquidity,
+        };
+        let data = gamma::instruction::UpdatePartnerLpLink { partner };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, user)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    pub async fn open_position(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        position_id: u64,
+        lp_token_amount: u64,
+        unlock_time: u64,
+    ) -> Pubkey {
+        let user_pool_liquidity = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                user.pubkey().to_bytes().as_ref(),
+            ],
+            &gamma::id(),
+        )
+        .0;
+        let position = Pubkey::find_program_address(
+            &[
+                POSITION_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                user.pubkey().to_bytes().as_ref(),
+                &position_id.to_le_bytes(),
+            ],
+            &gamma::id(),
+        )
+        .0;
+
+        let accounts = gamma::accounts::OpenPosition {
+            owner: user.pubkey(),
+            pool_state: pool_id,
+            user_pool_liquidity,
+            position,
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::OpenPosition {
+            position_id,
+            lp_token_amount,
+            unlock_time,
+        };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, user)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+
+        position
+    }
+
+    pub async fn emergency_withdraw_to_escrow(
+        &mut self,
+        admin: &Keypair,
+        pool_id: Pubkey,
+    ) -> Pubkey {
+        self.try_emergency_withdraw_to_escrow(admin, pool_id)
+            .await
+            .unwrap_transaction();
+        self.emergency_escrow_accounts(pool_id).0
+    }
+
+    pub async fn try_emergency_withdraw_to_escrow(
+        &mut self,
+        admin: &Keypair,
+        pool_id: Pubkey,
+    ) -> ProcessTransactionResult {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (emergency_escrow, escrow_token_0_vault, escrow_token_1_vault) =
+            self.emergency_escrow_accounts(pool_id);
+        let (token_0_vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                self.token_0_mint.to_bytes().as_ref(),
+            ],
+            &gamma::ID,
+        );
+        let (token_1_vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                self.token_1_mint.to_bytes().as_ref(),
+            ],
+            &gamma::ID,
+        );
+
+        let accounts = gamma::accounts::EmergencyWithdrawToEscrow {
+            owner: admin.pubkey(),
+            authority,
+            pool_state: pool_id,
+            emergency_escrow,
+            escrow_token_0_vault,
+            escrow_token_1_vault,
+            token_0_vault,
+            token_1_vault,
+            vault_0_mint: self.token_0_mint,
+            vault_1_mint: self.token_1_mint,
+            token_0_program: spl_token::id(),
+            token_1_program: spl_token::id(),
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::EmergencyWithdrawToEscrow {};
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Moves the escrow of the pool back into the pool vaults
+    pub async fn try_restore_escrow_to_pool(
+        &mut self,
+        admin: &Keypair,
+        pool_id: Pubkey,
+    ) -> ProcessTransactionResult {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (emergency_escrow, escrow_token_0_vault, escrow_token_1_vault) =
+            self.emergency_escrow_accounts(pool_id);
+
+        let accounts = gamma::accounts::RestoreEscrowToPool {
+            owner: admin.pubkey(),
+            authority,
+            pool_state: pool_id,
+            emergency_escrow,
+            escrow_token_0_vault,
+            escrow_token_1_vault,
+            token_0_vault: self.pool_vault_key(pool_id, self.token_0_mint),
+            token_1_vault: self.pool_vault_key(pool_id, self.token_1_mint),
+            vault_0_mint: self.token_0_mint,
+            vault_1_mint: self.token_1_mint,
+            token_0_program: spl_token::id(),
+            token_1_program: spl_token::id(),
+        };
+        let data = gamma::instruction::RestoreEscrowToPool {};
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Claims the user's share of the escrow for their liquidity and the given positions, the
+    /// user holds no LP tokens
+    pub async fn try_claim_emergency_escrow(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        positions: &[Pubkey],
+    ) -> ProcessTransactionResult {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (emergency_escrow, escrow_token_0_vault, escrow_token_1_vault) =
+            self.emergency_escrow_accounts(pool_id);
+        let user_pool_liquidity = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                user.pubkey().to_bytes().as_ref(),
+            ],
+            &gamma::id(),
+        )
+        .0;
+        let token_0_account = self
+            .get_or_create_associated_token_account(user.pubkey(), self.token_0_mint, user)
+            .await;
+        let token_1_account = self
+            .get_or_create_associated_token_account(user.pubkey(), self.token_1_mint, user)
+            .await;
+
+        let accounts = gamma::accounts::ClaimEmergencyEscrow {
+            owner: user.pubkey(),
+            authority,
+            pool_state: pool_id,
+            user_pool_liquidity,
+            emergency_escrow,
+            escrow_token_0_vault,
+            escrow_token_1_vault,
+            token_0_account,
+            token_1_account,
+            vault_0_mint: self.token_0_mint,
+            vault_1_mint: self.token_1_mint,
+            token_0_program: spl_token::id(),
+            token_1_program: spl_token::id(),
+        };
+        let mut instruction =
+            get_instruction(gamma::instruction::ClaimEmergencyEscrow {}, accounts);
+        instruction
+            .accounts
+            .extend(vec![AccountMeta::new_readonly(gamma::id(), false); 3]);
+        instruction.accounts.extend(
+            positions
+                .iter()
+                .map(|position| AccountMeta::new(*position, false)),
+        );
+
+        let transaction =
+            get_signed_transaction(&mut self.program_test_context, &[instruction], user).await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// The escrow of the pool and its token_0 and token_1 vaults
+    pub fn emergency_escrow_accounts(&self, pool_id: Pubkey) -> (Pubkey, Pubkey, Pubkey) {
+        let emergency_escrow = Pubkey::find_program_address(
+            &[
+                EMERGENCY_ESCROW_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+            ],
+            &gamma::id(),
+        )
+        .0;
+        let escrow_vault = |mint: Pubkey| {
+            Pubkey::find_program_address(
+                &[
+                    EMERGENCY_ESCROW_VAULT_SEED.as_bytes(),
+                    emergency_escrow.to_bytes().as_ref(),
+                    mint.to_bytes().as_ref(),
+                ],
+                &gamma::id(),
+            )
+            .0
+        };
+
+        (
+            emergency_escrow,
+            escrow_vault(self.token_0_mint),
+            escrow_vault(self.token_1_mint),
+        )
+    }
+
+    pub async fn update_amm_config(
+        &mut self,
+        admin: &Keypair,
+        amm_config_index: u16,
+        param: u16,
+        value: u64,
+    ) {
+        let (amm_config, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+        let accounts = gamma::accounts::UpdateAmmConfig {
+            owner: admin.pubkey(),
+            amm_config,
+            config_roles: None,
+        };
+        let data = gamma::instruction::UpdateAmmConfig { param, value };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    pub fn config_roles_key(&self, amm_config_index: u16) -> Pubkey {
+        let (amm_config, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+        Pubkey::find_program_address(
+            &[AMM_CONFIG_ROLES_SEED.as_bytes(), amm_config.as_ref()],
+            &gamma::ID,
+        )
+        .0
+    }
+
+    pub async fn try_set_config_role(
+        &mut self,
+        owner: &Keypair,
+        amm_config_index: u16,
+        role: ConfigRole,
+        authority: Pubkey,
+    ) -> ProcessTransactionResult {
+        let (amm_config, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+        let accounts = gamma::accounts::SetConfigRole {
+            owner: owner.pubkey(),
+            amm_config,
+            config_roles: self.config_roles_key(amm_config_index),
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::SetConfigRole { role, authority };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, owner)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Updates the config as `owner`, passing the config's roles when `with_config_roles` is set
+    pub async fn try_update_amm_config(
+        &mut self,
+        owner: &Keypair,
+        amm_config_index: u16,
+        param: u16,
+        value: u64,
+        with_config_roles: bool,
+    ) -> ProcessTransactionResult {
+        let (amm_config, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+        let accounts = gamma::accounts::UpdateAmmConfig {
+            owner: owner.pubkey(),
+            amm_config,
+            config_roles: with_config_roles.then(|| self.config_roles_key(amm_config_index)),
+        };
+        let data = gamma::instruction::UpdateAmmConfig { param, value };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, owner)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Pauses the pool as `owner`, passing the config's roles when `with_config_roles` is set
+    pub async fn try_emergency_pause(
+        &mut self,
+        owner: &Keypair,
+        pool_id: Pubkey,
+        amm_config_index: u16,
+        status: u8,
+        duration: u64,
+        with_config_roles: bool,
+    ) -> ProcessTransactionResult {
+        let (pause_expiry, __bump) = Pubkey::find_program_address(
+            &[PAUSE_EXPIRY_SEED.as_bytes(), pool_id.as_ref()],
+            &gamma::ID,
+        );
+        let accounts = gamma::accounts::EmergencyPause {
+            owner: owner.pubkey(),
+            pool_state: pool_id,
+            config_roles: with_config_roles.then(|| self.config_roles_key(amm_config_index)),
+            pause_expiry,
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::EmergencyPause { status, duration };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, owner)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Sends `instructions` in a single transaction paid and signed by `signer`
+    pub async fn process_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        signer: &Keypair,
+    ) -> ProcessTransactionResult {
+        let transaction =
+            get_signed_transaction(&mut self.program_test_context, instructions, signer).await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// The `flash_loan` of `amount` token_0 to the borrower and the `flash_repay` paying it back
+    pub async fn flash_loan_instructions(
+        &mut self,
+        borrower: &Keypair,
+        pool_id: Pubkey,
+        amm_config_index: u16,
+        amount: u64,
+    ) -> (Instruction, Instruction) {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (amm_config, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+        let (flash_loan_state, __bump) = Pubkey::find_program_address(
+            &[FLASH_LOAN_SEED.as_bytes(), pool_id.to_bytes().as_ref()],
+            &gamma::ID,
+        );
+        let (vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                self.token_0_mint.to_bytes().as_ref(),
+            ],
+            &gamma::ID,
+        );
+        let borrower_token_account = self
+            .get_or_create_associated_token_account(borrower.pubkey(), self.token_0_mint, borrower)
+            .await;
+
+        let flash_loan = get_instruction(
+            gamma::instruction::FlashLoan { amount },
+            gamma::accounts::FlashLoan {
+                borrower: borrower.pubkey(),
+                authority,
+                amm_config,
+                pool_state: pool_id,
+                flash_loan_state,
+                vault,
+                borrower_token_account,
+                token_program: spl_token::id(),
+                vault_mint: self.token_0_mint,
+                instructions_sysvar: sysvar::instructions::id(),
+                system_program: system_program::ID,
+            },
+        );
+        let flash_repay = get_instruction(
+            gamma::instruction::FlashRepay {},
+            gamma::accounts::FlashRepay {
+                borrower: borrower.pubkey(),
+                pool_state: pool_id,
+                flash_loan_state,
+                vault,
+                borrower_token_account,
+                token_program: spl_token::id(),
+                vault_mint: self.token_0_mint,
+            },
+        );
+
+        (flash_loan, flash_repay)
+    }
+
+    pub async fn update_pool(&mut self, admin: &Keypair, pool_id: Pubkey, param: u32, value: u64) {
+        let accounts = gamma::accounts::UpdatePool {
+            authority: admin.pubkey(),
+            pool_state: pool_id,
+        };
+        let data = gamma::instruction::UpdatePool { param, value };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    /// Grows the observation account of the pool to the current layout, paid by `payer`
+    pub async fn try_migrate_observation_state(
+        &mut self,
+        payer: &Keypair,
+        pool_id: Pubkey,
+    ) -> ProcessTransactionResult {
+        let pool_state: PoolState = self.fetch_account(pool_id).await;
+        let instruction = get_instruction(
+            gamma::instruction::MigrateObservationState {},
+            gamma::accounts::MigrateObservationState {
+                payer: payer.pubkey(),
+                pool_state: pool_id,
+                observation_state: pool_state.observation_key,
+                system_program: system_program::ID,
+            },
+        );
+        self.process_instructions(&[instruction], payer).await
+    }
+
+    /// Grows the pool to the current `PoolState` layout and version, paid by `payer`
+    pub async fn try_migrate_pool_state(
+        &mut self,
+        payer: &Keypair,
+        pool_id: Pubkey,
+    ) -> ProcessTransactionResult {
+        let (lp_burn_proof, __bump) = Pubkey::find_program_address(
+            &[LP_BURN_PROOF_SEED.as_bytes(), pool_id.as_ref()],
+            &gamma::id(),
+        );
+        let instruction = get_instruction(
+            gamma::instruction::MigratePoolState {},
+            gamma::accounts::MigratePoolState {
+                payer: payer.pubkey(),
+                pool_state: pool_id,
+                lp_burn_proof,
+                system_program: system_program::ID,
+            },
+        );
+        self.process_instructions(&[instruction], payer).await
+    }
+
+    /// Closes the pool, sent by `payer` as anyone can close an empty pool
+    pub async fn try_close_pool(
+        &mut self,
+        payer: &Keypair,
+        pool_id: Pubkey,
+    ) -> ProcessTransactionResult {
+        let pool_state: PoolState = self.fetch_account(pool_id).await;
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (pair_index, __bump) = Pubkey::find_program_address(
+            &[
+                PAIR_INDEX_SEED.as_bytes(),
+                pool_state.token_0_mint.as_ref(),
+                pool_state.token_1_mint.as_ref(),
+            ],
+            &gamma::ID,
+        );
+        let creator_token_0 = self
+            .get_or_create_associated_token_account(
+                pool_state.pool_creator,
+                pool_state.token_0_mint,
+                payer,
+            )
+            .await;
+        let creator_token_1 = self
+            .get_or_create_associated_token_account(
+                pool_state.pool_creator,
+                pool_state.token_1_mint,
+                payer,
+            )
+            .await;
+
+        let instruction = get_instruction(
+            gamma::instruction::ClosePool {},
+            gamma::accounts::ClosePool {
+                authority,
+                pool_state: pool_id,
+                pool_creator: pool_state.pool_creator,
+                observation_state: pool_state.observation_key,
+                token_0_vault: pool_state.token_0_vault,
+                token_1_vault: pool_state.token_1_vault,
+                vault_0_mint: pool_state.token_0_mint,
+                vault_1_mint: pool_state.token_1_mint,
+                creator_token_0,
+                creator_token_1,
+                pair_index: Some(pair_index),
+                token_0_program: pool_state.token_0_program,
+                token_1_program: pool_state.token_1_program,
+            },
+        );
+        self.process_instructions(&[instruction], payer).await
+    }
+
+    /// Claims the LP fees accrued to the user's liquidity into their token accounts
+    pub async fn try_claim_lp_fees(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        amm_config_index: u16,
+    ) -> ProcessTransactionResult {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (amm_config, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+        let (user_pool_liquidity, __bump) = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                user.pubkey().as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let (token_0_vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                self.token_0_mint.as_ref(),
+            ],
+            &gamma::ID,
+        );
+        let (token_1_vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                self.token_1_mint.as_ref(),
+            ],
+            &gamma::ID,
+        );
+        let recipient_token_0_account = self
+            .get_or_create_associated_token_account(user.pubkey(), self.token_0_mint, user)
+            .await;
+        let recipient_token_1_account = self
+            .get_or_create_associated_token_account(user.pubkey(), self.token_1_mint, user)
+            .await;
+
+        let accounts = gamma::accounts::ClaimLpFees {
+            owner: user.pubkey(),
+            authority,
+            amm_config,
+            pool_state: pool_id,
+            user_pool_liquidity,
+            token_0_vault,
+            token_1_vault,
+            vault_0_mint: self.token_0_mint,
+            vault_1_mint: self.token_1_mint,
+            recipient_token_0_account,
+            recipient_token_1_account,
+            token_program: spl_token::id(),
+            token_program_2022: spl_token_2022::id(),
+        };
+        let data = gamma::instruction::ClaimLpFees {};
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, user)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    pub async fn burn_lp_forever(&mut self, user: &Keypair, pool_id: Pubkey, lp_token_amount: u64) {
+        let (user_pool_liquidity, __bump) = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                user.pubkey().as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let (lp_burn_proof, __bump) = Pubkey::find_program_address(
+            &[LP_BURN_PROOF_SEED.as_bytes(), pool_id.as_ref()],
+            &gamma::id(),
+        );
+
+        let accounts = gamma::accounts::BurnLpForever {
+            owner: user.pubkey(),
+            pool_state: pool_id,
+            user_pool_liquidity,
+            lp_burn_proof,
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::BurnLpForever { lp_token_amount };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, user)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    pub fn pool_vault_key(&self, pool_id: Pubkey, mint: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[POOL_VAULT_SEED.as_bytes(), pool_id.as_ref(), mint.as_ref()],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    pub fn user_pool_liquidity_key(&self, pool_id: Pubkey, user: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                user.as_ref(),
+            ],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    pub fn strategy_allowlist_key(&self, adapter_program: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[STRATEGY_ALLOWLIST_SEED.as_bytes(), adapter_program.as_ref()],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    /// The `VaultStrategy` of the pool's vault of `mint`, its strategy authority and the token
+    /// account of the strategy authority
+    pub fn vault_strategy_keys(&self, pool_id: Pubkey, mint: Pubkey) -> (Pubkey, Pubkey, Pubkey) {
+        let vault = self.pool_vault_key(pool_id, mint);
+        let (vault_strategy, __bump) = Pubkey::find_program_address(
+            &[
+                VAULT_STRATEGY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                vault.as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let (strategy_authority, __bump) = Pubkey::find_program_address(
+            &[STRATEGY_SEED.as_bytes(), pool_id.as_ref(), mint.as_ref()],
+            &gamma::id(),
+        );
+        let (strategy_token_account, __bump) = Pubkey::find_program_address(
+            &[STRATEGY_SEED.as_bytes(), strategy_authority.as_ref()],
+            &gamma::id(),
+        );
+        (vault_strategy, strategy_authority, strategy_token_account)
+    }
+
+    pub async fn add_strategy_program(&mut self, admin: &Keypair, adapter_program: Pubkey) {
+        let accounts = gamma::accounts::AddStrategyProgram {
+            owner: admin.pubkey(),
+            adapter_program,
+            allowlist_entry: self.strategy_allowlist_key(adapter_program),
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::AddStrategyProgram {
+            protocol: LendingProtocol::Kamino,
+        };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    pub async fn remove_strategy_program(&mut self, admin: &Keypair, adapter_program: Pubkey) {
+        let accounts = gamma::accounts::RemoveStrategyProgram {
+            owner: admin.pubkey(),
+            allowlist_entry: self.strategy_allowlist_key(adapter_program),
+        };
+        let data = gamma::instruction::RemoveStrategyProgram {};
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    /// Sets the strategy of the pool's vault of `mint`, the adapter taking `protocol_accounts`
+    pub async fn try_set_vault_strategy(
+        &mut self,
+        admin: &Keypair,
+        pool_id: Pubkey,
+        mint: Pubkey,
+        adapter_program: Pubkey,
+        target_rate: u64,
+        protocol_accounts: &[Pubkey],
+    ) -> ProcessTransactionResult {
+        let (vault_strategy, strategy_authority, strategy_token_account) =
+            self.vault_strategy_keys(pool_id, mint);
+        let accounts = gamma::accounts::SetVaultStrategy {
+            owner: admin.pubkey(),
+            pool_state: pool_id,
+            vault: self.pool_vault_key(pool_id, mint),
+            vault_mint: mint,
+            adapter_program,
+            allowlist_entry: self.strategy_allowlist_key(adapter_program),
+            vault_strategy,
+            strategy_authority,
+            strategy_token_account,
+            token_program: self.token_program_of(mint).await,
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::SetVaultStrategy { target_rate };
+
+        let mut instruction = get_instruction(data, accounts);
+        instruction.accounts.extend(
+            protocol_accounts
+                .iter()
+                .map(|account| AccountMeta::new(*account, false)),
+        );
+        self.process_instructions(&[instruction], admin).await
+    }
+
+    /// `rebalance_strategy` of the pool's vault of `mint`, the adapter taking `protocol_accounts`
+    pub async fn rebalance_strategy_instruction(
+        &mut self,
+        signer: &Keypair,
+        pool_id: Pubkey,
+        mint: Pubkey,
+        adapter_program: Pubkey,
+        protocol_accounts: &[Pubkey],
+    ) -> Instruction {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (vault_strategy, strategy_authority, strategy_token_account) =
+            self.vault_strategy_keys(pool_id, mint);
+        let accounts = gamma::accounts::RebalanceStrategy {
+            signer: signer.pubkey(),
+            authority,
+            pool_state: pool_id,
+            vault: self.pool_vault_key(pool_id, mint),
+            vault_mint: mint,
+            token_program: self.token_program_of(mint).await,
+            vault_strategy,
+            adapter_program,
+            allowlist_entry: self.strategy_allowlist_key(adapter_program),
+            strategy_authority,
+            strategy_token_account,
+        };
+
+        let mut instruction = get_instruction(gamma::instruction::RebalanceStrategy {}, accounts);
+        instruction.accounts.extend(
+            protocol_accounts
+                .iter()
+                .map(|account| AccountMeta::new(*account, false)),
+        );
+        instruction
+    }
+
+    /// The accounts of the strategy of the pool's vault of `mint` passed along a swap or
+    /// withdrawal, see `StrategyAccounts`
+    pub fn strategy_remaining_accounts(
+        &self,
+        pool_id: Pubkey,
+        mint: Pubkey,
+        adapter_program: Pubkey,
+        protocol_accounts: &[Pubkey],
+    ) -> Vec<AccountMeta> {
+        let (vault_strategy, strategy_authority, strategy_token_account) =
+            self.vault_strategy_keys(pool_id, mint);
+        let mut accounts = vec![
+            AccountMeta::new(vault_strategy, false),
+            AccountMeta::new_readonly(self.strategy_allowlist_key(adapter_program), false),
+            AccountMeta::new_readonly(strategy_authority, false),
+            AccountMeta::new(strategy_token_account, false),
+            AccountMeta::new_readonly(adapter_program, false),
+        ];
+        accounts.extend(
+            protocol_accounts
+                .iter()
+                .map(|account| AccountMeta::new(*account, false)),
+        );
+        accounts
+    }
+
+    pub fn swap_hook_allowlist_key(&self, hook_program: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[SWAP_HOOK_ALLOWLIST_SEED.as_bytes(), hook_program.as_ref()],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    pub fn pool_swap_hook_key(&self, pool_id: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[POOL_SWAP_HOOK_SEED.as_bytes(), pool_id.as_ref()],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    pub async fn add_swap_hook_program(&mut self, admin: &Keypair, hook_program: Pubkey) {
+        let accounts = gamma::accounts::AddSwapHookProgram {
+            owner: admin.pubkey(),
+            hook_program,
+            allowlist_entry: self.swap_hook_allowlist_key(hook_program),
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::AddSwapHookProgram {};
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    /// Attaches `hook_program` to the pool, the hook taking `hook_accounts_len` accounts
+    pub async fn set_pool_swap_hook(
+        &mut self,
+        authority: &Keypair,
+        pool_id: Pubkey,
+        hook_program: Pubkey,
+        hook_accounts_len: u8,
+    ) {
+        let accounts = gamma::accounts::SetPoolSwapHook {
+            authority: authority.pubkey(),
+            pool_state: pool_id,
+            hook_program,
+            allowlist_entry: self.swap_hook_allowlist_key(hook_program),
+            pool_swap_hook: self.pool_swap_hook_key(pool_id),
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::SetPoolSwapHook { hook_accounts_len };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, authority)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    pub fn segmenter_registry_key(&self, partner_id: u16) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                SEGMENTER_REGISTRY_SEED.as_bytes(),
+                &partner_id.to_le_bytes(),
+            ],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    pub fn segmenter_kickback_key(&self, pool_id: Pubkey, segmenter: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                SEGMENTER_KICKBACK_SEED.as_bytes(),
+                pool_id.as_ref(),
+                segmenter.as_ref(),
+            ],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    /// Onboards the order-flow partner `partner_id`, returns its `SegmenterRegistry`
+    pub async fn set_segmenter_registry(
+        &mut self,
+        admin: &Keypair,
+        partner_id: u16,
+        kickback_bps: u64,
+        segmenters: Vec<Pubkey>,
+    ) -> Pubkey {
+        let segmenter_registry = self.segmenter_registry_key(partner_id);
+        let accounts = gamma::accounts::SetSegmenterRegistry {
+            owner: admin.pubkey(),
+            segmenter_registry,
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::SetSegmenterRegistry {
+            partner_id,
+            kickback_bps,
+            segmenters,
+        };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+        segmenter_registry
+    }
+
+    pub async fn try_init_segmenter_kickback(
+        &mut self,
+        segmenter: &Keypair,
+        registry: Pubkey,
+        pool_id: Pubkey,
+    ) -> ProcessTransactionResult {
+        let accounts = gamma::accounts::InitSegmenterKickback {
+            segmenter: segmenter.pubkey(),
+            registry,
+            pool_state: pool_id,
+            segmenter_kickback: self.segmenter_kickback_key(pool_id, segmenter.pubkey()),
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::InitSegmenterKickback {};
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, segmenter)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    pub fn gauge_controller_key(&self) -> Pubkey {
+        Pubkey::find_program_address(&[GAUGE_CONTROLLER_SEED.as_bytes()], &gamma::id()).0
+    }
+
+    pub fn gauge_key(&self, pool_id: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[GAUGE_SEED.as_bytes(), pool_id.as_ref()], &gamma::id()).0
+    }
+
+    pub fn gauge_voter_key(&self, voter: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[GAUGE_VOTER_SEED.as_bytes(), voter.as_ref()], &gamma::id()).0
+    }
+
+    pub async fn create_gauge_controller(
+        &mut self,
+        admin: &Keypair,
+        ve_mint: Pubkey,
+        epoch_duration: u64,
+        emission_per_epoch: u64,
+    ) {
+        let instruction = get_instruction(
+            gamma::instruction::CreateGaugeController {
+                epoch_duration,
+                emission_per_epoch,
+            },
+            gamma::accounts::CreateGaugeController {
+                owner: admin.pubkey(),
+                ve_mint,
+                gauge_controller: self.gauge_controller_key(),
+                system_program: system_program::ID,
+            },
+        );
+        self.process_instructions(&[instruction], admin)
+            .await
+            .unwrap_transaction();
+    }
+
+    pub async fn create_gauge(&mut self, payer: &Keypair, pool_id: Pubkey) {
+        let instruction = get_instruction(
+            gamma::instruction::CreateGauge {},
+            gamma::accounts::CreateGauge {
+                payer: payer.pubkey(),
+                pool_state: pool_id,
+                gauge: self.gauge_key(pool_id),
+                system_program: system_program::ID,
+            },
+        );
+        self.process_instructions(&[instruction], payer)
+            .await
+            .unwrap_transaction();
+    }
+
+    /// Votes for the gauge of the pool with `power` of the voter's balance of `ve_mint`
+    pub async fn try_vote_gauge(
+        &mut self,
+        voter: &Keypair,
+        pool_id: Pubkey,
+        ve_mint: Pubkey,
+        power: u64,
+    ) -> ProcessTransactionResult {
+        let ve_token_account = self
+            .get_or_create_associated_token_account(voter.pubkey(), ve_mint, voter)
+            .await;
+        let instruction = get_instruction(
+            gamma::instruction::VoteGauge { power },
+            gamma::accounts::VoteGauge {
+                voter: voter.pubkey(),
+                gauge_controller: self.gauge_controller_key(),
+                gauge: self.gauge_key(pool_id),
+                ve_token_account,
+                gauge_voter: self.gauge_voter_key(voter.pubkey()),
+                system_program: system_program::ID,
+            },
+        );
+        self.process_instructions(&[instruction], voter).await
+    }
+
+    pub async fn try_settle_gauge(
+        &mut self,
+        payer: &Keypair,
+        pool_id: Pubkey,
+    ) -> ProcessTransactionResult {
+        let instruction = get_instruction(
+            gamma::instruction::SettleGauge {},
+            gamma::accounts::SettleGauge {
+                gauge_controller: self.gauge_controller_key(),
+                gauge: self.gauge_key(pool_id),
+            },
+        );
+        self.process_instructions(&[instruction], payer).await
+    }
+
+    pub fn solver_key(&self, solver_authority: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[SOLVER_SEED.as_bytes(), solver_authority.as_ref()],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    /// Registers the solver signing with `solver_authority`, bonding `bond_mint`
+    pub async fn register_solver(
+        &mut self,
+        admin: &Keypair,
+        solver_authority: Pubkey,
+        bond_mint: Pubkey,
+        min_bond: u64,
+    ) {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let solver = self.solver_key(solver_authority);
+        let (bond_vault, __bump) = Pubkey::find_program_address(
+            &[SOLVER_BOND_VAULT_SEED.as_bytes(), solver.as_ref()],
+            &gamma::id(),
+        );
+        let instruction = get_instruction(
+            gamma::instruction::RegisterSolver { min_bond },
+            gamma::accounts::RegisterSolver {
+                owner: admin.pubkey(),
+                authority,
+                solver_authority,
+                solver,
+                bond_vault,
+                bond_mint,
+                bond_token_program: self.token_program_of(bond_mint).await,
+                system_program: system_program::ID,
+            },
+        );
+        self.process_instructions(&[instruction], admin)
+            .await
+            .unwrap_transaction();
+    }
+
+    pub async fn update_solver(
+        &mut self,
+        admin: &Keypair,
+        solver_authority: Pubkey,
+        active: bool,
+        min_bond: u64,
+    ) {
+        let instruction = get_instruction(
+            gamma::instruction::UpdateSolver { active, min_bond },
+            gamma::accounts::UpdateSolver {
+                owner: admin.pubkey(),
+                solver: self.solver_key(solver_authority),
+            },
+        );
+        self.process_instructions(&[instruction], admin)
+            .await
+            .unwrap_transaction();
+    }
+
+    /// Slashes `amount` of the solver's bond to the `recipient`'s token account of the bond mint
+    pub async fn try_slash_solver(
+        &mut self,
+        owner: &Keypair,
+        solver_authority: Pubkey,
+        recipient: &Keypair,
+        amount: u64,
+    ) -> ProcessTransactionResult {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let solver_key = self.solver_key(solver_authority);
+        let solver: Solver = self.fetch_account(solver_key).await;
+        let recipient_token_account = self
+            .get_or_create_associated_token_account(recipient.pubkey(), solver.bond_mint, recipient)
+            .await;
+        let instruction = get_instruction(
+            gamma::instruction::SlashSolver { amount },
+            gamma::accounts::SlashSolver {
+                owner: owner.pubkey(),
+                authority,
+                solver: solver_key,
+                bond_vault: solver.bond_vault,
+                bond_mint: solver.bond_mint,
+                recipient_token_account,
+                bond_token_program: self.token_program_of(solver.bond_mint).await,
+            },
+        );
+        self.process_instructions(&[instruction], owner).await
+    }
+
+    /// Accounts bonding, or withdrawing, the bond of the solver from its token account
+    async fn bond_solver_accounts(
+        &mut self,
+        solver_authority: &Keypair,
+    ) -> gamma::accounts::BondSolver {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let solver_key = self.solver_key(solver_authority.pubkey());
+        let solver: Solver = self.fetch_account(solver_key).await;
+        let solver_token_account = self
+            .get_or_create_associated_token_account(
+                solver_authority.pubkey(),
+                solver.bond_mint,
+                solver_authority,
+            )
+            .await;
+        gamma::accounts::BondSolver {
+            solver_authority: solver_authority.pubkey(),
+            authority,
+            solver: solver_key,
+            bond_vault: solver.bond_vault,
+            bond_mint: solver.bond_mint,
+            solver_token_account,
+            bond_token_program: self.token_program_of(solver.bond_mint).await,
+        }
+    }
+
+    pub async fn try_bond_solver(
+        &mut self,
+        solver_authority: &Keypair,
+        amount: u64,
+    ) -> ProcessTransactionResult {
+        let accounts = self.bond_solver_accounts(solver_authority).await;
+        let instruction = get_instruction(gamma::instruction::BondSolver { amount }, accounts);
+        self.process_instructions(&[instruction], solver_authority)
+            .await
+    }
+
+    pub async fn try_withdraw_solver_bond(
+        &mut self,
+        solver_authority: &Keypair,
+        amount: u64,
+    ) -> ProcessTransactionResult {
+        let accounts = self.bond_solver_accounts(solver_authority).await;
+        let instruction =
+            get_instruction(gamma::instruction::WithdrawSolverBond { amount }, accounts);
+        self.process_instructions(&[instruction], solver_authority)
+            .await
+    }
+
+    /// Adds `reward_mint` to the pool's rewards, returns the reward vault
+    pub async fn create_reward_vault(
+        &mut self,
+        admin: &Keypair,
+        pool_id: Pubkey,
+        reward_mint: Pubkey,
+        emissions_per_second: u64,
+    ) -> Pubkey {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let pool_rewards = self.pool_rewards_key(pool_id);
+        let (reward_vault, __bump) = Pubkey::find_program_address(
+            &[
+                REWARD_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                reward_mint.as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let reward_token_program = self.token_program_of(reward_mint).await;
+
+        let accounts = gamma::accounts::CreateRewardVault {
+            owner: admin.pubkey(),
+            authority,
+            pool_state: pool_id,
+            pool_rewards,
+            reward_mint,
+            reward_vault,
+            reward_token_program,
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::CreateRewardVault {
+            emissions_per_second,
+        };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, admin)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+
+        reward_vault
+    }
+
+    pub fn pool_rewards_key(&self, pool_id: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[POOL_REWARDS_SEED.as_bytes(), pool_id.as_ref()],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    pub fn user_stake_key(&self, pool_id: Pubkey, owner: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[USER_STAKE_SEED.as_bytes(), pool_id.as_ref(), owner.as_ref()],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    pub async fn try_stake_lp(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        lp_token_amount: u64,
+    ) -> ProcessTransactionResult {
+        let (user_pool_liquidity, __bump) = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                user.pubkey().as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let accounts = gamma::accounts::StakeLp {
+            owner: user.pubkey(),
+            pool_state: pool_id,
+            user_pool_liquidity,
+            pool_rewards: self.pool_rewards_key(pool_id),
+            user_stake: self.user_stake_key(pool_id, user.pubkey()),
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::StakeLp { lp_token_amount };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, user)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    pub async fn try_unstake_lp(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        lp_token_amount: u64,
+    ) -> ProcessTransactionResult {
+        let (user_pool_liquidity, __bump) = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                user.pubkey().as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let accounts = gamma::accounts::UnstakeLp {
+            owner: user.pubkey(),
+            pool_state: pool_id,
+            user_pool_liquidity,
+            pool_rewards: self.pool_rewards_key(pool_id),
+            user_stake: self.user_stake_key(pool_id, user.pubkey()),
+        };
+        let data = gamma::instruction::UnstakeLp { lp_token_amount };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, user)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Claims the rewards of `reward_index` into the user's associated token account of
+    /// `reward_mint`
+    pub async fn try_claim_rewards(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        reward_index: u8,
+        reward_mint: Pubkey,
+    ) -> ProcessTransactionResult {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (user_pool_liquidity, __bump) = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                user.pubkey().as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let (reward_vault, __bump) = Pubkey::find_program_address(
+            &[
+                REWARD_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                reward_mint.as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let recipient_token_account = self
+            .get_or_create_associated_token_account(user.pubkey(), reward_mint, user)
+            .await;
+        let reward_token_program = self.token_program_of(reward_mint).await;
+
+        let accounts = gamma::accounts::ClaimRewards {
+            owner: user.pubkey(),
+            authority,
+            pool_rewards: self.pool_rewards_key(pool_id),
+            user_pool_liquidity,
+            user_stake: self.user_stake_key(pool_id, user.pubkey()),
+            reward_vault,
+            reward_mint,
+            recipient_token_account,
+            reward_token_program,
+        };
+        let data = gamma::instruction::ClaimRewards { reward_index };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, user)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Creates a mint of `supply` tokens held by `owner`, which can't be minted or frozen
+    /// anymore unless `keep_mint_authority`
+    pub async fn create_launch_mint(
+        &mut self,
+        owner: &Keypair,
+        supply: u64,
+        keep_mint_authority: bool,
+    ) -> Pubkey {
+        let token_mint = Keypair::new();
+        let mint_authority = self.mint_authority.pubkey();
+        self.create_token_mint(&token_mint, &mint_authority, 6)
+            .await;
+        let owner_token_account = self
+            .get_or_create_associated_token_account(owner.pubkey(), token_mint.pubkey(), owner)
+            .await;
+        self.mint_base_tokens(owner_token_account, supply, token_mint.pubkey())
+            .await;
+        if !keep_mint_authority {
+            let ix = spl_token::instruction::set_authority(
+                &spl_token::id(),
+                &token_mint.pubkey(),
+                None,
+                spl_token::instruction::AuthorityType::MintTokens,
+                &mint_authority,
+                &[&mint_authority],
+            )
+            .unwrap();
+            let transaction =
+                get_signed_transaction(&mut self.program_test_context, &[ix], &self.mint_authority)
+                    .await;
+            self.program_test_context
+                .banks_client
+                .process_transaction(transaction)
+                .await
+                .unwrap();
+        }
+        token_mint.pubkey()
+    }
+
+    pub fn bonding_curve_key(
+        &self,
+        amm_config_index: u16,
+        token_mint: Pubkey,
+        quote_mint: Pubkey,
+    ) -> Pubkey {
+        let (amm_config, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+        Pubkey::find_program_address(
+            &[
+                BONDING_CURVE_SEED.as_bytes(),
+                amm_config.as_ref(),
+                token_mint.as_ref(),
+                quote_mint.as_ref(),
+            ],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    fn bonding_curve_vault_key(&self, bonding_curve: Pubkey, mint: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                BONDING_CURVE_VAULT_SEED.as_bytes(),
+                bonding_curve.as_ref(),
+                mint.as_ref(),
+            ],
+            &gamma::id(),
+        )
+        .0
+    }
+
+    pub async fn try_create_bonding_curve(
+        &mut self,
+        creator: &Keypair,
+        amm_config_index: u16,
+        token_mint: Pubkey,
+        quote_mint: Pubkey,
+        virtual_token_reserve: u64,
+        virtual_quote_reserve: u64,
+        graduation_quote_amount: u64,
+    ) -> ProcessTransactionResult {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (amm_config, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+        let bonding_curve = self.bonding_curve_key(amm_config_index, token_mint, quote_mint);
+        let creator_token_account = self
+            .get_or_create_associated_token_account(creator.pubkey(), token_mint, creator)
+            .await;
+        let token_mint_program = self.token_program_of(token_mint).await;
+        let quote_mint_program = self.token_program_of(quote_mint).await;
+
+        let accounts = gamma::accounts::CreateBondingCurve {
+            creator: creator.pubkey(),
+            amm_config,
+            authority,
+            bonding_curve,
+            token_mint,
+            quote_mint,
+            creator_token_account,
+            token_vault: self.bonding_curve_vault_key(bonding_curve, token_mint),
+            quote_vault: self.bonding_curve_vault_key(bonding_curve, quote_mint),
+            create_pool_fee: gamma::create_pool_fee_reveiver::id(),
+            token_program: spl_token::id(),
+            token_mint_program,
+            quote_mint_program,
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::CreateBondingCurve {
+            virtual_token_reserve,
+            virtual_quote_reserve,
+            graduation_quote_amount,
+            max_trade_fee_rate: 0,
+            volatility_factor: 0,
+        };
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, creator)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Buys tokens of the curve for `amount_in` quote, or sells `amount_in` tokens for quote
+    pub async fn try_trade_bonding_curve(
+        &mut self,
+        trader: &Keypair,
+        bonding_curve: Pubkey,
+        is_buy: bool,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> ProcessTransactionResult {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let bonding_curve_state: BondingCurve = self.fetch_account(bonding_curve).await;
+        let token_mint = bonding_curve_state.token_mint;
+        let quote_mint = bonding_curve_state.quote_mint;
+        let trader_token_account = self
+            .get_or_create_associated_token_account(trader.pubkey(), token_mint, trader)
+            .await;
+        let trader_quote_account = self
+            .get_or_create_associated_token_account(trader.pubkey(), quote_mint, trader)
+            .await;
+        let token_mint_program = self.token_program_of(token_mint).await;
+        let quote_mint_program = self.token_program_of(quote_mint).await;
+
+        let accounts = gamma::accounts::TradeBondingCurve {
+            trader: trader.pubkey(),
+            authority,
+            bonding_curve,
+            token_vault: bonding_curve_state.token_vault,
+            quote_vault: bonding_curve_state.quote_vault,
+            trader_token_account,
+            trader_quote_account,
+            token_mint,
+            quote_mint,
+            token_mint_program,
+            quote_mint_program,
+        };
+        let transaction = if is_buy {
+            let data = gamma::instruction::BuyBondingCurve {
+                quote_amount_in: amount_in,
+                minimum_token_amount_out: minimum_amount_out,
+            };
+            self.encode_instruction_and_sign_transaction(data, accounts, trader)
+                .await
+        } else {
+            let data = gamma::instruction::SellBondingCurve {
+                token_amount_in: amount_in,
+                minimum_quote_amount_out: minimum_amount_out,
+            };
+            self.encode_instruction_and_sign_transaction(data, accounts, trader)
+                .await
+        };
+
+        self.program_test_context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+    }
+
+    /// Graduates a completed curve, returns the pool and the creator's position
+    pub async fn graduate(&mut self, payer: &Keypair, bonding_curve: Pubkey) -> (Pubkey, Pubkey) {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let bonding_curve_state: BondingCurve = self.fetch_account(bonding_curve).await;
+        let amm_config = bonding_curve_state.amm_config;
+        let (token_0_mint, token_1_mint) =
+            if bonding_curve_state.token_mint < bonding_curve_state.quote_mint {
+                (
+                    bonding_curve_state.token_mint,
+                    bonding_curve_state.quote_mint,
+                )
+            } else {
+                (
+                    bonding_curve_state.quote_mint,
+                    bonding_curve_state.token_mint,
+                )
+            };
+        let (pool_id, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_SEED.as_bytes(),
+                amm_config.as_ref(),
+                token_0_mint.as_ref(),
+                token_1_mint.as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let (token_0_vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                token_0_mint.as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let (token_1_vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                token_1_mint.as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let (observation_state, __bump) = Pubkey::find_program_address(
+            &[OBSERVATION_SEED.as_bytes(), pool_id.as_ref()],
+            &gamma::id(),
+        );
+        let (pair_index, __bump) = Pubkey::find_program_address(
+            &[
+                PAIR_INDEX_SEED.as_bytes(),
+                token_0_mint.as_ref(),
+                token_1_mint.as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let (position, __bump) = Pubkey::find_program_address(
+            &[
+                POSITION_SEED.as_bytes(),
+                pool_id.as_ref(),
+                bonding_curve_state.creator.as_ref(),
+                &GRADUATION_POSITION_ID.to_le_bytes(),
+            ],
+            &gamma::id(),
+        );
+        let token_0_program = self.token_program_of(token_0_mint).await;
+        let token_1_program = self.token_program_of(token_1_mint).await;
+
+        let accounts = gamma::accounts::Graduate {
+            payer: payer.pubkey(),
+            amm_config,
+            authority,
+            bonding_curve,
+            curve_token_vault: bonding_curve_state.token_vault,
+            curve_quote_vault: bonding_curve_state.quote_vault,
+            pool_state: pool_id,
+            token_0_mint,
+            token_1_mint,
+            token_0_vault,
+            token_1_vault,
+            observation_state,
+            pair_index,
+            position,
+            token_0_program,
+            token_1_program,
+            system_program: system_program::ID,
+        };
+        let data = gamma::instruction::Graduate {};
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, payer)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+
+        (pool_id, position)
+    }
+
+    pub async fn collect_position_lp_fees(
+        &mut self,
+        owner: &Keypair,
+        pool_id: Pubkey,
+        position: Pubkey,
+    ) {
+        let (user_pool_liquidity, __bump) = Pubkey::find_program_address(
+            &[
+                USER_POOL_LIQUIDITY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                owner.pubkey().as_ref(),
+            ],
+            &gamma::id(),
+        );
+        let accounts = gamma::accounts::CollectPositionLpFees {
+            owner: owner.pubkey(),
+            pool_state: pool_id,
+            user_pool_liquidity,
+            position,
+        };
+        let data = gamma::instruction::CollectPositionLpFees {};
+
+        let transaction = self
+            .encode_instruction_and_sign_transaction(data, accounts, owner)
+            .await;
+
+        self.program_test_context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    pub async fn swap_base_input(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        amm_config_index: u16,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        trade_direction: TradeDirection,
+    ) {
+        self.try_swap_base_input(
+            user,
+            pool_id,
+            amm_config_index,
+            amount_in,
+            minimum_amount_out,
+            trade_direction,
+        )
+        .await
+        .unwrap_transaction();
+    }
+
+    pub async fn try_swap_base_input(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        amm_config_index: u16,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        trade_direction: TradeDirection,
+    ) -> ProcessTransactionResult {
+        let instruction = self
+            .swap_base_input_instruction(
+                user,
+                pool_id,
+                amm_config_index,
+                amount_in,
+                minimum_amount_out,
+                trade_direction,
+            )
+            .await;
+        self.process_instructions(&[instruction], user).await
+    }
+
+    pub async fn swap_base_input_instruction(
+        &mut self,
+        user: &Keypair,
+        pool_id: Pubkey,
+        amm_config_index: u16,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        trade_direction: TradeDirection,
+    ) -> Instruction {
+        let (authority, __bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+        let (amm_config_key, __bump) = Pubkey::find_program_address(
+            &[AMM_CONFIG_SEED.as_bytes(), &amm_config_index.to_be_bytes()],
+            &gamma::ID,
+        );
+
+        let user_token_0_account: Pubkey = self
+            .get_or_create_associated_token_account(user.pubkey(), self.token_0_mint.clone(), &user)
+            .await;
+
+        let user_token_1_account = self
+            .get_or_create_associated_token_account(user.pubkey(), self.token_1_mint.clone(), &user)
+            .await;
+
+        let (token_0_vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                self.token_0_mint.to_bytes().as_ref(),
+            ],
+            &gamma::ID,
+        );
+        let (token_1_vault, __bump) = Pubkey::find_program_address(
+            &[
+                POOL_VAULT_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                self.token_1_mint.to_bytes().as_ref(),
+            ],
+            &gamma::ID,
+        );
+        let (observation_key, __bump) = Pubkey::find_program_address(
+            &[OBSERVATION_SEED.as_bytes(), pool_id.to_bytes().as_ref()],
+            &gamma::ID,
+        );
+
+        let token_0_program = self.token_program_of(self.token_0_mint).await;
+        let token_1_program = self.token_program_of(self.token_1_mint).await;
+        let (
+            input_token_account,
+            output_token_account,
+            input_token_mint,
+            output_token_mint,
+            input_vault,
+            output_vault,
+            input_token_program,
+            output_token_program,
+        ) = match trade_direction {
+            TradeDirection::ZeroForOne => (
+                user_token_0_account,
+                user_token_1_account,
+                self.token_0_mint,
+                self.token_1_mint,
+                token_0_vault,
+                token_1_vault,
+                token_0_program,
+                token_1_program,
+            ),
+            TradeDirection::OneForZero => (
+                user_token_1_account,
+                user_token_0_account,
+                self.token_1_mint,
+                self.token_0_mint,
+                token_1_vault,
+                token_0_vault,
+                token_1_program,
+                token_0_program,
+            ),
+        };
+
+        let accounts = gamma::accounts::Swap {
+            payer: user.pubkey(),
+            authority,
+            amm_config: amm_config_key,
+            pool_state: pool_id,
+            observation_state: observation_key,
+            input_token_account,
+            output_token_account,
+            input_vault,
+            output_vault,
+            input_token_program,
+            output_token_program,
+            input_token_mint,
+            output_token_mint,
+        };
+
+        let data = gamma::instruction::SwapBaseInput {
+            amount_in,
+            minimum_amount_out,
+        };
+
+        get_instruction(data, accounts)
+    }
+
     pub async fn swap_base_output(
         &mut self,
         user: &Keypair,
@@ -1126,6 +3272,8 @@ impl TestEnv {
             &gamma::ID,
         );
 
+        let token_0_program = self.token_program_of(self.token_0_mint).await;
+        let token_1_program = self.token_program_of(self.token_1_mint).await;
         let (
             input_token_account,
             output_token_account,
@@ -1143,8 +3291,8 @@ impl TestEnv {
                 self.token_1_mint,
                 token_0_vault,
                 token_1_vault,
-                spl_token::id(),
-                spl_token::id(),
+                token_0_program,
+                token_1_program,
             ),
             TradeDirection::OneForZero => (
                 user_token_1_account,
@@ -1153,8 +3301,8 @@ impl TestEnv {
                 self.token_0_mint,
                 token_1_vault,
                 token_0_vault,
-                spl_token::id(),
-                spl_token::id(),
+                token_1_program,
+                token_0_program,
             ),
         };
 
@@ -0,0 +1,145 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use gamma::states::PoolState;
+use gamma::AUTH_SEED;
+use serde::Deserialize;
+use solana_program_test::ProgramTest;
+use solana_sdk::account::Account;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::str::FromStr;
+
+use super::{get_wallet, TestEnv, INITIAL_ACCOUNT_LAMPORTS};
+
+/// Matches the file written by `gamma-cli snapshot-pool`
+#[derive(Deserialize, Clone, Debug)]
+pub struct AccountSnapshot {
+    pub address: String,
+    pub owner: String,
+    pub lamports: u64,
+    pub executable: bool,
+    pub data: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PoolSnapshot {
+    pub program_id: String,
+    pub pool_id: String,
+    pub slot: u64,
+    pub accounts: Vec<AccountSnapshot>,
+}
+
+impl PoolSnapshot {
+    pub fn from_file(path: &str) -> Result<PoolSnapshot> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn pool_id(&self) -> Pubkey {
+        Pubkey::from_str(&self.pool_id).unwrap()
+    }
+}
+
+// Offsets into the spl token layouts, shared by token 2022 base accounts
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+const MINT_AUTHORITY_OFFSET: usize = 0;
+
+impl TestEnv {
+    /// Starts a test environment with a pool reconstructed from a mainnet snapshot.
+    ///
+    /// Accounts owned by the snapshotted program are re-owned by the program under test, the
+    /// vaults are handed to its authority PDA, and the mint authorities are replaced by the
+    /// test mint authority so tests can fund users with the pool tokens.
+    pub async fn new_from_snapshot(snapshot: &PoolSnapshot, mut accounts: Vec<Pubkey>) -> TestEnv {
+        let mut program_test = ProgramTest::new("gamma", gamma::id(), None);
+
+        accounts.push(get_wallet().pubkey());
+        let mint_authority = Keypair::new();
+        accounts.push(mint_authority.pubkey());
+        accounts.iter().for_each(|pubkey| {
+            program_test.add_account(
+                pubkey.to_owned(),
+                Account {
+                    lamports: INITIAL_ACCOUNT_LAMPORTS,
+                    ..Default::default()
+                },
+            )
+        });
+
+        let snapshot_program_id = Pubkey::from_str(&snapshot.program_id).unwrap();
+        let pool_id = snapshot.pool_id();
+        let (authority, auth_bump) =
+            Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &gamma::id());
+
+        let mut snapshot_accounts = snapshot
+            .accounts
+            .iter()
+            .map(|account| {
+                let address = Pubkey::from_str(&account.address).unwrap();
+                let mut owner = Pubkey::from_str(&account.owner).unwrap();
+                if owner == snapshot_program_id {
+                    owner = gamma::id();
+                }
+                let data = STANDARD.decode(&account.data).unwrap();
+                (
+                    address,
+                    Account {
+                        lamports: account.lamports,
+                        data,
+                        owner,
+                        executable: account.executable,
+                        rent_epoch: 0,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let pool_state = {
+            let (_, pool_account) = snapshot_accounts
+                .iter_mut()
+                .find(|(address, _)| *address == pool_id)
+                .expect("snapshot is missing the pool state");
+            fix_up_pool_state(&mut pool_account.data, auth_bump).unwrap()
+        };
+
+        for (address, account) in snapshot_accounts.iter_mut() {
+            if *address == pool_state.token_0_vault || *address == pool_state.token_1_vault {
+                account.data[TOKEN_ACCOUNT_OWNER_OFFSET..TOKEN_ACCOUNT_OWNER_OFFSET + 32]
+                    .copy_from_slice(authority.as_ref());
+            }
+            if *address == pool_state.token_0_mint || *address == pool_state.token_1_mint {
+                // COption<Pubkey>: a u32 tag followed by the key
+                account.data[MINT_AUTHORITY_OFFSET..MINT_AUTHORITY_OFFSET + 4]
+                    .copy_from_slice(&1u32.to_le_bytes());
+                account.data[MINT_AUTHORITY_OFFSET + 4..MINT_AUTHORITY_OFFSET + 36]
+                    .copy_from_slice(mint_authority.pubkey().as_ref());
+            }
+        }
+
+        for (address, account) in snapshot_accounts {
+            program_test.add_account(address, account);
+        }
+
+        let context = program_test.start_with_context().await;
+
+        TestEnv {
+            program_test_context: context,
+            mint_authority,
+            token_0_mint: pool_state.token_0_mint,
+            token_1_mint: pool_state.token_1_mint,
+            treasury: Pubkey::new_unique(),
+        }
+    }
+}
+
+/// Points the pool at the authority PDA of the program under test and returns a copy of it
+fn fix_up_pool_state(data: &mut [u8], auth_bump: u8) -> Result<PoolState> {
+    if data.len() < 8 + std::mem::size_of::<PoolState>() || data[..8] != PoolState::DISCRIMINATOR {
+        return Err(anyhow!("account is not a pool state"));
+    }
+    let pool_state: &mut PoolState =
+        bytemuck::from_bytes_mut(&mut data[8..8 + std::mem::size_of::<PoolState>()]);
+    pool_state.auth_bump = auth_bump;
+    Ok(*pool_state)
+}
@@ -0,0 +1,352 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token::TokenAccount;
+use gamma::error::GammaError;
+use gamma::instructions::WITHDRAW_FIXED_REMAINING_ACCOUNTS;
+use gamma::states::{PoolState, VaultStrategy};
+use solana_program_test::{processor, tokio};
+use solana_sdk::account::AccountSharedData;
+use solana_sdk::account_info::AccountInfo;
+use solana_sdk::entrypoint::ProgramResult;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::program::{invoke, set_return_data};
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::{signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+const TARGET_RATE: u64 = 900_000;
+/// Discriminator of the adapters' `deposit`, see `strategy_adapter`
+const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+
+/// Adapter keeping the position in a token account of the strategy authority, the only
+/// protocol account it takes. It reports the balance of that account as the position.
+fn process_mock_adapter(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [strategy_authority, token_account, mint, token_program, position] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let amount = data
+        .get(8..16)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let (from, to) = if data[..8] == DEPOSIT_DISCRIMINATOR {
+        (token_account, position)
+    } else {
+        (position, token_account)
+    };
+    let decimals = spl_token::state::Mint::unpack(&mint.data.borrow())?.decimals;
+    invoke(
+        &spl_token::instruction::transfer_checked(
+            token_program.key,
+            from.key,
+            mint.key,
+            to.key,
+            strategy_authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            from.clone(),
+            mint.clone(),
+            to.clone(),
+            strategy_authority.clone(),
+        ],
+    )?;
+
+    let position_value = spl_token::state::Account::unpack(&position.data.borrow())?.amount;
+    set_return_data(&position_value.to_le_bytes());
+    Ok(())
+}
+
+/// A pool whose token_0 vault deploys `TARGET_RATE` of its reserve through the mock adapter,
+/// rebalanced once. Returns the admin, the user, the pool, the adapter and the position.
+async fn setup() -> (TestEnv, Keypair, Keypair, Pubkey, Pubkey, Pubkey) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let adapter_program = Pubkey::new_unique();
+    let mut test_env = TestEnv::new_with_config(
+        vec![user.pubkey(), admin.pubkey()],
+        vec![ProgramInfo {
+            program_name: "mock_adapter".to_string(),
+            program_id: adapter_program,
+            process_instruction: processor!(process_mock_adapter),
+        }],
+    )
+    .await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_0_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_0_account, 100000000000000, test_env.token_0_mint)
+        .await;
+    let user_token_1_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), test_env.token_1_mint, &user)
+        .await;
+    test_env
+        .mint_base_tokens(user_token_1_account, 100000000000000, test_env.token_1_mint)
+        .await;
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000,
+            100000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    let mint = test_env.token_0_mint;
+    let (_, strategy_authority, _) = test_env.vault_strategy_keys(pool_id, mint);
+    let position = Keypair::new();
+    test_env
+        .create_token_account(&position, &strategy_authority, &mint, &user)
+        .await;
+    let protocol_accounts = [position.pubkey()];
+
+    test_env.add_strategy_program(&admin, adapter_program).await;
+    test_env
+        .try_set_vault_strategy(
+            &admin,
+            pool_id,
+            mint,
+            adapter_program,
+            TARGET_RATE,
+            &protocol_accounts,
+        )
+        .await
+        .unwrap_transaction();
+    let rebalance = test_env
+        .rebalance_strategy_instruction(&user, pool_id, mint, adapter_program, &protocol_accounts)
+        .await;
+    test_env
+        .process_instructions(&[rebalance], &user)
+        .await
+        .unwrap_transaction();
+
+    (
+        test_env,
+        admin,
+        user,
+        pool_id,
+        adapter_program,
+        position.pubkey(),
+    )
+}
+
+/// Withdraws half of the user's liquidity, more token_0 than the vault keeps as a buffer
+async fn try_withdraw_half(
+    test_env: &mut TestEnv,
+    user: &Keypair,
+    pool_id: Pubkey,
+    strategy_accounts: Vec<AccountMeta>,
+) -> ProcessTransactionResult {
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let mut remaining_accounts =
+        vec![AccountMeta::new_readonly(gamma::id(), false); WITHDRAW_FIXED_REMAINING_ACCOUNTS];
+    remaining_accounts.extend(strategy_accounts);
+    test_env
+        .try_withdraw(
+            user,
+            pool_id,
+            0,
+            pool_state.lp_supply / 2,
+            0,
+            0,
+            remaining_accounts,
+        )
+        .await
+}
+
+#[tokio::test]
+async fn should_deploy_the_target_share_of_the_reserve() {
+    let (mut test_env, _, user, pool_id, adapter_program, position) = setup().await;
+    let mint = test_env.token_0_mint;
+    let (vault_strategy_key, _, _) = test_env.vault_strategy_keys(pool_id, mint);
+
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    let vault_strategy: VaultStrategy = test_env.fetch_account(vault_strategy_key).await;
+    let deployed_amount = u64::try_from(
+        u128::from(pool_state.token_0_vault_amount) * u128::from(TARGET_RATE) / 1_000_000,
+    )
+    .unwrap();
+    assert_eq!(vault_strategy.deployed_amount, deployed_amount);
+    assert_eq!(vault_strategy.position_value, deployed_amount);
+    let position_account: TokenAccount = test_env.fetch_account(position).await;
+    assert_eq!(position_account.amount, deployed_amount);
+    let vault: TokenAccount = test_env
+        .fetch_account(test_env.pool_vault_key(pool_id, mint))
+        .await;
+    assert_eq!(
+        vault.amount,
+        pool_state.token_0_vault_amount - deployed_amount
+    );
+
+    // The adapter only ever gets the protocol accounts of the strategy
+    let other_position = Keypair::new();
+    let (_, strategy_authority, _) = test_env.vault_strategy_keys(pool_id, mint);
+    test_env
+        .create_token_account(&other_position, &strategy_authority, &mint, &user)
+        .await;
+    let rebalance = test_env
+        .rebalance_strategy_instruction(
+            &user,
+            pool_id,
+            mint,
+            adapter_program,
+            &[other_position.pubkey()],
+        )
+        .await;
+    let result = test_env.process_instructions(&[rebalance], &user).await;
+    assert_error!(result, GammaError::InvalidStrategy);
+
+    // Leaving out the allowlist entry doesn't unwind the strategy
+    let mut rebalance = test_env
+        .rebalance_strategy_instruction(&user, pool_id, mint, adapter_program, &[position])
+        .await;
+    rebalance.accounts[8].pubkey = Pubkey::new_unique();
+    let result = test_env.process_instructions(&[rebalance], &user).await;
+    assert_error!(result, anchor_lang::error::ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn should_recall_what_the_vault_is_missing_for_a_payout() {
+    let (mut test_env, _, user, pool_id, adapter_program, position) = setup().await;
+    let mint = test_env.token_0_mint;
+    let (vault_strategy_key, _, _) = test_env.vault_strategy_keys(pool_id, mint);
+    let vault_key = test_env.pool_vault_key(pool_id, mint);
+
+    // The buffer alone can't pay the withdrawal
+    let result = try_withdraw_half(&mut test_env, &user, pool_id, vec![]).await;
+    assert!(result.unwrap().result.is_err());
+
+    let vault_strategy_before: VaultStrategy = test_env.fetch_account(vault_strategy_key).await;
+    let vault_before: TokenAccount = test_env.fetch_account(vault_key).await;
+    let user_token_0_account = test_env
+        .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+        .await;
+    let user_account_before: TokenAccount = test_env.fetch_account(user_token_0_account).await;
+    let strategy_accounts =
+        test_env.strategy_remaining_accounts(pool_id, mint, adapter_program, &[position]);
+    try_withdraw_half(&mut test_env, &user, pool_id, strategy_accounts)
+        .await
+        .unwrap_transaction();
+
+    let user_account_after: TokenAccount = test_env.fetch_account(user_token_0_account).await;
+    let paid_amount = user_account_after.amount - user_account_before.amount;
+    let recall_amount = paid_amount - vault_before.amount;
+    let vault_strategy: VaultStrategy = test_env.fetch_account(vault_strategy_key).await;
+    assert_eq!(
+        vault_strategy.deployed_amount,
+        vault_strategy_before.deployed_amount - recall_amount
+    );
+    assert_eq!(
+        vault_strategy.position_value,
+        vault_strategy.deployed_amount
+    );
+    let vault: TokenAccount = test_env.fetch_account(vault_key).await;
+    assert_eq!(vault.amount, 0);
+}
+
+#[tokio::test]
+async fn should_take_a_loss_of_the_position_out_of_the_reserve() {
+    let (mut test_env, _, user, pool_id, adapter_program, position) = setup().await;
+    let mint = test_env.token_0_mint;
+    let (vault_strategy_key, _, _) = test_env.vault_strategy_keys(pool_id, mint);
+    let loss_amount = 1000000;
+
+    // The protocol loses part of the position
+    let mut position_account = test_env
+        .program_test_context
+        .banks_client
+        .get_account(position)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut position_state = spl_token::state::Account::unpack(&position_account.data).unwrap();
+    position_state.amount -= loss_amount;
+    position_state.pack_into_slice(&mut position_account.data);
+    test_env
+        .program_test_context
+        .set_account(&position, &AccountSharedData::from(position_account));
+
+    let pool_state_before: PoolState = test_env.fetch_account(pool_id).await;
+    let vault_strategy_before: VaultStrategy = test_env.fetch_account(vault_strategy_key).await;
+    let rebalance = test_env
+        .rebalance_strategy_instruction(&user, pool_id, mint, adapter_program, &[position])
+        .await;
+    test_env
+        .process_instructions(&[rebalance], &user)
+        .await
+        .unwrap_transaction();
+
+    let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq!(
+        pool_state.token_0_vault_amount,
+        pool_state_before.token_0_vault_amount - loss_amount
+    );
+    let vault_strategy: VaultStrategy = test_env.fetch_account(vault_strategy_key).await;
+    assert_eq!(
+        vault_strategy.deployed_amount,
+        vault_strategy_before.deployed_amount - loss_amount
+    );
+    assert_eq!(
+        vault_strategy.position_value,
+        vault_strategy.deployed_amount
+    );
+
+    // Interest is only counted once it is in the vault
+    let interest_amount = 500000;
+    test_env
+        .mint_base_tokens(position, interest_amount, mint)
+        .await;
+    let rebalance = test_env
+        .rebalance_strategy_instruction(&user, pool_id, mint, adapter_program, &[position])
+        .await;
+    test_env
+        .process_instructions(&[rebalance], &user)
+        .await
+        .unwrap_transaction();
+    let pool_state_after: PoolState = test_env.fetch_account(pool_id).await;
+    assert_eq!(
+        pool_state_after.token_0_vault_amount,
+        pool_state.token_0_vault_amount + interest_amount
+    );
+    let vault_strategy: VaultStrategy = test_env.fetch_account(vault_strategy_key).await;
+    assert!(vault_strategy.position_value <= vault_strategy.deployed_amount);
+}
+
+#[tokio::test]
+async fn should_not_call_an_adapter_removed_from_the_allowlist() {
+    let (mut test_env, admin, user, pool_id, adapter_program, position) = setup().await;
+    let mint = test_env.token_0_mint;
+    test_env
+        .remove_strategy_program(&admin, adapter_program)
+        .await;
+
+    let rebalance = test_env
+        .rebalance_strategy_instruction(&user, pool_id, mint, adapter_program, &[position])
+        .await;
+    let result = test_env.process_instructions(&[rebalance], &user).await;
+    assert_error!(result, GammaError::InvalidStrategy);
+
+    let strategy_accounts =
+        test_env.strategy_remaining_accounts(pool_id, mint, adapter_program, &[position]);
+    let result = try_withdraw_half(&mut test_env, &user, pool_id, strategy_accounts).await;
+    assert_error!(result, GammaError::InvalidStrategy);
+}
@@ -0,0 +1,150 @@
+use anchor_spl::token_interface::TokenAccount;
+use gamma::error::GammaError;
+use gamma::states::{PoolState, UserPoolLiquidity};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+mod utils;
+
+use utils::*;
+
+/// A pool created by the user. Returns the user and the pool.
+async fn setup() -> (TestEnv, Keypair, Pubkey) {
+    let user = Keypair::new();
+    let admin = get_admin();
+    let amm_index = 0;
+    let mut test_env = TestEnv::new(vec![user.pubkey(), admin.pubkey()]).await;
+
+    test_env
+        .create_config(&admin, amm_index, 3000, 2000, 50, 0)
+        .await;
+    for mint in [test_env.token_0_mint, test_env.token_1_mint] {
+        let user_token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, &user)
+            .await;
+        test_env
+            .mint_base_tokens(user_token_account, 100000000000000, mint)
+            .await;
+    }
+
+    let pool_id = test_env
+        .initialize_pool(
+            &user,
+            amm_index,
+            200000000000,
+            100000000000,
+            0,
+            gamma::create_pool_fee_reveiver::id(),
+        )
+        .await;
+    test_env.jump_seconds(100).await;
+
+    (test_env, user, pool_id)
+}
+
+/// Balances of the user's token_0 and token_1 accounts
+async fn token_balances(test_env: &mut TestEnv, user: &Keypair) -> (u64, u64) {
+    let mut balances = [0; 2];
+    for (balance, mint) in balances
+        .iter_mut()
+        .zip([test_env.token_0_mint, test_env.token_1_mint])
+    {
+        let token_account = test_env
+            .get_or_create_associated_token_account(user.pubkey(), mint, user)
+            .await;
+        let token_account: TokenAccount = test_env.fetch_account(token_account).await;
+        *balance = token_account.amount;
+    }
+    (balances[0], balances[1])
+}
+
+#[tokio::test]
+async fn should_withdraw_a_single_token() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let lp_token_amount = 1000000000;
+
+    for token_0 in [true, false] {
+        let pool_state_before: PoolState = test_env.fetch_account(pool_id).await;
+        let user_pool_liquidity_before: UserPoolLiquidity = test_env
+            .fetch_account(test_env.user_pool_liquidity_key(pool_id, user.pubkey()))
+            .await;
+        let balances_before = token_balances(&mut test_env, &user).await;
+
+        test_env
+            .try_withdraw_single_token(&user, pool_id, token_0, lp_token_amount, 1)
+            .await
+            .unwrap_transaction();
+
+        let pool_state: PoolState = test_env.fetch_account(pool_id).await;
+        let user_pool_liquidity: UserPoolLiquidity = test_env
+            .fetch_account(test_env.user_pool_liquidity_key(pool_id, user.pubkey()))
+            .await;
+        let balances = token_balances(&mut test_env, &user).await;
+        assert_eq!(
+            pool_state_before.lp_supply - pool_state.lp_supply,
+            lp_token_amount
+        );
+        assert_eq!(
+            user_pool_liquidity_before.lp_tokens_owned - user_pool_liquidity.lp_tokens_owned,
+            u128::from(lp_token_amount)
+        );
+
+        // Only the withdrawn token is paid out, more of it than the lp's share of its reserve
+        let (received, other_token_received, reserve_share) = if token_0 {
+            (
+                balances.0 - balances_before.0,
+                balances.1 - balances_before.1,
+                u128::from(pool_state_before.token_0_vault_amount),
+            )
+        } else {
+            (
+                balances.1 - balances_before.1,
+                balances.0 - balances_before.0,
+                u128::from(pool_state_before.token_1_vault_amount),
+            )
+        };
+        let reserve_share =
+            reserve_share * u128::from(lp_token_amount) / u128::from(pool_state_before.lp_supply);
+        assert!(u128::from(received) > reserve_share);
+        assert_eq!(other_token_received, 0);
+        test_env.jump_seconds(100).await;
+    }
+}
+
+#[tokio::test]
+async fn should_not_withdraw_a_single_token_below_the_minimum_amount_out() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let lp_token_amount = 1000000000;
+
+    let balances_before = token_balances(&mut test_env, &user).await;
+    test_env
+        .try_withdraw_single_token(&user, pool_id, true, lp_token_amount, 1)
+        .await
+        .unwrap_transaction();
+    let received = token_balances(&mut test_env, &user).await.0 - balances_before.0;
+    test_env.jump_seconds(100).await;
+
+    // The same withdrawal can't pay out twice as much
+    let result = test_env
+        .try_withdraw_single_token(&user, pool_id, true, lp_token_amount, received * 2)
+        .await;
+    assert_error!(result, GammaError::ExceededSlippage);
+}
+
+#[tokio::test]
+async fn should_not_withdraw_a_single_token_with_more_lp_than_owned() {
+    let (mut test_env, user, pool_id) = setup().await;
+    let user_pool_liquidity: UserPoolLiquidity = test_env
+        .fetch_account(test_env.user_pool_liquidity_key(pool_id, user.pubkey()))
+        .await;
+
+    let result = test_env
+        .try_withdraw_single_token(
+            &user,
+            pool_id,
+            true,
+            u64::try_from(user_pool_liquidity.lp_tokens_owned).unwrap() + 1,
+            0,
+        )
+        .await;
+    assert_error!(result, GammaError::LpTokensStaked);
+}